@@ -1,4 +1,5 @@
 use prost_build::Config;
+use std::fmt::Write as _;
 use std::io::Result;
 
 fn main() -> Result<()> {
@@ -21,5 +22,215 @@ fn main() -> Result<()> {
         std::env::var("OUT_DIR").unwrap()
     );
 
+    generate_dispatch_table()?;
+
+    Ok(())
+}
+
+/// A single row of `operators.in`; see that file's header for the column
+/// layout.
+struct OpRow {
+    op_family: String,
+    arity: u8,
+    dtype_a: String,
+    dtype_b: String,
+    dtype_out: String,
+    variant: String,
+    binding: String,
+    extra_let: Option<(String, String)>,
+    map_fn: String,
+    call_args: Vec<String>,
+}
+
+fn rust_type(dtype: &str) -> &'static str {
+    match dtype {
+        "F32" => "f32",
+        "U64" => "u64",
+        other => panic!("operators.in: unknown dtype `{other}`"),
+    }
+}
+
+/// Parses `operators.in` and emits `$OUT_DIR/dispatch_table.rs`, which
+/// `proto_driver::dispatch` pulls in with `include!`. See `operators.in`'s
+/// header comment for the table format and the guarantee this generates.
+fn generate_dispatch_table() -> Result<()> {
+    println!("cargo:rerun-if-changed=operators.in");
+
+    let src = std::fs::read_to_string("operators.in")?;
+    let rows: Vec<OpRow> = src
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let cols: Vec<&str> = line.split('|').map(str::trim).collect();
+            assert_eq!(
+                cols.len(),
+                10,
+                "operators.in: expected 10 `|`-delimited columns, got {}: {line}",
+                cols.len()
+            );
+            OpRow {
+                op_family: cols[0].to_string(),
+                arity: cols[1].parse().expect("operators.in: arity must be 1 or 2"),
+                dtype_a: cols[2].to_string(),
+                dtype_b: cols[3].to_string(),
+                dtype_out: cols[4].to_string(),
+                variant: cols[5].to_string(),
+                binding: cols[6].to_string(),
+                extra_let: (cols[7] != "-").then(|| {
+                    let (name, expr) = cols[7]
+                        .split_once('=')
+                        .expect("operators.in: extra_let must be `name=expr` or `-`");
+                    (name.to_string(), expr.to_string())
+                }),
+                map_fn: cols[8].to_string(),
+                call_args: cols[9].split(',').map(str::trim).map(String::from).collect(),
+            }
+        })
+        .collect();
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// @generated by build.rs from operators.in. Do not edit by hand."
+    )
+    .unwrap();
+
+    // One dispatch fn per (op_family, dtype combo) group, in table order.
+    let mut seen_groups = std::collections::HashSet::new();
+    for row in &rows {
+        let group_key = (
+            row.op_family.clone(),
+            row.dtype_a.clone(),
+            row.dtype_b.clone(),
+            row.dtype_out.clone(),
+        );
+        if !seen_groups.insert(group_key.clone()) {
+            continue;
+        }
+        let group: Vec<&OpRow> = rows
+            .iter()
+            .filter(|r| {
+                (
+                    r.op_family.clone(),
+                    r.dtype_a.clone(),
+                    r.dtype_b.clone(),
+                    r.dtype_out.clone(),
+                ) == group_key
+            })
+            .collect();
+        emit_dispatch_fn(&mut out, &group);
+    }
+
+    emit_exhaustiveness_check(&mut out, &rows);
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(
+        std::path::Path::new(&out_dir).join("dispatch_table.rs"),
+        out,
+    )?;
     Ok(())
 }
+
+fn emit_dispatch_fn(out: &mut String, group: &[&OpRow]) {
+    let first = group[0];
+    let fn_name = format!(
+        "dispatch_{}_{}",
+        first.op_family,
+        [&first.dtype_a, &first.dtype_b, &first.dtype_out]
+            .iter()
+            .filter(|d| d.as_str() != "-")
+            .map(|d| d.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_")
+    );
+
+    let ret_ty = match first.arity {
+        1 => format!(
+            "std::sync::Arc<dyn Fn(&Tile<{}>, u64, bool) -> (u64, Tile<{}>) + Send + Sync>",
+            rust_type(&first.dtype_a),
+            rust_type(&first.dtype_b)
+        ),
+        2 => format!(
+            "std::sync::Arc<dyn Fn(&Tile<{}>, &Tile<{}>, u64, bool) -> (u64, Tile<{}>) + Send + Sync>",
+            rust_type(&first.dtype_a),
+            rust_type(&first.dtype_b),
+            rust_type(&first.dtype_out)
+        ),
+        n => panic!("operators.in: unsupported arity {n}"),
+    };
+
+    writeln!(
+        out,
+        "pub fn {fn_name}(variant: elemto_elem_func::ElemElemFn) -> {ret_ty} {{"
+    )
+    .unwrap();
+    writeln!(out, "    match variant {{").unwrap();
+    for row in group {
+        let pattern = if row.binding == "_" {
+            format!("elemto_elem_func::ElemElemFn::{}(_)", row.variant)
+        } else {
+            format!(
+                "elemto_elem_func::ElemElemFn::{}({})",
+                row.variant, row.binding
+            )
+        };
+        let closure_params = if row.arity == 1 {
+            "tile, comp_bw, write_back_mu"
+        } else {
+            "tile1, tile2, comp_bw, write_back_mu"
+        };
+        writeln!(out, "        {pattern} => {{").unwrap();
+        if let Some((name, expr)) = &row.extra_let {
+            writeln!(out, "            let {name} = {expr};").unwrap();
+        }
+        writeln!(
+            out,
+            "            std::sync::Arc::new(move |{closure_params}| {{"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "                {}({})",
+            row.map_fn,
+            row.call_args.join(", ")
+        )
+        .unwrap();
+        writeln!(out, "            }})").unwrap();
+        writeln!(out, "        }}").unwrap();
+    }
+    let unsupported_msg = if first.op_family == "unary_map" {
+        "Unsupported unary map function type"
+    } else {
+        "Unsupported binary map function type"
+    };
+    writeln!(out, "        _ => panic!(\"{unsupported_msg}\"),").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+}
+
+/// Emits a no-wildcard match over every `ElemElemFn` variant that appears in
+/// `operators.in`, regardless of dtype combo. If the proto gains a new
+/// `ElemElemFn` variant without a corresponding table row, this fails to
+/// compile instead of dispatch falling through to a runtime panic.
+fn emit_exhaustiveness_check(out: &mut String, rows: &[OpRow]) {
+    let mut seen_variants = std::collections::HashSet::new();
+    writeln!(
+        out,
+        "/// Every `ElemElemFn` variant in `operators.in` must be listed here. This\n\
+         /// function is never called; its only job is to fail to compile if a\n\
+         /// proto variant has no table row.\n\
+         #[allow(dead_code)]\n\
+         fn _assert_elem_elem_fn_table_exhaustive(f: elemto_elem_func::ElemElemFn) {{"
+    )
+    .unwrap();
+    writeln!(out, "    match f {{").unwrap();
+    for row in rows {
+        if seen_variants.insert(row.variant.clone()) {
+            writeln!(out, "        elemto_elem_func::ElemElemFn::{}(_) => {{}}", row.variant).unwrap();
+        }
+    }
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+}
@@ -1,5 +1,11 @@
-use std::{collections::HashMap, fmt, marker::PhantomData};
+use std::{any::TypeId, collections::HashMap, fmt, marker::PhantomData};
 
+use crate::build_sim::occupancy::{OccupancyLog, OccupancyTap};
+use crate::build_sim::profiler::{ChannelProfilerLog, ChannelStats, ProfileTap};
+use crate::build_sim::trace::{TraceHandle, TraceSink, TraceTap};
+use crate::build_sim::watchdog::{ActivityLog, WatchdogTap};
+use crate::operator::broadcast::BroadcastContext;
+use crate::operator::rechunk::{Chunk, TileChunker, TileDechunker};
 use crate::primitives::{buffer::Buffer, elem::Elem, select::MultiHotN, tile::Tile};
 use dam::{
     channel::{Receiver, Sender},
@@ -22,38 +28,109 @@ impl<T: DAMType> fmt::Debug for ChanType<T> {
     }
 }
 
-#[derive(Debug)]
 pub enum ChannelMapEntry<T: DAMType> {
     Single(ChanType<T>),
+    /// One independent channel per `stream_idx` -- despite the name, this
+    /// is *not* a true multicast: each `stream_idx` gets its own stream of
+    /// elements, so there's no way to deliver one produced `Elem<T>` to
+    /// several consumers. See [`ChannelMapEntry::Multicast`] for that.
     Broadcast(HashMap<u32, ChanType<T>>),
+    /// Built by [`ChannelMap::get_broadcast_receiver`]: a single upstream
+    /// channel feeds a [`BroadcastContext`] fan-out node that clones every
+    /// `Elem<T>` into one bounded sub-channel per subscriber, so (unlike
+    /// `Broadcast`) every subscriber sees the *same* stream. `node`
+    /// accumulates subscribers as `get_broadcast_receiver` is called
+    /// again for the same `id`; the producer's `get_sender(id, None, ..)`
+    /// call claims `upstream` and registers the finished node with the
+    /// builder, since no more subscribers can be added once that consumes
+    /// the entry.
+    Multicast {
+        upstream: Sender<Elem<T>>,
+        node: BroadcastContext<T>,
+        hint: usize,
+    },
 }
 
-pub fn inspect_sender<T: DAMType>(snd: &Sender<T>, target_id: u32) {
-    let target_id = format!("Channel({})", target_id);
-    let curr_id = format!("{}", snd.id());
-    if target_id == curr_id {
-        panic!("{} sender found here", target_id);
+impl<T: DAMType> fmt::Debug for ChannelMapEntry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelMapEntry::Single(chan_type) => write!(f, "Single({:?})", chan_type),
+            ChannelMapEntry::Broadcast(map) => write!(f, "Broadcast({:?})", map),
+            ChannelMapEntry::Multicast { hint, .. } => write!(f, "Multicast(hint={})", hint),
+        }
     }
 }
 
-pub fn inspect_receiver<T: DAMType>(
-    rcv: &Receiver<T>,
-    target_id: u32,
-    id: u32,
-    idx: Option<u32>,
-    location: &str,
-) {
-    let target_id = format!("Channel({})", target_id);
-    let curr_id = format!("{}", rcv.id());
-    if target_id == curr_id {
-        panic!(
-            "{} receiver found {} (op id({}), stream idx({:?}))",
-            target_id, location, id, idx
-        );
+const DEFAULT_CHAN_SIZE: usize = 1024;
+
+/// A representative per-element byte footprint, used only to translate a
+/// [`CapacityPolicy::Bytes`] budget into an element count for
+/// `builder.bounded` -- not an exact memory accounting. Types whose real
+/// footprint varies at runtime (a [`Tile`]'s shape, a [`MultiHotN`]'s
+/// length) report a stand-in scalar size instead, the same way `dam`'s own
+/// `StaticallySized` already does for those types (see e.g.
+/// `impl StaticallySized for Tile<T>`); kept as its own trait since it
+/// serves this byte-budget heuristic rather than `dam`'s channel-width
+/// bookkeeping.
+pub trait ByteSized {
+    const BYTE_HINT: usize;
+}
+
+macro_rules! impl_byte_sized_scalar {
+    ($ty:ty) => {
+        impl ByteSized for $ty {
+            const BYTE_HINT: usize = std::mem::size_of::<$ty>();
+        }
+    };
+}
+
+impl_byte_sized_scalar!(());
+impl_byte_sized_scalar!(bool);
+impl_byte_sized_scalar!(u64);
+impl_byte_sized_scalar!(f32);
+
+impl<T: ByteSized> ByteSized for Tile<T> {
+    const BYTE_HINT: usize = T::BYTE_HINT;
+}
+
+impl<T: ByteSized> ByteSized for Buffer<T> {
+    const BYTE_HINT: usize = T::BYTE_HINT;
+}
+
+impl ByteSized for MultiHotN {
+    // Mirrors `impl StaticallySized for MultiHotN`'s own stand-in: the
+    // real length varies per-instance, so this is just "about 64 bools'
+    // worth" rather than anything exact.
+    const BYTE_HINT: usize = std::mem::size_of::<bool>() * 64;
+}
+
+/// How [`ChannelMap::get_receiver`]/[`ChannelMap::get_sender`] size a
+/// freshly created channel. `Elements` is passed straight through to
+/// `builder.bounded`, same as a plain `capacity: usize` always was.
+/// `Bytes(b)` is resolved via [`ByteSized`] into `max(1, b / T::BYTE_HINT)`
+/// slots, so a channel of large `Tile`s and one of `bool`s aren't handed
+/// the same flat slot count despite wildly different payload sizes -- each
+/// stays sized to roughly the same worst-case buffered memory instead.
+#[derive(Debug, Clone, Copy)]
+pub enum CapacityPolicy {
+    Elements(usize),
+    Bytes(usize),
+}
+
+impl CapacityPolicy {
+    fn resolve<T: ByteSized>(self) -> usize {
+        match self {
+            CapacityPolicy::Elements(n) => n,
+            CapacityPolicy::Bytes(b) => (b / T::BYTE_HINT.max(1)).max(1),
+        }
     }
 }
 
-const DEFAULT_CHAN_SIZE: usize = 1024;
+impl Default for CapacityPolicy {
+    fn default() -> Self {
+        CapacityPolicy::Elements(DEFAULT_CHAN_SIZE)
+    }
+}
 
 #[derive(Default, Constructor)]
 pub struct ChannelMap<'a, T: DAMType> {
@@ -64,9 +141,93 @@ pub struct ChannelMap<'a, T: DAMType> {
     // If the node outputs multiple streams, the ChannelMapEntry will be a map of
     // the unconnected side of each channel.
     _marker: PhantomData<&'a ()>,
+    // Which ids (if any) should have their tokens recorded to disk -- see
+    // `configure_trace` and `bounded_instrumented`.
+    trace: TraceHandle,
+    // Set once a run configures a wall-clock watchdog (see
+    // `configure_watchdog`); when present, every channel -- not just
+    // selected ids -- gets a `WatchdogTap` so a deadlock can be localized
+    // no matter where it occurs.
+    watchdog: Option<ActivityLog>,
+    // Set once a run configures HTML report generation (see
+    // `configure_occupancy`); when present, every channel gets an
+    // `OccupancyTap` so the report can table each channel's depth, peak
+    // stall, and stalled-token count.
+    occupancy: Option<OccupancyLog>,
+    // Used by `get_receiver`/`get_sender` whenever a call site passes
+    // `capacity: None` -- see `configure_default_policy`. Defaults to
+    // `CapacityPolicy::Elements(DEFAULT_CHAN_SIZE)`, the flat slot count
+    // every channel used to get unconditionally.
+    default_policy: CapacityPolicy,
+    // Union-find over node ids, populated by `register_alias`: maps an id
+    // to its parent on the way to its canonical representative. An id
+    // absent from this map (or mapped to itself) is already canonical.
+    // `get_receiver`/`get_sender` canonicalize their `id` before touching
+    // `map`, so a producer and consumer that address the same logical
+    // node by different ids (e.g. after a graph rewrite splits or merges
+    // nodes) still share one channel instead of each allocating its own.
+    aliases: HashMap<u32, u32>,
+    // Set once a run configures buffer-sizing profiling (see
+    // `configure_profiler`); when present, every channel gets a
+    // `ProfileTap` so `ChannelMapCollection::report` can surface which
+    // channels are worth retuning.
+    profiler: Option<ChannelProfilerLog>,
+}
+
+/// Like `builder.bounded`, but splices a [`TraceTap`], [`WatchdogTap`],
+/// [`OccupancyTap`], and/or [`ProfileTap`] between the channel's two ends
+/// when `trace_sink`/`watchdog_log`/`occupancy_log`/`profiler_log` are
+/// `Some` -- the immediate producer/consumer never see the difference, but
+/// every token that crosses also gets recorded/tracked. A free function
+/// (rather than a `&self` method) so callers can look up the sink before
+/// taking a mutable borrow of `self.map`.
+fn bounded_instrumented<'a, T: DAMType + fmt::Debug>(
+    trace_sink: Option<&TraceSink>,
+    watchdog_log: Option<&ActivityLog>,
+    occupancy_log: Option<&OccupancyLog>,
+    profiler_log: Option<&ChannelProfilerLog>,
+    id: u32,
+    idx: Option<u32>,
+    builder: &mut ProgramBuilder<'a>,
+    capacity: usize,
+) -> (Sender<Elem<T>>, Receiver<Elem<T>>) {
+    let (prod_snd, mut rcv) = builder.bounded::<Elem<T>>(capacity);
+
+    if let Some(sink) = trace_sink {
+        let (tap_snd, next_rcv) = builder.bounded::<Elem<T>>(capacity);
+        builder.add_child(TraceTap::new(rcv, tap_snd, id, sink.clone()));
+        rcv = next_rcv;
+    }
+
+    if let Some(log) = watchdog_log {
+        let (tap_snd, next_rcv) = builder.bounded::<Elem<T>>(capacity);
+        builder.add_child(WatchdogTap::new(rcv, tap_snd, id, log.clone()));
+        rcv = next_rcv;
+    }
+
+    if let Some(log) = occupancy_log {
+        let (tap_snd, next_rcv) = builder.bounded::<Elem<T>>(capacity);
+        builder.add_child(OccupancyTap::new(rcv, tap_snd, id, capacity, log.clone()));
+        rcv = next_rcv;
+    }
+
+    if let Some(log) = profiler_log {
+        let (tap_snd, next_rcv) = builder.bounded::<Elem<T>>(capacity);
+        builder.add_child(ProfileTap::new(
+            rcv,
+            tap_snd,
+            id,
+            idx,
+            capacity,
+            log.clone(),
+        ));
+        rcv = next_rcv;
+    }
+
+    (prod_snd, rcv)
 }
 
-impl<'a, T: DAMType> ChannelMap<'a, T>
+impl<'a, T: DAMType + fmt::Debug + ByteSized> ChannelMap<'a, T>
 where
     T: 'a,
 {
@@ -86,6 +247,9 @@ where
                         ChannelMapEntry::Broadcast(hash_map) => {
                             println!("{}: Broadcast", *idx)
                         }
+                        ChannelMapEntry::Multicast { .. } => {
+                            println!("{}: Multicast", *idx)
+                        }
                     }
                 }
             }
@@ -97,12 +261,21 @@ where
         id: u32,
         idx: Option<u32>,
         builder: &mut ProgramBuilder<'a>,
-        capacity: Option<usize>,
+        capacity: Option<CapacityPolicy>,
     ) -> Receiver<Elem<T>> {
         // if id == 272 {
         //     println!("get_sender: {:?}", idx);
         //     println!("{:?}", self.map.as_ref().unwrap().get(&id));
         // }
+        let trace_sink = self.trace.sink_for(id).cloned();
+        let watchdog_log = self.watchdog.clone();
+        let occupancy_log = self.occupancy.clone();
+        let profiler_log = self.profiler.clone();
+        let capacity = capacity.unwrap_or(self.default_policy).resolve::<T>();
+        // Canonicalize before touching `map`, so a producer/consumer
+        // addressing the same logical node by different ids (see
+        // `register_alias`) still share one channel.
+        let id = self.canonicalize(id);
         match &mut self.map {
             Some(chan_map) => match idx {
                 Some(stream_idx) => match chan_map.get_mut(&id) {
@@ -110,77 +283,112 @@ where
                     Some(ChannelMapEntry::Broadcast(x)) => match x.remove(&stream_idx) {
                         Some(ChanType::Receiver(rcv)) => rcv,
                         None => {
-                            match capacity {
-                                Some(cap) => {
-                                    let (snd, rcv) = builder.bounded::<Elem<T>>(cap);
-                                    //inspect_sender(&snd, 24);
-                                    x.insert(stream_idx, ChanType::Sender(snd));
-                                    rcv
-                                }
-                                None => {
-                                    // Default capacity
-                                    let (snd, rcv) = builder.bounded::<Elem<T>>(DEFAULT_CHAN_SIZE);
-                                    x.insert(stream_idx, ChanType::Sender(snd));
-                                    rcv
-                                }
-                            }
+                            let (snd, rcv) = bounded_instrumented(trace_sink.as_ref(), watchdog_log.as_ref(), occupancy_log.as_ref(), profiler_log.as_ref(), id, idx, builder, capacity);
+                            x.insert(stream_idx, ChanType::Sender(snd));
+                            rcv
                         }
                         _ => panic!("Check whether your id or ChannelMap is correct"),
                     },
                     None => {
-                        match capacity {
-                            Some(cap) => {
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(cap);
-                                //inspect_sender(&snd, 24);
-                                let mut broadcast_map = HashMap::new();
-                                broadcast_map.insert(stream_idx, ChanType::Sender(snd));
-                                chan_map.insert(id, ChannelMapEntry::Broadcast(broadcast_map));
-                                rcv
-                            }
-                            None => {
-                                // Default capacity
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(DEFAULT_CHAN_SIZE);
-                                //inspect_sender(&snd, 24);
-                                let mut broadcast_map = HashMap::new();
-                                broadcast_map.insert(stream_idx, ChanType::Sender(snd));
-                                chan_map.insert(id, ChannelMapEntry::Broadcast(broadcast_map));
-                                rcv
-                            }
-                        }
+                        let (snd, rcv) = bounded_instrumented(trace_sink.as_ref(), watchdog_log.as_ref(), occupancy_log.as_ref(), profiler_log.as_ref(), id, idx, builder, capacity);
+                        let mut broadcast_map = HashMap::new();
+                        broadcast_map.insert(stream_idx, ChanType::Sender(snd));
+                        chan_map.insert(id, ChannelMapEntry::Broadcast(broadcast_map));
+                        rcv
                     }
                     _ => panic!("Check whether your id or ChannelMap is correct"),
                 },
                 None => match chan_map.remove(&id) {
                     // Single
                     Some(ChannelMapEntry::Single(ChanType::Receiver(x))) => {
-                        // inspect_receiver(&x, 141, id, idx, "L122");
                         x
                     }
                     None => {
-                        match capacity {
-                            Some(cap) => {
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(cap);
-                                // inspect_sender(&snd, 24);
-                                chan_map.insert(id, ChannelMapEntry::Single(ChanType::Sender(snd)));
-                                rcv
-                            }
-                            None => {
-                                // Default capacity
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(DEFAULT_CHAN_SIZE);
-                                // inspect_sender(&snd, 24);
-                                chan_map.insert(id, ChannelMapEntry::Single(ChanType::Sender(snd)));
-                                rcv
-                            }
-                        }
+                        let (snd, rcv) = bounded_instrumented(trace_sink.as_ref(), watchdog_log.as_ref(), occupancy_log.as_ref(), profiler_log.as_ref(), id, idx, builder, capacity);
+                        chan_map.insert(id, ChannelMapEntry::Single(ChanType::Sender(snd)));
+                        rcv
                     }
                     _ => panic!("Check whether your id or ChannelMap is correct"),
                 },
             },
             None => {
                 self.instantiate();
-                self.get_receiver(id, idx, builder, capacity)
+                self.get_receiver(id, idx, builder, Some(CapacityPolicy::Elements(capacity)))
+            }
+        }
+    }
+
+    /// Returns a fresh per-subscriber [`Receiver`] backed by a shared
+    /// multicast fan-out, rather than an independent channel: every
+    /// subscriber sees the exact same sequence of `Elem<T>`s produced on
+    /// the single upstream channel for `id`. The first call for `id`
+    /// allocates that upstream channel plus a [`BroadcastContext`] fan-out
+    /// node; each later call for the same `id` just adds another bounded
+    /// sub-channel of size `fanout_hint` as a new target. The node is only
+    /// registered with the builder once the producer's `get_sender(id,
+    /// None, ..)` call claims `upstream` -- see that match arm.
+    pub fn get_broadcast_receiver(
+        &mut self,
+        id: u32,
+        builder: &mut ProgramBuilder<'a>,
+        fanout_hint: usize,
+    ) -> Receiver<Elem<T>>
+    where
+        Elem<T>: DAMType,
+    {
+        let trace_sink = self.trace.sink_for(id).cloned();
+        let watchdog_log = self.watchdog.clone();
+        let occupancy_log = self.occupancy.clone();
+        let profiler_log = self.profiler.clone();
+        if self.map.is_none() {
+            self.instantiate();
+        }
+        let (sub_snd, sub_rcv) = bounded_instrumented(
+            trace_sink.as_ref(),
+            watchdog_log.as_ref(),
+            occupancy_log.as_ref(),
+            profiler_log.as_ref(),
+            id,
+            None,
+            builder,
+            fanout_hint,
+        );
+        let chan_map = self.map.as_mut().unwrap();
+        match chan_map.remove(&id) {
+            Some(ChannelMapEntry::Multicast {
+                upstream,
+                mut node,
+                hint,
+            }) => {
+                node.add_target(sub_snd);
+                chan_map.insert(id, ChannelMapEntry::Multicast { upstream, node, hint });
             }
+            None => {
+                let upstream_capacity = self.default_policy.resolve::<T>();
+                let (upstream_snd, upstream_rcv) = bounded_instrumented(
+                    trace_sink.as_ref(),
+                    watchdog_log.as_ref(),
+                    occupancy_log.as_ref(),
+                    profiler_log.as_ref(),
+                    id,
+                    None,
+                    builder,
+                    upstream_capacity,
+                );
+                let mut node = BroadcastContext::new(upstream_rcv);
+                node.add_target(sub_snd);
+                chan_map.insert(
+                    id,
+                    ChannelMapEntry::Multicast {
+                        upstream: upstream_snd,
+                        node,
+                        hint: fanout_hint,
+                    },
+                );
+            }
+            _ => panic!("Check whether your id or ChannelMap is correct"),
         }
+        sub_rcv
     }
 
     pub fn get_sender(
@@ -188,8 +396,20 @@ where
         id: u32,
         idx: Option<u32>,
         builder: &mut ProgramBuilder<'a>,
-        capacity: Option<usize>,
-    ) -> Sender<Elem<T>> {
+        capacity: Option<CapacityPolicy>,
+    ) -> Sender<Elem<T>>
+    where
+        Elem<T>: DAMType,
+    {
+        let trace_sink = self.trace.sink_for(id).cloned();
+        let watchdog_log = self.watchdog.clone();
+        let occupancy_log = self.occupancy.clone();
+        let profiler_log = self.profiler.clone();
+        let capacity = capacity.unwrap_or(self.default_policy).resolve::<T>();
+        // Canonicalize before touching `map`, so a producer/consumer
+        // addressing the same logical node by different ids (see
+        // `register_alias`) still share one channel.
+        let id = self.canonicalize(id);
         match &mut self.map {
             Some(chan_map) => match idx {
                 Some(stream_idx) => match chan_map.get_mut(&id) {
@@ -197,75 +417,43 @@ where
                     Some(ChannelMapEntry::Broadcast(x)) => match x.remove(&stream_idx) {
                         Some(ChanType::Sender(snd)) => snd,
                         None => {
-                            match capacity {
-                                Some(cap) => {
-                                    let (snd, rcv) = builder.bounded::<Elem<T>>(cap);
-                                    // inspect_receiver(&rcv, 229, id, idx, "203");
-                                    x.insert(stream_idx, ChanType::Receiver(rcv));
-                                    snd
-                                }
-                                None => {
-                                    // Default capacity
-                                    let (snd, rcv) = builder.bounded::<Elem<T>>(DEFAULT_CHAN_SIZE);
-                                    // inspect_receiver(&rcv, 229, id, idx, "210");
-                                    x.insert(stream_idx, ChanType::Receiver(rcv));
-                                    snd
-                                }
-                            }
+                            let (snd, rcv) = bounded_instrumented(trace_sink.as_ref(), watchdog_log.as_ref(), occupancy_log.as_ref(), profiler_log.as_ref(), id, idx, builder, capacity);
+                            x.insert(stream_idx, ChanType::Receiver(rcv));
+                            snd
                         }
                         _ => panic!("Check whether your id or ChannelMap is correct"),
                     },
                     None => {
-                        match capacity {
-                            Some(cap) => {
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(cap);
-                                // inspect_receiver(&rcv, 229, id, idx, "L222");
-                                let mut broadcast_map = HashMap::new();
-                                broadcast_map.insert(stream_idx, ChanType::Receiver(rcv));
-                                chan_map.insert(id, ChannelMapEntry::Broadcast(broadcast_map));
-                                snd
-                            }
-                            None => {
-                                // Default capacity
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(DEFAULT_CHAN_SIZE);
-                                // inspect_receiver(&rcv, 229, id, idx, "L231");
-                                let mut broadcast_map = HashMap::new();
-                                broadcast_map.insert(stream_idx, ChanType::Receiver(rcv));
-                                chan_map.insert(id, ChannelMapEntry::Broadcast(broadcast_map));
-                                snd
-                            }
-                        }
+                        let (snd, rcv) = bounded_instrumented(trace_sink.as_ref(), watchdog_log.as_ref(), occupancy_log.as_ref(), profiler_log.as_ref(), id, idx, builder, capacity);
+                        let mut broadcast_map = HashMap::new();
+                        broadcast_map.insert(stream_idx, ChanType::Receiver(rcv));
+                        chan_map.insert(id, ChannelMapEntry::Broadcast(broadcast_map));
+                        snd
                     }
                     _ => panic!("Check whether your id or ChannelMap is correct"),
                 },
                 None => match chan_map.remove(&id) {
                     // Single
                     Some(ChannelMapEntry::Single(ChanType::Sender(x))) => x,
+                    // Multicast: no more subscribers can join once the
+                    // producer claims `upstream`, so this is where the
+                    // fan-out node built up by `get_broadcast_receiver`
+                    // finally gets registered with the builder.
+                    Some(ChannelMapEntry::Multicast { upstream, node, .. }) => {
+                        builder.add_child(node);
+                        upstream
+                    }
                     None => {
-                        match capacity {
-                            Some(cap) => {
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(cap);
-                                // inspect_receiver(&rcv, 229, id, idx, "L248");
-                                chan_map
-                                    .insert(id, ChannelMapEntry::Single(ChanType::Receiver(rcv)));
-                                snd
-                            }
-                            None => {
-                                // Default capacity
-                                let (snd, rcv) = builder.bounded::<Elem<T>>(DEFAULT_CHAN_SIZE);
-                                // inspect_receiver(&rcv, 229, id, idx, "L256");
-                                chan_map
-                                    .insert(id, ChannelMapEntry::Single(ChanType::Receiver(rcv)));
-                                snd
-                            }
-                        }
+                        let (snd, rcv) = bounded_instrumented(trace_sink.as_ref(), watchdog_log.as_ref(), occupancy_log.as_ref(), profiler_log.as_ref(), id, idx, builder, capacity);
+                        chan_map.insert(id, ChannelMapEntry::Single(ChanType::Receiver(rcv)));
+                        snd
                     }
                     _ => panic!("Check whether your id or ChannelMap is correct"),
                 },
             },
             None => {
                 self.instantiate();
-                self.get_sender(id, idx, builder, capacity)
+                self.get_sender(id, idx, builder, Some(CapacityPolicy::Elements(capacity)))
             }
         }
     }
@@ -273,18 +461,299 @@ where
     pub fn instantiate(&mut self) {
         self.map = Some(HashMap::new());
     }
+
+    /// Merges `alias` into `canonical`'s class: after this,
+    /// `get_sender`/`get_receiver` called with either id resolve to the
+    /// same `map` entry. Safe to call whether or not a channel under
+    /// either id already exists -- canonicalization happens on every
+    /// `get_sender`/`get_receiver` call, not just at registration time.
+    pub fn register_alias(&mut self, canonical: u32, alias: u32) {
+        let canonical_root = self.canonicalize(canonical);
+        let alias_root = self.canonicalize(alias);
+        if canonical_root != alias_root {
+            self.aliases.insert(alias_root, canonical_root);
+        }
+    }
+
+    /// Resolves `id` to its canonical representative, path-compressing
+    /// every link walked along the way so later lookups for the same id
+    /// (or anything that was aliased to it) are O(1).
+    fn canonicalize(&mut self, id: u32) -> u32 {
+        let mut root = id;
+        while let Some(&parent) = self.aliases.get(&root) {
+            root = parent;
+        }
+        let mut cur = id;
+        while cur != root {
+            cur = self.aliases.insert(cur, root).unwrap();
+        }
+        root
+    }
+
+    /// Enables per-channel tracing on this `ChannelMap` -- see
+    /// `ChannelMapCollection::configure_trace`.
+    pub fn configure_trace(&mut self, trace: TraceHandle) {
+        self.trace = trace;
+    }
+
+    /// Enables a [`WatchdogTap`] on every channel this map hands out from
+    /// here on -- see `ChannelMapCollection::configure_watchdog`.
+    pub fn configure_watchdog(&mut self, log: ActivityLog) {
+        self.watchdog = Some(log);
+    }
+
+    /// Enables an [`OccupancyTap`] on every channel this map hands out
+    /// from here on -- see `ChannelMapCollection::configure_occupancy`.
+    pub fn configure_occupancy(&mut self, log: OccupancyLog) {
+        self.occupancy = Some(log);
+    }
+
+    /// Sets the [`CapacityPolicy`] `get_receiver`/`get_sender` fall back to
+    /// when a call site passes `capacity: None` -- see
+    /// `ChannelMapCollection::configure_default_policy`.
+    pub fn configure_default_policy(&mut self, policy: CapacityPolicy) {
+        self.default_policy = policy;
+    }
+
+    /// Enables a [`ProfileTap`] on every channel this map hands out from
+    /// here on -- see `ChannelMapCollection::configure_profiler`.
+    pub fn configure_profiler(&mut self, log: ChannelProfilerLog) {
+        self.profiler = Some(log);
+    }
+}
+
+impl<'a, X: DAMType> ChannelMap<'a, Tile<X>>
+where
+    Tile<X>: DAMType + fmt::Debug + ByteSized + 'a,
+    Elem<Tile<X>>: DAMType,
+    Elem<Chunk<X>>: DAMType,
+{
+    /// Like [`Self::get_receiver`], but the producer's `Elem<Tile<X>>`s are
+    /// rechunked onto a bounded wire of at most `chunk_elems` elements per
+    /// slice before being reassembled back into whole tiles for the
+    /// consumer -- see `crate::operator::rechunk`. Lets a very large tile
+    /// flow through a modestly-sized bounded channel instead of needing one
+    /// queue slot big enough for the whole thing.
+    pub fn get_chunked_receiver(
+        &mut self,
+        id: u32,
+        builder: &mut ProgramBuilder<'a>,
+        chunk_elems: usize,
+    ) -> Receiver<Elem<Tile<X>>> {
+        let raw_rcv = self.get_receiver(id, None, builder, None);
+        let capacity = self.default_policy.resolve::<Tile<X>>().max(1);
+
+        let (chunk_snd, chunk_rcv) = builder.bounded::<Elem<Chunk<X>>>(capacity);
+        builder.add_child(TileChunker::new(raw_rcv, chunk_snd, chunk_elems));
+
+        let (out_snd, out_rcv) = builder.bounded::<Elem<Tile<X>>>(capacity);
+        builder.add_child(TileDechunker::new(chunk_rcv, out_snd));
+
+        out_rcv
+    }
+}
+
+/// Type-erased handle to a `ChannelMap<'a, T>` for some `T`, minus
+/// `std::any::Any`'s `Self: 'static` bound. `ChannelMap<'a, T>` is only
+/// non-`'static` because of its marker `PhantomData<&'a ()>` field -- it
+/// holds no real data borrowed for `'a` -- but that's still enough to make
+/// `Any`'s bound unsatisfiable, so `ChannelMapCollection` can't use it
+/// directly and rolls its own narrow equivalent instead.
+trait ErasedChannelMap<'a> {
+    fn type_id(&self) -> TypeId;
+    fn configure_trace_erased(&mut self, trace: TraceHandle);
+    fn configure_watchdog_erased(&mut self, log: ActivityLog);
+    fn configure_occupancy_erased(&mut self, log: OccupancyLog);
+    fn configure_default_policy_erased(&mut self, policy: CapacityPolicy);
+    fn configure_profiler_erased(&mut self, log: ChannelProfilerLog);
+}
+
+impl<'a, T: DAMType + fmt::Debug + ByteSized + 'static> ErasedChannelMap<'a> for ChannelMap<'a, T> {
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+    fn configure_trace_erased(&mut self, trace: TraceHandle) {
+        self.configure_trace(trace);
+    }
+    fn configure_watchdog_erased(&mut self, log: ActivityLog) {
+        self.configure_watchdog(log);
+    }
+    fn configure_occupancy_erased(&mut self, log: OccupancyLog) {
+        self.configure_occupancy(log);
+    }
+    fn configure_default_policy_erased(&mut self, policy: CapacityPolicy) {
+        self.configure_default_policy(policy);
+    }
+    fn configure_profiler_erased(&mut self, log: ChannelProfilerLog) {
+        self.configure_profiler(log);
+    }
+}
+
+/// Casts an [`ErasedChannelMap`] back to the concrete `ChannelMap<'a, T>`
+/// it was built from.
+///
+/// Safety: [`ChannelMapCollection::channels`] is the only place entries
+/// are inserted, and it always keys an entry by `TypeId::of::<T>()` for
+/// the exact `T` it boxes -- so a value looked up under that same key is
+/// guaranteed to be a `ChannelMap<'a, T>`.
+fn downcast_channel_map<'a, 'b, T: DAMType + fmt::Debug + ByteSized + 'static>(
+    erased: &'b mut Box<dyn ErasedChannelMap<'a> + 'a>,
+) -> &'b mut ChannelMap<'a, T> {
+    debug_assert_eq!(erased.type_id(), TypeId::of::<T>());
+    unsafe { &mut *(erased.as_mut() as *mut dyn ErasedChannelMap<'a> as *mut ChannelMap<'a, T>) }
 }
 
+/// The `configure_*` settings [`ChannelMapCollection::channels`] applies
+/// to a `ChannelMap` at the moment it's lazily created, so a payload type
+/// first touched after e.g. `configure_trace` was called still gets it --
+/// the same as if every field had existed up front.
+#[derive(Clone, Default)]
+struct CollectionConfig {
+    trace: TraceHandle,
+    watchdog: Option<ActivityLog>,
+    occupancy: Option<OccupancyLog>,
+    default_policy: CapacityPolicy,
+    profiler: Option<ChannelProfilerLog>,
+}
+
+/// Registry of one [`ChannelMap`] per distinct payload type, keyed by
+/// `TypeId` via [`Self::channels`] instead of a hardcoded field per type --
+/// a new `DAMType` used in a graph (a new tile element type, a composite
+/// struct) just calls `channels::<NewType>()`, with no edit to this module
+/// needed. The named accessors below (`tile_f32`, `multihot`, ...) are
+/// thin wrappers over `channels` kept for source compatibility with the
+/// call sites already written against them in `proto_driver`.
 #[derive(Default)]
 pub struct ChannelMapCollection<'a> {
-    pub dummy: ChannelMap<'a, ()>,
-    // data types
-    pub tile_f32: ChannelMap<'a, Tile<f32>>,
-    pub tile_u64: ChannelMap<'a, Tile<u64>>,
-    // buffered data types
-    pub buff_tile_f32: ChannelMap<'a, Buffer<Tile<f32>>>,
-    // select
-    pub multihot: ChannelMap<'a, MultiHotN>,
-    pub u64: ChannelMap<'a, u64>,
-    pub bool: ChannelMap<'a, bool>,
+    channels: HashMap<TypeId, Box<dyn ErasedChannelMap<'a> + 'a>>,
+    config: CollectionConfig,
+}
+
+impl<'a> ChannelMapCollection<'a> {
+    /// Lazily creates (applying this collection's current
+    /// trace/watchdog/occupancy/default-policy config, same as every
+    /// other payload type got) and returns the `ChannelMap` for `T`.
+    pub fn channels<T>(&mut self) -> &mut ChannelMap<'a, T>
+    where
+        T: DAMType + fmt::Debug + ByteSized + 'static,
+    {
+        let config = self.config.clone();
+        let entry = self.channels.entry(TypeId::of::<T>()).or_insert_with(|| {
+            let mut map = ChannelMap::<'a, T>::default();
+            map.configure_trace(config.trace);
+            if let Some(log) = config.watchdog {
+                map.configure_watchdog(log);
+            }
+            if let Some(log) = config.occupancy {
+                map.configure_occupancy(log);
+            }
+            map.configure_default_policy(config.default_policy);
+            if let Some(log) = config.profiler {
+                map.configure_profiler(log);
+            }
+            Box::new(map) as Box<dyn ErasedChannelMap<'a> + 'a>
+        });
+        downcast_channel_map::<T>(entry)
+    }
+
+    pub fn dummy(&mut self) -> &mut ChannelMap<'a, ()> {
+        self.channels::<()>()
+    }
+    pub fn tile_f32(&mut self) -> &mut ChannelMap<'a, Tile<f32>> {
+        self.channels::<Tile<f32>>()
+    }
+    pub fn tile_u64(&mut self) -> &mut ChannelMap<'a, Tile<u64>> {
+        self.channels::<Tile<u64>>()
+    }
+    pub fn buff_tile_f32(&mut self) -> &mut ChannelMap<'a, Buffer<Tile<f32>>> {
+        self.channels::<Buffer<Tile<f32>>>()
+    }
+    pub fn buff_tile_u64(&mut self) -> &mut ChannelMap<'a, Buffer<Tile<u64>>> {
+        self.channels::<Buffer<Tile<u64>>>()
+    }
+    pub fn buff_multihot(&mut self) -> &mut ChannelMap<'a, Buffer<MultiHotN>> {
+        self.channels::<Buffer<MultiHotN>>()
+    }
+    pub fn multihot(&mut self) -> &mut ChannelMap<'a, MultiHotN> {
+        self.channels::<MultiHotN>()
+    }
+    pub fn u64(&mut self) -> &mut ChannelMap<'a, u64> {
+        self.channels::<u64>()
+    }
+    pub fn bool(&mut self) -> &mut ChannelMap<'a, bool> {
+        self.channels::<bool>()
+    }
+
+    /// Turns on tracing (cheaply cloned into every already-created
+    /// `ChannelMap`, and stashed for any created later) for every
+    /// `get_receiver`/`get_sender` call made through this collection from
+    /// here on -- see `proto_driver::build_from_proto`'s setup of
+    /// `SimConfig::trace_channel_ids`.
+    pub fn configure_trace(&mut self, trace: TraceHandle) {
+        self.config.trace = trace.clone();
+        for erased in self.channels.values_mut() {
+            erased.configure_trace_erased(trace.clone());
+        }
+    }
+
+    /// Turns on a [`WatchdogTap`] (cheaply cloned into every
+    /// already-created `ChannelMap`, and stashed for any created later)
+    /// for every `get_receiver`/`get_sender` call made through this
+    /// collection from here on -- see `proto_driver::build_from_proto`'s
+    /// setup of `SimConfig::watchdog_timeout_ms`.
+    pub fn configure_watchdog(&mut self, log: ActivityLog) {
+        self.config.watchdog = Some(log.clone());
+        for erased in self.channels.values_mut() {
+            erased.configure_watchdog_erased(log.clone());
+        }
+    }
+
+    /// Turns on an [`OccupancyTap`] (cheaply cloned into every
+    /// already-created `ChannelMap`, and stashed for any created later)
+    /// for every `get_receiver`/`get_sender` call made through this
+    /// collection from here on -- see `proto_driver::build_from_proto`'s
+    /// setup of `SimConfig::html_report_path`.
+    pub fn configure_occupancy(&mut self, log: OccupancyLog) {
+        self.config.occupancy = Some(log.clone());
+        for erased in self.channels.values_mut() {
+            erased.configure_occupancy_erased(log.clone());
+        }
+    }
+
+    /// Sets the [`CapacityPolicy`] every already-created (and, stashed for
+    /// any created later) `ChannelMap` falls back to when a
+    /// `get_receiver`/`get_sender` call passes `capacity: None` -- so a
+    /// `Bytes` budget (translated per payload type via its own
+    /// [`ByteSized`] hint) sizes every channel this collection creates
+    /// consistently, rather than each type needing its own override at
+    /// every call site.
+    pub fn configure_default_policy(&mut self, policy: CapacityPolicy) {
+        self.config.default_policy = policy;
+        for erased in self.channels.values_mut() {
+            erased.configure_default_policy_erased(policy);
+        }
+    }
+
+    /// Turns on buffer-sizing profiling (cheaply cloned into every
+    /// already-created `ChannelMap`, and stashed for any created later,
+    /// same as `configure_occupancy`) for every `get_receiver`/`get_sender`
+    /// call made through this collection from here on -- see
+    /// [`Self::report`] for reading the results back out.
+    pub fn configure_profiler(&mut self, log: ChannelProfilerLog) {
+        self.config.profiler = Some(log.clone());
+        for erased in self.channels.values_mut() {
+            erased.configure_profiler_erased(log.clone());
+        }
+    }
+
+    /// Returns the accumulated [`ChannelStats`] for every channel profiled
+    /// so far (see [`Self::configure_profiler`]), sorted by
+    /// peak-occupancy-to-capacity ratio. Empty if profiling was never
+    /// enabled.
+    pub fn report(&self) -> Vec<ChannelStats> {
+        match &self.config.profiler {
+            Some(log) => log.snapshot(),
+            None => Vec::new(),
+        }
+    }
 }
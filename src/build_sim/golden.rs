@@ -0,0 +1,121 @@
+//! Golden-vector capture/compare for `ConsumerContext` (see
+//! `proto_driver::get_golden_mode` and its use in the `OpType::ConsumerContext`
+//! builder arm): per `input_id`, a run can either record every token it
+//! receives as a canonical hex-encoded vector file, or replay a
+//! previously-recorded file and assert the live stream matches it
+//! token-for-token. This turns a plain sink into a regression oracle --
+//! capture once from a known-good run, then compare on every later run to
+//! catch a change to an upstream operator perturbing the stream.
+//!
+//! Each line of a vector file is one token (an `Elem<T>`, `Val` or
+//! `ValStop` alike) rendered via `{:?}` and hex-encoded, so the format is
+//! plain text but dtype-agnostic -- it works the same for `Tile<f32>`,
+//! `Tile<u64>`, `MultiHot`, `u64`, and `bool` without per-dtype framing.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::Elem;
+
+/// Which golden-vector behavior a `ConsumerContext` operation should run in
+/// -- see `proto_driver::get_golden_mode`.
+#[derive(Debug, Clone)]
+pub enum GoldenMode {
+    /// Record every received token to `path` as a hex-encoded vector,
+    /// overwriting any existing file.
+    Capture(String),
+    /// Read `path` as a previously-captured vector and assert the live
+    /// stream matches it token-for-token.
+    Compare(String),
+}
+
+fn encode_hex(value: &dyn Debug) -> String {
+    let debug = format!("{value:?}");
+    let mut hex = String::with_capacity(debug.len() * 2);
+    for byte in debug.as_bytes() {
+        hex.push_str(&format!("{byte:02x}"));
+    }
+    hex
+}
+
+/// A `ConsumerContext` replacement that captures or compares a golden
+/// vector instead of silently discarding its input -- see [`GoldenMode`].
+#[context_macro]
+pub struct GoldenContext<T: DAMType + Debug> {
+    in_stream: Receiver<Elem<T>>,
+    mode: GoldenMode,
+}
+
+impl<T: DAMType + Debug> GoldenContext<T>
+where
+    Self: Context,
+{
+    pub fn new(in_stream: Receiver<Elem<T>>, mode: GoldenMode) -> Self {
+        let ctx = Self {
+            in_stream,
+            mode,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType + Debug> Context for GoldenContext<T> {
+    fn run(&mut self) {
+        match &self.mode {
+            GoldenMode::Capture(path) => {
+                let file = File::create(path)
+                    .unwrap_or_else(|e| panic!("golden capture: couldn't create {path}: {e}"));
+                let mut writer = BufWriter::new(file);
+                loop {
+                    match self.in_stream.dequeue(&self.time) {
+                        Ok(ChannelElement { data, .. }) => {
+                            writeln!(writer, "{}", encode_hex(&data)).unwrap();
+                        }
+                        Err(_) => {
+                            writer.flush().unwrap();
+                            return;
+                        }
+                    }
+                }
+            }
+            GoldenMode::Compare(path) => {
+                let file = File::open(path)
+                    .unwrap_or_else(|e| panic!("golden compare: couldn't open {path}: {e}"));
+                let mut reference = BufReader::new(file).lines();
+                let mut index = 0usize;
+                loop {
+                    match self.in_stream.dequeue(&self.time) {
+                        Ok(ChannelElement { data, .. }) => {
+                            let actual = encode_hex(&data);
+                            match reference.next() {
+                                Some(expected) => {
+                                    let expected = expected.unwrap();
+                                    assert_eq!(
+                                        expected, actual,
+                                        "golden vector mismatch at index {index}: expected {expected}, got {actual}"
+                                    );
+                                }
+                                None => panic!(
+                                    "golden vector mismatch at index {index}: stream is longer than {path}"
+                                ),
+                            }
+                            index += 1;
+                        }
+                        Err(_) => {
+                            assert!(
+                                reference.next().is_none(),
+                                "golden vector mismatch: stream stopped at index {index}, shorter than {path}"
+                            );
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod channel;
+pub mod golden;
+pub mod occupancy;
+pub mod profiler;
+pub mod trace;
+pub mod watchdog;
@@ -0,0 +1,138 @@
+//! Per-channel occupancy/stall tracking for `crate::utils::html_report`:
+//! when an HTML run report is requested (`SimConfig::html_report_path`),
+//! every channel gets an [`OccupancyTap`] spliced in (see
+//! `crate::build_sim::channel::ChannelMap::configure_occupancy`) that
+//! records, per token, how many cycles it sat in the channel before being
+//! forwarded. `dam`'s internal channel queue depth isn't introspectable
+//! from outside it, so this crate can't report a literal "N tokens
+//! currently buffered" peak; `peak_stall` (the longest wait any single
+//! token saw) is used as the occupancy proxy instead -- a channel that's
+//! never made a token wait is never a bottleneck, and one where tokens
+//! routinely wait dozens of cycles is.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::Elem;
+
+/// One channel's accumulated stats at the end of a run, as returned by
+/// [`OccupancyLog::snapshot`].
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelOccupancy {
+    pub id: u32,
+    pub depth: usize,
+    pub tokens: u64,
+    pub stalled_tokens: u64,
+    pub peak_stall: u64,
+    pub total_stall: u64,
+}
+
+#[derive(Default, Clone, Copy)]
+struct ChannelOccupancyState {
+    depth: usize,
+    tokens: u64,
+    stalled_tokens: u64,
+    peak_stall: u64,
+    total_stall: u64,
+}
+
+/// Shared accumulator every [`OccupancyTap`] records into -- cheap to
+/// clone (an `Arc` around the map), so every traced channel can hold its
+/// own handle to the same log.
+#[derive(Clone, Default)]
+pub struct OccupancyLog(Arc<Mutex<HashMap<u32, ChannelOccupancyState>>>);
+
+impl OccupancyLog {
+    fn record(&self, id: u32, depth: usize, stall: u64) {
+        let mut log = self.0.lock().unwrap();
+        let state = log.entry(id).or_insert_with(|| ChannelOccupancyState {
+            depth,
+            ..Default::default()
+        });
+        state.tokens += 1;
+        state.total_stall += stall;
+        state.peak_stall = state.peak_stall.max(stall);
+        if stall > 0 {
+            state.stalled_tokens += 1;
+        }
+    }
+
+    /// A snapshot of every channel seen so far, ordered by id.
+    pub fn snapshot(&self) -> Vec<ChannelOccupancy> {
+        let mut entries: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&id, state)| ChannelOccupancy {
+                id,
+                depth: state.depth,
+                tokens: state.tokens,
+                stalled_tokens: state.stalled_tokens,
+                peak_stall: state.peak_stall,
+                total_stall: state.total_stall,
+            })
+            .collect();
+        entries.sort_by_key(|occupancy| occupancy.id);
+        entries
+    }
+}
+
+/// Spliced transparently between a channel's real producer and consumer
+/// (see `ChannelMap::bounded_instrumented`): forwards every token unchanged
+/// while recording how long it waited (arrival cycle vs. forwarding cycle)
+/// into `log`.
+#[context_macro]
+pub struct OccupancyTap<T: DAMType + Debug> {
+    in_stream: Receiver<Elem<T>>,
+    out_stream: Sender<Elem<T>>,
+    id: u32,
+    depth: usize,
+    log: OccupancyLog,
+}
+
+impl<T: DAMType + Debug> OccupancyTap<T>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<T>>,
+        out_stream: Sender<Elem<T>>,
+        id: u32,
+        depth: usize,
+        log: OccupancyLog,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            id,
+            depth,
+            log,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType + Debug> Context for OccupancyTap<T> {
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: arrive, data }) => {
+                    let forward = self.time.tick();
+                    let stall = forward.time().saturating_sub(arrive.time());
+                    self.log.record(self.id, self.depth, stall);
+                    self.out_stream
+                        .enqueue(&self.time, ChannelElement { time: forward, data })
+                        .unwrap();
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Opt-in per-channel buffer-sizing profiler: replaces the old
+//! `inspect_sender`/`inspect_receiver` scaffolding in
+//! `crate::build_sim::channel` (which only ever panicked when a
+//! hardcoded target id turned up, and was commented out at every call
+//! site) with something a user can actually act on. When enabled (see
+//! `ChannelMap::configure_profiler`), every channel gets a
+//! [`ProfileTap`] spliced in that records its allocated capacity, its
+//! element type's name, and -- using the same stall-as-occupancy-proxy
+//! rationale as `crate::build_sim::occupancy` (`dam`'s internal queue
+//! depth isn't introspectable from outside it) -- how long the longest
+//! token waited and how many tokens waited at all. `ChannelMapCollection`
+//! exposes the accumulated [`ChannelStats`] through `report()`, sorted by
+//! peak-occupancy-to-capacity ratio so the channels most worth retuning
+//! sort to the top.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::Elem;
+
+/// One channel's accumulated stats at the end of a run, as returned by
+/// [`ChannelProfilerLog::snapshot`].
+#[derive(Debug, Clone)]
+pub struct ChannelStats {
+    pub id: u32,
+    pub stream_idx: Option<u32>,
+    pub type_name: &'static str,
+    pub capacity: usize,
+    /// Longest any single token sat in this channel before being
+    /// forwarded, in cycles -- a proxy for "how full did this channel
+    /// get", not a literal token count (see module docs).
+    pub peak_occupancy: u64,
+    /// Number of tokens that waited at all (`stall > 0`) before being
+    /// forwarded -- a proxy for how often the producer ran ahead of a
+    /// slower consumer and had to block on a full queue.
+    pub blocked_count: u64,
+}
+
+impl ChannelStats {
+    fn occupancy_ratio(&self) -> f64 {
+        if self.capacity == 0 {
+            0.0
+        } else {
+            self.peak_occupancy as f64 / self.capacity as f64
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ChannelProfilerState {
+    type_name: &'static str,
+    capacity: usize,
+    peak_occupancy: u64,
+    blocked_count: u64,
+}
+
+/// Shared accumulator every [`ProfileTap`] records into -- cheap to
+/// clone (an `Arc` around the map), so every profiled channel can hold
+/// its own handle to the same log.
+#[derive(Clone, Default)]
+pub struct ChannelProfilerLog(Arc<Mutex<HashMap<(u32, Option<u32>), ChannelProfilerState>>>);
+
+impl ChannelProfilerLog {
+    fn record(
+        &self,
+        id: u32,
+        stream_idx: Option<u32>,
+        type_name: &'static str,
+        capacity: usize,
+        stall: u64,
+    ) {
+        let mut log = self.0.lock().unwrap();
+        let state = log
+            .entry((id, stream_idx))
+            .or_insert_with(|| ChannelProfilerState {
+                type_name,
+                capacity,
+                peak_occupancy: 0,
+                blocked_count: 0,
+            });
+        state.peak_occupancy = state.peak_occupancy.max(stall);
+        if stall > 0 {
+            state.blocked_count += 1;
+        }
+    }
+
+    /// A snapshot of every profiled channel seen so far, sorted by
+    /// peak-occupancy-to-capacity ratio (descending) so the channels
+    /// worth retuning first sort to the top.
+    pub fn snapshot(&self) -> Vec<ChannelStats> {
+        let mut entries: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&(id, stream_idx), state)| ChannelStats {
+                id,
+                stream_idx,
+                type_name: state.type_name,
+                capacity: state.capacity,
+                peak_occupancy: state.peak_occupancy,
+                blocked_count: state.blocked_count,
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.occupancy_ratio()
+                .partial_cmp(&a.occupancy_ratio())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        entries
+    }
+}
+
+/// Spliced transparently between a channel's real producer and consumer
+/// (see `ChannelMap::bounded_instrumented`): forwards every token
+/// unchanged while recording its wait time into `log` under `(id,
+/// stream_idx)`.
+#[context_macro]
+pub struct ProfileTap<T: DAMType + Debug> {
+    in_stream: Receiver<Elem<T>>,
+    out_stream: Sender<Elem<T>>,
+    id: u32,
+    stream_idx: Option<u32>,
+    capacity: usize,
+    log: ChannelProfilerLog,
+}
+
+impl<T: DAMType + Debug> ProfileTap<T>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<T>>,
+        out_stream: Sender<Elem<T>>,
+        id: u32,
+        stream_idx: Option<u32>,
+        capacity: usize,
+        log: ChannelProfilerLog,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            id,
+            stream_idx,
+            capacity,
+            log,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType + Debug> Context for ProfileTap<T> {
+    fn run(&mut self) {
+        let type_name = std::any::type_name::<T>();
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: arrive, data }) => {
+                    let forward = self.time.tick();
+                    let stall = forward.time().saturating_sub(arrive.time());
+                    self.log
+                        .record(self.id, self.stream_idx, type_name, self.capacity, stall);
+                    self.out_stream
+                        .enqueue(&self.time, ChannelElement { time: forward, data })
+                        .unwrap();
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Optional per-channel token trace wired into
+//! [`crate::build_sim::channel::ChannelMap`]'s `get_receiver`/`get_sender`:
+//! when a channel's id is selected (`SimConfig::trace_channel_ids`), every
+//! token that crosses it also gets appended to an on-disk file as
+//! `id,cycle,value`. Modeled as a rolling buffered sink -- records
+//! accumulate up to `buffer_size` before a flush, and once the file reaches
+//! `max_file_size` recording stops rather than growing it further across a
+//! long simulation.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::sync::{Arc, Mutex};
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::Elem;
+
+/// Default record count buffered in memory before a flush, used when
+/// `SimConfig`'s Python object doesn't set `trace_buffer_size`.
+pub const DEFAULT_TRACE_BUFFER_SIZE: usize = 256;
+/// Default cap on a trace file's size, used when `SimConfig`'s Python
+/// object doesn't set `trace_max_file_size`.
+pub const DEFAULT_TRACE_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The on-disk sink every [`TraceTap`] on a traced run writes into. Cheap
+/// to clone -- an `Arc` around the buffered writer -- so every traced
+/// channel can hold its own handle to the same file without fighting over
+/// who opens it.
+#[derive(Clone)]
+pub struct TraceSink(Arc<Mutex<TraceSinkState>>);
+
+struct TraceSinkState {
+    writer: BufWriter<File>,
+    buffer_size: usize,
+    max_file_size: u64,
+    bytes_written: u64,
+    pending: usize,
+    stopped: bool,
+}
+
+impl TraceSink {
+    /// Opens (creating if needed) `data_file` for appending.
+    pub fn open(data_file: &str, buffer_size: usize, max_file_size: u64) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(data_file)?;
+        Ok(Self(Arc::new(Mutex::new(TraceSinkState {
+            writer: BufWriter::new(file),
+            buffer_size,
+            max_file_size,
+            bytes_written: 0,
+            pending: 0,
+            stopped: false,
+        }))))
+    }
+
+    /// Appends one `id,cycle,value` record, flushing every `buffer_size`
+    /// records. Stops writing (rather than growing the file past the cap)
+    /// once `max_file_size` bytes have been written.
+    fn record(&self, id: u32, cycle: u64, value: &dyn Debug) {
+        let mut state = self.0.lock().unwrap();
+        if state.stopped {
+            return;
+        }
+        let line = format!("{id},{cycle},{value:?}\n");
+        state.bytes_written += line.len() as u64;
+        let _ = state.writer.write_all(line.as_bytes());
+        state.pending += 1;
+        if state.pending >= state.buffer_size {
+            let _ = state.writer.flush();
+            state.pending = 0;
+        }
+        if state.bytes_written >= state.max_file_size {
+            let _ = state.writer.flush();
+            state.stopped = true;
+        }
+    }
+}
+
+/// Which channel ids are traced, and where to -- a cheaply-cloneable view
+/// shared across every `ChannelMap` of a
+/// [`crate::build_sim::channel::ChannelMapCollection`]; see
+/// `ChannelMapCollection::configure_trace`. Disabled (`None`) by default, so
+/// a graph built without ever calling `configure_trace` pays no cost.
+#[derive(Clone, Default)]
+pub struct TraceHandle {
+    enabled: Option<(Arc<HashSet<u32>>, TraceSink)>,
+}
+
+impl TraceHandle {
+    pub fn new(channel_ids: Arc<HashSet<u32>>, sink: TraceSink) -> Self {
+        Self {
+            enabled: Some((channel_ids, sink)),
+        }
+    }
+
+    /// The sink to record to if `id` is selected for tracing, else `None`.
+    /// Mirrors `proto_driver::get_chan_depth`'s per-id lookup, but answers
+    /// "should this channel be traced" instead of "what's its depth".
+    pub fn sink_for(&self, id: u32) -> Option<&TraceSink> {
+        self.enabled
+            .as_ref()
+            .filter(|(ids, _)| ids.contains(&id))
+            .map(|(_, sink)| sink)
+    }
+}
+
+/// Spliced transparently between a channel's real producer and consumer
+/// (see `ChannelMap::bounded_instrumented`): forwards every token unchanged while
+/// also recording its value and arrival cycle to `sink`.
+#[context_macro]
+pub struct TraceTap<T: DAMType + Debug> {
+    in_stream: Receiver<Elem<T>>,
+    out_stream: Sender<Elem<T>>,
+    id: u32,
+    sink: TraceSink,
+}
+
+impl<T: DAMType + Debug> TraceTap<T>
+where
+    Self: Context,
+{
+    pub fn new(in_stream: Receiver<Elem<T>>, out_stream: Sender<Elem<T>>, id: u32, sink: TraceSink) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            id,
+            sink,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType + Debug> Context for TraceTap<T> {
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: arrive, data }) => {
+                    self.sink.record(self.id, arrive.time(), &data);
+                    self.out_stream
+                        .enqueue(
+                            &self.time,
+                            ChannelElement {
+                                time: self.time.tick(),
+                                data,
+                            },
+                        )
+                        .unwrap();
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Per-channel activity tracking for [`crate::utils::watchdog`]: when a run's
+//! wall-clock budget is configured (`SimConfig::watchdog_timeout_ms`), every
+//! channel gets a [`WatchdogTap`] spliced in (see
+//! `crate::build_sim::channel::ChannelMap::configure_watchdog`) that records
+//! the last cycle a token crossed it into a shared [`ActivityLog`]. If the
+//! run is still going when the budget expires, the watchdog reads this log
+//! to report which channels have gone quiet -- the likely deadlock site --
+//! instead of leaving a hung CI job with no diagnostic at all.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::Elem;
+
+/// One channel's last-known activity, as of an [`ActivityLog::snapshot`].
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelActivity {
+    pub id: u32,
+    pub last_cycle: u64,
+    pub tokens: u64,
+}
+
+/// Shared, cheaply-cloneable record of the last cycle each channel id
+/// advanced to, updated by every spliced-in [`WatchdogTap`]. Empty (and
+/// free to clone/hold onto) when no run has configured a watchdog.
+#[derive(Clone, Default)]
+pub struct ActivityLog(Arc<Mutex<HashMap<u32, (u64, u64)>>>);
+
+impl ActivityLog {
+    fn record(&self, id: u32, cycle: u64) {
+        let mut activity = self.0.lock().unwrap();
+        let entry = activity.entry(id).or_insert((0, 0));
+        entry.0 = cycle;
+        entry.1 += 1;
+    }
+
+    /// Every channel id a token has ever crossed, oldest `last_cycle`
+    /// first -- the channels at the front are the ones that have gone
+    /// quiet the longest, and so are the most likely to be the stuck side
+    /// of a deadlock.
+    pub fn snapshot(&self) -> Vec<ChannelActivity> {
+        let mut entries: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&id, &(last_cycle, tokens))| ChannelActivity {
+                id,
+                last_cycle,
+                tokens,
+            })
+            .collect();
+        entries.sort_by_key(|activity| activity.last_cycle);
+        entries
+    }
+}
+
+/// Spliced transparently between a channel's real producer and consumer
+/// (see `ChannelMap::bounded_instrumented`): forwards every token unchanged
+/// while also recording its arrival cycle into `log`.
+#[context_macro]
+pub struct WatchdogTap<T: DAMType + Debug> {
+    in_stream: Receiver<Elem<T>>,
+    out_stream: Sender<Elem<T>>,
+    id: u32,
+    log: ActivityLog,
+}
+
+impl<T: DAMType + Debug> WatchdogTap<T>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<T>>,
+        out_stream: Sender<Elem<T>>,
+        id: u32,
+        log: ActivityLog,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            id,
+            log,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType + Debug> Context for WatchdogTap<T> {
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: arrive, data }) => {
+                    self.log.record(self.id, arrive.time());
+                    self.out_stream
+                        .enqueue(
+                            &self.time,
+                            ChannelElement {
+                                time: self.time.tick(),
+                                data,
+                            },
+                        )
+                        .unwrap();
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
@@ -150,22 +150,46 @@ pub fn retile_row<T: Debug + ndarray::LinalgScalar>(
     match &in_data.underlying {
         Some(in_arr) => {
             let cur_arr = accumulator.underlying.clone().unwrap();
-
-            (
-                0, // TODO: Add cycles it took for grouping smaller tiles into larger tiles
-                ndarray::concatenate(ndarray::Axis(0), &[cur_arr.view(), in_arr.view()])
-                    .map(|arr| {
-                        Tile::new_padded(
-                            arr.to_shared(),
-                            in_data.bytes_per_elem,
-                            in_data.read_from_mu,
-                            accum_offset + in_offset,
-                        )
-                    })
-                    .unwrap_or_else(|_| {
-                        panic!("Failed to concatenate input data and accumulator data")
-                    }),
-            )
+            let new_offset = accum_offset + in_offset;
+
+            // If `accumulator` was built with `Tile::with_capacity` (or is a
+            // prior output of this fast path), its backing array is already
+            // sized for the final row count and has `shape[0] - accum_offset`
+            // spare rows beyond what's filled so far. Write the new active
+            // rows into that spare capacity in place instead of reallocating
+            // via `concatenate` on every step.
+            if cur_arr.shape()[0] >= new_offset && cur_arr.shape()[1] == in_arr.shape()[1] {
+                let mut result = cur_arr.to_owned();
+                result
+                    .slice_mut(ndarray::s![accum_offset..new_offset, ..])
+                    .assign(&in_arr.slice(ndarray::s![0..in_offset, ..]));
+
+                (
+                    0,
+                    Tile::new_padded(
+                        result.into_shared(),
+                        in_data.bytes_per_elem,
+                        in_data.read_from_mu,
+                        new_offset,
+                    ),
+                )
+            } else {
+                (
+                    0, // TODO: Add cycles it took for grouping smaller tiles into larger tiles
+                    ndarray::concatenate(ndarray::Axis(0), &[cur_arr.view(), in_arr.view()])
+                        .map(|arr| {
+                            Tile::new_padded(
+                                arr.to_shared(),
+                                in_data.bytes_per_elem,
+                                in_data.read_from_mu,
+                                new_offset,
+                            )
+                        })
+                        .unwrap_or_else(|_| {
+                            panic!("Failed to concatenate input data and accumulator data")
+                        }),
+                )
+            }
         }
         None => {
             assert_eq!(in_data.shape[1], accumulator.shape[1]);
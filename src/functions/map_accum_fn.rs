@@ -1,6 +1,62 @@
 use crate::primitives::tile::Tile;
 use crate::utils::calculation::div_ceil;
 
+/// Sparse-times-dense matmul: `in1` is a CSR-backed `Tile` [M,K]
+/// (see [`crate::primitives::tile::CsrData`]) and `in2` is a dense `Tile`
+/// [K,N]. Only the `nnz` non-zero entries of `in1` are ever touched, so the
+/// modeled cost is `div_ceil(2*nnz*n, flop_per_cycle)` rather than the dense
+/// `2*m*k*n` matmul pays. `accumulator`, `write_back_mu`, and `offset`
+/// follow the same conventions as [`dyn_matmul`].
+pub fn spmm<T: ndarray::LinalgScalar>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    accumulator: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    assert_eq!(in2.shape.len(), 2);
+    let m = in1.shape[0];
+    let k = in1.shape[1];
+    let n = in2.shape[1];
+    assert_eq!(k, in2.shape[0]); // reduction dim has to be the same (K)
+
+    let offset = in1.offset;
+
+    let csr = in1
+        .csr
+        .as_ref()
+        .expect("spmm requires in1 to be a CSR-backed Tile");
+    let nnz = csr.values.len();
+
+    match &in2.underlying {
+        Some(dense) => {
+            let mut out_arr = ndarray::Array2::<T>::zeros((m, n));
+            for row in 0..m {
+                for p in csr.indptr[row]..csr.indptr[row + 1] {
+                    let col = csr.indices[p];
+                    let val = csr.values[p];
+                    let dense_row = dense.row(col);
+                    for j in 0..n {
+                        out_arr[[row, j]] = out_arr[[row, j]] + val * dense_row[j];
+                    }
+                }
+            }
+            let out_arr = match &accumulator.underlying {
+                Some(acc_arr) => acc_arr + out_arr,
+                None => panic!("Accumulator tile must have an underlying array for spmm operation"),
+            };
+            (
+                div_ceil((2 * nnz * n) as u64, flop_per_cycle),
+                Tile::new_padded(out_arr.to_shared(), in2.bytes_per_elem, write_back_mu, offset),
+            )
+        }
+        None => (
+            div_ceil((2 * nnz * n) as u64, flop_per_cycle),
+            Tile::new_blank_padded(vec![m, n], in2.bytes_per_elem, write_back_mu, offset),
+        ),
+    }
+}
+
 /// matmul
 /// - `write_back_mu`: Whether the output is written to a memory unit. <br/>
 ///     - If yes, the `read_from_mu` field of output tile should be set to this value
@@ -69,6 +125,197 @@ pub fn matmul<T: ndarray::LinalgScalar>(
     }
 }
 
+/// Batched matmul: `numpy.matmul`'s semantics for stacks of matrices,
+/// where leading batch dimensions are an independent GEMM loop rather than
+/// a reduction. Any number of leading batch dims is allowed (e.g.
+/// `[experts, batch, M, K]`) -- they're folded into a single flattened
+/// batch count, the product of all dims but the trailing two. `Tile` stays
+/// 2D-backed (its `underlying` is an `ArcArray2`), so each batch is stored
+/// back-to-back along axis 0 of the flat array, the same row-stacking
+/// convention [`crate::functions::accum_fn::retile_row`] uses: `in1` is
+/// logically `[..B, M, K]` but its `underlying` has shape `[prod(B)*M, K]`,
+/// and `in2` is `[..B, K, N]` (or `[..B, N, K]` when `weight_transposed`)
+/// stored as `[prod(B)*K, N]` (or `[prod(B)*N, K]`). `accumulator` and the
+/// output carry the same `[..B, M, N]` / `[prod(B)*M, N]` split, with the
+/// batch dims preserved in `shape` rather than flattened away, so
+/// downstream consumers can tell a batched tile apart from a plain 2D one.
+///
+/// Cost scales with the full batch: `2*prod(B)*M*K*N` flops at
+/// `flop_per_cycle`.
+pub fn batched_matmul<T: ndarray::LinalgScalar>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    accumulator: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+    weight_transposed: bool,
+) -> (u64, Tile<T>) {
+    assert!(
+        in1.shape.len() >= 3,
+        "batched_matmul expects a [..batch_dims, M, K] in1 tile"
+    );
+    assert!(
+        in2.shape.len() >= 3,
+        "batched_matmul expects a [..batch_dims, K, N] (or [..batch_dims, N, K]) in2 tile"
+    );
+    let in1_batch_dims = &in1.shape[..in1.shape.len() - 2];
+    let in2_batch_dims = &in2.shape[..in2.shape.len() - 2];
+    assert_eq!(in1_batch_dims, in2_batch_dims); // batch dims have to match
+    let batch: usize = in1_batch_dims.iter().product();
+    let m = in1.shape[in1.shape.len() - 2];
+    let k = in1.shape[in1.shape.len() - 1];
+    let n = if !weight_transposed {
+        assert_eq!(k, in2.shape[in2.shape.len() - 2]); // reduction dim has to be the same (K)
+        in2.shape[in2.shape.len() - 1]
+    } else {
+        assert_eq!(k, in2.shape[in2.shape.len() - 1]); // reduction dim has to be the same (K)
+        in2.shape[in2.shape.len() - 2]
+    };
+    let out_shape: Vec<usize> = in1_batch_dims.iter().copied().chain([m, n]).collect();
+    assert_eq!(accumulator.shape, out_shape); // accumulator shape check
+    assert_eq!(in1.bytes_per_elem, in2.bytes_per_elem); // has to be represented in the same data type
+
+    // offset is propagated from the input tile
+    let offset = in1.offset;
+    let in2_rows_per_batch = if weight_transposed { n } else { k };
+
+    match (&in1.underlying, &in2.underlying) {
+        (Some(arr1), Some(arr2)) => {
+            let acc_arr = accumulator
+                .underlying
+                .as_ref()
+                .expect("Accumulator tile must have an underlying array for batched_matmul operation");
+            let mut out_arr = ndarray::Array2::<T>::zeros((batch * m, n));
+            for b in 0..batch {
+                let a_blk = arr1.slice(ndarray::s![b * m..(b + 1) * m, ..]);
+                let w_blk = arr2.slice(ndarray::s![
+                    b * in2_rows_per_batch..(b + 1) * in2_rows_per_batch,
+                    ..
+                ]);
+                let prod = match weight_transposed {
+                    true => a_blk.dot(&w_blk.t()),
+                    false => a_blk.dot(&w_blk),
+                };
+                let acc_blk = acc_arr.slice(ndarray::s![b * m..(b + 1) * m, ..]);
+                out_arr
+                    .slice_mut(ndarray::s![b * m..(b + 1) * m, ..])
+                    .assign(&(&acc_blk + &prod));
+            }
+            (
+                div_ceil((2 * batch * m * k * n) as u64, flop_per_cycle),
+                Tile {
+                    shape: out_shape,
+                    bytes_per_elem: in1.bytes_per_elem,
+                    read_from_mu: write_back_mu,
+                    underlying: Some(out_arr.to_shared()),
+                    offset,
+                    col_offset: n,
+                    pad: None,
+                    row_align: 1,
+                    csr: None,
+                    rle: None,
+                },
+            )
+        }
+        (_, _) => (
+            div_ceil((2 * batch * m * k * n) as u64, flop_per_cycle),
+            Tile {
+                shape: out_shape,
+                bytes_per_elem: in1.bytes_per_elem,
+                read_from_mu: write_back_mu,
+                underlying: None,
+                offset,
+                col_offset: n,
+                pad: None,
+                row_align: 1,
+                csr: None,
+                rle: None,
+            },
+        ),
+    }
+}
+
+/// matmul_systolic
+///
+/// Same functional behavior as [`matmul`], but the cycle count models a
+/// weight-stationary PE array instead of an idealized `flop_per_cycle`
+/// roofline. The `K x N` weight (`in2`) is tiled into
+/// `div_ceil(K, pe_rows) * div_ceil(N, pe_cols)` blocks; each block pays a
+/// `pe_rows + pe_cols` cycle weight-load/pipeline-fill phase, then streams
+/// the `M` activation rows in `M` cycles. Partial blocks
+/// still pay the full per-block latency, so small/odd-shaped matmuls where
+/// fill/drain dominates are modeled realistically instead of being hidden
+/// by a flat flop count.
+pub fn matmul_systolic<T: ndarray::LinalgScalar>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    accumulator: &Tile<T>,
+    pe_rows: u64,
+    pe_cols: u64,
+    weight_transposed: bool,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    assert_eq!(in1.shape.len(), 2);
+    assert_eq!(in2.shape.len(), 2);
+    if !weight_transposed {
+        assert_eq!(in1.shape[1], in2.shape[0]); // reduction dim has to be the same (K)
+        assert_eq!(accumulator.shape[0], in1.shape[0]); // accumulator shape check (M)
+        assert_eq!(accumulator.shape[1], in2.shape[1]); // accumulator shape check (N)
+    } else {
+        assert_eq!(in1.shape[1], in2.shape[1]); // reduction dim has to be the same (K)
+        assert_eq!(accumulator.shape[0], in1.shape[0]); // accumulator shape check (M)
+        assert_eq!(accumulator.shape[1], in2.shape[0]); // accumulator shape check (N)
+    }
+    assert_eq!(in1.bytes_per_elem, in2.bytes_per_elem); // has to be represented in the same data type
+
+    // offset is propagated from the input tile
+    let offset = in1.offset;
+
+    let m = in1.shape[0] as u64;
+    let k = in1.shape[1] as u64;
+    let n = if !weight_transposed {
+        in2.shape[1] as u64 // in2: [K,N]
+    } else {
+        in2.shape[0] as u64 // in2: [N,K]
+    };
+
+    let tiles = div_ceil(k, pe_rows) * div_ceil(n, pe_cols);
+    let cycles = tiles * (pe_rows + pe_cols + m);
+
+    match (&in1.underlying, &in2.underlying) {
+        (Some(arr1), Some(arr2)) => {
+            let map_arr = match weight_transposed {
+                true => arr1.dot(&arr2.t()),
+                false => arr1.dot(arr2),
+            };
+            let out_arr = match &accumulator.underlying {
+                Some(acc_arr) => acc_arr + map_arr,
+                None => {
+                    panic!("Accumulator tile must have an underlying array for matmul_systolic operation")
+                }
+            };
+            (
+                cycles,
+                Tile::new_padded(
+                    out_arr.to_shared(),
+                    in1.bytes_per_elem,
+                    write_back_mu,
+                    offset,
+                ),
+            )
+        }
+        (_, _) => (
+            cycles,
+            Tile::new_blank_padded(
+                vec![m as usize, n as usize],
+                in1.bytes_per_elem,
+                write_back_mu,
+                offset,
+            ),
+        ),
+    }
+}
+
 /// - `write_back_mu`: Whether the output is written to a memory unit. <br/>
 ///     - If yes, the `read_from_mu` field of output tile should be set to this value
 ///     so that the next unit receiving the tile knows it's reading in a tile that was
@@ -1,7 +1,7 @@
-use ndarray::Array2;
+use ndarray::{Array2, ArrayD, ArrayViewD, IxDyn};
 
 use crate::primitives::tile::Tile;
-use crate::utils::calculation::div_ceil;
+use crate::utils::calculation::{div_ceil, effective_flop_per_cycle};
 
 /// matmul
 /// - `write_back_mu`: Whether the output is written to a memory unit. <br/>
@@ -12,6 +12,101 @@ use crate::utils::calculation::div_ceil;
 ///     way to optimize memory access
 use std::fmt::Debug;
 
+/// Computes the NumPy-style broadcast output shape for two tensors by
+/// right-aligning their shapes and, per axis, requiring the sizes to be
+/// equal or one of them to be 1.
+fn broadcast_shape(shape1: &[usize], shape2: &[usize]) -> Vec<usize> {
+    let rank = shape1.len().max(shape2.len());
+    let mut out = vec![0usize; rank];
+    for axis in 0..rank {
+        let d1 = *shape1.iter().rev().nth(axis).unwrap_or(&1);
+        let d2 = *shape2.iter().rev().nth(axis).unwrap_or(&1);
+        assert!(
+            d1 == d2 || d1 == 1 || d2 == 1,
+            "incompatible broadcast shapes: {:?} vs {:?}",
+            shape1,
+            shape2
+        );
+        out[rank - 1 - axis] = d1.max(d2);
+    }
+    out
+}
+
+/// Applies `f` elementwise to two arbitrary-rank arrays with NumPy-style
+/// broadcasting. Walks the broadcast output shape with an "odometer"
+/// multi-index (a counter that increments the last axis and carries into
+/// earlier axes), deriving each input's index by clamping to 0 on axes
+/// where that input's extent is 1.
+pub fn broadcast_binary<T, F>(a: &ArrayViewD<T>, b: &ArrayViewD<T>, f: F) -> ArrayD<T>
+where
+    T: Clone + Default,
+    F: Fn(&T, &T) -> T,
+{
+    let out_shape = broadcast_shape(a.shape(), b.shape());
+    let rank = out_shape.len();
+    let mut out = ArrayD::default(IxDyn(&out_shape));
+
+    let mut idx = vec![0usize; rank];
+    let total: usize = out_shape.iter().product();
+    for _ in 0..total {
+        let a_idx: Vec<usize> = idx
+            .iter()
+            .enumerate()
+            .map(|(axis, &i)| {
+                let a_rank = a.ndim();
+                if axis + a_rank < rank {
+                    0
+                } else {
+                    let a_axis = axis + a_rank - rank;
+                    if a.shape()[a_axis] == 1 {
+                        0
+                    } else {
+                        i
+                    }
+                }
+            })
+            .collect();
+        let b_idx: Vec<usize> = idx
+            .iter()
+            .enumerate()
+            .map(|(axis, &i)| {
+                let b_rank = b.ndim();
+                if axis + b_rank < rank {
+                    0
+                } else {
+                    let b_axis = axis + b_rank - rank;
+                    if b.shape()[b_axis] == 1 {
+                        0
+                    } else {
+                        i
+                    }
+                }
+            })
+            .collect();
+
+        out[IxDyn(&idx)] = f(&a[IxDyn(&a_idx)], &b[IxDyn(&b_idx)]);
+
+        // Odometer increment: bump the last axis, carrying into earlier ones.
+        for axis in (0..rank).rev() {
+            idx[axis] += 1;
+            if idx[axis] < out_shape[axis] {
+                break;
+            }
+            idx[axis] = 0;
+        }
+    }
+
+    out
+}
+
+/// matmul
+///
+/// `flop_per_cycle` is a reference MAC rate at the 4-byte fp32 width; it is
+/// scaled up via [`effective_flop_per_cycle`] for narrower operands, so an
+/// int8 matmul models 4x the throughput of an fp32 one at the same nominal
+/// `flop_per_cycle`. `in1` (activations) and `in2` (weights) no longer have
+/// to share a width — the wider of the two (the conservative choice) sets
+/// the effective rate — enabling weight-only and fully-quantized modes.
 pub fn matmul<T: Debug + ndarray::LinalgScalar>(
     in1: &Tile<T>,
     in2: &Tile<T>,
@@ -26,7 +121,6 @@ pub fn matmul<T: Debug + ndarray::LinalgScalar>(
     } else {
         assert_eq!(in1.shape[1], in2.shape[1]); // reduction dim has to be the same (K)
     }
-    assert_eq!(in1.bytes_per_elem, in2.bytes_per_elem);
 
     // offset is propagated from the input tile
     let offset = in1.offset;
@@ -39,6 +133,11 @@ pub fn matmul<T: Debug + ndarray::LinalgScalar>(
         in2.shape[0] // in2: [N,K]
     };
 
+    let effective_rate = effective_flop_per_cycle(
+        flop_per_cycle,
+        in1.bytes_per_elem.max(in2.bytes_per_elem),
+    );
+
     match (&in1.underlying, &in2.underlying) {
         (Some(arr1), Some(arr2)) => {
             // println!("in1: {:?}", arr1);
@@ -50,7 +149,7 @@ pub fn matmul<T: Debug + ndarray::LinalgScalar>(
             // println!("out_arr: {:?}", out_arr);
 
             (
-                div_ceil((2 * m * k * n) as u64, flop_per_cycle),
+                div_ceil((2 * m * k * n) as u64, effective_rate),
                 Tile::new_padded(
                     out_arr.to_shared(),
                     in1.bytes_per_elem,
@@ -60,18 +159,420 @@ pub fn matmul<T: Debug + ndarray::LinalgScalar>(
             )
         }
         (_, _) => (
-            div_ceil((2 * m * k * n) as u64, flop_per_cycle),
+            div_ceil((2 * m * k * n) as u64, effective_rate),
             Tile::new_blank_padded(vec![m, n], in1.bytes_per_elem, write_back_mu, offset),
         ),
     }
 }
 
-pub fn div<T: Debug + ndarray::LinalgScalar + Default>(
+/// matmul_accumulate
+///
+/// Same `in1 . in2` product as [`matmul`] (with the same `weight_transposed`
+/// convention), but adds the result into the caller-provided `acc` tile
+/// (`[M,N]`) instead of returning a fresh one -- the missing piece for
+/// modeling a matmul split along the reduction dimension `K` across several
+/// passes, each pass's partial product landing in the same accumulator.
+/// Costed as the `matmul` roofline (`2*m*k*n / flop_per_cycle`) plus `m*n`
+/// for the add. `acc.offset` and `write_back_mu` are preserved from `acc`
+/// rather than recomputed from `in1`/`in2`.
+pub fn matmul_accumulate<T: Debug + ndarray::LinalgScalar + Default>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    acc: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+    weight_transposed: bool,
+) -> (u64, Tile<T>) {
+    assert_eq!(in1.shape.len(), 2);
+    assert_eq!(in2.shape.len(), 2);
+    if !weight_transposed {
+        assert_eq!(in1.shape[1], in2.shape[0]); // reduction dim has to be the same (K)
+    } else {
+        assert_eq!(in1.shape[1], in2.shape[1]); // reduction dim has to be the same (K)
+    }
+
+    let m = in1.shape[0];
+    let k = in1.shape[1];
+    let n = if !weight_transposed {
+        in2.shape[1] // in2: [K,N]
+    } else {
+        in2.shape[0] // in2: [N,K]
+    };
+    assert_eq!(acc.shape, vec![m, n]);
+
+    let effective_rate = effective_flop_per_cycle(
+        flop_per_cycle,
+        in1.bytes_per_elem.max(in2.bytes_per_elem),
+    );
+    let matmul_cycles = div_ceil((2 * m * k * n) as u64, effective_rate);
+    let add_cycles = div_ceil((m * n) as u64, flop_per_cycle);
+    let cycles = matmul_cycles + add_cycles;
+
+    match (&in1.underlying, &in2.underlying, &acc.underlying) {
+        (Some(arr1), Some(arr2), Some(acc_arr)) => {
+            let product = match weight_transposed {
+                true => arr1.dot(&arr2.t()),
+                false => arr1.dot(arr2),
+            };
+            let mut out_arr = ndarray::Array2::default((m, n));
+            for i in 0..m {
+                for j in 0..n {
+                    out_arr[[i, j]] = acc_arr[[i, j]].add(product[[i, j]]);
+                }
+            }
+            (
+                cycles,
+                Tile::new_padded(
+                    out_arr.to_shared(),
+                    acc.bytes_per_elem,
+                    write_back_mu,
+                    acc.offset,
+                ),
+            )
+        }
+        (_, _, _) => (
+            cycles,
+            Tile::new_blank_padded(vec![m, n], acc.bytes_per_elem, write_back_mu, acc.offset),
+        ),
+    }
+}
+
+/// matmul_quant
+///
+/// Integer/mixed-precision counterpart to [`matmul`], following the same
+/// "values still live in `T`, the narrower width only changes the modeled
+/// rate" convention `matmul`'s own int8 doc note already uses: `in1`/`in2`
+/// hold the quantized values (optionally zero-point-shifted by
+/// `zero_point1`/`zero_point2` before the dot product), and `scale1`
+/// (`[M,1]`) / `scale2` (`[1,N]`) hold the per-row/per-column dequant
+/// scales -- the result is the integer dot product dequantized by the outer
+/// product `scale1 x scale2ᵀ`, broadcast over the `[M,N]` output the same
+/// way [`mul`]'s `[R,1]`/`[1,C]` broadcasting does. Unlike `matmul`,
+/// `flop_per_cycle` here is taken as-is (the packed-lane rate for the
+/// smaller integer dtype the caller is modeling) rather than scaled via
+/// [`effective_flop_per_cycle`], since the caller has already picked the
+/// rate for that dtype. Costed as the integer matmul FLOPs
+/// (`2*m*k*n / flop_per_cycle`) plus an `m*n` dequant pass. The output
+/// tile's `bytes_per_elem` is `acc_bytes_per_elem` (the accumulator width),
+/// not either input's.
+#[allow(clippy::too_many_arguments)]
+pub fn matmul_quant<T: Debug + ndarray::LinalgScalar + Default>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    scale1: &Tile<T>,
+    scale2: &Tile<T>,
+    zero_point1: Option<T>,
+    zero_point2: Option<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+    weight_transposed: bool,
+    acc_bytes_per_elem: usize,
+) -> (u64, Tile<T>) {
+    assert_eq!(in1.shape.len(), 2);
+    assert_eq!(in2.shape.len(), 2);
+    if !weight_transposed {
+        assert_eq!(in1.shape[1], in2.shape[0]); // reduction dim has to be the same (K)
+    } else {
+        assert_eq!(in1.shape[1], in2.shape[1]); // reduction dim has to be the same (K)
+    }
+
+    let offset = in1.offset;
+
+    let m = in1.shape[0];
+    let k = in1.shape[1];
+    let n = if !weight_transposed {
+        in2.shape[1] // in2: [K,N]
+    } else {
+        in2.shape[0] // in2: [N,K]
+    };
+    assert_eq!(scale1.shape, vec![m, 1]);
+    assert_eq!(scale2.shape, vec![1, n]);
+
+    let matmul_cycles = div_ceil((2 * m * k * n) as u64, flop_per_cycle);
+    let dequant_cycles = div_ceil((m * n) as u64, flop_per_cycle);
+    let cycles = matmul_cycles + dequant_cycles;
+
+    match (
+        &in1.underlying,
+        &in2.underlying,
+        &scale1.underlying,
+        &scale2.underlying,
+    ) {
+        (Some(arr1), Some(arr2), Some(s1), Some(s2)) => {
+            let zp1 = zero_point1.unwrap_or_else(T::zero);
+            let zp2 = zero_point2.unwrap_or_else(T::zero);
+            let adjusted1 = arr1.mapv(|x| x.sub(zp1));
+            let adjusted2 = arr2.mapv(|x| x.sub(zp2));
+            let int_product = match weight_transposed {
+                true => adjusted1.dot(&adjusted2.t()),
+                false => adjusted1.dot(&adjusted2),
+            };
+
+            let mut out_arr = ndarray::Array2::default((m, n));
+            for i in 0..m {
+                for j in 0..n {
+                    out_arr[[i, j]] = int_product[[i, j]].mul(s1[[i, 0]]).mul(s2[[0, j]]);
+                }
+            }
+            (
+                cycles,
+                Tile::new_padded(out_arr.to_shared(), acc_bytes_per_elem, write_back_mu, offset),
+            )
+        }
+        (_, _, _, _) => (
+            cycles,
+            Tile::new_blank_padded(vec![m, n], acc_bytes_per_elem, write_back_mu, offset),
+        ),
+    }
+}
+
+/// matmul_systolic
+///
+/// Same functional behavior as [`matmul`], but the cycle count models a
+/// weight-stationary PE-array (systolic) accelerator instead of an
+/// idealized `flop_per_cycle` roofline. `A[M,K] @ B[K,N]` is tiled onto a
+/// `sys_rows x sys_cols` MAC grid: `M` is chunked into `sys_rows`-sized
+/// blocks and `N` into `sys_cols`-sized blocks, so there are
+/// `div_ceil(M, sys_rows) * div_ceil(N, sys_cols)` output tiles. Each
+/// output tile pays a weight-load phase of `K` cycles, halved when the
+/// weight operand's `read_from_mu` flag shows it's already resident in
+/// the PE array and so cheaper to reuse, a pipeline fill/drain of
+/// `sys_rows + sys_cols - 1` cycles, and a `K`-cycle stream phase. The
+/// result is bounded below by
+/// the ideal `flop_per_cycle` roofline so an unrealistically small grid
+/// can't report fewer cycles than the MAC throughput allows.
+pub fn matmul_systolic<T: Debug + ndarray::LinalgScalar>(
     in1: &Tile<T>,
     in2: &Tile<T>,
+    sys_rows: u64,
+    sys_cols: u64,
+    flop_per_cycle: u64,
+    weight_transposed: bool,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    assert_eq!(in1.shape.len(), 2);
+    assert_eq!(in2.shape.len(), 2);
+    if !weight_transposed {
+        assert_eq!(in1.shape[1], in2.shape[0]); // reduction dim has to be the same (K)
+    } else {
+        assert_eq!(in1.shape[1], in2.shape[1]); // reduction dim has to be the same (K)
+    }
+    assert_eq!(in1.bytes_per_elem, in2.bytes_per_elem);
+
+    let offset = in1.offset;
+
+    let m = in1.shape[0] as u64;
+    let k = in1.shape[1] as u64;
+    let n = if !weight_transposed {
+        in2.shape[1] as u64
+    } else {
+        in2.shape[0] as u64
+    };
+
+    let weight_load = if in2.read_from_mu { div_ceil(k, 2) } else { k };
+    let fill_drain = sys_rows + sys_cols - 1;
+    let tiles = div_ceil(m, sys_rows) * div_ceil(n, sys_cols);
+    let systolic_cycles = tiles * (weight_load + fill_drain + k);
+    let roofline_cycles = div_ceil(m * k * n, flop_per_cycle);
+    let cycles = systolic_cycles.max(roofline_cycles);
+
+    match (&in1.underlying, &in2.underlying) {
+        (Some(arr1), Some(arr2)) => {
+            let out_arr = match weight_transposed {
+                true => arr1.dot(&arr2.t()),
+                false => arr1.dot(arr2),
+            };
+
+            (
+                cycles,
+                Tile::new_padded(
+                    out_arr.to_shared(),
+                    in1.bytes_per_elem,
+                    write_back_mu,
+                    offset,
+                ),
+            )
+        }
+        (_, _) => (
+            cycles,
+            Tile::new_blank_padded(
+                vec![m as usize, n as usize],
+                in1.bytes_per_elem,
+                write_back_mu,
+                offset,
+            ),
+        ),
+    }
+}
+
+/// Fused dot-product attention: `q` [M,d], `k` [N,d], `v` [N,d] ->
+/// output [M,d]. The scores are not scaled by `1/sqrt(d)` before the softmax. Rather than wiring up `matmul` + softmax + `matmul` as
+/// three separate nodes, this models the whole block as one fused op and
+/// sums three independent cost terms: `scores = Q @ K^T` and
+/// `out = softmax(scores) @ V` each cost `2*M*N*d` flops (scaled by
+/// `flop_per_cycle`), while the softmax over the N axis is not a matmul and
+/// gets its own `M*N`-element throughput term scaled by `exp_per_cycle`.
+/// `alibi_slope`, when given, adds `slope * (j - i)` to `score[i][j]` before
+/// the softmax (negligible flops, so it's folded into the softmax element
+/// count rather than given its own term).
+pub fn attention<T: Debug + ndarray::LinalgScalar + num_traits::Float + Copy>(
+    q: &Tile<T>,
+    k: &Tile<T>,
+    v: &Tile<T>,
+    flop_per_cycle: u64,
+    exp_per_cycle: u64,
+    write_back_mu: bool,
+    alibi_slope: Option<T>,
+) -> (u64, Tile<T>) {
+    assert_eq!(q.shape.len(), 2);
+    assert_eq!(k.shape.len(), 2);
+    assert_eq!(v.shape.len(), 2);
+    let m = q.shape[0];
+    let d = q.shape[1];
+    let n = k.shape[0];
+    assert_eq!(k.shape[1], d); // reduction dim has to be the same (d)
+    assert_eq!(v.shape[0], n);
+    assert_eq!(v.shape[1], d);
+
+    let offset = q.offset;
+
+    let scores_cycles = div_ceil((2 * m * n * d) as u64, flop_per_cycle);
+    let softmax_cycles = div_ceil((m * n) as u64, exp_per_cycle);
+    let out_cycles = div_ceil((2 * m * n * d) as u64, flop_per_cycle);
+    let cycles = scores_cycles + softmax_cycles + out_cycles;
+
+    match (&q.underlying, &k.underlying, &v.underlying) {
+        (Some(q_arr), Some(k_arr), Some(v_arr)) => {
+            let mut scores = q_arr.dot(&k_arr.t()); // [M,N]
+            if let Some(slope) = alibi_slope {
+                for i in 0..m {
+                    for j in 0..n {
+                        scores[[i, j]] =
+                            scores[[i, j]] + slope * T::from(j as i64 - i as i64).unwrap();
+                    }
+                }
+            }
+            for i in 0..m {
+                let mut row = scores.row_mut(i);
+                let max = row.iter().cloned().fold(T::neg_infinity(), T::max);
+                row.mapv_inplace(|x| (x - max).exp());
+                let sum = row.sum();
+                row.mapv_inplace(|x| x / sum);
+            }
+            let out_arr = scores.dot(v_arr);
+            (
+                cycles,
+                Tile::new_padded(out_arr.to_shared(), q.bytes_per_elem, write_back_mu, offset),
+            )
+        }
+        _ => (
+            cycles,
+            Tile::new_blank_padded(vec![m, d], q.bytes_per_elem, write_back_mu, offset),
+        ),
+    }
+}
+
+/// Flash-attention-style streaming softmax attention: same result as
+/// [`attention`], but never materializes the full `[M,N]` score matrix.
+/// Sweeps `k_blocks`/`v_blocks` (equal-length slices of `[Bk,d]` tiles) one
+/// block at a time, maintaining a running per-row max `m`, denominator `l`,
+/// and output accumulator `O` ([M,1], [M,1], [M,d]) using the online-softmax
+/// correction (Milakov & Gimelshein): for each block, `S = Q . Kjᵀ`
+/// ([`matmul`], `weight_transposed: true`), `mj` = [`row_wise_max`] of `S`,
+/// `m' = `[`max`]`(m, mj)`, `alpha = `[`exp`]`(`[`sub`]`(m, m'))` rescales the
+/// existing `l`/`O` via [`mul`], `P = `[`exp`]`(`[`sub`]`(S, m'))`, `l` picks
+/// up [`row_wise_sum`] of `P` via [`add`], and `O` picks up `P . Vj`
+/// ([`matmul`]) via [`add`]. The final `O / l` reuses [`div`]. Cycles are the
+/// sum of every sub-primitive call across every block, so the total matches
+/// the tiled schedule rather than a single giant matmul's cost.
+///
+/// Before any block has contributed, `m` is `-inf` and `alpha = exp(m - m')`
+/// would be `exp(-inf - -inf) = NaN` if the first block's scores were
+/// themselves all `-inf`-equivalent; since `l`/`O` are still exactly zero at
+/// that point, rescaling by 1 instead of NaN is the correct no-op, so any
+/// NaN produced this way is patched to 1 before use.
+pub fn flash_attention<T: Debug + ndarray::LinalgScalar + num_traits::Float + Copy + Default>(
+    q: &Tile<T>,
+    k_blocks: &[Tile<T>],
+    v_blocks: &[Tile<T>],
     flop_per_cycle: u64,
     write_back_mu: bool,
 ) -> (u64, Tile<T>) {
+    assert_eq!(q.shape.len(), 2);
+    assert_eq!(k_blocks.len(), v_blocks.len());
+    assert!(!k_blocks.is_empty());
+
+    let m_rows = q.shape[0];
+    let d = q.shape[1];
+
+    let mut cycles = 0u64;
+    let mut m = Tile::new_padded(
+        Array2::from_elem((m_rows, 1), T::neg_infinity()).to_shared(),
+        q.bytes_per_elem,
+        false,
+        q.offset,
+    );
+    let mut l = Tile::new_zero_padded([m_rows, 1], q.bytes_per_elem, false, q.offset);
+    let mut o = Tile::new_zero_padded([m_rows, d], q.bytes_per_elem, false, q.offset);
+
+    for (kj, vj) in k_blocks.iter().zip(v_blocks.iter()) {
+        let (s_cycles, s) = matmul(q, kj, flop_per_cycle, false, true);
+        let (mj_cycles, mj) = row_wise_max(&s, flop_per_cycle, false);
+        let (mnew_cycles, m_new) = max(&m, &mj, flop_per_cycle, false);
+
+        let (diff_cycles, diff) = sub(&m, &m_new, flop_per_cycle, false);
+        let (alpha_cycles, alpha_raw) = exp(&diff, flop_per_cycle, false);
+        let alpha = match &alpha_raw.underlying {
+            Some(arr) => Tile::new_padded(
+                arr.mapv(|x| if x.is_nan() { T::one() } else { x }).to_shared(),
+                alpha_raw.bytes_per_elem,
+                alpha_raw.read_from_mu,
+                alpha_raw.offset,
+            ),
+            None => alpha_raw,
+        };
+
+        let (l_rescale_cycles, l_scaled) = mul(&l, &alpha, flop_per_cycle, false);
+        let (o_rescale_cycles, o_scaled) = mul(&o, &alpha, flop_per_cycle, false);
+
+        let (p_shift_cycles, p_shifted) = sub(&s, &m_new, flop_per_cycle, false);
+        let (p_cycles, p) = exp(&p_shifted, flop_per_cycle, false);
+        let (p_sum_cycles, p_sum) = row_wise_sum(&p, flop_per_cycle, false);
+        let (l_new_cycles, l_new) = add(&l_scaled, &p_sum, flop_per_cycle, false);
+
+        let (pv_cycles, pv) = matmul(&p, vj, flop_per_cycle, false, false);
+        let (o_new_cycles, o_new) = add(&o_scaled, &pv, flop_per_cycle, false);
+
+        cycles += s_cycles
+            + mj_cycles
+            + mnew_cycles
+            + diff_cycles
+            + alpha_cycles
+            + l_rescale_cycles
+            + o_rescale_cycles
+            + p_shift_cycles
+            + p_cycles
+            + p_sum_cycles
+            + l_new_cycles
+            + pv_cycles
+            + o_new_cycles;
+
+        m = m_new;
+        l = l_new;
+        o = o_new;
+    }
+
+    let (div_cycles, out) = div(&o, &l, flop_per_cycle, write_back_mu);
+    cycles += div_cycles;
+    (cycles, out)
+}
+
+/// Shared shape/offset resolution for the `[R,C]`/`[1,C]`/`[R,1]` 2-D
+/// broadcasting fast path used by [`div`]/[`mul`]/[`add`] below: validates
+/// that the two shapes broadcast together, then returns the output shape
+/// and the offset to carry onto the result (the offset of whichever input
+/// isn't the degenerate size-1 side, or the max of both when neither is).
+fn broadcast2d_meta<T>(in1: &Tile<T>, in2: &Tile<T>) -> (usize, usize, usize) {
     assert_eq!(in1.shape.len(), 2);
     assert_eq!(in2.shape.len(), 2);
     let in1_shape_0 = in1.shape[0];
@@ -93,23 +594,45 @@ pub fn div<T: Debug + ndarray::LinalgScalar + Default>(
         in1.offset
     };
 
+    (out_shape_0, out_shape_1, offset)
+}
+
+/// Elementwise binary kernel shared by [`div`]/[`mul`]/[`add`]: resolves
+/// the broadcast output shape/offset via [`broadcast2d_meta`], then
+/// applies `f` with `ndarray`'s own broadcasting (`ArrayBase::broadcast`
+/// into a `Zip`/`azip!`) instead of walking `[i, j]` by hand. With the
+/// `rayon` feature enabled the fill is split across cores via `par_azip!`.
+fn broadcast2d_elementwise<T, F>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+    f: F,
+) -> (u64, Tile<T>)
+where
+    T: Debug + Clone + Default + Send + Sync,
+    F: Fn(T, T) -> T + Sync,
+{
+    let (out_shape_0, out_shape_1, offset) = broadcast2d_meta(in1, in2);
+    let cycles = div_ceil((out_shape_0 * out_shape_1) as u64, flop_per_cycle);
+
     match (&in1.underlying, &in2.underlying) {
         (Some(arr1), Some(arr2)) => {
-            let mut out_arr = ndarray::Array2::default((out_shape_0, out_shape_1));
-            for i in 0..out_shape_0 {
-                for j in 0..out_shape_1 {
-                    let i0 = i.min(in1_shape_0 - 1);
-                    let j0 = j.min(in1_shape_1 - 1);
-                    let val1 = arr1.get((i0, j0)).unwrap();
-                    let i1 = i.min(in2_shape_0 - 1);
-                    let j1 = j.min(in2_shape_1 - 1);
-                    let val2 = arr2.get((i1, j1)).unwrap();
-                    let out_val = val1.div(*val2);
-                    out_arr[[i, j]] = out_val;
-                }
-            }
+            let a = arr1
+                .broadcast((out_shape_0, out_shape_1))
+                .expect("shape compatibility checked by broadcast2d_meta");
+            let b = arr2
+                .broadcast((out_shape_0, out_shape_1))
+                .expect("shape compatibility checked by broadcast2d_meta");
+            let mut out_arr = Array2::default((out_shape_0, out_shape_1));
+
+            #[cfg(feature = "rayon")]
+            ndarray::par_azip!((out in &mut out_arr, a in &a, b in &b) *out = f(a.clone(), b.clone()));
+            #[cfg(not(feature = "rayon"))]
+            ndarray::azip!((out in &mut out_arr, a in &a, b in &b) *out = f(a.clone(), b.clone()));
+
             (
-                div_ceil((out_shape_0 * out_shape_1) as u64, flop_per_cycle),
+                cycles,
                 Tile::new_padded(
                     out_arr.to_shared(),
                     in1.bytes_per_elem,
@@ -119,7 +642,7 @@ pub fn div<T: Debug + ndarray::LinalgScalar + Default>(
             )
         }
         (_, _) => (
-            div_ceil((out_shape_0 * out_shape_1) as u64, flop_per_cycle),
+            cycles,
             Tile::new_blank_padded(
                 vec![out_shape_0, out_shape_1],
                 in1.bytes_per_elem,
@@ -130,7 +653,50 @@ pub fn div<T: Debug + ndarray::LinalgScalar + Default>(
     }
 }
 
-pub fn mul<T: Debug + ndarray::LinalgScalar + Default>(
+/// Elementwise divide with NumPy-style broadcasting. This is the 2-D fast
+/// path over `Tile`'s current `ArcArray2` storage; `broadcast_binary`
+/// above is the general N-D primitive and is exercised directly by callers
+/// that need rank > 2 (e.g. attention/conv workloads) until `Tile` itself
+/// carries arbitrary-rank storage.
+pub fn div<T: Debug + ndarray::LinalgScalar + Default + Send + Sync>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    broadcast2d_elementwise(in1, in2, flop_per_cycle, write_back_mu, |a, b| a.div(b))
+}
+
+/// Elementwise multiply with NumPy-style broadcasting. This is the 2-D
+/// fast path over `Tile`'s current `ArcArray2` storage; `broadcast_binary`
+/// above is the general N-D primitive and is exercised directly by callers
+/// that need rank > 2 (e.g. attention/conv workloads) until `Tile` itself
+/// carries arbitrary-rank storage.
+pub fn mul<T: Debug + ndarray::LinalgScalar + Default + Send + Sync>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    broadcast2d_elementwise(in1, in2, flop_per_cycle, write_back_mu, |a, b| a.mul(b))
+}
+
+/// Elementwise add with NumPy-style broadcasting, the same 2-D fast path
+/// as [`mul`]/[`div`] above.
+pub fn add<T: Debug + ndarray::LinalgScalar + Default + Send + Sync>(
+    in1: &Tile<T>,
+    in2: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    broadcast2d_elementwise(in1, in2, flop_per_cycle, write_back_mu, |a, b| a.add(b))
+}
+
+/// Elementwise subtract with NumPy-style broadcasting, the same 2-D fast
+/// path as [`mul`]/[`div`]/[`add`] above -- lets a `[R,C]` tensor have a
+/// `[1,C]`/`[R,1]` bias subtracted directly instead of requiring an
+/// Expand/Repeat operator upstream to match shapes.
+pub fn sub<T: Debug + ndarray::LinalgScalar + Default>(
     in1: &Tile<T>,
     in2: &Tile<T>,
     flop_per_cycle: u64,
@@ -168,7 +734,7 @@ pub fn mul<T: Debug + ndarray::LinalgScalar + Default>(
                     let i1 = i.min(in2_shape_0 - 1);
                     let j1 = j.min(in2_shape_1 - 1);
                     let val2 = arr2.get((i1, j1)).unwrap();
-                    let out_val = val1.mul(*val2);
+                    let out_val = val1.sub(*val2);
                     out_arr[[i, j]] = out_val;
                 }
             }
@@ -193,7 +759,11 @@ pub fn mul<T: Debug + ndarray::LinalgScalar + Default>(
         ),
     }
 }
-pub fn add<T: Debug + ndarray::LinalgScalar + Default>(
+
+/// Elementwise max with the same NumPy-style `[R,C]`/`[1,C]`/`[R,1]`
+/// broadcasting as [`sub`]/[`mul`]/[`div`]/[`add`] above -- e.g. folding a
+/// running per-row max tile against a freshly computed block max.
+pub fn max<T: Debug + num_traits::Float + Copy + Default>(
     in1: &Tile<T>,
     in2: &Tile<T>,
     flop_per_cycle: u64,
@@ -231,8 +801,7 @@ pub fn add<T: Debug + ndarray::LinalgScalar + Default>(
                     let i1 = i.min(in2_shape_0 - 1);
                     let j1 = j.min(in2_shape_1 - 1);
                     let val2 = arr2.get((i1, j1)).unwrap();
-                    let out_val = val1.add(*val2);
-                    out_arr[[i, j]] = out_val;
+                    out_arr[[i, j]] = val1.max(*val2);
                 }
             }
             (
@@ -366,6 +935,110 @@ pub fn row_wise_sum<T: Debug + num_traits::Num + Copy>(
     }
 }
 
+/// Row-wise max, the reduction a numerically-stable softmax subtracts
+/// before `exp` (`exp(x - row_wise_max(x))` avoids overflowing on large
+/// logits). Same shape/cost contract as [`row_wise_sum`]: tracks a running
+/// max per row initialized to `-inf` and writes a `[shape_0, 1]` tile.
+pub fn row_wise_max<T: Debug + num_traits::Float + Copy>(
+    in_data: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    assert_eq!(in_data.shape.len(), 2);
+
+    let shape_0 = in_data.shape[0];
+    let shape_1 = in_data.shape[1];
+
+    let offset = in_data.offset;
+
+    match &in_data.underlying {
+        Some(arr) => {
+            // Perform row-wise max: reduce each row to get a [shape_0, 1] array
+            let row_maxes = arr
+                .fold_axis(ndarray::Axis(1), T::neg_infinity(), |acc, &x| acc.max(x))
+                .insert_axis(ndarray::Axis(1));
+            (
+                div_ceil((shape_0 * shape_1) as u64, flop_per_cycle),
+                Tile::new_padded(
+                    row_maxes.to_shared(),
+                    in_data.bytes_per_elem,
+                    write_back_mu,
+                    offset,
+                ),
+            )
+        }
+        None => (
+            div_ceil((shape_0 * shape_1) as u64, flop_per_cycle),
+            Tile::new_blank_padded(
+                vec![shape_0, 1],
+                in_data.bytes_per_elem,
+                write_back_mu,
+                offset,
+            ),
+        ),
+    }
+}
+
+/// Fused, numerically-stable softmax: subtracts each row's max before
+/// exponentiating (`exp(x - row_wise_max(x))`, avoiding the overflow a naive
+/// `exp(x)` risks on large logits), then divides by the row sum -- built
+/// from [`row_wise_max`], [`sub`], [`exp`], [`row_wise_sum`], and [`div`]
+/// rather than composed by hand at each call site. Costed as the sum of all
+/// five passes' cycles; `offset` is carried through the same way each of
+/// those primitives already does.
+pub fn softmax<T: Debug + ndarray::LinalgScalar + num_traits::Float + Copy + Default>(
+    in_data: &Tile<T>,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    let (max_cycles, row_max) = row_wise_max(in_data, flop_per_cycle, write_back_mu);
+    let (sub_cycles, shifted) = sub(in_data, &row_max, flop_per_cycle, write_back_mu);
+    let (exp_cycles, exped) = exp(&shifted, flop_per_cycle, write_back_mu);
+    let (sum_cycles, row_sum) = row_wise_sum(&exped, flop_per_cycle, write_back_mu);
+    let (div_cycles, out) = div(&exped, &row_sum, flop_per_cycle, write_back_mu);
+
+    let cycles = max_cycles + sub_cycles + exp_cycles + sum_cycles + div_cycles;
+    (cycles, out)
+}
+
+/// Embedding-table row gather: `table` is `[V, d]` and `sel` marks which of
+/// the `V` rows to fetch. Only the `nnz` selected rows are ever touched, so
+/// the modeled cost is `div_ceil(nnz * d, elems_per_cycle)` rather than
+/// scaling with the full table size `V`. The output is `[nnz, d]`, in the
+/// same row order as `sel`'s selected indices.
+pub fn gather<T: Debug + Clone + Default>(
+    table: &Tile<T>,
+    sel: &crate::primitives::select::MultiHotN,
+    elems_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    use crate::primitives::select::SelectAdapter;
+
+    assert_eq!(table.shape.len(), 2);
+    let d = table.shape[1];
+    let rows = sel.to_sel_vec();
+    let nnz = rows.len();
+
+    let cycles = div_ceil((nnz * d) as u64, elems_per_cycle);
+
+    match &table.underlying {
+        Some(arr) => {
+            let mut out_arr = ndarray::Array2::<T>::default((nnz, d));
+            for (out_row, &row) in rows.iter().enumerate() {
+                out_arr.row_mut(out_row).assign(&arr.row(row));
+            }
+            (
+                cycles,
+                Tile::new(out_arr.to_shared(), table.bytes_per_elem, write_back_mu),
+            )
+        }
+        None => (
+            cycles,
+            Tile::new_blank(vec![nnz, d], table.bytes_per_elem, write_back_mu),
+        ),
+    }
+}
+
 pub fn set_offset<T: Debug + ndarray::LinalgScalar + Default>(
     in_data: &Tile<T>,
     offset: &Tile<u64>,
@@ -486,6 +1159,175 @@ mod tests {
         assert_eq!(flop_count, 2);
     }
 
+    #[test]
+    fn test_broadcast_binary_nd() {
+        // [2,1,3] * [4,3] -> [2,4,3], broadcasting both the middle axis of
+        // `a` and the missing leading axis of `b`.
+        let a = ArrayD::from_shape_fn(IxDyn(&[2, 1, 3]), |idx| (idx[0] * 3 + idx[2]) as f32);
+        let b = ArrayD::from_shape_fn(IxDyn(&[4, 3]), |idx| (idx[0] + idx[1]) as f32);
+
+        let out = broadcast_binary(&a.view(), &b.view(), |x, y| x + y);
+        assert_eq!(out.shape(), &[2, 4, 3]);
+        assert_eq!(out[IxDyn(&[1, 2, 0])], a[IxDyn(&[1, 0, 0])] + b[IxDyn(&[2, 0])]);
+    }
+
+    #[test]
+    fn test_matmul_accumulate() {
+        let arr1 = ndarray::Array2::from_shape_fn((2, 3), |(i, j)| (i + j) as f32);
+        let arr2 = ndarray::Array2::from_shape_fn((3, 2), |(i, j)| (i * j) as f32);
+        let in1 = Tile::new(arr1.to_shared(), 4, false);
+        let in2 = Tile::new(arr2.to_shared(), 4, false);
+        let acc_arr = ndarray::Array2::from_shape_fn((2, 2), |(i, j)| (i + j + 1) as f32);
+        let acc = Tile::new_padded(acc_arr.to_shared(), 4, false, 2);
+
+        let (cycles, out_data) = matmul_accumulate(&in1, &in2, &acc, 1024, false, false);
+        // matmul: div_ceil(2*2*3*2, 1024) = 1, add: div_ceil(2*2, 1024) = 1 -> 2
+        assert_eq!(cycles, 2);
+        let product = arr1.dot(&arr2);
+        let out_arr = out_data.underlying.unwrap();
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((out_arr[[i, j]] - (acc_arr[[i, j]] + product[[i, j]])).abs() < 1e-5);
+            }
+        }
+        assert_eq!(out_data.offset, acc.offset);
+    }
+
+    #[test]
+    fn test_matmul_quant() {
+        let arr1 = ndarray::Array2::from_shape_vec((2, 3), vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .unwrap();
+        let arr2 = ndarray::Array2::from_shape_vec((3, 2), vec![1.0f32, 0.0, 0.0, 1.0, 1.0, 1.0])
+            .unwrap();
+        let in1 = Tile::new(arr1.to_shared(), 1, false);
+        let in2 = Tile::new(arr2.to_shared(), 1, false);
+
+        let scale1 = Tile::new(
+            ndarray::Array2::from_shape_vec((2, 1), vec![2.0f32, 0.5]).unwrap().to_shared(),
+            4,
+            false,
+        );
+        let scale2 = Tile::new(
+            ndarray::Array2::from_shape_vec((1, 2), vec![1.0f32, 2.0]).unwrap().to_shared(),
+            4,
+            false,
+        );
+
+        let (cycles, out_data) =
+            matmul_quant(&in1, &in2, &scale1, &scale2, None, None, 1024, false, false, 4);
+        // matmul: div_ceil(2*2*3*2, 1024) = 1, dequant: div_ceil(2*2, 1024) = 1 -> 2
+        assert_eq!(cycles, 2);
+        let out_arr = out_data.underlying.unwrap();
+        assert!((out_arr[[0, 0]] - 8.0).abs() < 1e-5);
+        assert!((out_arr[[0, 1]] - 20.0).abs() < 1e-5);
+        assert!((out_arr[[1, 0]] - 5.0).abs() < 1e-5);
+        assert!((out_arr[[1, 1]] - 11.0).abs() < 1e-5);
+        assert_eq!(out_data.bytes_per_elem, 4);
+    }
+
+    #[test]
+    fn test_matmul_systolic() {
+        let arr1 = ndarray::Array2::from_shape_fn((4, 6), |(i, j)| i as f32 + j as f32);
+        let arr2 = ndarray::Array2::from_shape_fn((6, 4), |(i, j)| i as f32 - j as f32);
+        let in1 = Tile::new_padded(arr1.to_shared(), 4, false, 4);
+        let in2 = Tile::new_padded(arr2.to_shared(), 4, false, 6);
+
+        let (cycles, out_data) = matmul_systolic(&in1, &in2, 2, 2, 1024, false, false);
+        // tiles = ceil(4/2)*ceil(4/2) = 4, weight_load = 6 (not resident, full K cycles),
+        // fill_drain = 2 + 2 - 1 = 3, stream = 6 -> 4 * (6 + 3 + 6) = 60
+        assert_eq!(cycles, 60);
+        assert_eq!(out_data.underlying.unwrap().shape(), &[4, 4]);
+    }
+
+    #[test]
+    fn test_attention() {
+        // d=2, M=2 queries, N=2 keys/values. Zero queries make every score 0,
+        // so the softmax is a uniform 0.5/0.5 mix of the two rows of V.
+        let q = ndarray::Array2::from_shape_vec((2, 2), vec![0.0, 0.0, 0.0, 0.0]).unwrap();
+        let k = ndarray::Array2::from_shape_vec((2, 2), vec![1.0, 0.0, 0.0, 1.0]).unwrap();
+        let v = ndarray::Array2::from_shape_vec((2, 2), vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        let q_tile = Tile::new(q.to_shared(), 4, false);
+        let k_tile = Tile::new(k.to_shared(), 4, false);
+        let v_tile = Tile::new(v.to_shared(), 4, false);
+
+        let (cycles, out) = attention(&q_tile, &k_tile, &v_tile, 1024, 1024, false, None);
+        // scores/out matmuls: div_ceil(2*2*2*2, 1024) = 1 each, softmax: div_ceil(2*2, 1024) = 1
+        assert_eq!(cycles, 3);
+        let out_arr = out.underlying.unwrap();
+        assert!((out_arr[[0, 0]] - 2.0).abs() < 1e-5);
+        assert!((out_arr[[0, 1]] - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_flash_attention() {
+        // Same Q/K/V as test_attention, but K/V are split into one-row
+        // blocks to exercise the running max/denominator/output
+        // accumulators -- the result should match the non-streaming
+        // `attention` exactly.
+        let q = ndarray::Array2::from_shape_vec((2, 2), vec![0.0, 0.0, 0.0, 0.0]).unwrap();
+        let k = ndarray::Array2::from_shape_vec((2, 2), vec![1.0, 0.0, 0.0, 1.0]).unwrap();
+        let v = ndarray::Array2::from_shape_vec((2, 2), vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        let q_tile = Tile::new(q.to_shared(), 4, false);
+        let k_blocks: Vec<_> = (0..2)
+            .map(|i| {
+                let row = k.slice(ndarray::s![i..i + 1, ..]).to_owned().into_shared();
+                Tile::new(row, 4, false)
+            })
+            .collect();
+        let v_blocks: Vec<_> = (0..2)
+            .map(|i| {
+                let row = v.slice(ndarray::s![i..i + 1, ..]).to_owned().into_shared();
+                Tile::new(row, 4, false)
+            })
+            .collect();
+
+        let (cycles, out) = flash_attention(&q_tile, &k_blocks, &v_blocks, 1024, false);
+        // 13 sub-primitive calls per block (each div_ceil(.., 1024) = 1) * 2
+        // blocks + 1 final div = 27.
+        assert_eq!(cycles, 27);
+        let out_arr = out.underlying.unwrap();
+        for row in 0..2 {
+            assert!((out_arr[[row, 0]] - 2.0).abs() < 1e-5);
+            assert!((out_arr[[row, 1]] - 3.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_gather() {
+        use crate::primitives::select::MultiHotN;
+
+        let table = ndarray::Array2::from_shape_fn((5, 3), |(i, j)| (i * 10 + j) as f32);
+        let table_tile = Tile::new(table.to_shared(), 4, false);
+        let sel = MultiHotN::new(vec![false, true, false, true, false], false);
+
+        let (cycles, out) = gather(&table_tile, &sel, 6, false);
+        assert_eq!(cycles, 1); // div_ceil(2*3, 6)
+        let out_arr = out.underlying.unwrap();
+        assert_eq!(out_arr.shape(), &[2, 3]);
+        assert_eq!(out_arr.row(0).to_vec(), vec![10.0, 11.0, 12.0]);
+        assert_eq!(out_arr.row(1).to_vec(), vec![30.0, 31.0, 32.0]);
+    }
+
+    #[test]
+    fn test_softmax() {
+        // Two rows with the same gap between elements ([0,1] and [2,3]), so
+        // both rows soften to the same distribution once the row max is
+        // subtracted out.
+        let arr = ndarray::Array2::from_shape_fn((2, 2), |(i, j)| (i * 2 + j) as f32);
+        let in_data = Tile::new_padded(arr.to_shared(), 4, false, 2);
+
+        let (cycles, out_data) = softmax(&in_data, 8, false);
+        // max: div_ceil(4,8)=1, sub: div_ceil(4,8)=1, exp: div_ceil(4*4,8)=2,
+        // sum: div_ceil(4,8)=1, div: div_ceil(4,8)=1 -> 6
+        assert_eq!(cycles, 6);
+
+        let out_arr = out_data.underlying.unwrap();
+        for row in 0..2 {
+            assert!((out_arr[[row, 0]] - 0.268_941).abs() < 1e-5);
+            assert!((out_arr[[row, 1]] - 0.731_059).abs() < 1e-5);
+        }
+    }
+
     #[test]
     fn test_row_wise_append() {
         let arr =
@@ -0,0 +1,5 @@
+pub mod accum_fn;
+pub mod map_accum_fn;
+pub mod map_fn;
+pub mod op;
+pub mod schedule;
@@ -0,0 +1,199 @@
+//! A unified dispatch point over the free functions in [`crate::functions`],
+//! so an operation sequence can be built, inspected, and disassembled as
+//! data instead of as a sequence of ad hoc function calls.
+use crate::functions::{accum_fn, map_fn};
+use crate::primitives::tile::Tile;
+
+/// One simulated operation and its parameters. Each variant mirrors a free
+/// function in [`crate::functions::map_fn`] / [`crate::functions::accum_fn`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Mul {
+        flop_per_cycle: u64,
+        write_back_mu: bool,
+    },
+    Add {
+        flop_per_cycle: u64,
+        write_back_mu: bool,
+    },
+    Matmul {
+        flop_per_cycle: u64,
+        write_back_mu: bool,
+        weight_transposed: bool,
+    },
+    RetileRow {
+        flop_per_cycle: u64,
+        write_back_mu: bool,
+    },
+    RetileCol {
+        flop_per_cycle: u64,
+        write_back_mu: bool,
+    },
+    SignalReqAllRead {
+        write_back_mu: bool,
+    },
+}
+
+impl Op {
+    /// The opcode name, used by `disasm` and error messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Op::Mul { .. } => "mul",
+            Op::Add { .. } => "add",
+            Op::Matmul { .. } => "matmul",
+            Op::RetileRow { .. } => "retile_row",
+            Op::RetileCol { .. } => "retile_col",
+            Op::SignalReqAllRead { .. } => "signal_req_all_read",
+        }
+    }
+
+    /// Dispatches to the existing op body, returning the cycle count and
+    /// the materialized output tile.
+    pub fn apply(&self, inputs: &[&Tile<f32>]) -> (u64, Tile<f32>) {
+        match *self {
+            Op::Mul {
+                flop_per_cycle,
+                write_back_mu,
+            } => map_fn::mul(inputs[0], inputs[1], flop_per_cycle, write_back_mu),
+            Op::Add {
+                flop_per_cycle,
+                write_back_mu,
+            } => map_fn::add(inputs[0], inputs[1], flop_per_cycle, write_back_mu),
+            Op::Matmul {
+                flop_per_cycle,
+                write_back_mu,
+                weight_transposed,
+            } => map_fn::matmul(
+                inputs[0],
+                inputs[1],
+                flop_per_cycle,
+                write_back_mu,
+                weight_transposed,
+            ),
+            Op::RetileRow {
+                flop_per_cycle,
+                write_back_mu,
+            } => accum_fn::retile_row(inputs[0], inputs[1], flop_per_cycle, write_back_mu),
+            Op::RetileCol {
+                flop_per_cycle,
+                write_back_mu,
+            } => accum_fn::retile_col(inputs[0], inputs[1], flop_per_cycle, write_back_mu),
+            Op::SignalReqAllRead { write_back_mu } => {
+                let (cycles, out) =
+                    accum_fn::signal_req_all_read(inputs[0], &Tile::new_blank(vec![1, 1], 8, false), write_back_mu);
+                (cycles, Tile::new_blank(out.shape, inputs[0].bytes_per_elem, write_back_mu))
+            }
+        }
+    }
+
+    /// Returns just the cycle count for the given input shapes, without
+    /// materializing any data. Mirrors the cost half of `apply`, except that
+    /// shapes carry no element width: `Matmul` is costed at the 4-byte
+    /// reference rate, which `apply` scales up for narrower input tiles.
+    pub fn cycles(&self, input_shapes: &[&[usize]]) -> u64 {
+        use crate::utils::calculation::div_ceil;
+
+        match *self {
+            Op::Mul {
+                flop_per_cycle, ..
+            }
+            | Op::Add {
+                flop_per_cycle, ..
+            } => {
+                let out_0 = input_shapes[0][0].max(input_shapes[1][0]);
+                let out_1 = input_shapes[0][1].max(input_shapes[1][1]);
+                div_ceil((out_0 * out_1) as u64, flop_per_cycle)
+            }
+            Op::Matmul {
+                flop_per_cycle,
+                weight_transposed,
+                ..
+            } => {
+                let m = input_shapes[0][0];
+                let k = input_shapes[0][1];
+                let n = if weight_transposed {
+                    input_shapes[1][0]
+                } else {
+                    input_shapes[1][1]
+                };
+                div_ceil((2 * m * k * n) as u64, flop_per_cycle)
+            }
+            Op::RetileRow { .. } | Op::RetileCol { .. } => 0,
+            Op::SignalReqAllRead { .. } => 1,
+        }
+    }
+}
+
+/// Renders a human-readable, one-line-per-op trace: opcode name, input and
+/// output shapes, and cycle count, analogous to a bytecode disassembler.
+pub fn disasm(ops: &[(Op, Vec<Vec<usize>>, Vec<usize>, u64)]) -> String {
+    let mut out = String::new();
+    for (op, input_shapes, output_shape, cycles) in ops {
+        let inputs = input_shapes
+            .iter()
+            .map(|s| format!("{:?}", s))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!(
+            "{:<20} ({}) -> {:?}  [{} cycles]\n",
+            op.name(),
+            inputs,
+            output_shape,
+            cycles
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_mul_matches_map_fn() {
+        let arr1 = ndarray::Array2::from_shape_fn((2, 2), |(i, j)| i as f32 + j as f32);
+        let arr2 = ndarray::Array2::from_shape_fn((2, 2), |(i, j)| i as f32 * j as f32);
+        let in1 = Tile::new(arr1.to_shared(), 4, false);
+        let in2 = Tile::new(arr2.to_shared(), 4, false);
+
+        let op = Op::Mul {
+            flop_per_cycle: 4,
+            write_back_mu: false,
+        };
+        let (cycles, out) = op.apply(&[&in1, &in2]);
+        let (expect_cycles, expect_out) = map_fn::mul(&in1, &in2, 4, false);
+        assert_eq!(cycles, expect_cycles);
+        assert_eq!(out.underlying, expect_out.underlying);
+    }
+
+    #[test]
+    fn test_cycles_matches_apply() {
+        let arr1 = ndarray::Array2::from_shape_fn((4, 4), |_| 1.0f32);
+        let arr2 = ndarray::Array2::from_shape_fn((4, 4), |_| 1.0f32);
+        let in1 = Tile::new(arr1.to_shared(), 4, false);
+        let in2 = Tile::new(arr2.to_shared(), 4, false);
+
+        let op = Op::Matmul {
+            flop_per_cycle: 8,
+            write_back_mu: false,
+            weight_transposed: false,
+        };
+        let (cycles, _) = op.apply(&[&in1, &in2]);
+        assert_eq!(cycles, op.cycles(&[&in1.shape, &in2.shape]));
+    }
+
+    #[test]
+    fn test_disasm_format() {
+        let trace = disasm(&[(
+            Op::Add {
+                flop_per_cycle: 4,
+                write_back_mu: false,
+            },
+            vec![vec![2, 2], vec![2, 2]],
+            vec![2, 2],
+            1,
+        )]);
+        assert!(trace.contains("add"));
+        assert!(trace.contains("[1 cycles]"));
+    }
+}
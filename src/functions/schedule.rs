@@ -0,0 +1,171 @@
+//! Models how an ordered sequence of [`Op`]s executes against a memory
+//! unit with its own load latency, so that a load hidden behind the prior
+//! op's compute doesn't show up as extra wall-clock cycles.
+use crate::functions::op::Op;
+use crate::primitives::elem::Bufferizable;
+use crate::primitives::tile::Tile;
+
+/// Parameters of the memory unit an op sequence's inputs are staged from.
+#[derive(Clone, Copy, Debug)]
+pub struct MemUnitParams {
+    /// Cycles to service a single tile load from this memory unit.
+    pub load_latency: u64,
+    /// Bytes/cycle this memory unit can sustain.
+    pub bandwidth: u64,
+}
+
+/// Whether loads for the next op's inputs are issued serially after the
+/// current op finishes, or issued ahead of time so they can overlap with
+/// the current op's compute (software double-buffering).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleMode {
+    /// Blocking issue-and-wait: each op's input load fully precedes its
+    /// compute, as if there were a single in-flight buffer.
+    Serial,
+    /// Issue-ahead: the next op's input load is prefetched while the
+    /// current op computes, hiding it behind compute when possible.
+    Overlapped,
+}
+
+/// One step of an op sequence together with its materialized inputs, so
+/// `Schedule::run` can compute per-op compute cycles without re-deriving
+/// shapes from the caller.
+pub struct ScheduleStep<'a> {
+    pub op: Op,
+    pub inputs: Vec<&'a Tile<f32>>,
+}
+
+/// Total pipelined cycle count for a sequence of ops against a memory unit.
+pub struct Schedule {
+    pub mem: MemUnitParams,
+    pub mode: ScheduleMode,
+}
+
+impl Schedule {
+    pub fn new(mem: MemUnitParams, mode: ScheduleMode) -> Self {
+        Self { mem, mode }
+    }
+
+    fn load_cycles(&self, tile: &Tile<f32>) -> u64 {
+        if !tile.read_from_mu {
+            return 0;
+        }
+        let bytes = tile.size_in_bytes() as u64;
+        self.mem
+            .load_latency
+            .max(crate::utils::calculation::div_ceil(
+                bytes,
+                self.mem.bandwidth.max(1),
+            ))
+    }
+
+    /// Runs `steps` in order and returns the total pipelined cycle count.
+    ///
+    /// In `Serial` mode every op pays its own load latency up front:
+    /// `sum(load_cycles(op) + compute_cycles(op))`.
+    ///
+    /// In `Overlapped` mode the first op still pays its full load latency,
+    /// but each subsequent op's load is prefetched during the prior op's
+    /// compute, so it only adds `max(0, load_cycles(op) - compute_cycles(prev))`
+    /// on top of the running total.
+    pub fn run(&self, steps: &[ScheduleStep]) -> u64 {
+        let mut total = 0u64;
+        let mut prev_compute = 0u64;
+
+        for (idx, step) in steps.iter().enumerate() {
+            let load = step
+                .inputs
+                .iter()
+                .map(|t| self.load_cycles(t))
+                .max()
+                .unwrap_or(0);
+            let shapes: Vec<&[usize]> = step.inputs.iter().map(|t| t.shape.as_slice()).collect();
+            let compute = step.op.cycles(&shapes);
+
+            match self.mode {
+                ScheduleMode::Serial => {
+                    total += load + compute;
+                }
+                ScheduleMode::Overlapped => {
+                    if idx == 0 {
+                        total += load + compute;
+                    } else {
+                        // The load for this step was already being prefetched
+                        // during the previous step's compute.
+                        total += load.saturating_sub(prev_compute) + compute;
+                    }
+                }
+            }
+            prev_compute = compute;
+        }
+
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(read_from_mu: bool) -> Tile<f32> {
+        let arr = ndarray::Array2::from_shape_fn((4, 4), |_| 1.0f32);
+        let mut t = Tile::new(arr.to_shared(), 4, read_from_mu);
+        t.read_from_mu = read_from_mu;
+        t
+    }
+
+    #[test]
+    fn test_serial_sums_load_and_compute() {
+        let in1 = tile(true);
+        let in2 = tile(true);
+        let schedule = Schedule::new(
+            MemUnitParams {
+                load_latency: 10,
+                bandwidth: 1,
+            },
+            ScheduleMode::Serial,
+        );
+        let steps = vec![ScheduleStep {
+            op: Op::Add {
+                flop_per_cycle: 16,
+                write_back_mu: false,
+            },
+            inputs: vec![&in1, &in2],
+        }];
+        // load = max(10, size_in_bytes/1), compute = ceil(16/16) = 1
+        let expected_load = in1.size_in_bytes() as u64;
+        assert_eq!(schedule.run(&steps), expected_load + 1);
+    }
+
+    #[test]
+    fn test_overlapped_hides_subsequent_loads() {
+        let in1 = tile(true);
+        let in2 = tile(true);
+        let schedule = Schedule::new(
+            MemUnitParams {
+                load_latency: 2,
+                bandwidth: u64::MAX,
+            },
+            ScheduleMode::Overlapped,
+        );
+        let steps = vec![
+            ScheduleStep {
+                op: Op::Add {
+                    flop_per_cycle: 1,
+                    write_back_mu: false,
+                },
+                inputs: vec![&in1, &in2],
+            },
+            ScheduleStep {
+                op: Op::Add {
+                    flop_per_cycle: 1,
+                    write_back_mu: false,
+                },
+                inputs: vec![&in1, &in2],
+            },
+        ];
+        // First op: load (2) + compute (16). Second op's load (2) is fully
+        // hidden behind the first op's 16-cycle compute.
+        assert_eq!(schedule.run(&steps), 2 + 16 + 16);
+    }
+}
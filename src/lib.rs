@@ -10,39 +10,91 @@ pub mod utils;
 
 use std::fs;
 use std::io::repeat;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 
 use prost::Message;
-use pyo3::exceptions::PyTypeError;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIOError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
 
 use crate::proto_driver::configs::SimConfig;
 use crate::proto_driver::parse_proto;
 use crate::proto_driver::proto_headers::graph_proto::ProgramGraph;
+use crate::proto_driver::schema;
 use crate::ramulator::hbm_context::HBMConfig;
+use crate::utils::error::StepPerfError;
+
+create_exception!(
+    step_perf,
+    ProtoDecodeError,
+    PyValueError,
+    "The proto file on disk is not a valid ProgramGraph."
+);
+
+fn step_perf_error_to_pyerr(err: &StepPerfError) -> PyErr {
+    match err {
+        StepPerfError::Io { source } => PyIOError::new_err(source.to_string()),
+        StepPerfError::ProtoDecode { .. } => ProtoDecodeError::new_err(err.to_string()),
+        StepPerfError::IntConversion { .. } => PyTypeError::new_err(err.to_string()),
+        StepPerfError::RankMismatch { .. } => PyValueError::new_err(err.to_string()),
+        StepPerfError::GraphValidation { .. } => PyValueError::new_err(err.to_string()),
+        StepPerfError::UnsupportedSchema { .. } => PyValueError::new_err(err.to_string()),
+        StepPerfError::MissingFeature { .. } => PyValueError::new_err(err.to_string()),
+        StepPerfError::UnsupportedOps { .. } => PyValueError::new_err(err.to_string()),
+    }
+}
+
+impl From<StepPerfError> for PyErr {
+    fn from(err: StepPerfError) -> PyErr {
+        step_perf_error_to_pyerr(&err)
+    }
+}
+
+fn read_program_graph(proto: &str) -> Result<ProgramGraph, StepPerfError> {
+    let file_contents = fs::read(proto).map_err(StepPerfError::from)?;
+    ProgramGraph::decode(file_contents.as_slice()).map_err(StepPerfError::from)
+}
+
+/// Checks a graph's `schema_version`/`required_features` against what this
+/// build supports, if the caller passed them. Frontends built before this
+/// handshake existed can omit both and keep their current (unchecked)
+/// behavior.
+fn check_schema_if_given(
+    schema_version: Option<u32>,
+    required_features: &Option<Vec<String>>,
+) -> Result<(), StepPerfError> {
+    match schema_version {
+        Some(version) => schema::check_compatible(
+            version,
+            required_features.as_deref().unwrap_or_default(),
+        ),
+        None => Ok(()),
+    }
+}
 
 #[pyfunction]
+#[pyo3(signature = (proto, logging, hbm_config, sim_config, db_name=None, schema_version=None, required_features=None))]
 fn run_graph(
-    py: Python,
     proto: String,
     logging: bool,
     hbm_config: HBMConfig,
     sim_config: SimConfig,
     db_name: Option<String>,
-) -> (bool, u64, u128, u64) {
-    let step_graph: ProgramGraph = {
-        let file_contents = fs::read(proto).unwrap();
-        ProgramGraph::decode(file_contents.as_slice()).unwrap()
-    };
+    schema_version: Option<u32>,
+    required_features: Option<Vec<String>>,
+) -> PyResult<(bool, u64, u128, u64)> {
+    check_schema_if_given(schema_version, &required_features)?;
+
+    let step_graph = read_program_graph(&proto)?;
 
     println!("Successfully read proto file");
 
-    let (passed, cycles, duration) =
-        parse_proto(step_graph, logging, hbm_config, sim_config, db_name.clone());
+    let report = parse_proto(step_graph, logging, hbm_config, sim_config, db_name.clone())?;
 
     println!(
         "Passed: {}, Elapsed Cycles: {}, Duration: {:?}",
-        passed, cycles, duration
+        report.passed, report.cycles, report.wall_duration
     );
 
     if logging {
@@ -53,14 +105,160 @@ fn run_graph(
     }
 
     // Convert duration to milliseconds as f64 for Python (better precision for short durations)
-    let duration_milliseconds = duration.as_millis();
-    let duration_seconds = duration.as_secs();
-    return (passed, cycles, duration_milliseconds, duration_seconds);
+    let duration_milliseconds = report.wall_duration.as_millis();
+    let duration_seconds = report.wall_duration.as_secs();
+    Ok((report.passed, report.cycles, duration_milliseconds, duration_seconds))
+}
+
+type SimResult = (bool, u64, u128, u64);
+
+enum AsyncSimState {
+    Running,
+    Done(SimResult),
+    Failed(StepPerfError),
+}
+
+/// A handle to a `parse_proto` run spawned on a background thread by
+/// [`run_graph_async`]. Lets notebooks and harnesses observe completion (and
+/// run several graphs concurrently) instead of blocking on `run_graph`.
+///
+/// `dam`'s `run()` does not yield intermediate cycle counts mid-simulation,
+/// so `poll()` can only report "still running" vs. the final tuple -- there
+/// is no true "elapsed cycles so far" to surface before the thread finishes.
+#[pyclass]
+struct AsyncSim {
+    state: Arc<Mutex<AsyncSimState>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[pymethods]
+impl AsyncSim {
+    /// Non-blocking status check: `None` while the simulation is still
+    /// running, or the `(passed, cycles, duration_ms, duration_s)` tuple
+    /// once it has finished. Raises if the background thread failed: the
+    /// schema check, reading or decoding the proto file, or the simulation.
+    fn poll(&self) -> PyResult<Option<SimResult>> {
+        match &*self.state.lock().unwrap() {
+            AsyncSimState::Running => Ok(None),
+            AsyncSimState::Done(result) => Ok(Some(*result)),
+            AsyncSimState::Failed(_) => Err(self.take_error()),
+        }
+    }
+
+    /// Blocks until the simulation finishes and returns its result tuple,
+    /// or raises the error the background thread hit.
+    fn join(&self) -> PyResult<SimResult> {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.join().unwrap();
+        }
+        match &*self.state.lock().unwrap() {
+            AsyncSimState::Done(result) => Ok(*result),
+            AsyncSimState::Failed(_) => Err(self.take_error()),
+            AsyncSimState::Running => {
+                unreachable!("join() always waits for the background thread first")
+            }
+        }
+    }
+
+    /// `True` once `poll()`/`join()` would return or raise.
+    fn is_done(&self) -> bool {
+        !matches!(&*self.state.lock().unwrap(), AsyncSimState::Running)
+    }
+}
+
+impl AsyncSim {
+    fn take_error(&self) -> PyErr {
+        match &*self.state.lock().unwrap() {
+            AsyncSimState::Failed(err) => step_perf_error_to_pyerr(err),
+            _ => unreachable!("take_error() is only called once state is Failed"),
+        }
+    }
+}
+
+/// Fire-and-forget counterpart to `run_graph`: spawns the `parse_proto` run
+/// on a background thread and returns immediately with an [`AsyncSim`]
+/// handle, instead of blocking until the simulation completes.
+///
+/// `on_complete`, if given, is invoked once from the background thread with
+/// the final `(passed, cycles, duration_ms, duration_s)` tuple. Per-cycle
+/// progress callbacks are not available -- see [`AsyncSim::poll`].
+#[pyfunction]
+#[pyo3(signature = (proto, logging, hbm_config, sim_config, db_name=None, on_complete=None, schema_version=None, required_features=None))]
+fn run_graph_async(
+    proto: String,
+    logging: bool,
+    hbm_config: HBMConfig,
+    sim_config: SimConfig,
+    db_name: Option<String>,
+    on_complete: Option<PyObject>,
+    schema_version: Option<u32>,
+    required_features: Option<Vec<String>>,
+) -> AsyncSim {
+    let state = Arc::new(Mutex::new(AsyncSimState::Running));
+    let thread_state = Arc::clone(&state);
+
+    let handle = std::thread::spawn(move || {
+        if let Err(err) = check_schema_if_given(schema_version, &required_features) {
+            *thread_state.lock().unwrap() = AsyncSimState::Failed(err);
+            return;
+        }
+
+        let step_graph = match read_program_graph(&proto) {
+            Ok(step_graph) => step_graph,
+            Err(err) => {
+                *thread_state.lock().unwrap() = AsyncSimState::Failed(err);
+                return;
+            }
+        };
+
+        let report = match parse_proto(step_graph, logging, hbm_config, sim_config, db_name) {
+            Ok(report) => report,
+            Err(err) => {
+                *thread_state.lock().unwrap() = AsyncSimState::Failed(err);
+                return;
+            }
+        };
+        let result: SimResult = (
+            report.passed,
+            report.cycles,
+            report.wall_duration.as_millis(),
+            report.wall_duration.as_secs(),
+        );
+
+        *thread_state.lock().unwrap() = AsyncSimState::Done(result);
+
+        if let Some(callback) = on_complete {
+            Python::with_gil(|py| {
+                let _ = callback.call1(py, result);
+            });
+        }
+    });
+
+    AsyncSim {
+        state,
+        handle: Mutex::new(Some(handle)),
+    }
+}
+
+/// Lets a frontend check compatibility before emitting a graph, instead of
+/// only finding out when `run_graph`/`run_graph_async` rejects it. Returns
+/// `(min_schema_version, max_schema_version, supported_features)`.
+#[pyfunction]
+fn supported_schema() -> (u32, u32, Vec<&'static str>) {
+    (
+        schema::MIN_SUPPORTED_SCHEMA_VERSION,
+        schema::MAX_SUPPORTED_SCHEMA_VERSION,
+        schema::SUPPORTED_FEATURES.to_vec(),
+    )
 }
 
 #[pymodule]
 fn step_perf(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(run_graph, m)?)?;
+    m.add_function(wrap_pyfunction!(run_graph_async, m)?)?;
+    m.add_function(wrap_pyfunction!(supported_schema, m)?)?;
     //    m.add_function(wrap_pyfunction!(run_graph_f64, m)?)?;
+    m.add_class::<AsyncSim>()?;
+    m.add("ProtoDecodeError", m.py().get_type::<ProtoDecodeError>())?;
     Ok(())
 }
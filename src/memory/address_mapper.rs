@@ -0,0 +1,157 @@
+/// Tile geometry an [`AddressMapper`] needs to turn a `(row, col_byte)`
+/// offset within a tile into a final DRAM address -- the same quantities
+/// [`crate::memory::random_offchip_load::RandomOffChipLoad`] already tracks,
+/// bundled up so a mapper doesn't need its own copy of them.
+pub struct TileGeom {
+    pub tile_row: usize,
+    pub tile_col: usize,
+    pub n_byte: usize,
+    /// Byte stride between consecutive tile-rows in the underlying tensor
+    /// (i.e. `tensor_shape_tiled.last() * tile_col * n_byte`).
+    pub row_offset: u64,
+}
+
+/// Turns a tile's logical `(row, col_byte)` offset into a physical DRAM
+/// address. `RandomOffChipLoad` calls this once per address instead of
+/// baking in a single fixed layout, so the same context can model different
+/// channel/bank interleavings and swizzles without changing its read loop.
+pub trait AddressMapper {
+    /// `base` is the tile's base address (`base_addr_byte` offset by
+    /// `tile_idx * tile_offset`); `row`/`col_byte` locate a byte within the
+    /// tile; `tile_idx` is repeated separately for mappers that fold it into
+    /// the channel/bank selection independently of `base`.
+    fn map(&self, base: u64, row: usize, col_byte: usize, tile_idx: u64, cfg: &TileGeom) -> u64;
+}
+
+/// The original row-major layout: `base + row * row_offset + col_byte`.
+/// Every simulation got this mapping before `AddressMapper` existed.
+pub struct LinearMapper;
+
+impl AddressMapper for LinearMapper {
+    fn map(&self, base: u64, row: usize, col_byte: usize, _tile_idx: u64, cfg: &TileGeom) -> u64 {
+        base + row as u64 * cfg.row_offset + col_byte as u64
+    }
+}
+
+/// Rotates `tile_idx` across `n_channels` channels and `n_banks` banks per
+/// channel, inserting the resulting channel/bank select fields into the
+/// linear address starting at `interleave_bit` -- consecutive tiles land on
+/// different banks (and channels) instead of all landing on the same one,
+/// the way a fixed linear mapping would whenever the tile stride is a
+/// multiple of the bank size. `n_channels` and `n_banks` must be powers of
+/// two, matching how real DRAM channel/bank counts are sized.
+pub struct BankInterleaveMapper {
+    pub n_channels: u64,
+    pub n_banks: u64,
+    /// Bit position in the linear address where the inserted channel/bank
+    /// select field begins; bits below this are preserved verbatim.
+    pub interleave_bit: u32,
+}
+
+impl AddressMapper for BankInterleaveMapper {
+    fn map(&self, base: u64, row: usize, col_byte: usize, tile_idx: u64, cfg: &TileGeom) -> u64 {
+        assert!(self.n_channels.is_power_of_two() && self.n_banks.is_power_of_two());
+        let linear = base + row as u64 * cfg.row_offset + col_byte as u64;
+
+        let channel = tile_idx % self.n_channels;
+        let bank = (tile_idx / self.n_channels) % self.n_banks;
+        let channel_bits = self.n_channels.trailing_zeros();
+        let bank_bits = self.n_banks.trailing_zeros();
+        let select = (bank << channel_bits) | channel;
+
+        let low_mask = (1u64 << self.interleave_bit) - 1;
+        let low = linear & low_mask;
+        let high = linear >> self.interleave_bit;
+        (high << (self.interleave_bit + channel_bits + bank_bits))
+            | (select << self.interleave_bit)
+            | low
+    }
+}
+
+/// XORs a configurable set of `row` bits into the linear address's
+/// bank-select field -- the classic permutation-based swizzle that breaks
+/// power-of-two stride bank conflicts a pure bit-select mapping would hit
+/// (e.g. every tile-row landing in the same bank because the row stride is
+/// itself a multiple of the bank size).
+pub struct XorSwizzleMapper {
+    /// Bit position in the linear address where the bank-select field
+    /// begins.
+    pub bank_select_bit: u32,
+    /// Number of bits in the bank-select field, and the length of
+    /// `row_xor_bits` that's actually consulted.
+    pub bank_bits: u32,
+    /// `row_xor_bits[i]` is the bit of `row` XORed into bank-select bit `i`;
+    /// `i` with no entry (or whose entry is out of bounds) is left alone.
+    pub row_xor_bits: Vec<u32>,
+}
+
+impl AddressMapper for XorSwizzleMapper {
+    fn map(&self, base: u64, row: usize, col_byte: usize, _tile_idx: u64, cfg: &TileGeom) -> u64 {
+        let linear = base + row as u64 * cfg.row_offset + col_byte as u64;
+
+        let bank_mask = ((1u64 << self.bank_bits) - 1) << self.bank_select_bit;
+        let mut bank_field = (linear & bank_mask) >> self.bank_select_bit;
+        for i in 0..self.bank_bits {
+            if let Some(&row_bit) = self.row_xor_bits.get(i as usize) {
+                let xor_bit = (row as u64 >> row_bit) & 1;
+                bank_field ^= xor_bit << i;
+            }
+        }
+
+        (linear & !bank_mask) | (bank_field << self.bank_select_bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geom() -> TileGeom {
+        TileGeom {
+            tile_row: 4,
+            tile_col: 4,
+            n_byte: 4,
+            row_offset: 64,
+        }
+    }
+
+    #[test]
+    fn linear_mapper_matches_original_formula() {
+        let mapper = LinearMapper;
+        let cfg = geom();
+        assert_eq!(mapper.map(1000, 2, 8, 5, &cfg), 1000 + 2 * 64 + 8);
+    }
+
+    #[test]
+    fn bank_interleave_spreads_consecutive_tiles_across_banks() {
+        let mapper = BankInterleaveMapper {
+            n_channels: 2,
+            n_banks: 4,
+            interleave_bit: 6,
+        };
+        let cfg = geom();
+        let addr_for = |tile_idx: u64| mapper.map(0, 0, 0, tile_idx, &cfg);
+
+        let bank_of = |addr: u64| (addr >> 6) & 0b111;
+        assert_ne!(bank_of(addr_for(0)), bank_of(addr_for(1)));
+        // Low bits (below interleave_bit) are untouched.
+        let mapper_row = mapper.map(0, 1, 4, 3, &cfg);
+        assert_eq!(mapper_row & 0b111111, (1 * 64 + 4) & 0b111111);
+    }
+
+    #[test]
+    fn xor_swizzle_flips_bank_select_with_row_bit() {
+        let mapper = XorSwizzleMapper {
+            bank_select_bit: 6,
+            bank_bits: 2,
+            row_xor_bits: vec![0, 1],
+        };
+        let cfg = geom();
+
+        // Same base/col, but row's low bits differ -> bank select differs.
+        let addr_row0 = mapper.map(0, 0, 0, 0, &cfg);
+        let addr_row1 = mapper.map(0, 1, 0, 0, &cfg);
+        let bank_of = |addr: u64| (addr >> 6) & 0b11;
+        assert_ne!(bank_of(addr_row0), bank_of(addr_row1));
+    }
+}
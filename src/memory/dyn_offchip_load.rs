@@ -1,8 +1,8 @@
+use std::collections::VecDeque;
 use std::marker::PhantomData;
 
 use dam::logging::LogEvent;
 use dam::{context_tools::*, types::StaticallySized};
-use itertools::Itertools;
 use ndarray::{IntoDimension, Ix2, IxDyn, IxDynImpl};
 
 use crate::ramulator::hbm_context::ParAddrs;
@@ -12,9 +12,70 @@ use crate::{
 };
 
 use crate::memory::HbmAddrEnum;
+use crate::primitives::dtype::DType;
 use crate::primitives::tile::Tile;
+use crate::ramulator::hbm_context::AddressMapping;
 use crate::utils::events::LoggableEventSimple;
 
+/// How `stream_tiles` groups a tile's flat address list into `ParAddrs`
+/// dispatch batches: which of `channel_num` channels each address resolves
+/// to (via the same [`AddressMapping`] `HBMContext` uses), so that
+/// consecutive dispatches round-robin across channels instead of
+/// contiguously chunking addresses that may all hash to the same one.
+#[derive(Debug, Clone)]
+pub struct ChannelSwizzle {
+    pub mapping: AddressMapping,
+    pub channel_num: usize,
+}
+
+/// Tile-level sparsity index for [`DynOffChipLoad`]'s block-sparse mode:
+/// which tiles of the (flattened) `tensor_shape_tiled` grid actually hold
+/// nonzero data and are worth fetching from HBM.
+pub enum SparseTileIndex {
+    /// One entry per tile in `tensor_shape_tiled`, in row-major order.
+    Bitmap(Vec<bool>),
+    /// CSR over the tile grid flattened to (rows, cols), `cols` being
+    /// `tensor_shape_tiled`'s last entry: `indices[indptr[r]..indptr[r+1]]`
+    /// are the present columns of row `r`.
+    Csr {
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+    },
+}
+
+/// A tile whose address requests have been issued but whose responses
+/// haven't been waited on yet: the in-flight record `stream_tiles`'
+/// prefetch FIFO keeps so it can launch tile N+1's requests before
+/// finishing tile N.
+struct PendingTile<T> {
+    send_request_ns: u64,
+    remaining_responses: usize,
+    elem_tile: Elem<Tile<T>>,
+    is_stop: bool,
+}
+
+impl SparseTileIndex {
+    fn into_bitmap(self, tensor_shape_tiled: &[usize]) -> Vec<bool> {
+        let total: usize = tensor_shape_tiled.iter().product();
+        match self {
+            SparseTileIndex::Bitmap(bitmap) => {
+                assert_eq!(bitmap.len(), total);
+                bitmap
+            }
+            SparseTileIndex::Csr { indptr, indices } => {
+                let cols = *tensor_shape_tiled.last().unwrap();
+                let mut bitmap = vec![false; total];
+                for row in 0..indptr.len() - 1 {
+                    for &col in &indices[indptr[row]..indptr[row + 1]] {
+                        bitmap[row * cols + col] = true;
+                    }
+                }
+                bitmap
+            }
+        }
+    }
+}
+
 #[context_macro]
 pub struct DynOffChipLoad<E: LoggableEventSimple, T: DAMType, R: DAMType> {
     // Tiling configurations
@@ -25,10 +86,36 @@ pub struct DynOffChipLoad<E: LoggableEventSimple, T: DAMType, R: DAMType> {
     pub tile_row: usize,
     pub tile_col: usize,
     pub n_byte: usize, // size of the datatype
+    // The on-HBM element format `n_byte` is checked against in `new`, so a
+    // caller can't silently pass an `n_byte` that doesn't match what's
+    // actually packed on disk. `None` means "whatever `n_byte` says" (the
+    // pre-existing, unvalidated behavior); `Some(DType::BF16 | DType::F16)`
+    // additionally up-converts the `.npy`'s raw 2-byte elements to `f32` on
+    // load -- see `new`'s doc comment for the `T = f32` constraint that
+    // comes with it.
+    pub storage_format: Option<DType>,
+    // When true, walk the tile in column-major order so the on-chip consumer
+    // sees the tile transposed (e.g. the RHS operand of a matmul), instead of
+    // requiring the `.npy` on disk to be pre-transposed.
+    pub transposed: bool,
+    // Presence bitmap over `tensor_shape_tiled` (row-major): `None` means
+    // every tile is fetched; `Some(bitmap)` skips HBM reads for tiles the
+    // bitmap marks absent, modeling block-sparse weight streaming.
+    pub tile_present: Option<Vec<bool>>,
+    // When set, `stream_tiles` groups a tile's addresses by the channel
+    // they resolve to under `ChannelSwizzle::mapping` before chunking into
+    // `ParAddrs` batches, instead of contiguous `par_dispatch`-sized slices.
+    pub channel_swizzle: Option<ChannelSwizzle>,
     // HBM Configurations & Addresses
     pub base_addr_byte: u64, // The base address for the given tensor
     pub addr_offset: u64,    // The data received per request
     pub par_dispatch: usize,
+    // How many tiles' worth of address requests `stream_tiles` keeps
+    // outstanding at once: depth 1 reproduces the fully-serialized
+    // request/wait/forward behavior, depth > 1 overlaps the HBM latency
+    // of later tiles with earlier tiles still being waited on, mirroring
+    // double-buffered shared-memory loading.
+    pub prefetch_depth: usize,
     // Sender & Receiver (DAM details)
     pub ref_rcv: Receiver<Elem<R>>,
     pub addr_snd: Sender<ParAddrs>,
@@ -39,14 +126,27 @@ pub struct DynOffChipLoad<E: LoggableEventSimple, T: DAMType, R: DAMType> {
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
-        T: npyz::Deserialize + DAMType,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        T: npyz::Deserialize + DAMType + 'static,
         R: DAMType,
     > DynOffChipLoad<E, T, R>
 where
     Elem<Tile<T>>: DAMType,
     Elem<R>: DAMType,
 {
+    /// `storage_format` asserts `n_byte` matches the declared on-HBM element
+    /// width instead of trusting it blindly (`Some(DType::BF16 | DType::F16)`
+    /// for a `.npy` packed as raw 2-byte bf16/f16 bit patterns, `None` to
+    /// skip the check as before). When set to `DType::BF16`/`DType::F16`,
+    /// the `.npy` is read as raw `u16` bits and up-converted to `f32` on
+    /// load (bf16 by widening into f32's top 16 bits, f16 via
+    /// [`half::f16`]) rather than deserialized as `T` directly, since
+    /// neither packed format is a type `npyz` knows how to decode as `T`.
+    /// That up-conversion only type-checks when this loader's `T` actually
+    /// is `f32` -- same one-element-type constraint as
+    /// [`crate::primitives::dtype::quantize_bf16`] -- which `new` asserts
+    /// via a runtime downcast rather than a new `Tile<Bf16>`/storage-vs-compute
+    /// split across `DynOffChipLoad`.
     pub fn new(
         tensor_shape_tiled: Vec<usize>,
         stride: Vec<usize>,
@@ -55,15 +155,29 @@ where
         tile_row: usize,
         tile_col: usize,
         n_byte: usize,
+        storage_format: Option<DType>,
+        transposed: bool,
+        sparse_index: Option<SparseTileIndex>,
+        channel_swizzle: Option<ChannelSwizzle>,
         base_addr_byte: u64,
         addr_offset: u64,
         par_dispatch: usize,
+        prefetch_depth: usize,
         ref_rcv: Receiver<Elem<R>>,
         addr_snd: Sender<ParAddrs>,
         resp_addr_rcv: Receiver<u64>,
         on_chip_snd: Sender<Elem<Tile<T>>>,
         id: u32,
     ) -> Self {
+        if let Some(format) = storage_format {
+            assert_eq!(
+                n_byte,
+                format.bytes(),
+                "n_byte {n_byte} doesn't match storage_format {format:?}'s width of {} bytes",
+                format.bytes(),
+            );
+        }
+        let tile_present = sparse_index.map(|idx| idx.into_bitmap(&tensor_shape_tiled));
         let underlying = match npy_path {
             Some(file_path) => {
                 // Open the file
@@ -77,16 +191,56 @@ where
                     .map(|x| *x as usize)
                     .collect::<Vec<usize>>();
 
-                let total_cols = tile_col * tensor_shape_tiled.last().unwrap();
-                let total_rows = tile_row * tensor_shape_tiled[tensor_shape_tiled.len() - 2];
-                let mut untiled_shape = tensor_shape_tiled[..tensor_shape_tiled.len() - 2].to_vec();
-                untiled_shape.append(&mut vec![total_rows, total_cols]);
+                match &tile_present {
+                    Some(present) => {
+                        // Sparse mode: only present tiles are stored, packed
+                        // contiguously, so the `.npy` shape is the present
+                        // count rather than the full dense tile grid.
+                        let num_present = present.iter().filter(|&&p| p).count();
+                        assert_eq!(vec![num_present, tile_row, tile_col], shape_vec);
+                    }
+                    None => {
+                        let total_cols = tile_col * tensor_shape_tiled.last().unwrap();
+                        let total_rows =
+                            tile_row * tensor_shape_tiled[tensor_shape_tiled.len() - 2];
+                        let mut untiled_shape =
+                            tensor_shape_tiled[..tensor_shape_tiled.len() - 2].to_vec();
+                        untiled_shape.append(&mut vec![total_rows, total_cols]);
 
-                assert_eq!(untiled_shape, shape_vec);
+                        assert_eq!(untiled_shape, shape_vec);
+                    }
+                }
 
                 let shape: ndarray::Dim<IxDynImpl> = shape_vec.into_dimension();
 
-                let vec_data: Vec<T> = file_data.into_vec().unwrap();
+                let vec_data: Vec<T> = match storage_format {
+                    Some(format @ (DType::BF16 | DType::F16)) => {
+                        // Neither packed format is one `npyz` can decode as
+                        // `T` directly, so read the raw on-disk bits and
+                        // widen them to `f32` ourselves.
+                        let reader = file_data.data::<u16>().unwrap();
+                        let upconverted: Vec<f32> = reader
+                            .map(|bits| {
+                                let bits = bits.unwrap();
+                                match format {
+                                    DType::BF16 => f32::from_bits((bits as u32) << 16),
+                                    DType::F16 => half::f16::from_bits(bits).to_f32(),
+                                    _ => unreachable!(),
+                                }
+                            })
+                            .collect();
+                        *(Box::new(upconverted) as Box<dyn std::any::Any>)
+                            .downcast::<Vec<T>>()
+                            .unwrap_or_else(|_| {
+                                panic!(
+                                    "storage_format {format:?} up-converts to f32 on load; \
+                                     DynOffChipLoad's element type T must be f32, not \
+                                     whatever this instance was built with"
+                                )
+                            })
+                    }
+                    _ => file_data.into_vec().unwrap(),
+                };
                 Some(ndarray::ArcArray::from_shape_vec(shape, vec_data).unwrap())
             }
             None => None,
@@ -99,9 +253,14 @@ where
             tile_row,
             tile_col,
             n_byte,
+            storage_format,
+            transposed,
+            tile_present,
+            channel_swizzle,
             base_addr_byte,
             addr_offset,
             par_dispatch,
+            prefetch_depth,
             ref_rcv,
             addr_snd,
             resp_addr_rcv,
@@ -122,6 +281,27 @@ where
         let mut tile_data = vec![];
         // Tile the actual data
         match &self.underlying {
+            Some(arr) if self.tile_present.is_some() => {
+                // Sparse mode: `arr` is packed as [num_present_tiles, tile_row,
+                // tile_col] -- only present tiles are stored, in ascending
+                // tile-index order -- so we walk it directly instead of
+                // windowing over a (nonexistent) dense grid.
+                for tile_i in arr.outer_iter() {
+                    let shaped = if self.transposed {
+                        tile_i
+                            .reversed_axes()
+                            .to_shared()
+                            .into_shape_with_order((self.tile_col, self.tile_row))
+                            .unwrap()
+                    } else {
+                        tile_i
+                            .to_shared()
+                            .into_shape_with_order((self.tile_row, self.tile_col))
+                            .unwrap()
+                    };
+                    tile_data.push(Tile::new(shaped, self.n_byte, true))
+                }
+            }
             Some(arr) => {
                 let ndim = arr.ndim();
 
@@ -139,22 +319,55 @@ where
                 // Remaining dimensions keep size/stride of 1 (as you suggested)
 
                 for tile_i in arr.windows_with_stride(IxDyn(&window_size), IxDyn(&stride)) {
-                    tile_data.push(Tile::new(
+                    let shaped = if self.transposed {
+                        tile_i
+                            .reversed_axes()
+                            .to_shared()
+                            .into_shape_with_order((self.tile_col, self.tile_row))
+                            .unwrap()
+                    } else {
                         tile_i
                             .to_shared()
                             .into_shape_with_order((self.tile_row, self.tile_col))
-                            .unwrap(),
-                        self.n_byte,
-                        true,
-                    ))
+                            .unwrap()
+                    };
+                    tile_data.push(Tile::new(shaped, self.n_byte, true))
                 }
             }
             None => {}
         };
 
+        // Tile-index -> packed tile_data index, advancing only on present
+        // tiles; `None` when there's no sparsity index, meaning identity.
+        let data_idx_map: Option<Vec<usize>> = self.tile_present.as_ref().map(|present| {
+            let mut map = Vec::with_capacity(present.len());
+            let mut next_data_idx = 0;
+            for &is_present in present {
+                map.push(next_data_idx);
+                if is_present {
+                    next_data_idx += 1;
+                }
+            }
+            map
+        });
+
         // Calculate total elements in the output tensor
         let total_tiles: usize = self.out_shape_tiled.iter().product();
 
+        // When transposed, the outer two tiled dimensions are consumed in
+        // swapped order, so the tile-selection stride across
+        // `out_shape_tiled` swaps its last two entries to match.
+        let stride = if self.transposed {
+            let mut swapped = self.stride.clone();
+            let n = swapped.len();
+            if n >= 2 {
+                swapped.swap(n - 2, n - 1);
+            }
+            swapped
+        } else {
+            self.stride.clone()
+        };
+
         // Create a vector to hold all the addresses
         let mut addrs: Vec<HbmAddrEnum<T>> = vec![];
 
@@ -172,7 +385,7 @@ where
             // Calculate the index in the original flat tensor using strides
             let mut tile_idx = 0;
             for (dim, &idx_in_dim) in multi_index.iter().enumerate() {
-                tile_idx += idx_in_dim * self.stride[dim];
+                tile_idx += idx_in_dim * stride[dim];
             }
 
             // Ensure we don't go out of bounds of the original tensor
@@ -184,17 +397,46 @@ where
             }
             // println!("tile_idx: {}", tile_idx);
 
+            // Block-sparse mode: skip the HBM round-trip entirely for tiles
+            // the presence bitmap marks absent -- they cost zero bandwidth,
+            // but still flow through as a blank tile below so dataflow and
+            // stop-token accounting stay correct.
+            let present = self
+                .tile_present
+                .as_ref()
+                .map_or(true, |bitmap| bitmap[tile_idx]);
+
             // Generate addresses to fetch the given tile
             let tile_offset = self.tile_row * self.tile_col * self.n_byte;
             let base_addr_i = self.base_addr_byte + (tile_idx * tile_offset) as u64;
             let row_offset = self.tensor_shape_tiled.last().unwrap() * self.tile_col * self.n_byte;
 
-            // Generate all addresses for this tile
+            // Generate all addresses for this tile. In transposed mode the
+            // outer iteration walks columns instead of rows, and the
+            // per-element stride within the tile becomes `row_offset`
+            // (successive elements of a column live a full row apart)
+            // rather than `n_byte` (successive elements of a row are
+            // contiguous).
             let mut tile_addrs = vec![];
-            for r in 0..self.tile_row {
-                for c in (0..(self.tile_col * self.n_byte)).step_by(self.addr_offset as usize) {
-                    let addr: u64 = base_addr_i + (r * row_offset + c) as u64;
-                    tile_addrs.push(addr);
+            if present {
+                if self.transposed {
+                    for c in 0..self.tile_col {
+                        for r in
+                            (0..(self.tile_row * row_offset)).step_by(self.addr_offset as usize)
+                        {
+                            let addr: u64 = base_addr_i + (c * self.n_byte + r) as u64;
+                            tile_addrs.push(addr);
+                        }
+                    }
+                } else {
+                    for r in 0..self.tile_row {
+                        for c in
+                            (0..(self.tile_col * self.n_byte)).step_by(self.addr_offset as usize)
+                        {
+                            let addr: u64 = base_addr_i + (r * row_offset + c) as u64;
+                            tile_addrs.push(addr);
+                        }
+                    }
                 }
             }
 
@@ -220,48 +462,32 @@ where
                 }
             }
 
-            match self.underlying {
-                Some(_) => {
-                    // Add the addresses to the result list
-                    if !tile_addrs.is_empty() {
-                        if let Some(stop_type) = highest_stop_token {
-                            // If there's a stop token, add all addresses except the last one
-                            addrs.push(HbmAddrEnum::ADDRSTOP(
-                                tile_addrs,
-                                tile_data[tile_idx].clone(),
-                                stop_type,
-                            ));
-                        } else {
-                            // No stop token, add all addresses normally
-                            addrs.push(HbmAddrEnum::ADDR(tile_addrs, tile_data[tile_idx].clone()));
-                        }
+            let blank_shape = if self.transposed {
+                vec![self.tile_col, self.tile_row]
+            } else {
+                vec![self.tile_row, self.tile_col]
+            };
+
+            let payload = if present {
+                match &self.underlying {
+                    Some(_) => {
+                        let data_idx = data_idx_map.as_ref().map_or(tile_idx, |map| map[tile_idx]);
+                        tile_data[data_idx].clone()
                     }
+                    None => Tile::new_blank(blank_shape, self.n_byte, true),
                 }
-                None => {
-                    if !tile_addrs.is_empty() {
-                        if let Some(stop_type) = highest_stop_token {
-                            // If there's a stop token, add all addresses except the last one
-                            addrs.push(HbmAddrEnum::ADDRSTOP(
-                                tile_addrs,
-                                Tile::new_blank(
-                                    vec![self.tile_row, self.tile_col],
-                                    self.n_byte,
-                                    true,
-                                ),
-                                stop_type,
-                            ));
-                        } else {
-                            // No stop token, add all addresses normally
-                            addrs.push(HbmAddrEnum::ADDR(
-                                tile_addrs,
-                                Tile::new_blank(
-                                    vec![self.tile_row, self.tile_col],
-                                    self.n_byte,
-                                    true,
-                                ),
-                            ));
-                        }
-                    }
+            } else {
+                Tile::new_blank(blank_shape, self.n_byte, true)
+            };
+
+            // Absent tiles always flow through with an empty `tile_addrs`
+            // (no HBM round-trip, no latency); present tiles only emit when
+            // they actually produced addresses.
+            if !present || !tile_addrs.is_empty() {
+                if let Some(stop_type) = highest_stop_token {
+                    addrs.push(HbmAddrEnum::ADDRSTOP(tile_addrs, payload, stop_type));
+                } else {
+                    addrs.push(HbmAddrEnum::ADDR(tile_addrs, payload));
                 }
             }
         }
@@ -269,7 +495,95 @@ where
         addrs.into_iter()
     }
 
+    /// Splits `tile_addrs` into `par_dispatch`-sized `ParAddrs` batches. With
+    /// no swizzle configured this is the original contiguous chunking; with
+    /// one configured, addresses are first bucketed by the channel they
+    /// resolve to, each bucket is chunked independently, and the buckets'
+    /// chunks are then interleaved round-robin, so consecutive batches land
+    /// on different channels instead of whichever channel a contiguous run
+    /// happens to hash to.
+    fn swizzled_chunks(&self, tile_addrs: &[u64]) -> Vec<Vec<u64>> {
+        let Some(swizzle) = &self.channel_swizzle else {
+            return tile_addrs
+                .chunks(self.par_dispatch)
+                .map(|chunk| chunk.to_vec())
+                .collect();
+        };
+
+        let mut by_channel: Vec<Vec<u64>> = vec![Vec::new(); swizzle.channel_num.max(1)];
+        for &addr in tile_addrs {
+            let channel = swizzle
+                .mapping
+                .channel_of(addr, self.addr_offset, swizzle.channel_num);
+            by_channel[channel].push(addr);
+        }
+
+        let mut per_channel_chunks: Vec<_> = by_channel
+            .into_iter()
+            .map(|addrs| {
+                addrs
+                    .chunks(self.par_dispatch)
+                    .map(|chunk| chunk.to_vec())
+                    .collect::<Vec<_>>()
+                    .into_iter()
+            })
+            .collect();
+
+        let mut ordered = vec![];
+        loop {
+            let mut any = false;
+            for chunks in per_channel_chunks.iter_mut() {
+                if let Some(chunk) = chunks.next() {
+                    ordered.push(chunk);
+                    any = true;
+                }
+            }
+            if !any {
+                break;
+            }
+        }
+        ordered
+    }
+
+    // Waits on `pending`'s remaining responses, logs its request-issue to
+    // read-finish window, then forwards its tile on-chip. To properly
+    // preserve backpressure under the double buffering setting, `on_chip_snd`
+    // should have a depth of 1.
+    fn finish_tile(&self, pending: PendingTile<T>) {
+        for _i in 0..pending.remaining_responses {
+            // Wait until you get back the response
+            self.resp_addr_rcv.dequeue(&self.time).unwrap();
+        }
+
+        let read_finish_time = self.time.tick();
+
+        crate::utils::events::log_event(&E::new(
+            "DynOffChipLoad".to_string(),
+            self.id,
+            pending.send_request_ns,
+            read_finish_time.time(),
+            pending.is_stop,
+        ));
+
+        self.on_chip_snd
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data: pending.elem_tile,
+                },
+            )
+            .unwrap();
+    }
+
     fn stream_tiles(&self, ref_stop_lev: Option<StopType>) {
+        // At most `prefetch_depth` tiles have their address requests
+        // in flight at once; older tiles are drained (waited on, then
+        // forwarded on-chip) as newer ones are issued, overlapping each
+        // tile's HBM latency with the wait on the tiles ahead of it.
+        let prefetch_depth = self.prefetch_depth.max(1);
+        let mut outstanding: VecDeque<PendingTile<T>> = VecDeque::new();
+
         for addr_enum in self.generate_addr() {
             let (tile_addrs, elem_tile, is_stop) = match addr_enum {
                 HbmAddrEnum::ADDR(addrs, tile) => (addrs, Elem::Val(tile), false),
@@ -287,17 +601,36 @@ where
                     };
                     (addrs, Elem::ValStop(tile, final_stop_lev), true)
                 }
+                // This context's `generate_addr` never emits the
+                // burst-descriptor variants (swizzling needs the explicit
+                // address list), but the match must stay exhaustive --
+                // expand back into a `Vec` so the rest of the pipeline is
+                // unaffected.
+                HbmAddrEnum::ADDR2D(burst, tile) => {
+                    (burst.expand().collect(), Elem::Val(tile), false)
+                }
+                HbmAddrEnum::ADDRSTOP2D(burst, tile, level) => {
+                    let final_stop_lev = match ref_stop_lev {
+                        Some(ref_stop) => {
+                            if level == self.out_shape_tiled.len() as u32 {
+                                ref_stop + level
+                            } else {
+                                level
+                            }
+                        }
+                        None => level,
+                    };
+                    (
+                        burst.expand().collect(),
+                        Elem::ValStop(tile, final_stop_lev),
+                        true,
+                    )
+                }
             };
 
             // Send read request to HBM
             let send_request_time = self.time.tick();
-            for (idx, addr_chunk) in tile_addrs
-                .iter()
-                .chunks(self.par_dispatch)
-                .into_iter()
-                .enumerate()
-            {
-                let chunk_vec: Vec<u64> = addr_chunk.cloned().collect();
+            for (idx, chunk_vec) in self.swizzled_chunks(&tile_addrs).into_iter().enumerate() {
                 self.addr_snd
                     .enqueue(
                         &self.time,
@@ -309,35 +642,24 @@ where
                     .unwrap();
             }
 
-            for _i in tile_addrs {
-                // Wait until you get back the response
-                self.resp_addr_rcv.dequeue(&self.time).unwrap();
-            }
-
-            let read_finish_time = self.time.tick();
-
-            dam::logging::log_event(&E::new(
-                "DynOffChipLoad".to_string(),
-                self.id,
-                send_request_time.time(),
-                read_finish_time.time(),
+            outstanding.push_back(PendingTile {
+                send_request_ns: send_request_time.time(),
+                remaining_responses: tile_addrs.len(),
+                elem_tile,
                 is_stop,
-            ))
-            .unwrap();
+            });
+
+            // Keep at most `prefetch_depth` tiles in flight: once that many
+            // are outstanding, drain the oldest before issuing the next.
+            while outstanding.len() >= prefetch_depth {
+                let pending = outstanding.pop_front().unwrap();
+                self.finish_tile(pending);
+            }
+        }
 
-            // Send the data to on-chip
-            // To properly the backpressure under the double buffering setting,
-            // this channel should have a depth of 1
-
-            self.on_chip_snd
-                .enqueue(
-                    &self.time,
-                    ChannelElement {
-                        time: self.time.tick(),
-                        data: elem_tile,
-                    },
-                )
-                .unwrap();
+        // Drain whatever is still in flight once every tile's been issued.
+        while let Some(pending) = outstanding.pop_front() {
+            self.finish_tile(pending);
         }
     }
 
@@ -347,7 +669,7 @@ where
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: npyz::Deserialize + DAMType,
         R: DAMType,
     > Context for DynOffChipLoad<E, T, R>
@@ -397,7 +719,7 @@ mod tests {
             select::MultiHotN,
             tile::Tile,
         },
-        ramulator::hbm_context::{HBMConfig, HBMContext, ReadBundle},
+        ramulator::hbm_context::{AddressMapping, HBMConfig, HBMContext, ReadBundle},
         utils::events::{SimpleEvent, DUMMY_ID},
     };
 
@@ -436,6 +758,216 @@ mod tests {
                 per_channel_latency: 2,
                 per_channel_outstanding: 1,
                 per_channel_start_up_time: 14,
+                bank_num: 1,
+                row_size_bytes: ADDR_OFFSET,
+                row_conflict_penalty: 0,
+                address_mapping: AddressMapping::Linear,
+            },
+        );
+        mem_context.add_reader(ReadBundle {
+            addr: addr_rcv,
+            resp: resp_snd,
+        });
+
+        ctx.add_child(mem_context);
+
+        ctx.add_child(GeneratorContext::new(
+            move || ref_buff.to_elem_iter().collect::<Vec<_>>().into_iter(),
+            ref_snd,
+        ));
+        ctx.add_child(DynOffChipLoad::<SimpleEvent, VT, _>::new(
+            vec![2, 2],
+            vec![2, 1],
+            vec![2, 2],
+            None,
+            TILE_ROW,
+            TILE_COL,
+            BYTES_PER_ELEM,
+            None, // storage_format
+            false,
+            None,
+            None,
+            0,
+            ADDR_OFFSET,
+            4,
+            1, // prefetch_depth: reproduce today's fully-serialized behavior
+            ref_rcv,
+            addr_snd,
+            resp_rcv,
+            snd,
+            0,
+        ));
+
+        const READ_FROM_MU: bool = true;
+        const DUMMY_CREATION_TIME: u64 = 0;
+        let tile_vec =
+            vec![
+                Tile::<VT>::new_blank(vec![TILE_ROW, TILE_COL], BYTES_PER_ELEM, READ_FROM_MU);
+                2 * 3 * 2 * 2
+            ];
+
+        // =============== Input [2,2] ================
+        // Create 2x2 Buffers (each are a buffer of 2x2 tiles)
+        let arr = Arc::new(
+            ArcArray::from_vec(tile_vec)
+                .into_shape_with_order((2, 3, 2, 2))
+                .unwrap(),
+        );
+        let buff = Buffer::new((*arr).clone().into_dyn(), DUMMY_CREATION_TIME);
+
+        // =============== Output Stream [2,3,2,2] ================
+        ctx.add_child(ApproxCheckerContext::new(
+            move || buff.to_elem_iter().collect::<Vec<_>>().into_iter(),
+            rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't match")]
+    fn storage_format_rejects_mismatched_n_byte() {
+        // BF16 is 2 bytes/elem; claiming 4 should be caught at construction
+        // rather than silently mis-sizing every generated address.
+        let mut ctx = ProgramBuilder::default();
+        let (addr_snd, _addr_rcv) = ctx.unbounded();
+        let (_resp_snd, resp_rcv) = ctx.unbounded();
+        let (_ref_snd, ref_rcv) = ctx.unbounded::<Elem<u32>>();
+        let (snd, _rcv) = ctx.unbounded();
+
+        DynOffChipLoad::<SimpleEvent, u32, _>::new(
+            vec![2, 2],
+            vec![2, 1],
+            vec![2, 2],
+            None,
+            16,
+            16,
+            4,
+            Some(crate::primitives::dtype::DType::BF16),
+            false,
+            None,
+            None,
+            0,
+            64,
+            4,
+            1,
+            ref_rcv,
+            addr_snd,
+            resp_rcv,
+            snd,
+            0,
+        );
+    }
+
+    #[test]
+    fn bf16_storage_format_upconverts_to_f32() {
+        // 1.5 and -2.0 round-trip exactly through bf16 (their low mantissa
+        // bits are already zero), so widening the raw bf16 bits back into
+        // f32's top 16 bits should reproduce them precisely. npyz has no
+        // bf16 element type to read directly, so the `.npy` written here is
+        // declared `<u2` and only reinterpreted as bf16 by `storage_format`.
+        let values: [f32; 2] = [1.5, -2.0];
+        let raw_bits: Vec<u16> = values.iter().map(|v| (v.to_bits() >> 16) as u16).collect();
+
+        let mut header = "{'descr': '<u2', 'fortran_order': False, 'shape': (1, 2), }".to_string();
+        let prefix_len = 6 /* magic */ + 2 /* version */ + 2 /* header_len field */;
+        let unpadded_len = prefix_len + header.len() + 1 /* trailing '\n' */;
+        let padding = (64 - unpadded_len % 64) % 64;
+        header.push_str(&" ".repeat(padding));
+        header.push('\n');
+
+        let mut npy_bytes = Vec::new();
+        npy_bytes.extend_from_slice(b"\x93NUMPY");
+        npy_bytes.extend_from_slice(&[1, 0]);
+        npy_bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        npy_bytes.extend_from_slice(header.as_bytes());
+        for bits in &raw_bits {
+            npy_bytes.extend_from_slice(&bits.to_le_bytes());
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "step_perf_dyn_offchip_load_bf16_{:?}.npy",
+            std::thread::current().id()
+        ));
+        std::fs::write(&path, &npy_bytes).unwrap();
+
+        let mut ctx = ProgramBuilder::default();
+        let (addr_snd, _addr_rcv) = ctx.unbounded();
+        let (_resp_snd, resp_rcv) = ctx.unbounded();
+        let (_ref_snd, ref_rcv) = ctx.unbounded::<Elem<u32>>();
+        let (snd, _rcv) = ctx.unbounded();
+
+        let load = DynOffChipLoad::<SimpleEvent, f32, _>::new(
+            vec![1, 1],
+            vec![1, 1],
+            vec![1, 1],
+            Some(path.to_str().unwrap().to_string()),
+            1,
+            2,
+            2, // n_byte: bf16 is 2 bytes/elem
+            Some(crate::primitives::dtype::DType::BF16),
+            false,
+            None,
+            None,
+            0,
+            64,
+            1,
+            1,
+            ref_rcv,
+            addr_snd,
+            resp_rcv,
+            snd,
+            0,
+        );
+
+        let underlying = load.underlying.as_ref().unwrap();
+        assert_eq!(underlying.iter().copied().collect::<Vec<f32>>(), values.to_vec());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn round_trip_test_4d_prefetch_depth_4() {
+        // Same grid as `round_trip_test_4d`, but with `prefetch_depth: 4`
+        // (every tile's requests issued before any is waited on), checking
+        // that pipelining doesn't change the tiles actually delivered.
+        type VT = u32;
+
+        const BYTES_PER_ELEM: usize = 4;
+        const TILE_ROW: usize = 16;
+        const TILE_COL: usize = 16;
+
+        const ADDR_OFFSET: u64 = 64; // The number of bytes to read per request
+
+        let mut ctx = ProgramBuilder::default();
+        let (addr_snd, addr_rcv) = ctx.unbounded();
+        let (resp_snd, resp_rcv) = ctx.unbounded();
+        let (ref_snd, ref_rcv) = ctx.unbounded();
+        let (snd, rcv) = ctx.unbounded();
+
+        let ref_arr = Arc::new(
+            ArcArray::from_vec(vec![MultiHotN::new(vec![true, false], false); 2 * 3])
+                .into_shape_with_order((2, 3))
+                .unwrap(),
+        );
+        let ref_buff = Buffer::new((*ref_arr).clone().into_dyn(), DUMMY_CREATION_TIME);
+
+        let mut mem_context = HBMContext::new(
+            &mut ctx,
+            HBMConfig {
+                addr_offset: ADDR_OFFSET,
+                channel_num: 8,
+                per_channel_init_interval: 2,
+                per_channel_latency: 2,
+                per_channel_outstanding: 1,
+                per_channel_start_up_time: 14,
+                bank_num: 1,
+                row_size_bytes: ADDR_OFFSET,
+                row_conflict_penalty: 0,
+                address_mapping: AddressMapping::Linear,
             },
         );
         mem_context.add_reader(ReadBundle {
@@ -457,9 +989,14 @@ mod tests {
             TILE_ROW,
             TILE_COL,
             BYTES_PER_ELEM,
+            None, // storage_format
+            false,
+            None,
+            None,
             0,
             ADDR_OFFSET,
             4,
+            4, // prefetch_depth
             ref_rcv,
             addr_snd,
             resp_rcv,
@@ -495,4 +1032,210 @@ mod tests {
             .unwrap()
             .run(Default::default());
     }
+
+    #[test]
+    fn round_trip_test_4d_transposed() {
+        // Same grid as `round_trip_test_4d`, but with `transposed: true` and
+        // a non-square tile, so each emitted tile comes out as
+        // [TILE_COL, TILE_ROW] instead of [TILE_ROW, TILE_COL].
+        type VT = u32;
+
+        const BYTES_PER_ELEM: usize = 4;
+        const TILE_ROW: usize = 8;
+        const TILE_COL: usize = 16;
+
+        const ADDR_OFFSET: u64 = 64; // The number of bytes to read per request
+
+        let mut ctx = ProgramBuilder::default();
+        let (addr_snd, addr_rcv) = ctx.unbounded();
+        let (resp_snd, resp_rcv) = ctx.unbounded();
+        let (ref_snd, ref_rcv) = ctx.unbounded();
+        let (snd, rcv) = ctx.unbounded();
+
+        let ref_arr = Arc::new(
+            ArcArray::from_vec(vec![MultiHotN::new(vec![true, false], false); 2 * 3])
+                .into_shape_with_order((2, 3))
+                .unwrap(),
+        );
+        let ref_buff = Buffer::new((*ref_arr).clone().into_dyn(), DUMMY_CREATION_TIME);
+
+        let mut mem_context = HBMContext::new(
+            &mut ctx,
+            HBMConfig {
+                addr_offset: ADDR_OFFSET,
+                channel_num: 8,
+                per_channel_init_interval: 2,
+                per_channel_latency: 2,
+                per_channel_outstanding: 1,
+                per_channel_start_up_time: 14,
+                bank_num: 1,
+                row_size_bytes: ADDR_OFFSET,
+                row_conflict_penalty: 0,
+                address_mapping: AddressMapping::Linear,
+            },
+        );
+        mem_context.add_reader(ReadBundle {
+            addr: addr_rcv,
+            resp: resp_snd,
+        });
+
+        ctx.add_child(mem_context);
+
+        ctx.add_child(GeneratorContext::new(
+            move || ref_buff.to_elem_iter().collect::<Vec<_>>().into_iter(),
+            ref_snd,
+        ));
+        ctx.add_child(DynOffChipLoad::<SimpleEvent, VT, _>::new(
+            vec![2, 2],
+            vec![2, 1],
+            vec![2, 2],
+            None,
+            TILE_ROW,
+            TILE_COL,
+            BYTES_PER_ELEM,
+            None, // storage_format
+            true,
+            None,
+            None,
+            0,
+            ADDR_OFFSET,
+            4,
+            1, // prefetch_depth: reproduce today's fully-serialized behavior
+            ref_rcv,
+            addr_snd,
+            resp_rcv,
+            snd,
+            0,
+        ));
+
+        const READ_FROM_MU: bool = true;
+        const DUMMY_CREATION_TIME: u64 = 0;
+        let tile_vec =
+            vec![
+                Tile::<VT>::new_blank(vec![TILE_COL, TILE_ROW], BYTES_PER_ELEM, READ_FROM_MU);
+                2 * 3 * 2 * 2
+            ];
+
+        // =============== Input [2,2] ================
+        // Create 2x2 Buffers (each are a buffer of 2x2 tiles)
+        let arr = Arc::new(
+            ArcArray::from_vec(tile_vec)
+                .into_shape_with_order((2, 3, 2, 2))
+                .unwrap(),
+        );
+        let buff = Buffer::new((*arr).clone().into_dyn(), DUMMY_CREATION_TIME);
+
+        // =============== Output Stream [2,3,2,2], tiles transposed ================
+        ctx.add_child(ApproxCheckerContext::new(
+            move || buff.to_elem_iter().collect::<Vec<_>>().into_iter(),
+            rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn sparse_skip_absent_tiles_still_stream_blank() {
+        // A 2x2 tile grid where only the diagonal tiles (0 and 3) are
+        // present; the off-diagonal tiles must still stream through as
+        // blank tiles even though no HBM address is ever generated for them.
+        type VT = u32;
+
+        const BYTES_PER_ELEM: usize = 4;
+        const TILE_ROW: usize = 16;
+        const TILE_COL: usize = 16;
+
+        const ADDR_OFFSET: u64 = 64;
+
+        let mut ctx = ProgramBuilder::default();
+        let (addr_snd, addr_rcv) = ctx.unbounded();
+        let (resp_snd, resp_rcv) = ctx.unbounded();
+        let (ref_snd, ref_rcv) = ctx.unbounded();
+        let (snd, rcv) = ctx.unbounded();
+
+        let ref_arr = Arc::new(
+            ArcArray::from_vec(vec![MultiHotN::new(vec![true, false], false); 2 * 2])
+                .into_shape_with_order((2, 2))
+                .unwrap(),
+        );
+        let ref_buff = Buffer::new((*ref_arr).clone().into_dyn(), DUMMY_CREATION_TIME);
+
+        let mut mem_context = HBMContext::new(
+            &mut ctx,
+            HBMConfig {
+                addr_offset: ADDR_OFFSET,
+                channel_num: 8,
+                per_channel_init_interval: 2,
+                per_channel_latency: 2,
+                per_channel_outstanding: 1,
+                per_channel_start_up_time: 14,
+                bank_num: 1,
+                row_size_bytes: ADDR_OFFSET,
+                row_conflict_penalty: 0,
+                address_mapping: AddressMapping::Linear,
+            },
+        );
+        mem_context.add_reader(ReadBundle {
+            addr: addr_rcv,
+            resp: resp_snd,
+        });
+
+        ctx.add_child(mem_context);
+
+        ctx.add_child(GeneratorContext::new(
+            move || ref_buff.to_elem_iter().collect::<Vec<_>>().into_iter(),
+            ref_snd,
+        ));
+        ctx.add_child(DynOffChipLoad::<SimpleEvent, VT, _>::new(
+            vec![2, 2],
+            vec![2, 1],
+            vec![2, 2],
+            None,
+            TILE_ROW,
+            TILE_COL,
+            BYTES_PER_ELEM,
+            None, // storage_format
+            false,
+            Some(SparseTileIndex::Bitmap(vec![true, false, false, true])),
+            None,
+            0,
+            ADDR_OFFSET,
+            4,
+            1, // prefetch_depth: reproduce today's fully-serialized behavior
+            ref_rcv,
+            addr_snd,
+            resp_rcv,
+            snd,
+            0,
+        ));
+
+        const READ_FROM_MU: bool = true;
+        const DUMMY_CREATION_TIME: u64 = 0;
+        let tile_vec = vec![
+            Tile::<VT>::new_blank(vec![TILE_ROW, TILE_COL], BYTES_PER_ELEM, READ_FROM_MU);
+            2 * 2 * 2 * 2
+        ];
+
+        // =============== Input [2,2] ================
+        let arr = Arc::new(
+            ArcArray::from_vec(tile_vec)
+                .into_shape_with_order((2, 2, 2, 2))
+                .unwrap(),
+        );
+        let buff = Buffer::new((*arr).clone().into_dyn(), DUMMY_CREATION_TIME);
+
+        // =============== Output Stream [2,2,2,2], all still blank tiles ================
+        ctx.add_child(ApproxCheckerContext::new(
+            move || buff.to_elem_iter().collect::<Vec<_>>().into_iter(),
+            rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
 }
@@ -1,38 +1,100 @@
+use std::io::{Cursor, Read};
+use std::marker::PhantomData;
+
 use crate::primitives::{elem::Elem, tile::Tile};
 use dam::{context_tools::*, types::DAMType};
 
-use itertools::enumerate;
-use ndarray::{Array2, IntoDimension, IxDyn, IxDynImpl};
+use memmap2::Mmap;
+use ndarray::Array2;
+
+/// Backing bytes for a [`MetadataGen`] instance: either a read-only memory
+/// map over a standalone `.npy` file -- pages are faulted in by the OS as
+/// [`Context::run`] reads through them, never fully materialized up front
+/// -- or an owned buffer holding one member's bytes extracted from a
+/// `.npz` archive via [`MetadataGen::from_npz`]. `.npz` members are
+/// ordinary zip entries, usually compressed, so they can't be mapped
+/// directly and have to land in memory once they're decompressed.
+enum MetadataBytes {
+    Mmap(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl std::ops::Deref for MetadataBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            MetadataBytes::Mmap(mmap) => mmap,
+            MetadataBytes::Owned(bytes) => bytes,
+        }
+    }
+}
 
 #[context_macro]
 pub struct MetadataGen<T: Clone> {
-    pub underlying: ndarray::ArcArray<T, IxDyn>,
+    bytes: MetadataBytes,
+    shape: Vec<usize>,
     pub snd: Sender<Elem<Tile<u64>>>,
     pub id: u32,
+    _phantom: PhantomData<T>,
 }
 
 impl<T: npyz::Deserialize + Clone + TryInto<u64> + TryFrom<u64> + Send + Sync> MetadataGen<T> {
     pub fn new(npy_path: String, snd: Sender<Elem<Tile<u64>>>, id: u32) -> Self {
-        let mut file = std::fs::File::open(npy_path).unwrap();
+        let file = std::fs::File::open(npy_path).unwrap();
+        // Safety: the file was just opened read-only by us and isn't
+        // concurrently truncated or rewritten while this context is alive.
+        let mmap = unsafe { Mmap::map(&file).unwrap() };
+        let shape = Self::parse_shape(&mmap);
+        Self::from_bytes(MetadataBytes::Mmap(mmap), shape, snd, id)
+    }
 
-        // Read the data and shape of the `.npy` file
-        let file_data = npyz::NpyFile::new(&mut file).unwrap();
-        let shape_vec = file_data
-            .shape()
-            .iter()
-            .map(|x| *x as usize)
-            .collect::<Vec<usize>>();
+    /// Loads one named array out of a `.npz` archive (a zip of `.npy`
+    /// members) instead of a standalone `.npy` file, e.g. pulling the
+    /// `crd`/`seg`/`ptr` streams for a compressed tensor's fiber metadata
+    /// out of one archive file. `array_name` is the member name with or
+    /// without its `.npy` suffix, matching `numpy.savez`'s convention.
+    pub fn from_npz(
+        npz_path: String,
+        array_name: &str,
+        snd: Sender<Elem<Tile<u64>>>,
+        id: u32,
+    ) -> Self {
+        let file = std::fs::File::open(npz_path).unwrap();
+        let mut archive = zip::ZipArchive::new(file).unwrap();
+        let member_name = if array_name.ends_with(".npy") {
+            array_name.to_string()
+        } else {
+            format!("{array_name}.npy")
+        };
+        let mut member = archive.by_name(&member_name).unwrap();
+        let mut bytes = Vec::with_capacity(member.size() as usize);
+        member.read_to_end(&mut bytes).unwrap();
 
-        let shape: ndarray::Dim<IxDynImpl> = shape_vec.into_dimension();
+        let shape = Self::parse_shape(&bytes);
+        Self::from_bytes(MetadataBytes::Owned(bytes), shape, snd, id)
+    }
 
-        let vec_data: Vec<T> = file_data.into_vec().unwrap();
-        let underlying = ndarray::ArcArray::from_shape_vec(shape, vec_data).unwrap();
+    /// Parses just the `.npy` header out of `bytes` for its shape, without
+    /// reading any element data.
+    fn parse_shape(bytes: &[u8]) -> Vec<usize> {
+        let npy_file = npyz::NpyFile::new(Cursor::new(bytes)).unwrap();
+        npy_file.shape().iter().map(|x| *x as usize).collect()
+    }
 
+    fn from_bytes(
+        bytes: MetadataBytes,
+        shape: Vec<usize>,
+        snd: Sender<Elem<Tile<u64>>>,
+        id: u32,
+    ) -> Self {
         let ctx = Self {
-            underlying,
+            bytes,
+            shape,
             snd,
             id,
             context_info: Default::default(),
+            _phantom: PhantomData,
         };
 
         ctx.snd.attach_sender(&ctx);
@@ -40,106 +102,47 @@ impl<T: npyz::Deserialize + Clone + TryInto<u64> + TryFrom<u64> + Send + Sync> M
         ctx
     }
 
-    fn get_elem_array(&self) -> Vec<Elem<Tile<u64>>> {
-        let mut result = Vec::new();
-        let shape = self.underlying.shape();
-
+    /// The [`Elem`] stop-token level at flat position `i` of an array
+    /// shaped `shape`, or `None` for a bare `Val` -- unchanged from the
+    /// once-eager implementation, since it depends only on where `i` falls
+    /// in the multi-dimensional index space, not on the value stored there.
+    fn stop_token_for(shape: &[usize], i: usize) -> Option<u32> {
         // Handle 1D arrays
         if shape.len() == 1 {
-            for (i, val) in self.underlying.iter().enumerate() {
-                let val_u64 = val
-                    .clone()
-                    .try_into()
-                    .unwrap_or_else(|_| panic!("Error converting T into u64"));
-                if i == shape[0] - 1 {
-                    result.push(Elem::ValStop(
-                        Tile::new(
-                            Array2::from_shape_vec((1, 1), vec![val_u64])
-                                .unwrap()
-                                .to_shared(),
-                            8,
-                            false,
-                        ),
-                        1,
-                    ));
-                } else {
-                    result.push(Elem::Val(Tile::new(
-                        Array2::from_shape_vec((1, 1), vec![val_u64])
-                            .unwrap()
-                            .to_shared(),
-                        8,
-                        false,
-                    )));
-                }
-            }
-            return result;
+            return if i == shape[0] - 1 { Some(1) } else { None };
         }
 
-        // Handle 2D and higher dimensional arrays
-        let total_elements = self.underlying.len();
-        let elements_per_row = shape[1..].iter().product::<usize>();
-        let num_rows = shape[0];
+        // Convert flat index to multi-dimensional indices
+        let mut remaining = i;
+        let mut multi_index = vec![0; shape.len()];
+        for dim in (0..shape.len()).rev() {
+            multi_index[dim] = remaining % shape[dim];
+            remaining /= shape[dim];
+        }
 
-        for (i, val) in self.underlying.iter().enumerate() {
-            // Convert flat index to multi-dimensional indices
-            let mut remaining = i;
-            let mut multi_index = vec![0; shape.len()];
+        // Determine the highest-dimensional stop token needed
+        let mut highest_stop_token: Option<u32> = None;
+        let mut all_inner_dims_at_end = true;
 
-            // Calculate multi-dimensional indices
-            for dim in (0..shape.len()).rev() {
-                multi_index[dim] = remaining % shape[dim];
-                remaining /= shape[dim];
-            }
+        // Check from innermost to outermost
+        for dim in (0..shape.len()).rev() {
+            // If all inner dimensions are at their end, check this dimension
+            if all_inner_dims_at_end {
+                let is_dim_size_one = shape[dim] == 1;
+                let is_last_elem = multi_index[dim] == shape[dim] - 1;
 
-            // Determine the highest-dimensional stop token needed
-            let mut highest_stop_token: Option<u32> = None;
-            let mut all_inner_dims_at_end = true;
-
-            // Check from innermost to outermost
-            for dim in (0..shape.len()).rev() {
-                // If all inner dimensions are at their end, check this dimension
-                if all_inner_dims_at_end {
-                    let is_dim_size_one = shape[dim] == 1;
-                    let is_last_elem = multi_index[dim] == shape[dim] - 1;
-
-                    // If at end or dim size is 1, update the highest stop token
-                    if is_last_elem || is_dim_size_one {
-                        highest_stop_token = Some((shape.len() - dim) as u32);
-                    }
-
-                    // Update tracking for outer dimensions
-                    // Only continue checking outer dimensions if this one is at its last element
-                    all_inner_dims_at_end = is_last_elem;
+                // If at end or dim size is 1, update the highest stop token
+                if is_last_elem || is_dim_size_one {
+                    highest_stop_token = Some((shape.len() - dim) as u32);
                 }
-            }
 
-            let val_u64 = val
-                .clone()
-                .try_into()
-                .unwrap_or_else(|_| panic!("Error converting T into u64"));
-            if let Some(stop_type) = highest_stop_token {
-                result.push(Elem::ValStop(
-                    Tile::new(
-                        Array2::from_shape_vec((1, 1), vec![val_u64])
-                            .unwrap()
-                            .to_shared(),
-                        8,
-                        false,
-                    ),
-                    stop_type,
-                ));
-            } else {
-                result.push(Elem::Val(Tile::new(
-                    Array2::from_shape_vec((1, 1), vec![val_u64])
-                        .unwrap()
-                        .to_shared(),
-                    8,
-                    false,
-                )));
+                // Update tracking for outer dimensions
+                // Only continue checking outer dimensions if this one is at its last element
+                all_inner_dims_at_end = is_last_elem;
             }
         }
 
-        result
+        highest_stop_token
     }
 }
 
@@ -147,14 +150,35 @@ impl<T: npyz::Deserialize + Clone + TryInto<u64> + TryFrom<u64> + Send + Sync> C
     for MetadataGen<T>
 {
     fn run(&mut self) {
-        let elems = self.get_elem_array();
+        let mut cursor = Cursor::new(&self.bytes[..]);
+        let npy_file = npyz::NpyFile::new(&mut cursor).unwrap();
+        let mut reader = npy_file.data::<T>().unwrap();
+
+        let total_elements: usize = self.shape.iter().product();
         let start_time = self.time.tick();
-        for (idx, elem) in enumerate(elems) {
+        for i in 0..total_elements {
+            let val = reader.next().unwrap().unwrap();
+            let val_u64 = val
+                .clone()
+                .try_into()
+                .unwrap_or_else(|_| panic!("Error converting T into u64"));
+            let tile = Tile::new(
+                Array2::from_shape_vec((1, 1), vec![val_u64])
+                    .unwrap()
+                    .to_shared(),
+                8,
+                false,
+            );
+            let elem = match Self::stop_token_for(&self.shape, i) {
+                Some(stop_type) => Elem::ValStop(tile, stop_type),
+                None => Elem::Val(tile),
+            };
+
             self.snd
                 .enqueue(
                     &self.time,
                     ChannelElement {
-                        time: start_time + idx as u64,
+                        time: start_time + i as u64,
                         data: elem,
                     },
                 )
@@ -1,9 +1,12 @@
+pub mod address_mapper;
+pub mod arbiter;
 pub mod dyn_offchip_load;
 pub mod metadata_gen;
 pub mod offchip_load;
 pub mod offchip_store;
 pub mod random_offchip_load;
 pub mod random_offchip_store;
+pub mod store_backend;
 
 /// PMU bandwidth (bytes/cycle)
 pub static PMU_BW: u64 = 64;
@@ -11,8 +14,47 @@ pub static PMU_BW: u64 = 64;
 use crate::primitives::{elem::StopType, tile::Tile};
 use dam::types::DAMType;
 
+/// A regular 2D burst pattern: `rows` rows of `beats_per_row` beats each,
+/// the row starting at `base_addr + row * row_stride` and successive beats
+/// within a row `beat_stride` bytes apart. Mirrors the `d1`/`d2`/`src_stride`
+/// parameters of a 2D memcpy, letting a tile's addresses be carried as five
+/// fields instead of a fully materialized `Vec<u64>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Burst2D {
+    pub base_addr: u64,
+    pub rows: usize,
+    pub beats_per_row: usize,
+    pub beat_stride: u64,
+    pub row_stride: u64,
+}
+
+impl Burst2D {
+    /// Total beats this descriptor expands to.
+    pub fn len(&self) -> usize {
+        self.rows * self.beats_per_row
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Lazily yields every beat address in row-major order, without
+    /// materializing them up front.
+    pub fn expand(self) -> impl Iterator<Item = u64> {
+        (0..self.rows).flat_map(move |r| {
+            (0..self.beats_per_row)
+                .map(move |c| self.base_addr + r as u64 * self.row_stride + c as u64 * self.beat_stride)
+        })
+    }
+}
+
 #[derive(Debug)]
 pub enum HbmAddrEnum<T: DAMType> {
     ADDR(Vec<u64>, Tile<T>),
     ADDRSTOP(Vec<u64>, Tile<T>, StopType),
+    /// A regular-2D-pattern equivalent of `ADDR`/`ADDRSTOP`: same semantics,
+    /// but the beat addresses are expanded lazily from a [`Burst2D`]
+    /// descriptor instead of being carried as an explicit `Vec<u64>`.
+    ADDR2D(Burst2D, Tile<T>),
+    ADDRSTOP2D(Burst2D, Tile<T>, StopType),
 }
@@ -11,10 +11,86 @@ use crate::{
     ramulator::access::MemoryData,
 };
 
-use crate::memory::HbmAddrEnum;
+use crate::memory::{Burst2D, HbmAddrEnum};
 use crate::primitives::tile::Tile;
 use crate::utils::events::LoggableEventSimple;
 
+/// Overlapping-window (im2col) patch-extraction config: when set on
+/// [`OffChipLoad`], the trailing two tiled dimensions stream
+/// `window_row x window_col` spatial patches spaced `step_row`/`step_col`
+/// apart -- `step < window` means adjacent patches re-read shared rows --
+/// instead of tiling into disjoint `tile_row x tile_col` blocks. Lets a
+/// direct-convolution dataflow pull patches straight off the stored
+/// tensor rather than needing a host-side im2col pass.
+///
+/// `out_shape_tiled`'s trailing two entries must already reflect this
+/// windowing, i.e. `floor((h - window_row) / step_row) + 1` and
+/// `floor((w - window_col) / step_col) + 1`.
+#[derive(Debug, Clone, Copy)]
+pub struct Im2ColWindow {
+    pub window_row: usize,
+    pub window_col: usize,
+    pub step_row: usize,
+    pub step_col: usize,
+}
+
+/// A compacted `(shape, stride)` pair produced by [`coalesce_dims`]:
+/// iterating it reproduces the exact same flat-index -> `tile_idx` mapping
+/// as the original `out_shape_tiled`/`stride` pair, just with fewer
+/// dimensions to divide/modulo through per tile.
+struct CoalescedDims {
+    shape: Vec<usize>,
+    stride: Vec<usize>,
+}
+
+/// Walks `out_shape_tiled`/`stride` from innermost (the last entry) to
+/// outermost, fusing dimension `i` into the dimension it's adjacent to
+/// whenever they're "mergeable": either row-major contiguous
+/// (`stride[i] == inner_stride * inner_shape`) or both pure broadcasts
+/// (`stride == 0`, merged into one broadcast dim of the combined size).
+/// Stop-token levels still derive from the uncoalesced `out_shape_tiled`
+/// (see `generate_addr`) -- this pre-pass only speeds up deriving
+/// `tile_idx` from `stride`, which is the actual per-tile cost it's
+/// cutting down on.
+fn coalesce_dims(out_shape_tiled: &[usize], stride: &[usize]) -> CoalescedDims {
+    if out_shape_tiled.is_empty() {
+        return CoalescedDims {
+            shape: vec![],
+            stride: vec![],
+        };
+    }
+
+    // Accumulate from innermost to outermost, merging each outer dim into
+    // whatever's currently the innermost entry; reversed back to the
+    // original outer-to-inner order at the end.
+    let mut shape = vec![out_shape_tiled[out_shape_tiled.len() - 1]];
+    let mut strd = vec![stride[stride.len() - 1]];
+
+    for i in (0..out_shape_tiled.len() - 1).rev() {
+        let inner_shape = *shape.last().unwrap();
+        let inner_stride = *strd.last().unwrap();
+        let mergeable =
+            (stride[i] == 0 && inner_stride == 0) || stride[i] == inner_stride * inner_shape;
+
+        if mergeable {
+            *shape.last_mut().unwrap() = out_shape_tiled[i] * inner_shape;
+            if inner_stride == 0 {
+                *strd.last_mut().unwrap() = 0;
+            }
+        } else {
+            shape.push(out_shape_tiled[i]);
+            strd.push(stride[i]);
+        }
+    }
+
+    shape.reverse();
+    strd.reverse();
+    CoalescedDims {
+        shape,
+        stride: strd,
+    }
+}
+
 #[context_macro]
 pub struct OffChipLoad<E: LoggableEventSimple, T: DAMType> {
     // Tiling configurations
@@ -25,6 +101,24 @@ pub struct OffChipLoad<E: LoggableEventSimple, T: DAMType> {
     pub tile_row: usize,
     pub tile_col: usize,
     pub n_byte: usize, // size of the datatype
+    // When set, each tile is loaded transposed (K-major instead of
+    // N-major, or vice versa) -- useful for matmul operands that need to
+    // be consumed in the opposite layout their producer wrote them in,
+    // without a separate transpose engine.
+    pub transposed: bool,
+    // When set, overrides the trailing-two-dimension tiling with an
+    // overlapping-window (im2col) patch extraction; see [`Im2ColWindow`].
+    pub im2col: Option<Im2ColWindow>,
+    // When set, `tile_row`/`tile_col` need not evenly divide the tensor:
+    // boundary tiles are filled out with this value past the real data's
+    // edge, letting odd-sized tensors (e.g. a GEMM dim that isn't a
+    // multiple of the tile size) be tiled without pre-padding the `.npy`
+    // input. `None` keeps the original exact-division requirement.
+    pub pad_value: Option<T>,
+    // The tensor's real, un-padded shape, recorded when `pad_value` is
+    // set and padding was actually applied, so boundary tiles know how
+    // many of their beats are real data vs. padding.
+    pub valid_shape: Option<Vec<usize>>,
     // HBM Configurations & Addresses
     pub base_addr_byte: u64, // The base address for the given tensor
     pub addr_offset: u64,    // The data received per request
@@ -38,7 +132,7 @@ pub struct OffChipLoad<E: LoggableEventSimple, T: DAMType> {
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: npyz::Deserialize + DAMType,
     > OffChipLoad<E, T>
 where
@@ -52,6 +146,9 @@ where
         tile_row: usize,
         tile_col: usize,
         n_byte: usize,
+        transposed: bool,
+        im2col: Option<Im2ColWindow>,
+        pad_value: Option<T>,
         base_addr_byte: u64,
         addr_offset: u64,
         par_dispatch: usize,
@@ -60,6 +157,8 @@ where
         on_chip_snd: Sender<Elem<Tile<T>>>,
         id: u32,
     ) -> Self {
+        let mut valid_shape = None;
+
         let underlying = match npy_path {
             Some(file_path) => {
                 // Open the file
@@ -78,12 +177,41 @@ where
                 let mut untiled_shape = tensor_shape_tiled[..tensor_shape_tiled.len() - 2].to_vec();
                 untiled_shape.append(&mut vec![total_rows, total_cols]);
 
-                assert_eq!(untiled_shape, shape_vec);
+                let vec_data: Vec<T> = file_data.into_vec().unwrap();
+                let real_shape: ndarray::Dim<IxDynImpl> = shape_vec.clone().into_dimension();
+                let real_arr = ndarray::ArcArray::from_shape_vec(real_shape, vec_data).unwrap();
 
-                let shape: ndarray::Dim<IxDynImpl> = shape_vec.into_dimension();
+                if untiled_shape == shape_vec {
+                    Some(real_arr)
+                } else {
+                    // `pad_value` relaxes the exact-division requirement
+                    // to only require that the tile grid *covers* the
+                    // tensor; any shortfall is filled with `pad_value`
+                    // out to `untiled_shape`.
+                    let pad_v = pad_value
+                        .clone()
+                        .expect("tile_row/tile_col don't evenly divide the tensor; pass a pad_value to allow boundary tiles");
+                    assert_eq!(untiled_shape.len(), shape_vec.len());
+                    assert!(
+                        untiled_shape
+                            .iter()
+                            .zip(shape_vec.iter())
+                            .all(|(u, s)| u >= s),
+                        "tiled extent {untiled_shape:?} must cover the tensor shape {shape_vec:?}"
+                    );
+
+                    let padded_shape: ndarray::Dim<IxDynImpl> = untiled_shape.clone().into_dimension();
+                    let padded = ndarray::ArcArray::from_shape_fn(padded_shape, |idx: IxDyn| {
+                        if (0..idx.ndim()).all(|d| idx[d] < shape_vec[d]) {
+                            real_arr[idx].clone()
+                        } else {
+                            pad_v.clone()
+                        }
+                    });
 
-                let vec_data: Vec<T> = file_data.into_vec().unwrap();
-                Some(ndarray::ArcArray::from_shape_vec(shape, vec_data).unwrap())
+                    valid_shape = Some(shape_vec);
+                    Some(padded)
+                }
             }
             None => None,
         };
@@ -96,6 +224,10 @@ where
             tile_row,
             tile_col,
             n_byte,
+            transposed,
+            im2col,
+            pad_value,
+            valid_shape,
             base_addr_byte,
             addr_offset,
             par_dispatch,
@@ -114,6 +246,14 @@ where
     }
 
     fn generate_addr(&self) -> impl Iterator<Item = HbmAddrEnum<T>> {
+        // `im2col` overrides the trailing-two-dimension window/step with an
+        // overlapping patch extraction; absent, window == step == the
+        // plain `tile_row`/`tile_col` tiling this context always had.
+        let (win_row, win_col, step_row, step_col) = match self.im2col {
+            Some(w) => (w.window_row, w.window_col, w.step_row, w.step_col),
+            None => (self.tile_row, self.tile_col, self.tile_row, self.tile_col),
+        };
+
         let mut tile_data = vec![];
         // Tile the actual data
         match &self.underlying {
@@ -125,23 +265,28 @@ where
                 let mut stride = vec![1; ndim];
 
                 // Set the first two dimensions for tiling
-                window_size[ndim - 2] = self.tile_row;
-                stride[ndim - 2] = self.tile_row;
+                window_size[ndim - 2] = win_row;
+                stride[ndim - 2] = step_row;
 
-                window_size[ndim - 1] = self.tile_col;
-                stride[ndim - 1] = self.tile_col;
+                window_size[ndim - 1] = win_col;
+                stride[ndim - 1] = step_col;
 
                 // Remaining dimensions keep size/stride of 1 (as you suggested)
 
                 for tile_i in arr.windows_with_stride(IxDyn(&window_size), IxDyn(&stride)) {
-                    tile_data.push(Tile::new(
+                    let shaped = if self.transposed {
                         tile_i
+                            .reversed_axes()
                             .to_shared()
-                            .into_shape_with_order((self.tile_row, self.tile_col))
-                            .unwrap(),
-                        self.n_byte,
-                        true,
-                    ))
+                            .into_shape_with_order((win_col, win_row))
+                            .unwrap()
+                    } else {
+                        tile_i
+                            .to_shared()
+                            .into_shape_with_order((win_row, win_col))
+                            .unwrap()
+                    };
+                    tile_data.push(Tile::new(shaped, self.n_byte, true))
                 }
             }
             None => {}
@@ -150,6 +295,27 @@ where
         // Calculate total elements in the output tensor
         let total_tiles: usize = self.out_shape_tiled.iter().product();
 
+        // When transposed, the outer two tiled dimensions are consumed in
+        // swapped order, so the tile-selection stride across
+        // `out_shape_tiled` swaps its last two entries to match.
+        let stride = if self.transposed {
+            let mut swapped = self.stride.clone();
+            let n = swapped.len();
+            if n >= 2 {
+                swapped.swap(n - 2, n - 1);
+            }
+            swapped
+        } else {
+            self.stride.clone()
+        };
+
+        // Fuse adjacent dimensions that iterate contiguously (or are pure
+        // broadcasts) so per-tile `tile_idx` derivation below divides and
+        // modulos through far fewer dimensions than `out_shape_tiled` has.
+        // Stop tokens still need the uncoalesced `multi_index`, computed
+        // separately below, so this only shortcuts the stride dot-product.
+        let coalesced = coalesce_dims(&self.out_shape_tiled, &stride);
+
         // Create a vector to hold all the addresses
         let mut addrs: Vec<HbmAddrEnum<T>> = vec![];
 
@@ -164,10 +330,16 @@ where
                 remaining /= self.out_shape_tiled[i];
             }
 
-            // Calculate the index in the original flat tensor using strides
+            // Calculate the index in the original flat tensor using the
+            // coalesced (shape, stride) pair -- reproduces the same value
+            // as summing `multi_index[dim] * stride[dim]` over every
+            // original dimension, with fewer divisions/modulos.
+            let mut coalesced_remaining = flat_idx;
             let mut tile_idx = 0;
-            for (dim, &idx_in_dim) in multi_index.iter().enumerate() {
-                tile_idx += idx_in_dim * self.stride[dim];
+            for i in (0..coalesced.shape.len()).rev() {
+                let idx_in_dim = coalesced_remaining % coalesced.shape[i];
+                coalesced_remaining /= coalesced.shape[i];
+                tile_idx += idx_in_dim * coalesced.stride[i];
             }
 
             // Ensure we don't go out of bounds of the original tensor
@@ -179,19 +351,79 @@ where
             }
             // println!("tile_idx: {}", tile_idx);
 
-            // Generate addresses to fetch the given tile
+            // Generate addresses to fetch the given tile/patch
             let tile_offset = self.tile_row * self.tile_col * self.n_byte;
-            let base_addr_i = self.base_addr_byte + (tile_idx * tile_offset) as u64;
             let row_offset = self.tensor_shape_tiled.last().unwrap() * self.tile_col * self.n_byte;
 
-            // Generate all addresses for this tile
-            let mut tile_addrs = vec![];
-            for r in 0..self.tile_row {
-                for c in (0..(self.tile_col * self.n_byte)).step_by(self.addr_offset as usize) {
-                    let addr: u64 = base_addr_i + (r * row_offset + c) as u64;
-                    tile_addrs.push(addr);
+            // `im2col` and `pad_value` both need the tile/patch's literal
+            // top-left (row0, col0) position rather than a whole-block
+            // index -- `im2col` because overlapping patches don't tile
+            // into disjoint blocks, `pad_value` because a boundary tile's
+            // valid extent depends on exactly where it starts. Plain,
+            // unpadded tiling keeps the original block-index math.
+            let n = self.out_shape_tiled.len();
+            let row0 = multi_index[n - 2] * step_row;
+            let col0 = multi_index[n - 1] * step_col;
+
+            let base_addr_i = if self.im2col.is_some() || self.pad_value.is_some() {
+                // Any outer (non-spatial) dims still address their
+                // disjoint block via the existing `tile_offset` scheme.
+                let mut outer_tile_idx = 0;
+                for dim in 0..n.saturating_sub(2) {
+                    outer_tile_idx += multi_index[dim] * stride[dim];
                 }
-            }
+                self.base_addr_byte
+                    + (outer_tile_idx * tile_offset) as u64
+                    + row0 as u64 * row_offset as u64
+                    + (col0 * self.n_byte) as u64
+            } else {
+                self.base_addr_byte + (tile_idx * tile_offset) as u64
+            };
+
+            // A boundary tile's in-bounds extent may be smaller than the
+            // full window -- HBM is only ever asked for beats that
+            // correspond to real, on-disk data; the rest of the tile is
+            // served from the padding already baked into `underlying`.
+            let (valid_rows, valid_cols) = match &self.valid_shape {
+                Some(valid) => (
+                    valid[n - 2].saturating_sub(row0).min(win_row),
+                    valid[n - 1].saturating_sub(col0).min(win_col),
+                ),
+                None => (win_row, win_col),
+            };
+
+            // This nested row/beat loop is always a regular 2D pattern, so
+            // carry it as a `Burst2D` descriptor instead of materializing
+            // every beat address up front. In transposed mode the outer
+            // iteration walks columns instead of rows, and the per-element
+            // stride within the tile becomes `row_offset` (successive
+            // elements of a column live a full row apart) rather than
+            // `n_byte` (successive elements of a row are contiguous).
+            let tile_burst = if self.transposed {
+                let beats_per_row = crate::utils::calculation::div_ceil(
+                    (valid_rows * row_offset) as u64,
+                    self.addr_offset,
+                ) as usize;
+                Burst2D {
+                    base_addr: base_addr_i,
+                    rows: valid_cols,
+                    beats_per_row,
+                    beat_stride: self.addr_offset,
+                    row_stride: self.n_byte as u64,
+                }
+            } else {
+                let beats_per_row = crate::utils::calculation::div_ceil(
+                    (valid_cols * self.n_byte) as u64,
+                    self.addr_offset,
+                ) as usize;
+                Burst2D {
+                    base_addr: base_addr_i,
+                    rows: valid_rows,
+                    beats_per_row,
+                    beat_stride: self.addr_offset,
+                    row_stride: row_offset as u64,
+                }
+            };
 
             // Determine the highest-dimensional stop token needed
             let mut highest_stop_token: Option<u32> = None;
@@ -218,42 +450,39 @@ where
             match self.underlying {
                 Some(_) => {
                     // Add the addresses to the result list
-                    if !tile_addrs.is_empty() {
+                    if !tile_burst.is_empty() {
                         if let Some(stop_type) = highest_stop_token {
                             // If there's a stop token, add all addresses except the last one
-                            addrs.push(HbmAddrEnum::ADDRSTOP(
-                                tile_addrs,
+                            addrs.push(HbmAddrEnum::ADDRSTOP2D(
+                                tile_burst,
                                 tile_data[tile_idx].clone(),
                                 stop_type,
                             ));
                         } else {
                             // No stop token, add all addresses normally
-                            addrs.push(HbmAddrEnum::ADDR(tile_addrs, tile_data[tile_idx].clone()));
+                            addrs.push(HbmAddrEnum::ADDR2D(tile_burst, tile_data[tile_idx].clone()));
                         }
                     }
                 }
                 None => {
-                    if !tile_addrs.is_empty() {
+                    let blank_shape = if self.transposed {
+                        vec![win_col, win_row]
+                    } else {
+                        vec![win_row, win_col]
+                    };
+                    if !tile_burst.is_empty() {
                         if let Some(stop_type) = highest_stop_token {
                             // If there's a stop token, add all addresses except the last one
-                            addrs.push(HbmAddrEnum::ADDRSTOP(
-                                tile_addrs,
-                                Tile::new_blank(
-                                    vec![self.tile_row, self.tile_col],
-                                    self.n_byte,
-                                    true,
-                                ),
+                            addrs.push(HbmAddrEnum::ADDRSTOP2D(
+                                tile_burst,
+                                Tile::new_blank(blank_shape, self.n_byte, true),
                                 stop_type,
                             ));
                         } else {
                             // No stop token, add all addresses normally
-                            addrs.push(HbmAddrEnum::ADDR(
-                                tile_addrs,
-                                Tile::new_blank(
-                                    vec![self.tile_row, self.tile_col],
-                                    self.n_byte,
-                                    true,
-                                ),
+                            addrs.push(HbmAddrEnum::ADDR2D(
+                                tile_burst,
+                                Tile::new_blank(blank_shape, self.n_byte, true),
                             ));
                         }
                     }
@@ -265,17 +494,27 @@ where
     }
 
     pub fn on_chip_req_elems(&self) -> usize {
-        self.tile_row * self.tile_col
+        match self.im2col {
+            Some(w) => w.window_row * w.window_col,
+            None => self.tile_row * self.tile_col,
+        }
     }
 
     pub fn loaded_elems(&self) -> usize {
-        let total_tiles: usize = self.out_shape_tiled.iter().product();
-        total_tiles * self.tile_row * self.tile_col
+        // When boundary tiles are padded, only the real (on-disk) data
+        // counts as "loaded" -- the padding never touched HBM.
+        match &self.valid_shape {
+            Some(valid) => valid.iter().product(),
+            None => {
+                let total_tiles: usize = self.out_shape_tiled.iter().product();
+                total_tiles * self.on_chip_req_elems()
+            }
+        }
     }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: npyz::Deserialize + DAMType,
     > Context for OffChipLoad<E, T>
 where
@@ -293,22 +532,46 @@ where
         // println!("Started run of OFFHCIP LOAD");
 
         for addr_enum in self.generate_addr() {
-            let (tile_addrs, elem_tile, is_stop) = match addr_enum {
-                HbmAddrEnum::ADDR(addrs, tile) => (addrs, Elem::Val(tile), false),
+            // Explicit-vector variants still need to be handled for
+            // exhaustiveness, even though this context only ever produces
+            // the `*2D` burst-descriptor variants below; a fallback
+            // producer (e.g. an irregular access pattern) would land here.
+            let (addr_iter, total_beats, elem_tile, is_stop): (
+                Box<dyn Iterator<Item = u64>>,
+                usize,
+                _,
+                _,
+            ) = match addr_enum {
+                HbmAddrEnum::ADDR(addrs, tile) => {
+                    let len = addrs.len();
+                    (Box::new(addrs.into_iter()), len, Elem::Val(tile), false)
+                }
                 HbmAddrEnum::ADDRSTOP(addrs, tile, level) => {
-                    (addrs, Elem::ValStop(tile, level), true)
+                    let len = addrs.len();
+                    (
+                        Box::new(addrs.into_iter()),
+                        len,
+                        Elem::ValStop(tile, level),
+                        true,
+                    )
+                }
+                HbmAddrEnum::ADDR2D(burst, tile) => {
+                    (Box::new(burst.expand()), burst.len(), Elem::Val(tile), false)
                 }
+                HbmAddrEnum::ADDRSTOP2D(burst, tile, level) => (
+                    Box::new(burst.expand()),
+                    burst.len(),
+                    Elem::ValStop(tile, level),
+                    true,
+                ),
             };
 
-            // Send read request to HBM
+            // Send read request to HBM, expanding the descriptor into
+            // `par_dispatch`-sized chunks lazily rather than materializing
+            // the whole tile's address vector up front.
             let send_request_time = self.time.tick();
-            for (idx, addr_chunk) in tile_addrs
-                .iter()
-                .chunks(self.par_dispatch)
-                .into_iter()
-                .enumerate()
-            {
-                let chunk_vec: Vec<u64> = addr_chunk.cloned().collect();
+            for (idx, addr_chunk) in addr_iter.chunks(self.par_dispatch).into_iter().enumerate() {
+                let chunk_vec: Vec<u64> = addr_chunk.collect();
                 self.addr_snd
                     .enqueue(
                         &self.time,
@@ -320,21 +583,20 @@ where
                     .unwrap();
             }
 
-            for _i in tile_addrs {
+            for _i in 0..total_beats {
                 // Wait until you get back the response
                 self.resp_addr_rcv.dequeue(&self.time).unwrap();
             }
 
             let read_finish_time = self.time.tick();
 
-            dam::logging::log_event(&E::new(
+            crate::utils::events::log_event(&E::new(
                 "OffChipLoad".to_string(),
                 self.id,
                 send_request_time.time(),
                 read_finish_time.time(),
                 is_stop,
-            ))
-            .unwrap();
+            ));
 
             // Send the data to on-chip
             // To properly the backpressure under the double buffering setting,
@@ -1,44 +1,160 @@
-use std::{fs::File, marker::PhantomData};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, Write},
+    marker::PhantomData,
+};
 
 use dam::context_tools::*;
 use dam::logging::LogEvent;
-use half::f16;
-use itertools::Itertools;
+use memmap2::MmapMut;
 use ndarray::{concatenate, Array2, Axis};
+use num::Zero;
 
-use crate::{
-    primitives::elem::{Bufferizable, Elem, StopType},
-    ramulator::{access::MemoryData, hbm_context::ParAddrs},
-};
+use crate::primitives::elem::{Bufferizable, Elem, StopType};
 
+use crate::memory::store_backend::{ParAddrBackend, RamulatorBackend, StoreBackend};
+use crate::ramulator::{access::MemoryData, hbm_context::ParAddrs};
 use crate::utils::events::LoggableEventSimple;
 
 use crate::primitives::tile::Tile;
 
+/// Writes a valid `.npy` header (magic, version, and an ASCII header dict
+/// padded so the total length is a multiple of 64 and ends in `\n`) for an
+/// array of `shape` with NumPy dtype descriptor `descr`. Returns the header
+/// so the caller can size the data region that follows it.
+fn npy_header(descr: &str, shape: &[usize]) -> Vec<u8> {
+    let shape_str = if shape.len() == 1 {
+        format!("({},)", shape[0])
+    } else {
+        format!(
+            "({})",
+            shape
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    let dict = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        descr, shape_str
+    );
+
+    // magic(6) + version(2) + header_len(2) = 10 bytes precede the dict.
+    let unpadded_len = 10 + dict.len() + 1; // +1 for trailing '\n'
+    let padded_len = unpadded_len.div_ceil(64) * 64;
+    let pad = padded_len - unpadded_len;
+
+    let mut header = Vec::with_capacity(padded_len);
+    header.extend_from_slice(b"\x93NUMPY");
+    header.extend_from_slice(&[0x01, 0x00]);
+    let dict_len = (dict.len() + pad + 1) as u16;
+    header.extend_from_slice(&dict_len.to_le_bytes());
+    header.extend_from_slice(dict.as_bytes());
+    header.extend(std::iter::repeat(b' ').take(pad));
+    header.push(b'\n');
+    header
+}
+
+/// NumPy dtype descriptor for a little-endian, `elem_bytes`-wide element,
+/// falling back to an opaque byte blob for widths with no matching numeric
+/// dtype.
+fn npy_descr(elem_bytes: usize) -> String {
+    match elem_bytes {
+        2 => "<f2".to_string(),
+        4 => "<f4".to_string(),
+        8 => "<f8".to_string(),
+        n => format!("|V{}", n),
+    }
+}
+
+/// Pre-sizes `path` as a valid `.npy` file for `shape` of `elem_bytes`-wide
+/// elements and returns a writable memory map over just the data region, so
+/// tiles can be written directly to their final offset without ever
+/// materializing the whole tensor in RAM.
+fn mmap_npy_output(path: &str, shape: &[usize], elem_bytes: usize) -> (MmapMut, usize) {
+    let header = npy_header(&npy_descr(elem_bytes), shape);
+    let data_bytes: usize = shape.iter().product::<usize>() * elem_bytes;
+
+    let file = File::create(path).unwrap();
+    file.set_len((header.len() + data_bytes) as u64).unwrap();
+    {
+        let mut header_writer = &file;
+        header_writer.write_all(&header).unwrap();
+    }
+
+    // Safety: the file was just created and sized by us, and is not
+    // concurrently written by any other process.
+    let mmap = unsafe { MmapMut::map_mut(&file).unwrap() };
+    (mmap, header.len())
+}
+
+/// Writes `data` as a `.npy.zst` file: an in-memory `.npy` payload (header
+/// plus raw element bytes) streamed through a buffered zstd encoder at
+/// `level`. Byte-for-byte uncompressed `.npy` output is left untouched by
+/// callers that pass `compress_lvl: None`.
+fn write_npy_zst<T: npyz::AutoSerialize>(path: &str, data: Vec<T>, level: i32) {
+    let file = File::create(path).unwrap();
+    let encoder = zstd::Encoder::new(file, level).expect("failed to open zstd encoder");
+    let mut writer = BufWriter::new(encoder.auto_finish());
+    npyz::to_writer_1d(&mut writer, &data).expect("failed to write compressed npy payload");
+}
+
+/// Reinterprets a row-major strip of `T` as raw little-endian bytes.
+///
+/// Safety: callers must only invoke this with `T` that is `Copy` and whose
+/// in-memory layout matches `bytes_per_elem` (true for the plain numeric
+/// tile element types this simulator uses).
+unsafe fn strip_as_bytes<T: Copy>(data: &[T]) -> &[u8] {
+    std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data))
+}
+
+/// Detiles a `Tile<T>` stream, generates its write addresses, and writes the
+/// reassembled tensor to `store_path` once the stream ends. Actually issuing
+/// a tile's requests and waiting for their acks is delegated to `backend: B`
+/// (see [`StoreBackend`]), so the same context drives both the
+/// parallel-address HBM channel (`B = `[`ParAddrBackend`]) and Ramulator's
+/// split address/data channels (`B = `[`RamulatorBackend`]).
 #[context_macro]
-pub struct OffChipStore<E: LoggableEventSimple, T: DAMType> {
+pub struct OffChipStore<E: LoggableEventSimple, T: DAMType, B> {
     // Tiling configurations
     pub tensor_shape_tiled: Vec<usize>,
     pub tile_row: usize,
     pub tile_col: usize,
     // Data
     pub store_path: Option<String>,
+    /// When set, detiled output is streamed directly into a memory-mapped
+    /// `.npy` file one row-strip at a time instead of accumulating the
+    /// whole tensor in RAM.
+    pub mmap_output: bool,
+    /// When set, the final `.npy` is instead written as a `.npy.zst` file
+    /// through a zstd encoder at this compression level. `None` preserves
+    /// byte-for-byte uncompressed `.npy` output.
+    pub compress_lvl: Option<i32>,
+    /// When set, tiles whose `underlying` data is entirely zero skip their
+    /// write requests (thin provisioning): the tile index is recorded
+    /// instead, and the full run map is written to a `.sparse.json`
+    /// sidecar on termination.
+    pub thin_provision: bool,
     // HBM Configurations & Addresses
     pub base_addr_byte: u64, // The base address for the given tensor
     pub addr_offset: u64,    // The data received per request
-    pub par_dispatch: usize,
+    /// Maximum number of tiles whose write requests may be outstanding
+    /// (dispatched but not yet fully acked) at once. `1` recovers the
+    /// original per-tile ack barrier.
+    pub max_inflight: usize,
     // Sender & Receiver (DAM details)
     pub on_chip_rcv: Receiver<Elem<Tile<T>>>,
-    pub addr_snd: Sender<ParAddrs>,
-    pub ack_rcv: Receiver<u64>,
+    pub backend: B,
     pub id: u32,
     _phantom: PhantomData<E>, // Needed to use the generic parameter E
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType + npyz::AutoSerialize,
-    > OffChipStore<E, T>
+    > OffChipStore<E, T, ParAddrBackend>
 where
     Elem<Tile<T>>: DAMType,
 {
@@ -47,9 +163,12 @@ where
         tile_row: usize,
         tile_col: usize,
         store_path: Option<String>,
+        mmap_output: bool,
+        compress_lvl: Option<i32>,
         base_addr_byte: u64,
         addr_offset: u64,
         par_dispatch: usize,
+        max_inflight: usize,
         on_chip_rcv: Receiver<Elem<Tile<T>>>,
         addr_snd: Sender<ParAddrs>,
         ack_rcv: Receiver<u64>,
@@ -60,237 +179,34 @@ where
             tile_row,
             tile_col,
             store_path,
+            mmap_output,
+            compress_lvl,
+            thin_provision: false,
             base_addr_byte,
             addr_offset,
+            max_inflight: max_inflight.max(1),
             on_chip_rcv,
-            par_dispatch,
-            addr_snd,
-            ack_rcv,
+            backend: ParAddrBackend {
+                par_dispatch,
+                addr_snd,
+                ack_rcv,
+            },
             id,
             context_info: Default::default(),
             _phantom: PhantomData,
         };
         ctx.on_chip_rcv.attach_receiver(&ctx);
-        ctx.addr_snd.attach_sender(&ctx);
-        ctx.ack_rcv.attach_receiver(&ctx);
+        ctx.backend.addr_snd.attach_sender(&ctx);
+        ctx.backend.ack_rcv.attach_receiver(&ctx);
 
         ctx
     }
-
-    pub fn on_chip_req_elems(&self) -> usize {
-        self.tile_row * self.tile_col
-    }
-
-    pub fn stored_elems(&self) -> usize {
-        let total_tiles: usize = self.tensor_shape_tiled.iter().product();
-        total_tiles * self.tile_row * self.tile_col
-    }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType + npyz::AutoSerialize,
-    > Context for OffChipStore<E, T>
-where
-    Elem<Tile<T>>: DAMType,
-{
-    fn run(&mut self) {
-        let mut accum: Array2<T> = Array2::from_shape_vec(
-            (0, self.tensor_shape_tiled.last().unwrap() * self.tile_col),
-            vec![],
-        )
-        .unwrap();
-        let mut horizontal_accum: Array2<T> =
-            Array2::from_shape_vec((self.tile_row, 0), vec![]).unwrap();
-
-        let mut tile_idx = 0;
-        let mut n_bytes = None;
-        loop {
-            // Get the tile data and concatenate if you're simulating with actual values
-            let tile_data = match self.on_chip_rcv.peek_next(&self.time) {
-                Ok(ChannelElement {
-                    time: _,
-                    data: tile,
-                }) => match tile {
-                    Elem::Val(tile_data) => {
-                        if self.store_path.is_some() {
-                            assert!(tile_data.underlying.is_some());
-
-                            let concatenated = concatenate(
-                                Axis(1),
-                                &[
-                                    horizontal_accum.view(),
-                                    tile_data.underlying.clone().unwrap().view(),
-                                ],
-                            )
-                            .unwrap_or_else(|_| panic!("Error concatenating tiles horizontally"));
-                            horizontal_accum = concatenated;
-                        }
-                        tile_data
-                    }
-                    Elem::ValStop(tile_data, s) => {
-                        if self.store_path.is_some() {
-                            assert!(tile_data.underlying.is_some());
-
-                            let concatenated_horizontal = concatenate(
-                                Axis(1),
-                                &[
-                                    horizontal_accum.view(),
-                                    tile_data.underlying.clone().unwrap().view(),
-                                ],
-                            )
-                            .unwrap_or_else(|_| panic!("Error concatenating tiles horizontally"));
-                            horizontal_accum = concatenated_horizontal;
-
-                            let concatenated =
-                                concatenate(Axis(0), &[accum.view(), horizontal_accum.view()])
-                                    .unwrap_or_else(|_| {
-                                        panic!("Error concatenating tiles horizontally")
-                                    });
-                            accum = concatenated;
-
-                            horizontal_accum =
-                                Array2::from_shape_vec((self.tile_row, 0), vec![]).unwrap();
-                        }
-                        tile_data
-                    }
-                },
-                Err(_) => {
-                    if self.store_path.is_some() {
-                        // Save the collected so far and return
-
-                        // Check whether the collected data is same as expected
-                        assert_eq!(
-                            accum.len(),
-                            self.tensor_shape_tiled.iter().product::<usize>()
-                                * self.tile_row
-                                * self.tile_col
-                        );
-                        let data: Vec<T> = accum.into_raw_vec_and_offset().0;
-
-                        // Save data in .npy
-                        let data_file_path = format!("{}.npy", self.store_path.clone().unwrap());
-                        match npyz::to_file_1d(data_file_path, data) {
-                            Ok(_) => {}
-                            Err(_) => panic!(
-                                "Error while writing data to {}",
-                                format!("{}.npy", self.store_path.clone().unwrap())
-                            ),
-                        }
-
-                        // save metadata as json file
-                        let total_cols = self.tile_col * self.tensor_shape_tiled.last().unwrap();
-                        let total_rows = self.tile_row
-                            * self.tensor_shape_tiled[self.tensor_shape_tiled.len() - 2];
-                        let mut shape =
-                            self.tensor_shape_tiled[..self.tensor_shape_tiled.len() - 2].to_vec();
-                        shape.append(&mut vec![total_rows, total_cols]);
-
-                        let meta_file_path: String =
-                            format!("{}.json", self.store_path.clone().unwrap());
-                        let meta_file = File::create(meta_file_path.clone()).unwrap();
-                        match serde_json::to_writer(meta_file, &shape) {
-                            Ok(_) => {}
-                            Err(_) => panic!("Error while writing metadata to {}", meta_file_path),
-                        }
-
-                        println!(
-                            "Successfully wrote the output to {}",
-                            self.store_path.clone().unwrap()
-                        );
-                    }
-                    return;
-                }
-            };
-
-            assert_eq!(tile_data.shape[0], self.tile_row);
-            assert_eq!(tile_data.shape[1], self.tile_col);
-
-            // Calculate the write addresses for the given tile
-            if n_bytes == None {
-                n_bytes = Some(tile_data.bytes_per_elem);
-            } else {
-                assert_eq!(n_bytes.unwrap(), tile_data.bytes_per_elem);
-            }
-
-            let tile_offset = tile_data.size_in_bytes();
-            let base_addr_i = self.base_addr_byte + (tile_idx * tile_offset) as u64;
-            let row_offset =
-                self.tensor_shape_tiled.last().unwrap() * self.tile_col * n_bytes.unwrap();
-
-            let mut tile_addrs = vec![];
-            for r in 0..self.tile_row {
-                for c in (0..(self.tile_col * n_bytes.unwrap())).step_by(self.addr_offset as usize)
-                {
-                    let addr: u64 = base_addr_i + (r * row_offset + c) as u64;
-                    tile_addrs.push(addr);
-                }
-            }
-
-            tile_idx += 1;
-
-            // Send write request to HBM
-            let send_request_time = self.time.tick();
-            for (idx, addr_chunk) in tile_addrs
-                .iter()
-                .chunks(self.par_dispatch)
-                .into_iter()
-                .enumerate()
-            {
-                let chunk_vec: Vec<u64> = addr_chunk.cloned().collect();
-                self.addr_snd
-                    .enqueue(
-                        &self.time,
-                        ChannelElement {
-                            time: send_request_time + idx as u64,
-                            data: ParAddrs::new(chunk_vec),
-                        },
-                    )
-                    .unwrap();
-            }
-
-            // Wait until you get back the response
-            for _i in tile_addrs {
-                self.ack_rcv.dequeue(&self.time).unwrap();
-            }
-
-            let read_finish_time = self.time.tick();
-
-            dam::logging::log_event(&E::new(
-                "OffChipStore".to_string(),
-                self.id,
-                send_request_time.time(),
-                read_finish_time.time(),
-                false,
-            ))
-            .unwrap();
-
-            // dequeue
-            self.on_chip_rcv.dequeue(&self.time).unwrap();
-        }
-    }
-}
-
-#[context_macro]
-pub struct OffChipStoreRamulator<E: LoggableEventSimple, T: DAMType> {
-    pub tensor_shape_tiled: Vec<usize>,
-    pub tile_row: usize,
-    pub tile_col: usize,
-    pub store_path: Option<String>,
-    pub base_addr_byte: u64, // The base address for the given tensor
-    pub addr_offset: u64,    // The data received per request
-    pub on_chip_rcv: Receiver<Elem<Tile<T>>>,
-    pub addr_snd: Sender<u64>,
-    pub wdata_snd: Sender<MemoryData>,
-    pub ack_rcv: Receiver<bool>,
-    pub id: u32,
-    _phantom: PhantomData<E>, // Needed to use the generic parameter E
-}
-
-impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
-        T: DAMType + npyz::AutoSerialize,
-    > OffChipStoreRamulator<E, T>
+    > OffChipStore<E, T, RamulatorBackend>
 where
     Elem<Tile<T>>: DAMType,
 {
@@ -299,8 +215,12 @@ where
         tile_row: usize,
         tile_col: usize,
         store_path: Option<String>,
+        mmap_output: bool,
+        compress_lvl: Option<i32>,
+        thin_provision: bool,
         base_addr_byte: u64,
         addr_offset: u64,
+        max_inflight: usize,
         on_chip_rcv: Receiver<Elem<Tile<T>>>,
         addr_snd: Sender<u64>,
         wdata_snd: Sender<MemoryData>,
@@ -312,28 +232,42 @@ where
             tile_row,
             tile_col,
             store_path,
+            mmap_output,
+            compress_lvl,
+            thin_provision,
             base_addr_byte,
             addr_offset,
+            max_inflight: max_inflight.max(1),
             on_chip_rcv,
-            addr_snd,
-            wdata_snd,
-            ack_rcv,
+            backend: RamulatorBackend {
+                addr_snd,
+                wdata_snd,
+                ack_rcv,
+            },
             id,
             context_info: Default::default(),
             _phantom: PhantomData,
         };
         ctx.on_chip_rcv.attach_receiver(&ctx);
-        ctx.addr_snd.attach_sender(&ctx);
-        ctx.wdata_snd.attach_sender(&ctx);
-        ctx.ack_rcv.attach_receiver(&ctx);
+        ctx.backend.addr_snd.attach_sender(&ctx);
+        ctx.backend.wdata_snd.attach_sender(&ctx);
+        ctx.backend.ack_rcv.attach_receiver(&ctx);
 
         ctx
     }
+}
 
+/// Convenience alias for the Ramulator-backed flavor of [`OffChipStore`].
+pub type OffChipStoreRamulator<E, T> = OffChipStore<E, T, RamulatorBackend>;
+
+impl<E: LoggableEventSimple, T: DAMType, B> OffChipStore<E, T, B> {
     pub fn on_chip_req_elems(&self) -> usize {
         self.tile_row * self.tile_col
     }
 
+    /// Total logical elements backing this tensor, independent of how many
+    /// were actually written: thin-provisioned (all-zero) tiles still count
+    /// here even though their writes are elided.
     pub fn stored_elems(&self) -> usize {
         let total_tiles: usize = self.tensor_shape_tiled.iter().product();
         total_tiles * self.tile_row * self.tile_col
@@ -341,9 +275,10 @@ where
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
-        T: DAMType + npyz::AutoSerialize,
-    > Context for OffChipStoreRamulator<E, T>
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        T: DAMType + npyz::AutoSerialize + Copy + num::Zero,
+        B: StoreBackend<T>,
+    > Context for OffChipStore<E, T, B>
 where
     Elem<Tile<T>>: DAMType,
 {
@@ -356,6 +291,31 @@ where
         let mut horizontal_accum: Array2<T> =
             Array2::from_shape_vec((self.tile_row, 0), vec![]).unwrap();
 
+        // tile_idx -> fill value, for tiles elided under thin provisioning.
+        let mut sparse_runs: Vec<(usize, f64)> = vec![];
+
+        // FIFO of (send_tick, remaining_acks) for tiles whose write requests
+        // have been dispatched but not yet fully acked.
+        let mut inflight: VecDeque<(u64, usize)> = VecDeque::new();
+
+        // In streaming mode, pre-size and map the output file up front so
+        // each completed strip can be written to its final offset without
+        // ever holding the full detiled tensor in memory.
+        let mut mmap_state: Option<(MmapMut, usize)> = None;
+        let mut strip_idx = 0usize;
+        if self.mmap_output && self.store_path.is_some() {
+            let total_cols = self.tile_col * self.tensor_shape_tiled.last().unwrap();
+            let total_rows =
+                self.tile_row * self.tensor_shape_tiled[self.tensor_shape_tiled.len() - 2];
+            let mut shape = self.tensor_shape_tiled[..self.tensor_shape_tiled.len() - 2].to_vec();
+            shape.append(&mut vec![total_rows, total_cols]);
+
+            let data_file_path = format!("{}.npy", self.store_path.clone().unwrap());
+            let (mmap, header_len) =
+                mmap_npy_output(&data_file_path, &shape, std::mem::size_of::<T>());
+            mmap_state = Some((mmap, header_len));
+        }
+
         let mut tile_idx = 0;
         let mut n_bytes = None;
         loop {
@@ -395,12 +355,26 @@ where
                             .unwrap_or_else(|_| panic!("Error concatenating tiles horizontally"));
                             horizontal_accum = concatenated_horizontal;
 
-                            let concatenated =
-                                concatenate(Axis(0), &[accum.view(), horizontal_accum.view()])
-                                    .unwrap_or_else(|_| {
-                                        panic!("Error concatenating tiles horizontally")
-                                    });
-                            accum = concatenated;
+                            if let Some((mmap, header_len)) = mmap_state.as_mut() {
+                                // Write this completed strip straight to its
+                                // final offset in the mapped output file.
+                                let strip_data: Vec<T> =
+                                    horizontal_accum.clone().into_raw_vec_and_offset().0;
+                                let strip_bytes = strip_data.len() * std::mem::size_of::<T>();
+                                let offset = *header_len + strip_idx * strip_bytes;
+                                // Safety: `strip_data` is a plain Copy numeric
+                                // buffer sized to `strip_bytes`.
+                                let bytes = unsafe { strip_as_bytes(&strip_data) };
+                                mmap[offset..offset + strip_bytes].copy_from_slice(bytes);
+                                strip_idx += 1;
+                            } else {
+                                let concatenated =
+                                    concatenate(Axis(0), &[accum.view(), horizontal_accum.view()])
+                                        .unwrap_or_else(|_| {
+                                            panic!("Error concatenating tiles horizontally")
+                                        });
+                                accum = concatenated;
+                            }
 
                             horizontal_accum =
                                 Array2::from_shape_vec((self.tile_row, 0), vec![]).unwrap();
@@ -409,25 +383,21 @@ where
                     }
                 },
                 Err(_) => {
-                    if self.store_path.is_some() {
-                        // Save the collected so far and return
-
-                        // Check whether the collected data is same as expected
-                        assert_eq!(
-                            accum.len(),
-                            self.tensor_shape_tiled.iter().product::<usize>()
-                                * self.tile_row
-                                * self.tile_col
-                        );
-                        let data: Vec<T> = accum.into_raw_vec_and_offset().0;
-
-                        // Save data in .npy
-                        let data_file_path = format!("output.npy");
-                        match npyz::to_file_1d(self.store_path.clone().unwrap(), data) {
-                            Ok(_) => {}
-                            Err(_) => panic!("Error while writing data to {}", data_file_path),
-                        }
+                    // Drain any tiles whose acks were still outstanding
+                    // under the inflight cap.
+                    while let Some((send_tick, remaining_acks)) = inflight.pop_front() {
+                        self.backend.await_acks(&self.time, remaining_acks);
+                        let read_finish_time = self.time.tick();
+                        crate::utils::events::log_event(&E::new(
+                            "OffChipStore".to_string(),
+                            self.id,
+                            send_tick,
+                            read_finish_time.time(),
+                            false,
+                        ));
+                    }
 
+                    if self.store_path.is_some() {
                         // save metadata as json file
                         let total_cols = self.tile_col * self.tensor_shape_tiled.last().unwrap();
                         let total_rows = self.tile_row
@@ -436,14 +406,57 @@ where
                             self.tensor_shape_tiled[..self.tensor_shape_tiled.len() - 2].to_vec();
                         shape.append(&mut vec![total_rows, total_cols]);
 
-                        let meta_file_path: String = format!("output.json");
+                        if let Some((mmap, _)) = mmap_state.as_mut() {
+                            mmap.flush().unwrap();
+                        } else {
+                            // Check whether the collected data is same as expected
+                            assert_eq!(
+                                accum.len(),
+                                self.tensor_shape_tiled.iter().product::<usize>()
+                                    * self.tile_row
+                                    * self.tile_col
+                            );
+                            let data: Vec<T> = accum.into_raw_vec_and_offset().0;
+
+                            // Save data, compressed if requested.
+                            if let Some(level) = self.compress_lvl {
+                                let data_file_path =
+                                    format!("{}.npy.zst", self.store_path.clone().unwrap());
+                                write_npy_zst(&data_file_path, data, level);
+                            } else {
+                                let data_file_path =
+                                    format!("{}.npy", self.store_path.clone().unwrap());
+                                match npyz::to_file_1d(data_file_path, data) {
+                                    Ok(_) => {}
+                                    Err(_) => panic!(
+                                        "Error while writing data to {}",
+                                        format!("{}.npy", self.store_path.clone().unwrap())
+                                    ),
+                                }
+                            }
+                        }
+
+                        let meta_file_path: String =
+                            format!("{}.json", self.store_path.clone().unwrap());
                         let meta_file = File::create(meta_file_path.clone()).unwrap();
                         match serde_json::to_writer(meta_file, &shape) {
                             Ok(_) => {}
                             Err(_) => panic!("Error while writing metadata to {}", meta_file_path),
                         }
 
-                        println!("Successfully wrote the output");
+                        if self.thin_provision && !sparse_runs.is_empty() {
+                            let sparse_file_path =
+                                format!("{}.sparse.json", self.store_path.clone().unwrap());
+                            let sparse_file = File::create(sparse_file_path.clone()).unwrap();
+                            serde_json::to_writer(sparse_file, &sparse_runs).unwrap_or_else(
+                                |_| panic!("Error while writing sparse map to {}", sparse_file_path),
+                            );
+                        }
+
+                        println!(
+                            "Successfully wrote the output to {}",
+                            self.store_path.clone().unwrap()
+                        );
                     }
                     return;
                 }
@@ -459,6 +472,20 @@ where
                 assert_eq!(n_bytes.unwrap(), tile_data.bytes_per_elem);
             }
 
+            // Thin provisioning: elide requests entirely for all-zero tiles.
+            let is_all_zero = self.thin_provision
+                && tile_data
+                    .underlying
+                    .as_ref()
+                    .map(|arr| arr.iter().all(|v| v.is_zero()))
+                    .unwrap_or(false);
+            if is_all_zero {
+                sparse_runs.push((tile_idx, 0.0));
+                tile_idx += 1;
+                self.on_chip_rcv.dequeue(&self.time).unwrap();
+                continue;
+            }
+
             let tile_offset = tile_data.size_in_bytes();
             let base_addr_i = self.base_addr_byte + (tile_idx * tile_offset) as u64;
             let row_offset =
@@ -475,46 +502,30 @@ where
 
             tile_idx += 1;
 
-            // Send write request to HBM
+            // Send write request to memory
             let send_request_time = self.time.tick();
-            for (idx, addr) in tile_addrs.iter().enumerate() {
-                self.addr_snd
-                    .enqueue(
-                        &self.time,
-                        ChannelElement {
-                            time: send_request_time + idx as u64,
-                            data: *addr,
-                        },
-                    )
-                    .unwrap();
-
-                self.wdata_snd
-                    .enqueue(
-                        &self.time,
-                        ChannelElement {
-                            time: send_request_time + idx as u64,
-                            data: MemoryData::F16([f16::from_f32(0.0); 32]),
-                        },
-                    )
-                    .unwrap();
-            }
-
-            // Wait until you get back the response
-            for _i in tile_addrs {
-                self.ack_rcv.dequeue(&self.time).unwrap();
+            let n_acks = self
+                .backend
+                .dispatch(&self.time, send_request_time.time(), &tile_addrs, &tile_data);
+
+            // Keep this tile's requests outstanding rather than blocking on
+            // every ack immediately; only drain the oldest pending tile once
+            // `max_inflight` tiles' worth of requests are in flight at once.
+            inflight.push_back((send_request_time.time(), n_acks));
+            while inflight.len() >= self.max_inflight {
+                let (send_tick, remaining_acks) = inflight.pop_front().unwrap();
+                self.backend.await_acks(&self.time, remaining_acks);
+                let read_finish_time = self.time.tick();
+
+                crate::utils::events::log_event(&E::new(
+                    "OffChipStore".to_string(),
+                    self.id,
+                    send_tick,
+                    read_finish_time.time(),
+                    false,
+                ));
             }
 
-            let read_finish_time = self.time.tick();
-
-            dam::logging::log_event(&E::new(
-                "OffChipStore".to_string(),
-                self.id,
-                send_request_time.time(),
-                read_finish_time.time(),
-                false,
-            ))
-            .unwrap();
-
             // dequeue
             self.on_chip_rcv.dequeue(&self.time).unwrap();
         }
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::marker::PhantomData;
 
 use dam::context_tools::*;
@@ -5,13 +6,76 @@ use dam::logging::LogEvent;
 use itertools::Itertools;
 use ndarray::{IntoDimension, IxDyn, IxDynImpl};
 
-use crate::primitives::elem::Elem;
+use crate::memory::address_mapper::{AddressMapper, TileGeom};
+use crate::primitives::elem::{Elem, StopType};
 use crate::primitives::tile::Tile;
 use crate::ramulator::hbm_context::ParAddrs;
+use crate::utils::debug_probe::{DebugAction, DebugProbe};
 use crate::utils::events::LoggableEventSimple;
 
+/// An MSHR-style entry for a tile whose address requests have been
+/// dispatched but not yet fully acked.
+struct Inflight<T> {
+    tile_idx: u64,
+    issue_time: u64,
+    expected_responses: usize,
+    received: usize,
+    tile_data: Tile<T>,
+    stop_level: Option<StopType>,
+}
+
+/// Running count of how many raw per-word tile addresses
+/// [`RandomOffChipLoad`]'s burst coalescing has collapsed into HBM burst
+/// requests, so callers can read off the coalescing ratio after a run.
+#[derive(Clone, Debug, Default)]
+pub struct CoalesceStats {
+    pub raw_addrs: u64,
+    pub burst_addrs: u64,
+}
+
+impl CoalesceStats {
+    /// Raw-to-burst ratio; `1.0` means coalescing never collapsed anything.
+    pub fn ratio(&self) -> f64 {
+        if self.burst_addrs == 0 {
+            1.0
+        } else {
+            self.raw_addrs as f64 / self.burst_addrs as f64
+        }
+    }
+}
+
+/// Sorts `addrs` and greedily folds runs where `next == prev + addr_offset`
+/// into burst descriptors -- represented by the burst's base address -- of
+/// at most `burst_bytes` each, so a tile whose columns are physically
+/// contiguous issues one wide request instead of many single-word ones.
+fn coalesce_addresses(mut addrs: Vec<u64>, addr_offset: u64, burst_bytes: u64) -> Vec<u64> {
+    if addrs.is_empty() {
+        return addrs;
+    }
+    addrs.sort_unstable();
+
+    let max_run_words = (burst_bytes / addr_offset).max(1);
+    let mut bursts = Vec::new();
+    let mut run_start = addrs[0];
+    let mut run_len: u64 = 1;
+
+    for &addr in &addrs[1..] {
+        let expected_next = run_start + run_len * addr_offset;
+        if addr == expected_next && run_len < max_run_words {
+            run_len += 1;
+        } else {
+            bursts.push(run_start);
+            run_start = addr;
+            run_len = 1;
+        }
+    }
+    bursts.push(run_start);
+
+    bursts
+}
+
 #[context_macro]
-pub struct RandomOffChipLoad<E: LoggableEventSimple, T: DAMType> {
+pub struct RandomOffChipLoad<E: LoggableEventSimple, T: DAMType, M> {
     // Tiling configurations
     pub tensor_shape_tiled: Vec<usize>, // In terms of tiles.
     pub underlying: Option<ndarray::ArcArray<T, IxDyn>>,
@@ -24,6 +88,26 @@ pub struct RandomOffChipLoad<E: LoggableEventSimple, T: DAMType> {
     pub base_addr_byte: u64, // The base address for the given tensor
     pub addr_offset: u64,    // The data received per request
     pub par_dispatch: usize,
+    /// How a tile's `(row, col_byte)` offset is turned into a DRAM address;
+    /// defaults to [`crate::memory::address_mapper::LinearMapper`]'s plain
+    /// row-major layout, but can model channel/bank interleaving or
+    /// swizzling instead.
+    pub mapper: M,
+    /// Maximum number of tiles whose address requests may be outstanding
+    /// (dispatched but not yet fully acked) at once. `None` recovers the
+    /// original fully-serialized behavior (wait for one tile's responses
+    /// before issuing the next).
+    pub max_outstanding: Option<usize>,
+    /// Maximum size, in bytes, of a coalesced HBM burst request. `None`
+    /// defaults to `addr_offset`, which reproduces the original one-word-
+    /// per-request behavior exactly (a burst of `addr_offset` bytes holds a
+    /// single word, so no run is ever folded).
+    pub burst_bytes: Option<u64>,
+    pub coalesce_stats: CoalesceStats,
+    /// Consulted once per tile (and once per response) when set; lets a
+    /// user watch or break on specific tiles/addresses. See
+    /// [`crate::utils::debug_probe`].
+    pub debug_probe: Option<Box<dyn DebugProbe>>,
     // Channels facing HBM memory
     pub addr_snd: Sender<ParAddrs>,
     pub resp_addr_rcv: Receiver<u64>,
@@ -36,12 +120,14 @@ pub struct RandomOffChipLoad<E: LoggableEventSimple, T: DAMType> {
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: npyz::Deserialize + DAMType,
-    > RandomOffChipLoad<E, T>
+        M: AddressMapper,
+    > RandomOffChipLoad<E, T, M>
 where
     Elem<Tile<T>>: DAMType,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         tensor_shape_tiled: Vec<usize>,
         npy_path: Option<String>,
@@ -51,6 +137,10 @@ where
         base_addr_byte: u64,
         addr_offset: u64,
         par_dispatch: usize,
+        mapper: M,
+        max_outstanding: Option<usize>,
+        burst_bytes: Option<u64>,
+        debug_probe: Option<Box<dyn DebugProbe>>,
         addr_snd: Sender<ParAddrs>,
         resp_addr_rcv: Receiver<u64>,
         raddr: Receiver<Elem<Tile<u64>>>,
@@ -132,6 +222,11 @@ where
             base_addr_byte,
             addr_offset,
             par_dispatch,
+            mapper,
+            max_outstanding,
+            burst_bytes,
+            coalesce_stats: CoalesceStats::default(),
+            debug_probe,
             addr_snd,
             resp_addr_rcv,
             raddr,
@@ -148,23 +243,38 @@ where
         ctx
     }
 
-    /// Generate addresses for a specific tile index
-    fn generate_tile_addresses(&self, tile_idx: u64) -> Vec<u64> {
+    /// Generate addresses for a specific tile index, coalescing contiguous
+    /// runs into HBM burst requests per `burst_bytes`.
+    fn generate_tile_addresses(&mut self, tile_idx: u64) -> Vec<u64> {
         // Calculate the base address for this tile
         let tile_offset = self.tile_row * self.tile_col * self.n_byte;
         let base_addr_i = self.base_addr_byte + (tile_idx * tile_offset as u64);
-        let row_offset = self.tensor_shape_tiled.last().unwrap() * self.tile_col * self.n_byte;
+        let row_offset =
+            (self.tensor_shape_tiled.last().unwrap() * self.tile_col * self.n_byte) as u64;
+        let cfg = TileGeom {
+            tile_row: self.tile_row,
+            tile_col: self.tile_col,
+            n_byte: self.n_byte,
+            row_offset,
+        };
 
         // Generate all addresses for this tile
         let mut tile_addrs = vec![];
         for r in 0..self.tile_row {
             for c in (0..(self.tile_col * self.n_byte)).step_by(self.addr_offset as usize) {
-                let addr: u64 = base_addr_i + (r * row_offset + c) as u64;
+                let addr = self.mapper.map(base_addr_i, r, c, tile_idx, &cfg);
                 tile_addrs.push(addr);
             }
         }
 
-        tile_addrs
+        let burst_bytes = self.burst_bytes.unwrap_or(self.addr_offset);
+        let raw_count = tile_addrs.len() as u64;
+        let bursts = coalesce_addresses(tile_addrs, self.addr_offset, burst_bytes);
+
+        self.coalesce_stats.raw_addrs += raw_count;
+        self.coalesce_stats.burst_addrs += bursts.len() as u64;
+
+        bursts
     }
 
     /// Create tile data for a specific tile index
@@ -186,134 +296,170 @@ where
             }
         }
     }
+
+    /// Waits for an in-flight entry's remaining acks, logs its event using
+    /// `issue_time..now`, and enqueues its tile onto `rdata`.
+    fn retire(&mut self, mut entry: Inflight<T>) {
+        for _ in entry.received..entry.expected_responses {
+            let resp = self.resp_addr_rcv.dequeue(&self.time).unwrap();
+            if let Some(probe) = self.debug_probe.as_mut() {
+                probe.on_response(self.id, entry.tile_idx, resp.data, self.time.tick().time());
+            }
+        }
+        entry.received = entry.expected_responses;
+
+        let read_finish_time = self.time.tick();
+        crate::utils::events::log_event(&E::new(
+            "RandomOffChipLoad".to_string(),
+            self.id,
+            entry.issue_time,
+            read_finish_time.time(),
+            entry.stop_level.is_some(),
+        ));
+
+        let data = match entry.stop_level {
+            Some(stop_level) => Elem::ValStop(entry.tile_data, stop_level),
+            None => Elem::Val(entry.tile_data),
+        };
+        self.rdata
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data,
+                },
+            )
+            .unwrap();
+    }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: npyz::Deserialize + DAMType,
-    > Context for RandomOffChipLoad<E, T>
+        M: AddressMapper,
+    > Context for RandomOffChipLoad<E, T, M>
 where
     Elem<Tile<T>>: DAMType,
 {
     fn run(&mut self) {
+        // Tiles retire strictly in `raddr` order (the front of this queue),
+        // even though responses for several in-flight tiles interleave on
+        // the single `resp_addr_rcv` channel.
+        let mut inflight: VecDeque<Inflight<T>> = VecDeque::new();
+        let max_outstanding = self.max_outstanding.unwrap_or(1).max(1);
+
         // Process requests from raddr until we get a stop signal
         while let Ok(addr_elem) = self.raddr.dequeue(&self.time) {
-            match addr_elem.data {
-                Elem::Val(addr_tile) => {
-                    // Generate addresses for the requested tile
-                    let tile_arr = addr_tile.underlying.as_ref().unwrap();
-                    let tile_idx = tile_arr[[0, 0]];
-                    let tile_addrs = self.generate_tile_addresses(tile_idx);
-
-                    // Send read request to HBM
-                    let send_request_time = self.time.tick();
-                    for (idx, addr_chunk) in tile_addrs
-                        .iter()
-                        .chunks(self.par_dispatch)
-                        .into_iter()
-                        .enumerate()
-                    {
-                        let chunk_vec: Vec<u64> = addr_chunk.cloned().collect();
-                        self.addr_snd
-                            .enqueue(
-                                &self.time,
-                                ChannelElement {
-                                    time: send_request_time + idx as u64,
-                                    data: ParAddrs::new(chunk_vec),
-                                },
-                            )
-                            .unwrap();
-                    }
-
-                    // Wait for all responses
-                    for _i in &tile_addrs {
-                        self.resp_addr_rcv.dequeue(&self.time).unwrap();
-                    }
-
-                    let read_finish_time = self.time.tick();
-
-                    // Log the event
-                    dam::logging::log_event(&E::new(
-                        "RandomOffChipLoad".to_string(),
-                        self.id,
-                        send_request_time.time(),
-                        read_finish_time.time(),
-                        false,
-                    ))
-                    .unwrap();
-
-                    // Create the tile data
-                    let tile_data = self.create_tile_data(tile_idx);
-
-                    // Send the tile data
-                    self.rdata
-                        .enqueue(
-                            &self.time,
-                            ChannelElement {
-                                time: self.time.tick(),
-                                data: Elem::Val(tile_data),
-                            },
-                        )
-                        .unwrap();
+            let (addr_tile, stop_level) = match addr_elem.data {
+                Elem::Val(addr_tile) => (addr_tile, None),
+                Elem::ValStop(addr_tile, stop_level) => (addr_tile, Some(stop_level)),
+            };
+
+            // A stop element must flush every prior in-flight entry before
+            // it propagates, so the stop marker really is the last thing
+            // `rdata` sees.
+            if stop_level.is_some() {
+                while let Some(entry) = inflight.pop_front() {
+                    self.retire(entry);
                 }
-                Elem::ValStop(addr_tile, stop_level) => {
-                    // Generate addresses for the requested tile
-                    let tile_arr = addr_tile.underlying.as_ref().unwrap();
-                    let tile_idx: u64 = tile_arr[[0, 0]];
-                    let tile_addrs = self.generate_tile_addresses(tile_idx);
-
-                    // Send read request to HBM
-                    let send_request_time = self.time.tick();
-                    for (idx, addr_chunk) in tile_addrs
-                        .iter()
-                        .chunks(self.par_dispatch)
-                        .into_iter()
-                        .enumerate()
-                    {
-                        let chunk_vec: Vec<u64> = addr_chunk.cloned().collect();
-                        self.addr_snd
-                            .enqueue(
-                                &self.time,
-                                ChannelElement {
-                                    time: send_request_time + idx as u64,
-                                    data: ParAddrs::new(chunk_vec),
-                                },
-                            )
-                            .unwrap();
-                    }
-
-                    // Wait for all responses
-                    for _i in &tile_addrs {
-                        self.resp_addr_rcv.dequeue(&self.time).unwrap();
-                    }
-
-                    let read_finish_time = self.time.tick();
-
-                    // Log the event
-                    dam::logging::log_event(&E::new(
-                        "RandomOffChipLoad".to_string(),
+            }
+
+            // Generate addresses for the requested tile
+            let tile_arr = addr_tile.underlying.as_ref().unwrap();
+            let tile_idx = tile_arr[[0, 0]];
+            let tile_addrs = self.generate_tile_addresses(tile_idx);
+
+            if let Some(probe) = self.debug_probe.as_mut() {
+                match probe.on_request(self.id, tile_idx, &tile_addrs) {
+                    DebugAction::Continue | DebugAction::TraceOnly => {}
+                    DebugAction::BreakAt { tile_idx } => panic!(
+                        "RandomOffChipLoad {}: breakpoint at tile_idx={tile_idx}, addrs={:?}, {} tile(s) already in flight",
                         self.id,
-                        send_request_time.time(),
-                        read_finish_time.time(),
-                        true,
-                    ))
+                        tile_addrs,
+                        inflight.len()
+                    ),
+                }
+            }
+
+            // Send read request to HBM
+            let send_request_time = self.time.tick();
+            for (idx, addr_chunk) in tile_addrs
+                .iter()
+                .chunks(self.par_dispatch)
+                .into_iter()
+                .enumerate()
+            {
+                let chunk_vec: Vec<u64> = addr_chunk.cloned().collect();
+                self.addr_snd
+                    .enqueue(
+                        &self.time,
+                        ChannelElement {
+                            time: send_request_time + idx as u64,
+                            data: ParAddrs::new(chunk_vec),
+                        },
+                    )
                     .unwrap();
+            }
 
-                    // Create the tile data
-                    let tile_data = self.create_tile_data(tile_idx);
-
-                    // Send the tile data with stop signal
-                    self.rdata
-                        .enqueue(
-                            &self.time,
-                            ChannelElement {
-                                time: self.time.tick(),
-                                data: Elem::ValStop(tile_data, stop_level),
-                            },
-                        )
-                        .unwrap();
-                }
+            inflight.push_back(Inflight {
+                tile_idx,
+                issue_time: send_request_time.time(),
+                expected_responses: tile_addrs.len(),
+                received: 0,
+                tile_data: self.create_tile_data(tile_idx),
+                stop_level,
+            });
+
+            // Keep issuing without blocking until `max_outstanding` tiles'
+            // worth of requests are in flight at once.
+            while inflight.len() >= max_outstanding {
+                let entry = inflight.pop_front().unwrap();
+                self.retire(entry);
             }
         }
+
+        // Drain whatever is still outstanding once `raddr` itself ends.
+        while let Some(entry) = inflight.pop_front() {
+            self.retire(entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod coalesce_tests {
+    use super::{coalesce_addresses, CoalesceStats};
+
+    #[test]
+    fn default_burst_equal_to_offset_never_coalesces() {
+        let addrs = vec![0, 4, 8, 12];
+        assert_eq!(coalesce_addresses(addrs, 4, 4), vec![0, 4, 8, 12]);
+    }
+
+    #[test]
+    fn contiguous_run_folds_into_one_burst() {
+        let addrs = vec![0, 4, 8, 12];
+        assert_eq!(coalesce_addresses(addrs, 4, 16), vec![0]);
+    }
+
+    #[test]
+    fn run_splits_once_burst_bytes_is_exceeded() {
+        let addrs = vec![0, 4, 8, 12, 16, 20];
+        assert_eq!(coalesce_addresses(addrs, 4, 16), vec![0, 16]);
+    }
+
+    #[test]
+    fn non_contiguous_addresses_stay_separate() {
+        let addrs = vec![0, 8, 16];
+        assert_eq!(coalesce_addresses(addrs, 4, 16), vec![0, 8, 16]);
+    }
+
+    #[test]
+    fn ratio_reflects_collapsed_accesses() {
+        let stats = CoalesceStats {
+            raw_addrs: 8,
+            burst_addrs: 2,
+        };
+        assert_eq!(stats.ratio(), 4.0);
+        assert_eq!(CoalesceStats::default().ratio(), 1.0);
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::fs::File;
 use std::marker::PhantomData;
 
@@ -11,10 +12,82 @@ use crate::primitives::tile::Tile;
 use crate::ramulator::hbm_context::ParAddrs;
 use crate::utils::events::LoggableEventSimple;
 
+/// Tracks which tiles [`RandomOffChipStore::run`] has written, for
+/// `verify_writes`'s close-time report: which tiles are aliased (written
+/// more than once without `allow_overwrite`), which tile addresses fell
+/// outside the tensor, and which tiles were never written at all.
+#[derive(Default)]
+struct WriteCoverageTracker {
+    touched_tiles: Vec<bool>,
+    aliasing_writes: Vec<u64>,
+    out_of_bounds_writes: Vec<u64>,
+}
+
+impl WriteCoverageTracker {
+    fn new(total_tiles: usize) -> Self {
+        Self {
+            touched_tiles: vec![false; total_tiles],
+            ..Default::default()
+        }
+    }
+
+    fn record(&mut self, waddr: u64, allow_overwrite: bool) {
+        let tile_idx = waddr as usize;
+        let Some(touched) = self.touched_tiles.get_mut(tile_idx) else {
+            self.out_of_bounds_writes.push(waddr);
+            return;
+        };
+        if *touched && !allow_overwrite {
+            self.aliasing_writes.push(waddr);
+        }
+        *touched = true;
+    }
+
+    fn into_report(self) -> WriteVerificationReport {
+        let untouched_tiles = self
+            .touched_tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, touched)| !**touched)
+            .map(|(idx, _)| idx as u64)
+            .collect();
+        WriteVerificationReport {
+            untouched_tiles,
+            aliasing_writes: self.aliasing_writes,
+            out_of_bounds_writes: self.out_of_bounds_writes,
+        }
+    }
+}
+
+/// Write-coverage summary saved alongside `RandomOffChipStore`'s `.npy`
+/// output when `verify_writes` is set -- see [`WriteCoverageTracker`].
+#[derive(serde::Serialize)]
+struct WriteVerificationReport {
+    untouched_tiles: Vec<u64>,
+    aliasing_writes: Vec<u64>,
+    out_of_bounds_writes: Vec<u64>,
+}
+
+/// `.json` metadata saved alongside `RandomOffChipStore`'s `.npy` output:
+/// the untiled tensor shape, plus an optional write-coverage report.
+#[derive(serde::Serialize)]
+struct StoreMetadata {
+    shape: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    write_verification: Option<WriteVerificationReport>,
+}
+
 #[context_macro]
 pub struct RandomOffChipStore<E: LoggableEventSimple, T: DAMType> {
     // Tiling configurations
-    pub tensor_shape_tiled: Vec<usize>, // In terms of tiles.
+    /// Tile-grid shape, in terms of tiles. Must have rank >= 2; the last
+    /// two entries are the tile-row and tile-column grid counts (each
+    /// `Tile` is always a 2D `tile_row x tile_col` block -- see
+    /// [`crate::primitives::tile::Tile`]), and any leading entries are
+    /// un-tiled batch axes a single tile spans exactly one position of, so
+    /// e.g. a convolution activation's `(batch, tile_rows, tile_cols)`
+    /// grid is supported alongside the original 2D case.
+    pub tensor_shape_tiled: Vec<usize>,
     pub npy_path: Option<String>,
     pub underlying: Option<ndarray::ArcArray<T, IxDyn>>,
     pub tile_row: usize,
@@ -24,6 +97,25 @@ pub struct RandomOffChipStore<E: LoggableEventSimple, T: DAMType> {
     pub base_addr_byte: u64, // The base address for the given tensor
     pub addr_offset: u64,    // The data received per request
     pub par_dispatch: usize,
+    /// Maximum number of tiles whose write requests may be outstanding
+    /// (dispatched but not yet fully acked) at once -- see
+    /// [`crate::memory::offchip_store::OffChipStore::max_inflight`], whose
+    /// FIFO-draining scheme this mirrors. `1` recovers the original
+    /// per-tile ack barrier. This is the credit: `run` only blocks waiting
+    /// on the oldest tile's acks once `max_inflight` tiles' requests are
+    /// already in flight, so the next tile's addresses can otherwise be
+    /// dispatched (and `update_underlying` applied) immediately, letting
+    /// later tiles' HBM latency overlap earlier tiles still being acked.
+    pub max_inflight: usize,
+    /// When set, `run` tracks which tiles are written (and how many times)
+    /// via a [`WriteCoverageTracker`], and its close path folds the result
+    /// into the saved `.json` metadata as a `write_verification` report.
+    /// Off by default, since the tracking has a (small) per-write cost.
+    pub verify_writes: bool,
+    /// With `verify_writes` on, suppresses flagging a tile as aliased when
+    /// it's written more than once (out-of-bounds and untouched-tile
+    /// reporting still happen). Ignored while `verify_writes` is off.
+    pub allow_overwrite: bool,
     // Channels facing HBM memory
     pub addr_snd: Sender<ParAddrs>,
     pub ack_rcv: Receiver<u64>,
@@ -39,7 +131,7 @@ pub struct RandomOffChipStore<E: LoggableEventSimple, T: DAMType> {
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType + npyz::Deserialize + npyz::AutoSerialize,
     > RandomOffChipStore<E, T>
 where
@@ -54,6 +146,9 @@ where
         base_addr_byte: u64,
         addr_offset: u64,
         par_dispatch: usize,
+        max_inflight: usize,
+        verify_writes: bool,
+        allow_overwrite: bool,
         // HBM context facing the channels
         addr_snd: Sender<ParAddrs>,
         ack_rcv: Receiver<u64>,
@@ -91,10 +186,10 @@ where
             }
             None => None,
         };
-        assert_eq!(
-            tensor_shape_tiled.len(),
-            2,
-            "Only 2D tensors are supported for now in RandomOffChipStore"
+        assert!(
+            tensor_shape_tiled.len() >= 2,
+            "RandomOffChipStore needs at least 2 dimensions (a tile_row x tile_col grid), got {}",
+            tensor_shape_tiled.len()
         );
 
         let ctx = Self {
@@ -107,6 +202,9 @@ where
             base_addr_byte,
             addr_offset,
             par_dispatch,
+            max_inflight: max_inflight.max(1),
+            verify_writes,
+            allow_overwrite,
             addr_snd,
             ack_rcv,
             waddr,
@@ -125,7 +223,11 @@ where
         ctx
     }
 
-    fn send_write_request(&mut self, waddr: u64, wdata: &Tile<T>) {
+    /// Dispatches `wdata`'s write requests without waiting for their acks,
+    /// returning the tick they were sent at and how many acks to expect --
+    /// see [`Self::drain_oldest_inflight`], which the caller pushes this
+    /// onto an `inflight` FIFO for.
+    fn send_write_request(&mut self, waddr: u64, wdata: &Tile<T>) -> (u64, usize) {
         // Calculate the write addresses for the given tile
         let n_bytes = wdata.bytes_per_elem;
 
@@ -161,21 +263,52 @@ where
                 .unwrap();
         }
 
-        // Wait until you get back the response
-        for _i in tile_addrs {
+        (send_request_time.time(), tile_addrs.len())
+    }
+
+    /// Awaits the oldest in-flight tile's remaining acks and forwards its
+    /// `wack` element downstream. With `max_inflight == 1` this runs right
+    /// after every `send_write_request`, recovering the original
+    /// fully-synchronous behavior; with a higher `max_inflight`, several
+    /// tiles' requests can be outstanding at once and this only blocks once
+    /// the FIFO is at the cap (or the stream ends).
+    fn drain_oldest_inflight(&mut self, inflight: &mut VecDeque<(u64, usize, Elem<bool>)>) {
+        let Some((send_request_time, remaining_acks, wack_elem)) = inflight.pop_front() else {
+            return;
+        };
+
+        for _ in 0..remaining_acks {
             self.ack_rcv.dequeue(&self.time).unwrap();
         }
 
         let read_finish_time = self.time.tick();
-
-        dam::logging::log_event(&E::new(
+        // Per-write events already go through a buffered, bulk-flushed sink
+        // rather than one `log_event` call per write: `log_event` forwards
+        // to `crate::proto_driver::log_sink`, which accumulates records
+        // in-memory (size set by `SimConfig::log_buffer_size`) and flushes
+        // in batches, attaching a `wall_clock_us` timestamp alongside this
+        // simulated `send_request_time`/`read_finish_time` pair when
+        // `SimConfig::log_wall_clock_timestamps` is set. It's flushed
+        // unconditionally once the whole simulation graph finishes running
+        // (`parse_proto`'s `log_sink::uninstall()`), so no record is lost
+        // regardless of which context's `run` returns first.
+        crate::utils::events::log_event(&E::new(
             "RandomOffChipStore".to_string(),
             self.id,
-            send_request_time.time(),
+            send_request_time,
             read_finish_time.time(),
             false,
-        ))
-        .unwrap();
+        ));
+
+        self.wack
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data: wack_elem,
+                },
+            )
+            .unwrap();
     }
 
     fn update_underlying(&mut self, waddr: u64, wdata: Tile<T>) {
@@ -183,18 +316,32 @@ where
             Some(tensor) => {
                 assert!(wdata.underlying.is_some());
 
-                // Calculate the tile index in the tensor
-                let tile_idx = waddr as usize;
-
-                // Calculate the total number of tiles in each dimension
-                let total_tiles_col = self.tensor_shape_tiled.last().unwrap();
-                let total_tiles_row = self.tensor_shape_tiled[self.tensor_shape_tiled.len() - 2];
+                let rank = self.tensor_shape_tiled.len();
+
+                // Unravel the flat tile index into a per-dimension tile
+                // coordinate (row-major over the full `tensor_shape_tiled`
+                // grid) -- the same scheme
+                // `DynOffChipLoad::stream_tiles` uses for its flat-index
+                // to multi-index conversion.
+                let mut tile_coord = vec![0usize; rank];
+                let mut remaining = waddr as usize;
+                for i in (0..rank).rev() {
+                    tile_coord[i] = remaining % self.tensor_shape_tiled[i];
+                    remaining /= self.tensor_shape_tiled[i];
+                }
 
-                // Calculate the tile position in the 2D grid of tiles
-                let tile_row_idx = tile_idx / total_tiles_col;
-                let tile_col_idx = tile_idx % total_tiles_col;
+                // Every dimension but the last two selects a single
+                // position -- batch-style axes a `Tile` (always a 2D
+                // block) can't itself span -- so index into each in turn,
+                // leaving a 2D view over this tile's own grid cell.
+                let mut tensor_view = tensor.view_mut();
+                for &coord in &tile_coord[..rank - 2] {
+                    tensor_view = tensor_view.index_axis_move(ndarray::Axis(0), coord);
+                }
 
-                // Calculate the starting position in the underlying tensor
+                // Calculate the starting position in the tile's grid cell
+                let tile_row_idx = tile_coord[rank - 2];
+                let tile_col_idx = tile_coord[rank - 1];
                 let start_row = tile_row_idx * self.tile_row;
                 let start_col = tile_col_idx * self.tile_col;
 
@@ -202,7 +349,7 @@ where
                 let tile_data = wdata.underlying.as_ref().unwrap();
 
                 // Update the corresponding region in the underlying tensor
-                let mut tile_slice = tensor.slice_mut(ndarray::s![
+                let mut tile_slice = tensor_view.slice_mut(ndarray::s![
                     start_row..start_row + self.tile_row,
                     start_col..start_col + self.tile_col
                 ]);
@@ -216,13 +363,22 @@ where
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType + npyz::Deserialize + npyz::AutoSerialize,
     > Context for RandomOffChipStore<E, T>
 where
     Elem<Tile<T>>: DAMType,
 {
     fn run(&mut self) {
+        // FIFO of (send_tick, remaining_acks, wack element) for tiles whose
+        // write requests have been dispatched but not yet fully acked and
+        // forwarded -- see [`Self::drain_oldest_inflight`].
+        let mut inflight: VecDeque<(u64, usize, Elem<bool>)> = VecDeque::new();
+        let mut coverage = self.verify_writes.then(|| {
+            let total_tiles: usize = self.tensor_shape_tiled.iter().product();
+            WriteCoverageTracker::new(total_tiles)
+        });
+
         loop {
             let peek_waddr = self.waddr.peek_next(&self.time);
             let peek_wdata = self.wdata.peek_next(&self.time);
@@ -238,103 +394,70 @@ where
                         data: wdata,
                     }),
                 ) => {
-                    match (waddr_tile, wdata) {
+                    let (waddr, wdata, wack_elem) = match (waddr_tile, wdata) {
                         (Elem::Val(waddr_tile), Elem::Val(wdata)) => {
                             let waddr = waddr_tile.underlying.as_ref().unwrap()[[0, 0]];
-                            // Send write request to HBM
-                            self.send_write_request(waddr, &wdata);
-
-                            // Update the tensor if underlying is not None
-                            self.update_underlying(waddr, wdata);
-
-                            self.wack
-                                .enqueue(
-                                    &self.time,
-                                    ChannelElement {
-                                        time: self.time.tick(),
-                                        data: Elem::Val(true),
-                                    },
-                                )
-                                .unwrap();
+                            (waddr, wdata, Elem::Val(true))
                         }
                         (
                             Elem::ValStop(waddr_tile, waddr_stop),
                             Elem::ValStop(wdata, wdata_stop),
                         ) => {
                             let waddr = waddr_tile.underlying.as_ref().unwrap()[[0, 0]];
-                            // Send write request to HBM
-                            self.send_write_request(waddr, &wdata);
-
-                            // Update the tensor if underlying is not None
-                            self.update_underlying(waddr, wdata);
-
                             let stop_level = if self.ack_based_on_waddr {
                                 waddr_stop
                             } else {
                                 wdata_stop
                             };
-
-                            self.wack
-                                .enqueue(
-                                    &self.time,
-                                    ChannelElement {
-                                        time: self.time.tick(),
-                                        data: Elem::ValStop(true, stop_level),
-                                    },
-                                )
-                                .unwrap();
+                            (waddr, wdata, Elem::ValStop(true, stop_level))
                         }
                         (Elem::Val(waddr_tile), Elem::ValStop(wdata, wdata_stop)) => {
                             let waddr = waddr_tile.underlying.as_ref().unwrap()[[0, 0]];
-                            // Send write request to HBM
-                            self.send_write_request(waddr, &wdata);
-
-                            // Update the tensor if underlying is not None
-                            self.update_underlying(waddr, wdata);
-
                             let out_elem = if self.ack_based_on_waddr {
                                 Elem::Val(true)
                             } else {
                                 Elem::ValStop(true, wdata_stop)
                             };
-
-                            self.wack
-                                .enqueue(
-                                    &self.time,
-                                    ChannelElement {
-                                        time: self.time.tick(),
-                                        data: out_elem,
-                                    },
-                                )
-                                .unwrap();
+                            (waddr, wdata, out_elem)
                         }
                         (Elem::ValStop(waddr_tile, waddr_stop), Elem::Val(wdata)) => {
                             let waddr = waddr_tile.underlying.as_ref().unwrap()[[0, 0]];
-                            // Send write request to HBM
-                            self.send_write_request(waddr, &wdata);
-
-                            // Update the tensor if underlying is not None
-                            self.update_underlying(waddr, wdata);
-
                             let out_elem = if self.ack_based_on_waddr {
                                 Elem::ValStop(true, waddr_stop)
                             } else {
                                 Elem::Val(true)
                             };
-
-                            self.wack
-                                .enqueue(
-                                    &self.time,
-                                    ChannelElement {
-                                        time: self.time.tick(),
-                                        data: out_elem,
-                                    },
-                                )
-                                .unwrap();
+                            (waddr, wdata, out_elem)
                         }
+                    };
+
+                    // Update the tensor if underlying is not None. This is
+                    // bookkeeping on the functional value, not a timed HBM
+                    // effect, so it happens immediately regardless of
+                    // `max_inflight`.
+                    self.update_underlying(waddr, wdata.clone());
+
+                    if let Some(tracker) = coverage.as_mut() {
+                        tracker.record(waddr, self.allow_overwrite);
+                    }
+
+                    // Dispatch the write request and keep it outstanding
+                    // rather than blocking on its acks immediately; only
+                    // drain the oldest pending tile once `max_inflight`
+                    // tiles' worth of requests are in flight at once.
+                    let (send_tick, remaining_acks) = self.send_write_request(waddr, &wdata);
+                    inflight.push_back((send_tick, remaining_acks, wack_elem));
+                    while inflight.len() >= self.max_inflight {
+                        self.drain_oldest_inflight(&mut inflight);
                     }
                 }
                 (Err(_), Err(_)) => {
+                    // Drain any tiles whose acks were still outstanding
+                    // under the inflight cap.
+                    while !inflight.is_empty() {
+                        self.drain_oldest_inflight(&mut inflight);
+                    }
+
                     if self.npy_path.is_some() {
                         // Save data in .npy
                         let data_file_path = format!("{}.npy", self.npy_path.clone().unwrap());
@@ -362,10 +485,15 @@ where
                             self.tensor_shape_tiled[..self.tensor_shape_tiled.len() - 2].to_vec();
                         shape.append(&mut vec![total_rows, total_cols]);
 
+                        let metadata = StoreMetadata {
+                            shape,
+                            write_verification: coverage.take().map(WriteCoverageTracker::into_report),
+                        };
+
                         let meta_file_path: String =
                             format!("{}.json", self.npy_path.clone().unwrap());
                         let meta_file = File::create(meta_file_path.clone()).unwrap();
-                        match serde_json::to_writer(meta_file, &shape) {
+                        match serde_json::to_writer(meta_file, &metadata) {
                             Ok(_) => {}
                             Err(_) => panic!("Error while writing metadata to {}", meta_file_path),
                         }
@@ -0,0 +1,125 @@
+use dam::{
+    channel::{ChannelElement, Receiver, Sender},
+    structures::TimeManager,
+};
+use half::f16;
+use itertools::Itertools;
+
+use crate::{
+    primitives::tile::Tile,
+    ramulator::{access::MemoryData, hbm_context::ParAddrs},
+};
+
+/// Issues a tile's write requests to memory and waits for their
+/// acknowledgements. `OffChipStore` drives the shared detiling,
+/// accumulation, and output-writing loop against this trait, so the same
+/// context works whether requests go out over the parallel-address HBM
+/// channel or Ramulator's split address/data channels -- a new backend (a
+/// DRAMsim-style model, or a trace-only backend that just logs requests)
+/// only needs to implement these two methods.
+pub trait StoreBackend<T> {
+    /// Sends `tile_addrs` (and `tile_data`, for backends that need the
+    /// payload) as write requests tagged with `send_time`. Returns the
+    /// number of acks `await_acks` must later drain for this tile.
+    fn dispatch(
+        &mut self,
+        time: &TimeManager,
+        send_time: u64,
+        tile_addrs: &[u64],
+        tile_data: &Tile<T>,
+    ) -> usize;
+
+    /// Blocks until `n` pending acks have arrived.
+    fn await_acks(&mut self, time: &TimeManager, n: usize);
+}
+
+/// Backend over the parallel-address HBM channel: a tile's addresses are
+/// chunked into `par_dispatch`-sized [`ParAddrs`] batches and acked one per
+/// address.
+pub struct ParAddrBackend {
+    pub par_dispatch: usize,
+    pub addr_snd: Sender<ParAddrs>,
+    pub ack_rcv: Receiver<u64>,
+}
+
+impl<T> StoreBackend<T> for ParAddrBackend {
+    fn dispatch(
+        &mut self,
+        time: &TimeManager,
+        send_time: u64,
+        tile_addrs: &[u64],
+        _tile_data: &Tile<T>,
+    ) -> usize {
+        for (idx, addr_chunk) in tile_addrs
+            .iter()
+            .chunks(self.par_dispatch)
+            .into_iter()
+            .enumerate()
+        {
+            let chunk_vec: Vec<u64> = addr_chunk.cloned().collect();
+            self.addr_snd
+                .enqueue(
+                    time,
+                    ChannelElement {
+                        time: send_time + idx as u64,
+                        data: ParAddrs::new(chunk_vec),
+                    },
+                )
+                .unwrap();
+        }
+        tile_addrs.len()
+    }
+
+    fn await_acks(&mut self, time: &TimeManager, n: usize) {
+        for _ in 0..n {
+            self.ack_rcv.dequeue(time).unwrap();
+        }
+    }
+}
+
+/// Backend over Ramulator's split address/data channels: one address and
+/// one write-data beat go out per element, acked individually.
+pub struct RamulatorBackend {
+    pub addr_snd: Sender<u64>,
+    pub wdata_snd: Sender<MemoryData>,
+    pub ack_rcv: Receiver<bool>,
+}
+
+impl<T> StoreBackend<T> for RamulatorBackend {
+    fn dispatch(
+        &mut self,
+        time: &TimeManager,
+        send_time: u64,
+        tile_addrs: &[u64],
+        _tile_data: &Tile<T>,
+    ) -> usize {
+        for (idx, addr) in tile_addrs.iter().enumerate() {
+            self.addr_snd
+                .enqueue(
+                    time,
+                    ChannelElement {
+                        time: send_time + idx as u64,
+                        data: *addr,
+                    },
+                )
+                .unwrap();
+
+            self.wdata_snd
+                .enqueue(
+                    time,
+                    ChannelElement {
+                        time: send_time + idx as u64,
+                        data: MemoryData::F16([f16::from_f32(0.0); 32]),
+                    },
+                )
+                .unwrap();
+        }
+        tile_addrs.len()
+    }
+
+    fn await_acks(&mut self, time: &TimeManager, n: usize) {
+        for _ in 0..n {
+            self.ack_rcv.dequeue(time).unwrap();
+        }
+    }
+}
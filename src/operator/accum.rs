@@ -1,5 +1,6 @@
 use std::{marker::PhantomData, sync::Arc};
 
+use crate::memory::arbiter::MemoryArbiterRequest;
 use crate::memory::PMU_BW;
 use crate::primitives::elem::{Bufferizable, Elem, StopType};
 use crate::primitives::tile::Tile;
@@ -10,6 +11,12 @@ use dam::{context_tools::*, logging::LogEvent};
 pub struct AccumConfig {
     pub compute_bw: u64,
     pub write_back_mu: bool,
+    /// Which [`crate::memory::arbiter::MemoryArbiter`] bank this
+    /// instance's PMU traffic counts against, when an arbiter is attached
+    /// via [`Accum::with_memory_arbiter`] -- unused (and harmless to leave
+    /// at its default) otherwise. Mirrors how `config_dict` maps operation
+    /// ids onto overrides elsewhere in `SimConfig`.
+    pub memory_unit_id: u32,
 }
 
 #[context_macro]
@@ -20,12 +27,18 @@ pub struct Accum<E, T: DAMType, OT: DAMType> {
     init_accum: Arc<dyn Fn() -> Tile<OT> + Sync + Send>,
     rank: StopType,
     config: AccumConfig,
+    /// When attached via [`Self::with_memory_arbiter`], every load/store
+    /// byte-transfer is arbitrated against a shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] bank instead of assuming
+    /// this instance owns the full `PMU_BW` in isolation.
+    arbiter_req_snd: Option<Sender<MemoryArbiterRequest>>,
+    arbiter_resp_rcv: Option<Receiver<u64>>,
     id: u32,
     _phantom: PhantomData<E>,
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType,
         OT: DAMType,
     > Accum<E, T, OT>
@@ -49,6 +62,8 @@ where
             init_accum,
             rank,
             config,
+            arbiter_req_snd: None,
+            arbiter_resp_rcv: None,
             id,
             context_info: Default::default(),
             _phantom: PhantomData,
@@ -58,9 +73,54 @@ where
         ctx
     }
 
+    /// Routes this instance's load/store byte-transfers through a shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] on bank
+    /// `config.memory_unit_id`, instead of assuming it always gets the
+    /// full `PMU_BW` to itself.
+    pub fn with_memory_arbiter(
+        mut self,
+        req_snd: Sender<MemoryArbiterRequest>,
+        resp_rcv: Receiver<u64>,
+    ) -> Self {
+        req_snd.attach_sender(&self);
+        resp_rcv.attach_receiver(&self);
+        self.arbiter_req_snd = Some(req_snd);
+        self.arbiter_resp_rcv = Some(resp_rcv);
+        self
+    }
+
+    /// Cycles to transfer `bytes`: arbitrated against the shared bank if
+    /// [`Self::with_memory_arbiter`] was used, else the unconditional
+    /// `div_ceil(bytes, PMU_BW)` every instance assumed before.
+    fn transfer_cycles(&mut self, bytes: u64) -> u64 {
+        match (&self.arbiter_req_snd, &self.arbiter_resp_rcv) {
+            (Some(req_snd), Some(resp_rcv)) => {
+                let requested_at = self.time.tick().time();
+                req_snd
+                    .enqueue(
+                        &self.time,
+                        ChannelElement {
+                            time: self.time.tick(),
+                            data: MemoryArbiterRequest {
+                                unit_id: self.config.memory_unit_id,
+                                bytes,
+                                requested_at,
+                            },
+                        },
+                    )
+                    .unwrap();
+                match resp_rcv.dequeue(&self.time) {
+                    Ok(ChannelElement { data, .. }) => data,
+                    Err(_) => panic!("Accum: memory arbiter closed its response channel"),
+                }
+            }
+            _ => div_ceil(bytes, PMU_BW),
+        }
+    }
+
     fn process_accum(&mut self, data: Tile<T>, accumulator: &mut Tile<OT>) {
         let load_cycles = if data.read_from_mu {
-            div_ceil(data.size_in_bytes() as u64, PMU_BW)
+            self.transfer_cycles(data.size_in_bytes() as u64)
         } else {
             0
         };
@@ -83,7 +143,7 @@ where
 
     fn process_accum_init(&mut self, data: Tile<T>, accumulator: &mut Tile<OT>) -> Tile<OT> {
         let load_cycles = if data.read_from_mu {
-            div_ceil(data.size_in_bytes() as u64, PMU_BW)
+            self.transfer_cycles(data.size_in_bytes() as u64)
         } else {
             0
         };
@@ -97,7 +157,7 @@ where
         *accumulator = (self.init_accum)();
 
         let store_cycles = if self.config.write_back_mu {
-            div_ceil(accumulator.size_in_bytes() as u64, PMU_BW)
+            self.transfer_cycles(accumulator.size_in_bytes() as u64)
         } else {
             0
         };
@@ -111,21 +171,20 @@ where
         self.in_stream.dequeue(&self.time).unwrap();
 
         // Logging
-        dam::logging::log_event(&E::new(
+        crate::utils::events::log_event(&E::new(
             "Accum".to_string(),
             self.id,
             self.time.tick().time() - roofline_cycles,
             self.time.tick().time(),
             true,
-        ))
-        .unwrap();
+        ));
 
         out_tile
     }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType,
         OT: DAMType,
     > Context for Accum<E, T, OT>
@@ -276,6 +335,7 @@ mod tests {
             AccumConfig {
                 compute_bw: 1000, // FLOPs per cycle
                 write_back_mu: true,
+                memory_unit_id: 0,
             },
             0, // id
         ));
@@ -369,6 +429,7 @@ mod tests {
             AccumConfig {
                 compute_bw: 1000, // FLOPs per cycle (Currently unused)
                 write_back_mu: true,
+                memory_unit_id: 0,
             },
             0, // id
         ));
@@ -0,0 +1,279 @@
+use dam::context_tools::*;
+
+use crate::primitives::elem::{Elem, StopType};
+
+/// An in-memory Compressed Sparse Fiber (CSF) tensor: for an N-rank tensor,
+/// `indptr`/`indices` each hold N levels -- `indptr[level][node]..indptr[level][node
+/// + 1]` is the range of `node`'s children in `indices[level]` (and, one level
+/// deeper, in `indptr[level + 1]`/`values`). This is exactly the fibertree
+/// this crate's `Elem<T>` stop-token streams walk, laid out the way
+/// [`crate::primitives::tile::CsrData`] lays out a single sparse row/col level.
+///
+/// [`ArrowCSFReader`]/[`ArrowCSFWriter`] convert between this and the
+/// `Elem<T>` stream; [`crate::utils::arrow_ipc::csf_tensor_to_ipc`]/
+/// `csf_tensor_from_ipc` round-trip a `CsfTensor<f32>` through Arrow's IPC
+/// record-batch format the same way [`crate::utils::arrow_ipc::tile_to_ipc`]
+/// does for a `Tile<f32>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CsfTensor<T> {
+    pub indptr: Vec<Vec<usize>>,
+    pub indices: Vec<Vec<usize>>,
+    pub values: Vec<T>,
+}
+
+impl<T: StaticallySized> StaticallySized for CsfTensor<T> {
+    const SIZE: usize = unimplemented!();
+    // As with `Buffer<T>`, a CSF tensor's size depends on how sparse it is,
+    // which isn't known at compile time.
+}
+
+impl<T: Clone> CsfTensor<T> {
+    /// Depth-first walk of the fiber tree (root implicit at level 0, leaves
+    /// in `values`), emitting `Elem::Val` for an ordinary leaf and
+    /// `Elem::ValStop(_, k)` wherever `k` fiber levels close simultaneously --
+    /// 1 for a single fiber boundary, up to `indptr.len()` at the very last
+    /// leaf, mirroring the `StopType` rank semantics `Buffer::to_elem_iter`
+    /// uses for dense tensors.
+    pub fn to_elem_iter(&self) -> Vec<Elem<T>> {
+        let ndim = self.indptr.len();
+        let mut out = Vec::with_capacity(self.values.len());
+        if ndim == 0 {
+            out.extend(self.values.iter().cloned().map(Elem::Val));
+            return out;
+        }
+        let mut is_last_at_level = Vec::with_capacity(ndim);
+        self.walk(0, 0, ndim, &mut is_last_at_level, &mut out);
+        out
+    }
+
+    fn walk(
+        &self,
+        level: usize,
+        node: usize,
+        ndim: usize,
+        is_last_at_level: &mut Vec<bool>,
+        out: &mut Vec<Elem<T>>,
+    ) {
+        if level == ndim {
+            let levels_closing = is_last_at_level.iter().rev().take_while(|&&b| b).count();
+            let value = self.values[node].clone();
+            out.push(if levels_closing == 0 {
+                Elem::Val(value)
+            } else {
+                Elem::ValStop(value, levels_closing as StopType)
+            });
+            return;
+        }
+
+        let start = self.indptr[level][node];
+        let end = self.indptr[level][node + 1];
+        for child in start..end {
+            is_last_at_level.push(child == end - 1);
+            self.walk(level + 1, child, ndim, is_last_at_level, out);
+            is_last_at_level.pop();
+        }
+    }
+
+    /// Inverse of [`Self::to_elem_iter`]: consumes a rank-`ndim` `Elem<T>`
+    /// stream and rebuilds the CSF `indptr`/`indices`/`values` arrays. Keeps
+    /// a `counters[level]` stack (children seen so far by the currently-open
+    /// node at that level) and a `pending_open[level]` flag (the next child
+    /// at that level needs a fresh node opened first): every value pushes a
+    /// coordinate into `indices[ndim - 1]`, and each `ValStop(_, k)` closes
+    /// out the innermost `k` levels' current node into `indptr`.
+    pub fn from_elem_stream(stream: impl IntoIterator<Item = Elem<T>>, ndim: usize) -> Self {
+        assert!(ndim > 0, "CsfTensor::from_elem_stream needs ndim >= 1");
+
+        let mut indptr: Vec<Vec<usize>> = vec![vec![0]; ndim];
+        let mut indices: Vec<Vec<usize>> = vec![Vec::new(); ndim];
+        let mut values = Vec::new();
+        let mut counters = vec![0usize; ndim];
+        let mut pending_open = vec![true; ndim];
+
+        for elem in stream {
+            let (value, stop_k) = match elem {
+                Elem::Val(value) => (value, 0),
+                Elem::ValStop(value, k) => (value, k as usize),
+            };
+
+            for level in 0..ndim {
+                if pending_open[level] {
+                    if level > 0 {
+                        indices[level - 1].push(counters[level - 1]);
+                        counters[level - 1] += 1;
+                    }
+                    counters[level] = 0;
+                    pending_open[level] = false;
+                }
+            }
+
+            indices[ndim - 1].push(counters[ndim - 1]);
+            counters[ndim - 1] += 1;
+            values.push(value);
+
+            for level in (ndim - stop_k..ndim).rev() {
+                indptr[level].push(indices[level].len());
+                pending_open[level] = true;
+            }
+        }
+
+        Self {
+            indptr,
+            indices,
+            values,
+        }
+    }
+}
+
+/// Streams a [`CsfTensor`]'s fiber tree out onto `out_stream`, depth-first,
+/// as the `Elem<T>` stop-token sequence -- see [`CsfTensor::to_elem_iter`].
+#[context_macro]
+pub struct ArrowCSFReader<T: DAMType> {
+    tensor: CsfTensor<T>,
+    out_stream: Sender<Elem<T>>,
+}
+
+impl<T: DAMType> ArrowCSFReader<T> {
+    pub fn new(tensor: CsfTensor<T>, out_stream: Sender<Elem<T>>) -> Self {
+        let ctx = Self {
+            tensor,
+            out_stream,
+            context_info: Default::default(),
+        };
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType> Context for ArrowCSFReader<T> {
+    fn run(&mut self) {
+        for elem in self.tensor.to_elem_iter() {
+            self.out_stream
+                .enqueue(
+                    &self.time,
+                    ChannelElement {
+                        time: self.time.tick(),
+                        data: elem,
+                    },
+                )
+                .unwrap();
+        }
+    }
+}
+
+/// Drains `in_stream` -- a rank-`rank` `Elem<T>` stop-token stream -- into a
+/// single [`CsfTensor`], emitted on `out_stream` once the stream closes. See
+/// [`CsfTensor::from_elem_stream`] for the reconstruction itself.
+#[context_macro]
+pub struct ArrowCSFWriter<T: DAMType> {
+    in_stream: Receiver<Elem<T>>,
+    out_stream: Sender<CsfTensor<T>>,
+    rank: StopType,
+}
+
+impl<T: DAMType> ArrowCSFWriter<T>
+where
+    CsfTensor<T>: DAMType,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<T>>,
+        out_stream: Sender<CsfTensor<T>>,
+        rank: StopType,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            rank,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType> Context for ArrowCSFWriter<T>
+where
+    CsfTensor<T>: DAMType,
+{
+    fn run(&mut self) {
+        let mut received = Vec::new();
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => received.push(data),
+                Err(_) => break,
+            }
+        }
+        let tensor = CsfTensor::from_elem_stream(received, self.rank as usize);
+        self.out_stream
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data: tensor,
+                },
+            )
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::{simulation::ProgramBuilder, utility_contexts::CheckerContext};
+
+    use super::{ArrowCSFReader, ArrowCSFWriter, CsfTensor};
+    use crate::primitives::elem::Elem;
+
+    /// The dense (2, 2) tensor [[0, 1], [2, 3]] laid out as a 2-level CSF
+    /// tensor, alongside the `Elem` stream it's expected to walk to/from.
+    fn sample_tensor() -> CsfTensor<u32> {
+        CsfTensor {
+            indptr: vec![vec![0, 2], vec![0, 2, 4]],
+            indices: vec![vec![0, 1], vec![0, 1, 0, 1]],
+            values: vec![0, 1, 2, 3],
+        }
+    }
+
+    fn sample_stream() -> Vec<Elem<u32>> {
+        vec![
+            Elem::Val(0),
+            Elem::ValStop(1, 1),
+            Elem::Val(2),
+            Elem::ValStop(3, 2),
+        ]
+    }
+
+    #[test]
+    fn to_elem_iter_matches_dense_boundary_semantics() {
+        assert_eq!(sample_tensor().to_elem_iter(), sample_stream());
+    }
+
+    #[test]
+    fn from_elem_stream_round_trips_to_elem_iter() {
+        assert_eq!(
+            CsfTensor::from_elem_stream(sample_stream(), 2),
+            sample_tensor()
+        );
+    }
+
+    #[test]
+    fn reader_then_writer_round_trips_tensor() {
+        let mut ctx = ProgramBuilder::default();
+
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(ArrowCSFReader::new(sample_tensor(), snd));
+
+        let (out_snd, out_rcv) = ctx.unbounded();
+        ctx.add_child(ArrowCSFWriter::new(rcv, out_snd, 2));
+
+        let expected = sample_tensor();
+        ctx.add_child(CheckerContext::new(
+            move || std::iter::once(expected.clone()),
+            out_rcv,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
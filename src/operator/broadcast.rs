@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use dam::{
     channel::{Receiver, Sender},
     context::Context,
@@ -9,10 +11,23 @@ use crate::primitives::{elem::Elem, tile::Tile};
 
 /// Since DAM channels are single-producer single-consumer, Broadcasts can be used to send from a single channel to multiple channels.
 
+/// Delivery mode for [`BroadcastContext`]. See [`BroadcastContext::new`] and
+/// [`BroadcastContext::new_buffered`].
+enum BroadcastMode {
+    /// One shared clock step per source element: every target must have
+    /// room before any of them receives it.
+    Sync,
+    /// Per-target cursors into a staging buffer of at most `depth`
+    /// not-yet-fully-delivered source elements; see
+    /// [`BroadcastContext::run_buffered`].
+    Buffered { depth: usize },
+}
+
 #[context_macro]
 pub struct BroadcastContext<T: Clone> {
     receiver: Receiver<Elem<T>>,
     targets: Vec<Sender<Elem<T>>>,
+    mode: BroadcastMode,
 }
 
 impl<T: DAMType> Context for BroadcastContext<T>
@@ -20,20 +35,9 @@ where
     Elem<T>: DAMType,
 {
     fn run(&mut self) {
-        loop {
-            let value = self.receiver.dequeue(&self.time);
-            match value {
-                Ok(mut data) => {
-                    for target in &self.targets {
-                        target.wait_until_available(&self.time).unwrap();
-                    }
-                    data.time = self.time.tick();
-                    for target in &self.targets {
-                        target.enqueue(&self.time, data.clone()).unwrap();
-                    }
-                }
-                Err(_) => return,
-            }
+        match self.mode {
+            BroadcastMode::Sync => self.run_sync(),
+            BroadcastMode::Buffered { depth } => self.run_buffered(depth),
         }
     }
 }
@@ -42,11 +46,33 @@ impl<T: DAMType> BroadcastContext<T>
 where
     Elem<T>: DAMType,
 {
-    /// Sets up a broadcast context with an empty target list.
+    /// Sets up a broadcast context with an empty target list, using the
+    /// original synchronous fan-out: every target must have room before
+    /// any of them receives the next element, so the whole broadcast
+    /// advances at the rate of the slowest target.
     pub fn new(receiver: Receiver<Elem<T>>) -> Self {
         let x = Self {
             receiver,
             targets: vec![],
+            mode: BroadcastMode::Sync,
+            context_info: Default::default(),
+        };
+        x.receiver.attach_receiver(&x);
+        x
+    }
+
+    /// Sets up a broadcast context whose targets are fed independently:
+    /// up to `depth` source elements can be buffered ahead of the
+    /// slowest target, so a fast consumer drains ahead of a slow one
+    /// instead of every target being synchronized on each source
+    /// element. See [`Self::run_buffered`] for the delivery model.
+    pub fn new_buffered(receiver: Receiver<Elem<T>>, depth: usize) -> Self {
+        let x = Self {
+            receiver,
+            targets: vec![],
+            mode: BroadcastMode::Buffered {
+                depth: depth.max(1),
+            },
             context_info: Default::default(),
         };
         x.receiver.attach_receiver(&x);
@@ -58,4 +84,73 @@ where
         target.attach_sender(self);
         self.targets.push(target);
     }
+
+    fn run_sync(&mut self) {
+        loop {
+            let value = self.receiver.dequeue(&self.time);
+            match value {
+                Ok(mut data) => {
+                    for target in &self.targets {
+                        target.wait_until_available(&self.time).unwrap();
+                    }
+                    data.time = self.time.tick();
+                    for target in &self.targets {
+                        target.enqueue(&self.time, data.clone()).unwrap();
+                    }
+                }
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Maintains a staging buffer of up to `depth` source elements not yet
+    /// delivered to every target, and a per-target cursor into it. Each
+    /// round, every target that still has buffered elements waiting is
+    /// serviced in turn -- a target that's already caught up isn't held
+    /// back by one that still needs to wait for room downstream, unlike
+    /// [`Self::run_sync`], which waits on every target before sending to
+    /// any of them. An element is dropped from the buffer once every
+    /// target has consumed it, and fresh elements are only pulled from
+    /// `receiver` while the buffer is under `depth`, bounding how far a
+    /// fast target can race ahead of a slow one.
+    ///
+    /// All targets still share this context's single simulated clock, so
+    /// a target's own wait still delays whichever target is serviced
+    /// after it *within the same round* -- this narrows head-of-line
+    /// blocking to that, rather than eliminating per-context scheduling
+    /// entirely.
+    fn run_buffered(&mut self, depth: usize) {
+        let mut buffer: VecDeque<_> = VecDeque::new();
+        let mut cursor = vec![0usize; self.targets.len()];
+        let mut base = 0usize;
+        let mut source_closed = false;
+
+        loop {
+            if !source_closed && buffer.len() < depth {
+                match self.receiver.dequeue(&self.time) {
+                    Ok(data) => buffer.push_back(data),
+                    Err(_) => source_closed = true,
+                }
+            }
+
+            if source_closed && buffer.is_empty() {
+                return;
+            }
+
+            for (i, target) in self.targets.iter().enumerate() {
+                if cursor[i] - base < buffer.len() {
+                    target.wait_until_available(&self.time).unwrap();
+                    let mut data = buffer[cursor[i] - base].clone();
+                    data.time = self.time.tick();
+                    target.enqueue(&self.time, data).unwrap();
+                    cursor[i] += 1;
+                }
+            }
+
+            while !buffer.is_empty() && cursor.iter().all(|&c| c > base) {
+                buffer.pop_front();
+                base += 1;
+            }
+        }
+    }
 }
@@ -92,6 +92,12 @@ where
                 Err(BufferizeError::Incomplete) => {
                     panic!("Stream terminated, but buffer was incomplete")
                 }
+                Err(BufferizeError::InvalidStopToken(st)) => {
+                    panic!("Stop token {st} doesn't fit in a usize")
+                }
+                Err(BufferizeError::ShapeMismatch(shape)) => {
+                    panic!("Stream produced an element count inconsistent with its inferred shape {shape:?}")
+                }
             }
         }
     }
@@ -0,0 +1,420 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use crate::memory::PMU_BW;
+use crate::primitives::dtype::{round_to_bf16, round_to_f8_e4m3, round_to_f8_e5m2, Fp8Overflow};
+use crate::primitives::elem::{Bufferizable, Elem};
+use crate::primitives::tile::Tile;
+use crate::utils::calculation::div_ceil;
+use crate::utils::events::LoggableEventSimple;
+use dam::{context_tools::*, logging::LogEvent};
+
+/// Target numeric format a [`Cast`] context asks its elements to emulate on
+/// top of the plain type conversion [`NumericCast`] performs. Unlike
+/// [`crate::primitives::dtype::DType`] (a cost-model byte-width tag only),
+/// every variant here drives an actual software rounding pass, so
+/// downstream compute sees true reduced-precision numerics rather than an
+/// `f32` wearing a smaller byte count.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CastFormat {
+    Bf16,
+    F8E4M3(Fp8Overflow),
+    F8E5M2(Fp8Overflow),
+}
+
+impl CastFormat {
+    pub fn bytes_per_elem(&self) -> usize {
+        match self {
+            CastFormat::Bf16 => 2,
+            CastFormat::F8E4M3(_) | CastFormat::F8E5M2(_) => 1,
+        }
+    }
+
+    fn round(&self, x: f32) -> f32 {
+        match self {
+            CastFormat::Bf16 => round_to_bf16(x),
+            CastFormat::F8E4M3(overflow) => round_to_f8_e4m3(x, *overflow),
+            CastFormat::F8E5M2(overflow) => round_to_f8_e5m2(x, *overflow),
+        }
+    }
+}
+
+/// Converts one tile element from `Self` to `OT`, consulting `format` when
+/// (and only when) the target representation is a float that a reduced-
+/// precision format could meaningfully emulate. Implemented for the
+/// element type pairs this crate's tiles actually carry; add a pair here
+/// the same way if a new one comes up.
+pub trait NumericCast<OT> {
+    fn numeric_cast(&self, format: Option<CastFormat>) -> OT;
+}
+
+impl NumericCast<f32> for f32 {
+    fn numeric_cast(&self, format: Option<CastFormat>) -> f32 {
+        match format {
+            Some(f) => f.round(*self),
+            None => *self,
+        }
+    }
+}
+
+impl NumericCast<i32> for f32 {
+    fn numeric_cast(&self, _format: Option<CastFormat>) -> i32 {
+        *self as i32
+    }
+}
+
+impl NumericCast<f32> for i32 {
+    fn numeric_cast(&self, format: Option<CastFormat>) -> f32 {
+        (*self as f32).numeric_cast(format)
+    }
+}
+
+impl NumericCast<f32> for u64 {
+    fn numeric_cast(&self, format: Option<CastFormat>) -> f32 {
+        (*self as f32).numeric_cast(format)
+    }
+}
+
+impl NumericCast<u64> for f32 {
+    fn numeric_cast(&self, _format: Option<CastFormat>) -> u64 {
+        *self as u64
+    }
+}
+
+pub struct CastConfig {
+    pub compute_bw: u64,     // FLOPs / cycle
+    pub write_back_mu: bool, // Whether the output is written to a memory unit
+    /// Reduced-precision format to additionally emulate, or `None` for a
+    /// plain type conversion.
+    pub format: Option<CastFormat>,
+}
+
+/// Dtype-casting context, sibling to [`crate::operator::accum::Accum`]: same
+/// roofline shape (load from `size_in_bytes`, a compute term from
+/// `compute_bw`, an optional write-back term, combined by `max`), but the
+/// per-element work is a [`NumericCast`] conversion plus optional
+/// [`CastFormat`] rounding instead of a caller-supplied accumulator.
+///
+/// `SimConfig::mock_bf16` is the run-wide switch that should make every
+/// float-producing operator route its output through `CastFormat::Bf16`;
+/// threading that through requires a first-class `Tile<Bf16>`/`Type::Bf16`
+/// proto variant and a matching `ChannelMapCollection` channel family (see
+/// `round_to_bf16`'s doc comment), which needs the `datatype.proto` schema
+/// `build.rs` compiles from `step_perf_ir/proto/` -- not present in this
+/// tree, so that wiring isn't implemented here.
+#[context_macro]
+pub struct Cast<E, T: DAMType, OT: DAMType> {
+    in_stream: Receiver<Elem<Tile<T>>>,
+    out_stream: Sender<Elem<Tile<OT>>>,
+    config: CastConfig,
+    id: u32,
+    _phantom: PhantomData<E>,
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        T: DAMType + NumericCast<OT>,
+        OT: DAMType,
+    > Cast<E, T, OT>
+where
+    Elem<Tile<T>>: DAMType,
+    Elem<Tile<OT>>: DAMType,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<Tile<T>>>,
+        out_stream: Sender<Elem<Tile<OT>>>,
+        config: CastConfig,
+        id: u32,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            config,
+            id,
+            context_info: Default::default(),
+            _phantom: PhantomData,
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+
+    fn cast_tile(&self, in_data: &Tile<T>) -> Tile<OT> {
+        Tile {
+            shape: in_data.shape.clone(),
+            bytes_per_elem: self
+                .config
+                .format
+                .map(|f| f.bytes_per_elem())
+                .unwrap_or(std::mem::size_of::<OT>()),
+            read_from_mu: in_data.read_from_mu,
+            underlying: in_data
+                .underlying
+                .as_ref()
+                .map(|arr| arr.mapv(|v| v.numeric_cast(self.config.format)).to_shared()),
+            offset: in_data.offset,
+            col_offset: in_data.col_offset,
+            pad: in_data
+                .pad
+                .as_ref()
+                .map(|p| p.numeric_cast(self.config.format)),
+            row_align: in_data.row_align,
+            // A dtype cast changes the element type, so a source tile's
+            // CSR/RLE backing (typed by the old element type) can't be
+            // carried across unchanged; re-encoding it is future work.
+            csr: None,
+            rle: None,
+        }
+    }
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        T: DAMType + NumericCast<OT>,
+        OT: DAMType,
+    > Context for Cast<E, T, OT>
+where
+    Elem<Tile<T>>: DAMType,
+    Elem<Tile<OT>>: DAMType,
+{
+    fn run(&mut self) {
+        loop {
+            let in_elem = self.in_stream.peek_next(&self.time);
+            let (in_tile, stop_lev) = match in_elem {
+                Ok(ChannelElement {
+                    time: _,
+                    data: data_enum,
+                }) => match data_enum {
+                    Elem::Val(data) => (data, None),
+                    Elem::ValStop(data, lev) => (data, Some(lev)),
+                },
+                Err(_) => return,
+            };
+
+            let start_time = self.time.tick().time();
+            let load_cycles = if in_tile.read_from_mu {
+                div_ceil(in_tile.size_in_bytes() as u64, PMU_BW)
+            } else {
+                0
+            };
+
+            let out_tile = self.cast_tile(&in_tile);
+            let compute_elems: usize = out_tile.shape.iter().product();
+            let comp_cycles = div_ceil(compute_elems as u64, self.config.compute_bw.max(1));
+
+            let store_cycles = if self.config.write_back_mu {
+                div_ceil(out_tile.size_in_bytes() as u64, PMU_BW)
+            } else {
+                0
+            };
+
+            let roofline_cycles = [load_cycles, comp_cycles, store_cycles]
+                .into_iter()
+                .max()
+                .unwrap_or(0);
+            self.time.incr_cycles(roofline_cycles);
+
+            let data = match stop_lev {
+                Some(level) => Elem::ValStop(out_tile, level),
+                None => Elem::Val(out_tile),
+            };
+            self.out_stream
+                .enqueue(
+                    &self.time,
+                    ChannelElement {
+                        time: self.time.tick(),
+                        data,
+                    },
+                )
+                .unwrap();
+
+            crate::utils::events::log_event(&E::new(
+                "Cast".to_string(),
+                self.id,
+                start_time,
+                self.time.tick().time(),
+                stop_lev.is_some(),
+            ));
+
+            self.in_stream.dequeue(&self.time).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cast, CastConfig, CastFormat};
+    use crate::primitives::dtype::{round_to_bf16, Fp8Overflow};
+    use crate::{primitives::elem::Elem, primitives::tile::Tile, utils::events::SimpleEvent};
+    use dam::simulation::ProgramBuilder;
+    use dam::utility_contexts::{ApproxCheckerContext, GeneratorContext};
+
+    fn tolerance_fn(a: &Elem<Tile<f32>>, b: &Elem<Tile<f32>>) -> bool {
+        match (a, b) {
+            (Elem::Val(a_tile), Elem::Val(b_tile)) => a_tile == b_tile,
+            (Elem::ValStop(a_tile, a_level), Elem::ValStop(b_tile, b_level)) => {
+                a_tile == b_tile && a_level == b_level
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn cast_f32_to_bf16_rounds_every_element() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        let arr = ndarray::Array2::from_shape_fn((1, 2), |_| 1.0f32 / 3.0);
+        let in_tile = Tile::new(arr.to_shared(), 4, false);
+
+        let expected_arr = arr.mapv(round_to_bf16);
+        let expected_tile = Tile {
+            bytes_per_elem: 2,
+            ..Tile::new(expected_arr.to_shared(), 2, false)
+        };
+
+        ctx.add_child(GeneratorContext::new(
+            move || vec![Elem::ValStop(in_tile.clone(), 1)].into_iter(),
+            in_snd,
+        ));
+        ctx.add_child(Cast::<SimpleEvent, f32, f32>::new(
+            in_rcv,
+            out_snd,
+            CastConfig {
+                compute_bw: 4,
+                write_back_mu: false,
+                format: Some(CastFormat::Bf16),
+            },
+            0,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            move || vec![Elem::ValStop(expected_tile.clone(), 1)].into_iter(),
+            out_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn cast_f32_to_f8_e4m3_saturates_overflow() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        let arr = ndarray::Array2::from_shape_fn((1, 1), |_| 300.0f32);
+        let in_tile = Tile::new(arr.to_shared(), 4, false);
+
+        let expected_arr = ndarray::Array2::from_shape_fn((1, 1), |_| 240.0f32);
+        let expected_tile = Tile {
+            bytes_per_elem: 1,
+            ..Tile::new(expected_arr.to_shared(), 1, false)
+        };
+
+        ctx.add_child(GeneratorContext::new(
+            move || vec![Elem::ValStop(in_tile.clone(), 1)].into_iter(),
+            in_snd,
+        ));
+        ctx.add_child(Cast::<SimpleEvent, f32, f32>::new(
+            in_rcv,
+            out_snd,
+            CastConfig {
+                compute_bw: 4,
+                write_back_mu: false,
+                format: Some(CastFormat::F8E4M3(Fp8Overflow::Saturate)),
+            },
+            0,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            move || vec![Elem::ValStop(expected_tile.clone(), 1)].into_iter(),
+            out_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn matmul_then_cast_matches_f32_reference_within_tolerance() {
+        use crate::functions::map_fn;
+        use crate::operator::map::{BinaryMap, BinaryMapConfig};
+
+        let in1 = Tile::new(
+            ndarray::Array2::from_shape_vec((2, 2), vec![1.0f32, 2.0, 3.0, 4.0])
+                .unwrap()
+                .to_shared(),
+            4,
+            false,
+        );
+        let in2 = Tile::new(
+            ndarray::Array2::from_shape_vec((2, 2), vec![5.0f32, 6.0, 7.0, 8.0])
+                .unwrap()
+                .to_shared(),
+            4,
+            false,
+        );
+
+        let (_, f32_reference) = map_fn::matmul(&in1, &in2, 1024, false, false);
+        let expected_arr = f32_reference
+            .underlying
+            .unwrap()
+            .mapv(round_to_bf16);
+        let expected_tile = Tile {
+            bytes_per_elem: 2,
+            ..Tile::new(expected_arr.to_shared(), 2, false)
+        };
+
+        let mut ctx = ProgramBuilder::default();
+        let (in1_snd, in1_rcv) = ctx.unbounded();
+        let (in2_snd, in2_rcv) = ctx.unbounded();
+        let (mm_snd, mm_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            move || vec![Elem::ValStop(in1, 1)].into_iter(),
+            in1_snd,
+        ));
+        ctx.add_child(GeneratorContext::new(
+            move || vec![Elem::ValStop(in2, 1)].into_iter(),
+            in2_snd,
+        ));
+        ctx.add_child(BinaryMap::<SimpleEvent, _, _, _>::new(
+            in1_rcv,
+            in2_rcv,
+            mm_snd,
+            std::sync::Arc::new(|a, b, bw, wb| map_fn::matmul(a, b, bw, wb, false)),
+            BinaryMapConfig {
+                compute_bw: 1024,
+                write_back_mu: false,
+                bandwidth: Default::default(),
+                memory_unit_id: 0,
+                energy: Default::default(),
+                overlap_model: Default::default(),
+            },
+            0,
+        ));
+        ctx.add_child(Cast::<SimpleEvent, f32, f32>::new(
+            mm_rcv,
+            out_snd,
+            CastConfig {
+                compute_bw: 1024,
+                write_back_mu: false,
+                format: Some(CastFormat::Bf16),
+            },
+            1,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            move || vec![Elem::ValStop(expected_tile.clone(), 1)].into_iter(),
+            out_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
@@ -0,0 +1,392 @@
+use crate::functions::accum_fn;
+use crate::memory::PMU_BW;
+use crate::primitives::elem::{Bufferizable, Elem, StopType};
+use crate::primitives::{select::SelectAdapter, tile::Tile};
+use crate::utils::calculation::div_ceil;
+use crate::utils::events::LoggableEventSimple;
+use dam::{context_tools::*, logging::LogEvent};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::panic;
+
+pub struct FlatCombineConfig {
+    pub switch_cycles: Vec<u64>, // cycles between receiving
+    pub write_back_mu: bool,     // Whether the output is written to a memory unit
+    // Per-expert weight applied to its contribution before accumulation
+    // (e.g. MoE routing weights). `None` combines every selected expert's
+    // contribution unweighted.
+    pub weights: Option<Vec<f64>>,
+}
+
+/// The gather/merge inverse of [`FlatPartition`](crate::operator::partition::FlatPartition):
+/// for each original token, reads back the contributions from exactly the
+/// experts `sel_stream`'s multi-hot selected and accumulates them (sum,
+/// optionally weighted per `config.weights`) into a single output stream,
+/// restoring the original token order and stop-level structure.
+#[context_macro]
+pub struct FlatCombine<E, A: DAMType, SELT: DAMType> {
+    in_streams: Vec<Receiver<Elem<Tile<A>>>>, // per-expert output streams
+    sel_stream: Receiver<Elem<SELT>>,
+    out_stream: Sender<Elem<Tile<A>>>,
+    partition_rank: StopType,
+    config: FlatCombineConfig,
+    id: u32,
+    _phantom: PhantomData<E>,
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        A: DAMType + Debug + ndarray::LinalgScalar + Default + num_traits::NumCast,
+        SELT: DAMType + SelectAdapter + Bufferizable,
+    > FlatCombine<E, A, SELT>
+where
+    Elem<Tile<A>>: DAMType,
+    Elem<SELT>: DAMType,
+{
+    pub fn new(
+        in_streams: Vec<Receiver<Elem<Tile<A>>>>,
+        sel_stream: Receiver<Elem<SELT>>,
+        out_stream: Sender<Elem<Tile<A>>>,
+        partition_rank: StopType,
+        config: FlatCombineConfig,
+        id: u32,
+    ) -> Self {
+        let ctx = Self {
+            in_streams,
+            sel_stream,
+            out_stream,
+            partition_rank,
+            config,
+            id,
+            context_info: Default::default(),
+            _phantom: PhantomData,
+        };
+        for in_stream in &ctx.in_streams {
+            in_stream.attach_receiver(&ctx);
+        }
+        ctx.sel_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+
+        ctx
+    }
+
+    /// Helper function to calculate and increment load cycles for memory operations
+    fn handle_load_cycles<T: Bufferizable>(&mut self, data: &T) {
+        if data.read_from_mu() {
+            let load_cycle = div_ceil(data.size_in_bytes() as u64, PMU_BW);
+            self.time.incr_cycles(load_cycle);
+        }
+    }
+
+    /// Helper function to calculate and increment write cycles based on expert indices
+    fn handle_write_cycles<T: Bufferizable>(&mut self, select_vec: &[usize], data: &T) {
+        let mut write_cycle = 0;
+
+        // Find maximum switch cycle among contributing experts
+        for expert_idx in select_vec.iter() {
+            if self.config.switch_cycles[*expert_idx] > write_cycle {
+                write_cycle = self.config.switch_cycles[*expert_idx];
+            }
+        }
+
+        // Add memory write back cycles if configured
+        if self.config.write_back_mu {
+            write_cycle += div_ceil(data.size_in_bytes() as u64, PMU_BW);
+        }
+
+        self.time.incr_cycles(write_cycle);
+    }
+
+    /// Scales `tile` by `weight`, casting the `f64` weight into `A`.
+    fn scale(tile: &Tile<A>, weight: f64) -> Tile<A> {
+        let w = <A as num_traits::NumCast>::from(weight)
+            .expect("weight does not fit in the combined type");
+        match &tile.underlying {
+            Some(arr) => Tile::new(
+                arr.map(|v| *v * w).to_shared(),
+                tile.bytes_per_elem,
+                tile.read_from_mu,
+            ),
+            None => Tile::new_blank(tile.shape.clone(), tile.bytes_per_elem, tile.read_from_mu),
+        }
+    }
+
+    /// Reads one contribution from every expert in `select_vec`, validates
+    /// they all agree on the stop level they're reporting (or lack
+    /// thereof), and folds them (summation, optionally weighted per
+    /// `config.weights`) into a single combined tile.
+    ///
+    /// Returns the combined tile and the stop level every contributing
+    /// expert agreed on, or `None` if none of them reported one.
+    fn read_and_combine(&mut self, select_vec: &[usize]) -> (Tile<A>, Option<StopType>) {
+        let mut combined: Option<Tile<A>> = None;
+        let mut agreed_stop_lev: Option<StopType> = None;
+        let mut seen_any_stop = false;
+
+        for &expert_idx in select_vec {
+            let elem = self.in_streams[expert_idx]
+                .dequeue(&self.time)
+                .unwrap_or_else(|_| {
+                    panic!(
+                        "Expert stream {expert_idx} ran out of things to dequeue during combine."
+                    )
+                })
+                .data;
+
+            let (tile, stop_lev) = match elem {
+                Elem::Val(x) => (x, None),
+                Elem::ValStop(x, lev) => (x, Some(lev)),
+            };
+            self.handle_load_cycles(&tile);
+
+            if seen_any_stop {
+                assert_eq!(
+                    agreed_stop_lev, stop_lev,
+                    "Expert stream {expert_idx}'s stop level disagrees with the other experts contributing to this token."
+                );
+            } else {
+                agreed_stop_lev = stop_lev;
+                seen_any_stop = true;
+            }
+
+            let weighted = match &self.config.weights {
+                Some(weights) => Self::scale(&tile, weights[expert_idx]),
+                None => tile,
+            };
+
+            combined = Some(match combined {
+                // The combine's own timing only counts load/write cycles
+                // (mirroring `FlatPartition`), not per-element FLOPs, so
+                // the cycle count `accum_fn::add` returns is unused here.
+                Some(acc) => accum_fn::add(&acc, &weighted, 1, self.config.write_back_mu).1,
+                None => weighted,
+            });
+        }
+
+        (
+            combined.expect("select_vec must select at least one expert"),
+            agreed_stop_lev,
+        )
+    }
+
+    /// Combines one token's contributions per call, restoring the
+    /// original nesting structure: for `partition_rank == 0` this handles
+    /// a single token; for higher ranks it keeps combining tokens until a
+    /// stop level matching `expected_stop_level` (or `self.partition_rank`
+    /// when there's no expectation) is reached, mirroring
+    /// `FlatPartition::process_input_stream`.
+    fn process_combine_stream(
+        &mut self,
+        select_vec: &[usize],
+        expected_stop_level: Option<StopType>,
+    ) {
+        let mut start_time: Option<u64> = None;
+        loop {
+            if start_time.is_none() {
+                start_time = Some(self.time.tick().time());
+            }
+
+            let (tile, stop_lev) = self.read_and_combine(select_vec);
+            self.handle_write_cycles(select_vec, &tile);
+
+            let tile = tile.clone_with_updated_read_from_mu(self.config.write_back_mu);
+
+            match stop_lev {
+                None => {
+                    self.out_stream
+                        .enqueue(
+                            &self.time,
+                            ChannelElement {
+                                time: self.time.tick(),
+                                data: Elem::Val(tile),
+                            },
+                        )
+                        .unwrap();
+
+                    if self.partition_rank == 0 {
+                        crate::utils::events::log_event(&E::new(
+                            "FlatCombine".to_string(),
+                            self.id,
+                            start_time.unwrap(),
+                            self.time.tick().time(),
+                            true,
+                        ));
+                        return;
+                    }
+                }
+                Some(stop_lev) => {
+                    if let Some(expected) = expected_stop_level {
+                        assert_eq!(
+                            expected, stop_lev,
+                            "The expected stop level does not match the stop level reported by the expert streams!"
+                        );
+                    } else if stop_lev > self.partition_rank {
+                        panic!("The stop level in the expert streams is greater than the partition rank!");
+                    }
+
+                    let output_stop_level = expected_stop_level
+                        .map(|_| self.partition_rank)
+                        .unwrap_or(stop_lev);
+
+                    if output_stop_level == 0 {
+                        self.out_stream
+                            .enqueue(
+                                &self.time,
+                                ChannelElement {
+                                    time: self.time.tick(),
+                                    data: Elem::Val(tile),
+                                },
+                            )
+                            .unwrap();
+                    } else {
+                        self.out_stream
+                            .enqueue(
+                                &self.time,
+                                ChannelElement {
+                                    time: self.time.tick(),
+                                    data: Elem::ValStop(tile, output_stop_level),
+                                },
+                            )
+                            .unwrap();
+                    }
+
+                    if stop_lev == self.partition_rank || expected_stop_level == Some(stop_lev) {
+                        crate::utils::events::log_event(&E::new(
+                            "FlatCombine".to_string(),
+                            self.id,
+                            start_time.unwrap(),
+                            self.time.tick().time(),
+                            true,
+                        ));
+                        return;
+                    }
+                }
+            }
+
+            start_time = None;
+        }
+    }
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        A: DAMType + Debug + ndarray::LinalgScalar + Default + num_traits::NumCast,
+        SELT: DAMType + SelectAdapter + Bufferizable,
+    > Context for FlatCombine<E, A, SELT>
+where
+    Elem<Tile<A>>: DAMType,
+    Elem<SELT>: DAMType,
+{
+    fn run(&mut self) {
+        loop {
+            match self.sel_stream.peek_next(&self.time) {
+                Ok(ChannelElement {
+                    time: _,
+                    data: sel_data,
+                }) => match sel_data {
+                    Elem::Val(sel) => {
+                        self.handle_load_cycles(&sel);
+                        self.sel_stream.dequeue(&self.time).unwrap();
+                        let select_vec = sel.to_sel_vec();
+                        self.process_combine_stream(&select_vec, None);
+                    }
+                    Elem::ValStop(sel, sel_level) => {
+                        self.handle_load_cycles(&sel);
+                        self.sel_stream.dequeue(&self.time).unwrap();
+                        let select_vec = sel.to_sel_vec();
+                        let expected_stop_level = sel_level + self.partition_rank;
+                        self.process_combine_stream(&select_vec, Some(expected_stop_level));
+                    }
+                },
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::primitives::select::MultiHotN;
+    use crate::{
+        operator::combine::{FlatCombine, FlatCombineConfig},
+        primitives::{elem::Elem, tile::Tile},
+        utils::events::SimpleEvent,
+    };
+    use dam::simulation::ProgramBuilder;
+    use dam::utility_contexts::{ApproxCheckerContext, GeneratorContext};
+    use ndarray::Array2;
+
+    fn tolerance_fn(a: &Elem<Tile<i32>>, b: &Elem<Tile<i32>>) -> bool {
+        match (a, b) {
+            (Elem::Val(a_tile), Elem::Val(b_tile)) => a_tile == b_tile,
+            (Elem::ValStop(a_tile, a_level), Elem::ValStop(b_tile, b_level)) => {
+                a_tile == b_tile && a_level == b_level
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn flat_combine_2_experts_rank_0() {
+        let read_from_mu = true;
+        let tile = |v: i32| {
+            Tile::new(
+                Array2::from_shape_vec((2, 2), vec![v; 4]).unwrap().into(),
+                4,
+                read_from_mu,
+            )
+        };
+
+        // Token 0: experts {0, 1} contribute 1 and 10 -> combined 11
+        // Token 1: expert {0} alone contributes 2 -> combined 2
+        // Token 2 (stop level 1): expert {1} alone contributes 20 -> combined 20
+        let expert0_data = vec![Elem::Val(tile(1)), Elem::Val(tile(2))];
+        let expert1_data = vec![Elem::Val(tile(10)), Elem::ValStop(tile(20), 1)];
+
+        let sel_stream_data = vec![
+            Elem::Val(MultiHotN::new(vec![true, true], read_from_mu)),
+            Elem::Val(MultiHotN::new(vec![true, false], read_from_mu)),
+            Elem::ValStop(MultiHotN::new(vec![false, true], read_from_mu), 1),
+        ];
+
+        let out_stream_data = vec![Elem::Val(tile(11)), Elem::Val(tile(2)), Elem::Val(tile(20))];
+
+        let mut ctx = ProgramBuilder::default();
+        let (exp0_snd, exp0_rcv) = ctx.unbounded();
+        let (exp1_snd, exp1_rcv) = ctx.unbounded();
+        let (sel_snd, sel_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(|| expert0_data.into_iter(), exp0_snd));
+        ctx.add_child(GeneratorContext::new(|| expert1_data.into_iter(), exp1_snd));
+        ctx.add_child(GeneratorContext::new(
+            || sel_stream_data.into_iter(),
+            sel_snd,
+        ));
+
+        let config = FlatCombineConfig {
+            switch_cycles: vec![1, 2],
+            write_back_mu: true,
+            weights: None,
+        };
+
+        ctx.add_child(FlatCombine::<SimpleEvent, _, _>::new(
+            vec![exp0_rcv, exp1_rcv],
+            sel_rcv,
+            out_snd,
+            0, // partition_rank
+            config,
+            0, // id
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || out_stream_data.into_iter(),
+            out_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
@@ -0,0 +1,365 @@
+use crate::primitives::elem::{Bufferizable, Elem, StopType};
+use crate::primitives::select::SelectAdapter;
+use dam::channel::PeekResult;
+use dam::context_tools::*;
+
+/// Set semantics for [`CoordinateMerge`]: whether a coordinate present in
+/// only *some* of the input fibers still appears in the merged output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMode {
+    /// Every coordinate seen in any input is emitted once, with the select
+    /// bitmask marking which inputs carried it.
+    Union,
+    /// A coordinate is only emitted once every still-open input carries it;
+    /// coordinates that only some inputs have are consumed and dropped.
+    Intersect,
+}
+
+/// Merges `N` sparse fiber streams by *coordinate value* rather than by
+/// arrival time, the way [`super::eager_merge::EagerMerge`] does. Intended
+/// for coordinate streams where `A` is itself the coordinate (so `Elem::Val`/
+/// `Elem::ValStop`'s payload is directly comparable), emitting the same
+/// `sel_stream`/`out_stream` shape as `EagerMerge` so downstream
+/// `FlatPartition`/`FlatReassemble` consumers work unchanged.
+///
+/// Coordinates within a fiber are assumed monotonically increasing; this is
+/// what lets the merge only ever look at each input's current head rather
+/// than buffering whole fibers.
+#[context_macro]
+pub struct CoordinateMerge<A: DAMType + PartialOrd, SELT: DAMType> {
+    in_streams: Vec<Receiver<Elem<A>>>,
+    sel_stream: Sender<Elem<SELT>>,
+    out_stream: Sender<Elem<A>>,
+    input_rank: StopType,
+    mode: MergeMode,
+    id: u32,
+}
+
+impl<A: DAMType + PartialOrd, SELT: DAMType + SelectAdapter + Bufferizable> CoordinateMerge<A, SELT>
+where
+    Elem<A>: DAMType,
+    Elem<SELT>: DAMType,
+{
+    pub fn new(
+        in_streams: Vec<Receiver<Elem<A>>>,
+        sel_stream: Sender<Elem<SELT>>,
+        out_stream: Sender<Elem<A>>,
+        input_rank: StopType,
+        mode: MergeMode,
+        id: u32,
+    ) -> Self {
+        let ctx = Self {
+            in_streams,
+            sel_stream,
+            out_stream,
+            input_rank,
+            mode,
+            id,
+            context_info: Default::default(),
+        };
+
+        ctx.in_streams.iter().for_each(|s| s.attach_receiver(&ctx));
+        ctx.sel_stream.attach_sender(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+
+        ctx
+    }
+
+    /// Peeks the head coordinate of every input that's still open (neither
+    /// permanently closed nor already exhausted for the current fiber),
+    /// busy-waiting a cycle at a time on inputs that haven't produced
+    /// anything yet. Returns `None` once every such input is closed, since
+    /// there's nothing left to peek.
+    fn peek_heads(
+        &mut self,
+        stopped_this_fiber: &[bool],
+        closed: &mut [bool],
+    ) -> Option<Vec<Option<(A, bool)>>> {
+        let n = self.in_streams.len();
+        let mut heads: Vec<Option<(A, bool)>> = vec![None; n];
+
+        loop {
+            if (0..n).all(|i| closed[i] || stopped_this_fiber[i]) {
+                return None;
+            }
+
+            let mut all_resolved = true;
+            for i in 0..n {
+                if closed[i] || stopped_this_fiber[i] || heads[i].is_some() {
+                    continue;
+                }
+
+                match self.in_streams[i].peek() {
+                    PeekResult::Something(elem) => match elem.data {
+                        Elem::Val(x) => heads[i] = Some((x, false)),
+                        Elem::ValStop(x, s) if s == self.input_rank => heads[i] = Some((x, true)),
+                        Elem::ValStop(_, s) if s > self.input_rank => panic!(
+                            "CoordinateMerge: found a stop token in input {i} with rank {s} higher than the given input_rank {}",
+                            self.input_rank
+                        ),
+                        Elem::ValStop(x, _) => heads[i] = Some((x, false)),
+                    },
+                    PeekResult::Nothing(_) => all_resolved = false,
+                    PeekResult::Closed => closed[i] = true,
+                }
+            }
+
+            if all_resolved {
+                return Some(heads);
+            }
+            self.time.incr_cycles(1);
+        }
+    }
+}
+
+impl<A: DAMType + PartialOrd, SELT: DAMType + SelectAdapter + Bufferizable> Context
+    for CoordinateMerge<A, SELT>
+where
+    Elem<A>: DAMType,
+    Elem<SELT>: DAMType,
+{
+    fn run(&mut self) {
+        let n = self.in_streams.len();
+        let mut closed = vec![false; n];
+
+        'fiber: loop {
+            let mut stopped_this_fiber = vec![false; n];
+
+            loop {
+                let Some(heads) = self.peek_heads(&stopped_this_fiber, &mut closed) else {
+                    return;
+                };
+
+                let participating: Vec<usize> = (0..n)
+                    .filter(|&i| !closed[i] && !stopped_this_fiber[i])
+                    .collect();
+
+                if participating.is_empty() {
+                    // Every input either closed permanently or already hit
+                    // its input_rank stop for this fiber -- if any one of
+                    // them is still just "stopped_this_fiber" (as opposed to
+                    // fully closed), the fiber has ended and there's a
+                    // next one to pick up; otherwise the whole merge is done.
+                    if (0..n).any(|i| stopped_this_fiber[i]) {
+                        continue 'fiber;
+                    }
+                    return;
+                }
+
+                let m = participating
+                    .iter()
+                    .map(|&i| &heads[i].as_ref().unwrap().0)
+                    .min_by(|a, b| a.partial_cmp(b).expect("coordinates must be comparable"))
+                    .unwrap()
+                    .clone();
+
+                let matching: Vec<usize> = participating
+                    .iter()
+                    .copied()
+                    .filter(|&i| heads[i].as_ref().unwrap().0 == m)
+                    .collect();
+
+                let should_emit = match self.mode {
+                    MergeMode::Union => true,
+                    MergeMode::Intersect => matching.len() == participating.len(),
+                };
+
+                for &i in &matching {
+                    let (_, is_stop) = heads[i].as_ref().unwrap();
+                    if *is_stop {
+                        stopped_this_fiber[i] = true;
+                    }
+                    self.in_streams[i].dequeue(&self.time).unwrap();
+                }
+
+                if !should_emit {
+                    continue;
+                }
+
+                let fiber_done = (0..n).all(|i| closed[i] || stopped_this_fiber[i]);
+
+                self.sel_stream
+                    .enqueue(
+                        &self.time,
+                        ChannelElement {
+                            time: self.time.tick(),
+                            data: Elem::Val(SELT::from_sel_vec(matching.clone(), n, false)),
+                        },
+                    )
+                    .unwrap();
+
+                let out_elem = if fiber_done {
+                    Elem::ValStop(m, self.input_rank)
+                } else {
+                    Elem::Val(m)
+                };
+
+                self.out_stream
+                    .enqueue(
+                        &self.time,
+                        ChannelElement {
+                            time: self.time.tick(),
+                            data: out_elem,
+                        },
+                    )
+                    .unwrap();
+
+                if fiber_done {
+                    continue 'fiber;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::{
+        channel::{ChannelElement, Sender},
+        context::Context,
+        dam_macros::context_macro,
+        simulation::ProgramBuilder,
+        types::DAMType,
+        utility_contexts::{ApproxCheckerContext, ConsumerContext},
+    };
+
+    use crate::primitives::{elem::Elem, select::MultiHotN};
+
+    use super::{CoordinateMerge, MergeMode};
+
+    #[context_macro]
+    pub struct SenderContext<A: DAMType> {
+        pub out_stream: Sender<Elem<A>>,
+        pub elems: Vec<Elem<A>>,
+    }
+
+    impl<A: DAMType> SenderContext<A>
+    where
+        Elem<A>: DAMType,
+    {
+        pub fn new(out_stream: Sender<Elem<A>>, elems: Vec<Elem<A>>) -> Self {
+            let ctx = Self {
+                out_stream,
+                elems,
+                context_info: Default::default(),
+            };
+            ctx.out_stream.attach_sender(&ctx);
+            ctx
+        }
+    }
+
+    impl<A: DAMType> Context for SenderContext<A>
+    where
+        Elem<A>: DAMType,
+    {
+        fn run(&mut self) {
+            for elem in self.elems.clone() {
+                self.out_stream
+                    .enqueue(
+                        &self.time,
+                        ChannelElement {
+                            time: self.time.tick(),
+                            data: elem,
+                        },
+                    )
+                    .unwrap();
+                self.time.incr_cycles(1);
+            }
+        }
+    }
+
+    #[test]
+    fn union_merges_and_marks_every_contributing_input() {
+        // input 0: coords 1, 3, stop @ 5
+        // input 1: coords 2, 3, stop @ 4
+        // union: 1(0), 2(1), 3(0,1), 4(1), 5(0)+stop
+        let mut ctx = ProgramBuilder::default();
+
+        let (snd0, rcv0) = ctx.unbounded();
+        let (snd1, rcv1) = ctx.unbounded();
+
+        ctx.add_child(SenderContext::new(
+            snd0,
+            vec![Elem::Val(1u32), Elem::Val(3), Elem::ValStop(5, 0)],
+        ));
+        ctx.add_child(SenderContext::new(
+            snd1,
+            vec![Elem::Val(2u32), Elem::Val(3), Elem::ValStop(4, 0)],
+        ));
+
+        let (out_snd, out_rcv) = ctx.unbounded();
+        let (sel_snd, sel_rcv) = ctx.unbounded();
+
+        ctx.add_child(CoordinateMerge::<u32, MultiHotN>::new(
+            vec![rcv0, rcv1],
+            sel_snd,
+            out_snd,
+            0,
+            MergeMode::Union,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1u32),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::Val(4),
+                    Elem::ValStop(5, 0),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+        ctx.add_child(ConsumerContext::new(sel_rcv));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn intersect_drops_coordinates_not_shared_by_every_input() {
+        // input 0: coords 1, 2, stop @ 3
+        // input 1: coords 2, stop @ 3
+        // intersect only keeps the shared coordinates 2 and the closing 3.
+        let mut ctx = ProgramBuilder::default();
+
+        let (snd0, rcv0) = ctx.unbounded();
+        let (snd1, rcv1) = ctx.unbounded();
+
+        ctx.add_child(SenderContext::new(
+            snd0,
+            vec![Elem::Val(1u32), Elem::Val(2), Elem::ValStop(3, 0)],
+        ));
+        ctx.add_child(SenderContext::new(
+            snd1,
+            vec![Elem::Val(2u32), Elem::ValStop(3, 0)],
+        ));
+
+        let (out_snd, out_rcv) = ctx.unbounded();
+        let (sel_snd, sel_rcv) = ctx.unbounded();
+
+        ctx.add_child(CoordinateMerge::<u32, MultiHotN>::new(
+            vec![rcv0, rcv1],
+            sel_snd,
+            out_snd,
+            0,
+            MergeMode::Intersect,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || vec![Elem::Val(2u32), Elem::ValStop(3, 0)].into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+        ctx.add_child(ConsumerContext::new(sel_rcv));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
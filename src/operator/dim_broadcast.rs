@@ -0,0 +1,182 @@
+use crate::primitives::elem::{Elem, StopType};
+use dam::context_tools::*;
+
+/// NumPy-style dimension stretching: re-emits each contiguous group of
+/// elements delimited by a `ValStop` at or above `broadcast_dim` `factor`
+/// times, turning a size-1 axis into size `factor`. This is the structural
+/// counterpart of [`super::reshape::Reshape`]'s split: where `Reshape`
+/// rewrites stop levels to carve one axis into two, `DimBroadcast` repeats
+/// a whole axis-group in place.
+#[context_macro]
+pub struct DimBroadcast<InputType: Clone> {
+    in_stream: Receiver<Elem<InputType>>,
+    out_stream: Sender<Elem<InputType>>,
+    broadcast_dim: StopType,
+    factor: usize,
+    id: u32,
+}
+
+impl<InputType: DAMType> DimBroadcast<InputType>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<InputType>>,
+        out_stream: Sender<Elem<InputType>>,
+        broadcast_dim: StopType,
+        factor: usize,
+        id: u32,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            broadcast_dim,
+            factor,
+            id,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+
+    fn emit_group(&mut self, group: &[Elem<InputType>]) {
+        let original_stop = match group.last() {
+            Some(Elem::ValStop(_, s)) => *s,
+            _ => panic!(
+                "DimBroadcast {}: a buffered group must end in a ValStop",
+                self.id
+            ),
+        };
+        for replay in 0..self.factor {
+            let is_last_replay = replay == self.factor - 1;
+            let last_idx = group.len() - 1;
+            for (i, elem) in group.iter().enumerate() {
+                let out_elem = if i == last_idx {
+                    let stop = if is_last_replay {
+                        original_stop
+                    } else {
+                        self.broadcast_dim
+                    };
+                    match elem {
+                        Elem::ValStop(x, _) => Elem::ValStop(x.clone(), stop),
+                        Elem::Val(x) => Elem::ValStop(x.clone(), stop),
+                    }
+                } else {
+                    elem.clone()
+                };
+                self.out_stream
+                    .enqueue(
+                        &self.time,
+                        ChannelElement {
+                            time: self.time.tick(),
+                            data: out_elem,
+                        },
+                    )
+                    .unwrap();
+            }
+        }
+    }
+}
+
+impl<InputType: DAMType> Context for DimBroadcast<InputType> {
+    fn run(&mut self) {
+        let mut group: Vec<Elem<InputType>> = vec![];
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => {
+                    let is_group_end =
+                        matches!(&data, Elem::ValStop(_, s) if *s >= self.broadcast_dim);
+                    group.push(data);
+                    if is_group_end {
+                        self.emit_group(&group);
+                        group.clear();
+                    }
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::{
+        simulation::ProgramBuilder,
+        utility_contexts::{ApproxCheckerContext, GeneratorContext},
+    };
+
+    use crate::primitives::elem::Elem;
+
+    use super::DimBroadcast;
+
+    #[test]
+    fn broadcast_single_group() {
+        // one group of 2 elements, broadcast factor 3 along dim 1
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(1), Elem::ValStop(2, 1)].into_iter(),
+            in_snd,
+        ));
+
+        ctx.add_child(DimBroadcast::new(in_rcv, out_snd, 1, 3, 0));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::ValStop(2, 1),
+                    Elem::Val(1),
+                    Elem::ValStop(2, 1),
+                    Elem::Val(1),
+                    Elem::ValStop(2, 1),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn broadcast_final_group_keeps_top_level_stop() {
+        // two groups; the second is the end of the whole stream (stop level 2)
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::ValStop(1, 1), Elem::ValStop(2, 2)].into_iter(),
+            in_snd,
+        ));
+
+        ctx.add_child(DimBroadcast::new(in_rcv, out_snd, 1, 2, 0));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::ValStop(1, 1),
+                    Elem::ValStop(1, 1),
+                    Elem::ValStop(2, 1),
+                    Elem::ValStop(2, 2),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
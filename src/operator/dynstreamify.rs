@@ -36,7 +36,7 @@ pub struct DynStreamify<E: LoggableEventSimple, T: Bufferizable + Clone, R: Clon
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: Bufferizable + DAMType,
         R: DAMType,
     > DynStreamify<E, T, R>
@@ -70,7 +70,7 @@ where
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: Bufferizable + DAMType,
         R: DAMType,
     > Context for DynStreamify<E, T, R>
@@ -207,14 +207,13 @@ where
                         }
                     }
                     self.in_stream.dequeue(&self.time).unwrap();
-                    dam::logging::log_event(&E::new(
+                    crate::utils::events::log_event(&E::new(
                         "DynStreamify".to_string(),
                         self.id,
                         start_time,
                         self.time.tick().time(),
                         false,
-                    ))
-                    .unwrap();
+                    ));
                 }
                 Err(_) => return,
             }
@@ -50,6 +50,11 @@ where
         let mut earliest_input_idx = None;
         let mut peeked_something = vec![false; self.in_streams.len()];
         let mut closed_streams = vec![false; self.in_streams.len()];
+        // `t` from the most recent `PeekResult::Nothing(t)` for each stream
+        // still pending -- the guaranteed no-data-before horizon, which
+        // only ever grows, and is this stream's only hope of ever beating
+        // `earliest_time`.
+        let mut nothing_until: Vec<Option<u64>> = vec![None; self.in_streams.len()];
 
         loop {
             for (i, stream) in self.in_streams.iter().enumerate() {
@@ -60,58 +65,69 @@ where
                 match stream.peek() {
                     PeekResult::Something(elem) => {
                         peeked_something[i] = true;
-                        if earliest_input_idx.is_none() {
-                            earliest_input_idx = Some(i);
-                            earliest_time = elem.time.time();
-                        } else if elem.time.time() < earliest_time {
+                        nothing_until[i] = None;
+                        if earliest_input_idx.is_none() || elem.time.time() < earliest_time {
                             earliest_input_idx = Some(i);
                             earliest_time = elem.time.time();
                         }
                     }
-                    PeekResult::Nothing(_) => continue,
+                    PeekResult::Nothing(t) => {
+                        nothing_until[i] = Some(t.time());
+                    }
                     PeekResult::Closed => {
                         peeked_something[i] = true;
                         closed_streams[i] = true;
-                        continue;
                     }
                 }
             }
 
             match earliest_input_idx {
                 Some(idx) => {
-                    if peeked_something.contains(&false) && self.time.tick().time() < earliest_time
-                    {
-                        // As we peeked all the inputs, we have an guarantee that there
-                        // will be no more elements that arrive before the current time.
-                        // However, this doesn't mean that there will be no data arriving
-                        // between the current time and the peeked element's time in the
-                        // input streams that returned nothing in the current cycle.
-
-                        // Therefore, we increment the time and repeat peeking the input
-                        // streams that we didn't find a peek result yet.
-                        self.time.incr_cycles(1);
-                        continue;
+                    let pending_horizon = (0..self.in_streams.len())
+                        .filter(|&i| !peeked_something[i])
+                        .filter_map(|i| nothing_until[i])
+                        .min();
+
+                    match pending_horizon {
+                        // Every input has either produced an element or told
+                        // us its no-data horizon already reached
+                        // `earliest_time`, so nothing closer can still show
+                        // up -- `idx` is genuinely the earliest.
+                        None => return Some(idx),
+                        Some(horizon) if horizon >= earliest_time => return Some(idx),
+
+                        // At least one pending stream's horizon hasn't
+                        // caught up to `earliest_time` yet -- a closer
+                        // element could still appear there. Jump straight to
+                        // that horizon (never past `earliest_time`, since an
+                        // element could land exactly there) instead of
+                        // single-stepping, then only the streams still
+                        // pending get re-peeked above.
+                        Some(horizon) => {
+                            let next = horizon.min(earliest_time);
+                            self.time.advance(next.into());
+                            continue;
+                        }
                     }
-
-                    // As we peeked all the inputs, we have an guarantee that there
-                    // will be no more elements that arrive before the current time.
-
-                    // If all the input streams returned something when peeked, then we
-                    // can guarantee that the current earliest_input_idx has the earliest input.
-
-                    // Even if not all the input streams returned something,
-                    // if the earliest_time is not a future timestamp,
-                    // this means that the input streams that returned nothing don't have
-                    // any data arriving before the current time.
-                    // Therefore, we can conclude that this is the earliest time the input
-                    // is available
-                    return Some(idx);
                 }
                 None => {
                     if !closed_streams.contains(&false) {
                         return None;
                     }
-                    self.time.incr_cycles(1);
+
+                    // No stream has produced an element at all yet; jump to
+                    // the nearest still-open stream's no-data horizon rather
+                    // than single-stepping through the gap.
+                    let next = (0..self.in_streams.len())
+                        .filter(|&i| !closed_streams[i])
+                        .filter_map(|i| nothing_until[i])
+                        .min();
+                    match next {
+                        Some(next) if next > self.time.tick().time() => {
+                            self.time.advance(next.into());
+                        }
+                        _ => self.time.incr_cycles(1),
+                    }
                     continue;
                 }
             }
@@ -0,0 +1,254 @@
+use crate::primitives::elem::Elem;
+use dam::context_tools::*;
+
+/// What to do when a narrowing [`ScalarCast`] can't represent the source
+/// value exactly -- e.g. casting `300u32` down to `u8`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NarrowPolicy {
+    /// Clamp to the target type's closest representable value.
+    Saturate,
+    /// Halt the context: out-of-range narrowing is treated as a pipeline
+    /// bug rather than data to silently mangle.
+    Halt,
+}
+
+/// Converts one scalar value from `Self` to `To`, consulting `policy` only
+/// when the conversion can lose information. Implementors fall into the
+/// same four families [`ElemCast`]'s callers reach for: integer
+/// widening/narrowing, float widening/narrowing, integer-as-boolean
+/// truthiness, and the identity "as-is" byte conversion (any `T` to
+/// itself). Add a pair here the same way if a new one comes up.
+pub trait ScalarCast<To> {
+    fn scalar_cast(&self, policy: NarrowPolicy) -> To;
+}
+
+macro_rules! impl_scalar_cast_identity {
+    ($ty:ty) => {
+        impl ScalarCast<$ty> for $ty {
+            fn scalar_cast(&self, _policy: NarrowPolicy) -> $ty {
+                *self
+            }
+        }
+    };
+}
+
+impl_scalar_cast_identity!(u8);
+impl_scalar_cast_identity!(u32);
+impl_scalar_cast_identity!(u64);
+impl_scalar_cast_identity!(f32);
+impl_scalar_cast_identity!(bool);
+
+/// Widening integer cast: every `u8` fits losslessly in a `u32` and every
+/// `u32` in a `u64`, so `policy` never applies.
+impl ScalarCast<u32> for u8 {
+    fn scalar_cast(&self, _policy: NarrowPolicy) -> u32 {
+        *self as u32
+    }
+}
+
+impl ScalarCast<u64> for u32 {
+    fn scalar_cast(&self, _policy: NarrowPolicy) -> u64 {
+        *self as u64
+    }
+}
+
+/// Narrowing integer cast: out-of-range values are clamped to `u8::MAX`
+/// under [`NarrowPolicy::Saturate`], or halt the context under
+/// [`NarrowPolicy::Halt`].
+impl ScalarCast<u8> for u32 {
+    fn scalar_cast(&self, policy: NarrowPolicy) -> u8 {
+        if *self <= u8::MAX as u32 {
+            *self as u8
+        } else {
+            match policy {
+                NarrowPolicy::Saturate => u8::MAX,
+                NarrowPolicy::Halt => panic!(
+                    "ScalarCast<u8> for u32: {self} doesn't fit in a u8 and policy is Halt"
+                ),
+            }
+        }
+    }
+}
+
+impl ScalarCast<u32> for u64 {
+    fn scalar_cast(&self, policy: NarrowPolicy) -> u32 {
+        if *self <= u32::MAX as u64 {
+            *self as u32
+        } else {
+            match policy {
+                NarrowPolicy::Saturate => u32::MAX,
+                NarrowPolicy::Halt => panic!(
+                    "ScalarCast<u32> for u64: {self} doesn't fit in a u32 and policy is Halt"
+                ),
+            }
+        }
+    }
+}
+
+/// Float/integer conversion with Rust `as` semantics: `f32 -> u32`
+/// truncates toward zero and saturates at the integer bounds, and
+/// `u32 -> f32` rounds to the nearest `f32` (exact only up to 2^24).
+/// `policy` is accepted but unused in both directions.
+impl ScalarCast<u32> for f32 {
+    fn scalar_cast(&self, _policy: NarrowPolicy) -> u32 {
+        *self as u32
+    }
+}
+
+impl ScalarCast<f32> for u32 {
+    fn scalar_cast(&self, _policy: NarrowPolicy) -> f32 {
+        *self as f32
+    }
+}
+
+/// Integer-as-boolean truthiness: zero is `false`, anything else `true`.
+impl ScalarCast<bool> for u32 {
+    fn scalar_cast(&self, _policy: NarrowPolicy) -> bool {
+        *self != 0
+    }
+}
+
+impl ScalarCast<u32> for bool {
+    fn scalar_cast(&self, _policy: NarrowPolicy) -> u32 {
+        *self as u32
+    }
+}
+
+/// Retypes an `Elem<From>` stream into `Elem<To>` element-by-element via
+/// [`ScalarCast`], leaving every `ValStop` rank untouched -- the raw-stream
+/// sibling of [`crate::operator::cast::Cast`], which instead retypes whole
+/// `Tile`s and models the roofline cost of doing so. `ElemCast` does no
+/// timing of its own, the same as [`crate::operator::flatten::Flatten`]:
+/// it exists to splice a type change into a fibertree pipeline, not to
+/// cost one.
+#[context_macro]
+pub struct ElemCast<From: DAMType, To: DAMType> {
+    in_stream: Receiver<Elem<From>>,
+    out_stream: Sender<Elem<To>>,
+    policy: NarrowPolicy,
+}
+
+impl<From: DAMType + ScalarCast<To>, To: DAMType> ElemCast<From, To>
+where
+    Elem<From>: DAMType,
+    Elem<To>: DAMType,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<From>>,
+        out_stream: Sender<Elem<To>>,
+        policy: NarrowPolicy,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            policy,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<From: DAMType + ScalarCast<To>, To: DAMType> Context for ElemCast<From, To>
+where
+    Elem<From>: DAMType,
+    Elem<To>: DAMType,
+{
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => {
+                    let out = match data {
+                        Elem::Val(v) => Elem::Val(v.scalar_cast(self.policy)),
+                        Elem::ValStop(v, s) => Elem::ValStop(v.scalar_cast(self.policy), s),
+                    };
+                    self.out_stream
+                        .enqueue(
+                            &self.time,
+                            ChannelElement {
+                                time: self.time.tick(),
+                                data: out,
+                            },
+                        )
+                        .unwrap();
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::simulation::ProgramBuilder;
+    use dam::utility_contexts::{CheckerContext, ConsumerContext, GeneratorContext};
+
+    use super::{ElemCast, NarrowPolicy};
+    use crate::primitives::elem::Elem;
+
+    #[test]
+    fn widens_u8_to_u32_preserving_stop_ranks() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(1u8), Elem::ValStop(2u8, 2)].into_iter(),
+            in_snd,
+        ));
+        ctx.add_child(ElemCast::<u8, u32>::new(in_rcv, out_snd, NarrowPolicy::Halt));
+        ctx.add_child(CheckerContext::new(
+            || vec![Elem::Val(1u32), Elem::ValStop(2u32, 2)].into_iter(),
+            out_rcv,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn narrows_u32_to_u8_saturating_on_overflow() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(10u32), Elem::ValStop(300u32, 1)].into_iter(),
+            in_snd,
+        ));
+        ctx.add_child(ElemCast::<u32, u8>::new(
+            in_rcv,
+            out_snd,
+            NarrowPolicy::Saturate,
+        ));
+        ctx.add_child(CheckerContext::new(
+            || vec![Elem::Val(10u8), Elem::ValStop(u8::MAX, 1)].into_iter(),
+            out_rcv,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in a u8 and policy is Halt")]
+    fn narrows_u32_to_u8_halts_on_overflow_when_configured() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(300u32)].into_iter(),
+            in_snd,
+        ));
+        ctx.add_child(ElemCast::<u32, u8>::new(in_rcv, out_snd, NarrowPolicy::Halt));
+        ctx.add_child(ConsumerContext::new(out_rcv));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
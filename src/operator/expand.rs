@@ -1,6 +1,22 @@
 use crate::primitives::elem::{Elem, StopType};
 use dam::context_tools::*;
 
+/// Broadcasts `in_stream` against the shape of `ref_stream`, which has
+/// `expand_rank` extra axes inserted below `in_stream`'s own structure
+/// (e.g. `in_stream` of shape `[2,3]` and `expand_rank = 1` broadcasts
+/// against a `ref_stream` of shape `[2,3,4]`).
+///
+/// `ref_stream`'s stop level `l` is one of the new axes while
+/// `l <= expand_rank`, and otherwise (a shift of) one of `in_stream`'s own
+/// levels. Each input leaf is held and replayed once per matching
+/// `ref_stream` element, re-emitting `ref_stream`'s own stop level
+/// unchanged (the output shape is exactly `ref_stream`'s shape); the held
+/// leaf only advances once `ref_stream`'s stop rank reaches
+/// `expand_rank` plus the held leaf's own stop level -- its outermost
+/// still-open level -- rather than `expand_rank` alone, so a leaf that
+/// closes several of `in_stream`'s own levels at once isn't replaced
+/// early. This supports `in_stream` of any rank, including rank 0 (a
+/// single `Elem::Val` broadcast against the whole of `ref_stream`).
 #[context_macro]
 pub struct ExpandRef<T: Clone, R: Clone> {
     in_stream: Receiver<Elem<T>>,
@@ -34,87 +50,78 @@ where
         ctx.out_stream.attach_sender(&ctx);
         ctx
     }
+
+    fn emit(&mut self, value: T, stop: Option<StopType>) {
+        let data = match stop {
+            Some(s) => Elem::ValStop(value, s),
+            None => Elem::Val(value),
+        };
+        self.out_stream
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data,
+                },
+            )
+            .unwrap();
+    }
 }
 
 impl<T: DAMType, R: DAMType> Context for ExpandRef<T, R> {
     fn run(&mut self) {
+        // The input leaf currently being replayed, paired with the level at
+        // which *its own* boundary closes -- `None` while it's a plain
+        // `Elem::Val`, i.e. not yet at the end of one of `in_stream`'s own
+        // groups.
+        let mut held: Option<(T, Option<StopType>)> = None;
+
         loop {
-            match self.in_stream.peek_next(&self.time) {
-                Ok(ChannelElement { time: _, data }) => match data {
-                    Elem::Val(x) => {
-                        // This means the input stream is rank 0
-                        assert_eq!(self.expand_rank, 1);
-                        loop {
-                            match self.ref_stream.dequeue(&self.time) {
-                                Ok(ChannelElement { time: _, data }) => match data {
-                                    Elem::Val(_) => {
-                                        self.out_stream
-                                            .enqueue(
-                                                &self.time,
-                                                ChannelElement {
-                                                    time: self.time.tick(),
-                                                    data: Elem::Val(x.clone()),
-                                                },
-                                            )
-                                            .unwrap();
-                                    }
-                                    Elem::ValStop(r, s) => {
-                                        panic!(
-                                            "ExpandRef {}: input stream should not have any Elem::ValStop values as the input stream is rank 0",
-                                            self.id
-                                        );
-                                    }
-                                },
-                                Err(_) => {
-                                    self.in_stream.dequeue(&self.time).unwrap();
-                                    return;
-                                }
-                            }
-                        }
+            if held.is_none() {
+                held = match self.in_stream.dequeue(&self.time) {
+                    Ok(ChannelElement {
+                        data: Elem::Val(x), ..
+                    }) => Some((x, None)),
+                    Ok(ChannelElement {
+                        data: Elem::ValStop(x, s),
+                        ..
+                    }) => Some((x, Some(s))),
+                    Err(_) => return,
+                };
+            }
+            let (x, own_stop) = held.clone().unwrap();
+            // `in_stream`'s outermost still-open level: the new axes close
+            // first, and only once the held leaf's own boundary is also
+            // reached does `in_stream` actually advance.
+            let advance_at = self.expand_rank + own_stop.unwrap_or(0);
+
+            match self.ref_stream.dequeue(&self.time) {
+                Ok(ChannelElement {
+                    data: Elem::Val(_), ..
+                }) => {
+                    self.emit(x, None);
+                }
+                Ok(ChannelElement {
+                    data: Elem::ValStop(_, s),
+                    ..
+                }) => {
+                    self.emit(x, Some(s));
+                    if s >= advance_at {
+                        held = None;
                     }
-                    Elem::ValStop(x, s) => {
-                        loop {
-                            match self.ref_stream.dequeue(&self.time) {
-                                Ok(ChannelElement { time: _, data }) => match data {
-                                    Elem::Val(r) => {
-                                        self.out_stream
-                                            .enqueue(
-                                                &self.time,
-                                                ChannelElement {
-                                                    time: self.time.tick(),
-                                                    data: Elem::Val(x.clone()),
-                                                },
-                                            )
-                                            .unwrap();
-                                    }
-                                    Elem::ValStop(r, s) => {
-                                        self.out_stream
-                                            .enqueue(
-                                                &self.time,
-                                                ChannelElement {
-                                                    time: self.time.tick(),
-                                                    data: Elem::ValStop(x.clone(), s),
-                                                },
-                                            )
-                                            .unwrap();
-                                        if s >= self.expand_rank {
-                                            self.in_stream.dequeue(&self.time).unwrap();
-                                            break; // move on to the next element in the input stream
-                                        }
-                                    }
-                                },
-                                Err(_) => {
-                                    panic!(
-                                        "ExpandRef {} should not reach here as it should have exited the loop on a stop token",
-                                        self.id
-                                    );
-                                }
-                            }
-                        }
+                }
+                Err(_) => {
+                    // A rank-0 `in_stream`'s single leaf has no stop level of
+                    // its own, so it stays held until `ref_stream` ends.
+                    if own_stop.is_none() && self.in_stream.peek_next(&self.time).is_err() {
+                        return;
                     }
-                },
-                Err(_) => return,
-            };
+                    panic!(
+                        "ExpandRef {}: ref_stream ended before in_stream's held leaf closed",
+                        self.id
+                    );
+                }
+            }
         }
     }
 }
@@ -123,9 +130,7 @@ impl<T: DAMType, R: DAMType> Context for ExpandRef<T, R> {
 mod tests {
     use dam::{
         simulation::ProgramBuilder,
-        utility_contexts::{
-            ApproxCheckerContext, CheckerContext, GeneratorContext, PrinterContext,
-        },
+        utility_contexts::{ApproxCheckerContext, GeneratorContext},
     };
 
     use crate::primitives::elem::Elem;
@@ -134,7 +139,8 @@ mod tests {
 
     #[test]
     fn expand_0d() {
-        // cargo test --package step_perf --lib -- operator::expand::tests::expand_0d --exact --show-output
+        // A rank-0 input (a single scalar) broadcast against a flat,
+        // unstopped reference of length 3.
         let mut ctx = ProgramBuilder::default();
 
         let (in_snd, in_rcv) = ctx.unbounded();
@@ -164,9 +170,11 @@ mod tests {
     }
 
     #[test]
-    fn expand_3d() {
-        // cargo test --package step_perf --lib -- operator::expand::tests::expand_3d --exact --show-output
-        // [2,3,1,1] => [2,3,2,4]
+    fn expand_rank2_into_new_innermost_axis() {
+        // [2,3] => [2,3,4]: a rank-2 input, each of whose 6 leaves is
+        // replayed 4 times for the newly inserted innermost axis.
+        // `in_stream`'s own level 1 (row of 3) and level 2 (the whole
+        // tensor) shift up by `expand_rank = 1` in the output.
         let mut ctx = ProgramBuilder::default();
 
         let (in_snd, in_rcv) = ctx.unbounded();
@@ -176,56 +184,52 @@ mod tests {
         ctx.add_child(GeneratorContext::new(
             || {
                 vec![
-                    Elem::ValStop(1, 2),
-                    Elem::ValStop(2, 2),
-                    Elem::ValStop(3, 3),
-                    Elem::ValStop(4, 2),
-                    Elem::ValStop(5, 2),
-                    Elem::ValStop(6, 3),
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::ValStop(3, 1),
+                    Elem::Val(4),
+                    Elem::Val(5),
+                    Elem::ValStop(6, 2),
                 ]
                 .into_iter()
             },
             in_snd,
         ));
 
-        let vec1 = vec![
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::ValStop(1, 1),
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::ValStop(2, 2),
-        ];
-        let vec2 = vec![
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::ValStop(1, 1),
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::Val(1),
-            Elem::ValStop(2, 3),
-        ];
-
-        // // Chain multiple vectors (borrowing)
-        let chained: Vec<Elem<i32>> = vec1
-            .iter()
-            .chain(vec1.iter())
-            .chain(vec2.iter())
-            .chain(vec1.iter())
-            .chain(vec1.iter())
-            .chain(vec2.iter())
-            .cloned() // Convert &i32 to i32
+        fn block(stop: u32) -> Vec<Elem<i32>> {
+            vec![Elem::Val(0), Elem::Val(0), Elem::Val(0), Elem::ValStop(0, stop)]
+        }
+        let ref_stream: Vec<Elem<i32>> = block(1)
+            .into_iter()
+            .chain(block(1))
+            .chain(block(2))
+            .chain(block(1))
+            .chain(block(1))
+            .chain(block(3))
             .collect();
+        ctx.add_child(GeneratorContext::new(move || ref_stream.clone().into_iter(), ref_snd));
 
-        ctx.add_child(GeneratorContext::new(move || chained.into_iter(), ref_snd));
+        ctx.add_child(ExpandRef::new(in_rcv, ref_rcv, 1, out_snd, 0));
 
-        ctx.add_child(ExpandRef::new(in_rcv, ref_rcv, 2, out_snd, 0));
+        fn expect(value: i32, stop: u32) -> Vec<Elem<i32>> {
+            vec![
+                Elem::Val(value),
+                Elem::Val(value),
+                Elem::Val(value),
+                Elem::ValStop(value, stop),
+            ]
+        }
+        let expected: Vec<Elem<i32>> = expect(1, 1)
+            .into_iter()
+            .chain(expect(2, 1))
+            .chain(expect(3, 2))
+            .chain(expect(4, 1))
+            .chain(expect(5, 1))
+            .chain(expect(6, 3))
+            .collect();
 
         ctx.add_child(ApproxCheckerContext::new(
-            || vec![Elem::Val(1), Elem::Val(1), Elem::Val(1)].into_iter(),
+            move || expected.clone().into_iter(),
             out_rcv,
             |x, y| x == y,
         ));
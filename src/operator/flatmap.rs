@@ -1,5 +1,7 @@
 // This is an operator that will be abstracted as a FlatMap operator
 
+use std::collections::HashMap;
+
 use crate::primitives::elem::{Elem, StopType};
 use crate::primitives::select::{MultiHotN, SelectAdapter};
 use crate::primitives::tile::Tile;
@@ -7,12 +9,112 @@ use dam::context_tools::*;
 use dam::types::DAMType;
 use ndarray::Array2;
 
+/// Banked-SRAM access-timing model shared by the address generators in this
+/// file: an address lands in bank `(addr / bank_stride) % num_banks`, and if
+/// two addresses land in the same bank within `window` ticks of each other,
+/// the second is delayed until `window` ticks after the first -- a 1-port-
+/// per-bank conflict, rather than the one-address-per-cycle idealization a
+/// plain `start_time + i` schedule assumes. `stall_cycles` accumulates the
+/// total delay added across every address this model has seen, for
+/// post-run bank-conflict analysis.
+#[derive(Clone, Debug)]
+pub struct BankConflictModel {
+    pub num_banks: u64,
+    pub bank_stride: u64,
+    pub window: u64,
+    pub stall_cycles: u64,
+    last_bank_tick: HashMap<u64, u64>,
+}
+
+impl BankConflictModel {
+    pub fn new(num_banks: u64, bank_stride: u64, window: u64) -> Self {
+        Self {
+            num_banks,
+            bank_stride,
+            window,
+            stall_cycles: 0,
+            last_bank_tick: HashMap::new(),
+        }
+    }
+
+    /// Builds the model from three constructor-supplied `Option`s that must
+    /// all be `Some` or all be `None` together -- `None` disables bank-
+    /// conflict modeling entirely, keeping the original one-address-per-
+    /// cycle schedule.
+    fn from_options(
+        num_banks: Option<u64>,
+        bank_stride: Option<u64>,
+        window: Option<u64>,
+        ctx_name: &str,
+        id: u32,
+    ) -> Option<Self> {
+        assert!(
+            num_banks.is_some() == bank_stride.is_some() && bank_stride.is_some() == window.is_some(),
+            "{ctx_name} {id}: num_banks, bank_stride, and bank_conflict_window must be set together"
+        );
+        num_banks.map(|n| Self::new(n, bank_stride.unwrap(), window.unwrap()))
+    }
+
+    /// Returns the tick `addr` should actually be emitted at, given it would
+    /// nominally be emitted at `nominal_tick`: `nominal_tick` itself, unless
+    /// `addr`'s bank was last accessed fewer than `window` ticks ago, in
+    /// which case it's pushed out to `window` ticks after that access and
+    /// the gap is added to `stall_cycles`.
+    fn stall_for(&mut self, addr: u64, nominal_tick: u64) -> u64 {
+        let bank = (addr / self.bank_stride) % self.num_banks;
+        let actual_tick = match self.last_bank_tick.get(&bank) {
+            Some(&last) if nominal_tick.saturating_sub(last) < self.window => last + self.window,
+            _ => nominal_tick,
+        };
+        self.stall_cycles += actual_tick - nominal_tick;
+        self.last_bank_tick.insert(bank, actual_tick);
+        actual_tick
+    }
+}
+
+#[cfg(test)]
+mod bank_conflict_tests {
+    use super::BankConflictModel;
+
+    #[test]
+    fn distinct_banks_never_stall() {
+        let mut model = BankConflictModel::new(4, 1, 3);
+        assert_eq!(model.stall_for(0, 10), 10); // bank 0
+        assert_eq!(model.stall_for(1, 11), 11); // bank 1
+        assert_eq!(model.stall_cycles, 0);
+    }
+
+    #[test]
+    fn same_bank_within_window_stalls() {
+        let mut model = BankConflictModel::new(1, 100, 3);
+        assert_eq!(model.stall_for(0, 10), 10);
+        // Same bank (num_banks == 1), only 1 tick later but window is 3.
+        assert_eq!(model.stall_for(1, 11), 13);
+        assert_eq!(model.stall_cycles, 2);
+    }
+
+    #[test]
+    fn same_bank_outside_window_does_not_stall() {
+        let mut model = BankConflictModel::new(1, 100, 3);
+        assert_eq!(model.stall_for(0, 10), 10);
+        assert_eq!(model.stall_for(5, 20), 20);
+        assert_eq!(model.stall_cycles, 0);
+    }
+}
+
 #[context_macro]
 pub struct RetileStreamify<T: Clone> {
     in_stream: Receiver<Elem<Tile<T>>>,
     out_stream: Sender<Elem<Tile<T>>>,
     split_row: bool,
     filter_mask: bool,
+    /// Block row count for 2D block-tiling mode. Set together with
+    /// `block_cols`; when both are `Some`, `run` streams out
+    /// `ceil(R/block_rows) * ceil(C/block_cols)` row-major sub-tiles instead
+    /// of using `split_row`'s 1D row/column splitting.
+    block_rows: Option<usize>,
+    /// Block column count for 2D block-tiling mode -- see `block_rows`.
+    block_cols: Option<usize>,
     id: u32,
 }
 
@@ -25,13 +127,22 @@ where
         out_stream: Sender<Elem<Tile<T>>>,
         split_row: bool,
         filter_mask: bool,
+        block_rows: Option<usize>,
+        block_cols: Option<usize>,
         id: u32,
     ) -> Self {
+        assert_eq!(
+            block_rows.is_some(),
+            block_cols.is_some(),
+            "RetileStreamify {id}: block_rows and block_cols must be set together"
+        );
         let ctx = Self {
             in_stream,
             out_stream,
             split_row,
             filter_mask,
+            block_rows,
+            block_cols,
             id,
             context_info: Default::default(),
         };
@@ -40,6 +151,86 @@ where
 
         ctx
     }
+
+    /// Splits `data` into `ceil(R/block_rows) * ceil(C/block_cols)` row-major
+    /// sub-tiles, padding ragged edge blocks out to the full `block_rows x
+    /// block_cols` shape via the `offset`/`col_offset` active-rectangle
+    /// convention (see [`Tile::new_masked`]) instead of densely zero-filling
+    /// them. The innermost stop level (1) closes each block-row; the
+    /// outermost closes the whole grid by passing `stop_level` through
+    /// unchanged on the final block, matching `retile`'s row-split
+    /// convention of forwarding the incoming stop level rather than nesting
+    /// a level on top of it. When the incoming tile carries no stop level
+    /// (`stop_level` is `None`), every block is emitted as a plain
+    /// `Elem::Val`, with no block-row stops either.
+    fn retile_2d(
+        &mut self,
+        data: &Tile<T>,
+        stop_level: Option<StopType>,
+        block_rows: usize,
+        block_cols: usize,
+    ) {
+        let rows = data.shape[0];
+        let cols = data.shape[1];
+        let num_block_rows =
+            crate::utils::calculation::div_ceil(rows as u64, block_rows as u64) as usize;
+        let num_block_cols =
+            crate::utils::calculation::div_ceil(cols as u64, block_cols as u64) as usize;
+
+        for block_row in 0..num_block_rows {
+            let row_start = block_row * block_rows;
+            let active_rows = (rows - row_start).min(block_rows);
+            let is_last_block_row = block_row + 1 == num_block_rows;
+
+            for block_col in 0..num_block_cols {
+                let col_start = block_col * block_cols;
+                let active_cols = (cols - col_start).min(block_cols);
+                let is_last_block_col = block_col + 1 == num_block_cols;
+
+                let underlying = data.underlying.as_ref().map(|arr| {
+                    arr.slice(ndarray::s![
+                        row_start..row_start + active_rows,
+                        col_start..col_start + active_cols
+                    ])
+                    .to_owned()
+                    .to_shared()
+                });
+                let out_data = Tile::<T> {
+                    shape: vec![block_rows, block_cols],
+                    bytes_per_elem: data.bytes_per_elem,
+                    read_from_mu: data.read_from_mu,
+                    underlying,
+                    offset: active_rows,
+                    col_offset: active_cols,
+                    pad: None,
+                    row_align: 1,
+                    csr: None,
+                    rle: None,
+                };
+
+                let is_last_block = is_last_block_row && is_last_block_col;
+                let elem = if stop_level.is_none() {
+                    Elem::Val(out_data)
+                } else if is_last_block {
+                    Elem::ValStop(out_data, stop_level.unwrap())
+                } else if is_last_block_col {
+                    Elem::ValStop(out_data, 1)
+                } else {
+                    Elem::Val(out_data)
+                };
+
+                self.out_stream
+                    .enqueue(
+                        &self.time,
+                        ChannelElement {
+                            time: self.time.tick(),
+                            data: elem,
+                        },
+                    )
+                    .unwrap();
+            }
+        }
+    }
     fn retile(&mut self, data: &Tile<T>, stop_level: Option<StopType>) {
         match &data.underlying {
             Some(arr) => {
@@ -148,12 +339,18 @@ where
                     time: _,
                     data: data_enum,
                 }) => match data_enum {
-                    Elem::Val(data) => {
-                        self.retile(&data, None);
-                    }
-                    Elem::ValStop(data, s) => {
-                        self.retile(&data, Some(s));
-                    }
+                    Elem::Val(data) => match (self.block_rows, self.block_cols) {
+                        (Some(block_rows), Some(block_cols)) => {
+                            self.retile_2d(&data, None, block_rows, block_cols);
+                        }
+                        _ => self.retile(&data, None),
+                    },
+                    Elem::ValStop(data, s) => match (self.block_rows, self.block_cols) {
+                        (Some(block_rows), Some(block_cols)) => {
+                            self.retile_2d(&data, Some(s), block_rows, block_cols);
+                        }
+                        _ => self.retile(&data, Some(s)),
+                    },
                 },
                 Err(_) => {
                     return;
@@ -163,12 +360,20 @@ where
     }
 }
 
+/// Emits each selected expert's `num_tile_per_expert` address tiles as a
+/// nested stream: `[[expert_0's tiles], [expert_1's tiles], ...]`. Supports
+/// top-k dispatch (`data.to_sel_vec()` returning more than one index), not
+/// just top-1 -- experts are visited in ascending index order regardless of
+/// the order `to_sel_vec` returns them in, so routing is deterministic.
 #[context_macro]
 pub struct ExpertAddrGen<SEL: Clone + SelectAdapter> {
-    in_stream: Receiver<Elem<SEL>>, // Index of the expert
+    in_stream: Receiver<Elem<SEL>>, // Selected expert indices (one or more, for top-k)
     out_stream: Sender<Elem<Tile<u64>>>,
     num_tile_per_expert: u64,
     expert_addr_base: u64,
+    /// Optional banked-SRAM timing model -- see [`BankConflictModel`]. `None`
+    /// keeps the original one-address-per-cycle schedule.
+    bank_model: Option<BankConflictModel>,
     id: u32,
 }
 
@@ -181,13 +386,24 @@ where
         out_stream: Sender<Elem<Tile<u64>>>,
         num_tile_per_expert: u64,
         expert_addr_base: u64,
+        num_banks: Option<u64>,
+        bank_stride: Option<u64>,
+        bank_conflict_window: Option<u64>,
         id: u32,
     ) -> Self {
+        let bank_model = BankConflictModel::from_options(
+            num_banks,
+            bank_stride,
+            bank_conflict_window,
+            "ExpertAddrGen",
+            id,
+        );
         let ctx = Self {
             in_stream,
             out_stream,
             num_tile_per_expert,
             expert_addr_base,
+            bank_model,
             id,
             context_info: Default::default(),
         };
@@ -210,38 +426,63 @@ where
                     data: data_enum,
                 }) => match data_enum {
                     Elem::Val(data) => {
-                        let expert_idx_list = data.to_sel_vec();
-                        assert_eq!(expert_idx_list.len(), 1);
-
-                        let expert_addr: u64 = self.expert_addr_base
-                            + expert_idx_list[0] as u64 * self.num_tile_per_expert;
-
-                        for i in 0..self.num_tile_per_expert {
-                            self.out_stream
-                                .enqueue(
-                                    &self.time,
-                                    ChannelElement {
-                                        time: self.time.tick(),
-                                        data: Elem::ValStop(
-                                            Tile::new(
-                                                Array2::from_shape_vec(
-                                                    (1, 1),
-                                                    vec![expert_addr + i],
-                                                )
-                                                .unwrap()
-                                                .to_shared(),
-                                                8,
-                                                false,
-                                            ),
-                                            if i < self.num_tile_per_expert - 1 {
-                                                1
-                                            } else {
-                                                2
-                                            },
-                                        ),
-                                    },
-                                )
-                                .unwrap();
+                        let mut expert_idx_list = data.to_sel_vec();
+                        assert!(
+                            !expert_idx_list.is_empty(),
+                            "ExpertAddrGen requires at least one selected expert"
+                        );
+                        // `to_sel_vec` doesn't guarantee an order across
+                        // `SelectAdapter` impls (e.g. `IndexN` returns
+                        // selections in whatever order they were set) --
+                        // sort so routing is deterministic regardless.
+                        expert_idx_list.sort_unstable();
+                        let num_experts = expert_idx_list.len();
+
+                        let mut tick_cursor = self.time.tick();
+                        for (expert_pos, &expert_idx) in expert_idx_list.iter().enumerate() {
+                            let expert_addr: u64 = self.expert_addr_base
+                                + expert_idx as u64 * self.num_tile_per_expert;
+                            let is_last_expert = expert_pos + 1 == num_experts;
+
+                            for i in 0..self.num_tile_per_expert {
+                                let addr = expert_addr + i;
+                                let is_last_tile_in_expert = i == self.num_tile_per_expert - 1;
+                                let tile = Tile::new(
+                                    Array2::from_shape_vec((1, 1), vec![addr])
+                                        .unwrap()
+                                        .to_shared(),
+                                    8,
+                                    false,
+                                );
+                                // Every tile but an expert's last is a plain
+                                // `Val`; an expert's last tile closes that
+                                // expert's block (level 1), except the final
+                                // expert's last tile, which also closes the
+                                // whole selection (level 2).
+                                let elem = if !is_last_tile_in_expert {
+                                    Elem::Val(tile)
+                                } else if is_last_expert {
+                                    Elem::ValStop(tile, 2)
+                                } else {
+                                    Elem::ValStop(tile, 1)
+                                };
+
+                                let emit_tick = match &mut self.bank_model {
+                                    Some(model) => model.stall_for(addr, tick_cursor),
+                                    None => tick_cursor,
+                                };
+                                tick_cursor = emit_tick + 1;
+
+                                self.out_stream
+                                    .enqueue(
+                                        &self.time,
+                                        ChannelElement {
+                                            time: emit_tick,
+                                            data: elem,
+                                        },
+                                    )
+                                    .unwrap();
+                            }
                         }
                     }
                     Elem::ValStop(_data, _s) => {
@@ -256,12 +497,38 @@ where
     }
 }
 
+/// Extracts the scalar a `Elem<Tile<u64>>` wraps, regardless of whether
+/// it's mid-stream (`Val`) or the last element of its level (`ValStop`) --
+/// used when draining a nested sub-stream (like a page table's per-block
+/// physical bases) where only the value matters, not its framing.
+fn scalar_tile_val(elem: Elem<Tile<u64>>) -> u64 {
+    match elem {
+        Elem::Val(tile) | Elem::ValStop(tile, _) => tile.underlying.as_ref().unwrap()[[0, 0]],
+    }
+}
+
 #[context_macro]
 pub struct CacheReadAddrGen {
     idx_stream: Receiver<Elem<Tile<u64>>>, // Index of the request
     seq_len_stream: Receiver<Elem<Tile<u64>>>, // Sequence length
     offset_per_idx: u64,
+    /// Paged (block-table) addressing mode: when set, each sequence's
+    /// cache occupies `page_size`-token physical blocks whose base
+    /// addresses are looked up per sequence from `page_table_stream`,
+    /// rather than one contiguous `offset_per_idx`-sized region -- models
+    /// PagedAttention-style KV caches whose blocks can be scattered.
+    /// `offset_per_idx` is unused in this mode. `None` recovers the
+    /// original contiguous addressing.
+    page_size: Option<u64>,
+    /// One element per logical block of the sequence currently being
+    /// addressed (in block order), giving that block's physical base
+    /// address -- read in full for each sequence before any of its token
+    /// addresses are emitted. Only consulted when `page_size` is set.
+    page_table_stream: Option<Receiver<Elem<Tile<u64>>>>,
     out_stream: Sender<Elem<Tile<u64>>>,
+    /// Optional banked-SRAM timing model -- see [`BankConflictModel`]. `None`
+    /// keeps the original one-address-per-cycle schedule.
+    bank_model: Option<BankConflictModel>,
     id: u32,
 }
 
@@ -270,23 +537,134 @@ impl CacheReadAddrGen {
         idx_stream: Receiver<Elem<Tile<u64>>>,
         seq_len_stream: Receiver<Elem<Tile<u64>>>,
         offset_per_idx: u64,
+        page_size: Option<u64>,
+        page_table_stream: Option<Receiver<Elem<Tile<u64>>>>,
         out_stream: Sender<Elem<Tile<u64>>>,
+        num_banks: Option<u64>,
+        bank_stride: Option<u64>,
+        bank_conflict_window: Option<u64>,
         id: u32,
     ) -> Self {
+        assert_eq!(
+            page_size.is_some(),
+            page_table_stream.is_some(),
+            "CacheReadAddrGen {id}: page_size and page_table_stream must be set together"
+        );
+        let bank_model = BankConflictModel::from_options(
+            num_banks,
+            bank_stride,
+            bank_conflict_window,
+            "CacheReadAddrGen",
+            id,
+        );
         let ctx = Self {
             idx_stream,
             seq_len_stream,
             offset_per_idx,
+            page_size,
+            page_table_stream,
             out_stream,
+            bank_model,
             id,
             context_info: Default::default(),
         };
         ctx.idx_stream.attach_receiver(&ctx);
         ctx.seq_len_stream.attach_receiver(&ctx);
+        if let Some(page_table_stream) = &ctx.page_table_stream {
+            page_table_stream.attach_receiver(&ctx);
+        }
         ctx.out_stream.attach_sender(&ctx);
 
         ctx
     }
+
+    /// Emits `seq_len_val` addresses for sequence `idx_val`, the last one
+    /// tagged with `final_stop_level` -- shared between the `Val`/`Val` and
+    /// `ValStop`/`ValStop` arms of `run`, which only differ in the stop
+    /// level their final address carries.
+    fn emit_sequence(&mut self, idx_val: u64, seq_len_val: u64, final_stop_level: StopType) {
+        // In paged mode, a sequence's whole block table is read up front,
+        // before any of its token addresses are computed, since token `i`
+        // needs `page_bases[i / page_size]` and later tokens may land in
+        // earlier blocks than earlier tokens don't (the table isn't
+        // necessarily sorted by physical address).
+        let page_bases: Option<Vec<u64>> = self.page_size.map(|page_size| {
+            let num_blocks = crate::utils::calculation::div_ceil(seq_len_val, page_size);
+            (0..num_blocks)
+                .map(|_| {
+                    scalar_tile_val(
+                        self.page_table_stream
+                            .as_mut()
+                            .unwrap()
+                            .dequeue(&self.time)
+                            .unwrap()
+                            .data,
+                    )
+                })
+                .collect()
+        });
+        let page_size = self.page_size;
+        let offset_per_idx = self.offset_per_idx;
+        let addrs: Vec<u64> = (0..seq_len_val)
+            .map(|i| match (&page_bases, page_size) {
+                (Some(page_bases), Some(page_size)) => {
+                    page_bases[(i / page_size) as usize] + i % page_size
+                }
+                _ => idx_val * offset_per_idx + i,
+            })
+            .collect();
+
+        let start_time = self.time.tick();
+        let mut tick_cursor = start_time;
+        let emit_ticks: Vec<u64> = addrs
+            .iter()
+            .enumerate()
+            .map(|(i, &addr)| {
+                let nominal_tick = start_time + i as u64;
+                tick_cursor = tick_cursor.max(nominal_tick);
+                let emit_tick = match &mut self.bank_model {
+                    Some(model) => model.stall_for(addr, tick_cursor),
+                    None => nominal_tick,
+                };
+                tick_cursor = emit_tick + 1;
+                emit_tick
+            })
+            .collect();
+
+        for (i, &addr) in addrs.iter().enumerate().take(addrs.len() - 1) {
+            self.out_stream
+                .enqueue(
+                    &self.time,
+                    ChannelElement {
+                        time: emit_ticks[i],
+                        data: Elem::Val(Tile::new(
+                            Array2::from_shape_vec((1, 1), vec![addr]).unwrap().to_shared(),
+                            8,
+                            false,
+                        )),
+                    },
+                )
+                .unwrap();
+        }
+        self.out_stream
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: *emit_ticks.last().unwrap(),
+                    data: Elem::ValStop(
+                        Tile::new(
+                            Array2::from_shape_vec((1, 1), vec![*addrs.last().unwrap()])
+                                .unwrap()
+                                .to_shared(),
+                            8,
+                            false,
+                        ),
+                        final_stop_level,
+                    ),
+                },
+            )
+            .unwrap();
+    }
 }
 
 impl Context for CacheReadAddrGen {
@@ -301,51 +679,7 @@ impl Context for CacheReadAddrGen {
                         let idx_val = idx_tile.underlying.as_ref().unwrap()[[0, 0]];
                         let seq_len_val = seq_len_tile.underlying.as_ref().unwrap()[[0, 0]];
 
-                        let start_time = self.time.tick();
-                        for i in 0..(seq_len_val - 1) {
-                            self.out_stream
-                                .enqueue(
-                                    &self.time,
-                                    ChannelElement {
-                                        time: start_time + i,
-                                        data: Elem::Val(Tile::new(
-                                            Array2::from_shape_vec(
-                                                (1, 1),
-                                                vec![idx_val * self.offset_per_idx + i as u64],
-                                            )
-                                            .unwrap()
-                                            .to_shared(),
-                                            8,
-                                            false,
-                                        )),
-                                    },
-                                )
-                                .unwrap();
-                        }
-                        self.out_stream
-                            .enqueue(
-                                &self.time,
-                                ChannelElement {
-                                    time: start_time + (seq_len_val - 1),
-                                    data: Elem::ValStop(
-                                        Tile::new(
-                                            Array2::from_shape_vec(
-                                                (1, 1),
-                                                vec![
-                                                    idx_val * self.offset_per_idx
-                                                        + (seq_len_val - 1) as u64,
-                                                ],
-                                            )
-                                            .unwrap()
-                                            .to_shared(),
-                                            8,
-                                            false,
-                                        ),
-                                        1,
-                                    ),
-                                },
-                            )
-                            .unwrap();
+                        self.emit_sequence(idx_val, seq_len_val, 1);
                     }
                     (
                         Elem::ValStop(idx_tile, idx_stop_level),
@@ -356,51 +690,7 @@ impl Context for CacheReadAddrGen {
                         let idx_val = idx_tile.underlying.as_ref().unwrap()[[0, 0]];
                         let seq_len_val = seq_len_tile.underlying.as_ref().unwrap()[[0, 0]];
 
-                        let start_time = self.time.tick();
-                        for i in 0..(seq_len_val - 1) {
-                            self.out_stream
-                                .enqueue(
-                                    &self.time,
-                                    ChannelElement {
-                                        time: start_time + i,
-                                        data: Elem::Val(Tile::new(
-                                            Array2::from_shape_vec(
-                                                (1, 1),
-                                                vec![idx_val * self.offset_per_idx + i as u64],
-                                            )
-                                            .unwrap()
-                                            .to_shared(),
-                                            8,
-                                            false,
-                                        )),
-                                    },
-                                )
-                                .unwrap();
-                        }
-                        self.out_stream
-                            .enqueue(
-                                &self.time,
-                                ChannelElement {
-                                    time: start_time + (seq_len_val - 1),
-                                    data: Elem::ValStop(
-                                        Tile::new(
-                                            Array2::from_shape_vec(
-                                                (1, 1),
-                                                vec![
-                                                    idx_val * self.offset_per_idx
-                                                        + (seq_len_val - 1) as u64,
-                                                ],
-                                            )
-                                            .unwrap()
-                                            .to_shared(),
-                                            8,
-                                            false,
-                                        ),
-                                        idx_stop_level + 1,
-                                    ),
-                                },
-                            )
-                            .unwrap();
+                        self.emit_sequence(idx_val, seq_len_val, idx_stop_level + 1);
                     }
                     _ => {
                         panic!(
@@ -617,6 +907,8 @@ mod retile_tests {
             out_data_snd,
             false,
             false,
+            None,
+            None,
             0, // id
         ));
         ctx.add_child(ApproxCheckerContext::new(
@@ -701,6 +993,8 @@ mod retile_tests {
             out_data_snd,
             true,
             false,
+            None,
+            None,
             0, // id
         ));
         ctx.add_child(ApproxCheckerContext::new(
@@ -788,6 +1082,81 @@ mod retile_tests {
             out_data_snd,
             true,
             true,
+            None,
+            None,
+            0, // id
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            || ground_truth_data.into_iter(),
+            out_data_rcv,
+            tolerance_fn,
+        ));
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn test_retile_2d() {
+        // 3x3 source tile, split into 2x2 blocks => a 2x2 grid of blocks,
+        // the bottom row and right column of which are ragged.
+        let read_from_mu = true;
+        let source: Array2<i32> = Array2::from_shape_vec((3, 3), (0..9).collect()).unwrap();
+        let in_stream_data = vec![Elem::ValStop(
+            Tile::new(source.clone().to_shared(), 4, read_from_mu),
+            2,
+        )];
+
+        fn block(
+            source: &Array2<i32>,
+            row_start: usize,
+            col_start: usize,
+            active_rows: usize,
+            active_cols: usize,
+            read_from_mu: bool,
+        ) -> Tile<i32> {
+            let underlying = source
+                .slice(ndarray::s![
+                    row_start..row_start + active_rows,
+                    col_start..col_start + active_cols
+                ])
+                .to_owned()
+                .to_shared();
+            Tile {
+                shape: vec![2, 2],
+                bytes_per_elem: 4,
+                read_from_mu,
+                underlying: Some(underlying),
+                offset: active_rows,
+                col_offset: active_cols,
+                pad: None,
+                row_align: 1,
+                csr: None,
+                rle: None,
+            }
+        }
+
+        let ground_truth_data = vec![
+            Elem::Val(block(&source, 0, 0, 2, 2, read_from_mu)),
+            Elem::ValStop(block(&source, 0, 2, 2, 1, read_from_mu), 1),
+            Elem::Val(block(&source, 2, 0, 1, 2, read_from_mu)),
+            Elem::ValStop(block(&source, 2, 2, 1, 1, read_from_mu), 2),
+        ];
+
+        let mut ctx = ProgramBuilder::default();
+        let (in_data_snd, in_data_rcv) = ctx.unbounded();
+        let (out_data_snd, out_data_rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(
+            || in_stream_data.into_iter(),
+            in_data_snd,
+        ));
+        ctx.add_child(RetileStreamify::<_>::new(
+            in_data_rcv,
+            out_data_snd,
+            false,
+            false,
+            Some(2),
+            Some(2),
             0, // id
         ));
         ctx.add_child(ApproxCheckerContext::new(
@@ -862,6 +1231,9 @@ mod tests {
             out_data_snd,
             num_tile_per_expert,
             0,
+            None,
+            None,
+            None,
             0,
         ));
 
@@ -874,23 +1246,25 @@ mod tests {
                         vec_addr
                             .iter()
                             .map(|addr| {
-                                Elem::ValStop(
-                                    Tile::new(
-                                        Array2::from_shape_vec(
-                                            (1, 1),
-                                            vec![expert_i * num_tile_per_expert + *addr as u64],
-                                        )
-                                        .unwrap()
-                                        .to_shared(),
-                                        8,
-                                        false,
-                                    ),
-                                    if *addr < num_tile_per_expert - 1 {
-                                        1
-                                    } else {
-                                        2
-                                    },
-                                )
+                                let tile = Tile::new(
+                                    Array2::from_shape_vec(
+                                        (1, 1),
+                                        vec![expert_i * num_tile_per_expert + *addr as u64],
+                                    )
+                                    .unwrap()
+                                    .to_shared(),
+                                    8,
+                                    false,
+                                );
+                                // Single selected expert per call, so its
+                                // last tile also closes the whole selection
+                                // (level 2); every earlier tile is a plain
+                                // `Val`.
+                                if *addr < num_tile_per_expert - 1 {
+                                    Elem::Val(tile)
+                                } else {
+                                    Elem::ValStop(tile, 2)
+                                }
                             })
                             .collect::<Vec<Elem<Tile<u64>>>>()
                     })
@@ -905,6 +1279,72 @@ mod tests {
             .run(Default::default());
     }
 
+    #[test]
+    fn test_expert_addr_gen_top_k() {
+        let num_tile_per_expert = 2;
+
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_data_snd, in_data_rcv) = ctx.unbounded();
+        let (out_data_snd, out_data_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![Elem::Val(MultiHotN::new(
+                    // Experts 1 and 3 both selected (top-2); `to_sel_vec`
+                    // already returns `MultiHotN` selections in ascending
+                    // index order, but the sort inside `ExpertAddrGen`
+                    // makes that guaranteed rather than incidental.
+                    vec![false, true, false, true, false],
+                    false,
+                ))]
+                .into_iter()
+            },
+            in_data_snd,
+        ));
+
+        ctx.add_child(ExpertAddrGen::<_>::new(
+            in_data_rcv,
+            out_data_snd,
+            num_tile_per_expert,
+            0,
+            None,
+            None,
+            None,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                let tile = |expert_i: u64, addr: u64| {
+                    Tile::new(
+                        Array2::from_shape_vec((1, 1), vec![expert_i * num_tile_per_expert + addr])
+                            .unwrap()
+                            .to_shared(),
+                        8,
+                        false,
+                    )
+                };
+                vec![
+                    // Expert 1's block: last tile closes the block (level 1).
+                    Elem::Val(tile(1, 0)),
+                    Elem::ValStop(tile(1, 1), 1),
+                    // Expert 3's block: last tile closes the block AND the
+                    // whole selection, since it's the final expert (level 2).
+                    Elem::Val(tile(3, 0)),
+                    Elem::ValStop(tile(3, 1), 2),
+                ]
+                .into_iter()
+            },
+            out_data_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
     #[test]
     fn test_cache_read_addr_gen() {
         // cargo test --package step_perf --lib -- operator::flatmap::tests::test_cache_read_addr_gen --exact --show-output
@@ -949,7 +1389,12 @@ mod tests {
             idx_data_rcv,
             seq_len_data_rcv,
             offset_per_idx,
+            None,
+            None,
             out_data_snd,
+            None,
+            None,
+            None,
             0,
         ));
 
@@ -991,6 +1436,83 @@ mod tests {
             .run(Default::default());
     }
 
+    #[test]
+    fn test_cache_read_addr_gen_paged() {
+        // cargo test --package step_perf --lib -- operator::flatmap::tests::test_cache_read_addr_gen_paged --exact --show-output
+        let page_size = 2;
+
+        let mut ctx = ProgramBuilder::default();
+
+        let (idx_data_snd, idx_data_rcv) = ctx.unbounded();
+        let (seq_len_data_snd, seq_len_data_rcv) = ctx.unbounded();
+        let (page_table_snd, page_table_rcv) = ctx.unbounded();
+        let (out_data_snd, out_data_rcv) = ctx.unbounded();
+
+        let tile = |v: u64| Tile::new(Array2::from_shape_vec((1, 1), vec![v]).unwrap().to_shared(), 8, false);
+
+        // Idx
+        ctx.add_child(GeneratorContext::new(
+            || vec![0, 1].into_iter().map(|i| Elem::Val(tile(i))),
+            idx_data_snd,
+        ));
+
+        // Seq len: sequence 0 has 3 tokens (2 blocks), sequence 1 has 2
+        // tokens (1 block).
+        ctx.add_child(GeneratorContext::new(
+            || vec![3, 2].into_iter().map(|i| Elem::Val(tile(i))),
+            seq_len_data_snd,
+        ));
+
+        // Page table: sequence 0's blocks live at physical bases 100, 200
+        // (scattered, not contiguous); sequence 1's single block is at 50.
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(tile(100)),
+                    Elem::ValStop(tile(200), 1),
+                    Elem::ValStop(tile(50), 1),
+                ]
+                .into_iter()
+            },
+            page_table_snd,
+        ));
+
+        ctx.add_child(CacheReadAddrGen::new(
+            idx_data_rcv,
+            seq_len_data_rcv,
+            0,
+            Some(page_size),
+            Some(page_table_rcv),
+            out_data_snd,
+            None,
+            None,
+            None,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    // Sequence 0: tokens 0,1 fall in block 0 (base 100),
+                    // token 2 falls in block 1 (base 200).
+                    Elem::Val(tile(100)),
+                    Elem::Val(tile(101)),
+                    Elem::ValStop(tile(200), 1),
+                    // Sequence 1: both tokens fall in its one block (base 50).
+                    Elem::Val(tile(50)),
+                    Elem::ValStop(tile(51), 1),
+                ]
+                .into_iter()
+            },
+            out_data_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
     #[test]
     fn test_filter_last_tile() {
         // cargo test --package step_perf --lib -- operator::flatmap::tests::test_filter_last_tile --exact --show-output
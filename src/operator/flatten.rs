@@ -1,6 +1,12 @@
+use crate::operator::rank_remap::{apply_schedule, RankSchedule};
 use crate::primitives::elem::{Elem, StopType};
 use dam::context_tools::*;
 
+/// Thin wrapper over [`crate::operator::rank_remap::RankRemap`]'s
+/// collapsing schedule: `run` builds a [`RankSchedule::collapsing`] from
+/// `min_rank`/`max_rank` and drives the same [`apply_schedule`] recurrence
+/// `RankRemap` does, so this type's public shape (and every existing
+/// caller/test) is unchanged.
 #[context_macro]
 pub struct Flatten<T: DAMType> {
     in_stream: Receiver<Elem<T>>,
@@ -35,45 +41,22 @@ where
 
 impl<T: DAMType> Context for Flatten<T> {
     fn run(&mut self) {
+        let schedule = RankSchedule::collapsing(self.min_rank, self.max_rank);
         loop {
             match self.in_stream.dequeue(&self.time) {
-                Ok(ChannelElement { time: _, data }) => match data {
-                    Elem::Val(x) => {
+                Ok(ChannelElement { time: _, data }) => {
+                    for out in apply_schedule(data, &schedule) {
                         self.out_stream
                             .enqueue(
                                 &self.time,
                                 ChannelElement {
                                     time: self.time.tick(),
-                                    data: Elem::Val(x.clone()),
+                                    data: out,
                                 },
                             )
                             .unwrap();
                     }
-                    Elem::ValStop(x, s) => {
-                        let new_rank = if s <= self.min_rank {
-                            s
-                        } else if self.min_rank < s && s <= self.max_rank {
-                            self.min_rank
-                        } else {
-                            s - (self.max_rank - self.min_rank)
-                        };
-
-                        let output_date = if new_rank == 0 {
-                            Elem::Val(x.clone())
-                        } else {
-                            Elem::ValStop(x.clone(), new_rank)
-                        };
-                        self.out_stream
-                            .enqueue(
-                                &self.time,
-                                ChannelElement {
-                                    time: self.time.tick(),
-                                    data: output_date,
-                                },
-                            )
-                            .unwrap();
-                    }
-                },
+                }
                 Err(_) => return,
             };
         }
@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+
+use crate::primitives::elem::Elem;
+use crate::primitives::select::MultiHotN;
+use crate::primitives::tile::Tile;
+use crate::utils::events::LoggableEventSimple;
+use dam::{context_tools::*, logging::LogEvent};
+
+pub struct GatherConfig {
+    pub elems_per_cycle: u64,
+    pub write_back_mu: bool,
+}
+
+/// Streaming wrapper around [`crate::functions::map_fn::gather`]: consumes a
+/// selection stream of `Elem<MultiHotN>` tokens (as produced by
+/// [`crate::utils::select_npy::read_multihot_elem_from_npy`]) against a
+/// fixed `table`, emitting one gathered `Tile<T>` per selection token. Each
+/// output token keeps the input token's `Elem::Val`/`Elem::ValStop` stop
+/// level unchanged, so a multi-dimensional selection stream's stop-level-1
+/// boundaries map one-to-one onto gathered output groups the same way
+/// [`crate::operator::dim_broadcast::DimBroadcast`] and
+/// [`crate::operator::slice::Slice`] preserve structure across their own
+/// streams.
+#[context_macro]
+pub struct Gather<E, T: DAMType> {
+    sel_stream: Receiver<Elem<MultiHotN>>,
+    out_stream: Sender<Elem<Tile<T>>>,
+    table: Tile<T>,
+    config: GatherConfig,
+    id: u32,
+    _phantom: PhantomData<E>,
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        T: DAMType + Default,
+    > Gather<E, T>
+where
+    Elem<Tile<T>>: DAMType,
+{
+    pub fn new(
+        sel_stream: Receiver<Elem<MultiHotN>>,
+        out_stream: Sender<Elem<Tile<T>>>,
+        table: Tile<T>,
+        config: GatherConfig,
+        id: u32,
+    ) -> Self {
+        let ctx = Self {
+            sel_stream,
+            out_stream,
+            table,
+            config,
+            id,
+            context_info: Default::default(),
+            _phantom: PhantomData,
+        };
+        ctx.sel_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        T: DAMType + Default,
+    > Context for Gather<E, T>
+where
+    Elem<Tile<T>>: DAMType,
+{
+    fn run(&mut self) {
+        loop {
+            let sel_elem = self.sel_stream.peek_next(&self.time);
+            let (sel, stop_lev) = match sel_elem {
+                Ok(ChannelElement {
+                    time: _,
+                    data: data_enum,
+                }) => match data_enum {
+                    Elem::Val(data) => (data, None),
+                    Elem::ValStop(data, lev) => (data, Some(lev)),
+                },
+                Err(_) => return,
+            };
+
+            let start_time = self.time.tick().time();
+
+            let (cycles, out_tile) = crate::functions::map_fn::gather(
+                &self.table,
+                &sel,
+                self.config.elems_per_cycle,
+                self.config.write_back_mu,
+            );
+            self.time.incr_cycles(cycles);
+
+            let data = match stop_lev {
+                Some(level) => Elem::ValStop(out_tile, level),
+                None => Elem::Val(out_tile),
+            };
+            self.out_stream
+                .enqueue(
+                    &self.time,
+                    ChannelElement {
+                        time: self.time.tick(),
+                        data,
+                    },
+                )
+                .unwrap();
+
+            crate::utils::events::log_event(&E::new(
+                "Gather".to_string(),
+                self.id,
+                start_time,
+                self.time.tick().time(),
+                stop_lev.is_some(),
+            ));
+
+            self.sel_stream.dequeue(&self.time).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gather, GatherConfig};
+    use crate::primitives::elem::Elem;
+    use crate::primitives::select::MultiHotN;
+    use crate::primitives::tile::Tile;
+    use crate::utils::events::SimpleEvent;
+    use dam::simulation::ProgramBuilder;
+    use dam::utility_contexts::{ApproxCheckerContext, GeneratorContext};
+
+    fn tolerance_fn(a: &Elem<Tile<f32>>, b: &Elem<Tile<f32>>) -> bool {
+        match (a, b) {
+            (Elem::Val(a_tile), Elem::Val(b_tile)) => a_tile == b_tile,
+            (Elem::ValStop(a_tile, a_level), Elem::ValStop(b_tile, b_level)) => {
+                a_tile == b_tile && a_level == b_level
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn gather_preserves_stop_level_across_selection_groups() {
+        let mut ctx = ProgramBuilder::default();
+        let (sel_snd, sel_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        let table = ndarray::Array2::from_shape_fn((5, 3), |(i, j)| (i * 10 + j) as f32);
+        let table_tile = Tile::new(table.to_shared(), 4, false);
+
+        let sel_0 = MultiHotN::new(vec![false, true, false, true, false], false);
+        let sel_1 = MultiHotN::new(vec![true, false, false, false, false], false);
+
+        let expected_0 = {
+            let arr = ndarray::Array2::from_shape_vec(
+                (2, 3),
+                vec![10.0, 11.0, 12.0, 30.0, 31.0, 32.0],
+            )
+            .unwrap();
+            Tile::new(arr.to_shared(), 4, false)
+        };
+        let expected_1 = {
+            let arr = ndarray::Array2::from_shape_vec((1, 3), vec![0.0, 1.0, 2.0]).unwrap();
+            Tile::new(arr.to_shared(), 4, false)
+        };
+
+        ctx.add_child(GeneratorContext::new(
+            move || vec![Elem::Val(sel_0.clone()), Elem::ValStop(sel_1.clone(), 1)].into_iter(),
+            sel_snd,
+        ));
+        ctx.add_child(Gather::<SimpleEvent, f32>::new(
+            sel_rcv,
+            out_snd,
+            table_tile,
+            GatherConfig {
+                elems_per_cycle: 6,
+                write_back_mu: false,
+            },
+            0,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            move || vec![Elem::Val(expected_0.clone()), Elem::ValStop(expected_1.clone(), 1)].into_iter(),
+            out_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
@@ -1,5 +1,6 @@
 use std::{marker::PhantomData, sync::Arc};
 
+use crate::memory::arbiter::MemoryArbiterRequest;
 use crate::memory::PMU_BW;
 use crate::primitives::elem::{Bufferizable, Elem};
 use crate::primitives::tile::Tile;
@@ -7,30 +8,269 @@ use crate::utils::calculation::div_ceil;
 use crate::utils::events::LoggableEventSimple;
 use dam::{context_tools::*, logging::LogEvent};
 
+/// How a map node's PMU read/write traffic shares bandwidth with itself.
+///
+/// The roofline below is optimistic by default: every load and store gets
+/// the full `PMU_BW`, and load/compute/store are assumed to fully overlap
+/// (`max([load, comp, store])`). Setting `shared_read_write_port` models a
+/// PMU with one shared port instead of independent read/write ports --
+/// loads and stores then divide `PMU_BW` by `read_fraction`/`write_fraction`
+/// and serialize against each other, so the roofline becomes
+/// `max(read_cycles + write_cycles, comp_cycles)`.
+///
+/// This is still a *static* division, not real contention: it doesn't know
+/// how many other nodes are hitting the same PMU at the same time. For
+/// that, route the node's traffic through a
+/// [`crate::memory::arbiter::MemoryArbiter`] bank instead.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PmuBandwidthConfig {
+    pub shared_read_write_port: bool,
+    pub read_fraction: f64,
+    pub write_fraction: f64,
+}
+
+impl Default for PmuBandwidthConfig {
+    fn default() -> Self {
+        Self {
+            shared_read_write_port: false,
+            read_fraction: 1.0,
+            write_fraction: 1.0,
+        }
+    }
+}
+
+/// Resolves the load/store byte totals for one node invocation against
+/// `bandwidth` into separate `(load_cycles, store_cycles)` figures. When
+/// `shared_read_write_port` is unset (the default), loads and stores each
+/// get the full `PMU_BW`; when set, they're charged against their divided
+/// share of `PMU_BW` instead, since they now contend for the same port.
+fn pmu_transfer_cycles(load_bytes: u64, store_bytes: u64, bandwidth: &PmuBandwidthConfig) -> (u64, u64) {
+    if !bandwidth.shared_read_write_port {
+        return (div_ceil(load_bytes, PMU_BW), div_ceil(store_bytes, PMU_BW));
+    }
+
+    let read_bw = ((PMU_BW as f64) * bandwidth.read_fraction).max(1.0) as u64;
+    let write_bw = ((PMU_BW as f64) * bandwidth.write_fraction).max(1.0) as u64;
+    (div_ceil(load_bytes, read_bw), div_ceil(store_bytes, write_bw))
+}
+
+/// Whether a map node's roofline assumes infinite load→compute→store
+/// overlap, or charges a one-time pipeline fill/drain cost instead.
+///
+/// [`pmu_transfer_cycles`]/arbiter transfers and `comp_cycles` are still
+/// combined with the usual `max` every invocation either way -- this only
+/// controls the *extra* latency added for the tile that fills the
+/// pipeline (the first one) and the tile that drains it (the one closing
+/// the outermost [`crate::primitives::elem::StopType`] level, i.e. whose
+/// level equals the output tile's rank).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverlapModel {
+    /// `max([load, comp, store])` every invocation, with no startup or
+    /// drain cost -- the original, optimistic steady-state-only model.
+    #[default]
+    Ideal,
+    /// Same steady-state `max` for interior tiles, but the first tile
+    /// additionally pays `load_cycles` to fill the pipeline (there's no
+    /// prior tile's compute/store to hide the load behind), and the tile
+    /// draining the outermost stop level additionally pays `store_cycles`
+    /// (there's no next tile's load/compute to hide the store behind). A
+    /// stream with a single tile pays both.
+    FillDrain,
+}
+
+/// Resolves one node invocation's roofline cycle count, dispatching to
+/// either the static [`pmu_transfer_cycles`] model or, when `arbiter` is
+/// `Some`, a [`crate::memory::arbiter::MemoryArbiter`]-arbitrated transfer
+/// for each of `load_bytes`/`store_bytes` -- shared by
+/// [`NaryMap`]/[`BinaryMap`]/[`UnaryMap`]'s `run` loops so the
+/// arbiter-vs-static branch lives in exactly one place. `overlap_model`
+/// then decides whether `is_first_tile`/`is_outermost_stop` add the
+/// [`OverlapModel::FillDrain`] startup/drain cost on top of the usual
+/// `max`; both are ignored under [`OverlapModel::Ideal`].
+#[allow(clippy::too_many_arguments)]
+fn resolve_roofline_cycles(
+    load_bytes: u64,
+    store_bytes: u64,
+    comp_cycles: u64,
+    bandwidth: &PmuBandwidthConfig,
+    arbiter: Option<&mut dyn FnMut(u64) -> u64>,
+    overlap_model: OverlapModel,
+    is_first_tile: bool,
+    is_outermost_stop: bool,
+) -> u64 {
+    let shared_port = bandwidth.shared_read_write_port;
+    let (load_cycles, store_cycles) = match arbiter {
+        Some(transfer) => (
+            if load_bytes > 0 { transfer(load_bytes) } else { 0 },
+            if store_bytes > 0 { transfer(store_bytes) } else { 0 },
+        ),
+        None => pmu_transfer_cycles(load_bytes, store_bytes, bandwidth),
+    };
+
+    let base = if shared_port {
+        (load_cycles + store_cycles).max(comp_cycles)
+    } else {
+        [load_cycles, comp_cycles, store_cycles]
+            .into_iter()
+            .max()
+            .unwrap_or(0)
+    };
+
+    match overlap_model {
+        OverlapModel::Ideal => base,
+        OverlapModel::FillDrain => {
+            let mut total = base;
+            if is_first_tile {
+                total += load_cycles;
+            }
+            if is_outermost_stop {
+                total += store_cycles;
+            }
+            total
+        }
+    }
+}
+
+/// Resolves one invocation's `(load_pj, compute_pj, store_pj)` against
+/// `energy`: `compute_pj` is charged against the predicted FLOP count
+/// `comp_cycles * compute_bw`, since individual map functions report only
+/// the cycles their computation takes, not how many FLOPs they actually
+/// performed. Split out from [`log_energy_event`] so the arithmetic itself
+/// is unit-testable without a running simulation.
+fn energy_components(
+    load_bytes: u64,
+    store_bytes: u64,
+    comp_cycles: u64,
+    compute_bw: u64,
+    energy: &EnergyConfig,
+) -> (f64, f64, f64) {
+    (
+        load_bytes as f64 * energy.pj_per_load_byte,
+        (comp_cycles * compute_bw) as f64 * energy.pj_per_flop,
+        store_bytes as f64 * energy.pj_per_store_byte,
+    )
+}
+
+/// Logs an [`crate::utils::events::EnergyEvent`] alongside a node's normal
+/// cycle event -- unlike the roofline cycles, the three components are
+/// summed, not maxed, since energy is spent regardless of how much
+/// load/compute/store overlap. See [`energy_components`] for the math.
+#[allow(clippy::too_many_arguments)]
+fn log_energy_event(
+    name: &str,
+    id: u32,
+    start_ns: u64,
+    end_ns: u64,
+    is_stop: bool,
+    load_bytes: u64,
+    store_bytes: u64,
+    comp_cycles: u64,
+    compute_bw: u64,
+    energy: &EnergyConfig,
+) {
+    let (load_pj, compute_pj, store_pj) =
+        energy_components(load_bytes, store_bytes, comp_cycles, compute_bw, energy);
+    crate::utils::events::log_event(&crate::utils::events::EnergyEvent::new(
+        name.to_string(),
+        id,
+        start_ns,
+        end_ns,
+        is_stop,
+        load_pj,
+        compute_pj,
+        store_pj,
+    ));
+}
+
+/// Per-byte/per-FLOP energy coefficients (in picojoules) for estimating a
+/// map node's load/compute/store energy alongside its roofline cycle
+/// count. All coefficients default to `0.0`, making energy accounting an
+/// opt-in overlay: a node that doesn't set these still reports cycles
+/// exactly as before, just alongside a zeroed [`crate::utils::events::EnergyEvent`].
+///
+/// `pj_per_flop` is charged against the *predicted* FLOP count
+/// `comp_cycles * compute_bw`, since individual map functions report only
+/// the cycles their computation takes, not how many FLOPs they actually
+/// performed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EnergyConfig {
+    pub pj_per_load_byte: f64,
+    pub pj_per_store_byte: f64,
+    pub pj_per_flop: f64,
+}
+
+/// Shared config for [`NaryMap`] and its [`BinaryMap`]/[`UnaryMap`]
+/// specializations.
+pub struct NaryMapConfig {
+    pub compute_bw: u64,     // FLOPs / cycle
+    pub write_back_mu: bool, // Whether the output is written to a memory unit
+    pub bandwidth: PmuBandwidthConfig,
+    /// Which [`crate::memory::arbiter::MemoryArbiter`] bank this
+    /// instance's PMU traffic counts against, when an arbiter is attached
+    /// via `with_memory_arbiter` -- unused (and harmless to leave at its
+    /// default) otherwise.
+    pub memory_unit_id: u32,
+    /// Coefficients for the [`crate::utils::events::EnergyEvent`] logged
+    /// alongside every invocation's normal cycle event -- see
+    /// [`EnergyConfig`].
+    pub energy: EnergyConfig,
+    /// Whether the roofline assumes infinite load→compute→store overlap
+    /// (the default) or charges a one-time pipeline fill/drain cost --
+    /// see [`OverlapModel`].
+    pub overlap_model: OverlapModel,
+}
+
+/// Config for [`BinaryMap`]; see [`NaryMapConfig`] for field docs.
+pub type BinaryMapConfig = NaryMapConfig;
+
 /// The function will be a binary function that returns the latency in cycles
 /// based on the size of the operands and allocated bandwidth.
 ///
 /// Assumptions used during the roofline analysis:
 /// - Each operand is stored in separate PMUs
 /// - No further on-chip tiling.
-/// - When reading from / writing to a PMU, we use the full bandwidth. This gives an optimistic (upper) bound.
-///   We can add flags to use a statically divided bandwidth to consider contention between read and write.
-///   However, as this uses a statically divided bandwidth, there are limits in terms of how accurate we can model contention.
-///   To accurately model on-chip memory accesses, one has to create a similar context as ramulator context for PMUs.
+/// - When reading from / writing to a PMU, we use the full bandwidth by
+///   default. [`BinaryMapConfig::bandwidth`] can divide it between reads and
+///   writes to model a single shared port instead (see
+///   [`PmuBandwidthConfig`]); either way this is still a static bound, not
+///   real cross-node contention -- for that, route traffic through a
+///   [`crate::memory::arbiter::MemoryArbiter`] bank.
+///
+/// For fusing three or more aligned streams (e.g. a fused multiply-add),
+/// see [`NaryMap`], which this type otherwise mirrors field-for-field.
+///
+/// Unlike [`UnaryMap`], this is *not* a thin wrapper over [`NaryMap`]: its
+/// two operands can have genuinely different element types (e.g.
+/// `proto_driver`'s `F32`/`U64` `SetOffset` dispatch, which maps a `Tile<f32>`
+/// against an offset `Tile<u64>`), whereas `NaryMap` fuses aligned streams of
+/// one common element type. `A != B` can't be expressed as a single
+/// `NaryMap<E, I, O>` instantiation, so this type keeps its own `run` loop,
+/// sharing only the cost-model/logging helpers above with [`NaryMap`]/
+/// [`UnaryMap`].
 #[context_macro]
 pub struct BinaryMap<E, A: DAMType, B: DAMType, O: DAMType> {
     in1_stream: Receiver<Elem<Tile<A>>>,
     in2_stream: Receiver<Elem<Tile<B>>>,
     out_stream: Sender<Elem<Tile<O>>>,
     func: Arc<dyn Fn(&Tile<A>, &Tile<B>, u64, bool) -> (u64, Tile<O>) + Send + Sync>, // bytes, bytes, FLOPs per cycle -> cycles
-    compute_bw: u64,     // FLOPs / cycle
-    write_back_mu: bool, // Whether the output is written to a memory unit
+    config: BinaryMapConfig,
+    /// When attached via [`Self::with_memory_arbiter`], every load/store
+    /// byte-transfer is arbitrated against a shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] bank instead of the static
+    /// [`PmuBandwidthConfig`] model, so this instance's latency reflects
+    /// how many other nodes are hitting the same PMU at the same time.
+    arbiter_req_snd: Option<Sender<MemoryArbiterRequest>>,
+    arbiter_resp_rcv: Option<Receiver<u64>>,
+    /// Whether this instance has processed a tile yet -- used by
+    /// [`OverlapModel::FillDrain`] to charge the pipeline-fill cost only
+    /// on the first one.
+    first_tile_seen: bool,
     id: u32,
     _phantom: PhantomData<E>,
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         A: DAMType,
         B: DAMType,
         O: DAMType,
@@ -45,8 +285,7 @@ where
         in2_stream: Receiver<Elem<Tile<B>>>,
         out_stream: Sender<Elem<Tile<O>>>,
         func: Arc<dyn Fn(&Tile<A>, &Tile<B>, u64, bool) -> (u64, Tile<O>) + Send + Sync>, // bytes, bytes, FLOPs per cycle -> cycles
-        compute_bw: u64, // FLOPs / cycle
-        write_back_mu: bool,
+        config: BinaryMapConfig,
         id: u32,
     ) -> Self {
         let ctx = Self {
@@ -54,8 +293,10 @@ where
             in2_stream,
             out_stream,
             func,
-            compute_bw,
-            write_back_mu,
+            config,
+            arbiter_req_snd: None,
+            arbiter_resp_rcv: None,
+            first_tile_seen: false,
             id,
             context_info: Default::default(),
             _phantom: PhantomData,
@@ -66,10 +307,57 @@ where
 
         ctx
     }
+
+    /// Routes this instance's load/store byte-transfers through a shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] on bank
+    /// `config.memory_unit_id`, instead of the static [`PmuBandwidthConfig`]
+    /// model it otherwise falls back to.
+    pub fn with_memory_arbiter(
+        mut self,
+        req_snd: Sender<MemoryArbiterRequest>,
+        resp_rcv: Receiver<u64>,
+    ) -> Self {
+        req_snd.attach_sender(&self);
+        resp_rcv.attach_receiver(&self);
+        self.arbiter_req_snd = Some(req_snd);
+        self.arbiter_resp_rcv = Some(resp_rcv);
+        self
+    }
+
+    /// Cycles to transfer `bytes` through the shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] bank attached via
+    /// [`Self::with_memory_arbiter`]. Only call when that's actually
+    /// attached (see [`Self::has_memory_arbiter`]).
+    fn arbitrated_transfer_cycles(&mut self, bytes: u64) -> u64 {
+        let req_snd = self.arbiter_req_snd.as_ref().unwrap();
+        let resp_rcv = self.arbiter_resp_rcv.as_ref().unwrap();
+        let requested_at = self.time.tick().time();
+        req_snd
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data: MemoryArbiterRequest {
+                        unit_id: self.config.memory_unit_id,
+                        bytes,
+                        requested_at,
+                    },
+                },
+            )
+            .unwrap();
+        match resp_rcv.dequeue(&self.time) {
+            Ok(ChannelElement { data, .. }) => data,
+            Err(_) => panic!("BinaryMap: memory arbiter closed its response channel"),
+        }
+    }
+
+    fn has_memory_arbiter(&self) -> bool {
+        self.arbiter_req_snd.is_some()
+    }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         A: DAMType,
         B: DAMType,
         O: DAMType,
@@ -123,25 +411,55 @@ where
 
             let start_time = self.time.tick().time();
 
-            let mut load_cycle: u64 = 0;
+            // Both operands may live on the same PMU, so their byte traffic
+            // is summed before dividing rather than ceil'd independently.
+            let mut load_bytes: u64 = 0;
             if tile1.read_from_mu {
-                load_cycle += div_ceil(tile1.size_in_bytes() as u64, PMU_BW);
+                load_bytes += tile1.size_in_bytes() as u64;
             }
             if tile2.read_from_mu {
-                load_cycle += div_ceil(tile2.size_in_bytes() as u64, PMU_BW);
+                load_bytes += tile2.size_in_bytes() as u64;
             }
-            let (comp_cycles, out_tile) =
-                (self.func)(&tile1, &tile2, self.compute_bw, self.write_back_mu);
-            let store_cycles = if self.write_back_mu {
-                div_ceil(out_tile.size_in_bytes() as u64, PMU_BW)
+            let (comp_cycles, out_tile) = (self.func)(
+                &tile1,
+                &tile2,
+                self.config.compute_bw,
+                self.config.write_back_mu,
+            );
+            let store_bytes = if self.config.write_back_mu {
+                out_tile.size_in_bytes() as u64
             } else {
                 0_u64
             };
 
-            let roofline_cycles = [load_cycle, comp_cycles, store_cycles]
-                .into_iter()
-                .max()
-                .unwrap_or(0);
+            let bandwidth = self.config.bandwidth;
+            let overlap_model = self.config.overlap_model;
+            let is_first_tile = !self.first_tile_seen;
+            let is_outermost_stop = stop_lev.is_some_and(|lev| lev as usize == out_tile.shape.len());
+            self.first_tile_seen = true;
+            let roofline_cycles = if self.has_memory_arbiter() {
+                resolve_roofline_cycles(
+                    load_bytes,
+                    store_bytes,
+                    comp_cycles,
+                    &bandwidth,
+                    Some(&mut |bytes: u64| self.arbitrated_transfer_cycles(bytes)),
+                    overlap_model,
+                    is_first_tile,
+                    is_outermost_stop,
+                )
+            } else {
+                resolve_roofline_cycles(
+                    load_bytes,
+                    store_bytes,
+                    comp_cycles,
+                    &bandwidth,
+                    None,
+                    overlap_model,
+                    is_first_tile,
+                    is_outermost_stop,
+                )
+            };
 
             self.time.incr_cycles(roofline_cycles);
 
@@ -159,14 +477,26 @@ where
                 )
                 .unwrap();
 
-            dam::logging::log_event(&E::new(
+            let end_time = self.time.tick().time();
+            crate::utils::events::log_event(&E::new(
                 "BinaryMap".to_string(),
                 self.id,
                 start_time,
-                self.time.tick().time(),
+                end_time,
                 stop_lev != None,
-            ))
-            .unwrap();
+            ));
+            log_energy_event(
+                "BinaryMap",
+                self.id,
+                start_time,
+                end_time,
+                stop_lev != None,
+                load_bytes,
+                store_bytes,
+                comp_cycles,
+                self.config.compute_bw,
+                &self.config.energy,
+            );
 
             self.in1_stream.dequeue(&self.time).unwrap();
             self.in2_stream.dequeue(&self.time).unwrap();
@@ -174,23 +504,21 @@ where
     }
 }
 
-pub struct UnaryMapConfig {
-    pub compute_bw: u64,     // FLOPs / cycle
-    pub write_back_mu: bool, // Whether the output is written to a memory unit
-}
+/// Config for [`UnaryMap`]; see [`NaryMapConfig`] for field docs.
+pub type UnaryMapConfig = NaryMapConfig;
 
+/// One-operand specialization of [`NaryMap`] -- a single input stream is
+/// never heterogeneous the way [`BinaryMap`]'s two operands can be (see its
+/// doc comment), so this is a genuine thin wrapper: `func`'s one-operand
+/// signature is adapted into [`NaryMap`]'s slice-based one, and everything
+/// else, including `run`, delegates straight to the inner instance.
 #[context_macro]
 pub struct UnaryMap<E, T: DAMType, OT: DAMType> {
-    in_stream: Receiver<Elem<Tile<T>>>,
-    out_stream: Sender<Elem<Tile<OT>>>,
-    func: Arc<dyn Fn(&Tile<T>, u64, bool) -> (u64, Tile<OT>) + Send + Sync>, // bytes, FLOPs per cycle -> cycles
-    config: UnaryMapConfig,
-    id: u32,
-    _phantom: PhantomData<E>,
+    inner: NaryMap<E, T, OT>,
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType,
         OT: DAMType,
     > UnaryMap<E, T, OT>
@@ -204,68 +532,272 @@ where
         func: Arc<dyn Fn(&Tile<T>, u64, bool) -> (u64, Tile<OT>) + Send + Sync>, // bytes, FLOPs per cycle -> cycles
         config: UnaryMapConfig,
         id: u32,
+    ) -> Self {
+        let nary_func: Arc<dyn Fn(&[Tile<T>], u64, bool) -> (u64, Tile<OT>) + Send + Sync> =
+            Arc::new(move |tiles, compute_bw, write_back_mu| func(&tiles[0], compute_bw, write_back_mu));
+        Self {
+            context_info: Default::default(),
+            inner: NaryMap::new_named("UnaryMap", vec![in_stream], out_stream, nary_func, config, id),
+        }
+    }
+
+    /// Routes this instance's load/store byte-transfers through a shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] on bank
+    /// `config.memory_unit_id`, instead of the static [`PmuBandwidthConfig`]
+    /// model it otherwise falls back to.
+    pub fn with_memory_arbiter(
+        mut self,
+        req_snd: Sender<MemoryArbiterRequest>,
+        resp_rcv: Receiver<u64>,
+    ) -> Self {
+        self.inner = self.inner.with_memory_arbiter(req_snd, resp_rcv);
+        self
+    }
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        T: DAMType,
+        OT: DAMType,
+    > Context for UnaryMap<E, T, OT>
+where
+    Elem<Tile<T>>: DAMType,
+    Elem<Tile<OT>>: DAMType,
+{
+    fn run(&mut self) {
+        self.inner.run()
+    }
+}
+
+/// Elementwise map over an arbitrary number of aligned input streams, e.g.
+/// a fused multiply-add over three operands -- the N-ary generalization of
+/// [`UnaryMap`], which wraps this type directly since one operand is never
+/// heterogeneous. [`BinaryMap`] keeps its own `run` loop instead, since its
+/// two operands can have different element types (see its doc comment).
+///
+/// Same roofline assumptions as [`BinaryMap`]: each operand lives in its
+/// own PMU by default (their byte traffic is summed before dividing), `func`
+/// gets the full `PMU_BW` unless [`NaryMapConfig::bandwidth`] says
+/// otherwise, and real cross-node contention needs a
+/// [`crate::memory::arbiter::MemoryArbiter`] bank via
+/// [`Self::with_memory_arbiter`].
+#[context_macro]
+pub struct NaryMap<E, I: DAMType, O: DAMType> {
+    in_streams: Vec<Receiver<Elem<Tile<I>>>>,
+    out_stream: Sender<Elem<Tile<O>>>,
+    func: Arc<dyn Fn(&[Tile<I>], u64, bool) -> (u64, Tile<O>) + Send + Sync>, // bytes, FLOPs per cycle -> cycles
+    config: NaryMapConfig,
+    /// When attached via [`Self::with_memory_arbiter`], every load/store
+    /// byte-transfer is arbitrated against a shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] bank instead of the static
+    /// [`PmuBandwidthConfig`] model, so this instance's latency reflects
+    /// how many other nodes are hitting the same PMU at the same time.
+    arbiter_req_snd: Option<Sender<MemoryArbiterRequest>>,
+    arbiter_resp_rcv: Option<Receiver<u64>>,
+    /// Whether this instance has processed a tile yet -- used by
+    /// [`OverlapModel::FillDrain`] to charge the pipeline-fill cost only
+    /// on the first one.
+    first_tile_seen: bool,
+    id: u32,
+    /// Event/energy-event name this instance logs under -- `"NaryMap"` for
+    /// direct use, or `"UnaryMap"` when constructed through [`UnaryMap`]'s
+    /// wrapper via [`Self::new_named`], so that specialization keeps its
+    /// original event name.
+    event_name: &'static str,
+    _phantom: PhantomData<E>,
+}
+
+impl<
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        I: DAMType,
+        O: DAMType,
+    > NaryMap<E, I, O>
+where
+    Elem<Tile<I>>: DAMType,
+    Elem<Tile<O>>: DAMType,
+{
+    pub fn new(
+        in_streams: Vec<Receiver<Elem<Tile<I>>>>,
+        out_stream: Sender<Elem<Tile<O>>>,
+        func: Arc<dyn Fn(&[Tile<I>], u64, bool) -> (u64, Tile<O>) + Send + Sync>, // bytes, FLOPs per cycle -> cycles
+        config: NaryMapConfig,
+        id: u32,
+    ) -> Self {
+        Self::new_named("NaryMap", in_streams, out_stream, func, config, id)
+    }
+
+    /// Like [`Self::new`], but logging under the given `event_name` instead
+    /// of the hardcoded `"NaryMap"` -- used by [`UnaryMap`]'s wrapper so its
+    /// instances keep logging under their own name.
+    fn new_named(
+        event_name: &'static str,
+        in_streams: Vec<Receiver<Elem<Tile<I>>>>,
+        out_stream: Sender<Elem<Tile<O>>>,
+        func: Arc<dyn Fn(&[Tile<I>], u64, bool) -> (u64, Tile<O>) + Send + Sync>,
+        config: NaryMapConfig,
+        id: u32,
     ) -> Self {
         let ctx = Self {
-            in_stream,
+            in_streams,
             out_stream,
             func,
             config,
+            arbiter_req_snd: None,
+            arbiter_resp_rcv: None,
+            first_tile_seen: false,
             id,
+            event_name,
             context_info: Default::default(),
             _phantom: PhantomData,
         };
-        ctx.in_stream.attach_receiver(&ctx);
+        for in_stream in &ctx.in_streams {
+            in_stream.attach_receiver(&ctx);
+        }
         ctx.out_stream.attach_sender(&ctx);
 
         ctx
     }
+
+    /// Routes this instance's load/store byte-transfers through a shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] on bank
+    /// `config.memory_unit_id`, instead of the static [`PmuBandwidthConfig`]
+    /// model it otherwise falls back to.
+    pub fn with_memory_arbiter(
+        mut self,
+        req_snd: Sender<MemoryArbiterRequest>,
+        resp_rcv: Receiver<u64>,
+    ) -> Self {
+        req_snd.attach_sender(&self);
+        resp_rcv.attach_receiver(&self);
+        self.arbiter_req_snd = Some(req_snd);
+        self.arbiter_resp_rcv = Some(resp_rcv);
+        self
+    }
+
+    /// Cycles to transfer `bytes` through the shared
+    /// [`crate::memory::arbiter::MemoryArbiter`] bank attached via
+    /// [`Self::with_memory_arbiter`]. Only call when that's actually
+    /// attached (see [`Self::has_memory_arbiter`]).
+    fn arbitrated_transfer_cycles(&mut self, bytes: u64) -> u64 {
+        let req_snd = self.arbiter_req_snd.as_ref().unwrap();
+        let resp_rcv = self.arbiter_resp_rcv.as_ref().unwrap();
+        let requested_at = self.time.tick().time();
+        req_snd
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data: MemoryArbiterRequest {
+                        unit_id: self.config.memory_unit_id,
+                        bytes,
+                        requested_at,
+                    },
+                },
+            )
+            .unwrap();
+        match resp_rcv.dequeue(&self.time) {
+            Ok(ChannelElement { data, .. }) => data,
+            Err(_) => panic!("NaryMap: memory arbiter closed its response channel"),
+        }
+    }
+
+    fn has_memory_arbiter(&self) -> bool {
+        self.arbiter_req_snd.is_some()
+    }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
-        T: DAMType,
-        OT: DAMType,
-    > Context for UnaryMap<E, T, OT>
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
+        I: DAMType,
+        O: DAMType,
+    > Context for NaryMap<E, I, O>
 where
-    Elem<Tile<T>>: DAMType,
-    Elem<Tile<OT>>: DAMType,
+    Elem<Tile<I>>: DAMType,
+    Elem<Tile<O>>: DAMType,
 {
     fn run(&mut self) {
         loop {
-            let in_elem = self.in_stream.peek_next(&self.time);
-            let (in_tile, stop_lev) = match in_elem {
-                Ok(ChannelElement {
-                    time: _,
-                    data: data_enum,
-                }) => match data_enum {
-                    Elem::Val(data) => (data, None),
-                    Elem::ValStop(data, lev) => (data, Some(lev)),
-                },
-                Err(_) => {
-                    return; // Stream closed
+            let peeks: Vec<_> = self
+                .in_streams
+                .iter()
+                .map(|s| s.peek_next(&self.time))
+                .collect();
+
+            if peeks.iter().all(|p| p.is_err()) {
+                return;
+            }
+            if let Some(Err(e)) = peeks.iter().find(|p| p.is_err()) {
+                panic!("node id ({}): One stream closed earlier: {}", self.id, e);
+            }
+
+            let mut tiles: Vec<Tile<I>> = Vec::with_capacity(peeks.len());
+            let mut stop_lev: Option<crate::primitives::elem::StopType> = None;
+            for (i, peek) in peeks.into_iter().enumerate() {
+                let ChannelElement { data, .. } = peek.unwrap();
+                let (tile, lev) = match data {
+                    Elem::Val(tile) => (tile, None),
+                    Elem::ValStop(tile, lev) => (tile, Some(lev)),
+                };
+                if i == 0 {
+                    stop_lev = lev;
+                } else if stop_lev != lev {
+                    panic!(
+                        "node id ({}): The input streams' shapes don't match!",
+                        self.id
+                    );
                 }
-            };
+                tiles.push(tile);
+            }
 
             let start_time = self.time.tick().time();
-            let load_cycles = if in_tile.read_from_mu {
-                div_ceil(in_tile.size_in_bytes() as u64, PMU_BW)
+
+            // All operands may live on the same PMU, so their byte traffic
+            // is summed before dividing rather than ceil'd independently.
+            let load_bytes: u64 = tiles
+                .iter()
+                .filter(|tile| tile.read_from_mu)
+                .map(|tile| tile.size_in_bytes() as u64)
+                .sum();
+
+            let (comp_cycles, out_tile) =
+                (self.func)(&tiles, self.config.compute_bw, self.config.write_back_mu);
+            let store_bytes = if self.config.write_back_mu {
+                out_tile.size_in_bytes() as u64
             } else {
-                0
+                0_u64
             };
 
-            let (comp_cycles, out_tile) =
-                (self.func)(&in_tile, self.config.compute_bw, self.config.write_back_mu);
-            let store_cycles = if self.config.write_back_mu {
-                div_ceil(out_tile.size_in_bytes() as u64, PMU_BW)
+            let bandwidth = self.config.bandwidth;
+            let overlap_model = self.config.overlap_model;
+            let is_first_tile = !self.first_tile_seen;
+            let is_outermost_stop = stop_lev.is_some_and(|lev| lev as usize == out_tile.shape.len());
+            self.first_tile_seen = true;
+            let roofline_cycles = if self.has_memory_arbiter() {
+                resolve_roofline_cycles(
+                    load_bytes,
+                    store_bytes,
+                    comp_cycles,
+                    &bandwidth,
+                    Some(&mut |bytes: u64| self.arbitrated_transfer_cycles(bytes)),
+                    overlap_model,
+                    is_first_tile,
+                    is_outermost_stop,
+                )
             } else {
-                0
+                resolve_roofline_cycles(
+                    load_bytes,
+                    store_bytes,
+                    comp_cycles,
+                    &bandwidth,
+                    None,
+                    overlap_model,
+                    is_first_tile,
+                    is_outermost_stop,
+                )
             };
 
-            let roofline_cycles = [load_cycles, comp_cycles, store_cycles]
-                .into_iter()
-                .max()
-                .unwrap_or(0);
             self.time.incr_cycles(roofline_cycles);
+
             let data = match stop_lev {
                 Some(level) => Elem::ValStop(out_tile, level),
                 None => Elem::Val(out_tile),
@@ -279,26 +811,43 @@ where
                     },
                 )
                 .unwrap();
-            dam::logging::log_event(&E::new(
-                "UnaryMap".to_string(),
+
+            let end_time = self.time.tick().time();
+            crate::utils::events::log_event(&E::new(
+                self.event_name.to_string(),
                 self.id,
                 start_time,
-                self.time.tick().time(),
+                end_time,
                 stop_lev != None,
-            ))
-            .unwrap();
-            self.in_stream.dequeue(&self.time).unwrap();
+            ));
+            log_energy_event(
+                self.event_name,
+                self.id,
+                start_time,
+                end_time,
+                stop_lev != None,
+                load_bytes,
+                store_bytes,
+                comp_cycles,
+                self.config.compute_bw,
+                &self.config.energy,
+            );
+
+            for in_stream in &self.in_streams {
+                in_stream.dequeue(&self.time).unwrap();
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::{
         functions::map_fn,
-        operator::map::BinaryMap,
+        operator::map::{BinaryMap, BinaryMapConfig, NaryMap, NaryMapConfig},
         primitives::{elem::Elem, tile::Tile},
-        utils::events::SimpleEvent,
+        utils::{calculation::div_ceil, events::SimpleEvent},
     };
     use dam::{
         simulation::ProgramBuilder,
@@ -372,9 +921,15 @@ mod tests {
             in2_data_rcv,
             out_data_snd,
             Arc::new(map_fn::mul),
-            1024, // FLOPs per cycle
-            true, // write_back_mu
-            0,    // id
+            BinaryMapConfig {
+                compute_bw: 1024,
+                write_back_mu: true,
+                bandwidth: Default::default(),
+                memory_unit_id: 0,
+                energy: Default::default(),
+                overlap_model: Default::default(),
+            },
+            0, // id
         ));
 
         ctx.add_child(ApproxCheckerContext::new(
@@ -386,4 +941,238 @@ mod tests {
             .unwrap()
             .run(Default::default());
     }
+
+    fn fma(tiles: &[Tile<i32>], flop_per_cycle: u64, write_back_mu: bool) -> (u64, Tile<i32>) {
+        let (a, b, c) = (&tiles[0], &tiles[1], &tiles[2]);
+        let n_elems = a.shape.iter().product::<usize>() as u64;
+        let cycles = div_ceil(n_elems, flop_per_cycle);
+        match (&a.underlying, &b.underlying, &c.underlying) {
+            (Some(a_arr), Some(b_arr), Some(c_arr)) => {
+                let out_arr = a_arr * b_arr + c_arr;
+                (
+                    cycles,
+                    Tile::new(out_arr.to_shared(), a.bytes_per_elem, write_back_mu),
+                )
+            }
+            _ => panic!("expected populated operands in test"),
+        }
+    }
+
+    #[test]
+    fn nary_map_fused_multiply_add() {
+        // Three aligned 2x2 streams: out = a * b + c.
+        let read_from_mu = true;
+        let a_arrays: Vec<Array2<i32>> = (0..4)
+            .map(|i| Array2::from_shape_vec((2, 2), vec![i as i32; 4]).unwrap())
+            .collect();
+        let b_arrays: Vec<Array2<i32>> = (0..4)
+            .map(|i| Array2::from_shape_vec((2, 2), vec![(i + 1) as i32; 4]).unwrap())
+            .collect();
+        let c_arrays: Vec<Array2<i32>> = (0..4)
+            .map(|i| Array2::from_shape_vec((2, 2), vec![(i + 2) as i32; 4]).unwrap())
+            .collect();
+
+        let make_stream = |arrays: &[Array2<i32>]| -> Vec<Elem<Tile<i32>>> {
+            arrays
+                .iter()
+                .map(|arr| Elem::Val(Tile::new(arr.clone().into(), 4, read_from_mu)))
+                .collect()
+        };
+
+        let expected_out_stream_data: Vec<Elem<Tile<i32>>> = a_arrays
+            .iter()
+            .zip(b_arrays.iter())
+            .zip(c_arrays.iter())
+            .map(|((a, b), c)| {
+                let tiles = vec![
+                    Tile::new(a.clone().into(), 4, read_from_mu),
+                    Tile::new(b.clone().into(), 4, read_from_mu),
+                    Tile::new(c.clone().into(), 4, read_from_mu),
+                ];
+                Elem::Val(fma(&tiles, 4, true).1)
+            })
+            .collect();
+
+        let mut ctx = ProgramBuilder::default();
+        let (in1_snd, in1_rcv) = ctx.unbounded();
+        let (in2_snd, in2_rcv) = ctx.unbounded();
+        let (in3_snd, in3_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        let in1_stream_data = make_stream(&a_arrays);
+        let in2_stream_data = make_stream(&b_arrays);
+        let in3_stream_data = make_stream(&c_arrays);
+        ctx.add_child(GeneratorContext::new(
+            || in1_stream_data.into_iter(),
+            in1_snd,
+        ));
+        ctx.add_child(GeneratorContext::new(
+            || in2_stream_data.into_iter(),
+            in2_snd,
+        ));
+        ctx.add_child(GeneratorContext::new(
+            || in3_stream_data.into_iter(),
+            in3_snd,
+        ));
+        ctx.add_child(NaryMap::<SimpleEvent, _, _>::new(
+            vec![in1_rcv, in2_rcv, in3_rcv],
+            out_snd,
+            Arc::new(fma),
+            NaryMapConfig {
+                compute_bw: 4,
+                write_back_mu: true,
+                bandwidth: Default::default(),
+                memory_unit_id: 0,
+                energy: Default::default(),
+                overlap_model: Default::default(),
+            },
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || expected_out_stream_data.into_iter(),
+            out_rcv,
+            tolerance_fn,
+        ));
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn pmu_transfer_cycles_shared_port_divides_and_serializes() {
+        let bandwidth = PmuBandwidthConfig::default();
+        // Independent ports (the default): each side gets the full PMU_BW.
+        assert_eq!(
+            pmu_transfer_cycles(PMU_BW, PMU_BW, &bandwidth),
+            (1, 1),
+            "independent ports should charge each side the full bandwidth"
+        );
+
+        // A shared port split 50/50 halves each side's bandwidth, so the
+        // same byte counts now take twice as many cycles each.
+        let shared = PmuBandwidthConfig {
+            shared_read_write_port: true,
+            read_fraction: 0.5,
+            write_fraction: 0.5,
+        };
+        assert_eq!(pmu_transfer_cycles(PMU_BW, PMU_BW, &shared), (2, 2));
+
+        // resolve_roofline_cycles must also *serialize* (sum) load+store
+        // against compute under a shared port, instead of maxing all three.
+        let separate_cycles = resolve_roofline_cycles(
+            PMU_BW,
+            PMU_BW,
+            1,
+            &bandwidth,
+            None,
+            OverlapModel::Ideal,
+            false,
+            false,
+        );
+        assert_eq!(separate_cycles, 1, "independent ports: max(load, comp, store)");
+
+        let shared_cycles = resolve_roofline_cycles(
+            PMU_BW, PMU_BW, 1, &shared, None, OverlapModel::Ideal, false, false,
+        );
+        assert_eq!(
+            shared_cycles, 4,
+            "shared port: load_cycles + store_cycles, maxed against comp_cycles"
+        );
+    }
+
+    #[test]
+    fn energy_components_sums_load_compute_store() {
+        let energy = EnergyConfig {
+            pj_per_load_byte: 0.5,
+            pj_per_store_byte: 0.25,
+            pj_per_flop: 2.0,
+        };
+        let (load_pj, compute_pj, store_pj) = energy_components(100, 40, 8, 4, &energy);
+        assert_eq!(load_pj, 50.0); // 100 bytes * 0.5 pJ/byte
+        assert_eq!(compute_pj, 64.0); // (8 cycles * 4 FLOPs/cycle) * 2.0 pJ/FLOP
+        assert_eq!(store_pj, 10.0); // 40 bytes * 0.25 pJ/byte
+
+        // Zero coefficients (the default) keep energy accounting a true
+        // no-op overlay, regardless of byte/cycle counts.
+        assert_eq!(
+            energy_components(100, 40, 8, 4, &EnergyConfig::default()),
+            (0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn overlap_model_fill_drain_charges_startup_and_drain_once() {
+        let bandwidth = PmuBandwidthConfig::default();
+        // load=2 cycles, comp=1 cycle, store=3 cycles -> steady-state max is 3.
+        let load_bytes = PMU_BW * 2;
+        let store_bytes = PMU_BW * 3;
+        let comp_cycles = 1;
+
+        let steady_state = resolve_roofline_cycles(
+            load_bytes,
+            store_bytes,
+            comp_cycles,
+            &bandwidth,
+            None,
+            OverlapModel::Ideal,
+            false,
+            false,
+        );
+        assert_eq!(steady_state, 3);
+
+        // An interior tile (neither first nor draining the outermost stop
+        // level) pays no extra fill/drain cost even under FillDrain.
+        let interior = resolve_roofline_cycles(
+            load_bytes,
+            store_bytes,
+            comp_cycles,
+            &bandwidth,
+            None,
+            OverlapModel::FillDrain,
+            false,
+            false,
+        );
+        assert_eq!(interior, steady_state);
+
+        // The first tile additionally pays load_cycles to fill the pipeline.
+        let first_tile = resolve_roofline_cycles(
+            load_bytes,
+            store_bytes,
+            comp_cycles,
+            &bandwidth,
+            None,
+            OverlapModel::FillDrain,
+            true,
+            false,
+        );
+        assert_eq!(first_tile, steady_state + 2);
+
+        // The tile draining the outermost stop level additionally pays
+        // store_cycles.
+        let draining_tile = resolve_roofline_cycles(
+            load_bytes,
+            store_bytes,
+            comp_cycles,
+            &bandwidth,
+            None,
+            OverlapModel::FillDrain,
+            false,
+            true,
+        );
+        assert_eq!(draining_tile, steady_state + 3);
+
+        // A single-tile stream is both first and draining, so it pays both.
+        let single_tile = resolve_roofline_cycles(
+            load_bytes,
+            store_bytes,
+            comp_cycles,
+            &bandwidth,
+            None,
+            OverlapModel::FillDrain,
+            true,
+            true,
+        );
+        assert_eq!(single_tile, steady_state + 2 + 3);
+    }
 }
@@ -25,7 +25,7 @@ pub struct BinaryMapAccum<E, T: DAMType, OT: DAMType> {
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType,
         OT: DAMType,
     > BinaryMapAccum<E, T, OT>
@@ -94,14 +94,13 @@ where
         self.in2_stream.dequeue(&self.time).unwrap();
 
         // Logging
-        dam::logging::log_event(&E::new(
+        crate::utils::events::log_event(&E::new(
             "BinaryMapAccum".to_string(),
             self.id,
             self.time.tick().time() - roofline_cycles,
             self.time.tick().time(),
             false,
-        ))
-        .unwrap();
+        ));
     }
 
     fn process_map_accum_init(
@@ -148,21 +147,20 @@ where
         self.in2_stream.dequeue(&self.time).unwrap();
 
         // Logging
-        dam::logging::log_event(&E::new(
+        crate::utils::events::log_event(&E::new(
             "BinaryMapAccum".to_string(),
             self.id,
             self.time.tick().time() - roofline_cycles,
             self.time.tick().time(),
             !is_reduction_rank,
-        ))
-        .unwrap();
+        ));
 
         out_tile
     }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: DAMType,
         OT: DAMType,
     > Context for BinaryMapAccum<E, T, OT>
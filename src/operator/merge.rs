@@ -0,0 +1,223 @@
+use crate::primitives::elem::{Elem, StopType};
+use dam::context_tools::*;
+
+/// Collapses two adjacent axes into one by removing a single stop-token
+/// level: any incoming `ValStop(x, s)` where `s == merge_dim` loses its
+/// boundary and is re-emitted as `Elem::Val(x)`, while levels `s >
+/// merge_dim` shift down by one so the outer hierarchy closes up over the
+/// gap. This undoes [`super::reshape::Reshape`]'s `split_dim == 0` split,
+/// which inserts stop level 1: that split followed by a `Merge` at
+/// `merge_dim == 1` is the identity on the token stream.
+#[context_macro]
+pub struct Merge<InputType: Clone> {
+    in_stream: Receiver<Elem<InputType>>,
+    out_stream: Sender<Elem<InputType>>,
+    merge_dim: StopType,
+    id: u32,
+}
+
+impl<InputType: DAMType> Merge<InputType>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<InputType>>,
+        out_stream: Sender<Elem<InputType>>,
+        merge_dim: StopType,
+        id: u32,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            merge_dim,
+            id,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<InputType: DAMType> Context for Merge<InputType> {
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => {
+                    let out_elem = match data {
+                        Elem::Val(x) => Elem::Val(x),
+                        Elem::ValStop(x, s) if s == self.merge_dim => Elem::Val(x),
+                        Elem::ValStop(x, s) if s > self.merge_dim => Elem::ValStop(x, s - 1),
+                        Elem::ValStop(x, s) => Elem::ValStop(x, s),
+                    };
+                    self.out_stream
+                        .enqueue(
+                            &self.time,
+                            ChannelElement {
+                                time: self.time.tick(),
+                                data: out_elem,
+                            },
+                        )
+                        .unwrap();
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::{
+        simulation::ProgramBuilder,
+        utility_contexts::{ApproxCheckerContext, GeneratorContext},
+    };
+
+    use crate::primitives::elem::Elem;
+
+    use super::Merge;
+
+    #[test]
+    fn merge_drops_the_interior_boundary() {
+        // (2, 3) flattened to (6,): the level-1 boundary between rows
+        // disappears, and the level-2 end-of-stream boundary shifts to 1.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::ValStop(3, 1),
+                    Elem::Val(4),
+                    Elem::Val(5),
+                    Elem::ValStop(6, 2),
+                ]
+                .into_iter()
+            },
+            in_snd,
+        ));
+
+        ctx.add_child(Merge::new(in_rcv, out_snd, 1, 0));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::Val(4),
+                    Elem::Val(5),
+                    Elem::ValStop(6, 1),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn merge_preserves_levels_below_merge_dim() {
+        // merging at dim 2 leaves an inner dim-1 boundary untouched.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::ValStop(2, 1),
+                    Elem::ValStop(3, 2),
+                    Elem::Val(4),
+                    Elem::ValStop(5, 1),
+                    Elem::ValStop(6, 3),
+                ]
+                .into_iter()
+            },
+            in_snd,
+        ));
+
+        ctx.add_child(Merge::new(in_rcv, out_snd, 2, 0));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::ValStop(2, 1),
+                    Elem::Val(3),
+                    Elem::Val(4),
+                    Elem::ValStop(5, 1),
+                    Elem::ValStop(6, 2),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn split_then_merge_round_trips_to_the_identity() {
+        use crate::operator::reshape::Reshape;
+
+        // splitting dim 0 into chunks of 3 and then merging it back
+        // at the same level must reproduce the original token stream.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (split_snd, split_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(1u32),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::Val(4),
+                    Elem::Val(5),
+                    Elem::ValStop(6, 1),
+                ]
+                .into_iter()
+            },
+            in_snd,
+        ));
+
+        ctx.add_child(Reshape::new(in_rcv, split_snd, 0, 3, None, 1, false, 0));
+        ctx.add_child(Merge::new(split_rcv, out_snd, 1, 0));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::Val(4),
+                    Elem::Val(5),
+                    Elem::ValStop(6, 1),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
@@ -1,20 +1,33 @@
 pub mod accum;
+pub mod arrow_csf;
 pub mod bufferize;
+pub mod cast;
+pub mod combine;
+pub mod coordinate_merge;
 pub mod map;
 pub mod map_accum;
 // pub mod mux_demux;
 pub mod broadcast;
+pub mod dim_broadcast;
 pub mod dynstreamify;
 pub mod eager_merge;
+pub mod elem_cast;
 pub mod expand;
 pub mod flatmap;
 pub mod flatten;
+pub mod gather;
+pub mod merge;
 pub mod parallelize;
 pub mod partition;
 pub mod promote;
+pub mod rank_remap;
 pub mod reassemble;
+pub mod rechunk;
 pub mod repeat;
 pub mod reshape;
+pub mod shape_broadcast;
+pub mod slice;
+pub mod stream_io;
 pub mod streamify;
 
 use dam::types::StaticallySized;
@@ -5,13 +5,44 @@ use crate::utils::calculation::div_ceil;
 use crate::utils::events::LoggableEventSimple;
 use dam::{context_tools::*, logging::LogEvent};
 use std::marker::PhantomData;
-use std::panic;
+use thiserror::Error;
 
 pub struct FlatPartitionConfig {
     pub switch_cycles: Vec<u64>, // cycles between receiving
     pub write_back_mu: bool,     // Whether the output is written to a memory unit
 }
 
+/// Structured failure for [`FlatPartition`], replacing the bare `panic!`s
+/// and out-of-range indexing this context used to abort the whole
+/// simulation with. See [`FlatPartition::poison`] for how a context reacts
+/// to one of these instead of crashing the process.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FlatPartitionError {
+    #[error("context {id}: select stream's stop level {found} disagrees with the stop level ({expected}) expected from the input stream")]
+    StopLevelMismatch {
+        id: u32,
+        expected: StopType,
+        found: StopType,
+    },
+
+    #[error("context {id}: select stream's stop level {found} exceeds the partition rank {rank}")]
+    StopLevelExceedsRank {
+        id: u32,
+        found: StopType,
+        rank: StopType,
+    },
+
+    #[error("context {id}: input stream ran out of things to dequeue during partition{}", expected.map(|e| format!(" while a stop token of level {e} was still expected")).unwrap_or_default())]
+    InputUnderrun { id: u32, expected: Option<StopType> },
+
+    #[error("context {id}: select vector picked expert {index}, but only {num_experts} expert stream(s) exist")]
+    ExpertIndexOutOfRange {
+        id: u32,
+        index: usize,
+        num_experts: usize,
+    },
+}
+
 #[context_macro]
 pub struct FlatPartition<E, A: DAMType, SELT: DAMType> {
     in_stream: Receiver<Elem<A>>,
@@ -24,7 +55,7 @@ pub struct FlatPartition<E, A: DAMType, SELT: DAMType> {
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         A: Bufferizable + DAMType,
         SELT: DAMType + SelectAdapter + Bufferizable,
     > FlatPartition<E, A, SELT>
@@ -68,14 +99,25 @@ where
     }
 
     /// Helper function to calculate and increment write cycles based on expert indices
-    fn handle_write_cycles<T: Bufferizable>(&mut self, select_vec: &[usize], data: &T) {
+    fn handle_write_cycles<T: Bufferizable>(
+        &mut self,
+        select_vec: &[usize],
+        data: &T,
+    ) -> Result<(), FlatPartitionError> {
         let mut write_cycle = 0;
 
         // Find maximum switch cycle among selected experts
         // TODO: This could be optimized further
         for expert_idx in select_vec.iter() {
-            if self.config.switch_cycles[*expert_idx] > write_cycle {
-                write_cycle = self.config.switch_cycles[*expert_idx];
+            let switch_cycle = *self.config.switch_cycles.get(*expert_idx).ok_or(
+                FlatPartitionError::ExpertIndexOutOfRange {
+                    id: self.id,
+                    index: *expert_idx,
+                    num_experts: self.config.switch_cycles.len(),
+                },
+            )?;
+            if switch_cycle > write_cycle {
+                write_cycle = switch_cycle;
             }
         }
 
@@ -85,12 +127,24 @@ where
         }
 
         self.time.incr_cycles(write_cycle);
+        Ok(())
     }
 
     /// Helper function to enqueue data to all selected expert output streams
-    fn enqueue_to_experts(&mut self, select_vec: &[usize], elem: Elem<A>) {
+    fn enqueue_to_experts(
+        &mut self,
+        select_vec: &[usize],
+        elem: Elem<A>,
+    ) -> Result<(), FlatPartitionError> {
         for expert_idx in select_vec.iter() {
-            self.out_streams[*expert_idx]
+            let out_stream = self.out_streams.get(*expert_idx).ok_or(
+                FlatPartitionError::ExpertIndexOutOfRange {
+                    id: self.id,
+                    index: *expert_idx,
+                    num_experts: self.out_streams.len(),
+                },
+            )?;
+            out_stream
                 .enqueue(
                     &self.time,
                     ChannelElement {
@@ -100,6 +154,7 @@ where
                 )
                 .unwrap();
         }
+        Ok(())
     }
 
     /// Process input stream elements with the given select vector
@@ -107,7 +162,7 @@ where
         &mut self,
         select_vec: &[usize],
         expected_stop_level: Option<StopType>,
-    ) {
+    ) -> Result<(), FlatPartitionError> {
         let mut start_time: Option<u64> = None;
         loop {
             match self.in_stream.peek_next(&self.time) {
@@ -121,26 +176,23 @@ where
                         }
                         self.handle_load_cycles(&x);
                         self.in_stream.dequeue(&self.time).unwrap();
-                        self.handle_write_cycles(select_vec, &x);
+                        self.handle_write_cycles(select_vec, &x)?;
 
                         self.enqueue_to_experts(
                             select_vec,
                             Elem::Val(x.clone_with_updated_read_from_mu(self.config.write_back_mu)),
-                        );
+                        )?;
 
                         if self.partition_rank == 0 {
-                            dam::logging::log_event(&E::new(
+                            crate::utils::events::log_event(&E::new(
                                 "FlatPartition".to_string(),
                                 self.id,
                                 start_time.unwrap(),
                                 self.time.tick().time(),
                                 true,
-                            ))
-                            .unwrap();
+                            ));
 
-                            start_time = None;
-
-                            break;
+                            return Ok(());
                         }
                     }
                     Elem::ValStop(x, stop_lev) => {
@@ -148,16 +200,20 @@ where
                             start_time = Some(self.time.tick().time());
                         }
                         // Validate stop level based on context
-                        if let Some(expected) = expected_stop_level.clone() {
+                        if let Some(expected) = expected_stop_level {
                             if expected != stop_lev {
-                                panic!("The expected stop level does not match the stop level in the select stream!");
+                                return Err(FlatPartitionError::StopLevelMismatch {
+                                    id: self.id,
+                                    expected,
+                                    found: stop_lev,
+                                });
                             }
                         } else if stop_lev > self.partition_rank {
-                            println!(
-                                "id {}: stop_lev in input {}, expected {:?}, partition_rank {}",
-                                self.id, stop_lev, expected_stop_level, self.partition_rank
-                            );
-                            panic!("The stop level in the select stream is greater than the partition rank!");
+                            return Err(FlatPartitionError::StopLevelExceedsRank {
+                                id: self.id,
+                                found: stop_lev,
+                                rank: self.partition_rank,
+                            });
                         }
                         // Determine output stop level
                         let output_stop_level = expected_stop_level
@@ -166,14 +222,14 @@ where
 
                         self.handle_load_cycles(&x);
                         self.in_stream.dequeue(&self.time).unwrap();
-                        self.handle_write_cycles(select_vec, &x);
+                        self.handle_write_cycles(select_vec, &x)?;
                         if output_stop_level == 0 {
                             self.enqueue_to_experts(
                                 select_vec,
                                 Elem::Val(
                                     x.clone_with_updated_read_from_mu(self.config.write_back_mu),
                                 ),
-                            );
+                            )?;
                         } else {
                             self.enqueue_to_experts(
                                 select_vec,
@@ -181,41 +237,57 @@ where
                                     x.clone_with_updated_read_from_mu(self.config.write_back_mu),
                                     output_stop_level,
                                 ),
-                            );
+                            )?;
                         }
                         // Break if we've reached the partition rank
                         if stop_lev == self.partition_rank || expected_stop_level == Some(stop_lev)
                         {
-                            dam::logging::log_event(&E::new(
+                            crate::utils::events::log_event(&E::new(
                                 "FlatPartition".to_string(),
                                 self.id,
                                 start_time.unwrap(),
                                 self.time.tick().time(),
                                 true,
-                            ))
-                            .unwrap();
-
-                            start_time = None;
+                            ));
 
-                            break;
+                            return Ok(());
                         }
                     }
                 },
                 Err(_) => {
-                    let error_msg = if expected_stop_level.is_some() {
-                        "The input stream lacks a stop token that corresponds to the stop token in the select stream!"
-                    } else {
-                        "Input stream ran out of things to dequeue during partition."
-                    };
-                    panic!("{}", error_msg);
+                    return Err(FlatPartitionError::InputUnderrun {
+                        id: self.id,
+                        expected: expected_stop_level,
+                    });
                 }
             }
         }
     }
+
+    /// Reacts to the first [`FlatPartitionError`] this context observes:
+    /// logs it as a terminal event (so it surfaces in the run's trace
+    /// instead of vanishing into a process-wide abort) so the caller can
+    /// return from `run` without sending anything further. Every
+    /// `out_stream` this context attached to is considered closed once
+    /// `run` returns -- mirroring a poisoned `Mutex`, a downstream context
+    /// still waiting on one of them sees a closed channel (the same `Err`
+    /// it would see after a clean finish) and can drain/exit on its own
+    /// terms, rather than this fault cascading into a panic that takes
+    /// down the whole simulation.
+    fn poison(&mut self, err: FlatPartitionError) {
+        let now = self.time.tick().time();
+        crate::utils::events::log_event(&E::new(
+            format!("FlatPartition fault: {err}"),
+            self.id,
+            now,
+            now,
+            true,
+        ));
+    }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         A: Bufferizable + DAMType,
         SELT: DAMType + SelectAdapter + Bufferizable,
     > Context for FlatPartition<E, A, SELT>
@@ -229,21 +301,27 @@ where
                 Ok(ChannelElement {
                     time: _,
                     data: sel_data,
-                }) => match sel_data {
-                    Elem::Val(sel) => {
-                        self.handle_load_cycles(&sel);
-                        self.sel_stream.dequeue(&self.time).unwrap();
-                        let select_vec = sel.to_sel_vec();
-                        self.process_input_stream(&select_vec, None);
-                    }
-                    Elem::ValStop(sel, sel_level) => {
-                        self.handle_load_cycles(&sel);
-                        self.sel_stream.dequeue(&self.time).unwrap();
-                        let select_vec = sel.to_sel_vec();
-                        let expected_stop_level = sel_level + self.partition_rank;
-                        self.process_input_stream(&select_vec, Some(expected_stop_level));
+                }) => {
+                    let result = match sel_data {
+                        Elem::Val(sel) => {
+                            self.handle_load_cycles(&sel);
+                            self.sel_stream.dequeue(&self.time).unwrap();
+                            let select_vec = sel.to_sel_vec();
+                            self.process_input_stream(&select_vec, None)
+                        }
+                        Elem::ValStop(sel, sel_level) => {
+                            self.handle_load_cycles(&sel);
+                            self.sel_stream.dequeue(&self.time).unwrap();
+                            let select_vec = sel.to_sel_vec();
+                            let expected_stop_level = sel_level + self.partition_rank;
+                            self.process_input_stream(&select_vec, Some(expected_stop_level))
+                        }
+                    };
+                    if let Err(err) = result {
+                        self.poison(err);
+                        return;
                     }
-                },
+                }
                 Err(_) => return,
             }
         }
@@ -0,0 +1,195 @@
+use crate::primitives::elem::{Elem, StopType};
+use dam::context_tools::*;
+
+/// Table-driven generalization of [`crate::operator::flatten::Flatten`]'s
+/// `new_rank` recurrence: `schedule[s]` gives the output rank(s) an
+/// incoming `ValStop` at input rank `s` should become, for `s` within the
+/// table; ranks beyond the table shift down by `tail_offset`, the same
+/// tail behavior `Flatten` used for ranks past its `max_rank`.
+///
+/// An entry with more than one output rank models a split -- one input
+/// boundary expanding into several output levels -- and is emitted as a
+/// cascade of `ValStop` tokens of increasing rank, each carrying the same
+/// closing value, by [`RankRemap::run`]. An entry of `[r]` reproduces
+/// `Flatten`'s passthrough (`r == s`) and merge (`r` equal across several
+/// consecutive input ranks) cases; reordering falls out of the table
+/// entries not needing to be monotonic in `s`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RankSchedule {
+    table: Vec<Vec<StopType>>,
+    tail_offset: StopType,
+}
+
+impl RankSchedule {
+    pub fn new(table: Vec<Vec<StopType>>, tail_offset: StopType) -> Self {
+        Self { table, tail_offset }
+    }
+
+    /// The schedule `Flatten::new(min_rank, max_rank)` used to build by
+    /// hand: ranks up to `min_rank` pass through, `(min_rank, max_rank]`
+    /// collapse onto `min_rank`, and anything higher shifts down by the
+    /// merged span.
+    pub fn collapsing(min_rank: StopType, max_rank: StopType) -> Self {
+        assert!(min_rank < max_rank, "min_rank must be less than max_rank");
+        let table = (0..=max_rank)
+            .map(|s| if s <= min_rank { vec![s] } else { vec![min_rank] })
+            .collect();
+        Self::new(table, max_rank - min_rank)
+    }
+
+    fn remap(&self, s: StopType) -> Vec<StopType> {
+        match self.table.get(s as usize) {
+            Some(ranks) => ranks.clone(),
+            None => vec![s - self.tail_offset],
+        }
+    }
+}
+
+/// Applies `schedule` to one stream element, returning the (possibly
+/// empty-of-stops, possibly multi-element) cascade it expands into. Shared
+/// by [`RankRemap`] and by [`crate::operator::flatten::Flatten`], which
+/// only ever produces single-rank schedules but drives the same
+/// recurrence.
+pub(crate) fn apply_schedule<T: Clone>(elem: Elem<T>, schedule: &RankSchedule) -> Vec<Elem<T>> {
+    match elem {
+        Elem::Val(x) => vec![Elem::Val(x)],
+        Elem::ValStop(x, s) => schedule
+            .remap(s)
+            .into_iter()
+            .map(|r| {
+                if r == 0 {
+                    Elem::Val(x.clone())
+                } else {
+                    Elem::ValStop(x.clone(), r)
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Generalizes [`crate::operator::flatten::Flatten`] from a single
+/// `(min_rank, max_rank)` collapse into an arbitrary [`RankSchedule`], so
+/// flattening, unflattening (splitting one rank into several), and
+/// reordering fibertree levels are all expressed by one operator.
+#[context_macro]
+pub struct RankRemap<T: DAMType> {
+    in_stream: Receiver<Elem<T>>,
+    out_stream: Sender<Elem<T>>,
+    schedule: RankSchedule,
+}
+
+impl<T: DAMType> RankRemap<T>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<T>>,
+        out_stream: Sender<Elem<T>>,
+        schedule: RankSchedule,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            schedule,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType> Context for RankRemap<T> {
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => {
+                    for out in apply_schedule(data, &self.schedule) {
+                        self.out_stream
+                            .enqueue(
+                                &self.time,
+                                ChannelElement {
+                                    time: self.time.tick(),
+                                    data: out,
+                                },
+                            )
+                            .unwrap();
+                    }
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::simulation::ProgramBuilder;
+    use dam::utility_contexts::{CheckerContext, GeneratorContext};
+
+    use super::{RankRemap, RankSchedule};
+    use crate::primitives::elem::Elem;
+
+    #[test]
+    fn collapsing_schedule_matches_flatten_0_1() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(1u32),
+                    Elem::ValStop(2u32, 1),
+                    Elem::ValStop(3u32, 2),
+                ]
+                .into_iter()
+            },
+            in_snd,
+        ));
+        ctx.add_child(RankRemap::new(
+            in_rcv,
+            out_snd,
+            RankSchedule::collapsing(0, 1),
+        ));
+        ctx.add_child(CheckerContext::new(
+            || vec![Elem::Val(1u32), Elem::Val(2u32), Elem::ValStop(3u32, 1)].into_iter(),
+            out_rcv,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn splitting_schedule_emits_a_cascade_of_stops() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(1u32), Elem::ValStop(2u32, 1)].into_iter(),
+            in_snd,
+        ));
+        // Input rank 1 expands into output ranks 1 then 2, splitting one
+        // boundary into two.
+        let schedule = RankSchedule::new(vec![vec![0], vec![1, 2]], 0);
+        ctx.add_child(RankRemap::new(in_rcv, out_snd, schedule));
+        ctx.add_child(CheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1u32),
+                    Elem::ValStop(2u32, 1),
+                    Elem::ValStop(2u32, 2),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
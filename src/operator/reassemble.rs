@@ -3,14 +3,49 @@ use crate::primitives::elem::{Bufferizable, Elem, StopType};
 use crate::primitives::{select::SelectAdapter, tile::Tile};
 use crate::utils::calculation::div_ceil;
 use crate::utils::events::LoggableEventSimple;
-use core::panic;
 use dam::channel::PeekResult;
 use dam::{context_tools::*, logging::LogEvent};
 use std::marker::PhantomData;
 
+/// How [`FlatReassemble::process_input_stream`] walks the streams selected
+/// for a given reassembly group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Sort the selected streams by arrival time and fully drain each one
+    /// (in that order) before moving to the next.
+    ArrivalOrder,
+    /// Visit the selected streams one element at a time, cycling through
+    /// whichever streams are still live, in the spirit of `stream::select`
+    /// -- e.g. two 3-element streams interleave as `1,4,2,5,3,6` rather
+    /// than `1,2,3,4,5,6`.
+    RoundRobin,
+}
+
 pub struct FlatReassembleConfig {
     pub switch_cycles: Vec<u64>,
     pub write_back_mu: bool,
+    pub merge_policy: MergePolicy,
+    /// When set, treats `PMU_BW` as a single budget shared across every
+    /// `read_from_mu()` element peeked in a given `process_input_stream`
+    /// wave, rather than charging `div_ceil(size, PMU_BW)` to each element
+    /// independently -- see [`FlatReassemble::process_input_stream`].
+    /// `None` keeps the original per-element accounting.
+    pub shared_bw: Option<u64>,
+    /// Per-`in_streams` capacity (indexed like `switch_cycles`): the max
+    /// number of `Elem::Val`/`ValStop` payloads that stream may contribute
+    /// within one selection group before later ones are dropped rather than
+    /// reassembled -- the token-dropping behavior real MoE routers use when
+    /// an expert is oversubscribed. `None` (or an unbounded per-stream
+    /// entry) keeps the original unconditional reassembly.
+    pub capacity: Option<Vec<u64>>,
+}
+
+/// Liveness of one of [`FlatReassemble`]'s input streams. A stream only
+/// ever moves `Reachable` -> `Unreachable`, when a peek reports it closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamState {
+    Reachable,
+    Unreachable,
 }
 
 #[context_macro]
@@ -20,12 +55,21 @@ pub struct FlatReassemble<E, A: DAMType, SELT: DAMType> {
     out_stream: Sender<Elem<A>>,
     reassemble_rank: StopType,
     config: FlatReassembleConfig,
+    /// One entry per `in_streams`; an `Unreachable` stream is skipped by
+    /// future reassembly groups instead of blocking (or panicking) on it,
+    /// so experts that finish early don't abort the whole simulation.
+    stream_states: Vec<StreamState>,
+    /// When attached via [`Self::with_drop_counter`], the total number of
+    /// `capacity`-overflow elements dropped is sent once per selection
+    /// group that still has a live selected stream, so downstream contexts
+    /// can observe load-balancing loss.
+    drop_snd: Option<Sender<Elem<usize>>>,
     id: u32,
     _phantom: PhantomData<E>,
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         A: DAMType + Bufferizable,
         SELT: DAMType + SelectAdapter + Bufferizable,
     > FlatReassemble<E, A, SELT>
@@ -41,12 +85,15 @@ where
         config: FlatReassembleConfig,
         id: u32,
     ) -> Self {
+        let stream_states = vec![StreamState::Reachable; in_streams.len()];
         let ctx = Self {
             in_streams,
             sel_stream,
             out_stream,
             reassemble_rank,
             config,
+            stream_states,
+            drop_snd: None,
             id,
             context_info: Default::default(),
             _phantom: PhantomData,
@@ -58,6 +105,16 @@ where
         ctx
     }
 
+    /// Attaches a side channel that reports, once per selection group with
+    /// a live selected stream, how many elements `capacity` caused
+    /// [`Self::process_input_stream`] to drop. Unused (and harmless to leave unattached) when `capacity` is
+    /// `None`.
+    pub fn with_drop_counter(mut self, drop_snd: Sender<Elem<usize>>) -> Self {
+        drop_snd.attach_sender(&self);
+        self.drop_snd = Some(drop_snd);
+        self
+    }
+
     /// Helper function to calculate and increment load cycles for memory operations
     fn handle_load_cycles<T: Bufferizable>(
         &mut self,
@@ -72,6 +129,51 @@ where
         self.time.advance((data_arrive_time + load_cycle).into());
     }
 
+    /// Same as [`Self::handle_load_cycles`], except the `read_from_mu` bytes
+    /// term is charged once for the whole wave (by
+    /// [`Self::charge_shared_bandwidth`]) rather than per element, when
+    /// `shared_bw` is configured -- used for the selected-stream elements
+    /// `process_input_stream` itself drains, but not for `sel_stream`'s own
+    /// load (that isn't part of the wave the config describes).
+    fn handle_stream_load_cycles<T: Bufferizable>(
+        &mut self,
+        data_arrive_time: u64,
+        data: &T,
+        constant: Option<u64>,
+    ) {
+        let mut load_cycle = constant.unwrap_or(0);
+        if data.read_from_mu() && self.config.shared_bw.is_none() {
+            load_cycle += div_ceil(data.size_in_bytes() as u64, PMU_BW);
+        }
+        self.time.advance((data_arrive_time + load_cycle).into());
+    }
+
+    /// When `shared_bw` is configured, treats `bandwidth` as a single budget
+    /// shared by every `read_from_mu()` element in `live_elems`: sums their
+    /// `size_in_bytes()` and charges `div_ceil(total, bandwidth)` cycles
+    /// against the slowest (latest) arrival in the wave, instead of each
+    /// element paying `div_ceil(size, PMU_BW)` independently.
+    fn charge_shared_bandwidth(&mut self, bandwidth: u64, live_elems: &[ChannelElement<Elem<A>>]) {
+        let total_bytes: u64 = live_elems
+            .iter()
+            .map(|elem| match &elem.data {
+                Elem::Val(x) | Elem::ValStop(x, _) => x,
+            })
+            .filter(|x| x.read_from_mu())
+            .map(|x| x.size_in_bytes() as u64)
+            .sum();
+        if total_bytes == 0 {
+            return;
+        }
+        let latest_arrival = live_elems
+            .iter()
+            .map(|elem| elem.time.time())
+            .max()
+            .unwrap_or(0);
+        let shared_cycles = div_ceil(total_bytes, bandwidth.max(1));
+        self.time.advance((latest_arrival + shared_cycles).into());
+    }
+
     fn handle_memory_writeback(&mut self, x: &A) {
         if self.config.write_back_mu {
             self.time
@@ -97,7 +199,13 @@ where
                         num_peeked += 1;
                     }
                     PeekResult::Nothing(_) => continue,
-                    PeekResult::Closed => return vec![], // Signal that streams are closed
+                    PeekResult::Closed => {
+                        // Leave peek_results[i] as None rather than aborting
+                        // the whole group -- the caller filters these out.
+                        self.stream_states[idx] = StreamState::Unreachable;
+                        peeked[i] = true;
+                        num_peeked += 1;
+                    }
                 }
             }
             if num_peeked < select_vec.len() {
@@ -108,27 +216,88 @@ where
         peek_results
     }
 
-    fn get_arrive_times(&self, peek_results: &[Option<ChannelElement<Elem<A>>>]) -> Vec<u64> {
-        let mut data_arrive_times = vec![];
-        peek_results.iter().for_each(|elem| {
-            if let Some(ChannelElement { time: arrive, .. }) = elem {
-                data_arrive_times.push(arrive.time());
+    /// Drops positions whose stream turned out to be closed
+    /// (`peek_results[i] == None`), keeping `select_vec` and the returned
+    /// elements aligned so downstream indices stay in sync with each other.
+    fn prune_unreachable(
+        select_vec: &[usize],
+        peek_results: Vec<Option<ChannelElement<Elem<A>>>>,
+    ) -> (Vec<usize>, Vec<ChannelElement<Elem<A>>>) {
+        let mut live_select_vec = Vec::new();
+        let mut live_elems = Vec::new();
+        for (&idx, result) in select_vec.iter().zip(peek_results.into_iter()) {
+            if let Some(elem) = result {
+                live_select_vec.push(idx);
+                live_elems.push(elem);
             }
-        });
-        data_arrive_times
+        }
+        (live_select_vec, live_elems)
     }
 
     fn process_input_stream(&mut self, select_vec: &[usize], select_level: Option<u32>) {
-        let addtional_rank = select_level.unwrap_or(0);
-        let num_selected_streams = select_vec.len();
-        // Peek the next wave of input elements
-        let peek_results = self.peek_all_streams(select_vec);
-        if peek_results.is_empty() {
-            panic!("All input streams are closed or empty");
+        // Only still-Reachable streams participate; Unreachable ones
+        // (closed before this group even started) are skipped rather than
+        // blocked on.
+        let candidate_select_vec: Vec<usize> = select_vec
+            .iter()
+            .copied()
+            .filter(|&idx| self.stream_states[idx] == StreamState::Reachable)
+            .collect();
+        if candidate_select_vec.is_empty() {
+            return;
         }
 
-        // Get the arrive time of each element in the peek_results
-        let data_arrive_times = self.get_arrive_times(&peek_results);
+        let peek_results = self.peek_all_streams(&candidate_select_vec);
+        let (live_select_vec, live_elems) =
+            Self::prune_unreachable(&candidate_select_vec, peek_results);
+        if live_select_vec.is_empty() {
+            return;
+        }
+
+        if let Some(shared_bw) = self.config.shared_bw {
+            self.charge_shared_bandwidth(shared_bw, &live_elems);
+        }
+        let data_arrive_times: Vec<u64> = live_elems.iter().map(|elem| elem.time.time()).collect();
+
+        let dropped = match self.config.merge_policy {
+            MergePolicy::ArrivalOrder => self.process_input_stream_arrival_order(
+                &live_select_vec,
+                &data_arrive_times,
+                select_level,
+            ),
+            MergePolicy::RoundRobin => self.process_input_stream_round_robin(
+                &live_select_vec,
+                &data_arrive_times,
+                select_level,
+            ),
+        };
+
+        if let Some(drop_snd) = &self.drop_snd {
+            drop_snd
+                .enqueue(
+                    &self.time,
+                    ChannelElement {
+                        time: self.time.tick(),
+                        data: Elem::Val(dropped),
+                    },
+                )
+                .unwrap();
+        }
+    }
+
+    fn process_input_stream_arrival_order(
+        &mut self,
+        select_vec: &[usize],
+        data_arrive_times: &[u64],
+        select_level: Option<u32>,
+    ) -> usize {
+        let addtional_rank = select_level.unwrap_or(0);
+        let num_selected_streams = select_vec.len();
+        // Per-stream contribution count for this group, checked against
+        // `config.capacity` to decide whether an element gets reassembled
+        // or dropped.
+        let mut contributed: Vec<u64> = vec![0; self.in_streams.len()];
+        let mut dropped = 0usize;
 
         // Create idx vector based on the data_arrive_times
         let mut sorted_indices: Vec<usize> = (0..num_selected_streams).collect();
@@ -148,14 +317,14 @@ where
                         // Handle load cycles for the current element
                         match &val_data {
                             Elem::Val(x) => {
-                                self.handle_load_cycles(
+                                self.handle_stream_load_cycles(
                                     data_arrive_times[i],
                                     x,
                                     Some(self.config.switch_cycles[stream_idx]),
                                 );
                             }
                             Elem::ValStop(x, _) => {
-                                self.handle_load_cycles(
+                                self.handle_stream_load_cycles(
                                     data_arrive_times[i],
                                     x,
                                     Some(self.config.switch_cycles[stream_idx]),
@@ -166,63 +335,72 @@ where
                         // Dequeue the current element
                         self.in_streams[stream_idx].dequeue(&self.time).unwrap();
 
-                        // Enqueue the current element to the output stream
-                        match &val_data {
-                            Elem::Val(x) => {
-                                self.handle_memory_writeback(x);
-                                let updated_x: A =
-                                    x.clone_with_updated_read_from_mu(self.config.write_back_mu);
-                                let data = if self.reassemble_rank == 0 {
-                                    if is_last_selected {
-                                        Elem::ValStop(updated_x.clone(), 1 + addtional_rank)
-                                    } else {
-                                        Elem::Val(updated_x.clone())
-                                    }
-                                } else {
-                                    Elem::Val(updated_x.clone())
-                                };
-                                self.out_stream
-                                    .enqueue(
-                                        &self.time,
-                                        ChannelElement {
-                                            time: self.time.tick(),
-                                            data,
-                                        },
-                                    )
-                                    .unwrap();
-                            }
-                            Elem::ValStop(x, level) => {
-                                self.handle_memory_writeback(x);
-                                let updated_x: A =
-                                    x.clone_with_updated_read_from_mu(self.config.write_back_mu);
-                                let data = if self.reassemble_rank == 0 {
-                                    if is_last_selected {
-                                        Elem::ValStop(
-                                            updated_x.clone(),
-                                            *level + addtional_rank + 1,
-                                        )
+                        // Once `stream_idx` has contributed `capacity`
+                        // payloads to this group, later ones are dropped
+                        // (already charged above) rather than reassembled,
+                        // modeling an oversubscribed expert.
+                        let at_capacity = self
+                            .config
+                            .capacity
+                            .as_ref()
+                            .is_some_and(|caps| contributed[stream_idx] >= caps[stream_idx]);
+
+                        if at_capacity {
+                            dropped += 1;
+                        } else {
+                            contributed[stream_idx] += 1;
+                            match &val_data {
+                                Elem::Val(x) => {
+                                    self.handle_memory_writeback(x);
+                                    let updated_x: A = x
+                                        .clone_with_updated_read_from_mu(self.config.write_back_mu);
+                                    let data = if self.reassemble_rank == 0 {
+                                        if is_last_selected {
+                                            Elem::ValStop(updated_x.clone(), 1 + addtional_rank)
+                                        } else {
+                                            Elem::Val(updated_x.clone())
+                                        }
                                     } else {
                                         Elem::Val(updated_x.clone())
-                                    }
-                                } else {
-                                    if is_last_selected && level >= &self.reassemble_rank {
-                                        Elem::ValStop(
-                                            updated_x.clone(),
-                                            *level + addtional_rank + 1,
+                                    };
+                                    self.out_stream
+                                        .enqueue(
+                                            &self.time,
+                                            ChannelElement {
+                                                time: self.time.tick(),
+                                                data,
+                                            },
                                         )
+                                        .unwrap();
+                                }
+                                Elem::ValStop(x, level) => {
+                                    self.handle_memory_writeback(x);
+                                    let updated_x: A = x
+                                        .clone_with_updated_read_from_mu(self.config.write_back_mu);
+                                    let data = if self.reassemble_rank == 0 {
+                                        if is_last_selected {
+                                            Elem::ValStop(
+                                                updated_x.clone(),
+                                                *level + addtional_rank + 1,
+                                            )
+                                        } else {
+                                            Elem::Val(updated_x.clone())
+                                        }
+                                    } else if is_last_selected && level >= &self.reassemble_rank {
+                                        Elem::ValStop(updated_x.clone(), *level + addtional_rank + 1)
                                     } else {
                                         Elem::ValStop(updated_x.clone(), *level)
-                                    }
-                                };
-                                self.out_stream
-                                    .enqueue(
-                                        &self.time,
-                                        ChannelElement {
-                                            time: self.time.tick(),
-                                            data,
-                                        },
-                                    )
-                                    .unwrap();
+                                    };
+                                    self.out_stream
+                                        .enqueue(
+                                            &self.time,
+                                            ChannelElement {
+                                                time: self.time.tick(),
+                                                data,
+                                            },
+                                        )
+                                        .unwrap();
+                                }
                             }
                         }
 
@@ -231,14 +409,13 @@ where
                             Elem::Val(_) => {
                                 if self.reassemble_rank == 0 {
                                     // Logging
-                                    dam::logging::log_event(&E::new(
+                                    crate::utils::events::log_event(&E::new(
                                         "FlatReassemble".to_string(),
                                         self.id,
                                         start_time,
                                         self.time.tick().time(),
                                         true,
-                                    ))
-                                    .unwrap();
+                                    ));
 
                                     break;
                                 }
@@ -246,30 +423,160 @@ where
                             Elem::ValStop(_, level) => {
                                 if level >= self.reassemble_rank {
                                     // Logging
-                                    dam::logging::log_event(&E::new(
+                                    crate::utils::events::log_event(&E::new(
                                         "FlatReassemble".to_string(),
                                         self.id,
                                         start_time,
                                         self.time.tick().time(),
                                         true,
-                                    ))
-                                    .unwrap();
+                                    ));
                                     break;
                                 }
                             }
                         }
                     }
                     Err(_) => {
-                        panic!("Stream {} is closed or empty", stream_idx);
+                        // Closed mid-drain: this stream stops contributing
+                        // to the current (and all future) groups, rather
+                        // than aborting the whole simulation.
+                        self.stream_states[stream_idx] = StreamState::Unreachable;
+                        break;
                     }
                 }
             }
         }
+        dropped
+    }
+
+    fn process_input_stream_round_robin(
+        &mut self,
+        select_vec: &[usize],
+        data_arrive_times: &[u64],
+        select_level: Option<u32>,
+    ) -> usize {
+        let addtional_rank = select_level.unwrap_or(0);
+        let mut contributed: Vec<u64> = vec![0; self.in_streams.len()];
+        let mut dropped = 0usize;
+
+        // Indices into `select_vec` for streams still contributing to this
+        // reassembly group, visited round-robin via `cursor`.
+        let mut live: Vec<usize> = (0..select_vec.len()).collect();
+        let mut cursor = 0;
+
+        while !live.is_empty() {
+            let pos = cursor % live.len();
+            let i = live[pos];
+            let stream_idx = select_vec[i];
+            let start_time = data_arrive_times[i];
+
+            match self.in_streams[stream_idx].peek_next(&self.time) {
+                Ok(ChannelElement {
+                    time: _,
+                    data: val_data,
+                }) => {
+                    match &val_data {
+                        Elem::Val(x) => self.handle_stream_load_cycles(
+                            data_arrive_times[i],
+                            x,
+                            Some(self.config.switch_cycles[stream_idx]),
+                        ),
+                        Elem::ValStop(x, _) => self.handle_stream_load_cycles(
+                            data_arrive_times[i],
+                            x,
+                            Some(self.config.switch_cycles[stream_idx]),
+                        ),
+                    };
+
+                    self.in_streams[stream_idx].dequeue(&self.time).unwrap();
+
+                    // A stream is done once it hits a stop token at (or
+                    // past) reassemble_rank, or -- at rank 0 -- as soon as
+                    // a single Val is drained, mirroring the one-shot
+                    // behavior of the arrival-order path.
+                    let stream_done = match &val_data {
+                        Elem::Val(_) => self.reassemble_rank == 0,
+                        Elem::ValStop(_, level) => *level >= self.reassemble_rank,
+                    };
+                    let is_last_overall = stream_done && live.len() == 1;
+
+                    // Once `stream_idx` has contributed `capacity`
+                    // payloads to this group, later ones are dropped
+                    // (already charged above) rather than reassembled,
+                    // modeling an oversubscribed expert.
+                    let at_capacity = self
+                        .config
+                        .capacity
+                        .as_ref()
+                        .is_some_and(|caps| contributed[stream_idx] >= caps[stream_idx]);
+
+                    if at_capacity {
+                        dropped += 1;
+                    } else {
+                        contributed[stream_idx] += 1;
+                        let data = match &val_data {
+                            Elem::Val(x) => {
+                                self.handle_memory_writeback(x);
+                                let updated_x: A =
+                                    x.clone_with_updated_read_from_mu(self.config.write_back_mu);
+                                if is_last_overall {
+                                    Elem::ValStop(updated_x, 1 + addtional_rank)
+                                } else {
+                                    Elem::Val(updated_x)
+                                }
+                            }
+                            Elem::ValStop(x, level) => {
+                                self.handle_memory_writeback(x);
+                                let updated_x: A =
+                                    x.clone_with_updated_read_from_mu(self.config.write_back_mu);
+                                if is_last_overall {
+                                    Elem::ValStop(updated_x, *level + addtional_rank + 1)
+                                } else if self.reassemble_rank == 0 {
+                                    Elem::Val(updated_x)
+                                } else {
+                                    Elem::ValStop(updated_x, *level)
+                                }
+                            }
+                        };
+
+                        self.out_stream
+                            .enqueue(
+                                &self.time,
+                                ChannelElement {
+                                    time: self.time.tick(),
+                                    data,
+                                },
+                            )
+                            .unwrap();
+                    }
+
+                    if stream_done {
+                        // Logging
+                        crate::utils::events::log_event(&E::new(
+                            "FlatReassemble".to_string(),
+                            self.id,
+                            start_time,
+                            self.time.tick().time(),
+                            true,
+                        ));
+                        live.remove(pos);
+                    } else {
+                        cursor += 1;
+                    }
+                }
+                Err(_) => {
+                    // Closed mid-drain: drop it from the round-robin
+                    // rotation instead of aborting the whole simulation.
+                    self.stream_states[stream_idx] = StreamState::Unreachable;
+                    live.remove(pos);
+                }
+            }
+        }
+        dropped
     }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         A: DAMType + Bufferizable,
         SELT: DAMType + SelectAdapter + Bufferizable,
     > Context for FlatReassemble<E, A, SELT>
@@ -299,6 +606,16 @@ where
                 },
                 Err(_) => return,
             }
+
+            // Every selected stream we track has closed -- nothing left to
+            // reassemble, so stop rather than keep draining sel_stream.
+            if self
+                .stream_states
+                .iter()
+                .all(|s| *s == StreamState::Unreachable)
+            {
+                return;
+            }
         }
     }
 }
@@ -307,7 +624,7 @@ where
 mod tests {
     use crate::primitives::select::MultiHotN;
     use crate::{
-        operator::reassemble::{FlatReassemble, FlatReassembleConfig},
+        operator::reassemble::{FlatReassemble, FlatReassembleConfig, MergePolicy},
         primitives::{elem::Elem, tile::Tile},
         utils::events::SimpleEvent,
     };
@@ -408,6 +725,9 @@ mod tests {
         let config = FlatReassembleConfig {
             switch_cycles: vec![1, 2, 3, 4],
             write_back_mu: true,
+            merge_policy: MergePolicy::ArrivalOrder,
+            shared_bw: None,
+            capacity: None,
         };
 
         ctx.add_child(GeneratorContext::new(
@@ -528,6 +848,9 @@ mod tests {
         let config = FlatReassembleConfig {
             switch_cycles: vec![1, 2, 3, 4],
             write_back_mu: true,
+            merge_policy: MergePolicy::ArrivalOrder,
+            shared_bw: None,
+            capacity: None,
         };
         ctx.add_child(GeneratorContext::new(
             || input_streams_data[0].clone().into_iter(),
@@ -568,4 +891,245 @@ mod tests {
             .unwrap()
             .run(Default::default());
     }
+
+    #[test]
+    fn flat_reassemble_round_robin_interleaves_streams() {
+        // Two 3-element streams under RoundRobin should interleave one
+        // element at a time (1, 4, 2, 5, ...) rather than fully draining
+        // stream 0 before moving to stream 1.
+        let tile = |v: i32| Tile::new(Array2::from_shape_vec((1, 1), vec![v]).unwrap().into(), 4, true);
+
+        let stream0 = vec![
+            Elem::Val(tile(1)),
+            Elem::Val(tile(2)),
+            Elem::ValStop(tile(3), 1),
+        ];
+        let stream1 = vec![
+            Elem::Val(tile(4)),
+            Elem::Val(tile(5)),
+            Elem::ValStop(tile(6), 1),
+        ];
+        let ground_truth = vec![
+            Elem::Val(tile(1)),
+            Elem::Val(tile(4)),
+            Elem::Val(tile(2)),
+            Elem::Val(tile(5)),
+            Elem::ValStop(tile(3), 1),
+            Elem::ValStop(tile(6), 2),
+        ];
+
+        let mut ctx = ProgramBuilder::default();
+        let (out_data_snd, out_data_rcv) = ctx.unbounded();
+        let (in_sel_snd, in_sel_rcv) = ctx.unbounded();
+        let (exp1_snd, exp1_rcv) = ctx.unbounded();
+        let (exp2_snd, exp2_rcv) = ctx.unbounded();
+
+        let config = FlatReassembleConfig {
+            switch_cycles: vec![1, 1],
+            write_back_mu: true,
+            merge_policy: MergePolicy::RoundRobin,
+            shared_bw: None,
+            capacity: None,
+        };
+
+        ctx.add_child(GeneratorContext::new(|| stream0.into_iter(), exp1_snd));
+        ctx.add_child(GeneratorContext::new(|| stream1.into_iter(), exp2_snd));
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::ValStop(MultiHotN::new(vec![true, true], true), 0)].into_iter(),
+            in_sel_snd,
+        ));
+        ctx.add_child(FlatReassemble::<SimpleEvent, _, _>::new(
+            vec![exp1_rcv, exp2_rcv],
+            in_sel_rcv,
+            out_data_snd,
+            1,
+            config,
+            0,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            || ground_truth.into_iter(),
+            out_data_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn flat_reassemble_survives_early_stream_closure() {
+        // Stream 0 only has one group's worth of data; stream 1 has two.
+        // The second select group still selects both, so the operator must
+        // skip stream 0 once it's found closed instead of panicking.
+        let tile = |v: i32| Tile::new(Array2::from_shape_vec((1, 1), vec![v]).unwrap().into(), 4, true);
+
+        let stream0 = vec![Elem::Val(tile(1))];
+        let stream1 = vec![Elem::Val(tile(2)), Elem::Val(tile(3))];
+        let select_stream_data = vec![
+            Elem::Val(MultiHotN::new(vec![true, true], true)),
+            Elem::ValStop(MultiHotN::new(vec![true, true], true), 0),
+        ];
+        let ground_truth = vec![
+            Elem::Val(tile(1)),
+            Elem::ValStop(tile(2), 1),
+            Elem::ValStop(tile(3), 1),
+        ];
+
+        let mut ctx = ProgramBuilder::default();
+        let (out_data_snd, out_data_rcv) = ctx.unbounded();
+        let (in_sel_snd, in_sel_rcv) = ctx.unbounded();
+        let (exp1_snd, exp1_rcv) = ctx.unbounded();
+        let (exp2_snd, exp2_rcv) = ctx.unbounded();
+
+        let config = FlatReassembleConfig {
+            switch_cycles: vec![1, 1],
+            write_back_mu: true,
+            merge_policy: MergePolicy::ArrivalOrder,
+            shared_bw: None,
+            capacity: None,
+        };
+
+        ctx.add_child(GeneratorContext::new(|| stream0.into_iter(), exp1_snd));
+        ctx.add_child(GeneratorContext::new(|| stream1.into_iter(), exp2_snd));
+        ctx.add_child(GeneratorContext::new(
+            || select_stream_data.into_iter(),
+            in_sel_snd,
+        ));
+        ctx.add_child(FlatReassemble::<SimpleEvent, _, _>::new(
+            vec![exp1_rcv, exp2_rcv],
+            in_sel_rcv,
+            out_data_snd,
+            0,
+            config,
+            0,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            || ground_truth.into_iter(),
+            out_data_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn flat_reassemble_shared_bw_preserves_payload_order() {
+        // `shared_bw` only changes how load cycles are charged across a
+        // wave -- the reassembled payload stream itself should be identical
+        // to the `shared_bw: None` case.
+        let tile = |v: i32| Tile::new(Array2::from_shape_vec((1, 1), vec![v]).unwrap().into(), 4, true);
+
+        let stream0 = vec![Elem::Val(tile(1)), Elem::ValStop(tile(2), 1)];
+        let stream1 = vec![Elem::Val(tile(3)), Elem::ValStop(tile(4), 1)];
+        let ground_truth = vec![
+            Elem::Val(tile(1)),
+            Elem::ValStop(tile(2), 1),
+            Elem::Val(tile(3)),
+            Elem::ValStop(tile(4), 2),
+        ];
+
+        let mut ctx = ProgramBuilder::default();
+        let (out_data_snd, out_data_rcv) = ctx.unbounded();
+        let (in_sel_snd, in_sel_rcv) = ctx.unbounded();
+        let (exp1_snd, exp1_rcv) = ctx.unbounded();
+        let (exp2_snd, exp2_rcv) = ctx.unbounded();
+
+        let config = FlatReassembleConfig {
+            switch_cycles: vec![1, 1],
+            write_back_mu: true,
+            merge_policy: MergePolicy::ArrivalOrder,
+            shared_bw: Some(32),
+            capacity: None,
+        };
+
+        ctx.add_child(GeneratorContext::new(|| stream0.into_iter(), exp1_snd));
+        ctx.add_child(GeneratorContext::new(|| stream1.into_iter(), exp2_snd));
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::ValStop(MultiHotN::new(vec![true, true], true), 0)].into_iter(),
+            in_sel_snd,
+        ));
+        ctx.add_child(FlatReassemble::<SimpleEvent, _, _>::new(
+            vec![exp1_rcv, exp2_rcv],
+            in_sel_rcv,
+            out_data_snd,
+            1,
+            config,
+            0,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            || ground_truth.into_iter(),
+            out_data_rcv,
+            tolerance_fn,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn flat_reassemble_capacity_drops_overflow_and_reports_count() {
+        // Stream 0 is capped at 1 contribution per group; its second and
+        // third elements should be dropped (dequeued, charged, but not
+        // reassembled) instead of appearing in the output.
+        let tile = |v: i32| Tile::new(Array2::from_shape_vec((1, 1), vec![v]).unwrap().into(), 4, true);
+
+        let stream0 = vec![
+            Elem::Val(tile(1)),
+            Elem::Val(tile(2)),
+            Elem::ValStop(tile(3), 1),
+        ];
+        let stream1 = vec![Elem::ValStop(tile(4), 1)];
+        let ground_truth = vec![Elem::Val(tile(1)), Elem::ValStop(tile(4), 2)];
+
+        let mut ctx = ProgramBuilder::default();
+        let (out_data_snd, out_data_rcv) = ctx.unbounded();
+        let (in_sel_snd, in_sel_rcv) = ctx.unbounded();
+        let (exp1_snd, exp1_rcv) = ctx.unbounded();
+        let (exp2_snd, exp2_rcv) = ctx.unbounded();
+        let (drop_snd, drop_rcv) = ctx.unbounded();
+
+        let config = FlatReassembleConfig {
+            switch_cycles: vec![1, 1],
+            write_back_mu: true,
+            merge_policy: MergePolicy::ArrivalOrder,
+            shared_bw: None,
+            capacity: Some(vec![1, 10]),
+        };
+
+        ctx.add_child(GeneratorContext::new(|| stream0.into_iter(), exp1_snd));
+        ctx.add_child(GeneratorContext::new(|| stream1.into_iter(), exp2_snd));
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::ValStop(MultiHotN::new(vec![true, true], true), 0)].into_iter(),
+            in_sel_snd,
+        ));
+        ctx.add_child(
+            FlatReassemble::<SimpleEvent, _, _>::new(
+                vec![exp1_rcv, exp2_rcv],
+                in_sel_rcv,
+                out_data_snd,
+                1,
+                config,
+                0,
+            )
+            .with_drop_counter(drop_snd),
+        );
+        ctx.add_child(ApproxCheckerContext::new(
+            || ground_truth.into_iter(),
+            out_data_rcv,
+            tolerance_fn,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            || vec![Elem::Val(2usize)].into_iter(),
+            drop_rcv,
+            |a: &Elem<usize>, b: &Elem<usize>| a == b,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
 }
@@ -0,0 +1,263 @@
+//! Rechunking adapter for oversized [`Tile`] payloads: splits a tile too
+//! large to buffer whole into row-wise sub-tiles of at most `chunk_elems`
+//! elements on one end ([`TileChunker`]) and coalesces the chunks back into
+//! the original tile on the other ([`TileDechunker`]) -- see
+//! `ChannelMap::get_chunked_receiver`, the usual entry point. The pair is
+//! wired back to back the same way [`crate::operator::broadcast::BroadcastContext`]
+//! sits between a producer and its targets: the producer and the eventual
+//! consumer only ever see whole `Elem<Tile<T>>`s, while the channel between
+//! the two internal nodes carries bounded-size [`Chunk`]s instead.
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::Elem;
+use crate::primitives::tile::Tile;
+
+/// One row-wise slice of a [`Tile`] in flight between [`TileChunker`] and
+/// [`TileDechunker`]. `has_continuation` is set on every chunk but the last
+/// one split from a given tile, so [`TileDechunker`] knows to keep
+/// accumulating rows rather than deliver what it has so far.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chunk<T> {
+    pub tile: Tile<T>,
+    pub has_continuation: bool,
+}
+
+impl<T: DAMType> DAMType for Chunk<T> {
+    fn dam_size(&self) -> usize {
+        self.tile.dam_size()
+    }
+}
+
+/// Splits each incoming tile's rows into chunks of at most `chunk_elems`
+/// elements (`max(1, chunk_elems / cols)` rows per chunk), tagging all but
+/// the last with `has_continuation: true`. Tiles backed by `csr`/`rle`
+/// instead of a dense `underlying` array, or with no backing data at all,
+/// aren't sliceable this way and pass through as a single chunk.
+#[context_macro]
+pub struct TileChunker<T: DAMType> {
+    in_stream: Receiver<Elem<Tile<T>>>,
+    out_stream: Sender<Elem<Chunk<T>>>,
+    chunk_elems: usize,
+}
+
+impl<T: DAMType> TileChunker<T>
+where
+    Elem<Tile<T>>: DAMType,
+    Elem<Chunk<T>>: DAMType,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<Tile<T>>>,
+        out_stream: Sender<Elem<Chunk<T>>>,
+        chunk_elems: usize,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            chunk_elems: chunk_elems.max(1),
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType> Context for TileChunker<T>
+where
+    Elem<Tile<T>>: DAMType,
+    Elem<Chunk<T>>: DAMType,
+{
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => {
+                    let (tile, stop) = match data {
+                        Elem::Val(tile) => (tile, None),
+                        Elem::ValStop(tile, level) => (tile, Some(level)),
+                    };
+                    let sub_tiles = split_tile(&tile, self.chunk_elems);
+                    let last = sub_tiles.len() - 1;
+                    for (i, sub_tile) in sub_tiles.into_iter().enumerate() {
+                        let has_continuation = i != last;
+                        let data = if has_continuation {
+                            Elem::Val(Chunk {
+                                tile: sub_tile,
+                                has_continuation: true,
+                            })
+                        } else {
+                            let chunk = Chunk {
+                                tile: sub_tile,
+                                has_continuation: false,
+                            };
+                            match stop {
+                                Some(level) => Elem::ValStop(chunk, level),
+                                None => Elem::Val(chunk),
+                            }
+                        };
+                        self.out_stream
+                            .enqueue(
+                                &self.time,
+                                ChannelElement {
+                                    time: self.time.tick(),
+                                    data,
+                                },
+                            )
+                            .unwrap();
+                    }
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+/// Coalesces the chunks [`TileChunker`] split a tile into back into the
+/// original whole tile before handing it to the consumer.
+#[context_macro]
+pub struct TileDechunker<T: DAMType> {
+    in_stream: Receiver<Elem<Chunk<T>>>,
+    out_stream: Sender<Elem<Tile<T>>>,
+}
+
+impl<T: DAMType> TileDechunker<T>
+where
+    Elem<Tile<T>>: DAMType,
+    Elem<Chunk<T>>: DAMType,
+{
+    pub fn new(in_stream: Receiver<Elem<Chunk<T>>>, out_stream: Sender<Elem<Tile<T>>>) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType> Context for TileDechunker<T>
+where
+    Elem<Tile<T>>: DAMType,
+    Elem<Chunk<T>>: DAMType,
+{
+    fn run(&mut self) {
+        let mut pending: Vec<Tile<T>> = Vec::new();
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => {
+                    let (chunk, stop) = match data {
+                        Elem::Val(chunk) => (chunk, None),
+                        Elem::ValStop(chunk, level) => (chunk, Some(level)),
+                    };
+                    let done = !chunk.has_continuation;
+                    pending.push(chunk.tile);
+                    if done {
+                        let tile = coalesce_rows(std::mem::take(&mut pending));
+                        let data = match stop {
+                            Some(level) => Elem::ValStop(tile, level),
+                            None => Elem::Val(tile),
+                        };
+                        self.out_stream
+                            .enqueue(
+                                &self.time,
+                                ChannelElement {
+                                    time: self.time.tick(),
+                                    data,
+                                },
+                            )
+                            .unwrap();
+                    }
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+/// Splits `tile`'s rows into sub-tiles of at most `chunk_elems` elements
+/// each, preserving `offset`/`col_offset` per sub-tile (a sub-tile entirely
+/// past the original's active-row `offset` gets `offset: 0`). Falls back to
+/// a single unsplit chunk when the tile has no dense `underlying` array to
+/// slice (CSR/RLE-backed, or blank).
+fn split_tile<T: Clone>(tile: &Tile<T>, chunk_elems: usize) -> Vec<Tile<T>> {
+    let cols = tile.shape.get(1).copied().unwrap_or(1).max(1);
+    let rows_per_chunk = (chunk_elems / cols).max(1);
+    let total_rows = tile.shape.first().copied().unwrap_or(0);
+
+    if tile.underlying.is_none()
+        || tile.csr.is_some()
+        || tile.rle.is_some()
+        || total_rows <= rows_per_chunk
+    {
+        return vec![tile.clone()];
+    }
+
+    let mut sub_tiles = Vec::new();
+    let mut row = 0;
+    while row < total_rows {
+        let end = (row + rows_per_chunk).min(total_rows);
+        sub_tiles.push(slice_rows(tile, row, end));
+        row = end;
+    }
+    sub_tiles
+}
+
+fn slice_rows<T: Clone>(tile: &Tile<T>, start: usize, end: usize) -> Tile<T> {
+    let underlying = tile.underlying.as_ref().map(|arr| {
+        arr.slice(ndarray::s![start..end, ..]).to_owned().into_shared()
+    });
+    let mut shape = tile.shape.clone();
+    shape[0] = end - start;
+    Tile {
+        shape,
+        bytes_per_elem: tile.bytes_per_elem,
+        read_from_mu: tile.read_from_mu,
+        underlying,
+        offset: tile.offset.saturating_sub(start).min(end - start),
+        col_offset: tile.col_offset,
+        pad: tile.pad.clone(),
+        row_align: tile.row_align,
+        csr: None,
+        rle: None,
+    }
+}
+
+/// Inverse of [`split_tile`]'s row-splitting: stacks the rows of every
+/// sub-tile back into one dense tile, in order.
+fn coalesce_rows<T: Clone>(mut sub_tiles: Vec<Tile<T>>) -> Tile<T> {
+    if sub_tiles.len() == 1 {
+        return sub_tiles.pop().unwrap();
+    }
+    let total_rows: usize = sub_tiles.iter().map(|t| t.shape.first().copied().unwrap_or(0)).sum();
+    let total_offset: usize = sub_tiles.iter().map(|t| t.offset).sum();
+    let first = &sub_tiles[0];
+    let cols = first.shape.get(1).copied().unwrap_or(0);
+
+    let views: Vec<_> = sub_tiles
+        .iter()
+        .map(|t| {
+            t.underlying
+                .as_ref()
+                .expect("chunked tile missing its underlying data")
+                .view()
+        })
+        .collect();
+    let merged = ndarray::concatenate(ndarray::Axis(0), &views)
+        .expect("chunk row counts must be consistent")
+        .into_shared();
+
+    Tile {
+        shape: vec![total_rows, cols],
+        bytes_per_elem: first.bytes_per_elem,
+        read_from_mu: first.read_from_mu,
+        underlying: Some(merged),
+        offset: total_offset,
+        col_offset: first.col_offset,
+        pad: first.pad.clone(),
+        row_align: first.row_align,
+        csr: None,
+        rle: None,
+    }
+}
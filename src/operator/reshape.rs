@@ -1,5 +1,23 @@
 use crate::primitives::elem::{Elem, StopType};
+use crate::utils::calculation::div_ceil;
 use dam::context_tools::*;
+use dam::simulation::ProgramBuilder;
+
+/// Padding strategy for the innermost split when a dimension isn't evenly
+/// divisible by `chunk_size`. Mirrors NumPy's `pad` modes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PadMode<InputType> {
+    /// Pad with a fixed value.
+    Constant(InputType),
+    /// Repeat the last real element of the partial chunk.
+    Edge,
+    /// Mirror inward from the last real element, excluding it (NumPy's
+    /// `reflect` semantics); the mirrored index clamps to the start of the
+    /// chunk once the needed pad length exceeds the buffered data.
+    Reflect,
+    /// Cycle from the start of the partial chunk.
+    Wrap,
+}
 
 #[context_macro]
 pub struct Reshape<InputType: Clone> {
@@ -7,7 +25,7 @@ pub struct Reshape<InputType: Clone> {
     out_stream: Sender<Elem<InputType>>,
     split_dim: usize,
     chunk_size: usize,
-    pad_val: Option<InputType>,
+    pad_mode: Option<PadMode<InputType>>,
     input_stream_rank: StopType,
     add_outer_dim: bool,
     id: u32,
@@ -22,7 +40,7 @@ where
         out_stream: Sender<Elem<InputType>>,
         split_dim: usize,
         chunk_size: usize,
-        pad_val: Option<InputType>,
+        pad_mode: Option<PadMode<InputType>>,
         input_stream_rank: StopType,
         add_outer_dim: bool,
         id: u32,
@@ -32,7 +50,7 @@ where
             out_stream,
             split_dim,
             chunk_size,
-            pad_val,
+            pad_mode,
             input_stream_rank,
             add_outer_dim,
             id,
@@ -42,20 +60,168 @@ where
         ctx.out_stream.attach_sender(&ctx);
         ctx
     }
+
+    /// Computes the value for the `i`-th padding slot (0-indexed from the
+    /// first element past the real data) needed to fill out a partial
+    /// chunk, given the elements buffered since the last emitted
+    /// `ValStop`. Panics if no `pad_mode` was configured.
+    fn pad_value(&self, buf: &[InputType], i: usize) -> InputType {
+        let n = buf.len();
+        match &self.pad_mode {
+            Some(PadMode::Constant(v)) => v.clone(),
+            Some(PadMode::Edge) => buf[n - 1].clone(),
+            Some(PadMode::Wrap) => buf[i % n].clone(),
+            Some(PadMode::Reflect) => {
+                let idx = (n as isize - 2 - i as isize).max(0) as usize;
+                buf[idx].clone()
+            }
+            None => panic!(
+                "Reshape {}: a partial chunk of {n} elements (chunk_size {}) needs padding; \
+                provide a pad_mode",
+                self.id, self.chunk_size
+            ),
+        }
+    }
+
+    /// Resolves a NumPy-style `target_shape` for a single axis of length
+    /// `axis_len`, inferring at most one `-1` entry from `axis_len`.
+    ///
+    /// When the known (non`-1`) entries don't evenly divide `axis_len`,
+    /// this rounds the inferred entry up rather than erroring, as long as
+    /// `allow_padding` is set -- the caller must then pass a `pad_mode`
+    /// through to the chain, matching the padding the `split_dim == 0`
+    /// path in [`Reshape::run`] already performs on a non-divisible chunk.
+    fn resolve_target_shape(
+        axis_len: usize,
+        target_shape: &[isize],
+        allow_padding: bool,
+    ) -> Vec<usize> {
+        let neg_count = target_shape.iter().filter(|&&d| d == -1).count();
+        assert!(
+            neg_count <= 1,
+            "Reshape::with_shape: target_shape may contain at most one -1 entry, got {neg_count}"
+        );
+        for &d in target_shape {
+            assert!(
+                d == -1 || d > 0,
+                "Reshape::with_shape: target_shape entries must be positive or -1, got {d}"
+            );
+        }
+
+        let known_product: usize = target_shape
+            .iter()
+            .filter(|&&d| d != -1)
+            .map(|&d| d as usize)
+            .product();
+
+        if neg_count == 0 {
+            assert_eq!(
+                known_product, axis_len,
+                "Reshape::with_shape: target_shape accounts for {known_product} elements, \
+                but the axis being reshaped has {axis_len}"
+            );
+            return target_shape.iter().map(|&d| d as usize).collect();
+        }
+
+        assert!(
+            known_product > 0,
+            "Reshape::with_shape: cannot infer a -1 dimension alongside a zero-sized axis"
+        );
+        let inferred = if axis_len % known_product == 0 {
+            axis_len / known_product
+        } else {
+            assert!(
+                allow_padding,
+                "Reshape::with_shape: {axis_len} elements do not divide evenly by {known_product}; \
+                provide a pad_mode to pad the inferred dimension"
+            );
+            div_ceil(axis_len as u64, known_product as u64) as usize
+        };
+
+        target_shape
+            .iter()
+            .map(|&d| if d == -1 { inferred } else { d as usize })
+            .collect()
+    }
+
+    /// Generalizes [`Reshape::new`] to expand a single axis of length
+    /// `axis_len` into an arbitrary `target_shape` (e.g. `[-1, D]`),
+    /// following NumPy's `reshape` inference rules for the one `-1` entry
+    /// `resolve_target_shape` allows.
+    ///
+    /// This lowers to a chain of plain splits, one per extra axis,
+    /// innermost dimension first -- each chained split shifts `split_dim`
+    /// out by one to account for the axis inserted below it. Fresh
+    /// channels linking the stages are allocated from `builder`, which
+    /// also takes ownership of every stage but the last; the caller adds
+    /// the returned (final, outermost) stage the same way it would a
+    /// plain `Reshape::new`. An arbitrary rank-R -> rank-R' transform
+    /// beyond a single axis composes this with the merge context requested
+    /// separately, since shrinking rank isn't expressible as a split chain.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_shape<'a>(
+        in_stream: Receiver<Elem<InputType>>,
+        out_stream: Sender<Elem<InputType>>,
+        split_dim: usize,
+        axis_len: usize,
+        target_shape: Vec<isize>,
+        pad_mode: Option<PadMode<InputType>>,
+        input_stream_rank: StopType,
+        add_outer_dim: bool,
+        builder: &mut ProgramBuilder<'a>,
+        id: u32,
+    ) -> Self {
+        assert!(
+            !target_shape.is_empty(),
+            "Reshape::with_shape: target_shape must name at least one axis"
+        );
+        let resolved = Self::resolve_target_shape(axis_len, &target_shape, pad_mode.is_some());
+        let stage_count = resolved.len();
+
+        let mut cur_in = in_stream;
+        for (step, &chunk_size) in resolved.iter().rev().enumerate().take(stage_count - 1) {
+            let (stage_snd, stage_rcv) = builder.unbounded();
+            builder.add_child(Reshape::new(
+                cur_in,
+                stage_snd,
+                split_dim + step,
+                chunk_size,
+                pad_mode.clone(),
+                input_stream_rank,
+                false,
+                id,
+            ));
+            cur_in = stage_rcv;
+        }
+
+        Reshape::new(
+            cur_in,
+            out_stream,
+            split_dim + stage_count - 1,
+            resolved[0],
+            pad_mode,
+            input_stream_rank,
+            add_outer_dim,
+            id,
+        )
+    }
 }
 
 impl<InputType: DAMType> Context for Reshape<InputType> {
     fn run(&mut self) {
         if self.split_dim == 0 {
-            let mut counter = 0;
+            // Buffers the elements seen since the last emitted ValStop, so
+            // Edge/Reflect/Wrap padding can reference real data instead of
+            // just a constant.
+            let mut buf: Vec<InputType> = vec![];
             loop {
                 match self.in_stream.dequeue(&self.time) {
                     Ok(ChannelElement { time: _, data }) => match data {
                         Elem::Val(x) => {
-                            counter += 1;
+                            buf.push(x.clone());
 
-                            let output_elem = if counter == self.chunk_size {
-                                counter = 0;
+                            let output_elem = if buf.len() == self.chunk_size {
+                                buf.clear();
                                 if self.add_outer_dim {
                                     let stop_level = match self.in_stream.peek_next(&self.time) {
                                         Ok(ChannelElement { time: _, data: _ }) => 1,
@@ -79,9 +245,9 @@ impl<InputType: DAMType> Context for Reshape<InputType> {
                                 .unwrap();
                         }
                         Elem::ValStop(x, s) => {
-                            counter += 1;
-                            if counter == self.chunk_size {
-                                counter = 0;
+                            buf.push(x.clone());
+                            if buf.len() == self.chunk_size {
+                                buf.clear();
                                 self.out_stream
                                     .enqueue(
                                         &self.time,
@@ -102,19 +268,13 @@ impl<InputType: DAMType> Context for Reshape<InputType> {
                                     )
                                     .unwrap();
 
-                                assert!(
-                                    self.pad_val.is_some(),
-                                    "When splitting the innermost dimension, \
-                                    we pad if the dimension is not exactly divisible by the chunk size. \
-                                    Therefore, the pad_val must be provided."
-                                );
-
                                 // pad so that the dimension is divisible by the chunk size
-                                for i in 0..self.chunk_size - counter {
-                                    let padded_val = if i == self.chunk_size - counter - 1 {
-                                        Elem::ValStop(self.pad_val.clone().unwrap(), s + 1)
+                                let remaining = self.chunk_size - buf.len();
+                                for i in 0..remaining {
+                                    let padded_val = if i == remaining - 1 {
+                                        Elem::ValStop(self.pad_value(&buf, i), s + 1)
                                     } else {
-                                        Elem::Val(self.pad_val.clone().unwrap())
+                                        Elem::Val(self.pad_value(&buf, i))
                                     };
 
                                     self.out_stream
@@ -127,31 +287,26 @@ impl<InputType: DAMType> Context for Reshape<InputType> {
                                         )
                                         .unwrap();
                                 }
-                                counter = 0;
+                                buf.clear();
                             };
                         }
                     },
                     Err(_) => {
-                        if 0 < counter && counter < self.chunk_size {
+                        if !buf.is_empty() && buf.len() < self.chunk_size {
                             // use this as if we got a done token
-                            assert!(
-                                self.pad_val.is_some(),
-                                "When splitting the innermost dimension, \
-                                we pad if the dimension is not exactly divisible by the chunk size. \
-                                Therefore, the pad_val must be provided."
-                            );
                             assert!(
                                 self.input_stream_rank == 0,
                                 "input stream rank should be 0 to enter here"
                             );
                             // pad so that the dimension is divisible by the chunk size
-                            for i in 0..self.chunk_size - counter {
-                                let is_last = i == self.chunk_size - counter - 1;
+                            let remaining = self.chunk_size - buf.len();
+                            for i in 0..remaining {
+                                let is_last = i == remaining - 1;
                                 let padded_val = if is_last {
                                     let stop_lev = if self.add_outer_dim { 2 } else { 1 };
-                                    Elem::ValStop(self.pad_val.clone().unwrap(), stop_lev)
+                                    Elem::ValStop(self.pad_value(&buf, i), stop_lev)
                                 } else {
-                                    Elem::Val(self.pad_val.clone().unwrap())
+                                    Elem::Val(self.pad_value(&buf, i))
                                 };
 
                                 self.out_stream
@@ -235,7 +390,7 @@ mod tests {
 
     use crate::primitives::{buffer::Buffer, elem::Elem, tile::Tile};
 
-    use super::Reshape;
+    use super::{PadMode, Reshape};
 
     #[test]
     fn reshape_0d_with_pad() {
@@ -280,12 +435,12 @@ mod tests {
             out_snd,
             0,
             4,
-            Some(Tile::new_blank_padded(
+            Some(PadMode::Constant(Tile::new_blank_padded(
                 tile_shape.clone(),
                 BYTES_PER_ELEM,
                 READ_FROM_MU,
                 0,
-            )),
+            ))),
             2,
             false,
             0,
@@ -363,12 +518,12 @@ mod tests {
             out_snd,
             0,
             4,
-            Some(Tile::new_blank_padded(
+            Some(PadMode::Constant(Tile::new_blank_padded(
                 tile_shape.clone(),
                 BYTES_PER_ELEM,
                 READ_FROM_MU,
                 0,
-            )),
+            ))),
             0,
             false,
             0,
@@ -448,12 +603,12 @@ mod tests {
             out_snd,
             0,
             3,
-            Some(Tile::new_blank_padded(
+            Some(PadMode::Constant(Tile::new_blank_padded(
                 tile_shape.clone(),
                 BYTES_PER_ELEM,
                 READ_FROM_MU,
                 0,
-            )),
+            ))),
             0,
             true,
             0,
@@ -524,12 +679,12 @@ mod tests {
             out_snd,
             0,
             4,
-            Some(Tile::new_blank_padded(
+            Some(PadMode::Constant(Tile::new_blank_padded(
                 tile_shape.clone(),
                 BYTES_PER_ELEM,
                 READ_FROM_MU,
                 0,
-            )),
+            ))),
             0,
             true,
             0,
@@ -713,4 +868,285 @@ mod tests {
             .unwrap()
             .run(Default::default());
     }
+
+    #[test]
+    fn with_shape_matches_a_manual_split_chain() {
+        // (9) => (3, 3), expressed as with_shape(target_shape = [3, 3])
+        // instead of a single Reshape::new(split_dim = 0, chunk_size = 3)
+        // call -- both should produce the same nested ValStop structure.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(|| (1..=9u32).map(Elem::Val), in_snd));
+
+        let reshape =
+            Reshape::with_shape(in_rcv, out_snd, 0, 9, vec![3, 3], None, 0, true, &mut ctx, 0);
+        ctx.add_child(reshape);
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::ValStop(3, 1),
+                    Elem::Val(4),
+                    Elem::Val(5),
+                    Elem::ValStop(6, 1),
+                    Elem::Val(7),
+                    Elem::Val(8),
+                    Elem::ValStop(9, 2),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn with_shape_infers_negative_one_across_multiple_stages() {
+        // (24) => (-1, 3, 4) => (2, 3, 4), chaining two intermediate split
+        // stages ahead of the final one.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || (1..=24u32).map(Elem::Val),
+            in_snd,
+        ));
+
+        let reshape = Reshape::with_shape(
+            in_rcv,
+            out_snd,
+            0,
+            24,
+            vec![-1, 3, 4],
+            None,
+            0,
+            true,
+            &mut ctx,
+            0,
+        );
+        ctx.add_child(reshape);
+
+        let mut expected = vec![];
+        for outer in 0..2u32 {
+            for middle in 0..3u32 {
+                for inner in 0..4u32 {
+                    let val = outer * 12 + middle * 4 + inner + 1;
+                    let stop = if inner != 3 {
+                        None
+                    } else if middle != 2 {
+                        Some(1)
+                    } else if outer != 1 {
+                        Some(2)
+                    } else {
+                        Some(3)
+                    };
+                    expected.push(match stop {
+                        None => Elem::Val(val),
+                        Some(s) => Elem::ValStop(val, s),
+                    });
+                }
+            }
+        }
+
+        ctx.add_child(ApproxCheckerContext::new(
+            move || expected.clone().into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn pad_mode_edge_repeats_last_buffered_element() {
+        // 7 elements split into chunks of 4; the trailing partial chunk of
+        // 3 is padded by repeating its last real element (7).
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(|| (1..=7u32).map(Elem::Val), in_snd));
+
+        ctx.add_child(Reshape::new(
+            in_rcv,
+            out_snd,
+            0,
+            4,
+            Some(PadMode::Edge),
+            0,
+            false,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::ValStop(4, 1),
+                    Elem::Val(5),
+                    Elem::Val(6),
+                    Elem::Val(7),
+                    Elem::ValStop(7, 1),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn pad_mode_wrap_cycles_from_the_start_of_the_buffer() {
+        // same partial chunk [5, 6, 7]; Wrap pads with buf[0] == 5.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(|| (1..=7u32).map(Elem::Val), in_snd));
+
+        ctx.add_child(Reshape::new(
+            in_rcv,
+            out_snd,
+            0,
+            4,
+            Some(PadMode::Wrap),
+            0,
+            false,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::ValStop(4, 1),
+                    Elem::Val(5),
+                    Elem::Val(6),
+                    Elem::Val(7),
+                    Elem::ValStop(5, 1),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn pad_mode_reflect_mirrors_inward_excluding_the_boundary() {
+        // same partial chunk [5, 6, 7]; Reflect mirrors from 7 excluding
+        // it, so the single pad slot is buf[n - 2] == 6.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(|| (1..=7u32).map(Elem::Val), in_snd));
+
+        ctx.add_child(Reshape::new(
+            in_rcv,
+            out_snd,
+            0,
+            4,
+            Some(PadMode::Reflect),
+            0,
+            false,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::ValStop(4, 1),
+                    Elem::Val(5),
+                    Elem::Val(6),
+                    Elem::Val(7),
+                    Elem::ValStop(6, 1),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn pad_mode_reflect_clamps_when_padding_exceeds_the_buffer() {
+        // 5 elements split into chunks of 4: partial chunk is [5] (len 1),
+        // needing 3 pad slots. With nothing to mirror past the single
+        // buffered element, Reflect clamps every slot to buf[0].
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(|| (1..=5u32).map(Elem::Val), in_snd));
+
+        ctx.add_child(Reshape::new(
+            in_rcv,
+            out_snd,
+            0,
+            4,
+            Some(PadMode::Reflect),
+            0,
+            false,
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::ValStop(4, 1),
+                    Elem::Val(5),
+                    Elem::Val(5),
+                    Elem::Val(5),
+                    Elem::ValStop(5, 1),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
 }
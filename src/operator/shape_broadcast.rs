@@ -0,0 +1,230 @@
+use crate::primitives::elem::{Elem, StopType};
+use dam::context_tools::*;
+
+/// NumPy-style broadcasting of a fiber against a reference shape: not to be
+/// confused with [`crate::operator::broadcast::BroadcastContext`], which
+/// fans a single channel out to multiple consumers.
+///
+/// `ref_stream` carries only the stop structure of the target shape (its
+/// payload is discarded); for every reference fiber at `broadcast_rank`,
+/// the corresponding `in_stream` fiber is either:
+/// - length 1: the single value is replicated once per reference
+///   coordinate (the actual broadcast case), or
+/// - the same length as the reference fiber: forwarded unchanged.
+///
+/// Any other length is a shape mismatch and panics, the same way `Promote`
+/// expects its caller to have picked a `promote_rank` consistent with the
+/// stream it's given.
+#[context_macro]
+pub struct Broadcast<T: DAMType> {
+    in_stream: Receiver<Elem<T>>,
+    ref_stream: Receiver<Elem<()>>,
+    out_stream: Sender<Elem<T>>,
+    broadcast_rank: StopType,
+}
+
+impl<T: DAMType> Broadcast<T>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<T>>,
+        ref_stream: Receiver<Elem<()>>,
+        out_stream: Sender<Elem<T>>,
+        broadcast_rank: StopType,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            ref_stream,
+            out_stream,
+            broadcast_rank,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.ref_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+
+    fn emit(&mut self, data: Elem<T>) {
+        self.out_stream
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data,
+                },
+            )
+            .unwrap();
+    }
+}
+
+impl<T: DAMType> Context for Broadcast<T> {
+    fn run(&mut self) {
+        loop {
+            // Start of a new fiber: the first data element tells us whether
+            // this fiber needs stretching (it's already closed, so it's a
+            // singleton) or passes through unchanged (it's the first of
+            // several).
+            let first = match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { data, .. }) => data,
+                Err(_) => return,
+            };
+
+            let mut stretch_value = match &first {
+                Elem::ValStop(x, _) => Some(x.clone()),
+                Elem::Val(_) => None,
+            };
+            let mut pending_passthrough = match &first {
+                Elem::Val(_) => Some(first),
+                Elem::ValStop(_, _) => None,
+            };
+
+            loop {
+                let ref_elem = match self.ref_stream.dequeue(&self.time) {
+                    Ok(ChannelElement { data, .. }) => data,
+                    Err(_) => return,
+                };
+                let ref_rank = match ref_elem {
+                    Elem::ValStop(_, s) => Some(s),
+                    Elem::Val(_) => None,
+                };
+                let fiber_closes = ref_rank.is_some();
+
+                let out = if let Some(x) = stretch_value.clone() {
+                    // Stretch mode: replicate the single held value. Every
+                    // repeat becomes its own closed singleton fiber, unless
+                    // this repeat also closes a rank above `broadcast_rank`,
+                    // in which case that outer close is propagated as-is.
+                    match ref_rank {
+                        Some(s) if s > self.broadcast_rank => Elem::ValStop(x, s),
+                        _ => Elem::ValStop(x, 1),
+                    }
+                } else {
+                    let data_elem = pending_passthrough
+                        .take()
+                        .unwrap_or_else(|| match self.in_stream.dequeue(&self.time) {
+                            Ok(ChannelElement { data, .. }) => data,
+                            Err(_) => panic!(
+                                "Broadcast: data fiber ended before reference fiber (length mismatch)"
+                            ),
+                        });
+                    match (data_elem, ref_rank) {
+                        (Elem::Val(x), None) => Elem::Val(x),
+                        (Elem::Val(_), Some(_)) => panic!(
+                            "Broadcast: reference fiber ended before data fiber (length mismatch)"
+                        ),
+                        (Elem::ValStop(_, s), None) => panic!(
+                            "Broadcast: data fiber ended before reference fiber (length mismatch); got ValStop(_, {s})"
+                        ),
+                        (Elem::ValStop(x, ds), Some(s)) if s > self.broadcast_rank => {
+                            Elem::ValStop(x, s)
+                        }
+                        (Elem::ValStop(x, ds), Some(_)) => Elem::ValStop(x, ds),
+                    }
+                };
+
+                self.emit(out);
+
+                if fiber_closes {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::{
+        simulation::ProgramBuilder,
+        utility_contexts::{ApproxCheckerContext, GeneratorContext},
+    };
+
+    use crate::primitives::elem::Elem;
+
+    use super::Broadcast;
+
+    #[test]
+    fn broadcast_stretches_length_one_fiber() {
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (ref_snd, ref_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        // Data: two singleton fibers, shape [2, 1].
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::ValStop(10u32, 1), Elem::ValStop(20, 2)].into_iter(),
+            in_snd,
+        ));
+        // Reference: two fibers of length 4, shape [2, 4].
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(()),
+                    Elem::Val(()),
+                    Elem::Val(()),
+                    Elem::ValStop((), 1),
+                    Elem::Val(()),
+                    Elem::Val(()),
+                    Elem::Val(()),
+                    Elem::ValStop((), 2),
+                ]
+                .into_iter()
+            },
+            ref_snd,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            || {
+                vec![
+                    Elem::ValStop(10u32, 1),
+                    Elem::ValStop(10, 1),
+                    Elem::ValStop(10, 1),
+                    Elem::ValStop(10, 1),
+                    Elem::ValStop(20, 1),
+                    Elem::ValStop(20, 1),
+                    Elem::ValStop(20, 1),
+                    Elem::ValStop(20, 2),
+                ]
+                .into_iter()
+            },
+            out_rcv,
+            |x, y| x == y,
+        ));
+        ctx.add_child(Broadcast::new(in_rcv, ref_rcv, out_snd, 0));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn broadcast_passes_through_matching_length_fiber() {
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (ref_snd, ref_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        // Data already matches the reference's fiber length: no stretching.
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(100u32), Elem::Val(200), Elem::ValStop(300, 1)].into_iter(),
+            in_snd,
+        ));
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(()), Elem::Val(()), Elem::ValStop((), 1)].into_iter(),
+            ref_snd,
+        ));
+        ctx.add_child(ApproxCheckerContext::new(
+            || vec![Elem::Val(100u32), Elem::Val(200), Elem::ValStop(300, 1)].into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+        ctx.add_child(Broadcast::new(in_rcv, ref_rcv, out_snd, 0));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
@@ -0,0 +1,315 @@
+use crate::primitives::elem::{Elem, StopType};
+use dam::context_tools::*;
+
+/// NumPy-style strided slice (`start:stop:step`) over a nested `Elem<T>`
+/// stream, with one independent window per rank. `windows[0]` indexes the
+/// elements of the innermost fiber, and `windows[r]` (`r >= 1`) indexes the
+/// fibers closed by level-`r` stops within the fiber enclosing them; a
+/// token is kept only if every configured window keeps the index that
+/// applies to it (a rank with no configured window always keeps). A
+/// token's *native rank* is 0 for `Elem::Val`, or `s` for
+/// `Elem::ValStop(_, s)`: that rank's running counter increments when it is
+/// seen, and every counter for a *lower* rank resets to zero, mirroring how
+/// a `ValStop` at level `s` closes every nested fiber below it.
+///
+/// Dropping a token can discard the fiber-closing boundary it carried, so
+/// the highest dropped stop level seen since the last kept token is held
+/// and transferred onto the last *kept* token's terminating `ValStop`
+/// instead of being lost.
+///
+/// Negative `start`/`stop` (NumPy's "from the end") can't be resolved
+/// until a fiber's length is known, so they're only supported on rank 0:
+/// when `windows[0]` has a negative bound, the whole innermost fiber is
+/// buffered until its closing `ValStop` arrives, then the window is
+/// resolved against the buffered length before replaying kept elements.
+/// Combining a negative rank-0 window with additional outer-rank windows
+/// is not supported -- only `windows[0]` is applied in that mode.
+#[context_macro]
+pub struct Slice<InputType: Clone> {
+    in_stream: Receiver<Elem<InputType>>,
+    out_stream: Sender<Elem<InputType>>,
+    windows: Vec<(i64, i64, i64)>,
+    id: u32,
+}
+
+impl<InputType: DAMType> Slice<InputType>
+where
+    Self: Context,
+{
+    pub fn new(
+        in_stream: Receiver<Elem<InputType>>,
+        out_stream: Sender<Elem<InputType>>,
+        windows: Vec<(i64, i64, i64)>,
+        id: u32,
+    ) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            windows,
+            id,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+
+    /// Resolves a possibly-negative `start`/`stop` against a known fiber
+    /// length (NumPy semantics: `-1` means the last element).
+    fn resolve(window: (i64, i64, i64), len: usize) -> (i64, i64, i64) {
+        let (start, stop, step) = window;
+        let resolve_one = |v: i64| if v < 0 { v + len as i64 } else { v };
+        (resolve_one(start), resolve_one(stop), step)
+    }
+
+    fn keeps(window: (i64, i64, i64), i: i64) -> bool {
+        let (start, stop, step) = window;
+        start <= i && i < stop && (i - start) % step == 0
+    }
+
+    fn emit(&mut self, data: Elem<InputType>) {
+        self.out_stream
+            .enqueue(
+                &self.time,
+                ChannelElement {
+                    time: self.time.tick(),
+                    data,
+                },
+            )
+            .unwrap();
+    }
+}
+
+impl<InputType: DAMType> Context for Slice<InputType> {
+    fn run(&mut self) {
+        // Only rank 0 can buffer (see the doc comment): a negative bound
+        // there means the whole innermost fiber must be held until its
+        // length is known.
+        let buffer_rank0 = self
+            .windows
+            .first()
+            .is_some_and(|&(start, stop, _)| start < 0 || stop < 0);
+
+        let mut group: Vec<i64> = vec![0; self.windows.len()];
+        let mut pending: Option<(InputType, StopType)> = None;
+        let mut rank0_buf: Vec<InputType> = Vec::new();
+
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => {
+                    let (value, native_rank) = match &data {
+                        Elem::Val(x) => (x.clone(), 0),
+                        Elem::ValStop(x, s) => (x.clone(), *s),
+                    };
+
+                    if buffer_rank0 {
+                        rank0_buf.push(value);
+                        if native_rank == 0 {
+                            continue; // still inside the current rank-0 fiber
+                        }
+                        let resolved = Self::resolve(self.windows[0], rank0_buf.len());
+                        let last = rank0_buf.len() - 1;
+                        let buffered = std::mem::take(&mut rank0_buf);
+                        for (i, x) in buffered.into_iter().enumerate() {
+                            if Self::keeps(resolved, i as i64) {
+                                if let Some((px, ps)) = pending.take() {
+                                    self.emit(Elem::ValStop(px, ps));
+                                }
+                                if i == last {
+                                    pending = Some((x, native_rank));
+                                } else {
+                                    self.emit(Elem::Val(x));
+                                }
+                            } else if i == last {
+                                if let Some((_, ref mut ps)) = pending {
+                                    if native_rank > *ps {
+                                        *ps = native_rank;
+                                    }
+                                }
+                            }
+                        }
+                        continue;
+                    }
+
+                    // Eager path: the same per-rank group counting as the
+                    // original single-rank `Slice`, generalized to
+                    // `windows.len()` independent ranks. A token is kept only
+                    // if every rank's window keeps the group it lies in.
+                    let keep = self
+                        .windows
+                        .iter()
+                        .zip(group.iter())
+                        .all(|(&window, &g)| Self::keeps(window, g));
+
+                    match &data {
+                        Elem::Val(_) => {
+                            if keep {
+                                if let Some((px, ps)) = pending.take() {
+                                    self.emit(Elem::ValStop(px, ps));
+                                }
+                                self.emit(Elem::Val(value));
+                            }
+                        }
+                        Elem::ValStop(_, s) => {
+                            if keep {
+                                if let Some((px, ps)) = pending.take() {
+                                    self.emit(Elem::ValStop(px, ps));
+                                }
+                                pending = Some((value, *s));
+                            } else if let Some((_, ref mut ps)) = pending {
+                                if *s > *ps {
+                                    *ps = *s;
+                                }
+                            }
+                        }
+                    }
+
+                    if (native_rank as usize) < group.len() {
+                        group[native_rank as usize] += 1;
+                    }
+                    for g in group.iter_mut().take(native_rank as usize) {
+                        *g = 0;
+                    }
+                }
+                Err(_) => {
+                    if let Some((px, ps)) = pending.take() {
+                        self.emit(Elem::ValStop(px, ps));
+                    }
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::{
+        simulation::ProgramBuilder,
+        utility_contexts::{ApproxCheckerContext, GeneratorContext},
+    };
+
+    use crate::primitives::elem::Elem;
+
+    use super::Slice;
+
+    #[test]
+    fn slice_keeps_every_other_group() {
+        // 4 groups of 1 element each: [0,1,2,3] with stop levels
+        // [1,1,1,2] (2 marks end of stream). Rank 1's window (0,4,2) keeps
+        // groups 0 and 2; group 3's stream-ending level 2 must carry onto
+        // the last kept element (group 2).
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::ValStop(0, 1),
+                    Elem::ValStop(1, 1),
+                    Elem::ValStop(2, 1),
+                    Elem::ValStop(3, 2),
+                ]
+                .into_iter()
+            },
+            in_snd,
+        ));
+
+        ctx.add_child(Slice::new(
+            in_rcv,
+            out_snd,
+            vec![(0, i64::MAX, 1), (0, 4, 2)],
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || vec![Elem::ValStop(0, 1), Elem::ValStop(2, 2)].into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn slice_keeps_val_elements_of_kept_group() {
+        // one kept group (index 0) with 2 Val elements then its ValStop,
+        // followed by one dropped group (index 1).
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::ValStop(3, 1),
+                    Elem::Val(4),
+                    Elem::ValStop(5, 2),
+                ]
+                .into_iter()
+            },
+            in_snd,
+        ));
+
+        ctx.add_child(Slice::new(
+            in_rcv,
+            out_snd,
+            vec![(0, i64::MAX, 1), (0, 1, 1)],
+            0,
+        ));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || vec![Elem::Val(1), Elem::Val(2), Elem::ValStop(3, 2)].into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn slice_negative_bounds_buffer_the_fiber() {
+        // Rank 0 window (-2, MAX, 1) keeps the last two elements of a
+        // length-5 fiber; the fiber's length isn't known until its
+        // closing ValStop(5, 1) arrives, so the whole thing is buffered.
+        let mut ctx = ProgramBuilder::default();
+
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || {
+                vec![
+                    Elem::Val(1),
+                    Elem::Val(2),
+                    Elem::Val(3),
+                    Elem::Val(4),
+                    Elem::ValStop(5, 1),
+                ]
+                .into_iter()
+            },
+            in_snd,
+        ));
+
+        ctx.add_child(Slice::new(in_rcv, out_snd, vec![(-2, i64::MAX, 1)], 0));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || vec![Elem::Val(4), Elem::ValStop(5, 1)].into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::{Arc, Mutex};
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::{read_varint, write_varint, Elem, StopType, StreamCodec};
+
+/// Dumps every `Elem<T>` it receives into a deterministic, length-prefixed
+/// binary blob appended to `sink` once the input stream closes: a tag byte
+/// per element (`0` = `Val`, `1` = `ValStop`), the element's
+/// [`StreamCodec::to_bytes`] encoding, and -- for `ValStop` -- a
+/// [`write_varint`]-encoded `StopType` rank, all preceded by a 4-byte
+/// little-endian length of that framed payload. `sink` is shared (rather
+/// than sent down an output channel) so the caller can read it back out
+/// after the simulation finishes, the same way a captured fixture would be
+/// written to a file.
+#[context_macro]
+pub struct StreamWriterContext<T: DAMType> {
+    in_stream: Receiver<Elem<T>>,
+    sink: Arc<Mutex<Vec<u8>>>,
+}
+
+impl<T: DAMType + StreamCodec> StreamWriterContext<T> {
+    pub fn new(in_stream: Receiver<Elem<T>>, sink: Arc<Mutex<Vec<u8>>>) -> Self {
+        let ctx = Self {
+            in_stream,
+            sink,
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType + StreamCodec> Context for StreamWriterContext<T> {
+    fn run(&mut self) {
+        let mut payload = Vec::new();
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: _, data }) => match data {
+                    Elem::Val(value) => {
+                        payload.push(0u8);
+                        value.to_bytes(&mut payload);
+                    }
+                    Elem::ValStop(value, stop) => {
+                        payload.push(1u8);
+                        value.to_bytes(&mut payload);
+                        write_varint(stop as u64, &mut payload);
+                    }
+                },
+                Err(_) => break,
+            }
+        }
+
+        let mut blob = self.sink.lock().unwrap();
+        blob.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        blob.extend_from_slice(&payload);
+    }
+}
+
+/// Replays a blob written by [`StreamWriterContext`] back onto `out_stream`.
+/// Decodes one tag-framed element at a time and `enqueue`s it immediately --
+/// it never materializes the whole decoded stream in memory first, so a
+/// replayed fixture costs no more memory at once than the live pipeline did.
+#[context_macro]
+pub struct StreamReaderContext<T: DAMType> {
+    blob: Vec<u8>,
+    out_stream: Sender<Elem<T>>,
+}
+
+impl<T: DAMType + StreamCodec> StreamReaderContext<T> {
+    pub fn new(blob: Vec<u8>, out_stream: Sender<Elem<T>>) -> Self {
+        let ctx = Self {
+            blob,
+            out_stream,
+            context_info: Default::default(),
+        };
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+}
+
+impl<T: DAMType + StreamCodec> Context for StreamReaderContext<T> {
+    fn run(&mut self) {
+        let mut cursor = 0;
+        let payload_len =
+            u32::from_le_bytes(self.blob[cursor..cursor + 4].try_into().unwrap()) as usize;
+        cursor += 4;
+        let end = cursor + payload_len;
+
+        while cursor < end {
+            let tag = self.blob[cursor];
+            cursor += 1;
+            let value = T::from_bytes(&self.blob, &mut cursor);
+            let elem = match tag {
+                0 => Elem::Val(value),
+                1 => {
+                    let stop = read_varint(&self.blob, &mut cursor) as StopType;
+                    Elem::ValStop(value, stop)
+                }
+                other => panic!("unrecognized StreamWriterContext element tag {other}"),
+            };
+            self.out_stream
+                .enqueue(
+                    &self.time,
+                    ChannelElement {
+                        time: self.time.tick(),
+                        data: elem,
+                    },
+                )
+                .unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use dam::{
+        simulation::ProgramBuilder,
+        utility_contexts::{CheckerContext, GeneratorContext},
+    };
+
+    use super::{StreamReaderContext, StreamWriterContext};
+    use crate::primitives::{elem::Elem, tile::Tile};
+
+    #[test]
+    fn scalar_stream_round_trips_through_blob() {
+        let golden = vec![
+            Elem::Val(1u32),
+            Elem::Val(2u32),
+            Elem::ValStop(3u32, 1),
+            Elem::Val(4u32),
+            Elem::ValStop(5u32, 2),
+        ];
+
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(|| golden.clone().into_iter(), snd));
+
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        ctx.add_child(StreamWriterContext::new(rcv, sink.clone()));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+
+        let blob = sink.lock().unwrap().clone();
+
+        let mut replay_ctx = ProgramBuilder::default();
+        let (out_snd, out_rcv) = replay_ctx.unbounded();
+        replay_ctx.add_child(StreamReaderContext::new(blob, out_snd));
+        replay_ctx.add_child(CheckerContext::new(move || golden.clone().into_iter(), out_rcv));
+
+        replay_ctx
+            .initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn tile_stream_round_trips_through_blob() {
+        type VT = u32;
+
+        let golden = vec![
+            Elem::Val(Tile::<VT>::new_blank(vec![2, 2], 2, false)),
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 1),
+        ];
+
+        let mut ctx = ProgramBuilder::default();
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(|| golden.clone().into_iter(), snd));
+
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        ctx.add_child(StreamWriterContext::new(rcv, sink.clone()));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+
+        let blob = sink.lock().unwrap().clone();
+
+        let mut replay_ctx = ProgramBuilder::default();
+        let (out_snd, out_rcv) = replay_ctx.unbounded();
+        replay_ctx.add_child(StreamReaderContext::new(blob, out_snd));
+        replay_ctx.add_child(CheckerContext::new(move || golden.clone().into_iter(), out_rcv));
+
+        replay_ctx
+            .initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+}
@@ -3,7 +3,7 @@ use std::marker::PhantomData;
 use dam::context_tools::*;
 use dam::logging::LogEvent;
 
-use crate::primitives::buffer::Buffer;
+use crate::primitives::buffer::{AccessPattern, Buffer};
 use crate::primitives::elem::Bufferizable;
 use crate::primitives::elem::{Elem, StopType};
 
@@ -15,17 +15,29 @@ pub struct Streamify<E: LoggableEventSimple, T: Bufferizable + Clone> {
     pub repeat_factor: Vec<usize>, // The number of repeated linear reads to do for each buffer
     pub rank: StopType,
     pub in_stream: Receiver<Elem<Buffer<T>>>,
+    /// Optional data-dependent override for `repeat_factor`'s innermost
+    /// entry: one count is dequeued per incoming buffer and used in place
+    /// of `repeat_factor.last()`, letting the broadcast multiplicity vary
+    /// per buffer at runtime (e.g. SpMM broadcasting a row-tile across a
+    /// variable number of nonzero columns produced upstream) instead of
+    /// being fixed for the whole run. See [`Self::with_repeat_descriptor`].
+    pub repeat_descriptor: Option<Receiver<Elem<StopType>>>,
+    /// The order in which each buffer's elements are walked before being
+    /// repeated/emitted. Defaults to [`AccessPattern::identity`] (today's
+    /// linear storage order); see [`Self::with_access_pattern`].
+    pub access_pattern: AccessPattern,
     pub out_stream: Sender<Elem<T>>,
     pub id: u32,
     _phantom: PhantomData<E>,
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: Bufferizable + DAMType,
     > Streamify<E, T>
 where
     Buffer<T>: DAMType,
+    Self: Context,
 {
     pub fn new(
         repeat_factor: Vec<usize>, // The number of repeated linear reads to do for each buffer
@@ -38,6 +50,8 @@ where
             repeat_factor,
             rank,
             in_stream,
+            repeat_descriptor: None,
+            access_pattern: AccessPattern::identity(),
             out_stream,
             id,
             context_info: Default::default(),
@@ -48,18 +62,66 @@ where
 
         ctx
     }
+
+    /// Attaches a repeat-descriptor stream: one `Elem<StopType>` is
+    /// dequeued from it for every buffer drained from `in_stream`, and its
+    /// value overrides `repeat_factor`'s innermost entry for that buffer
+    /// (see [`Self::repeat_descriptor`]). A descriptor `Elem::ValStop`
+    /// bumps the emitted stop level exactly as `in_stream`'s own
+    /// `Elem::ValStop(_, outer_stop_lev)` already does.
+    pub fn with_repeat_descriptor(mut self, repeat_descriptor: Receiver<Elem<StopType>>) -> Self {
+        repeat_descriptor.attach_receiver(&self);
+        self.repeat_descriptor = Some(repeat_descriptor);
+        self
+    }
+
+    /// Overrides the order each buffer's elements are walked in, e.g. to
+    /// re-stream the same buffered operand transposed for a different
+    /// downstream consumer than the one reading it in linear order.
+    pub fn with_access_pattern(mut self, access_pattern: AccessPattern) -> Self {
+        self.access_pattern = access_pattern;
+        self
+    }
+
+    /// If a repeat-descriptor stream is attached, dequeues its next count
+    /// and returns `(Some(k as usize), bump)`, where `bump` is the extra
+    /// stop-level increment carried by a descriptor `Elem::ValStop` (`0`
+    /// for a plain `Elem::Val`). Returns `(None, 0)` when no descriptor is
+    /// attached, leaving `repeat_factor`'s static innermost entry in
+    /// effect.
+    fn dequeue_repeat_descriptor(&mut self) -> (Option<usize>, StopType) {
+        match &self.repeat_descriptor {
+            None => (None, 0),
+            Some(_) => match self
+                .repeat_descriptor
+                .as_ref()
+                .unwrap()
+                .dequeue(&self.time)
+            {
+                Ok(ChannelElement {
+                    data: Elem::Val(k), ..
+                }) => (Some(k as usize), 0),
+                Ok(ChannelElement {
+                    data: Elem::ValStop(k, s),
+                    ..
+                }) => (Some(k as usize), s),
+                Err(_) => panic!(
+                    "Streamify {}: repeat_descriptor ended before in_stream",
+                    self.id
+                ),
+            },
+        }
+    }
 }
 
 impl<
-        E: LoggableEventSimple + LogEvent + std::marker::Sync + std::marker::Send,
+        E: LoggableEventSimple + LogEvent + serde::Serialize + std::marker::Sync + std::marker::Send,
         T: Bufferizable + DAMType,
     > Context for Streamify<E, T>
 where
     Buffer<T>: DAMType,
 {
     fn run(&mut self) {
-        let mut tensor_shape_tiled: Vec<usize>;
-
         loop {
             match self.in_stream.peek_next(&self.time) {
                 Ok(ChannelElement {
@@ -70,7 +132,7 @@ where
                     match buff_elem {
                         Elem::Val(buff) => {
                             if self.repeat_factor.is_empty() {
-                                for elem in buff.to_elem_iter() {
+                                for elem in buff.to_elem_iter_ordered(&self.access_pattern) {
                                     self.out_stream
                                         .enqueue(
                                             &self.time,
@@ -83,13 +145,22 @@ where
                                     self.time.incr_cycles(1);
                                 }
                             } else {
+                                let (descriptor_factor, descriptor_bump) =
+                                    self.dequeue_repeat_descriptor();
                                 for (i, repeat_factor) in
                                     self.repeat_factor.iter().rev().enumerate()
                                 {
-                                    // For each buffer, we will repeat the elements based on the repeat factor
-                                    for repeat_i in 0..*repeat_factor {
-                                        let buff_clone = buff.clone();
-                                        for elem in buff_clone.to_elem_iter() {
+                                    // The innermost rank (i == 0) takes its repeat
+                                    // factor from the descriptor stream, if attached.
+                                    let repeat_factor =
+                                        if i == 0 { descriptor_factor.unwrap_or(*repeat_factor) } else { *repeat_factor };
+                                    let descriptor_bump = if i == 0 { descriptor_bump } else { 0 };
+                                    // For each buffer, we will repeat the elements based on the repeat
+                                    // factor. `to_elem_iter` borrows `buff`, so repeating it R times
+                                    // only re-walks the same backing `ArcArray` R times instead of
+                                    // cloning the buffer's payload once per repeat.
+                                    for repeat_i in 0..repeat_factor {
+                                        for elem in buff.to_elem_iter_ordered(&self.access_pattern) {
                                             match elem {
                                                 Elem::Val(tile) => {
                                                     self.out_stream
@@ -106,9 +177,12 @@ where
                                                 }
                                                 Elem::ValStop(tile, stop_lev) => {
                                                     let new_stop_level = if stop_lev == self.rank
-                                                        && repeat_i == (*repeat_factor - 1)
+                                                        && repeat_i == (repeat_factor - 1)
                                                     {
-                                                        stop_lev + 1 + i as StopType
+                                                        stop_lev
+                                                            + descriptor_bump
+                                                            + 1
+                                                            + i as StopType
                                                     } else {
                                                         stop_lev
                                                     };
@@ -135,7 +209,7 @@ where
                         }
                         Elem::ValStop(buff, outer_stop_lev) => {
                             if self.repeat_factor.is_empty() {
-                                for elem in buff.to_elem_iter() {
+                                for elem in buff.to_elem_iter_ordered(&self.access_pattern) {
                                     match elem {
                                         Elem::Val(tile) => {
                                             self.out_stream
@@ -171,13 +245,22 @@ where
                                     }
                                 }
                             } else {
+                                let (descriptor_factor, descriptor_bump) =
+                                    self.dequeue_repeat_descriptor();
                                 for (i, repeat_factor) in
                                     self.repeat_factor.iter().rev().enumerate()
                                 {
-                                    // For each buffer, we will repeat the elements based on the repeat factor
-                                    for repeat_i in 0..*repeat_factor {
-                                        let buff_clone = buff.clone();
-                                        for elem in buff_clone.to_elem_iter() {
+                                    // The innermost rank (i == 0) takes its repeat
+                                    // factor from the descriptor stream, if attached.
+                                    let repeat_factor =
+                                        if i == 0 { descriptor_factor.unwrap_or(*repeat_factor) } else { *repeat_factor };
+                                    let descriptor_bump = if i == 0 { descriptor_bump } else { 0 };
+                                    // For each buffer, we will repeat the elements based on the repeat
+                                    // factor. `to_elem_iter` borrows `buff`, so repeating it R times
+                                    // only re-walks the same backing `ArcArray` R times instead of
+                                    // cloning the buffer's payload once per repeat.
+                                    for repeat_i in 0..repeat_factor {
+                                        for elem in buff.to_elem_iter_ordered(&self.access_pattern) {
                                             match elem {
                                                 Elem::Val(tile) => {
                                                     self.out_stream
@@ -194,10 +277,11 @@ where
                                                 }
                                                 Elem::ValStop(tile, stop_lev) => {
                                                     let new_stop_level = if stop_lev == self.rank
-                                                        && repeat_i == (*repeat_factor - 1)
+                                                        && repeat_i == (repeat_factor - 1)
                                                     {
                                                         stop_lev
                                                             + outer_stop_lev
+                                                            + descriptor_bump
                                                             + 1
                                                             + i as StopType
                                                     } else {
@@ -227,14 +311,13 @@ where
                     }
                     self.in_stream.dequeue(&self.time).unwrap();
 
-                    dam::logging::log_event(&E::new(
+                    crate::utils::events::log_event(&E::new(
                         "Streamify".to_string(),
                         self.id,
                         start_time,
                         self.time.tick().time(),
                         false,
-                    ))
-                    .unwrap();
+                    ));
                 }
                 Err(_) => {
                     return;
@@ -437,4 +520,181 @@ mod tests {
             .unwrap()
             .run(Default::default());
     }
+
+    #[test]
+    fn repeat_descriptor_overrides_innermost_factor_per_buffer() {
+        // Two single-element buffers, repeated 2x and 3x respectively --
+        // a multiplicity `repeat_factor` alone can't express since it's
+        // fixed for the whole run. The static `repeat_factor` entry (`0`)
+        // is never consulted once a descriptor is attached.
+        type VT = u32;
+
+        let mut ctx = ProgramBuilder::default();
+        let rank = 1;
+
+        let input_tiled_stream = vec![
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 1),
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 2),
+        ];
+
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(
+            || input_tiled_stream.into_iter(),
+            snd,
+        ));
+
+        let (buff_snd, buff_rcv) = ctx.bounded(1);
+        ctx.add_child(Bufferize::<SimpleEvent, _>::new(
+            rcv, buff_snd, rank, DUMMY_ID,
+        ));
+
+        let (descriptor_snd, descriptor_rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(2), Elem::ValStop(3, 1)].into_iter(),
+            descriptor_snd,
+        ));
+
+        let (out_snd, out_rcv) = ctx.unbounded();
+        ctx.add_child(
+            super::Streamify::<SimpleEvent, _>::new(vec![0], rank, buff_rcv, out_snd, DUMMY_ID)
+                .with_repeat_descriptor(descriptor_rcv),
+        );
+
+        let expected_stream = vec![
+            // first buffer: descriptor `Elem::Val(2)` repeats it twice, no bump
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 1),
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 2),
+            // second buffer: descriptor `Elem::ValStop(3, 1)` repeats it three
+            // times, and its own outer_stop_lev (1) plus the descriptor's bump
+            // (1) both fold into the final stop level
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 1),
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 1),
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 4),
+        ];
+
+        ctx.add_child(ApproxCheckerContext::new(
+            move || expected_stream.into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn large_repeat_factor_reuses_buffer_without_per_repeat_clone() {
+        // A single buffer repeated a large number of times: `run` calls
+        // `buff.to_elem_iter_ordered` directly on every `repeat_i`, so the
+        // buffer's backing `ArcArray` is re-walked by reference rather than
+        // deep-cloned once per repeat. This only asserts the (still correct)
+        // output shape; the repo has no allocation-tracking harness to
+        // assert directly on peak memory.
+        type VT = u32;
+        const REPEAT_FACTOR: usize = 50;
+
+        let mut ctx = ProgramBuilder::default();
+        let rank = 1;
+
+        let input_tiled_stream = vec![Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 1)];
+
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(
+            || input_tiled_stream.into_iter(),
+            snd,
+        ));
+
+        let (buff_snd, buff_rcv) = ctx.bounded(1);
+        ctx.add_child(Bufferize::<SimpleEvent, _>::new(
+            rcv, buff_snd, rank, DUMMY_ID,
+        ));
+
+        let (out_snd, out_rcv) = ctx.unbounded();
+        ctx.add_child(super::Streamify::<SimpleEvent, _>::new(
+            vec![REPEAT_FACTOR],
+            rank,
+            buff_rcv,
+            out_snd,
+            DUMMY_ID,
+        ));
+
+        let mut expected_stream = vec![
+            Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 1);
+            REPEAT_FACTOR - 1
+        ];
+        expected_stream.push(Elem::ValStop(Tile::<VT>::new_blank(vec![2, 2], 2, false), 2));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            move || expected_stream.into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn with_access_pattern_transposes_buffer_emission_order() {
+        use crate::primitives::buffer::AccessPattern;
+        use ndarray::ArcArray2;
+
+        // A single 2x3 buffer, re-streamed column-major via a transposed
+        // access pattern instead of the row-major order it was bufferized in.
+        let scalar_tile =
+            |v: u32| Tile::<u32>::new(ArcArray2::from_shape_vec((1, 1), vec![v]).unwrap(), 4, false);
+
+        let mut ctx = ProgramBuilder::default();
+        let bufferize_rank = 2;
+
+        let input_tiled_stream = vec![
+            Elem::Val(scalar_tile(0)),
+            Elem::Val(scalar_tile(1)),
+            Elem::ValStop(scalar_tile(2), 1),
+            Elem::Val(scalar_tile(3)),
+            Elem::Val(scalar_tile(4)),
+            Elem::ValStop(scalar_tile(5), 2),
+        ];
+
+        let (snd, rcv) = ctx.unbounded();
+        ctx.add_child(GeneratorContext::new(
+            || input_tiled_stream.into_iter(),
+            snd,
+        ));
+
+        let (buff_snd, buff_rcv) = ctx.bounded(1);
+        ctx.add_child(Bufferize::<SimpleEvent, _>::new(
+            rcv,
+            buff_snd,
+            bufferize_rank,
+            DUMMY_ID,
+        ));
+
+        let (out_snd, out_rcv) = ctx.unbounded();
+        ctx.add_child(
+            super::Streamify::<SimpleEvent, _>::new(vec![], bufferize_rank, buff_rcv, out_snd, DUMMY_ID)
+                .with_access_pattern(AccessPattern::transposed(vec![1, 0])),
+        );
+
+        let expected_stream = vec![
+            Elem::Val(scalar_tile(0)),
+            Elem::ValStop(scalar_tile(3), 1),
+            Elem::Val(scalar_tile(1)),
+            Elem::ValStop(scalar_tile(4), 1),
+            Elem::Val(scalar_tile(2)),
+            Elem::ValStop(scalar_tile(5), 2),
+        ];
+
+        ctx.add_child(ApproxCheckerContext::new(
+            move || expected_stream.into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
 }
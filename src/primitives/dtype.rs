@@ -0,0 +1,336 @@
+use std::fmt::Debug;
+
+use crate::primitives::tile::Tile;
+use crate::utils::calculation::div_ceil;
+
+/// The numeric formats the cost model knows how to pack tiles into.
+/// `Tile<T>` keeps its Rust element type (the simulator doesn't re-encode
+/// values), but `bytes_per_elem` is adjusted to match so that downstream
+/// bandwidth/throughput accounting reflects the packed size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DType {
+    F32,
+    BF16,
+    F16,
+    F8E4M3,
+    F8E5M2,
+    Int8,
+    Int4,
+}
+
+impl DType {
+    /// Bit width of a single element in this format.
+    pub fn bits(&self) -> usize {
+        match self {
+            DType::F32 => 32,
+            DType::BF16 => 16,
+            DType::F16 => 16,
+            DType::F8E4M3 => 8,
+            DType::F8E5M2 => 8,
+            DType::Int8 => 8,
+            DType::Int4 => 4,
+        }
+    }
+
+    /// Bytes occupied by a single element, rounding up for sub-byte formats.
+    pub fn bytes(&self) -> usize {
+        div_ceil(self.bits() as u64, 8) as usize
+    }
+
+    /// Parses a `DType` back out of its variant name (`"F32"`, `"BF16"`,
+    /// ...), for config surfaces (e.g. [`crate::proto_driver::configs::SimConfig`]'s
+    /// `storage_format_overrides`) that only have a plain string to work
+    /// with, not a Rust enum literal.
+    pub fn from_name(name: &str) -> Option<DType> {
+        match name {
+            "F32" => Some(DType::F32),
+            "BF16" => Some(DType::BF16),
+            "F16" => Some(DType::F16),
+            "F8E4M3" => Some(DType::F8E4M3),
+            "F8E5M2" => Some(DType::F8E5M2),
+            "Int8" => Some(DType::Int8),
+            "Int4" => Some(DType::Int4),
+            _ => None,
+        }
+    }
+}
+
+/// Re-packs a tile's modeled element width to `target`, at a cost
+/// proportional to the element count. The underlying array is passed
+/// through unchanged; only `bytes_per_elem` (and therefore downstream
+/// bandwidth/cycle accounting) reflects the new format.
+pub fn quantize<T: Debug + Clone>(
+    in_data: &Tile<T>,
+    target: DType,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    let total_elems: usize = in_data.shape.iter().product();
+
+    (
+        div_ceil(total_elems as u64, flop_per_cycle),
+        Tile {
+            shape: in_data.shape.clone(),
+            bytes_per_elem: target.bytes(),
+            read_from_mu: write_back_mu,
+            underlying: in_data.underlying.clone(),
+            offset: in_data.offset,
+            col_offset: in_data.col_offset,
+            pad: in_data.pad.clone(),
+            row_align: in_data.row_align,
+            csr: in_data.csr.clone(),
+            rle: in_data.rle.clone(),
+        },
+    )
+}
+
+/// Inverse of [`quantize`]: re-packs a tile back to `target`'s element
+/// width (typically a wider format such as `DType::F32`).
+pub fn dequantize<T: Debug + Clone>(
+    in_data: &Tile<T>,
+    target: DType,
+    flop_per_cycle: u64,
+    write_back_mu: bool,
+) -> (u64, Tile<T>) {
+    quantize(in_data, target, flop_per_cycle, write_back_mu)
+}
+
+/// Rounds an `f32` to `bf16` precision by truncating its lower 16 mantissa
+/// bits, with round-to-nearest-even on the discarded bit (the same rule
+/// hardware bf16 converters use). bf16 shares f32's exponent width, so this
+/// only ever loses mantissa precision, never range.
+pub fn round_to_bf16(x: f32) -> f32 {
+    let bits = x.to_bits();
+    let rounding_bias = 0x7fff + ((bits >> 16) & 1);
+    f32::from_bits(bits.wrapping_add(rounding_bias) & 0xffff_0000)
+}
+
+/// Rounds an `f32` to IEEE fp16 precision, returned as an `f32`. Unlike
+/// [`round_to_bf16`], fp16 narrows the exponent as well as the mantissa, so
+/// a bit-mask truncation doesn't apply -- this goes through `half::f16`
+/// (already a dependency, see [`crate::ramulator::access::MemoryData`])
+/// rather than reimplementing IEEE round-to-nearest-even by hand.
+pub fn round_to_f16(x: f32) -> f32 {
+    half::f16::from_f32(x).to_f32()
+}
+
+/// Like [`quantize`], but for `DType::BF16` specifically: rather than only
+/// adjusting `bytes_per_elem`, every element is actually rounded to bf16
+/// precision via [`round_to_bf16`]. This is what a real mixed-precision
+/// kernel should call so that downstream compute (matmul/silu/exp) sees
+/// true reduced-precision numerics instead of full fp32 values wearing a
+/// bf16 byte count.
+///
+/// `Tile<T>` stays generic over its Rust element type everywhere else in
+/// this crate, so this is deliberately narrower than [`quantize`]: going
+/// further (a first-class `Tile<Bf16>`, a matching `Type::Bf16` proto
+/// variant, and a `ChannelMapCollection::tile_bf16` channel family so
+/// `build_from_proto` can dispatch on it) needs the `datatype.proto`
+/// schema that `build.rs` compiles from `step_perf_ir/proto/`, which isn't
+/// present in this tree -- `Type` can't gain a new variant here, so
+/// nothing can dispatch on it either. This function is the numerics half
+/// of that request that's implementable without the schema.
+pub fn quantize_bf16(in_data: &Tile<f32>, flop_per_cycle: u64, write_back_mu: bool) -> (u64, Tile<f32>) {
+    let total_elems: usize = in_data.shape.iter().product();
+
+    (
+        div_ceil(total_elems as u64, flop_per_cycle),
+        Tile {
+            shape: in_data.shape.clone(),
+            bytes_per_elem: DType::BF16.bytes(),
+            read_from_mu: write_back_mu,
+            underlying: in_data
+                .underlying
+                .as_ref()
+                .map(|arr| arr.mapv(round_to_bf16).to_shared()),
+            offset: in_data.offset,
+            col_offset: in_data.col_offset,
+            pad: in_data.pad.clone(),
+            row_align: in_data.row_align,
+            csr: in_data.csr.clone(),
+            rle: in_data.rle.clone(),
+        },
+    )
+}
+
+/// What happens to a magnitude that overflows an fp8 format's finite range
+/// in [`round_to_f8_e4m3`]/[`round_to_f8_e5m2`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fp8Overflow {
+    /// Clamp to the format's largest finite value. The only faithful
+    /// choice for e4m3, which (in this emulation) has no infinity.
+    Saturate,
+    /// Round to +/- infinity, the way e5m2's IEEE-754-like layout would.
+    Inf,
+}
+
+/// Shared fp8 rounding core for [`round_to_f8_e4m3`]/[`round_to_f8_e5m2`]:
+/// rounds `x` to the nearest representable value of an `exp_bits`-exponent,
+/// `mantissa_bits`-mantissa float (round-to-nearest-even, ties to even),
+/// handling subnormals and overflow. Unlike [`round_to_bf16`]'s bit-mask
+/// trick (which works only because bf16 shares f32's exponent width), a
+/// narrower exponent needs re-biasing and subnormal flush, so this rounds
+/// by scaling to the target step size instead of masking bits.
+///
+/// Simplification: both formats reserve their top exponent code exclusively
+/// for overflow here, rather than OCP e4m3's trick of using it for finite
+/// values too (sacrificing only a NaN encoding) -- this costs e4m3 about an
+/// octave of range (saturates at 240 instead of 448) in exchange for one
+/// rounding routine shared by both formats.
+fn round_to_fp8(x: f32, exp_bits: u32, mantissa_bits: u32, overflow: Fp8Overflow) -> f32 {
+    if x == 0.0 || x.is_nan() {
+        return x;
+    }
+
+    let bias = (1i32 << (exp_bits - 1)) - 1;
+    let max_exp = (1i32 << exp_bits) - 2 - bias;
+    let min_normal_exp = 1 - bias;
+
+    let sign = x.is_sign_negative();
+    let ax = x.abs();
+    let raw_exp = ((ax.to_bits() >> 23) & 0xff) as i32 - 127;
+
+    let saturate_or_inf = || -> f32 {
+        let magnitude = match overflow {
+            Fp8Overflow::Inf => f32::INFINITY,
+            Fp8Overflow::Saturate => (2.0 - 2f32.powi(-(mantissa_bits as i32))) * 2f32.powi(max_exp),
+        };
+        if sign { -magnitude } else { magnitude }
+    };
+
+    if raw_exp > max_exp {
+        return saturate_or_inf();
+    }
+    if raw_exp < min_normal_exp - mantissa_bits as i32 - 1 {
+        return if sign { -0.0 } else { 0.0 };
+    }
+
+    let step_exp = raw_exp.max(min_normal_exp) - mantissa_bits as i32;
+    let step = 2f32.powi(step_exp);
+    let rounded = (ax / step).round_ties_even() * step;
+
+    let rounded_exp = ((rounded.to_bits() >> 23) & 0xff) as i32 - 127;
+    if rounded != 0.0 && rounded_exp > max_exp {
+        return saturate_or_inf();
+    }
+
+    if sign { -rounded } else { rounded }
+}
+
+/// Emulates rounding an `f32` to the OCP e4m3 fp8 layout (1 sign, 4
+/// exponent, 3 mantissa bits), returning the result still as an `f32` --
+/// see [`round_to_bf16`] for why `Tile<T>` stays in its native Rust type.
+pub fn round_to_f8_e4m3(x: f32, overflow: Fp8Overflow) -> f32 {
+    round_to_fp8(x, 4, 3, overflow)
+}
+
+/// Emulates rounding an `f32` to the e5m2 fp8 layout (1 sign, 5 exponent, 2
+/// mantissa bits) -- wider range than e4m3, at the cost of a mantissa bit.
+pub fn round_to_f8_e5m2(x: f32, overflow: Fp8Overflow) -> f32 {
+    round_to_fp8(x, 5, 2, overflow)
+}
+
+/// Throughput multiplier a dtype's packing affords relative to `DType::F32`,
+/// e.g. `Int8` elements pack 4x as densely so a fixed-width datapath can
+/// process 4x as many per cycle.
+pub fn throughput_scale(dtype: DType) -> u64 {
+    (DType::F32.bytes() / dtype.bytes().max(1)).max(1) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_and_bytes() {
+        assert_eq!(DType::F32.bits(), 32);
+        assert_eq!(DType::Int8.bytes(), 1);
+        assert_eq!(DType::Int4.bytes(), 1); // sub-byte rounds up
+    }
+
+    #[test]
+    fn test_quantize_updates_bytes_per_elem() {
+        let arr = ndarray::Array2::from_shape_fn((2, 2), |(i, j)| i as f32 + j as f32);
+        let in_data = Tile::new(arr.to_shared(), 4, false);
+
+        let (cycles, out) = quantize(&in_data, DType::Int8, 4, false);
+        assert_eq!(out.bytes_per_elem, 1);
+        assert_eq!(cycles, 1);
+    }
+
+    #[test]
+    fn test_throughput_scale() {
+        assert_eq!(throughput_scale(DType::F32), 1);
+        assert_eq!(throughput_scale(DType::Int8), 4);
+    }
+
+    #[test]
+    fn test_round_to_bf16_drops_mantissa_precision() {
+        // 1/3 needs more than bf16's 7 mantissa bits to represent exactly,
+        // so rounding it should actually change the value...
+        let x = 1.0f32 / 3.0;
+        let rounded = round_to_bf16(x);
+        assert_ne!(rounded, x);
+        // ...but a value exact in bf16 (few significant mantissa bits) should
+        // round-trip unchanged.
+        assert_eq!(round_to_bf16(1.5), 1.5);
+    }
+
+    #[test]
+    fn test_round_to_f16_drops_precision_and_clamps_range() {
+        // 1/3 needs more than fp16's 10 mantissa bits, so it should change...
+        let x = 1.0f32 / 3.0;
+        assert_ne!(round_to_f16(x), x);
+        // ...but a value exact in fp16 should round-trip unchanged.
+        assert_eq!(round_to_f16(1.5), 1.5);
+        // fp16's max finite magnitude is 65504; beyond that it overflows to
+        // infinity (unlike bf16, which shares f32's full exponent range).
+        assert!(round_to_f16(1.0e6).is_infinite());
+    }
+
+    #[test]
+    fn test_round_to_f8_e4m3_ties_to_even() {
+        // Representable e4m3 values at exponent 0 are multiples of 0.125;
+        // 1.1875 and 1.0625 sit exactly halfway between two of them, so
+        // round-to-nearest-even picks whichever neighbor is even.
+        assert_eq!(round_to_f8_e4m3(1.1875, Fp8Overflow::Saturate), 1.25);
+        assert_eq!(round_to_f8_e4m3(1.0625, Fp8Overflow::Saturate), 1.0);
+    }
+
+    #[test]
+    fn test_round_to_f8_e4m3_subnormals() {
+        // Smallest e4m3 subnormal is 2^-9; it round-trips unchanged, but
+        // anything below half that step flushes to zero.
+        let smallest = 2f32.powi(-9);
+        assert_eq!(round_to_f8_e4m3(smallest, Fp8Overflow::Saturate), smallest);
+        assert_eq!(round_to_f8_e4m3(2f32.powi(-11), Fp8Overflow::Saturate), 0.0);
+    }
+
+    #[test]
+    fn test_round_to_f8_e4m3_overflow() {
+        // 300 exceeds e4m3's largest finite magnitude (240): Saturate clamps,
+        // Inf rounds away to infinity.
+        assert_eq!(round_to_f8_e4m3(300.0, Fp8Overflow::Saturate), 240.0);
+        assert!(round_to_f8_e4m3(300.0, Fp8Overflow::Inf).is_infinite());
+        assert_eq!(round_to_f8_e4m3(-300.0, Fp8Overflow::Saturate), -240.0);
+    }
+
+    #[test]
+    fn test_round_to_f8_e5m2_overflow() {
+        // e5m2 has more range than e4m3 (max finite 57344) but less
+        // mantissa precision.
+        assert_eq!(round_to_f8_e5m2(70000.0, Fp8Overflow::Saturate), 57344.0);
+        assert!(round_to_f8_e5m2(70000.0, Fp8Overflow::Inf).is_infinite());
+    }
+
+    #[test]
+    fn test_quantize_bf16_rounds_values_and_updates_bytes() {
+        let arr = ndarray::Array2::from_shape_fn((2, 2), |_| 1.0f32 / 3.0);
+        let in_data = Tile::new(arr.to_shared(), 4, false);
+
+        let (cycles, out) = quantize_bf16(&in_data, 4, false);
+        assert_eq!(out.bytes_per_elem, 2);
+        assert_eq!(cycles, 1);
+        assert_eq!(out.underlying.unwrap()[[0, 0]], round_to_bf16(1.0 / 3.0));
+    }
+}
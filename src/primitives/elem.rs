@@ -29,3 +29,70 @@ pub trait Bufferizable {
     fn read_from_mu(&self) -> bool;
     fn clone_with_updated_read_from_mu(&self, read_from_mu: bool) -> Self;
 }
+
+/// Writes `value` to `out` as an unsigned LEB128 varint (7 payload bits per
+/// byte, high bit set on every byte but the last) -- used for the `StopType`
+/// rank in [`StreamCodec`]'s tag-per-element framing, and for any other
+/// small, usually-tiny count a codec needs to store compactly.
+pub fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Inverse of [`write_varint`]: reads one varint starting at `*cursor`,
+/// advancing `*cursor` past it.
+pub fn read_varint(bytes: &[u8], cursor: &mut usize) -> u64 {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = bytes[*cursor];
+        *cursor += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return result;
+        }
+        shift += 7;
+    }
+}
+
+/// Gives a `DAMType` a compact binary encoding for [`crate::operator::stream_io`]'s
+/// `StreamWriterContext`/`StreamReaderContext`: `to_bytes` appends `self`'s
+/// encoding to `out`, and `from_bytes` is its inverse, reading starting at
+/// `*cursor` and advancing it past whatever it consumed. Implementors pick
+/// their own encoding (fixed-width for scalars, length-prefixed for anything
+/// variable-sized); the per-element `Val`/`ValStop` tag and `StopType` varint
+/// live in the stream framing itself, not here.
+pub trait StreamCodec: DAMType + Sized {
+    fn to_bytes(&self, out: &mut Vec<u8>);
+    fn from_bytes(bytes: &[u8], cursor: &mut usize) -> Self;
+}
+
+macro_rules! impl_stream_codec_le_bytes {
+    ($ty:ty) => {
+        impl StreamCodec for $ty {
+            fn to_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn from_bytes(bytes: &[u8], cursor: &mut usize) -> Self {
+                const WIDTH: usize = std::mem::size_of::<$ty>();
+                let value = <$ty>::from_le_bytes(
+                    bytes[*cursor..*cursor + WIDTH].try_into().unwrap(),
+                );
+                *cursor += WIDTH;
+                value
+            }
+        }
+    };
+}
+
+impl_stream_codec_le_bytes!(u32);
+impl_stream_codec_le_bytes!(u64);
+impl_stream_codec_le_bytes!(f32);
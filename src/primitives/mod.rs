@@ -0,0 +1,7 @@
+pub mod buffer;
+pub mod dtype;
+pub mod elem;
+pub mod select;
+pub mod sparse;
+pub mod tile;
+pub mod tile_buffer;
@@ -1,6 +1,6 @@
 use dam::types::StaticallySized;
 
-use super::elem::Bufferizable;
+use super::elem::{read_varint, write_varint, Bufferizable, StreamCodec};
 
 pub trait SelectAdapter {
     fn to_sel_vec(&self) -> Vec<usize>;
@@ -81,6 +81,28 @@ impl Bufferizable for MultiHotN {
     }
 }
 
+impl StreamCodec for MultiHotN {
+    fn to_bytes(&self, out: &mut Vec<u8>) {
+        write_varint(self.underlying.len() as u64, out);
+        out.extend(self.underlying.iter().map(|&b| b as u8));
+        out.push(self.read_from_mu() as u8);
+    }
+
+    fn from_bytes(bytes: &[u8], cursor: &mut usize) -> Self {
+        let len = read_varint(bytes, cursor) as usize;
+        let underlying = (0..len)
+            .map(|_| {
+                let b = bytes[*cursor] != 0;
+                *cursor += 1;
+                b
+            })
+            .collect();
+        let read_from_mu = bytes[*cursor] != 0;
+        *cursor += 1;
+        MultiHotN::new(underlying, read_from_mu)
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct IndexN {
     underlying: Vec<Option<usize>>,
@@ -149,6 +171,25 @@ impl Bufferizable for IndexN {
     }
 }
 
+/// Lets property tests (e.g. [`crate::primitives::buffer`]'s
+/// `Buffer<T>: Arbitrary` round-trip fuzzing) generate `MultiHotN` values
+/// directly instead of needing a `Bufferizable`-flavored primitive that's
+/// simpler to fuzz than a full `Tile`.
+#[cfg(test)]
+impl<'a> arbitrary::Arbitrary<'a> for MultiHotN {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(1..=8)?;
+        let underlying = (0..len)
+            .map(|_| bool::arbitrary(u))
+            .collect::<arbitrary::Result<Vec<_>>>()?;
+        let read_from_mu = bool::arbitrary(u)?;
+        Ok(Self {
+            underlying,
+            read_from_mu,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::SelectAdapter;
@@ -0,0 +1,104 @@
+use std::fmt::Debug;
+
+use crate::primitives::tile::Tile;
+use crate::utils::calculation::div_ceil;
+
+/// Converts a CSR-backed `Tile` to a dense one. The hardware only has to
+/// touch the `nnz` stored entries to scatter them back out, plus a one-time
+/// `m*n` zero-fill of the destination buffer, so the modeled cost is
+/// `div_ceil(nnz, elems_per_cycle) + m*n` (the zero-fill is treated as free
+/// of `elems_per_cycle` scaling since it is typically a hardware memset).
+pub fn densify<T: Debug + Clone + Default + num_traits::Zero>(
+    tile: &Tile<T>,
+    elems_per_cycle: u64,
+) -> (u64, Tile<T>) {
+    let m = tile.shape[0];
+    let n = tile.shape[1];
+    let csr = tile
+        .csr
+        .as_ref()
+        .expect("densify requires a CSR-backed Tile");
+
+    let mut out_arr = ndarray::Array2::<T>::default((m, n));
+    for row in 0..m {
+        for p in csr.indptr[row]..csr.indptr[row + 1] {
+            out_arr[[row, csr.indices[p]]] = csr.values[p].clone();
+        }
+    }
+
+    let cycles = div_ceil(csr.values.len() as u64, elems_per_cycle) + (m * n) as u64;
+    (
+        cycles,
+        Tile::new(out_arr.to_shared(), tile.bytes_per_elem, tile.read_from_mu),
+    )
+}
+
+/// Converts a dense `Tile` to CSR form, keeping only entries whose
+/// magnitude exceeds `threshold`. Every element must be tested regardless
+/// of sparsity, so the modeled cost is `div_ceil(m*n, elems_per_cycle)`.
+pub fn sparsify<T: Debug + Clone + PartialOrd + num_traits::Signed>(
+    tile: &Tile<T>,
+    threshold: T,
+    elems_per_cycle: u64,
+) -> (u64, Tile<T>) {
+    let m = tile.shape[0];
+    let n = tile.shape[1];
+    let dense = tile
+        .underlying
+        .as_ref()
+        .expect("sparsify requires a dense-backed Tile");
+
+    let mut indptr = Vec::with_capacity(m + 1);
+    let mut indices = vec![];
+    let mut values = vec![];
+    indptr.push(0);
+    for row in 0..m {
+        for col in 0..n {
+            let val = dense[[row, col]].clone();
+            if val.abs() > threshold {
+                indices.push(col);
+                values.push(val);
+            }
+        }
+        indptr.push(values.len());
+    }
+
+    let cycles = div_ceil((m * n) as u64, elems_per_cycle);
+    (
+        cycles,
+        Tile::new_csr(
+            indptr,
+            indices,
+            values,
+            tile.shape.clone(),
+            tile.bytes_per_elem,
+            tile.read_from_mu,
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparsify_densify_roundtrip() {
+        let arr =
+            ndarray::Array2::from_shape_vec((2, 3), vec![0.0, 5.0, 0.0, -0.1, 0.0, 3.0]).unwrap();
+        let tile = Tile::new(arr.to_shared(), 4, false);
+
+        let (sparsify_cycles, sparse_tile) = sparsify(&tile, 0.5, 2);
+        assert_eq!(sparsify_cycles, 3); // div_ceil(6, 2)
+        let csr = sparse_tile.csr.as_ref().unwrap();
+        assert_eq!(csr.values, vec![5.0, 3.0]);
+        assert_eq!(csr.indices, vec![1, 2]);
+        assert_eq!(csr.indptr, vec![0, 1, 2]);
+
+        let (densify_cycles, dense_tile) = densify(&sparse_tile, 2);
+        assert_eq!(densify_cycles, 1 + 6); // div_ceil(2, 2) + m*n
+        assert_eq!(
+            dense_tile.underlying.unwrap().to_owned(),
+            ndarray::Array2::from_shape_vec((2, 3), vec![0.0, 5.0, 0.0, 0.0, 0.0, 3.0]).unwrap()
+        );
+    }
+}
@@ -1,13 +1,44 @@
-use dam::types::StaticallySized;
+use dam::types::{DAMType, StaticallySized};
 use ndarray::Array2;
+use num::Zero;
 
-use super::elem::Bufferizable;
+use super::elem::{read_varint, write_varint, Bufferizable, StreamCodec};
+use crate::utils::calculation::align_up;
+
+/// Compressed-sparse-row storage for a `Tile`: `values[indptr[r]..indptr[r+1]]`
+/// are the non-zero entries of row `r`, at column `indices[indptr[r]..indptr[r+1]]`.
+/// `indptr` has `shape[0] + 1` entries, following the usual CSR convention.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsrData<T> {
+    pub indptr: Vec<usize>,
+    pub indices: Vec<usize>,
+    pub values: Vec<T>,
+}
 
 /// Tile
 /// - offset: If the tile has a padded value, this is the offset expressing
 ///     non-padded rows (same as the number of non-padded rows in the tile).
 ///     If none of the rows in the tile are padded, this is same as the number of rows in the tile.
 ///     If the tile is a padded value, this is 0.
+/// - col_offset: The column analogue of `offset` -- the number of active
+///     (non-padded) columns. Together with `offset` this marks out the
+///     `offset × col_offset` rectangle of real data a masked tile load
+///     produces; everything outside it is `pad`. Defaults to the full
+///     column count (no column masking) wherever only row padding applies.
+/// - pad: The fill value a masked tile load uses outside the active
+///     `offset × col_offset` rectangle. `None` means zero-fill, matching
+///     the behavior of the all-zero-padded constructors below.
+/// - csr: When present, this tile's data lives in compressed-sparse-row form
+///     instead of (or alongside) `underlying`. Dense-only operators ignore it;
+///     sparse-aware operators like `spmm` read it directly.
+/// - row_align: Byte alignment each row's footprint is rounded up to in
+///     [`Bufferizable::size_in_bytes`], modeling the row-stride padding a
+///     DMA/bank-aligned store imposes. `1` (the default) means no padding.
+/// - rle: When present, this tile's logical contents are run-length-encoded
+///     as row-major `(value, run_length)` pairs instead of held densely in
+///     `underlying` (see [`Self::to_rle`]/[`Self::to_dense`]). Padded tiles
+///     are dominated by a single repeated pad/zero value, so this is a much
+///     smaller footprint than the dense array it replaces.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Tile<T> {
     pub shape: Vec<usize>,
@@ -15,16 +46,26 @@ pub struct Tile<T> {
     pub read_from_mu: bool,
     pub underlying: Option<ndarray::ArcArray2<T>>,
     pub offset: usize,
+    pub col_offset: usize,
+    pub pad: Option<T>,
+    pub row_align: usize,
+    pub csr: Option<CsrData<T>>,
+    pub rle: Option<Vec<(T, usize)>>,
     // As tile is treated as 'value' instead of 'reference,
     // we will use Array instead of ArcArray
 }
 impl<T: StaticallySized> StaticallySized for Tile<T> {
     const SIZE: usize = T::SIZE;
 }
-impl<T> Bufferizable for Tile<T> {
+impl<T: Clone> Bufferizable for Tile<T> {
     fn size_in_bytes(&self) -> usize {
-        let total_elems: usize = self.shape.iter().product();
-        self.bytes_per_elem * total_elems
+        if let Some(rle) = &self.rle {
+            return rle.len() * (self.bytes_per_elem + std::mem::size_of::<usize>());
+        }
+        let rows = *self.shape.first().unwrap_or(&0) as u64;
+        let cols_elems: usize = self.shape.iter().skip(1).product();
+        let row_bytes = cols_elems as u64 * self.bytes_per_elem as u64;
+        (rows * align_up(row_bytes, self.row_align as u64)) as usize
     }
     fn read_from_mu(&self) -> bool {
         self.read_from_mu
@@ -37,28 +78,174 @@ impl<T> Bufferizable for Tile<T> {
             read_from_mu: read_from_mu,
             underlying: self.underlying.clone(),
             offset: self.offset,
+            col_offset: self.col_offset,
+            pad: self.pad.clone(),
+            row_align: self.row_align,
+            csr: self.csr.clone(),
+            rle: self.rle.clone(),
+        }
+    }
+}
+
+fn write_usize_vec(vec: &[usize], out: &mut Vec<u8>) {
+    write_varint(vec.len() as u64, out);
+    for &v in vec {
+        write_varint(v as u64, out);
+    }
+}
+
+fn read_usize_vec(bytes: &[u8], cursor: &mut usize) -> Vec<usize> {
+    let len = read_varint(bytes, cursor) as usize;
+    (0..len)
+        .map(|_| read_varint(bytes, cursor) as usize)
+        .collect()
+}
+
+fn write_option<T>(value: &Option<T>, out: &mut Vec<u8>, encode: impl FnOnce(&T, &mut Vec<u8>)) {
+    match value {
+        Some(v) => {
+            out.push(1);
+            encode(v, out);
         }
+        None => out.push(0),
     }
 }
+
+fn read_option<T>(
+    bytes: &[u8],
+    cursor: &mut usize,
+    decode: impl FnOnce(&[u8], &mut usize) -> T,
+) -> Option<T> {
+    let tag = bytes[*cursor];
+    *cursor += 1;
+    (tag != 0).then(|| decode(bytes, cursor))
+}
+
+impl<T: StreamCodec> StreamCodec for Tile<T>
+where
+    Tile<T>: DAMType,
+{
+    fn to_bytes(&self, out: &mut Vec<u8>) {
+        write_usize_vec(&self.shape, out);
+        write_varint(self.bytes_per_elem as u64, out);
+        out.push(self.read_from_mu as u8);
+        write_option(&self.underlying, out, |arr, out| {
+            write_varint(arr.shape()[0] as u64, out);
+            write_varint(arr.shape()[1] as u64, out);
+            for v in arr.iter() {
+                v.to_bytes(out);
+            }
+        });
+        write_varint(self.offset as u64, out);
+        write_varint(self.col_offset as u64, out);
+        write_option(&self.pad, out, |v, out| v.to_bytes(out));
+        write_varint(self.row_align as u64, out);
+        write_option(&self.csr, out, |csr, out| {
+            write_usize_vec(&csr.indptr, out);
+            write_usize_vec(&csr.indices, out);
+            write_varint(csr.values.len() as u64, out);
+            for v in &csr.values {
+                v.to_bytes(out);
+            }
+        });
+        write_option(&self.rle, out, |rle, out| {
+            write_varint(rle.len() as u64, out);
+            for (v, run_length) in rle {
+                v.to_bytes(out);
+                write_varint(*run_length as u64, out);
+            }
+        });
+    }
+
+    fn from_bytes(bytes: &[u8], cursor: &mut usize) -> Self {
+        let shape = read_usize_vec(bytes, cursor);
+        let bytes_per_elem = read_varint(bytes, cursor) as usize;
+        let read_from_mu = bytes[*cursor] != 0;
+        *cursor += 1;
+        let underlying = read_option(bytes, cursor, |bytes, cursor| {
+            let rows = read_varint(bytes, cursor) as usize;
+            let cols = read_varint(bytes, cursor) as usize;
+            let values: Vec<T> = (0..rows * cols)
+                .map(|_| T::from_bytes(bytes, cursor))
+                .collect();
+            Array2::from_shape_vec((rows, cols), values)
+                .unwrap()
+                .to_shared()
+        });
+        let offset = read_varint(bytes, cursor) as usize;
+        let col_offset = read_varint(bytes, cursor) as usize;
+        let pad = read_option(bytes, cursor, |bytes, cursor| T::from_bytes(bytes, cursor));
+        let row_align = read_varint(bytes, cursor) as usize;
+        let csr = read_option(bytes, cursor, |bytes, cursor| {
+            let indptr = read_usize_vec(bytes, cursor);
+            let indices = read_usize_vec(bytes, cursor);
+            let value_count = read_varint(bytes, cursor) as usize;
+            let values = (0..value_count)
+                .map(|_| T::from_bytes(bytes, cursor))
+                .collect();
+            CsrData {
+                indptr,
+                indices,
+                values,
+            }
+        });
+        let rle = read_option(bytes, cursor, |bytes, cursor| {
+            let len = read_varint(bytes, cursor) as usize;
+            (0..len)
+                .map(|_| {
+                    let v = T::from_bytes(bytes, cursor);
+                    let run_length = read_varint(bytes, cursor) as usize;
+                    (v, run_length)
+                })
+                .collect()
+        });
+
+        Self {
+            shape,
+            bytes_per_elem,
+            read_from_mu,
+            underlying,
+            offset,
+            col_offset,
+            pad,
+            row_align,
+            csr,
+            rle,
+        }
+    }
+}
+
 impl<T> Tile<T> {
     /// This creates a tile with no underlying data
     pub fn new_blank(shape: Vec<usize>, bytes_per_elem: usize, read_from_mu: bool) -> Self {
+        let col_offset = shape[1];
         Self {
             shape: shape.clone(),
             bytes_per_elem: bytes_per_elem,
             read_from_mu: read_from_mu,
             underlying: None,
             offset: shape[0],
+            col_offset,
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
         }
     }
     pub fn new(arr: ndarray::ArcArray2<T>, bytes_per_elem: usize, read_from_mu: bool) -> Self {
         let rows = arr.shape().clone().to_vec()[0];
+        let cols = arr.shape()[1];
         Self {
             shape: arr.shape().to_vec(),
             bytes_per_elem: bytes_per_elem,
             read_from_mu: read_from_mu,
             underlying: Some(arr),
             offset: rows,
+            col_offset: cols,
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
         }
     }
 
@@ -69,12 +256,18 @@ impl<T> Tile<T> {
         read_from_mu: bool,
         offset: usize,
     ) -> Self {
+        let col_offset = shape[1];
         Self {
             shape: shape,
             bytes_per_elem: bytes_per_elem,
             read_from_mu: read_from_mu,
             underlying: None,
             offset: offset,
+            col_offset,
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
         }
     }
 
@@ -85,18 +278,132 @@ impl<T> Tile<T> {
         read_from_mu: bool,
         offset: usize,
     ) -> Self {
+        let col_offset = arr.shape()[1];
         Self {
             shape: arr.shape().to_vec(),
             bytes_per_elem: bytes_per_elem,
             read_from_mu: read_from_mu,
             underlying: Some(arr),
             offset: offset,
+            col_offset,
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
+        }
+    }
+
+    /// Creates a row+column masked tile: elements at row `< num_rows` and
+    /// column `< num_cols` are the real, active data read from `arr`;
+    /// everything else is the `pad` fill value (or zero when `pad` is
+    /// `None`) once [`Self::materialize`] is called. The 2D generalization
+    /// of [`Self::new_padded`]'s row-only `offset`.
+    pub fn new_masked(
+        arr: ndarray::ArcArray2<T>,
+        bytes_per_elem: usize,
+        read_from_mu: bool,
+        num_rows: usize,
+        num_cols: usize,
+        pad: Option<T>,
+    ) -> Self {
+        Self {
+            shape: arr.shape().to_vec(),
+            bytes_per_elem,
+            read_from_mu,
+            underlying: Some(arr),
+            offset: num_rows,
+            col_offset: num_cols,
+            pad,
+            row_align: 1,
+            csr: None,
+            rle: None,
+        }
+    }
+
+    /// Creates a fully-active tile whose [`Bufferizable::size_in_bytes`]
+    /// rounds each row's byte footprint up to `row_align` bytes, modeling a
+    /// DMA/bank-aligned store. The 2D generalization of [`Self::new`].
+    pub fn new_aligned(
+        arr: ndarray::ArcArray2<T>,
+        bytes_per_elem: usize,
+        read_from_mu: bool,
+        row_align: usize,
+    ) -> Self {
+        let rows = arr.shape()[0];
+        let cols = arr.shape()[1];
+        Self {
+            shape: arr.shape().to_vec(),
+            bytes_per_elem,
+            read_from_mu,
+            underlying: Some(arr),
+            offset: rows,
+            col_offset: cols,
+            pad: None,
+            row_align,
+            csr: None,
+            rle: None,
+        }
+    }
+
+    /// This creates a tile whose data lives in compressed-sparse-row form
+    /// (see [`CsrData`]) rather than as a dense `underlying` array.
+    pub fn new_csr(
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        values: Vec<T>,
+        shape: Vec<usize>,
+        bytes_per_elem: usize,
+        read_from_mu: bool,
+    ) -> Self {
+        assert_eq!(indptr.len(), shape[0] + 1);
+        let offset = shape[0];
+        let col_offset = shape[1];
+        Self {
+            shape,
+            bytes_per_elem,
+            read_from_mu,
+            underlying: None,
+            offset,
+            col_offset,
+            pad: None,
+            row_align: 1,
+            csr: Some(CsrData {
+                indptr,
+                indices,
+                values,
+            }),
+            rle: None,
         }
     }
 }
 
 // Functions to initialize tiles
 impl<T: Clone + num::Zero> Tile<T> {
+    /// Returns a tile pre-sized to its eventual full `shape` with zero
+    /// active rows (`offset: 0`), for accumulators grown by
+    /// [`crate::functions::accum_fn::retile_row`]/`retile_col` whose final
+    /// size is known up front. The backing `underlying` array is allocated
+    /// once at the full `shape`, so later growth steps can fill rows in
+    /// place instead of reallocating on every call, unlike [`Self::new_empty`]
+    /// (which allocates nothing because its 0-sized dimension has no
+    /// elements to reserve).
+    pub fn with_capacity(shape: Vec<usize>, bytes_per_elem: usize, read_from_mu: bool) -> Self {
+        let rows = shape[0];
+        let cols = shape[1];
+        Self {
+            shape: shape.clone(),
+            bytes_per_elem,
+            read_from_mu,
+            underlying: Some(ndarray::ArcArray2::zeros((rows, cols))),
+            offset: 0,
+            col_offset: cols,
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
+        }
+    }
+
     /// Returns a zero tile (All rows are active. No padding.)
     /// * Tile Shape: arr_shape
     /// * Tile content: all zeros
@@ -108,6 +415,11 @@ impl<T: Clone + num::Zero> Tile<T> {
             read_from_mu: read_from_mu,
             underlying: Some(ndarray::ArcArray2::zeros(arr_shape)),
             offset: arr_shape[0],
+            col_offset: arr_shape[1],
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
         }
     }
 
@@ -127,6 +439,11 @@ impl<T: Clone + num::Zero> Tile<T> {
             read_from_mu: read_from_mu,
             underlying: Some(ndarray::ArcArray2::zeros(arr_shape)),
             offset: offset,
+            col_offset: arr_shape[1],
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
         }
     }
 
@@ -146,6 +463,148 @@ impl<T: Clone + num::Zero> Tile<T> {
                     .to_shared(),
             ),
             offset: arr_shape[0],
+            col_offset: arr_shape[1],
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
+        }
+    }
+
+    /// Rebuilds the concrete `num_rows × num_cols` (i.e. `offset ×
+    /// col_offset`) active rectangle from `underlying`, replacing every
+    /// element outside it with `pad` (zero when `pad` is `None`) -- the
+    /// materialized view a masked tile load (see [`Self::new_masked`])
+    /// represents.
+    pub fn materialize(&self) -> ndarray::ArcArray2<T> {
+        let rows = self.shape[0];
+        let cols = self.shape[1];
+        let underlying = self
+            .underlying
+            .as_ref()
+            .expect("materialize requires an underlying array to read the active region from");
+        let fill = self.pad.clone().unwrap_or_else(T::zero);
+        ndarray::ArcArray2::from_shape_fn((rows, cols), |(r, c)| {
+            if r < self.offset && c < self.col_offset {
+                underlying[[r, c]].clone()
+            } else {
+                fill.clone()
+            }
+        })
+    }
+}
+
+impl<T: Clone + num::Zero + PartialEq> Tile<T> {
+    /// Run-length-encodes this tile's logical (materialized) contents in
+    /// row-major order, emitting a new `(value, run_length)` pair whenever
+    /// the value changes. Because [`Self::materialize`] fills the inactive
+    /// region uniformly, a padded tile's trailing `shape[0] - offset` rows
+    /// collapse into a single run of `shape[1] * (shape[0] - offset)` pad
+    /// values.
+    pub fn to_rle(&self) -> Vec<(T, usize)> {
+        let dense = self.materialize();
+        let mut runs: Vec<(T, usize)> = Vec::new();
+        for val in dense.iter() {
+            match runs.last_mut() {
+                Some((last, count)) if last == val => *count += 1,
+                _ => runs.push((val.clone(), 1)),
+            }
+        }
+        runs
+    }
+
+    /// Expands this tile's `rle` encoding back into a dense `ArcArray2`,
+    /// the inverse of [`Self::to_rle`].
+    pub fn to_dense(&self) -> ndarray::ArcArray2<T> {
+        let rle = self
+            .rle
+            .as_ref()
+            .expect("to_dense requires an rle-encoded buffer to expand");
+        let flat: Vec<T> = rle
+            .iter()
+            .flat_map(|(val, len)| std::iter::repeat(val.clone()).take(*len))
+            .collect();
+        ndarray::ArcArray2::from_shape_vec((self.shape[0], self.shape[1]), flat)
+            .expect("rle run lengths must sum to shape[0] * shape[1]")
+    }
+
+    /// Returns a copy of this tile with its dense `underlying` backing
+    /// replaced by the [`Self::to_rle`] encoding, freeing the full array
+    /// once only the compressed footprint is needed.
+    pub fn compressed(&self) -> Self {
+        Self {
+            rle: Some(self.to_rle()),
+            underlying: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// A pool of recycled `ArcArray2<T>` buffers, keyed by shape, for
+/// [`Tile::with_capacity`]-style allocations. Hot loops that repeatedly
+/// create and drop same-shaped tiles (e.g. one `new_zero` per step of a
+/// pipeline) can `acquire` from the pool instead of hitting the allocator
+/// each time, and `release` once the tile is no longer needed so the
+/// buffer can be handed back out.
+#[derive(Debug)]
+pub struct TileAllocator<T> {
+    free: std::collections::HashMap<Vec<usize>, Vec<ndarray::ArcArray2<T>>>,
+}
+
+impl<T> Default for TileAllocator<T> {
+    fn default() -> Self {
+        Self {
+            free: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl<T: Clone + num::Zero> TileAllocator<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hands out a zero-filled, fully-active `shape` tile, reusing a
+    /// previously [`Self::release`]d buffer of the same shape if one is
+    /// pooled instead of allocating a fresh one.
+    pub fn acquire(&mut self, shape: Vec<usize>, bytes_per_elem: usize, read_from_mu: bool) -> Tile<T> {
+        let rows = shape[0];
+        let cols = shape[1];
+        let mut underlying = match self.free.get_mut(&shape).and_then(Vec::pop) {
+            Some(arr) => arr,
+            None => ndarray::ArcArray2::zeros((rows, cols)),
+        };
+        underlying.fill(T::zero());
+        Self::into_tile(underlying, shape, bytes_per_elem, read_from_mu)
+    }
+
+    /// Returns `tile`'s backing buffer to the pool, making it available to
+    /// a future [`Self::acquire`] of the same shape.
+    pub fn release(&mut self, tile: Tile<T>) {
+        if let Some(arr) = tile.underlying {
+            self.free.entry(tile.shape).or_default().push(arr);
+        }
+    }
+
+    fn into_tile(
+        underlying: ndarray::ArcArray2<T>,
+        shape: Vec<usize>,
+        bytes_per_elem: usize,
+        read_from_mu: bool,
+    ) -> Tile<T> {
+        let cols = shape[1];
+        let rows = shape[0];
+        Tile {
+            shape,
+            bytes_per_elem,
+            read_from_mu,
+            underlying: Some(underlying),
+            offset: rows,
+            col_offset: cols,
+            pad: None,
+            row_align: 1,
+            csr: None,
+            rle: None,
         }
     }
 }
@@ -0,0 +1,73 @@
+use std::ops::{Index, IndexMut};
+use std::sync::Arc;
+
+/// A flat, row-major `T` buffer paired with a 2D `shape`, independent of
+/// `ndarray`. This is the intended eventual backing for
+/// [`super::tile::Tile`] -- `as_array2`/`from_array2` bridge the two
+/// representations while `Tile`'s constructors still build on
+/// `ndarray::ArcArray2` -- giving callers element indexing and iteration
+/// without pulling in `ndarray` themselves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileBuffer<T> {
+    data: Arc<[T]>,
+    shape: [usize; 2],
+}
+
+impl<T> TileBuffer<T> {
+    pub fn shape(&self) -> [usize; 2] {
+        self.shape
+    }
+
+    fn linear_index(&self, index: [usize; 2]) -> usize {
+        index[0] * self.shape[1] + index[1]
+    }
+}
+
+impl<T: Clone> TileBuffer<T> {
+    /// Builds a `TileBuffer` from an `ArcArray2`, copying its elements into
+    /// a flat row-major buffer.
+    pub fn from_array2(arr: &ndarray::ArcArray2<T>) -> Self {
+        let shape = [arr.shape()[0], arr.shape()[1]];
+        let data: Arc<[T]> = arr.iter().cloned().collect::<Vec<_>>().into();
+        Self { data, shape }
+    }
+
+    /// Inverse of [`Self::from_array2`]: rebuilds an `ArcArray2` view of
+    /// this buffer's contents for callers still working with `ndarray`.
+    pub fn as_array2(&self) -> ndarray::ArcArray2<T> {
+        ndarray::ArcArray2::from_shape_fn(self.shape, |(r, c)| self[[r, c]].clone())
+    }
+}
+
+impl<T> Index<[usize; 2]> for TileBuffer<T> {
+    type Output = T;
+
+    fn index(&self, index: [usize; 2]) -> &T {
+        &self.data[self.linear_index(index)]
+    }
+}
+
+impl<T: Clone> IndexMut<[usize; 2]> for TileBuffer<T> {
+    fn index_mut(&mut self, index: [usize; 2]) -> &mut T {
+        let idx = self.linear_index(index);
+        &mut Arc::make_mut(&mut self.data)[idx]
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TileBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl<T: Clone> IntoIterator for TileBuffer<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.to_vec().into_iter()
+    }
+}
@@ -0,0 +1,304 @@
+//! A simple `key=value` config file format for supplying per-operation
+//! [`SimConfig::config_dict`]/[`SimConfig::par_dispatch_overrides`] entries,
+//! an [`crate::ramulator::hbm_context::HBMConfig::addr_offset`] override, and
+//! per-operation [`SimConfig::switch_cycles_overrides`]/
+//! [`SimConfig::write_back_mu_overrides`] (for `FlatPartition`/
+//! `FlatReassemble`/`Parallelize`, whose `switch_cycles`/`write_back_mu` are
+//! otherwise baked into the proto) without editing code, so sweeps over
+//! buffer sizing and arbitration parameters are reproducible without
+//! recompilation.
+//!
+//! ```text
+//! # blank lines and lines starting with '#' are ignored
+//! chan_depth.47=32
+//! par_dispatch.47=8
+//! hbm.addr_offset=128
+//! op.47.switch_cycles=4
+//! op.47.write_back_mu=true
+//! ```
+//!
+//! Recognized keys are `chan_depth.<operation_id>`, `par_dispatch.<operation_id>`,
+//! `hbm.addr_offset`, `op.<operation_id>.switch_cycles`, and
+//! `op.<operation_id>.write_back_mu`. Any other key is ignored rather than
+//! rejected, so a file shared across tool versions doesn't break on keys
+//! this build doesn't know about yet.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::proto_driver::configs::SimConfig;
+use crate::ramulator::hbm_context::{AddressMapping, HBMConfig};
+
+/// Parsed, merge-ready contents of a config file -- see the module docs for
+/// the recognized key syntax.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectiveConfig {
+    pub chan_depth: HashMap<u32, usize>,
+    pub par_dispatch: HashMap<u32, usize>,
+    pub addr_offset: Option<u64>,
+    pub switch_cycles: HashMap<u32, u64>,
+    pub write_back_mu: HashMap<u32, bool>,
+}
+
+impl EffectiveConfig {
+    /// Parses `contents`, skipping blank lines and `#` comments. Lines that
+    /// don't contain `=`, whose key isn't a recognized `chan_depth.<id>` /
+    /// `par_dispatch.<id>` / `hbm.addr_offset` / `op.<id>.switch_cycles` /
+    /// `op.<id>.write_back_mu`, or whose value doesn't parse as the
+    /// expected type are ignored rather than erroring, since a malformed or
+    /// stale line shouldn't abort an otherwise-usable sweep.
+    pub fn parse(contents: &str) -> Self {
+        let mut config = Self::default();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim();
+
+            if let Some(id) = key.strip_prefix("chan_depth.") {
+                if let (Ok(id), Ok(value)) = (id.parse(), value.parse()) {
+                    config.chan_depth.insert(id, value);
+                }
+            } else if let Some(id) = key.strip_prefix("par_dispatch.") {
+                if let (Ok(id), Ok(value)) = (id.parse(), value.parse()) {
+                    config.par_dispatch.insert(id, value);
+                }
+            } else if key == "hbm.addr_offset" {
+                if let Ok(value) = value.parse() {
+                    config.addr_offset = Some(value);
+                }
+            } else if let Some(id) = key
+                .strip_prefix("op.")
+                .and_then(|rest| rest.strip_suffix(".switch_cycles"))
+            {
+                if let (Ok(id), Ok(value)) = (id.parse(), value.parse()) {
+                    config.switch_cycles.insert(id, value);
+                }
+            } else if let Some(id) = key
+                .strip_prefix("op.")
+                .and_then(|rest| rest.strip_suffix(".write_back_mu"))
+            {
+                if let (Ok(id), Ok(value)) = (id.parse(), value.parse()) {
+                    config.write_back_mu.insert(id, value);
+                }
+            }
+        }
+        config
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Merges this file's values into `sim_config`/`hbm_config`, in place.
+    /// `chan_depth`/`par_dispatch` entries only fill in ids `sim_config`
+    /// doesn't already have an entry for, so a value the caller set
+    /// explicitly (e.g. from the Python `SimConfig` object) always wins
+    /// over the file; `addr_offset`, which has no existing per-source
+    /// precedence to respect, overrides unconditionally when present.
+    pub fn apply(&self, sim_config: &mut SimConfig, hbm_config: &mut HBMConfig) {
+        for (&id, &depth) in &self.chan_depth {
+            sim_config.config_dict.entry(id).or_insert(depth);
+        }
+        for (&id, &dispatch) in &self.par_dispatch {
+            sim_config.par_dispatch_overrides.entry(id).or_insert(dispatch);
+        }
+        for (&id, &cycles) in &self.switch_cycles {
+            sim_config.switch_cycles_overrides.entry(id).or_insert(cycles);
+        }
+        for (&id, &write_back_mu) in &self.write_back_mu {
+            sim_config.write_back_mu_overrides.entry(id).or_insert(write_back_mu);
+        }
+        if let Some(addr_offset) = self.addr_offset {
+            hbm_config.addr_offset = addr_offset;
+        }
+    }
+
+    /// One line per `op_id` plus one for `hbm.addr_offset`, each showing the
+    /// value that id actually resolves to (a file override if one applies,
+    /// else the caller-supplied default) -- lets a user dump exactly what a
+    /// merged config produces for a given graph without re-running it.
+    pub fn report(
+        &self,
+        op_ids: &[u32],
+        default_chan_depth: Option<usize>,
+        default_par_dispatch: &HashMap<u32, usize>,
+        default_addr_offset: u64,
+    ) -> Vec<String> {
+        let mut lines: Vec<String> = op_ids
+            .iter()
+            .map(|&id| {
+                let chan_depth = self.chan_depth.get(&id).copied().or(default_chan_depth);
+                let par_dispatch = self
+                    .par_dispatch
+                    .get(&id)
+                    .or_else(|| default_par_dispatch.get(&id))
+                    .copied();
+                format!(
+                    "op {id}: chan_depth={}, par_dispatch={}",
+                    chan_depth.map_or("unset".to_string(), |d| d.to_string()),
+                    par_dispatch.map_or("unset".to_string(), |d| d.to_string()),
+                )
+            })
+            .collect();
+        lines.push(format!(
+            "hbm.addr_offset={}",
+            self.addr_offset.unwrap_or(default_addr_offset)
+        ));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_recognized_keys_and_ignores_the_rest() {
+        let config = EffectiveConfig::parse(
+            "\
+            # a comment\n\
+            \n\
+            chan_depth.47=32\n\
+            par_dispatch.47=8\n\
+            hbm.addr_offset=128\n\
+            op.47.switch_cycles=4\n\
+            op.47.write_back_mu=true\n\
+            some.unknown.key=1\n\
+            not_a_valid_line\n\
+            ",
+        );
+
+        assert_eq!(config.chan_depth.get(&47), Some(&32));
+        assert_eq!(config.par_dispatch.get(&47), Some(&8));
+        assert_eq!(config.addr_offset, Some(128));
+        assert_eq!(config.switch_cycles.get(&47), Some(&4));
+        assert_eq!(config.write_back_mu.get(&47), Some(&true));
+    }
+
+    #[test]
+    fn apply_merges_switch_cycles_and_write_back_mu_overrides() {
+        let config = EffectiveConfig::parse("op.9.switch_cycles=2\nop.9.write_back_mu=false\n");
+
+        let mut sim_config = SimConfig {
+            channel_depth: None,
+            functional_sim: true,
+            mock_bf16: false,
+            config_dict: HashMap::new(),
+            validate: false,
+            mock_clock_step_ms: None,
+            par_dispatch_overrides: HashMap::new(),
+            store_max_inflight: 1,
+            trace_channel_ids: std::collections::HashSet::new(),
+            trace_data_file: None,
+            trace_buffer_size: 256,
+            trace_max_file_size: 64 * 1024 * 1024,
+            switch_cycles_overrides: HashMap::new(),
+            write_back_mu_overrides: HashMap::new(),
+            golden_capture_ids: HashMap::new(),
+            golden_compare_ids: HashMap::new(),
+            log_file_path: None,
+            metrics_history_file: None,
+            metrics_commit_hash: None,
+            metrics_regression_threshold_pct: None,
+            metrics_history_window: None,
+            watchdog_timeout_ms: None,
+            html_report_path: None,
+            log_buffer_size: None,
+            log_wall_clock_timestamps: false,
+            verify_store_writes: false,
+            allow_store_overwrite: false,
+            storage_format_overrides: HashMap::new(),
+        };
+        let mut hbm_config = HBMConfig {
+            addr_offset: 64,
+            channel_num: 1,
+            per_channel_latency: 1,
+            per_channel_init_interval: 1,
+            per_channel_outstanding: 1,
+            per_channel_start_up_time: 1,
+            bank_num: 1,
+            row_size_bytes: 64,
+            row_conflict_penalty: 0,
+            address_mapping: AddressMapping::Linear,
+        };
+
+        config.apply(&mut sim_config, &mut hbm_config);
+
+        assert_eq!(sim_config.switch_cycles_overrides.get(&9), Some(&2));
+        assert_eq!(sim_config.write_back_mu_overrides.get(&9), Some(&false));
+    }
+
+    #[test]
+    fn apply_fills_in_missing_entries_without_overriding_existing_ones() {
+        let config = EffectiveConfig::parse("chan_depth.1=16\nchan_depth.2=32\n");
+
+        let mut sim_config = SimConfig {
+            channel_depth: None,
+            functional_sim: true,
+            mock_bf16: false,
+            config_dict: HashMap::from([(1, 4)]),
+            validate: false,
+            mock_clock_step_ms: None,
+            par_dispatch_overrides: HashMap::new(),
+            store_max_inflight: 1,
+            trace_channel_ids: std::collections::HashSet::new(),
+            trace_data_file: None,
+            trace_buffer_size: 256,
+            trace_max_file_size: 64 * 1024 * 1024,
+            switch_cycles_overrides: HashMap::new(),
+            write_back_mu_overrides: HashMap::new(),
+            golden_capture_ids: HashMap::new(),
+            golden_compare_ids: HashMap::new(),
+            log_file_path: None,
+            metrics_history_file: None,
+            metrics_commit_hash: None,
+            metrics_regression_threshold_pct: None,
+            metrics_history_window: None,
+            watchdog_timeout_ms: None,
+            html_report_path: None,
+            log_buffer_size: None,
+            log_wall_clock_timestamps: false,
+            verify_store_writes: false,
+            allow_store_overwrite: false,
+            storage_format_overrides: HashMap::new(),
+        };
+        let mut hbm_config = HBMConfig {
+            addr_offset: 64,
+            channel_num: 1,
+            per_channel_latency: 1,
+            per_channel_init_interval: 1,
+            per_channel_outstanding: 1,
+            per_channel_start_up_time: 1,
+            bank_num: 1,
+            row_size_bytes: 64,
+            row_conflict_penalty: 0,
+            address_mapping: AddressMapping::Linear,
+        };
+
+        config.apply(&mut sim_config, &mut hbm_config);
+
+        // id 1 already had an explicit entry, so the file doesn't touch it.
+        assert_eq!(sim_config.config_dict.get(&1), Some(&4));
+        // id 2 had none, so the file fills it in.
+        assert_eq!(sim_config.config_dict.get(&2), Some(&32));
+        assert_eq!(hbm_config.addr_offset, 64);
+    }
+
+    #[test]
+    fn report_shows_overrides_and_fallback_defaults() {
+        let config = EffectiveConfig::parse("chan_depth.1=16\n");
+        let lines = config.report(&[1, 2], Some(8), &HashMap::new(), 64);
+
+        assert_eq!(lines[0], "op 1: chan_depth=16, par_dispatch=unset");
+        assert_eq!(lines[1], "op 2: chan_depth=8, par_dispatch=unset");
+        assert_eq!(lines[2], "hbm.addr_offset=64");
+    }
+}
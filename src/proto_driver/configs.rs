@@ -1,6 +1,12 @@
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::build_sim::trace::{DEFAULT_TRACE_BUFFER_SIZE, DEFAULT_TRACE_MAX_FILE_SIZE};
+use crate::primitives::dtype::DType;
+use crate::utils::clock::{Clock, MockClock, SystemClock};
 
 #[derive(Debug, Clone)]
 pub struct SimConfig {
@@ -8,6 +14,162 @@ pub struct SimConfig {
     pub functional_sim: bool,
     pub mock_bf16: bool,
     pub config_dict: HashMap<u32, usize>,
+    /// Run the [`crate::proto_driver::validate`] lint pass over the
+    /// `ProgramGraph` before building/running it. Defaults to `false` when
+    /// absent from the Python `SimConfig` object, so existing callers keep
+    /// their current (unvalidated) behavior.
+    pub validate: bool,
+    /// If set, `parse_proto` times the run with a [`MockClock`] that
+    /// advances by this fixed step per tick instead of the real system
+    /// clock -- see [`SimConfig::clock`]. Absent (the default) keeps
+    /// `parse_proto`'s reported `duration` wall-clock-accurate.
+    pub mock_clock_step_ms: Option<u64>,
+    /// Per-operation `par_dispatch` overrides, keyed the same way as
+    /// `config_dict` keys a per-operation channel depth. Empty unless a
+    /// caller merges a [`crate::proto_driver::config_file::EffectiveConfig`]
+    /// in -- see `proto_driver::get_par_dispatch`.
+    pub par_dispatch_overrides: HashMap<u32, usize>,
+    /// Write-acknowledgement completion mode for
+    /// [`crate::memory::offchip_store::OffChipStore`] and
+    /// [`crate::memory::random_offchip_store::RandomOffChipStore`]: the
+    /// number of tiles' write requests allowed outstanding at once before
+    /// the store stalls to drain the oldest one. `1` (the default) recovers
+    /// fully synchronous behavior -- every tile blocks on its acks before
+    /// the next is dispatched; anything higher lets the producer race ahead
+    /// of HBM while caller-chosen back-pressure still bounds how far.
+    pub store_max_inflight: usize,
+    /// Ids of channels [`crate::build_sim::trace`] should record token
+    /// values and enqueue cycles for -- the same per-id selection mechanism
+    /// `config_dict` gives `proto_driver::get_chan_depth`, but naming
+    /// channels to trace rather than overriding their depth. Empty (the
+    /// default) traces nothing.
+    pub trace_channel_ids: HashSet<u32>,
+    /// Where to write recorded tokens when `trace_channel_ids` is
+    /// non-empty. `None` (the default) disables tracing outright,
+    /// regardless of `trace_channel_ids`, so callers built before this
+    /// field existed pay no cost.
+    pub trace_data_file: Option<String>,
+    /// Records buffered in memory per traced channel before a flush.
+    /// Ignored while tracing is disabled.
+    pub trace_buffer_size: usize,
+    /// Once a traced channel's file reaches this many bytes, recording for
+    /// it stops rather than growing the file further across a long
+    /// simulation.
+    pub trace_max_file_size: u64,
+    /// Per-operation `switch_cycles` overrides for `FlatPartition`/
+    /// `FlatReassemble` (including `Parallelize`, which reuses
+    /// `FlatPartitionConfig`), keyed the same way as `config_dict` keys a
+    /// per-operation channel depth: an id present here replaces every
+    /// element of the proto-supplied `switch_cycles` vector uniformly.
+    /// Empty unless a caller merges a
+    /// [`crate::proto_driver::config_file::EffectiveConfig`] in -- see
+    /// `proto_driver::get_switch_cycles`.
+    pub switch_cycles_overrides: HashMap<u32, u64>,
+    /// Mirrors `switch_cycles_overrides` for the same ops' `write_back_mu`.
+    /// Empty unless a caller merges an `EffectiveConfig` in -- see
+    /// `proto_driver::get_write_back_mu`.
+    pub write_back_mu_overrides: HashMap<u32, bool>,
+    /// `ConsumerContext` operations (keyed by `input_id`) that should record
+    /// their stream to the given file as a
+    /// [`crate::build_sim::golden::GoldenMode::Capture`] vector instead of
+    /// silently discarding it. Empty (the default) leaves every
+    /// `ConsumerContext` a plain sink. See `proto_driver::get_golden_mode`.
+    pub golden_capture_ids: HashMap<u32, String>,
+    /// `ConsumerContext` operations (keyed by `input_id`) that should
+    /// instead replay the given file and assert the live stream matches it
+    /// token-for-token ([`crate::build_sim::golden::GoldenMode::Compare`]).
+    /// An id present in both this and `golden_capture_ids` is captured, not
+    /// compared -- see `proto_driver::get_golden_mode`.
+    pub golden_compare_ids: HashMap<u32, String>,
+    /// If set, `parse_proto` installs a [`crate::proto_driver::log_sink`]
+    /// writing dam's structured event log to this file as
+    /// newline-delimited JSON -- an alternative to `LoggingOptions::Mongo`
+    /// for callers that don't want to stand up a MongoDB server. Absent
+    /// (the default) leaves file logging off.
+    pub log_file_path: Option<String>,
+    /// If set, `parse_proto` appends this run's `cycles`/`duration` to the
+    /// given [`crate::proto_driver::metrics_history`] file, keyed by a
+    /// fingerprint of `hbm_config`/`self`, and flags a regression if they
+    /// exceed the recent same-fingerprint median by more than
+    /// `metrics_regression_threshold_pct`. Absent (the default) disables
+    /// history tracking entirely.
+    pub metrics_history_file: Option<String>,
+    /// Commit hash recorded alongside this run's metrics history entry.
+    /// Callers are expected to supply their own VCS hash (e.g. from
+    /// `git rev-parse HEAD`), since this crate has no VCS integration of
+    /// its own; falls back to `"unknown"` if absent.
+    pub metrics_commit_hash: Option<String>,
+    /// Regression threshold for `metrics_history_file`, as a percentage
+    /// the new run's `cycles`/`duration` may exceed the baseline median
+    /// by. Falls back to
+    /// [`crate::proto_driver::metrics_history::DEFAULT_REGRESSION_THRESHOLD_PCT`]
+    /// if absent.
+    pub metrics_regression_threshold_pct: Option<f64>,
+    /// Number of most-recent same-fingerprint history records the
+    /// baseline median is computed over. Falls back to
+    /// [`crate::proto_driver::metrics_history::DEFAULT_HISTORY_WINDOW`] if
+    /// absent.
+    pub metrics_history_window: Option<usize>,
+    /// If set, `parse_proto` runs under a
+    /// [`crate::utils::watchdog::run_with_watchdog`] budget of this many
+    /// milliseconds: every channel gets a
+    /// [`crate::build_sim::watchdog::WatchdogTap`], and if the run is still
+    /// going when the budget expires, a deadlock report naming the
+    /// longest-quiet channels is printed. Absent (the default) disables the
+    /// watchdog, so an unconfigured run pays no per-channel tracking cost.
+    pub watchdog_timeout_ms: Option<u64>,
+    /// If set, `parse_proto` writes a self-contained
+    /// [`crate::utils::html_report`] page to this path at the end of the
+    /// run: pass/fail, cycles, wall duration, and a per-channel table/
+    /// timeline of depth, peak stall, and stalled-token counts. Every
+    /// channel gets a [`crate::build_sim::occupancy::OccupancyTap`] when
+    /// this is set. Absent (the default) disables occupancy tracking
+    /// entirely.
+    pub html_report_path: Option<String>,
+    /// Number of [`crate::proto_driver::log_sink`] records buffered in
+    /// memory before a flush, trading trace fidelity (a crash loses at
+    /// most this many unflushed records) against the syscall/contention
+    /// overhead of flushing every single event. Falls back to
+    /// [`crate::proto_driver::log_sink::DEFAULT_LOG_BUFFER_SIZE`] if unset.
+    pub log_buffer_size: Option<usize>,
+    /// If set, every `log_sink` record is also stamped with a monotonic
+    /// wall-clock microsecond timestamp alongside its simulated cycle, so
+    /// a run can be profiled by *real* execution cost per operator, not
+    /// just modeled cycles. Off by default, since most callers only care
+    /// about simulated time.
+    pub log_wall_clock_timestamps: bool,
+    /// Turns on [`crate::memory::random_offchip_store::RandomOffChipStore`]'s
+    /// write-coverage verification: tracking written byte ranges and tile
+    /// indices so its close path can flag aliasing writes, out-of-bounds
+    /// tiling math, and untouched tiles in the saved `.json` metadata. Off
+    /// by default, since the tracking has a (small) per-write cost.
+    pub verify_store_writes: bool,
+    /// With `verify_store_writes` on, suppresses the aliasing/double-write
+    /// check specifically (out-of-bounds and coverage-gap checks still
+    /// run), for tensors a caller genuinely intends to overwrite the same
+    /// region of more than once. Ignored while `verify_store_writes` is
+    /// off.
+    pub allow_store_overwrite: bool,
+    /// Per-operation `storage_format` overrides for
+    /// [`crate::memory::dyn_offchip_load::DynOffChipLoad`], keyed the same
+    /// way as `config_dict` keys a per-operation channel depth:
+    /// `dyn_offchip_load`'s proto message has no `storage_format` field of
+    /// its own to carry this, the same situation `par_dispatch_overrides`
+    /// works around for `par_dispatch`. Empty unless a caller merges one in
+    /// -- see `proto_driver::get_storage_format`.
+    pub storage_format_overrides: HashMap<u32, DType>,
+}
+
+impl SimConfig {
+    /// The [`Clock`] `parse_proto` should time the run with: a
+    /// [`MockClock`] if `mock_clock_step_ms` is set, or the real
+    /// [`SystemClock`] otherwise.
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        match self.mock_clock_step_ms {
+            Some(step_ms) => Arc::new(MockClock::new(Duration::from_millis(step_ms))),
+            None => Arc::new(SystemClock),
+        }
+    }
 }
 
 impl<'py> FromPyObject<'py> for SimConfig {
@@ -61,11 +223,206 @@ impl<'py> FromPyObject<'py> for SimConfig {
             })?
         };
 
+        // `validate` is a newer, optional attribute: fall back to `false`
+        // rather than erroring out for callers built before it existed.
+        let validate: bool = obj
+            .getattr("validate")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or(false);
+
+        // `mock_clock_step_ms` is a newer, optional attribute: fall back to
+        // `None` (the real system clock) for callers built before it
+        // existed.
+        let mock_clock_step_ms: Option<u64> = obj
+            .getattr("mock_clock_step_ms")
+            .ok()
+            .and_then(|v| v.extract().ok());
+
+        // `par_dispatch_overrides` is a newer, optional attribute: fall back
+        // to no overrides for callers built before it existed.
+        let par_dispatch_overrides: HashMap<u32, usize> = obj
+            .getattr("par_dispatch_overrides")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or_default();
+
+        // `store_max_inflight` is a newer, optional attribute: fall back to
+        // `1` (fully synchronous stores) for callers built before it existed.
+        let store_max_inflight: usize = obj
+            .getattr("store_max_inflight")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or(1);
+
+        // `trace_channel_ids`/`trace_data_file`/`trace_buffer_size`/
+        // `trace_max_file_size` are newer, optional attributes: fall back to
+        // tracing fully disabled for callers built before they existed.
+        let trace_channel_ids: HashSet<u32> = obj
+            .getattr("trace_channel_ids")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or_default();
+        let trace_data_file: Option<String> = obj
+            .getattr("trace_data_file")
+            .ok()
+            .and_then(|v| v.extract().ok());
+        let trace_buffer_size: usize = obj
+            .getattr("trace_buffer_size")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or(DEFAULT_TRACE_BUFFER_SIZE);
+        let trace_max_file_size: u64 = obj
+            .getattr("trace_max_file_size")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or(DEFAULT_TRACE_MAX_FILE_SIZE);
+
+        // `switch_cycles_overrides`/`write_back_mu_overrides` are newer,
+        // optional attributes: fall back to no overrides for callers built
+        // before they existed.
+        let switch_cycles_overrides: HashMap<u32, u64> = obj
+            .getattr("switch_cycles_overrides")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or_default();
+        let write_back_mu_overrides: HashMap<u32, bool> = obj
+            .getattr("write_back_mu_overrides")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or_default();
+
+        // `golden_capture_ids`/`golden_compare_ids` are newer, optional
+        // attributes: fall back to no golden-vector recording/checking for
+        // callers built before they existed.
+        let golden_capture_ids: HashMap<u32, String> = obj
+            .getattr("golden_capture_ids")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or_default();
+        let golden_compare_ids: HashMap<u32, String> = obj
+            .getattr("golden_compare_ids")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or_default();
+
+        // `log_file_path` is a newer, optional attribute: fall back to no
+        // file logging for callers built before it existed.
+        let log_file_path: Option<String> = obj
+            .getattr("log_file_path")
+            .ok()
+            .and_then(|v| v.extract().ok());
+
+        // `metrics_history_file`/`metrics_commit_hash`/
+        // `metrics_regression_threshold_pct`/`metrics_history_window` are
+        // newer, optional attributes: fall back to history tracking fully
+        // disabled for callers built before they existed.
+        let metrics_history_file: Option<String> = obj
+            .getattr("metrics_history_file")
+            .ok()
+            .and_then(|v| v.extract().ok());
+        let metrics_commit_hash: Option<String> = obj
+            .getattr("metrics_commit_hash")
+            .ok()
+            .and_then(|v| v.extract().ok());
+        let metrics_regression_threshold_pct: Option<f64> = obj
+            .getattr("metrics_regression_threshold_pct")
+            .ok()
+            .and_then(|v| v.extract().ok());
+        let metrics_history_window: Option<usize> = obj
+            .getattr("metrics_history_window")
+            .ok()
+            .and_then(|v| v.extract().ok());
+
+        // `watchdog_timeout_ms` is a newer, optional attribute: fall back
+        // to no watchdog for callers built before it existed.
+        let watchdog_timeout_ms: Option<u64> = obj
+            .getattr("watchdog_timeout_ms")
+            .ok()
+            .and_then(|v| v.extract().ok());
+
+        // `html_report_path` is a newer, optional attribute: fall back to
+        // no report for callers built before it existed.
+        let html_report_path: Option<String> = obj
+            .getattr("html_report_path")
+            .ok()
+            .and_then(|v| v.extract().ok());
+
+        // `log_buffer_size`/`log_wall_clock_timestamps` are newer, optional
+        // attributes: fall back to the sink's default buffer size and
+        // cycle-only timestamps for callers built before they existed.
+        let log_buffer_size: Option<usize> = obj
+            .getattr("log_buffer_size")
+            .ok()
+            .and_then(|v| v.extract().ok());
+        let log_wall_clock_timestamps: bool = obj
+            .getattr("log_wall_clock_timestamps")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or(false);
+
+        // `verify_store_writes`/`allow_store_overwrite` are newer, optional
+        // attributes: fall back to no write-coverage tracking (and, moot
+        // while tracking is off, no overwrite allowance) for callers built
+        // before they existed.
+        let verify_store_writes: bool = obj
+            .getattr("verify_store_writes")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or(false);
+        let allow_store_overwrite: bool = obj
+            .getattr("allow_store_overwrite")
+            .ok()
+            .and_then(|v| v.extract().ok())
+            .unwrap_or(false);
+
+        // `storage_format_overrides` is a newer, optional attribute: fall
+        // back to no overrides for callers built before it existed. Python
+        // supplies variant names as strings (there's no pyo3 binding for
+        // `DType` itself), parsed the same way `build.rs`'s `operators.in`
+        // table rejects an unrecognized dtype name.
+        let storage_format_overrides: HashMap<u32, DType> = obj
+            .getattr("storage_format_overrides")
+            .ok()
+            .and_then(|v| v.extract::<HashMap<u32, String>>().ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(id, name)| {
+                let dtype = DType::from_name(&name)
+                    .unwrap_or_else(|| panic!("storage_format_overrides: unknown dtype `{name}`"));
+                (id, dtype)
+            })
+            .collect();
+
         Ok(SimConfig {
             channel_depth,
             functional_sim,
             mock_bf16,
             config_dict,
+            validate,
+            mock_clock_step_ms,
+            par_dispatch_overrides,
+            store_max_inflight,
+            trace_channel_ids,
+            trace_data_file,
+            trace_buffer_size,
+            trace_max_file_size,
+            switch_cycles_overrides,
+            write_back_mu_overrides,
+            golden_capture_ids,
+            golden_compare_ids,
+            log_file_path,
+            metrics_history_file,
+            metrics_commit_hash,
+            metrics_regression_threshold_pct,
+            metrics_history_window,
+            watchdog_timeout_ms,
+            html_report_path,
+            log_buffer_size,
+            log_wall_clock_timestamps,
+            verify_store_writes,
+            allow_store_overwrite,
+            storage_format_overrides,
         })
     }
 }
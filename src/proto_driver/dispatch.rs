@@ -0,0 +1,22 @@
+//! Table-driven `ElemElemFn` dispatch for [`super::build_from_proto`].
+//!
+//! The `(op family, dtype combo) -> ElemElemFn variant -> functions::map_fn`
+//! wiring used to be a hand-written nested `match` per combo, repeating the
+//! same `Arc<dyn Fn...>` boilerplate for every new function. It's now
+//! generated at build time from `operators.in` (see that file's header for
+//! the table format) by `build.rs`, which writes the `dispatch_*` functions
+//! and an exhaustiveness check below into `$OUT_DIR/dispatch_table.rs`.
+//!
+//! Only the `unary_map` `(F32, F32)` and `binary_map` `(F32, F32, F32)`
+//! combos have been migrated to call through here so far; the other combos
+//! in `build_from_proto` still have
+//! their original hand-written matches, even though `operators.in` already
+//! catalogs them (so the exhaustiveness check below covers every variant in
+//! use today, not just the migrated ones). Future requests can migrate the
+//! remaining call sites one table row at a time.
+
+use crate::functions;
+use crate::primitives::tile::Tile;
+use crate::proto_driver::proto_headers::graph_proto::elemto_elem_func;
+
+include!(concat!(env!("OUT_DIR"), "/dispatch_table.rs"));
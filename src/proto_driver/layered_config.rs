@@ -0,0 +1,392 @@
+//! A single TOML/JSON file holding a `[base]` section plus named
+//! environment sections (e.g. `[profiles.dev]`, `[profiles.prod]`) that
+//! override it -- mirrors how layered deployment manifests select an active
+//! profile, so retargeting [`HBMConfig`]/[`SimConfig`] knobs and per-tensor
+//! address placements across environments doesn't require recompiling.
+//! Unlike [`super::config_file`]'s flat `key=value` overrides (meant for
+//! one-off sweeps over a couple of ids), this is meant for a handful of
+//! named, reusable environments checked into a config file.
+//!
+//! ```toml
+//! [base]
+//! addr_offset = 64
+//! channel_num = 32
+//! functional_sim = true
+//!
+//! [base.tensors.activations]
+//! base_addr_byte = 0
+//! tile_row = 32
+//! tile_col = 32
+//!
+//! [profiles.prod]
+//! channel_num = 64
+//!
+//! [profiles.prod.tensors.activations]
+//! base_addr_byte = 4096
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::proto_driver::configs::SimConfig;
+use crate::ramulator::hbm_context::HBMConfig;
+
+/// Per-tensor placement knobs consumed by `RandomOffChipStore::new`'s and
+/// `DynOffChipLoad::new`'s address-map and dispatch parameters, keyed by
+/// tensor name in [`ConfigProfile::tensors`].
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+pub struct TensorPlacement {
+    #[serde(default)]
+    pub base_addr_byte: Option<u64>,
+    #[serde(default)]
+    pub addr_offset: Option<u64>,
+    #[serde(default)]
+    pub par_dispatch: Option<usize>,
+    #[serde(default)]
+    pub tile_row: Option<usize>,
+    #[serde(default)]
+    pub tile_col: Option<usize>,
+}
+
+impl TensorPlacement {
+    /// `override_`'s fields win where set; `self`'s fields fill in the rest.
+    fn layered_under(&self, override_: &TensorPlacement) -> TensorPlacement {
+        TensorPlacement {
+            base_addr_byte: override_.base_addr_byte.or(self.base_addr_byte),
+            addr_offset: override_.addr_offset.or(self.addr_offset),
+            par_dispatch: override_.par_dispatch.or(self.par_dispatch),
+            tile_row: override_.tile_row.or(self.tile_row),
+            tile_col: override_.tile_col.or(self.tile_col),
+        }
+    }
+}
+
+/// One layer's settings -- either the `[base]` section or a named
+/// `[profiles.<name>]` section of a [`LayeredConfig`] file. Every field is
+/// optional, so a profile only needs to state what it changes from the base.
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+pub struct ConfigProfile {
+    #[serde(default)]
+    pub addr_offset: Option<u64>,
+    #[serde(default)]
+    pub channel_num: Option<usize>,
+    #[serde(default)]
+    pub per_channel_latency: Option<u64>,
+    #[serde(default)]
+    pub per_channel_init_interval: Option<u64>,
+    #[serde(default)]
+    pub per_channel_outstanding: Option<usize>,
+    #[serde(default)]
+    pub per_channel_start_up_time: Option<u64>,
+    #[serde(default)]
+    pub bank_num: Option<usize>,
+    #[serde(default)]
+    pub row_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub row_conflict_penalty: Option<u64>,
+
+    #[serde(default)]
+    pub channel_depth: Option<usize>,
+    #[serde(default)]
+    pub functional_sim: Option<bool>,
+    #[serde(default)]
+    pub mock_bf16: Option<bool>,
+    #[serde(default)]
+    pub validate: Option<bool>,
+    #[serde(default)]
+    pub store_max_inflight: Option<usize>,
+
+    /// Per-tensor address/dispatch placements, keyed by tensor name.
+    #[serde(default)]
+    pub tensors: HashMap<String, TensorPlacement>,
+}
+
+impl ConfigProfile {
+    /// `override_`'s fields win where set; `self`'s fields fill in the
+    /// rest. Tensor placements are merged per-name via
+    /// [`TensorPlacement::layered_under`] rather than one replacing the
+    /// other wholesale, so a profile can override just one tensor's
+    /// `base_addr_byte` without restating its `tile_row`/`tile_col`.
+    fn layered_under(&self, override_: &ConfigProfile) -> ConfigProfile {
+        let mut tensors = self.tensors.clone();
+        for (name, placement) in &override_.tensors {
+            tensors
+                .entry(name.clone())
+                .and_modify(|base| *base = base.layered_under(placement))
+                .or_insert_with(|| placement.clone());
+        }
+        ConfigProfile {
+            addr_offset: override_.addr_offset.or(self.addr_offset),
+            channel_num: override_.channel_num.or(self.channel_num),
+            per_channel_latency: override_.per_channel_latency.or(self.per_channel_latency),
+            per_channel_init_interval: override_
+                .per_channel_init_interval
+                .or(self.per_channel_init_interval),
+            per_channel_outstanding: override_
+                .per_channel_outstanding
+                .or(self.per_channel_outstanding),
+            per_channel_start_up_time: override_
+                .per_channel_start_up_time
+                .or(self.per_channel_start_up_time),
+            bank_num: override_.bank_num.or(self.bank_num),
+            row_size_bytes: override_.row_size_bytes.or(self.row_size_bytes),
+            row_conflict_penalty: override_.row_conflict_penalty.or(self.row_conflict_penalty),
+            channel_depth: override_.channel_depth.or(self.channel_depth),
+            functional_sim: override_.functional_sim.or(self.functional_sim),
+            mock_bf16: override_.mock_bf16.or(self.mock_bf16),
+            validate: override_.validate.or(self.validate),
+            store_max_inflight: override_.store_max_inflight.or(self.store_max_inflight),
+            tensors,
+        }
+    }
+
+    /// Merges this profile's set fields into `hbm_config`/`sim_config`, in
+    /// place, overriding unconditionally (the caller is expected to pass in
+    /// the same defaults a fresh `HBMConfig`/`SimConfig` would use, since
+    /// this profile is already the fully-resolved result of layering base
+    /// over named profile).
+    fn apply(&self, hbm_config: &mut HBMConfig, sim_config: &mut SimConfig) {
+        if let Some(v) = self.addr_offset {
+            hbm_config.addr_offset = v;
+        }
+        if let Some(v) = self.channel_num {
+            hbm_config.channel_num = v;
+        }
+        if let Some(v) = self.per_channel_latency {
+            hbm_config.per_channel_latency = v;
+        }
+        if let Some(v) = self.per_channel_init_interval {
+            hbm_config.per_channel_init_interval = v;
+        }
+        if let Some(v) = self.per_channel_outstanding {
+            hbm_config.per_channel_outstanding = v;
+        }
+        if let Some(v) = self.per_channel_start_up_time {
+            hbm_config.per_channel_start_up_time = v;
+        }
+        if let Some(v) = self.bank_num {
+            hbm_config.bank_num = v;
+        }
+        if let Some(v) = self.row_size_bytes {
+            hbm_config.row_size_bytes = v;
+        }
+        if let Some(v) = self.row_conflict_penalty {
+            hbm_config.row_conflict_penalty = v;
+        }
+
+        if let Some(v) = self.channel_depth {
+            sim_config.channel_depth = Some(v);
+        }
+        if let Some(v) = self.functional_sim {
+            sim_config.functional_sim = v;
+        }
+        if let Some(v) = self.mock_bf16 {
+            sim_config.mock_bf16 = v;
+        }
+        if let Some(v) = self.validate {
+            sim_config.validate = v;
+        }
+        if let Some(v) = self.store_max_inflight {
+            sim_config.store_max_inflight = v;
+        }
+    }
+}
+
+/// Error resolving a [`LayeredConfig`]'s active profile.
+#[derive(Error, Debug, PartialEq)]
+pub enum LayeredConfigError {
+    #[error("unknown profile {name:?} (known profiles: {known:?})")]
+    UnknownProfile { name: String, known: Vec<String> },
+}
+
+/// A parsed layered config file -- see the module docs for the section
+/// layout and merge semantics.
+#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
+pub struct LayeredConfig {
+    #[serde(default)]
+    pub base: ConfigProfile,
+    #[serde(default)]
+    pub profiles: HashMap<String, ConfigProfile>,
+}
+
+impl LayeredConfig {
+    pub fn from_toml_str(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn from_json_str(contents: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(contents)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        } else {
+            Self::from_toml_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+    }
+
+    /// Merges `active`'s section over `[base]`, then applies the result
+    /// onto `hbm_config`/`sim_config` and returns the resolved per-tensor
+    /// placement table. An unset `active` (the base-only run) always
+    /// resolves; an `active` naming a profile this file doesn't have is a
+    /// clear error rather than silently falling back to the base.
+    pub fn resolve(
+        &self,
+        active: Option<&str>,
+        hbm_config: &mut HBMConfig,
+        sim_config: &mut SimConfig,
+    ) -> Result<HashMap<String, TensorPlacement>, LayeredConfigError> {
+        let resolved = match active {
+            None => self.base.clone(),
+            Some(name) => {
+                let profile =
+                    self.profiles
+                        .get(name)
+                        .ok_or_else(|| LayeredConfigError::UnknownProfile {
+                            name: name.to_string(),
+                            known: self.profiles.keys().cloned().collect(),
+                        })?;
+                self.base.layered_under(profile)
+            }
+        };
+
+        resolved.apply(hbm_config, sim_config);
+        Ok(resolved.tensors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ramulator::hbm_context::AddressMapping;
+
+    const LAYERED_TOML: &str = r#"
+        [base]
+        addr_offset = 64
+        channel_num = 32
+        functional_sim = true
+
+        [base.tensors.activations]
+        base_addr_byte = 0
+        tile_row = 32
+        tile_col = 32
+
+        [profiles.prod]
+        channel_num = 64
+
+        [profiles.prod.tensors.activations]
+        base_addr_byte = 4096
+    "#;
+
+    fn default_hbm_config() -> HBMConfig {
+        HBMConfig {
+            addr_offset: 1,
+            channel_num: 1,
+            per_channel_latency: 1,
+            per_channel_init_interval: 1,
+            per_channel_outstanding: 1,
+            per_channel_start_up_time: 1,
+            bank_num: 1,
+            row_size_bytes: 1,
+            row_conflict_penalty: 0,
+            address_mapping: AddressMapping::Linear,
+        }
+    }
+
+    fn default_sim_config() -> SimConfig {
+        SimConfig {
+            channel_depth: None,
+            functional_sim: false,
+            mock_bf16: false,
+            config_dict: HashMap::new(),
+            validate: false,
+            mock_clock_step_ms: None,
+            par_dispatch_overrides: HashMap::new(),
+            store_max_inflight: 1,
+            trace_channel_ids: std::collections::HashSet::new(),
+            trace_data_file: None,
+            trace_buffer_size: 256,
+            trace_max_file_size: 64 * 1024 * 1024,
+            switch_cycles_overrides: HashMap::new(),
+            write_back_mu_overrides: HashMap::new(),
+            golden_capture_ids: HashMap::new(),
+            golden_compare_ids: HashMap::new(),
+            log_file_path: None,
+            metrics_history_file: None,
+            metrics_commit_hash: None,
+            metrics_regression_threshold_pct: None,
+            metrics_history_window: None,
+            watchdog_timeout_ms: None,
+            html_report_path: None,
+            log_buffer_size: None,
+            log_wall_clock_timestamps: false,
+            verify_store_writes: false,
+            allow_store_overwrite: false,
+            storage_format_overrides: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn base_only_resolves_without_an_active_profile() {
+        let config = LayeredConfig::from_toml_str(LAYERED_TOML).unwrap();
+        let mut hbm_config = default_hbm_config();
+        let mut sim_config = default_sim_config();
+
+        let tensors = config.resolve(None, &mut hbm_config, &mut sim_config).unwrap();
+
+        assert_eq!(hbm_config.addr_offset, 64);
+        assert_eq!(hbm_config.channel_num, 32);
+        assert!(sim_config.functional_sim);
+        assert_eq!(tensors["activations"].base_addr_byte, Some(0));
+    }
+
+    #[test]
+    fn named_profile_overrides_base() {
+        let config = LayeredConfig::from_toml_str(LAYERED_TOML).unwrap();
+        let mut hbm_config = default_hbm_config();
+        let mut sim_config = default_sim_config();
+
+        let tensors = config
+            .resolve(Some("prod"), &mut hbm_config, &mut sim_config)
+            .unwrap();
+
+        // `channel_num` came from the profile...
+        assert_eq!(hbm_config.channel_num, 64);
+        // ...but `addr_offset`, unset by the profile, still falls back to base.
+        assert_eq!(hbm_config.addr_offset, 64);
+
+        // Tensor placements merge per-field too: `base_addr_byte` came from
+        // the profile, `tile_row`/`tile_col` fell back to the base entry.
+        let activations = &tensors["activations"];
+        assert_eq!(activations.base_addr_byte, Some(4096));
+        assert_eq!(activations.tile_row, Some(32));
+        assert_eq!(activations.tile_col, Some(32));
+    }
+
+    #[test]
+    fn unknown_active_profile_is_a_clear_error() {
+        let config = LayeredConfig::from_toml_str(LAYERED_TOML).unwrap();
+        let mut hbm_config = default_hbm_config();
+        let mut sim_config = default_sim_config();
+
+        let err = config
+            .resolve(Some("staging"), &mut hbm_config, &mut sim_config)
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            LayeredConfigError::UnknownProfile {
+                name: "staging".to_string(),
+                known: vec!["prod".to_string()],
+            }
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Local file logging backend for dam's structured event log
+//! (`crate::utils::events::log_event`), for callers who want
+//! `SimpleEvent`-style traces without standing up a MongoDB server the way
+//! `LoggingOptions::Mongo` requires.
+//!
+//! Selected via `SimConfig::log_file_path` alongside (or instead of) Mongo
+//! logging -- see `parse_proto`'s `match logging` branch. Records are
+//! written as newline-delimited JSON, one line per event, each carrying
+//! whatever fields the event type itself serializes (for `SimpleEvent`:
+//! name, node id, start/end cycle, and the stop flag).
+//!
+//! Records aren't written synchronously: they accumulate in memory (size
+//! controlled by `SimConfig::log_buffer_size`) and are flushed to disk in
+//! batches, on a size threshold or at `uninstall()` (always called at
+//! simulation end by `parse_proto`) -- cutting per-event syscall/contention
+//! overhead on simulations that emit heavily. When
+//! `SimConfig::log_wall_clock_timestamps` is set, every record also gets a
+//! `wall_clock_us` field alongside its simulated cycle, so a run can be
+//! profiled by real execution cost, not just modeled cycles. Columnar
+//! (Parquet/Arrow) output is left as future work -- this only implements
+//! the newline-delimited JSON format.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default record count buffered in memory before a flush, used when
+/// `SimConfig::log_buffer_size` is unset.
+pub const DEFAULT_LOG_BUFFER_SIZE: usize = 256;
+
+struct SinkState {
+    writer: BufWriter<File>,
+    buffer: Vec<String>,
+    buffer_size: usize,
+    wall_clock_timestamps: bool,
+}
+
+static SINK: OnceLock<Mutex<Option<SinkState>>> = OnceLock::new();
+
+/// Opens (truncating) `path` and installs it as the process-wide file log
+/// sink, replacing any previously-installed one.
+pub fn install(path: &str, buffer_size: usize, wall_clock_timestamps: bool) -> std::io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    *SINK.get_or_init(|| Mutex::new(None)).lock().unwrap() = Some(SinkState {
+        writer,
+        buffer: Vec::with_capacity(buffer_size),
+        buffer_size,
+        wall_clock_timestamps,
+    });
+    Ok(())
+}
+
+/// Flushes any buffered records and removes the installed sink, if any, so
+/// a later run in the same process doesn't keep writing to a stale file
+/// handle.
+pub fn uninstall() {
+    if let Some(cell) = SINK.get() {
+        if let Some(mut state) = cell.lock().unwrap().take() {
+            flush(&mut state);
+        }
+    }
+}
+
+/// Buffers one newline-delimited JSON record if a sink is installed,
+/// flushing once `buffer_size` records have accumulated; otherwise a no-op.
+pub(crate) fn record(event: &impl serde::Serialize) {
+    let Some(cell) = SINK.get() else { return };
+    let mut guard = cell.lock().unwrap();
+    let Some(state) = guard.as_mut() else { return };
+
+    let line = if state.wall_clock_timestamps {
+        let mut value = match serde_json::to_value(event) {
+            Ok(value) => value,
+            Err(_) => return,
+        };
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("wall_clock_us".to_string(), now_wall_clock_us().into());
+        }
+        value.to_string()
+    } else {
+        match serde_json::to_string(event) {
+            Ok(line) => line,
+            Err(_) => return,
+        }
+    };
+
+    state.buffer.push(line);
+    if state.buffer.len() >= state.buffer_size {
+        flush(state);
+    }
+}
+
+fn flush(state: &mut SinkState) {
+    for line in state.buffer.drain(..) {
+        let _ = writeln!(state.writer, "{line}");
+    }
+    let _ = state.writer.flush();
+}
+
+fn now_wall_clock_us() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
@@ -0,0 +1,311 @@
+//! Cross-run metrics history with automatic regression detection, in the
+//! style of rustc-perf: each [`crate::proto_driver::parse_proto`] run can
+//! append its result to a JSON-lines history file, then compare itself
+//! against the recent history for the *same simulation configuration*
+//! before reporting a regression.
+//!
+//! Records are keyed by [`fingerprint`], a hash of the `HBMConfig`/
+//! `SimConfig` fields that actually affect simulated `cycles`/`duration`
+//! (channel depths, dispatch/switch/write-back overrides, HBM timing --
+//! not bookkeeping fields like `trace_data_file` or `log_file_path`), so
+//! a history file shared across several configs only ever compares a run
+//! against others of its own kind. Comparing against the median of the
+//! last few same-fingerprint runs, rather than just the previous one,
+//! keeps ordinary wall-clock noise from tripping the regression flag.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::proto_driver::configs::SimConfig;
+use crate::ramulator::hbm_context::HBMConfig;
+
+/// One run's entry in a metrics history file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsRecord {
+    pub commit_hash: String,
+    pub timestamp_unix_secs: u64,
+    pub config_fingerprint: String,
+    pub cycles: u64,
+    pub duration_ms: u128,
+}
+
+/// How far a new run's `cycles`/`duration` may exceed its history's
+/// median before [`check_and_record`] flags a regression.
+pub const DEFAULT_REGRESSION_THRESHOLD_PCT: f64 = 5.0;
+
+/// How many of the most recent same-fingerprint records the baseline
+/// median is computed over.
+pub const DEFAULT_HISTORY_WINDOW: usize = 10;
+
+/// The comparison of one run against its history, returned by
+/// [`check_and_record`] -- `None` there means no prior history exists
+/// for that fingerprint yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionReport {
+    pub is_regression: bool,
+    pub baseline_cycles_median: f64,
+    pub baseline_duration_ms_median: f64,
+    pub cycles_pct_change: f64,
+    pub duration_pct_change: f64,
+}
+
+/// Hashes the subset of `hbm_config`/`sim_config` fields that affect
+/// simulated `cycles`/`duration` into a short, stable identifier. Map
+/// fields are sorted before hashing, since `HashMap`'s own iteration
+/// order is randomized per-process and would otherwise fingerprint two
+/// identical configs differently from one run to the next.
+pub fn fingerprint(hbm_config: &HBMConfig, sim_config: &SimConfig) -> String {
+    let relevant = format!(
+        "{hbm_config:?}|{:?}|{}|{}|{}|{}|{}|{}|{}",
+        sim_config.channel_depth,
+        sim_config.functional_sim,
+        sim_config.mock_bf16,
+        sorted_entries(&sim_config.config_dict),
+        sorted_entries(&sim_config.par_dispatch_overrides),
+        sim_config.store_max_inflight,
+        sorted_entries(&sim_config.switch_cycles_overrides),
+        sorted_entries(&sim_config.write_back_mu_overrides),
+    );
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    relevant.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn sorted_entries<V: std::fmt::Debug>(map: &HashMap<u32, V>) -> String {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|&(id, _)| *id);
+    format!("{entries:?}")
+}
+
+/// Loads the `path` history matching `fingerprint`, compares `cycles`/
+/// `duration` against the median of its last `window` records, appends
+/// a new record for this run, and returns the comparison. A missing
+/// `path` is treated as empty history. Returns `Ok(None)` rather than a
+/// report when there's no prior history for this fingerprint yet.
+pub fn check_and_record(
+    path: &Path,
+    commit_hash: &str,
+    fingerprint: &str,
+    cycles: u64,
+    duration: Duration,
+    threshold_pct: f64,
+    window: usize,
+) -> std::io::Result<Option<RegressionReport>> {
+    let history = load_matching(path, fingerprint)?;
+    let duration_ms = duration.as_millis();
+
+    let report = if history.is_empty() {
+        None
+    } else {
+        let recent = &history[history.len().saturating_sub(window)..];
+        let baseline_cycles_median = median(recent.iter().map(|r| r.cycles as f64).collect());
+        let baseline_duration_ms_median =
+            median(recent.iter().map(|r| r.duration_ms as f64).collect());
+        let cycles_pct_change = pct_change(baseline_cycles_median, cycles as f64);
+        let duration_pct_change = pct_change(baseline_duration_ms_median, duration_ms as f64);
+        Some(RegressionReport {
+            is_regression: cycles_pct_change > threshold_pct
+                || duration_pct_change > threshold_pct,
+            baseline_cycles_median,
+            baseline_duration_ms_median,
+            cycles_pct_change,
+            duration_pct_change,
+        })
+    };
+
+    append(
+        path,
+        &MetricsRecord {
+            commit_hash: commit_hash.to_string(),
+            timestamp_unix_secs: now_unix_secs(),
+            config_fingerprint: fingerprint.to_string(),
+            cycles,
+            duration_ms,
+        },
+    )?;
+
+    Ok(report)
+}
+
+fn load_matching(path: &Path, fingerprint: &str) -> std::io::Result<Vec<MetricsRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let records = BufReader::new(File::open(path)?)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<MetricsRecord>(&line).ok())
+        .filter(|record| record.config_fingerprint == fingerprint)
+        .collect();
+    Ok(records)
+}
+
+fn append(path: &Path, record: &MetricsRecord) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let line = serde_json::to_string(record)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    writeln!(file, "{line}")
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+fn pct_change(baseline: f64, new: f64) -> f64 {
+    if baseline == 0.0 {
+        0.0
+    } else {
+        (new - baseline) / baseline * 100.0
+    }
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "step_perf_metrics_history_{name}_{:?}.jsonl",
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn first_run_for_a_fingerprint_has_no_baseline() {
+        let path = history_path("first_run");
+        let report =
+            check_and_record(&path, "abc123", "fp", 1_000, Duration::from_millis(100), 5.0, 10)
+                .unwrap();
+        assert!(report.is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn flags_a_regression_beyond_the_threshold() {
+        let path = history_path("regression");
+        for _ in 0..3 {
+            check_and_record(&path, "abc123", "fp", 1_000, Duration::from_millis(100), 5.0, 10)
+                .unwrap();
+        }
+
+        let report =
+            check_and_record(&path, "def456", "fp", 1_200, Duration::from_millis(100), 5.0, 10)
+                .unwrap()
+                .unwrap();
+
+        assert!(report.is_regression);
+        assert!(report.cycles_pct_change > 5.0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn does_not_flag_changes_within_the_threshold() {
+        let path = history_path("steady");
+        for _ in 0..3 {
+            check_and_record(&path, "abc123", "fp", 1_000, Duration::from_millis(100), 5.0, 10)
+                .unwrap();
+        }
+
+        let report =
+            check_and_record(&path, "def456", "fp", 1_010, Duration::from_millis(100), 5.0, 10)
+                .unwrap()
+                .unwrap();
+
+        assert!(!report.is_regression);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn ignores_records_from_a_different_fingerprint() {
+        let path = history_path("different_fingerprint");
+        check_and_record(&path, "abc123", "other_fp", 10_000, Duration::from_millis(500), 5.0, 10)
+            .unwrap();
+
+        let report = check_and_record(&path, "def456", "fp", 1_000, Duration::from_millis(100), 5.0, 10)
+            .unwrap();
+
+        assert!(report.is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn fingerprint_is_independent_of_hashmap_insertion_order() {
+        let hbm_config = HBMConfig {
+            addr_offset: 64,
+            channel_num: 1,
+            per_channel_latency: 1,
+            per_channel_init_interval: 1,
+            per_channel_outstanding: 1,
+            per_channel_start_up_time: 1,
+            bank_num: 1,
+            row_size_bytes: 64,
+            row_conflict_penalty: 0,
+            address_mapping: crate::ramulator::hbm_context::AddressMapping::Linear,
+        };
+
+        let mut a = base_sim_config();
+        a.config_dict.insert(1, 4);
+        a.config_dict.insert(2, 8);
+
+        let mut b = base_sim_config();
+        b.config_dict.insert(2, 8);
+        b.config_dict.insert(1, 4);
+
+        assert_eq!(fingerprint(&hbm_config, &a), fingerprint(&hbm_config, &b));
+    }
+
+    fn base_sim_config() -> SimConfig {
+        SimConfig {
+            channel_depth: None,
+            functional_sim: true,
+            mock_bf16: false,
+            config_dict: HashMap::new(),
+            validate: false,
+            mock_clock_step_ms: None,
+            par_dispatch_overrides: HashMap::new(),
+            store_max_inflight: 1,
+            trace_channel_ids: std::collections::HashSet::new(),
+            trace_data_file: None,
+            trace_buffer_size: 256,
+            trace_max_file_size: 64 * 1024 * 1024,
+            switch_cycles_overrides: HashMap::new(),
+            write_back_mu_overrides: HashMap::new(),
+            golden_capture_ids: HashMap::new(),
+            golden_compare_ids: HashMap::new(),
+            log_file_path: None,
+            metrics_history_file: None,
+            metrics_commit_hash: None,
+            metrics_regression_threshold_pct: None,
+            metrics_history_window: None,
+            watchdog_timeout_ms: None,
+            html_report_path: None,
+            log_buffer_size: None,
+            log_wall_clock_timestamps: false,
+            verify_store_writes: false,
+            allow_store_overwrite: false,
+            storage_format_overrides: HashMap::new(),
+        }
+    }
+}
@@ -1,8 +1,17 @@
+pub mod config_file;
 pub mod configs;
+pub mod dispatch;
+pub mod layered_config;
+pub mod log_sink;
+pub mod metrics_history;
 pub mod proto_headers;
+pub mod run_report;
+pub mod scenario;
+pub mod schema;
+pub mod validate;
 
 use crate::functions;
-use crate::memory::dyn_offchip_load::DynOffChipLoad;
+use crate::memory::dyn_offchip_load::{ChannelSwizzle, DynOffChipLoad};
 use crate::memory::metadata_gen::MetadataGen;
 use crate::memory::random_offchip_load::RandomOffChipLoad;
 use crate::memory::random_offchip_store::RandomOffChipStore;
@@ -10,7 +19,6 @@ use crate::operator::eager_merge::EagerMerge;
 use crate::operator::expand::ExpandRef;
 use crate::operator::parallelize::Parallelize;
 use std::collections::HashMap;
-use std::time::Instant;
 
 use crate::operator::accum::{Accum, AccumConfig};
 use crate::operator::broadcast::BroadcastContext;
@@ -22,8 +30,8 @@ use crate::operator::map::{UnaryMap, UnaryMapConfig};
 use crate::operator::map_accum::BinaryMapAccum;
 use crate::operator::partition::{FlatPartition, FlatPartitionConfig};
 use crate::operator::promote::Promote;
-use crate::operator::reassemble::{FlatReassemble, FlatReassembleConfig};
-use crate::operator::reshape::Reshape;
+use crate::operator::reassemble::{FlatReassemble, FlatReassembleConfig, MergePolicy};
+use crate::operator::reshape::{PadMode, Reshape};
 use crate::operator::streamify::Streamify;
 use crate::proto_driver::proto_headers::graph_proto::map_accum_func;
 use crate::utils::select_npy::read_multihot_elem_from_npy_iter;
@@ -35,27 +43,40 @@ use dam::utility_contexts::{ConsumerContext, GeneratorContext, PrinterContext};
 use std::sync::Arc;
 use std::usize;
 
-use crate::build_sim::channel::ChannelMapCollection;
+use crate::build_sim::channel::{CapacityPolicy, ChannelMapCollection};
+use crate::build_sim::golden::{GoldenContext, GoldenMode};
+use crate::build_sim::trace::{TraceHandle, TraceSink};
+use crate::build_sim::occupancy::OccupancyLog;
+use crate::build_sim::watchdog::ActivityLog;
 use crate::memory::offchip_load::OffChipLoad;
 use crate::memory::offchip_store::OffChipStore;
-use crate::operator::{map::BinaryMap, repeat::RepeatStatic};
+use crate::operator::{map::BinaryMap, map::BinaryMapConfig, repeat::RepeatStatic};
+use crate::primitives::buffer::Buffer;
+use crate::primitives::dtype::DType;
+use crate::primitives::elem::{Bufferizable, Elem, StopType};
+use crate::primitives::select::MultiHotN;
 use crate::primitives::tile::Tile;
 use crate::proto_driver::configs::SimConfig;
+use crate::proto_driver::run_report::RunReport;
 use crate::proto_driver::proto_headers::graph_proto::{
     accum_func, buffer, data_type::Type, elemto_elem_func, init_func, operation::OpType,
     ProgramGraph,
 };
 use crate::ramulator::hbm_context::{HBMConfig, HBMContext, ReadBundle, WriteBundle};
 use crate::utils::{
+    benchmark::{BenchmarkOpts, BenchmarkReport, BenchmarkRunner},
     cast::{to_u64_vec, to_usize_vec},
+    error::StepPerfError,
     events::SimpleEvent,
 };
+use dam::channel::{Receiver, Sender};
+use dam::types::DAMType;
 
 // channel_depth will be set from sim_config.channel_depth
 
 macro_rules! make_broadcast {
     ($collection:expr, $operation: expr, $broadcast: expr, $type:ident, $builder:expr, $channel_depth:expr) => {
-        let rcv = $collection.$type.get_receiver(
+        let rcv = $collection.$type().get_receiver(
             $broadcast.input_id,
             $broadcast.stream_idx,
             $builder,
@@ -63,7 +84,7 @@ macro_rules! make_broadcast {
         );
         let mut broadcast_node = BroadcastContext::new(rcv);
         for stream_idx in 0..$broadcast.num_consumers {
-            let snd = $collection.$type.get_sender(
+            let snd = $collection.$type().get_sender(
                 $operation.id,
                 Some(stream_idx),
                 $builder,
@@ -78,7 +99,8 @@ macro_rules! make_broadcast {
 
 macro_rules! make_dyn_offchip_load {
     ($collection:expr, $operation: expr, $dyn_offchip_load: expr,$hbm_config: expr,
-     $type_ref:ident, $type:ident, $n_bytes: expr,$mem_context: expr, $builder:expr, $channel_depth:expr) => {
+     $type_ref:ident, $type:ident, $n_bytes: expr,$mem_context: expr, $builder:expr, $channel_depth:expr,
+     $par_dispatch_overrides:expr, $storage_format_overrides:expr) => {
         let ref_rcv = $collection.$type_ref.get_receiver(
             $dyn_offchip_load.ref_id,
             $dyn_offchip_load.ref_stream_idx,
@@ -94,16 +116,39 @@ macro_rules! make_dyn_offchip_load {
         let (resp_snd, resp_rcv) = $builder.unbounded();
 
         $builder.add_child(DynOffChipLoad::<SimpleEvent, _, _>::new(
-            to_usize_vec($dyn_offchip_load.tensor_shape_tiled),
-            to_usize_vec($dyn_offchip_load.stride),
-            to_usize_vec($dyn_offchip_load.out_shape_tiled),
+            to_usize_vec($dyn_offchip_load.tensor_shape_tiled).unwrap(),
+            to_usize_vec($dyn_offchip_load.stride).unwrap(),
+            to_usize_vec($dyn_offchip_load.out_shape_tiled).unwrap(),
             $dyn_offchip_load.npy_path,
             $dyn_offchip_load.tile_row as usize,
             $dyn_offchip_load.tile_col as usize,
             $n_bytes,
+            // `dyn_offchip_load` carries no `storage_format` field of its
+            // own -- same situation `par_dispatch_overrides` works around
+            // for `par_dispatch` -- so it comes from `sim_config` instead.
+            get_storage_format($storage_format_overrides, $operation.id),
+            // `dyn_offchip_load` carries no `transposed`/sparsity fields
+            // yet -- those need a new `graph.proto` schema `build.rs`
+            // compiles from, which isn't present in this tree -- so every
+            // load stays row-major and fully dense until those fields
+            // exist.
+            false,
+            None,
+            Some(ChannelSwizzle {
+                mapping: $hbm_config.address_mapping,
+                channel_num: $hbm_config.channel_num,
+            }),
             0,
             $hbm_config.addr_offset,
-            $dyn_offchip_load.par_dispatch as usize,
+            get_par_dispatch(
+                $par_dispatch_overrides,
+                $operation.id,
+                $dyn_offchip_load.par_dispatch as usize,
+            ),
+            // `dyn_offchip_load` carries no `prefetch_depth` field either,
+            // so loads stay fully serialized (no prefetch) until
+            // `graph.proto` grows one.
+            1,
             ref_rcv,
             addr_snd,
             resp_rcv,
@@ -117,32 +162,390 @@ macro_rules! make_dyn_offchip_load {
     };
 }
 
+/// An operation [`build_from_proto`] couldn't lower -- an `OpType`/dtype
+/// combination it has no construction logic for. Carries enough to locate
+/// and describe the offending node without aborting the rest of the build;
+/// see [`crate::utils::error::StepPerfError::UnsupportedOps`].
+#[derive(Debug, Clone)]
+pub struct BuildError {
+    pub operation_id: u32,
+    pub op_type: String,
+    pub unsupported: String,
+}
+
+/// The `OpType`/`Type` discriminant name, e.g. `"Unarymap"` or `"F32"`, read
+/// off a prost enum's derived `Debug` output (`"Variant(inner)"` for a
+/// tuple/struct variant, `"Variant"` for a unit one) so [`BuildError`]
+/// doesn't need a hand-written name table kept in sync with every variant.
+fn discriminant_name(value: &impl std::fmt::Debug) -> String {
+    format!("{value:?}")
+        .split(['(', ' '])
+        .next()
+        .unwrap_or("?")
+        .to_string()
+}
+
 fn get_chan_depth(
     custom_depth_chan: &HashMap<u32, usize>,
     id: u32,
-    base_depth: Option<usize>,
-) -> Option<usize> {
+    base_depth: Option<CapacityPolicy>,
+) -> Option<CapacityPolicy> {
     if custom_depth_chan.contains_key(&id) {
-        Some(custom_depth_chan[&id])
+        Some(CapacityPolicy::Elements(custom_depth_chan[&id]))
     } else {
         base_depth
     }
 }
 
+/// Mirrors [`get_chan_depth`] for `par_dispatch`: an id present in
+/// `sim_config.par_dispatch_overrides` (e.g. from a loaded
+/// [`config_file::EffectiveConfig`]) wins over the proto-supplied default,
+/// letting a user sweep dispatch width without re-exporting the graph.
+fn get_par_dispatch(
+    par_dispatch_overrides: &HashMap<u32, usize>,
+    id: u32,
+    base_par_dispatch: usize,
+) -> usize {
+    par_dispatch_overrides
+        .get(&id)
+        .copied()
+        .unwrap_or(base_par_dispatch)
+}
+
+/// Mirrors [`get_par_dispatch`] for [`crate::memory::dyn_offchip_load::DynOffChipLoad`]'s
+/// `storage_format`: `dyn_offchip_load`'s proto message has no field for
+/// this, so an id absent from `storage_format_overrides` gets `None` (no
+/// declared format, same as before this override existed) rather than a
+/// proto-supplied default.
+fn get_storage_format(
+    storage_format_overrides: &HashMap<u32, DType>,
+    id: u32,
+) -> Option<DType> {
+    storage_format_overrides.get(&id).copied()
+}
+
+/// Mirrors [`get_chan_depth`] for `FlatPartition`/`FlatReassemble`/
+/// `Parallelize`'s `switch_cycles`: an id present in
+/// `sim_config.switch_cycles_overrides` replaces every element of the
+/// proto-supplied vector uniformly, letting a user sweep switch/arbitration
+/// cost without re-exporting the graph.
+fn get_switch_cycles(
+    switch_cycles_overrides: &HashMap<u32, u64>,
+    id: u32,
+    base_switch_cycles: Vec<u64>,
+) -> Vec<u64> {
+    match switch_cycles_overrides.get(&id) {
+        Some(&override_cycles) => vec![override_cycles; base_switch_cycles.len()],
+        None => base_switch_cycles,
+    }
+}
+
+/// Mirrors [`get_chan_depth`] for the same ops' `write_back_mu`.
+fn get_write_back_mu(
+    write_back_mu_overrides: &HashMap<u32, bool>,
+    id: u32,
+    base_write_back_mu: bool,
+) -> bool {
+    write_back_mu_overrides
+        .get(&id)
+        .copied()
+        .unwrap_or(base_write_back_mu)
+}
+
+/// Looks up whether `ConsumerContext`'s `input_id` is selected for
+/// golden-vector capture or compare in `sim_config`, answering "what should
+/// this sink do" rather than [`get_chan_depth`]'s "what's this channel's
+/// depth". An id present in both `golden_capture_ids` and
+/// `golden_compare_ids` is captured, not compared.
+fn get_golden_mode(sim_config: &SimConfig, input_id: u32) -> Option<GoldenMode> {
+    if let Some(path) = sim_config.golden_capture_ids.get(&input_id) {
+        Some(GoldenMode::Capture(path.clone()))
+    } else {
+        sim_config
+            .golden_compare_ids
+            .get(&input_id)
+            .map(|path| GoldenMode::Compare(path.clone()))
+    }
+}
+
+/// One `channel_map_collection` field (`tile_f32`, `tile_u64`, `multihot`,
+/// `buff_tile_f32`, ...), named generically so a single-dtype op's
+/// construction path can be written once and driven by a runtime proto
+/// `Type` match, instead of copy-pasting the same `get_receiver`/
+/// `get_sender` block per dtype arm (see [`build_promote`], [`build_flatten`],
+/// [`build_bufferize`], [`build_streamify`]). A new dtype plugs in by adding
+/// a `typed_channel_set!` line, not another hand-written match arm.
+trait TypedChannelSet<'a> {
+    type Elem: DAMType;
+
+    fn receiver(
+        collection: &mut ChannelMapCollection<'a>,
+        id: u32,
+        idx: Option<u32>,
+        builder: &mut ProgramBuilder<'a>,
+        capacity: Option<CapacityPolicy>,
+    ) -> Receiver<Elem<Self::Elem>>;
+
+    fn sender(
+        collection: &mut ChannelMapCollection<'a>,
+        id: u32,
+        idx: Option<u32>,
+        builder: &mut ProgramBuilder<'a>,
+        capacity: Option<CapacityPolicy>,
+    ) -> Sender<Elem<Self::Elem>>;
+}
+
+macro_rules! typed_channel_set {
+    ($marker:ident, $elem:ty, $field:ident) => {
+        struct $marker;
+        impl<'a> TypedChannelSet<'a> for $marker {
+            type Elem = $elem;
+
+            fn receiver(
+                collection: &mut ChannelMapCollection<'a>,
+                id: u32,
+                idx: Option<u32>,
+                builder: &mut ProgramBuilder<'a>,
+                capacity: Option<CapacityPolicy>,
+            ) -> Receiver<Elem<Self::Elem>> {
+                collection.$field().get_receiver(id, idx, builder, capacity)
+            }
+
+            fn sender(
+                collection: &mut ChannelMapCollection<'a>,
+                id: u32,
+                idx: Option<u32>,
+                builder: &mut ProgramBuilder<'a>,
+                capacity: Option<CapacityPolicy>,
+            ) -> Sender<Elem<Self::Elem>> {
+                collection.$field().get_sender(id, idx, builder, capacity)
+            }
+        }
+    };
+}
+
+typed_channel_set!(TileF32Chan, Tile<f32>, tile_f32);
+typed_channel_set!(TileU64Chan, Tile<u64>, tile_u64);
+typed_channel_set!(MultiHotChan, MultiHotN, multihot);
+typed_channel_set!(BuffTileF32Chan, Buffer<Tile<f32>>, buff_tile_f32);
+typed_channel_set!(BuffTileU64Chan, Buffer<Tile<u64>>, buff_tile_u64);
+typed_channel_set!(BuffMultiHotChan, Buffer<MultiHotN>, buff_multihot);
+
+/// Shared by every `OpType` arm below whose construction is "one input
+/// channel in, one of the same dtype out": looks up the per-id channel
+/// depth the same way every hand-written arm already did, then wires
+/// `op_new` between the two.
+fn build_single_in_out<'a, C: TypedChannelSet<'a>, Op>(
+    collection: &mut ChannelMapCollection<'a>,
+    builder: &mut ProgramBuilder<'a>,
+    sim_config: &SimConfig,
+    channel_depth: Option<CapacityPolicy>,
+    operation_id: u32,
+    input_id: u32,
+    stream_idx: Option<u32>,
+    op_new: impl FnOnce(Receiver<Elem<C::Elem>>, Sender<Elem<C::Elem>>) -> Op,
+) -> Op {
+    let rcv = C::receiver(
+        collection,
+        input_id,
+        stream_idx,
+        builder,
+        get_chan_depth(&sim_config.config_dict, input_id, channel_depth),
+    );
+    let snd = C::sender(
+        collection,
+        operation_id,
+        None,
+        builder,
+        get_chan_depth(&sim_config.config_dict, operation_id, channel_depth),
+    );
+    op_new(rcv, snd)
+}
+
+/// [`Promote`] construction generic over the channel dtype, driven by a
+/// proto `Type` match in `build_from_proto`.
+fn build_promote<'a, C: TypedChannelSet<'a>>(
+    collection: &mut ChannelMapCollection<'a>,
+    builder: &mut ProgramBuilder<'a>,
+    sim_config: &SimConfig,
+    channel_depth: Option<CapacityPolicy>,
+    operation_id: u32,
+    input_id: u32,
+    stream_idx: Option<u32>,
+    promote_rank: StopType,
+) -> Promote<C::Elem> {
+    build_single_in_out::<C, _>(
+        collection,
+        builder,
+        sim_config,
+        channel_depth,
+        operation_id,
+        input_id,
+        stream_idx,
+        |rcv, snd| Promote::new(rcv, snd, promote_rank),
+    )
+}
+
+/// [`Flatten`] construction generic over the channel dtype.
+fn build_flatten<'a, C: TypedChannelSet<'a>>(
+    collection: &mut ChannelMapCollection<'a>,
+    builder: &mut ProgramBuilder<'a>,
+    sim_config: &SimConfig,
+    channel_depth: Option<CapacityPolicy>,
+    operation_id: u32,
+    input_id: u32,
+    stream_idx: Option<u32>,
+    min_rank: StopType,
+    max_rank: StopType,
+) -> Flatten<C::Elem> {
+    build_single_in_out::<C, _>(
+        collection,
+        builder,
+        sim_config,
+        channel_depth,
+        operation_id,
+        input_id,
+        stream_idx,
+        |rcv, snd| Flatten::new(rcv, snd, min_rank, max_rank),
+    )
+}
+
+/// [`Bufferize`] construction generic over the channel dtype: `In` carries
+/// the unbuffered element, `Out` its `Buffer<In::Elem>` counterpart (e.g.
+/// `TileF32Chan` paired with `BuffTileF32Chan`).
+fn build_bufferize<'a, In, Out>(
+    collection: &mut ChannelMapCollection<'a>,
+    builder: &mut ProgramBuilder<'a>,
+    sim_config: &SimConfig,
+    channel_depth: Option<CapacityPolicy>,
+    operation_id: u32,
+    input_id: u32,
+    stream_idx: Option<u32>,
+    rank: StopType,
+) -> Bufferize<SimpleEvent, In::Elem>
+where
+    In: TypedChannelSet<'a>,
+    In::Elem: Clone + Bufferizable,
+    Out: TypedChannelSet<'a, Elem = Buffer<In::Elem>>,
+{
+    let rcv = In::receiver(
+        collection,
+        input_id,
+        stream_idx,
+        builder,
+        get_chan_depth(&sim_config.config_dict, input_id, channel_depth),
+    );
+    let snd = Out::sender(
+        collection,
+        operation_id,
+        None,
+        builder,
+        get_chan_depth(&sim_config.config_dict, operation_id, channel_depth),
+    );
+    Bufferize::<SimpleEvent, _>::new(rcv, snd, rank, operation_id)
+}
+
+/// [`Streamify`] construction generic over the channel dtype: `In` carries
+/// the `Buffer<Out::Elem>` element (e.g. `BuffTileF32Chan`), `Out` the
+/// unbuffered counterpart it emits (e.g. `TileF32Chan`) -- the mirror image
+/// of [`build_bufferize`].
+fn build_streamify<'a, In, Out>(
+    collection: &mut ChannelMapCollection<'a>,
+    builder: &mut ProgramBuilder<'a>,
+    sim_config: &SimConfig,
+    channel_depth: Option<CapacityPolicy>,
+    operation_id: u32,
+    input_id: u32,
+    stream_idx: Option<u32>,
+    repeat_factor: Vec<usize>,
+    rank: StopType,
+) -> Streamify<SimpleEvent, Out::Elem>
+where
+    Out: TypedChannelSet<'a>,
+    Out::Elem: Bufferizable + Clone,
+    In: TypedChannelSet<'a, Elem = Buffer<Out::Elem>>,
+{
+    let rcv = In::receiver(
+        collection,
+        input_id,
+        stream_idx,
+        builder,
+        get_chan_depth(&sim_config.config_dict, input_id, channel_depth),
+    );
+    let snd = Out::sender(
+        collection,
+        operation_id,
+        None,
+        builder,
+        get_chan_depth(&sim_config.config_dict, operation_id, channel_depth),
+    );
+    Streamify::<SimpleEvent, _>::new(repeat_factor, rank, rcv, snd, operation_id)
+}
+
 fn build_from_proto<'a>(
     step_graph: ProgramGraph,
     channel_map_collection: &mut ChannelMapCollection<'a>,
     builder: &mut ProgramBuilder<'a>,
     hbm_config: &HBMConfig,
     sim_config: &SimConfig,
-) {
-    let channel_depth = sim_config.channel_depth;
+    activity_log: &ActivityLog,
+    occupancy_log: &OccupancyLog,
+) -> Vec<BuildError> {
+    let channel_depth = sim_config.channel_depth.map(CapacityPolicy::Elements);
     let mut mem_context = HBMContext::new(builder, hbm_config.clone());
+    let mut errors: Vec<BuildError> = Vec::new();
+
+    // Wire up per-channel tracing (see `crate::build_sim::trace`) before any
+    // `get_receiver`/`get_sender` call below so every traced channel, no
+    // matter which operation creates it first, gets a `TraceTap` spliced
+    // in. Left disabled (the default `TraceHandle`) when no data file is
+    // configured, so an untraced run never opens a file.
+    if let Some(data_file) = &sim_config.trace_data_file {
+        match TraceSink::open(
+            data_file,
+            sim_config.trace_buffer_size,
+            sim_config.trace_max_file_size,
+        ) {
+            Ok(sink) => channel_map_collection.configure_trace(TraceHandle::new(
+                Arc::new(sim_config.trace_channel_ids.clone()),
+                sink,
+            )),
+            Err(e) => {
+                println!("failed to open trace data file {data_file}: {e}");
+            }
+        }
+    }
+
+    // Likewise for the wall-clock watchdog (see `crate::build_sim::watchdog`
+    // and `crate::utils::watchdog`): every channel gets a `WatchdogTap`
+    // spliced in when a budget is configured, so a deadlock can be
+    // localized no matter which operation's channel it blocks on.
+    if sim_config.watchdog_timeout_ms.is_some() {
+        channel_map_collection.configure_watchdog(activity_log.clone());
+    }
+
+    // Likewise for the HTML run report (see `crate::build_sim::occupancy`
+    // and `crate::utils::html_report`): every channel gets an
+    // `OccupancyTap` spliced in when a report path is configured, so the
+    // report's table/timeline covers every channel in the graph.
+    if sim_config.html_report_path.is_some() {
+        channel_map_collection.configure_occupancy(occupancy_log.clone());
+    }
 
     // Use a regular variable instead of a const, since sim_config.mock_bf16 is not a constant
+    //
+    // This only fakes mixed precision at the byte-accounting level: every
+    // tile here is still `Tile<f32>`, so compute ops see full fp32 values
+    // wearing a bf16 byte count. `dtype::quantize_bf16` now exists for
+    // callers that need the numerics (not just the bandwidth) to reflect
+    // bf16 rounding, but wiring a first-class `Type::Bf16` dtype through
+    // here -- a `ChannelMapCollection::tile_bf16` family and `(Bf16, ...)`
+    // dispatch arms -- needs a `Type::Bf16` variant in the `datatype.proto`
+    // schema `build.rs` compiles, which isn't present in this tree.
     let f32_bytes: usize = if sim_config.mock_bf16 { 2 } else { 4 }; // we will use this to mimic bfloat16
+    let u64_bytes: usize = std::mem::size_of::<u64>();
 
-    for operation in step_graph.operators {
+    'operations: for operation in step_graph.operators {
         // if operation.id == 336 || operation.id == 272 || operation.id == 721 {
         //     println!("processing {:?}\n", operation);
         // }
@@ -154,40 +557,22 @@ fn build_from_proto<'a>(
                 unarymap.dtype_b.clone().unwrap().r#type.clone().unwrap(),
             ) {
                 (Type::F32(_), Type::F32(_)) => {
-                    let rcv = channel_map_collection.tile_f32.get_receiver(
+                    let rcv = channel_map_collection.tile_f32().get_receiver(
                         unarymap.input_id,
                         unarymap.stream_idx,
                         builder,
                         get_chan_depth(&sim_config.config_dict, unarymap.input_id, channel_depth),
                     );
-                    let snd = channel_map_collection.tile_f32.get_sender(
+                    let snd = channel_map_collection.tile_f32().get_sender(
                         operation.id,
                         None,
                         builder,
                         get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
                     );
-                    let map_fn: Arc<
-                        dyn Fn(&Tile<f32>, u64, bool) -> (u64, Tile<f32>) + Send + Sync,
-                    > = match unarymap.func.unwrap().elem_elem_fn.unwrap() {
-                        elemto_elem_func::ElemElemFn::Silu(silu) => {
-                            Arc::new(move |tile, comp_bw, write_back_mu| {
-                                functions::map_fn::silu(tile, comp_bw, write_back_mu)
-                            })
-                        }
-                        elemto_elem_func::ElemElemFn::Exp(exp) => {
-                            Arc::new(move |tile, comp_bw, write_back_mu| {
-                                functions::map_fn::exp(tile, comp_bw, write_back_mu)
-                            })
-                        }
-                        elemto_elem_func::ElemElemFn::RowWiseSum(row_wise_sum) => {
-                            Arc::new(move |tile, comp_bw, write_back_mu| {
-                                functions::map_fn::row_wise_sum(tile, comp_bw, write_back_mu)
-                            })
-                        }
-                        _ => {
-                            panic!("Unsupported unary map function type")
-                        }
-                    };
+                    // Generated from `operators.in`'s `unary_map|1|F32|F32|...`
+                    // rows -- see `proto_driver::dispatch`.
+                    let map_fn =
+                        dispatch::dispatch_unary_map_f32_f32(unarymap.func.unwrap().elem_elem_fn.unwrap());
 
                     builder.add_child(UnaryMap::<SimpleEvent, _, _>::new(
                         rcv,
@@ -196,11 +581,26 @@ fn build_from_proto<'a>(
                         UnaryMapConfig {
                             compute_bw: unarymap.compute_bw as u64,
                             write_back_mu: unarymap.write_back_mu,
+                            bandwidth: Default::default(),
+                            memory_unit_id: operation.id,
+                            energy: Default::default(),
+                            overlap_model: Default::default(),
                         },
                         operation.id,
                     ));
                 }
-                (_, _) => panic!("Unsupported data types for UnaryMap operation yet"),
+                (dtype_a, dtype_b) => {
+                    errors.push(BuildError {
+                        operation_id: operation.id,
+                        op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                        unsupported: format!(
+                            "unsupported dtype combination for UnaryMap operation, got ({}, {})",
+                            discriminant_name(&dtype_a),
+                            discriminant_name(&dtype_b),
+                        ),
+                    });
+                    continue 'operations;
+                }
             },
             OpType::Binarymap(binary_map) => match (
                 binary_map.dtype_a.clone().unwrap().r#type.clone().unwrap(),
@@ -215,7 +615,7 @@ fn build_from_proto<'a>(
             ) {
                 (Type::F32(_), Type::F32(_), Type::F32(_)) => {
                     // create
-                    let rcv1 = channel_map_collection.tile_f32.get_receiver(
+                    let rcv1 = channel_map_collection.tile_f32().get_receiver(
                         binary_map.input_id1,
                         binary_map.stream_idx1,
                         builder,
@@ -225,7 +625,7 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let rcv2 = channel_map_collection.tile_f32.get_receiver(
+                    let rcv2 = channel_map_collection.tile_f32().get_receiver(
                         binary_map.input_id2,
                         binary_map.stream_idx2,
                         builder,
@@ -235,71 +635,36 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let snd = channel_map_collection.tile_f32.get_sender(
+                    let snd = channel_map_collection.tile_f32().get_sender(
                         operation.id,
                         None,
                         builder,
                         get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
                     );
-                    let map_fn: Arc<
-                        dyn Fn(&Tile<f32>, &Tile<f32>, u64, bool) -> (u64, Tile<f32>) + Send + Sync,
-                    > = match binary_map.func.unwrap().elem_elem_fn.unwrap() {
-                        elemto_elem_func::ElemElemFn::Matmul(matmul) => {
-                            let weight_transposed = matmul.weight_transposed;
-                            Arc::new(move |tile1, tile2, comp_bw, write_back_mu| {
-                                functions::map_fn::matmul(
-                                    tile1,
-                                    tile2,
-                                    comp_bw,
-                                    write_back_mu,
-                                    weight_transposed,
-                                )
-                            })
-                        }
-                        elemto_elem_func::ElemElemFn::DynMatmul(matmul) => {
-                            let weight_transposed = matmul.weight_transposed;
-                            Arc::new(move |tile1, tile2, comp_bw, write_back_mu| {
-                                functions::map_fn::matmul(
-                                    tile1,
-                                    tile2,
-                                    comp_bw,
-                                    write_back_mu,
-                                    weight_transposed,
-                                )
-                            })
-                        }
-                        elemto_elem_func::ElemElemFn::Mul(_) => {
-                            Arc::new(move |tile1, tile2, comp_bw, write_back_mu| {
-                                functions::map_fn::mul(tile1, tile2, comp_bw, write_back_mu)
-                            })
-                        }
-                        elemto_elem_func::ElemElemFn::RowWiseAppend(row_wise_append) => {
-                            Arc::new(move |tile1, tile2, comp_bw, write_back_mu| {
-                                functions::map_fn::row_wise_append(tile1, tile2, write_back_mu)
-                            })
-                        }
-                        elemto_elem_func::ElemElemFn::Div(_) => {
-                            Arc::new(move |tile1, tile2, comp_bw, write_back_mu| {
-                                functions::map_fn::div(tile1, tile2, comp_bw, write_back_mu)
-                            })
-                        }
-                        _ => {
-                            panic!("Unsupported binary map function type")
-                        }
-                    };
+                    // Generated from `operators.in`'s `binary_map|2|F32|F32|F32|...`
+                    // rows -- see `proto_driver::dispatch`.
+                    let map_fn = dispatch::dispatch_binary_map_f32_f32_f32(
+                        binary_map.func.unwrap().elem_elem_fn.unwrap(),
+                    );
                     builder.add_child(BinaryMap::<SimpleEvent, _, _, _>::new(
                         rcv1,
                         rcv2,
                         snd,
                         map_fn,
-                        binary_map.compute_bw as u64,
-                        binary_map.write_back_mu,
+                        BinaryMapConfig {
+                            compute_bw: binary_map.compute_bw as u64,
+                            write_back_mu: binary_map.write_back_mu,
+                            bandwidth: Default::default(),
+                            memory_unit_id: operation.id,
+                            energy: Default::default(),
+                            overlap_model: Default::default(),
+                        },
                         operation.id,
                     ));
                 }
                 (Type::U64(_), Type::U64(_), Type::U64(_)) => {
                     // create
-                    let rcv1 = channel_map_collection.tile_u64.get_receiver(
+                    let rcv1 = channel_map_collection.tile_u64().get_receiver(
                         binary_map.input_id1,
                         binary_map.stream_idx1,
                         builder,
@@ -309,7 +674,7 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let rcv2 = channel_map_collection.tile_u64.get_receiver(
+                    let rcv2 = channel_map_collection.tile_u64().get_receiver(
                         binary_map.input_id2,
                         binary_map.stream_idx2,
                         builder,
@@ -319,7 +684,7 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let snd = channel_map_collection.tile_u64.get_sender(
+                    let snd = channel_map_collection.tile_u64().get_sender(
                         operation.id,
                         None,
                         builder,
@@ -339,8 +704,13 @@ fn build_from_proto<'a>(
                                 )
                             })
                         }
-                        _ => {
-                            panic!("Unsupported binary map function type")
+                        other => {
+                            errors.push(BuildError {
+                                operation_id: operation.id,
+                                op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                unsupported: format!("unsupported binary map function for U64 BinaryMap operation, got {}", discriminant_name(&other)),
+                            });
+                            continue 'operations;
                         }
                     };
                     builder.add_child(BinaryMap::<SimpleEvent, _, _, _>::new(
@@ -348,14 +718,20 @@ fn build_from_proto<'a>(
                         rcv2,
                         snd,
                         map_fn,
-                        binary_map.compute_bw as u64,
-                        binary_map.write_back_mu,
+                        BinaryMapConfig {
+                            compute_bw: binary_map.compute_bw as u64,
+                            write_back_mu: binary_map.write_back_mu,
+                            bandwidth: Default::default(),
+                            memory_unit_id: operation.id,
+                            energy: Default::default(),
+                            overlap_model: Default::default(),
+                        },
                         operation.id,
                     ));
                 }
                 (Type::F32(_), Type::U64(_), Type::F32(_)) => {
                     // create
-                    let rcv1 = channel_map_collection.tile_f32.get_receiver(
+                    let rcv1 = channel_map_collection.tile_f32().get_receiver(
                         binary_map.input_id1,
                         binary_map.stream_idx1,
                         builder,
@@ -365,7 +741,7 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let rcv2 = channel_map_collection.tile_u64.get_receiver(
+                    let rcv2 = channel_map_collection.tile_u64().get_receiver(
                         binary_map.input_id2,
                         binary_map.stream_idx2,
                         builder,
@@ -375,7 +751,7 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let snd = channel_map_collection.tile_f32.get_sender(
+                    let snd = channel_map_collection.tile_f32().get_sender(
                         operation.id,
                         None,
                         builder,
@@ -389,8 +765,13 @@ fn build_from_proto<'a>(
                                 functions::map_fn::set_offset(tile1, tile2, write_back_mu)
                             })
                         }
-                        _ => {
-                            panic!("Unsupported binary map function type")
+                        other => {
+                            errors.push(BuildError {
+                                operation_id: operation.id,
+                                op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                unsupported: format!("unsupported binary map function for F32/U64 BinaryMap operation, got {}", discriminant_name(&other)),
+                            });
+                            continue 'operations;
                         }
                     };
                     builder.add_child(BinaryMap::<SimpleEvent, _, _, _>::new(
@@ -398,12 +779,30 @@ fn build_from_proto<'a>(
                         rcv2,
                         snd,
                         map_fn,
-                        binary_map.compute_bw as u64,
-                        binary_map.write_back_mu,
+                        BinaryMapConfig {
+                            compute_bw: binary_map.compute_bw as u64,
+                            write_back_mu: binary_map.write_back_mu,
+                            bandwidth: Default::default(),
+                            memory_unit_id: operation.id,
+                            energy: Default::default(),
+                            overlap_model: Default::default(),
+                        },
                         operation.id,
                     ));
                 }
-                _ => panic!("Unsupported data types for BinaryMap operation"),
+                other => {
+                    errors.push(BuildError {
+                        operation_id: operation.id,
+                        op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                        unsupported: format!(
+                            "unsupported dtype combination for BinaryMap operation, got ({}, {}, {})",
+                            discriminant_name(&other.0),
+                            discriminant_name(&other.1),
+                            discriminant_name(&other.2),
+                        ),
+                    });
+                    continue 'operations;
+                }
             },
             OpType::BinarymapAccum(binary_map_accum) => match (
                 binary_map_accum
@@ -423,7 +822,7 @@ fn build_from_proto<'a>(
             ) {
                 (Type::F32(_), Type::F32(_)) => {
                     // create
-                    let in1_stream = channel_map_collection.tile_f32.get_receiver(
+                    let in1_stream = channel_map_collection.tile_f32().get_receiver(
                         binary_map_accum.input_id1,
                         binary_map_accum.stream_idx1,
                         builder,
@@ -433,7 +832,7 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let in2_stream = channel_map_collection.tile_f32.get_receiver(
+                    let in2_stream = channel_map_collection.tile_f32().get_receiver(
                         binary_map_accum.input_id2,
                         binary_map_accum.stream_idx2,
                         builder,
@@ -443,7 +842,7 @@ fn build_from_proto<'a>(
                             channel_depth,
                         ),
                     );
-                    let out_stream = channel_map_collection.tile_f32.get_sender(
+                    let out_stream = channel_map_collection.tile_f32().get_sender(
                         operation.id,
                         None,
                         builder,
@@ -454,17 +853,36 @@ fn build_from_proto<'a>(
                             + Send
                             + Sync,
                     > = match binary_map_accum.func.unwrap().map_accum_fn.unwrap() {
+                        // `tile1`'s rank is only known once tiles are actually streamed,
+                        // so the batch dimensions don't need a dedicated proto field: a
+                        // `[..batch_dims, M, K]` tile of any rank above 2 (e.g. a single
+                        // `[batch, M, K]` dim or several, like `[experts, batch, M, K]`)
+                        // selects `functions::map_accum_fn::batched_matmul`'s per-batch
+                        // GEMM loop (which folds all leading dims into one flattened
+                        // batch count), and a plain rank-2 `[M, K]` tile keeps using
+                        // `matmul`.
                         map_accum_func::MapAccumFn::Matmul(matmul) => {
                             let weight_transposed = matmul.weight_transposed;
                             Arc::new(move |tile1, tile2, accumulator, comp_bw, write_back_mu| {
-                                functions::map_accum_fn::matmul(
-                                    tile1,
-                                    tile2,
-                                    accumulator,
-                                    comp_bw,
-                                    write_back_mu,
-                                    weight_transposed,
-                                )
+                                if tile1.shape.len() > 2 {
+                                    functions::map_accum_fn::batched_matmul(
+                                        tile1,
+                                        tile2,
+                                        accumulator,
+                                        comp_bw,
+                                        write_back_mu,
+                                        weight_transposed,
+                                    )
+                                } else {
+                                    functions::map_accum_fn::matmul(
+                                        tile1,
+                                        tile2,
+                                        accumulator,
+                                        comp_bw,
+                                        write_back_mu,
+                                        weight_transposed,
+                                    )
+                                }
                             })
                         }
                         map_accum_func::MapAccumFn::DynMatmul(matmul) => {
@@ -480,8 +898,13 @@ fn build_from_proto<'a>(
                                 )
                             })
                         }
-                        _ => {
-                            panic!("Unsupported binary map accumulation function type",)
+                        other => {
+                            errors.push(BuildError {
+                                operation_id: operation.id,
+                                op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                unsupported: format!("unsupported accumulation function for BinaryMapAccum operation, got {}", discriminant_name(&other)),
+                            });
+                            continue 'operations;
                         }
                     };
 
@@ -506,12 +929,23 @@ fn build_from_proto<'a>(
                         operation.id,
                     ));
                 }
-                (_, _) => todo!(),
+                (dtype_a, dtype_b) => {
+                    errors.push(BuildError {
+                        operation_id: operation.id,
+                        op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                        unsupported: format!(
+                            "unsupported dtype combination for BinaryMapAccum operation, got ({}, {})",
+                            discriminant_name(&dtype_a),
+                            discriminant_name(&dtype_b),
+                        ),
+                    });
+                    continue 'operations;
+                }
             },
             OpType::OffChipLoad(off_chip_load) => {
                 match off_chip_load.dtype.clone().unwrap().r#type.clone().unwrap() {
                     Type::F32(_) => {
-                        let on_chip_snd = channel_map_collection.tile_f32.get_sender(
+                        let on_chip_snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -521,16 +955,35 @@ fn build_from_proto<'a>(
                         let (resp_snd, resp_rcv) = builder.unbounded();
 
                         builder.add_child(OffChipLoad::<SimpleEvent, _>::new(
-                            to_usize_vec(off_chip_load.tensor_shape_tiled),
-                            to_usize_vec(off_chip_load.stride),
-                            to_usize_vec(off_chip_load.out_shape_tiled),
+                            to_usize_vec(off_chip_load.tensor_shape_tiled).unwrap(),
+                            to_usize_vec(off_chip_load.stride).unwrap(),
+                            to_usize_vec(off_chip_load.out_shape_tiled).unwrap(),
                             off_chip_load.npy_path,
                             off_chip_load.tile_row as usize,
                             off_chip_load.tile_col as usize,
                             f32_bytes,
+                            // `off_chip_load` carries no `transposed` field
+                            // yet -- needs a new `graph.proto` schema
+                            // `build.rs` compiles from, which isn't present
+                            // in this tree -- so every load stays row-major
+                            // until that field exists.
+                            false,
+                            // Same for an im2col window selector: not
+                            // modeled in `graph.proto` yet, so every load
+                            // stays a plain disjoint tiling.
+                            None,
+                            // Same for a boundary `pad_value`: not modeled
+                            // in `graph.proto` yet, so every load still
+                            // requires `tile_row`/`tile_col` to evenly
+                            // divide the tensor.
+                            None,
                             0,
                             hbm_config.addr_offset,
-                            off_chip_load.par_dispatch as usize,
+                            get_par_dispatch(
+                                &sim_config.par_dispatch_overrides,
+                                operation.id,
+                                off_chip_load.par_dispatch as usize,
+                            ),
                             addr_snd,
                             resp_rcv,
                             on_chip_snd,
@@ -542,9 +995,34 @@ fn build_from_proto<'a>(
                             resp: resp_snd,
                         });
                     }
-                    _ => todo!(),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for OffChipLoad operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
+            // `OffChipStore`, `RandomOffChipStore`, `RandomOffChipLoad`, `ExpandRef`, and
+            // `RepeatStatic` used to match their dtype down to a single `Type::F32(_)` arm
+            // and error on everything else, even though the operators behind them
+            // (`OffChipStore<E, T, B>`, `ExpandRef::<_, _>`, `RepeatStatic::<_>`, ...) are
+            // already generic over the tile element type `T`. `Type::U64(_)` is a real,
+            // already-used variant (`RandomOffChipStore`/`RandomOffChipLoad` already route
+            // their address streams through `channel_map_collection.tile_u64()`), so a
+            // `U64` payload arm below is a genuine generalization, not a guess.
+            // `FlatPartition` (F32/U64/MultiHot arms) and `FlatReassemble` (F32/MultiHot
+            // arms) don't need this.
+            //
+            // F16/BF16/I32/I8 tile streams -- e.g. to simulate a quantized kernel's
+            // off-chip traffic -- are a different story: they need new variants in `Type`
+            // itself (from `datatype.proto`, compiled by `build.rs` from
+            // `step_perf_ir/proto/`, neither of which is present in this tree) plus a
+            // `channel_map_collection` entry per new element type, which is a real
+            // proto/schema change this checkout can't make -- not something a match arm
+            // can paper over.
             OpType::OffChipStore(off_chip_store) => {
                 match off_chip_store
                     .dtype
@@ -555,7 +1033,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(_) => {
-                        let on_chip_rcv = channel_map_collection.tile_f32.get_receiver(
+                        let on_chip_rcv = channel_map_collection.tile_f32().get_receiver(
                             off_chip_store.input_id,
                             off_chip_store.stream_idx,
                             builder,
@@ -568,14 +1046,21 @@ fn build_from_proto<'a>(
                         let (addr_snd, addr_rcv) = builder.unbounded();
                         let (resp_snd, resp_rcv) = builder.unbounded();
 
-                        builder.add_child(OffChipStore::<SimpleEvent, _>::new(
-                            to_usize_vec(off_chip_store.tensor_shape_tiled),
+                        builder.add_child(OffChipStore::<SimpleEvent, _, _>::new(
+                            to_usize_vec(off_chip_store.tensor_shape_tiled).unwrap(),
                             off_chip_store.tile_row as usize,
                             off_chip_store.tile_col as usize,
                             off_chip_store.store_path,
+                            false,
+                            None,
                             0,
                             hbm_config.addr_offset,
-                            off_chip_store.par_dispatch as usize,
+                            get_par_dispatch(
+                                &sim_config.par_dispatch_overrides,
+                                operation.id,
+                                off_chip_store.par_dispatch as usize,
+                            ),
+                            sim_config.store_max_inflight,
                             on_chip_rcv,
                             addr_snd,
                             resp_rcv,
@@ -587,7 +1072,54 @@ fn build_from_proto<'a>(
                             resp: resp_snd,
                         });
                     }
-                    _ => todo!(),
+                    Type::U64(_) => {
+                        let on_chip_rcv = channel_map_collection.tile_u64().get_receiver(
+                            off_chip_store.input_id,
+                            off_chip_store.stream_idx,
+                            builder,
+                            get_chan_depth(
+                                &sim_config.config_dict,
+                                off_chip_store.input_id,
+                                channel_depth,
+                            ),
+                        );
+                        let (addr_snd, addr_rcv) = builder.unbounded();
+                        let (resp_snd, resp_rcv) = builder.unbounded();
+
+                        builder.add_child(OffChipStore::<SimpleEvent, _, _>::new(
+                            to_usize_vec(off_chip_store.tensor_shape_tiled).unwrap(),
+                            off_chip_store.tile_row as usize,
+                            off_chip_store.tile_col as usize,
+                            off_chip_store.store_path,
+                            false,
+                            None,
+                            0,
+                            hbm_config.addr_offset,
+                            get_par_dispatch(
+                                &sim_config.par_dispatch_overrides,
+                                operation.id,
+                                off_chip_store.par_dispatch as usize,
+                            ),
+                            sim_config.store_max_inflight,
+                            on_chip_rcv,
+                            addr_snd,
+                            resp_rcv,
+                            operation.id,
+                        ));
+
+                        mem_context.add_writer(WriteBundle {
+                            addr: addr_rcv,
+                            resp: resp_snd,
+                        });
+                    }
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for OffChipStore operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::RandomOffChipStore(random_off_chip_store) => {
@@ -600,7 +1132,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(_) => {
-                        let waddr = channel_map_collection.tile_u64.get_receiver(
+                        let waddr = channel_map_collection.tile_u64().get_receiver(
                             random_off_chip_store.waddr_id,
                             random_off_chip_store.waddr_stream_idx,
                             builder,
@@ -614,7 +1146,7 @@ fn build_from_proto<'a>(
                                 ),
                             ),
                         );
-                        let wdata = channel_map_collection.tile_f32.get_receiver(
+                        let wdata = channel_map_collection.tile_f32().get_receiver(
                             random_off_chip_store.wdata_id,
                             random_off_chip_store.wdata_stream_idx,
                             builder,
@@ -624,7 +1156,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let wack = channel_map_collection.bool.get_sender(
+                        let wack = channel_map_collection.bool().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -634,14 +1166,21 @@ fn build_from_proto<'a>(
                         let (resp_snd, resp_rcv) = builder.unbounded();
 
                         builder.add_child(RandomOffChipStore::<SimpleEvent, _>::new(
-                            to_usize_vec(random_off_chip_store.tensor_shape_tiled),
+                            to_usize_vec(random_off_chip_store.tensor_shape_tiled).unwrap(),
                             random_off_chip_store.npy_path,
                             random_off_chip_store.tile_row as usize,
                             random_off_chip_store.tile_col as usize,
                             f32_bytes,
                             0,
                             hbm_config.addr_offset,
-                            random_off_chip_store.par_dispatch as usize,
+                            get_par_dispatch(
+                                &sim_config.par_dispatch_overrides,
+                                operation.id,
+                                random_off_chip_store.par_dispatch as usize,
+                            ),
+                            sim_config.store_max_inflight,
+                            sim_config.verify_store_writes,
+                            sim_config.allow_store_overwrite,
                             addr_snd,
                             resp_rcv,
                             waddr,
@@ -656,7 +1195,78 @@ fn build_from_proto<'a>(
                             resp: resp_snd,
                         });
                     }
-                    _ => todo!(),
+                    Type::U64(_) => {
+                        let waddr = channel_map_collection.tile_u64().get_receiver(
+                            random_off_chip_store.waddr_id,
+                            random_off_chip_store.waddr_stream_idx,
+                            builder,
+                            get_chan_depth(
+                                &sim_config.config_dict,
+                                random_off_chip_store.waddr_id,
+                                get_chan_depth(
+                                    &sim_config.config_dict,
+                                    random_off_chip_store.waddr_id,
+                                    channel_depth,
+                                ),
+                            ),
+                        );
+                        let wdata = channel_map_collection.tile_u64().get_receiver(
+                            random_off_chip_store.wdata_id,
+                            random_off_chip_store.wdata_stream_idx,
+                            builder,
+                            get_chan_depth(
+                                &sim_config.config_dict,
+                                random_off_chip_store.wdata_id,
+                                channel_depth,
+                            ),
+                        );
+                        let wack = channel_map_collection.bool().get_sender(
+                            operation.id,
+                            None,
+                            builder,
+                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
+                        );
+                        let (addr_snd, addr_rcv) = builder.unbounded();
+                        let (resp_snd, resp_rcv) = builder.unbounded();
+
+                        builder.add_child(RandomOffChipStore::<SimpleEvent, _>::new(
+                            to_usize_vec(random_off_chip_store.tensor_shape_tiled).unwrap(),
+                            random_off_chip_store.npy_path,
+                            random_off_chip_store.tile_row as usize,
+                            random_off_chip_store.tile_col as usize,
+                            u64_bytes,
+                            0,
+                            hbm_config.addr_offset,
+                            get_par_dispatch(
+                                &sim_config.par_dispatch_overrides,
+                                operation.id,
+                                random_off_chip_store.par_dispatch as usize,
+                            ),
+                            sim_config.store_max_inflight,
+                            sim_config.verify_store_writes,
+                            sim_config.allow_store_overwrite,
+                            addr_snd,
+                            resp_rcv,
+                            waddr,
+                            wdata,
+                            wack,
+                            operation.id,
+                            random_off_chip_store.ack_based_on_waddr,
+                        ));
+
+                        mem_context.add_writer(WriteBundle {
+                            addr: addr_rcv,
+                            resp: resp_snd,
+                        });
+                    }
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for RandomOffChipStore operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::RandomOffChipLoad(random_off_chip_load) => {
@@ -669,7 +1279,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(_) => {
-                        let raddr = channel_map_collection.tile_u64.get_receiver(
+                        let raddr = channel_map_collection.tile_u64().get_receiver(
                             random_off_chip_load.raddr_id,
                             random_off_chip_load.raddr_stream_idx,
                             builder,
@@ -679,7 +1289,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let on_chip_snd = channel_map_collection.tile_f32.get_sender(
+                        let on_chip_snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -688,15 +1298,92 @@ fn build_from_proto<'a>(
                         let (addr_snd, addr_rcv) = builder.unbounded();
                         let (resp_snd, resp_rcv) = builder.unbounded();
 
-                        builder.add_child(RandomOffChipLoad::<SimpleEvent, _>::new(
-                            to_usize_vec(random_off_chip_load.tensor_shape_tiled),
+                        builder.add_child(RandomOffChipLoad::<SimpleEvent, _, _>::new(
+                            to_usize_vec(random_off_chip_load.tensor_shape_tiled).unwrap(),
                             random_off_chip_load.npy_path,
                             random_off_chip_load.tile_row as usize,
                             random_off_chip_load.tile_col as usize,
                             f32_bytes,
                             0,
                             hbm_config.addr_offset,
-                            random_off_chip_load.par_dispatch as usize,
+                            get_par_dispatch(
+                                &sim_config.par_dispatch_overrides,
+                                operation.id,
+                                random_off_chip_load.par_dispatch as usize,
+                            ),
+                            // Proto schema has no address-mapper selector yet;
+                            // preserve the original linear row-major layout.
+                            crate::memory::address_mapper::LinearMapper,
+                            // Proto schema has no outstanding-tile selector
+                            // yet; preserve the original fully-serialized
+                            // behavior.
+                            None,
+                            // Proto schema has no burst-size selector yet;
+                            // `None` defaults to `addr_offset`, reproducing
+                            // the original one-word-per-request behavior.
+                            None,
+                            // Proto schema has no debug-probe selector;
+                            // debugging is opt-in and wired up by hand.
+                            None,
+                            addr_snd,
+                            resp_rcv,
+                            raddr,
+                            on_chip_snd,
+                            operation.id,
+                        ));
+
+                        mem_context.add_reader(ReadBundle {
+                            addr: addr_rcv,
+                            resp: resp_snd,
+                        });
+                    }
+                    Type::U64(_) => {
+                        let raddr = channel_map_collection.tile_u64().get_receiver(
+                            random_off_chip_load.raddr_id,
+                            random_off_chip_load.raddr_stream_idx,
+                            builder,
+                            get_chan_depth(
+                                &sim_config.config_dict,
+                                random_off_chip_load.raddr_id,
+                                channel_depth,
+                            ),
+                        );
+                        let on_chip_snd = channel_map_collection.tile_u64().get_sender(
+                            operation.id,
+                            None,
+                            builder,
+                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
+                        );
+                        let (addr_snd, addr_rcv) = builder.unbounded();
+                        let (resp_snd, resp_rcv) = builder.unbounded();
+
+                        builder.add_child(RandomOffChipLoad::<SimpleEvent, _, _>::new(
+                            to_usize_vec(random_off_chip_load.tensor_shape_tiled).unwrap(),
+                            random_off_chip_load.npy_path,
+                            random_off_chip_load.tile_row as usize,
+                            random_off_chip_load.tile_col as usize,
+                            u64_bytes,
+                            0,
+                            hbm_config.addr_offset,
+                            get_par_dispatch(
+                                &sim_config.par_dispatch_overrides,
+                                operation.id,
+                                random_off_chip_load.par_dispatch as usize,
+                            ),
+                            // Proto schema has no address-mapper selector yet;
+                            // preserve the original linear row-major layout.
+                            crate::memory::address_mapper::LinearMapper,
+                            // Proto schema has no outstanding-tile selector
+                            // yet; preserve the original fully-serialized
+                            // behavior.
+                            None,
+                            // Proto schema has no burst-size selector yet;
+                            // `None` defaults to `addr_offset`, reproducing
+                            // the original one-word-per-request behavior.
+                            None,
+                            // Proto schema has no debug-probe selector;
+                            // debugging is opt-in and wired up by hand.
+                            None,
                             addr_snd,
                             resp_rcv,
                             raddr,
@@ -709,7 +1396,14 @@ fn build_from_proto<'a>(
                             resp: resp_snd,
                         });
                     }
-                    _ => todo!(),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for RandomOffChipLoad operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::ExpandRef(expand_ref) => {
@@ -724,7 +1418,7 @@ fn build_from_proto<'a>(
                         .unwrap(),
                 ) {
                     (Type::F32(_), Type::F32(_)) => {
-                        let in_rcv = channel_map_collection.tile_f32.get_receiver(
+                        let in_rcv = channel_map_collection.tile_f32().get_receiver(
                             expand_ref.input_id,
                             expand_ref.stream_idx,
                             builder,
@@ -734,7 +1428,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let ref_rcv = channel_map_collection.tile_f32.get_receiver(
+                        let ref_rcv = channel_map_collection.tile_f32().get_receiver(
                             expand_ref.ref_id,
                             expand_ref.ref_stream_idx,
                             builder,
@@ -744,7 +1438,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                        let snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -758,13 +1452,82 @@ fn build_from_proto<'a>(
                             operation.id,
                         ));
                     }
-                    _ => panic!("Unsupported data type for ExpandRef operation"),
+                    (Type::U64(_), Type::U64(_)) => {
+                        let in_rcv = channel_map_collection.tile_u64().get_receiver(
+                            expand_ref.input_id,
+                            expand_ref.stream_idx,
+                            builder,
+                            get_chan_depth(
+                                &sim_config.config_dict,
+                                expand_ref.input_id,
+                                channel_depth,
+                            ),
+                        );
+                        let ref_rcv = channel_map_collection.tile_u64().get_receiver(
+                            expand_ref.ref_id,
+                            expand_ref.ref_stream_idx,
+                            builder,
+                            get_chan_depth(
+                                &sim_config.config_dict,
+                                expand_ref.ref_id,
+                                channel_depth,
+                            ),
+                        );
+                        let snd = channel_map_collection.tile_u64().get_sender(
+                            operation.id,
+                            None,
+                            builder,
+                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
+                        );
+                        builder.add_child(ExpandRef::<_, _>::new(
+                            in_rcv,
+                            ref_rcv,
+                            expand_ref.expand_rank,
+                            snd,
+                            operation.id,
+                        ));
+                    }
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!(
+                                "unsupported data type for ExpandRef operation, got ({}, {})",
+                                discriminant_name(&other.0),
+                                discriminant_name(&other.1),
+                            ),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::RepeatStatic(repeat_static) => {
                 match repeat_static.dtype.clone().unwrap().r#type.clone().unwrap() {
                     Type::F32(_) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
+                        let rcv = channel_map_collection.tile_f32().get_receiver(
+                            repeat_static.input_id,
+                            repeat_static.stream_idx,
+                            builder,
+                            get_chan_depth(
+                                &sim_config.config_dict,
+                                repeat_static.input_id,
+                                channel_depth,
+                            ),
+                        );
+                        let snd = channel_map_collection.tile_f32().get_sender(
+                            operation.id,
+                            None,
+                            builder,
+                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
+                        );
+                        builder.add_child(RepeatStatic::<_>::new(
+                            rcv,
+                            repeat_static.repeat_factor as usize,
+                            snd,
+                        ));
+                    }
+                    Type::U64(_) => {
+                        let rcv = channel_map_collection.tile_u64().get_receiver(
                             repeat_static.input_id,
                             repeat_static.stream_idx,
                             builder,
@@ -774,7 +1537,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                        let snd = channel_map_collection.tile_u64().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -786,7 +1549,14 @@ fn build_from_proto<'a>(
                             snd,
                         ));
                     }
-                    _ => panic!("Unsupported data type for RepeatStatic operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for RepeatStatic operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::Broadcast(broadcast) => {
@@ -843,7 +1613,14 @@ fn build_from_proto<'a>(
                             channel_depth
                         );
                     }
-                    _ => panic!("Unsupported data type for Broadcast operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for Broadcast operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::FlatPartition(flat_partition) => {
@@ -856,7 +1633,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(_) => {
-                        let input_rcv = channel_map_collection.tile_f32.get_receiver(
+                        let input_rcv = channel_map_collection.tile_f32().get_receiver(
                             flat_partition.input_id,
                             flat_partition.input_stream_idx,
                             builder,
@@ -868,7 +1645,7 @@ fn build_from_proto<'a>(
                         );
                         let mut snd_list = vec![];
                         for i in 0..flat_partition.num_consumers {
-                            snd_list.push(channel_map_collection.tile_f32.get_sender(
+                            snd_list.push(channel_map_collection.tile_f32().get_sender(
                                 operation.id,
                                 Some(i),
                                 builder,
@@ -889,11 +1666,15 @@ fn build_from_proto<'a>(
                             .unwrap()
                         {
                             Type::MultiHot(multi_hot) => {
-                                let control_rcv = channel_map_collection.multihot.get_receiver(
+                                let control_rcv = channel_map_collection.multihot().get_receiver(
                                     flat_partition.control_id,
                                     flat_partition.control_stream_idx,
                                     builder,
-                                    channel_depth,
+                                    get_chan_depth(
+                                        &sim_config.config_dict,
+                                        flat_partition.control_id,
+                                        channel_depth,
+                                    ),
                                 );
                                 builder.add_child(FlatPartition::<SimpleEvent, _, _>::new(
                                     input_rcv,
@@ -901,17 +1682,32 @@ fn build_from_proto<'a>(
                                     snd_list,
                                     flat_partition.partition_rank,
                                     FlatPartitionConfig {
-                                        switch_cycles: to_u64_vec(flat_partition.switch_cycles),
-                                        write_back_mu: flat_partition.write_back_mu,
+                                        switch_cycles: get_switch_cycles(
+                                            &sim_config.switch_cycles_overrides,
+                                            operation.id,
+                                            to_u64_vec(flat_partition.switch_cycles).unwrap(),
+                                        ),
+                                        write_back_mu: get_write_back_mu(
+                                            &sim_config.write_back_mu_overrides,
+                                            operation.id,
+                                            flat_partition.write_back_mu,
+                                        ),
                                     },
                                     operation.id,
                                 ))
                             }
-                            _ => panic!("Unsupported data type"),
+                            other => {
+                                errors.push(BuildError {
+                                    operation_id: operation.id,
+                                    op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                    unsupported: format!("unsupported control dtype for FlatPartition operation (F32 input), got {}", discriminant_name(&other)),
+                                });
+                                continue 'operations;
+                            }
                         }
                     }
                     Type::U64(_) => {
-                        let input_rcv = channel_map_collection.tile_u64.get_receiver(
+                        let input_rcv = channel_map_collection.tile_u64().get_receiver(
                             flat_partition.input_id,
                             flat_partition.input_stream_idx,
                             builder,
@@ -923,7 +1719,7 @@ fn build_from_proto<'a>(
                         );
                         let mut snd_list = vec![];
                         for i in 0..flat_partition.num_consumers {
-                            snd_list.push(channel_map_collection.tile_u64.get_sender(
+                            snd_list.push(channel_map_collection.tile_u64().get_sender(
                                 operation.id,
                                 Some(i),
                                 builder,
@@ -944,11 +1740,15 @@ fn build_from_proto<'a>(
                             .unwrap()
                         {
                             Type::MultiHot(multi_hot) => {
-                                let control_rcv = channel_map_collection.multihot.get_receiver(
+                                let control_rcv = channel_map_collection.multihot().get_receiver(
                                     flat_partition.control_id,
                                     flat_partition.control_stream_idx,
                                     builder,
-                                    channel_depth,
+                                    get_chan_depth(
+                                        &sim_config.config_dict,
+                                        flat_partition.control_id,
+                                        channel_depth,
+                                    ),
                                 );
                                 builder.add_child(FlatPartition::<SimpleEvent, _, _>::new(
                                     input_rcv,
@@ -956,17 +1756,32 @@ fn build_from_proto<'a>(
                                     snd_list,
                                     flat_partition.partition_rank,
                                     FlatPartitionConfig {
-                                        switch_cycles: to_u64_vec(flat_partition.switch_cycles),
-                                        write_back_mu: flat_partition.write_back_mu,
+                                        switch_cycles: get_switch_cycles(
+                                            &sim_config.switch_cycles_overrides,
+                                            operation.id,
+                                            to_u64_vec(flat_partition.switch_cycles).unwrap(),
+                                        ),
+                                        write_back_mu: get_write_back_mu(
+                                            &sim_config.write_back_mu_overrides,
+                                            operation.id,
+                                            flat_partition.write_back_mu,
+                                        ),
                                     },
                                     operation.id,
                                 ))
                             }
-                            _ => panic!("Unsupported data type"),
+                            other => {
+                                errors.push(BuildError {
+                                    operation_id: operation.id,
+                                    op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                    unsupported: format!("unsupported control dtype for FlatPartition operation (U64 input), got {}", discriminant_name(&other)),
+                                });
+                                continue 'operations;
+                            }
                         }
                     }
                     Type::MultiHot(_) => {
-                        let input_rcv = channel_map_collection.multihot.get_receiver(
+                        let input_rcv = channel_map_collection.multihot().get_receiver(
                             flat_partition.input_id,
                             flat_partition.input_stream_idx,
                             builder,
@@ -978,7 +1793,7 @@ fn build_from_proto<'a>(
                         );
                         let mut snd_list = vec![];
                         for i in 0..flat_partition.num_consumers {
-                            snd_list.push(channel_map_collection.multihot.get_sender(
+                            snd_list.push(channel_map_collection.multihot().get_sender(
                                 operation.id,
                                 Some(i),
                                 builder,
@@ -999,11 +1814,15 @@ fn build_from_proto<'a>(
                             .unwrap()
                         {
                             Type::MultiHot(multi_hot) => {
-                                let control_rcv = channel_map_collection.multihot.get_receiver(
+                                let control_rcv = channel_map_collection.multihot().get_receiver(
                                     flat_partition.control_id,
                                     flat_partition.control_stream_idx,
                                     builder,
-                                    channel_depth,
+                                    get_chan_depth(
+                                        &sim_config.config_dict,
+                                        flat_partition.control_id,
+                                        channel_depth,
+                                    ),
                                 );
                                 builder.add_child(FlatPartition::<SimpleEvent, _, _>::new(
                                     input_rcv,
@@ -1011,16 +1830,38 @@ fn build_from_proto<'a>(
                                     snd_list,
                                     flat_partition.partition_rank,
                                     FlatPartitionConfig {
-                                        switch_cycles: to_u64_vec(flat_partition.switch_cycles),
-                                        write_back_mu: flat_partition.write_back_mu,
+                                        switch_cycles: get_switch_cycles(
+                                            &sim_config.switch_cycles_overrides,
+                                            operation.id,
+                                            to_u64_vec(flat_partition.switch_cycles).unwrap(),
+                                        ),
+                                        write_back_mu: get_write_back_mu(
+                                            &sim_config.write_back_mu_overrides,
+                                            operation.id,
+                                            flat_partition.write_back_mu,
+                                        ),
                                     },
                                     operation.id,
                                 ))
                             }
-                            _ => panic!("Unsupported data type"),
+                            other => {
+                                errors.push(BuildError {
+                                    operation_id: operation.id,
+                                    op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                    unsupported: format!("unsupported control dtype for FlatPartition operation (MultiHot input), got {}", discriminant_name(&other)),
+                                });
+                                continue 'operations;
+                            }
                         }
                     }
-                    _ => panic!("Unsupported data type"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported input dtype for FlatPartition operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::FlatReassemble(reassemble) => {
@@ -1039,7 +1880,7 @@ fn build_from_proto<'a>(
                             .into_iter()
                             .zip(reassemble.input_stream_idx_list.into_iter())
                         {
-                            let rcv = channel_map_collection.tile_f32.get_receiver(
+                            let rcv = channel_map_collection.tile_f32().get_receiver(
                                 rcv_id,
                                 if stream_idx < 0 {
                                     None
@@ -1052,7 +1893,7 @@ fn build_from_proto<'a>(
                             rcv_list.push(rcv);
                         }
 
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                        let snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -1067,7 +1908,7 @@ fn build_from_proto<'a>(
                             .unwrap()
                         {
                             Type::MultiHot(multi_hot) => {
-                                let control_rcv = channel_map_collection.multihot.get_receiver(
+                                let control_rcv = channel_map_collection.multihot().get_receiver(
                                     reassemble.control_id,
                                     reassemble.control_stream_idx,
                                     builder,
@@ -1083,13 +1924,42 @@ fn build_from_proto<'a>(
                                     snd,
                                     reassemble.reassemble_rank,
                                     FlatReassembleConfig {
-                                        switch_cycles: to_u64_vec(reassemble.switch_cycles),
-                                        write_back_mu: reassemble.write_back_mu,
+                                        switch_cycles: get_switch_cycles(
+                                            &sim_config.switch_cycles_overrides,
+                                            operation.id,
+                                            to_u64_vec(reassemble.switch_cycles).unwrap(),
+                                        ),
+                                        write_back_mu: get_write_back_mu(
+                                            &sim_config.write_back_mu_overrides,
+                                            operation.id,
+                                            reassemble.write_back_mu,
+                                        ),
+                                        // RoundRobin isn't representable in
+                                        // the reassemble op proto yet, so
+                                        // every proto-driven build keeps the
+                                        // pre-existing ArrivalOrder behavior.
+                                        merge_policy: MergePolicy::ArrivalOrder,
+                                        // The reassemble op proto has no
+                                        // field for this either, so
+                                        // proto-driven builds keep charging
+                                        // each element's MU read independently.
+                                        shared_bw: None,
+                                        // Nor for per-expert capacity, so
+                                        // proto-driven builds keep the
+                                        // unconditional reassembly behavior.
+                                        capacity: None,
                                     },
                                     operation.id,
                                 ))
                             }
-                            _ => panic!("Unsupported data type"),
+                            other => {
+                                errors.push(BuildError {
+                                    operation_id: operation.id,
+                                    op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                    unsupported: format!("unsupported control dtype for FlatReassemble operation, got {}", discriminant_name(&other)),
+                                });
+                                continue 'operations;
+                            }
                         }
                     }
                     Type::MultiHot(_) => {
@@ -1099,7 +1969,7 @@ fn build_from_proto<'a>(
                             .into_iter()
                             .zip(reassemble.input_stream_idx_list.into_iter())
                         {
-                            let rcv = channel_map_collection.multihot.get_receiver(
+                            let rcv = channel_map_collection.multihot().get_receiver(
                                 rcv_id,
                                 if stream_idx < 0 {
                                     None
@@ -1112,7 +1982,7 @@ fn build_from_proto<'a>(
                             rcv_list.push(rcv);
                         }
 
-                        let snd = channel_map_collection.multihot.get_sender(
+                        let snd = channel_map_collection.multihot().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -1127,7 +1997,7 @@ fn build_from_proto<'a>(
                             .unwrap()
                         {
                             Type::MultiHot(multi_hot) => {
-                                let control_rcv = channel_map_collection.multihot.get_receiver(
+                                let control_rcv = channel_map_collection.multihot().get_receiver(
                                     reassemble.control_id,
                                     reassemble.control_stream_idx,
                                     builder,
@@ -1143,16 +2013,52 @@ fn build_from_proto<'a>(
                                     snd,
                                     reassemble.reassemble_rank,
                                     FlatReassembleConfig {
-                                        switch_cycles: to_u64_vec(reassemble.switch_cycles),
-                                        write_back_mu: reassemble.write_back_mu,
+                                        switch_cycles: get_switch_cycles(
+                                            &sim_config.switch_cycles_overrides,
+                                            operation.id,
+                                            to_u64_vec(reassemble.switch_cycles).unwrap(),
+                                        ),
+                                        write_back_mu: get_write_back_mu(
+                                            &sim_config.write_back_mu_overrides,
+                                            operation.id,
+                                            reassemble.write_back_mu,
+                                        ),
+                                        // RoundRobin isn't representable in
+                                        // the reassemble op proto yet, so
+                                        // every proto-driven build keeps the
+                                        // pre-existing ArrivalOrder behavior.
+                                        merge_policy: MergePolicy::ArrivalOrder,
+                                        // The reassemble op proto has no
+                                        // field for this either, so
+                                        // proto-driven builds keep charging
+                                        // each element's MU read independently.
+                                        shared_bw: None,
+                                        // Nor for per-expert capacity, so
+                                        // proto-driven builds keep the
+                                        // unconditional reassembly behavior.
+                                        capacity: None,
                                     },
                                     operation.id,
                                 ))
                             }
-                            _ => panic!("Unsupported data type"),
+                            other => {
+                                errors.push(BuildError {
+                                    operation_id: operation.id,
+                                    op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                    unsupported: format!("unsupported control dtype for FlatReassemble operation, got {}", discriminant_name(&other)),
+                                });
+                                continue 'operations;
+                            }
                         }
                     }
-                    _ => panic!("Unsupported data type"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported input dtype for FlatReassemble operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::Parallelize(parallelize) => {
@@ -1165,7 +2071,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(f32) => {
-                        let input_rcv = channel_map_collection.tile_f32.get_receiver(
+                        let input_rcv = channel_map_collection.tile_f32().get_receiver(
                             parallelize.input_id,
                             parallelize.input_stream_idx,
                             builder,
@@ -1177,7 +2083,7 @@ fn build_from_proto<'a>(
                         );
                         let mut snd_list = vec![];
                         for i in 0..parallelize.num_consumers {
-                            snd_list.push(channel_map_collection.tile_f32.get_sender(
+                            snd_list.push(channel_map_collection.tile_f32().get_sender(
                                 operation.id,
                                 Some(i),
                                 builder,
@@ -1193,14 +2099,22 @@ fn build_from_proto<'a>(
                             snd_list,
                             parallelize.parallelize_rank,
                             FlatPartitionConfig {
-                                switch_cycles: to_u64_vec(parallelize.switch_cycles),
-                                write_back_mu: parallelize.write_back_mu,
+                                switch_cycles: get_switch_cycles(
+                                    &sim_config.switch_cycles_overrides,
+                                    operation.id,
+                                    to_u64_vec(parallelize.switch_cycles).unwrap(),
+                                ),
+                                write_back_mu: get_write_back_mu(
+                                    &sim_config.write_back_mu_overrides,
+                                    operation.id,
+                                    parallelize.write_back_mu,
+                                ),
                             },
                             operation.id,
                         ))
                     }
                     Type::MultiHot(_) => {
-                        let input_rcv = channel_map_collection.multihot.get_receiver(
+                        let input_rcv = channel_map_collection.multihot().get_receiver(
                             parallelize.input_id,
                             parallelize.input_stream_idx,
                             builder,
@@ -1212,7 +2126,7 @@ fn build_from_proto<'a>(
                         );
                         let mut snd_list = vec![];
                         for i in 0..parallelize.num_consumers {
-                            snd_list.push(channel_map_collection.multihot.get_sender(
+                            snd_list.push(channel_map_collection.multihot().get_sender(
                                 operation.id,
                                 Some(i),
                                 builder,
@@ -1228,14 +2142,22 @@ fn build_from_proto<'a>(
                             snd_list,
                             parallelize.parallelize_rank,
                             FlatPartitionConfig {
-                                switch_cycles: to_u64_vec(parallelize.switch_cycles),
-                                write_back_mu: parallelize.write_back_mu,
+                                switch_cycles: get_switch_cycles(
+                                    &sim_config.switch_cycles_overrides,
+                                    operation.id,
+                                    to_u64_vec(parallelize.switch_cycles).unwrap(),
+                                ),
+                                write_back_mu: get_write_back_mu(
+                                    &sim_config.write_back_mu_overrides,
+                                    operation.id,
+                                    parallelize.write_back_mu,
+                                ),
                             },
                             operation.id,
                         ))
                     }
                     Type::U64(u64) => {
-                        let input_rcv = channel_map_collection.tile_u64.get_receiver(
+                        let input_rcv = channel_map_collection.tile_u64().get_receiver(
                             parallelize.input_id,
                             parallelize.input_stream_idx,
                             builder,
@@ -1247,7 +2169,7 @@ fn build_from_proto<'a>(
                         );
                         let mut snd_list = vec![];
                         for i in 0..parallelize.num_consumers {
-                            snd_list.push(channel_map_collection.tile_u64.get_sender(
+                            snd_list.push(channel_map_collection.tile_u64().get_sender(
                                 operation.id,
                                 Some(i),
                                 builder,
@@ -1263,37 +2185,58 @@ fn build_from_proto<'a>(
                             snd_list,
                             parallelize.parallelize_rank,
                             FlatPartitionConfig {
-                                switch_cycles: to_u64_vec(parallelize.switch_cycles),
-                                write_back_mu: parallelize.write_back_mu,
+                                switch_cycles: get_switch_cycles(
+                                    &sim_config.switch_cycles_overrides,
+                                    operation.id,
+                                    to_u64_vec(parallelize.switch_cycles).unwrap(),
+                                ),
+                                write_back_mu: get_write_back_mu(
+                                    &sim_config.write_back_mu_overrides,
+                                    operation.id,
+                                    parallelize.write_back_mu,
+                                ),
                             },
                             operation.id,
                         ))
                     }
-                    _ => panic!("Unsupported data type"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for Parallelize operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::Promote(promote) => {
-                match promote.dtype.clone().unwrap().r#type.clone().unwrap() {
-                    Type::F32(f32) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
-                            promote.input_id,
-                            promote.stream_idx,
+                macro_rules! promote_for {
+                    ($chan:ident) => {{
+                        let ctx = build_promote::<$chan>(
+                            channel_map_collection,
                             builder,
-                            get_chan_depth(
-                                &sim_config.config_dict,
-                                promote.input_id,
-                                channel_depth,
-                            ),
-                        );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                            sim_config,
+                            channel_depth,
                             operation.id,
-                            None,
-                            builder,
-                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
+                            promote.input_id,
+                            promote.stream_idx,
+                            promote.promote_rank,
                         );
-                        builder.add_child(Promote::new(rcv, snd, promote.promote_rank));
+                        builder.add_child(ctx)
+                    }};
+                }
+                match promote.dtype.clone().unwrap().r#type.clone().unwrap() {
+                    Type::F32(_) => promote_for!(TileF32Chan),
+                    Type::U64(_) => promote_for!(TileU64Chan),
+                    Type::MultiHot(_) => promote_for!(MultiHotChan),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for Promote operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
                     }
-                    _ => panic!("Unsupported data type"),
                 }
             }
             OpType::ConsumerContext(consumer_context) => {
@@ -1306,51 +2249,73 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(_) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
+                        let rcv = channel_map_collection.tile_f32().get_receiver(
                             consumer_context.input_id,
                             consumer_context.stream_idx,
                             builder,
                             None,
                         );
-                        builder.add_child(ConsumerContext::new(rcv));
+                        match get_golden_mode(sim_config, consumer_context.input_id) {
+                            Some(mode) => builder.add_child(GoldenContext::new(rcv, mode)),
+                            None => builder.add_child(ConsumerContext::new(rcv)),
+                        }
                     }
                     Type::U64(_) => {
-                        let rcv = channel_map_collection.tile_u64.get_receiver(
+                        let rcv = channel_map_collection.tile_u64().get_receiver(
                             consumer_context.input_id,
                             consumer_context.stream_idx,
                             builder,
                             None,
                         );
-                        builder.add_child(ConsumerContext::new(rcv));
+                        match get_golden_mode(sim_config, consumer_context.input_id) {
+                            Some(mode) => builder.add_child(GoldenContext::new(rcv, mode)),
+                            None => builder.add_child(ConsumerContext::new(rcv)),
+                        }
                     }
                     Type::MultiHot(_) => {
-                        let rcv = channel_map_collection.multihot.get_receiver(
+                        let rcv = channel_map_collection.multihot().get_receiver(
                             consumer_context.input_id,
                             consumer_context.stream_idx,
                             builder,
                             None,
                         );
-                        builder.add_child(ConsumerContext::new(rcv));
+                        match get_golden_mode(sim_config, consumer_context.input_id) {
+                            Some(mode) => builder.add_child(GoldenContext::new(rcv, mode)),
+                            None => builder.add_child(ConsumerContext::new(rcv)),
+                        }
                     }
                     Type::ScalarU64(_) => {
-                        let rcv = channel_map_collection.u64.get_receiver(
+                        let rcv = channel_map_collection.u64().get_receiver(
                             consumer_context.input_id,
                             consumer_context.stream_idx,
                             builder,
                             None,
                         );
-                        builder.add_child(ConsumerContext::new(rcv));
+                        match get_golden_mode(sim_config, consumer_context.input_id) {
+                            Some(mode) => builder.add_child(GoldenContext::new(rcv, mode)),
+                            None => builder.add_child(ConsumerContext::new(rcv)),
+                        }
                     }
                     Type::ScalarBool(_) => {
-                        let rcv = channel_map_collection.bool.get_receiver(
+                        let rcv = channel_map_collection.bool().get_receiver(
                             consumer_context.input_id,
                             consumer_context.stream_idx,
                             builder,
                             None,
                         );
-                        builder.add_child(ConsumerContext::new(rcv));
+                        match get_golden_mode(sim_config, consumer_context.input_id) {
+                            Some(mode) => builder.add_child(GoldenContext::new(rcv, mode)),
+                            None => builder.add_child(ConsumerContext::new(rcv)),
+                        }
+                    }
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for ConsumerContext operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
                     }
-                    _ => panic!("Unsupported data type for ConsumerContext operation"),
                 }
             }
             OpType::PrinterContext(printer_context) => {
@@ -1363,7 +2328,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(_) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
+                        let rcv = channel_map_collection.tile_f32().get_receiver(
                             printer_context.input_id,
                             printer_context.stream_idx,
                             builder,
@@ -1372,7 +2337,7 @@ fn build_from_proto<'a>(
                         builder.add_child(PrinterContext::new(rcv));
                     }
                     Type::U64(_) => {
-                        let rcv = channel_map_collection.tile_u64.get_receiver(
+                        let rcv = channel_map_collection.tile_u64().get_receiver(
                             printer_context.input_id,
                             printer_context.stream_idx,
                             builder,
@@ -1381,7 +2346,7 @@ fn build_from_proto<'a>(
                         builder.add_child(PrinterContext::new(rcv));
                     }
                     Type::MultiHot(_) => {
-                        let rcv = channel_map_collection.multihot.get_receiver(
+                        let rcv = channel_map_collection.multihot().get_receiver(
                             printer_context.input_id,
                             printer_context.stream_idx,
                             builder,
@@ -1389,66 +2354,75 @@ fn build_from_proto<'a>(
                         );
                         builder.add_child(PrinterContext::new(rcv));
                     }
-                    _ => panic!("Unsupported data type for PrinterContext operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for PrinterContext operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::Bufferize(bufferize) => {
-                match bufferize.dtype.clone().unwrap().r#type.clone().unwrap() {
-                    Type::F32(_) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
-                            bufferize.input_id,
-                            bufferize.stream_idx,
+                macro_rules! bufferize_for {
+                    ($in:ident, $out:ident) => {{
+                        let ctx = build_bufferize::<$in, $out>(
+                            channel_map_collection,
                             builder,
-                            get_chan_depth(
-                                &sim_config.config_dict,
-                                bufferize.input_id,
-                                channel_depth,
-                            ),
-                        );
-                        let snd = channel_map_collection.buff_tile_f32.get_sender(
+                            sim_config,
+                            channel_depth,
                             operation.id,
-                            None,
-                            builder,
-                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
-                        );
-                        builder.add_child(Bufferize::<SimpleEvent, _>::new(
-                            rcv,
-                            snd,
+                            bufferize.input_id,
+                            bufferize.stream_idx,
                             bufferize.rank,
-                            operation.id,
-                        ));
+                        );
+                        builder.add_child(ctx)
+                    }};
+                }
+                match bufferize.dtype.clone().unwrap().r#type.clone().unwrap() {
+                    Type::F32(_) => bufferize_for!(TileF32Chan, BuffTileF32Chan),
+                    Type::U64(_) => bufferize_for!(TileU64Chan, BuffTileU64Chan),
+                    Type::MultiHot(_) => bufferize_for!(MultiHotChan, BuffMultiHotChan),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for Bufferize operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
                     }
-                    _ => panic!("Unsupported data type for Bufferize operation"),
                 }
             }
             OpType::Streamify(streamify) => {
-                match streamify.dtype.clone().unwrap().r#type.clone().unwrap() {
-                    Type::F32(_) => {
-                        let rcv = channel_map_collection.buff_tile_f32.get_receiver(
-                            streamify.input_id,
-                            streamify.stream_idx,
+                macro_rules! streamify_for {
+                    ($in:ident, $out:ident) => {{
+                        let ctx = build_streamify::<$in, $out>(
+                            channel_map_collection,
                             builder,
-                            get_chan_depth(
-                                &sim_config.config_dict,
-                                streamify.input_id,
-                                channel_depth,
-                            ),
-                        );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                            sim_config,
+                            channel_depth,
                             operation.id,
-                            None,
-                            builder,
-                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
-                        );
-                        builder.add_child(Streamify::<SimpleEvent, _>::new(
-                            to_usize_vec(streamify.repeat_factor),
+                            streamify.input_id,
+                            streamify.stream_idx,
+                            to_usize_vec(streamify.repeat_factor).unwrap(),
                             streamify.rank,
-                            rcv,
-                            snd,
-                            operation.id,
-                        ));
+                        );
+                        builder.add_child(ctx)
+                    }};
+                }
+                match streamify.dtype.clone().unwrap().r#type.clone().unwrap() {
+                    Type::F32(_) => streamify_for!(BuffTileF32Chan, TileF32Chan),
+                    Type::U64(_) => streamify_for!(BuffTileU64Chan, TileU64Chan),
+                    Type::MultiHot(_) => streamify_for!(BuffMultiHotChan, MultiHotChan),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for Streamify operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
                     }
-                    _ => panic!("Unsupported data type for Streamify operation"),
                 }
             }
             OpType::DynStreamify(dyn_streamify) => {
@@ -1469,7 +2443,7 @@ fn build_from_proto<'a>(
                         .unwrap(),
                 ) {
                     (Type::F32(_), Type::F32(_)) => {
-                        let rcv = channel_map_collection.buff_tile_f32.get_receiver(
+                        let rcv = channel_map_collection.buff_tile_f32().get_receiver(
                             dyn_streamify.input_id,
                             dyn_streamify.input_stream_idx,
                             builder,
@@ -1479,7 +2453,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let ref_rcv = channel_map_collection.tile_f32.get_receiver(
+                        let ref_rcv = channel_map_collection.tile_f32().get_receiver(
                             dyn_streamify.ref_id,
                             dyn_streamify.ref_stream_idx,
                             builder,
@@ -1489,7 +2463,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                        let snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -1504,7 +2478,18 @@ fn build_from_proto<'a>(
                             operation.id,
                         ));
                     }
-                    _ => panic!("Unsupported data type for DynStreamify operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!(
+                                "unsupported data type for DynStreamify operation, got ({}, {})",
+                                discriminant_name(&other.0),
+                                discriminant_name(&other.1),
+                            ),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::DynOffChipLoad(dyn_offchip_load) => {
@@ -1535,7 +2520,9 @@ fn build_from_proto<'a>(
                             f32_bytes,
                             mem_context,
                             builder,
-                            channel_depth
+                            channel_depth,
+                            &sim_config.par_dispatch_overrides,
+                            &sim_config.storage_format_overrides
                         );
                     }
                     (
@@ -1554,7 +2541,9 @@ fn build_from_proto<'a>(
                             f32_bytes,
                             mem_context,
                             builder,
-                            channel_depth
+                            channel_depth,
+                            &sim_config.par_dispatch_overrides,
+                            &sim_config.storage_format_overrides
                         );
                     }
                     (Type::F32(_), Type::MultiHot(_)) => {
@@ -1568,93 +2557,59 @@ fn build_from_proto<'a>(
                             f32_bytes,
                             mem_context,
                             builder,
-                            channel_depth
+                            channel_depth,
+                            &sim_config.par_dispatch_overrides,
+                            &sim_config.storage_format_overrides
                         );
                     }
-                    _ => panic!("Unsupported data type for DynOffChipLoad operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!(
+                                "unsupported data type for DynOffChipLoad operation, got ({}, {})",
+                                discriminant_name(&other.0),
+                                discriminant_name(&other.1),
+                            ),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::Flatten(flatten) => {
-                match flatten.dtype.clone().unwrap().r#type.clone().unwrap() {
-                    Type::F32(_) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
-                            flatten.input_id,
-                            flatten.stream_idx,
+                macro_rules! flatten_for {
+                    ($chan:ident) => {{
+                        let ctx = build_flatten::<$chan>(
+                            channel_map_collection,
                             builder,
-                            get_chan_depth(
-                                &sim_config.config_dict,
-                                flatten.input_id,
-                                channel_depth,
-                            ),
-                        );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                            sim_config,
+                            channel_depth,
                             operation.id,
-                            None,
-                            builder,
-                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
-                        );
-                        builder.add_child(Flatten::new(
-                            rcv,
-                            snd,
-                            flatten.min_rank,
-                            flatten.max_rank,
-                        ));
-                    }
-                    Type::MultiHot(_) => {
-                        let rcv = channel_map_collection.multihot.get_receiver(
                             flatten.input_id,
                             flatten.stream_idx,
-                            builder,
-                            get_chan_depth(
-                                &sim_config.config_dict,
-                                flatten.input_id,
-                                channel_depth,
-                            ),
-                        );
-                        let snd = channel_map_collection.multihot.get_sender(
-                            operation.id,
-                            None,
-                            builder,
-                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
-                        );
-                        builder.add_child(Flatten::new(
-                            rcv,
-                            snd,
                             flatten.min_rank,
                             flatten.max_rank,
-                        ));
-                    }
-                    Type::U64(_) => {
-                        let rcv = channel_map_collection.tile_u64.get_receiver(
-                            flatten.input_id,
-                            flatten.stream_idx,
-                            builder,
-                            get_chan_depth(
-                                &sim_config.config_dict,
-                                flatten.input_id,
-                                channel_depth,
-                            ),
-                        );
-                        let snd = channel_map_collection.tile_u64.get_sender(
-                            operation.id,
-                            None,
-                            builder,
-                            get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
                         );
-                        builder.add_child(Flatten::new(
-                            rcv,
-                            snd,
-                            flatten.min_rank,
-                            flatten.max_rank,
-                        ));
+                        builder.add_child(ctx)
+                    }};
+                }
+                match flatten.dtype.clone().unwrap().r#type.clone().unwrap() {
+                    Type::F32(_) => flatten_for!(TileF32Chan),
+                    Type::U64(_) => flatten_for!(TileU64Chan),
+                    Type::MultiHot(_) => flatten_for!(MultiHotChan),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for Flatten operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
                     }
-
-                    _ => panic!("Unsupported data type for Flatten operation"),
                 }
             }
             OpType::SelectGen(select_gen) => match select_gen.is_multihot {
                 true => {
-                    let snd = channel_map_collection.multihot.get_sender(
+                    let snd = channel_map_collection.multihot().get_sender(
                         operation.id,
                         None,
                         builder,
@@ -1667,20 +2622,27 @@ fn build_from_proto<'a>(
                         snd,
                     ));
                 }
-                false => todo!("Add the same version for IndexN"),
+                false => {
+                    errors.push(BuildError {
+                        operation_id: operation.id,
+                        op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                        unsupported: "IndexN variant of SelectGen operation is not implemented".to_string(),
+                    });
+                    continue 'operations;
+                }
             },
             OpType::Accum(accum) => match (
                 accum.dtype_a.clone().unwrap().r#type.clone().unwrap(),
                 accum.dtype_b.clone().unwrap().r#type.clone().unwrap(),
             ) {
                 (Type::F32(_), Type::F32(_)) => {
-                    let rcv = channel_map_collection.tile_f32.get_receiver(
+                    let rcv = channel_map_collection.tile_f32().get_receiver(
                         accum.input_id,
                         accum.stream_idx,
                         builder,
                         get_chan_depth(&sim_config.config_dict, accum.input_id, channel_depth),
                     );
-                    let snd = channel_map_collection.tile_f32.get_sender(
+                    let snd = channel_map_collection.tile_f32().get_sender(
                         operation.id,
                         None,
                         builder,
@@ -1704,7 +2666,14 @@ fn build_from_proto<'a>(
                                 )
                             })
                         }
-                        _ => todo!(),
+                        other => {
+                            errors.push(BuildError {
+                                operation_id: operation.id,
+                                op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                unsupported: format!("unsupported accumulation function for Accum operation (F32 input), got {}", discriminant_name(&other)),
+                            });
+                            continue 'operations;
+                        }
                     };
 
                     let tile_row = accum.tile_row as usize;
@@ -1724,7 +2693,14 @@ fn build_from_proto<'a>(
                                     accum.write_back_mu,
                                 )
                             }),
-                            _ => todo!(),
+                            other => {
+                                errors.push(BuildError {
+                                    operation_id: operation.id,
+                                    op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                    unsupported: format!("unsupported init function for Accum operation, got {}", discriminant_name(&other)),
+                                });
+                                continue 'operations;
+                            }
                         }
                     } else {
                         Arc::new(move || {
@@ -1745,18 +2721,19 @@ fn build_from_proto<'a>(
                         AccumConfig {
                             compute_bw: accum.compute_bw as u64,
                             write_back_mu: accum.write_back_mu,
+                            memory_unit_id: operation.id,
                         },
                         operation.id,
                     ));
                 }
                 (Type::F32(_), Type::U64(_)) => {
-                    let rcv = channel_map_collection.tile_f32.get_receiver(
+                    let rcv = channel_map_collection.tile_f32().get_receiver(
                         accum.input_id,
                         accum.stream_idx,
                         builder,
                         get_chan_depth(&sim_config.config_dict, accum.input_id, channel_depth),
                     );
-                    let snd = channel_map_collection.tile_u64.get_sender(
+                    let snd = channel_map_collection.tile_u64().get_sender(
                         operation.id,
                         None,
                         builder,
@@ -1774,7 +2751,14 @@ fn build_from_proto<'a>(
                                 )
                             })
                         }
-                        _ => todo!(),
+                        other => {
+                            errors.push(BuildError {
+                                operation_id: operation.id,
+                                op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                unsupported: format!("unsupported accumulation function for Accum operation (U64 input), got {}", discriminant_name(&other)),
+                            });
+                            continue 'operations;
+                        }
                     };
 
                     let tile_row = accum.tile_row as usize;
@@ -1793,11 +2777,23 @@ fn build_from_proto<'a>(
                         AccumConfig {
                             compute_bw: accum.compute_bw as u64,
                             write_back_mu: accum.write_back_mu,
+                            memory_unit_id: operation.id,
                         },
                         operation.id,
                     ));
                 }
-                _ => todo!(),
+                other => {
+                    errors.push(BuildError {
+                        operation_id: operation.id,
+                        op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                        unsupported: format!(
+                            "unsupported dtype combination for Accum operation, got ({}, {})",
+                            discriminant_name(&other.0),
+                            discriminant_name(&other.1),
+                        ),
+                    });
+                    continue 'operations;
+                }
             },
             OpType::RetileStreamify(retile_streamify) => {
                 match retile_streamify
@@ -1809,7 +2805,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::F32(_) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
+                        let rcv = channel_map_collection.tile_f32().get_receiver(
                             retile_streamify.input_id,
                             retile_streamify.stream_idx,
                             builder,
@@ -1819,7 +2815,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                        let snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -1830,14 +2826,26 @@ fn build_from_proto<'a>(
                             snd,
                             retile_streamify.split_row,
                             retile_streamify.filter_mask,
+                            // retile_streamify has no 2D block-shape fields yet, so every
+                            // graph still gets the original 1D row/column split until the
+                            // proto schema grows block_rows/block_cols.
+                            None,
+                            None,
                             operation.id,
                         ));
                     }
-                    _ => panic!("Unsupported data type for RetileStreamify operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for RetileStreamify operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::MetadataGen(metadata_gen) => {
-                let snd = channel_map_collection.tile_u64.get_sender(
+                let snd = channel_map_collection.tile_u64().get_sender(
                     operation.id,
                     None,
                     builder,
@@ -1858,7 +2866,14 @@ fn build_from_proto<'a>(
                             operation.id,
                         ));
                     }
-                    _ => panic!("Unsupported data type for MetadataGen operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for MetadataGen operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::ExpertAddrGen(expert_addr_gen) => {
@@ -1871,7 +2886,7 @@ fn build_from_proto<'a>(
                     .unwrap()
                 {
                     Type::MultiHot(_) => {
-                        let rcv = channel_map_collection.multihot.get_receiver(
+                        let rcv = channel_map_collection.multihot().get_receiver(
                             expert_addr_gen.input_id,
                             expert_addr_gen.input_stream_idx,
                             builder,
@@ -1881,7 +2896,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let snd = channel_map_collection.tile_u64.get_sender(
+                        let snd = channel_map_collection.tile_u64().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -1892,14 +2907,28 @@ fn build_from_proto<'a>(
                             snd,
                             expert_addr_gen.num_tile_per_expert as u64,
                             expert_addr_gen.expert_addr_base as u64,
+                            // expert_addr_gen has no bank-conflict-modeling fields
+                            // yet, so every graph still gets the original
+                            // one-address-per-cycle schedule until the proto
+                            // schema grows num_banks/bank_stride/window.
+                            None,
+                            None,
+                            None,
                             operation.id,
                         ));
                     }
-                    _ => panic!("Unsupported data type for ExpertAddrGen operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for ExpertAddrGen operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::CacheReadAddrGen(cache_read_addr_gen) => {
-                let idx_rcv = channel_map_collection.tile_u64.get_receiver(
+                let idx_rcv = channel_map_collection.tile_u64().get_receiver(
                     cache_read_addr_gen.idx_id,
                     cache_read_addr_gen.idx_stream_idx,
                     builder,
@@ -1909,7 +2938,7 @@ fn build_from_proto<'a>(
                         channel_depth,
                     ),
                 );
-                let seq_len_rcv = channel_map_collection.tile_u64.get_receiver(
+                let seq_len_rcv = channel_map_collection.tile_u64().get_receiver(
                     cache_read_addr_gen.seq_len_id,
                     cache_read_addr_gen.seq_len_stream_idx,
                     builder,
@@ -1919,7 +2948,7 @@ fn build_from_proto<'a>(
                         channel_depth,
                     ),
                 );
-                let snd = channel_map_collection.tile_u64.get_sender(
+                let snd = channel_map_collection.tile_u64().get_sender(
                     operation.id,
                     None,
                     builder,
@@ -1929,12 +2958,22 @@ fn build_from_proto<'a>(
                     idx_rcv,
                     seq_len_rcv,
                     cache_read_addr_gen.offset_per_idx,
+                    // `cache_read_addr_gen` has no paged-addressing fields
+                    // yet, so every graph still gets the original
+                    // contiguous addressing until the proto schema grows
+                    // `page_size`/a page-table stream id.
+                    None,
+                    None,
                     snd,
+                    // Likewise no bank-conflict-modeling fields yet.
+                    None,
+                    None,
+                    None,
                     operation.id,
                 ));
             }
             OpType::FilterLastTile(filter_last_tile) => {
-                let seq_len_rcv = channel_map_collection.tile_u64.get_receiver(
+                let seq_len_rcv = channel_map_collection.tile_u64().get_receiver(
                     filter_last_tile.seq_len_id,
                     filter_last_tile.seq_len_stream_idx,
                     builder,
@@ -1944,7 +2983,7 @@ fn build_from_proto<'a>(
                         channel_depth,
                     ),
                 );
-                let snd = channel_map_collection.multihot.get_sender(
+                let snd = channel_map_collection.multihot().get_sender(
                     operation.id,
                     None,
                     builder,
@@ -1955,7 +2994,7 @@ fn build_from_proto<'a>(
             OpType::Reshape(reshape) => {
                 match reshape.dtype.clone().unwrap().r#type.clone().unwrap() {
                     Type::F32(_) => {
-                        let rcv = channel_map_collection.tile_f32.get_receiver(
+                        let rcv = channel_map_collection.tile_f32().get_receiver(
                             reshape.input_id,
                             reshape.stream_idx,
                             builder,
@@ -1965,7 +3004,7 @@ fn build_from_proto<'a>(
                                 channel_depth,
                             ),
                         );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                        let snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             None,
                             builder,
@@ -1995,14 +3034,21 @@ fn build_from_proto<'a>(
                                             )
                                         }
                                     }
-                                    _ => todo!(),
+                                    other => {
+                                        errors.push(BuildError {
+                                            operation_id: operation.id,
+                                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                                            unsupported: format!("unsupported pad mode for Reshape operation, got {}", discriminant_name(&other)),
+                                        });
+                                        continue 'operations;
+                                    }
                                 };
                                 builder.add_child(Reshape::new(
                                     rcv,
                                     snd,
                                     reshape.split_dim as usize,
                                     reshape.chunk_size as usize,
-                                    Some(pad_val),
+                                    Some(PadMode::Constant(pad_val)),
                                     reshape.input_stream_rank,
                                     reshape.add_outer_dim,
                                     operation.id,
@@ -2022,7 +3068,14 @@ fn build_from_proto<'a>(
                             }
                         }
                     }
-                    _ => panic!("Unsupported data type for Reshape operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for Reshape operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
             OpType::EagerMerge(eager_merge) => {
@@ -2034,7 +3087,7 @@ fn build_from_proto<'a>(
                             .into_iter()
                             .zip(eager_merge.input_stream_idx_list.into_iter())
                         {
-                            let rcv = channel_map_collection.tile_f32.get_receiver(
+                            let rcv = channel_map_collection.tile_f32().get_receiver(
                                 rcv_id,
                                 if stream_idx < 0 {
                                     None
@@ -2047,13 +3100,13 @@ fn build_from_proto<'a>(
                             rcv_list.push(rcv);
                         }
 
-                        let sel_snd = channel_map_collection.multihot.get_sender(
+                        let sel_snd = channel_map_collection.multihot().get_sender(
                             operation.id,
                             Some(1),
                             builder,
                             get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
                         );
-                        let snd = channel_map_collection.tile_f32.get_sender(
+                        let snd = channel_map_collection.tile_f32().get_sender(
                             operation.id,
                             Some(0),
                             builder,
@@ -2074,7 +3127,7 @@ fn build_from_proto<'a>(
                             .into_iter()
                             .zip(eager_merge.input_stream_idx_list.into_iter())
                         {
-                            let rcv = channel_map_collection.tile_u64.get_receiver(
+                            let rcv = channel_map_collection.tile_u64().get_receiver(
                                 rcv_id,
                                 if stream_idx < 0 {
                                     None
@@ -2087,13 +3140,13 @@ fn build_from_proto<'a>(
                             rcv_list.push(rcv);
                         }
 
-                        let sel_snd = channel_map_collection.multihot.get_sender(
+                        let sel_snd = channel_map_collection.multihot().get_sender(
                             operation.id,
                             Some(1),
                             builder,
                             get_chan_depth(&sim_config.config_dict, operation.id, channel_depth),
                         );
-                        let snd = channel_map_collection.tile_u64.get_sender(
+                        let snd = channel_map_collection.tile_u64().get_sender(
                             operation.id,
                             Some(0),
                             builder,
@@ -2107,14 +3160,29 @@ fn build_from_proto<'a>(
                             operation.id,
                         ));
                     }
-                    _ => panic!("Unsupported data type for EagerMerge operation"),
+                    other => {
+                        errors.push(BuildError {
+                            operation_id: operation.id,
+                            op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                            unsupported: format!("unsupported data type for EagerMerge operation, got {}", discriminant_name(&other)),
+                        });
+                        continue 'operations;
+                    }
                 }
             }
-            _ => todo!(),
+            other => {
+                errors.push(BuildError {
+                    operation_id: operation.id,
+                    op_type: discriminant_name(&operation.op_type.clone().unwrap()),
+                    unsupported: format!("unsupported operation type, got {}", discriminant_name(&other)),
+                });
+                continue 'operations;
+            }
         }
     }
 
     builder.add_child(mem_context);
+    errors
 }
 
 pub fn parse_proto<'a>(
@@ -2123,17 +3191,45 @@ pub fn parse_proto<'a>(
     hbm_config: HBMConfig,
     sim_config: SimConfig,
     db_name: Option<String>,
-) -> (bool, u64, std::time::Duration) {
+) -> Result<RunReport, StepPerfError> {
+    if sim_config.validate {
+        let diagnostics = validate::validate_graph(&step_graph, &validate::default_rules());
+        let (errors, warnings): (Vec<_>, Vec<_>) = diagnostics
+            .into_iter()
+            .partition(|d| d.severity == validate::Severity::Error);
+
+        for warning in &warnings {
+            println!(
+                "graph validation warning: operator {}: {}",
+                warning.node_id, warning.message
+            );
+        }
+
+        if !errors.is_empty() {
+            return Err(StepPerfError::GraphValidation { diagnostics: errors });
+        }
+    }
+
     let mut builder = ProgramBuilder::default();
     let mut channel_map_collection = ChannelMapCollection::default();
-    build_from_proto(
+    let activity_log = ActivityLog::default();
+    let occupancy_log = OccupancyLog::default();
+    let build_errors = build_from_proto(
         step_graph,
         &mut channel_map_collection,
         &mut builder,
         &hbm_config,
         &sim_config,
+        &activity_log,
+        &occupancy_log,
     );
 
+    if !build_errors.is_empty() {
+        return Err(StepPerfError::UnsupportedOps {
+            errors: build_errors,
+        });
+    }
+
     let initialized = builder.initialize(Default::default()).unwrap();
     let run_options = match logging {
         true => {
@@ -2155,13 +3251,150 @@ pub fn parse_proto<'a>(
 
     // println!("{}", initialized.to_dot_string());
 
-    let start = Instant::now();
-    let executed = initialized.run(run_options);
-    let duration = start.elapsed();
+    if let Some(path) = &sim_config.log_file_path {
+        log_sink::install(
+            path,
+            sim_config
+                .log_buffer_size
+                .unwrap_or(log_sink::DEFAULT_LOG_BUFFER_SIZE),
+            sim_config.log_wall_clock_timestamps,
+        )
+        .map_err(|e| StepPerfError::Io { source: e })?;
+    }
+
+    let clock = sim_config.clock();
+    let before_rusage = run_report::sample();
+    let start = clock.now();
+    let executed = match sim_config.watchdog_timeout_ms {
+        Some(timeout_ms) => {
+            let (executed, deadlock) = crate::utils::watchdog::run_with_watchdog(
+                std::time::Duration::from_millis(timeout_ms),
+                activity_log.clone(),
+                || initialized.run(run_options),
+            );
+            if let Some(report) = deadlock {
+                println!("{report}");
+            }
+            executed
+        }
+        None => initialized.run(run_options),
+    };
+    let duration = clock.now().duration_since(start);
+    let after_rusage = run_report::sample();
+
+    if sim_config.log_file_path.is_some() {
+        log_sink::uninstall();
+    }
 
     println!("Duration: {:?}", duration);
 
     let cycles = executed.elapsed_cycles().unwrap();
     let passed = executed.passed();
-    (passed, cycles, duration)
+
+    let regression = match &sim_config.metrics_history_file {
+        Some(history_path) => {
+            let fp = metrics_history::fingerprint(&hbm_config, &sim_config);
+            let commit_hash = sim_config
+                .metrics_commit_hash
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string());
+            let threshold_pct = sim_config
+                .metrics_regression_threshold_pct
+                .unwrap_or(metrics_history::DEFAULT_REGRESSION_THRESHOLD_PCT);
+            let window = sim_config
+                .metrics_history_window
+                .unwrap_or(metrics_history::DEFAULT_HISTORY_WINDOW);
+            metrics_history::check_and_record(
+                std::path::Path::new(history_path),
+                &commit_hash,
+                &fp,
+                cycles,
+                duration,
+                threshold_pct,
+                window,
+            )
+            .map_err(|e| StepPerfError::Io { source: e })?
+        }
+        None => None,
+    };
+
+    let report = RunReport::new(
+        passed,
+        cycles,
+        duration,
+        before_rusage,
+        after_rusage,
+        regression,
+    );
+
+    if let Some(path) = &sim_config.html_report_path {
+        let channels = occupancy_log.snapshot();
+        let html = crate::utils::html_report::render(&report, &channels);
+        std::fs::write(path, html).map_err(|e| StepPerfError::Io { source: e })?;
+    }
+
+    Ok(report)
+}
+
+/// A [`BenchmarkReport`] over repeated [`parse_proto`] runs of the same
+/// configuration, plus the `cycles` every sample agreed on. Serializable so
+/// it can feed [`metrics_history::check_and_record`] directly (the report's
+/// `median` as `duration`, `cycles` as-is).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BenchmarkRunReport {
+    pub benchmark: BenchmarkReport,
+    pub cycles: u64,
+}
+
+/// Runs `step_graph`/`hbm_config`/`sim_config` through [`parse_proto`]
+/// `opts.warmup_iters + opts.sample_iters` times -- rebuilding and
+/// reinitializing the dataflow graph fresh each time, since a `dam`
+/// program can only be run once -- and reports wall-time statistics over
+/// the timed samples via [`BenchmarkRunner`]. A single `Instant` around one
+/// `run` is dominated by cold caches and one-off allocation noise; this
+/// discards a configurable warmup and folds the rest into min/median/mean/
+/// stddev instead.
+///
+/// Every iteration's `cycles` (warmup included) is recorded and compared:
+/// if any of them disagree, the runs aren't simulating the same thing and
+/// the wall-time comparison would be meaningless, so this returns
+/// [`StepPerfError::NondeterministicCycles`] rather than a report.
+pub fn run_benchmark(
+    step_graph: ProgramGraph,
+    logging: bool,
+    hbm_config: HBMConfig,
+    sim_config: SimConfig,
+    db_name: Option<String>,
+    opts: BenchmarkOpts,
+) -> Result<BenchmarkRunReport, StepPerfError> {
+    let observed_cycles: std::sync::Mutex<Vec<u64>> = std::sync::Mutex::new(Vec::new());
+
+    let runner = BenchmarkRunner::new(opts);
+    let benchmark = runner.run(
+        || {
+            let report = parse_proto(
+                step_graph.clone(),
+                logging,
+                hbm_config.clone(),
+                sim_config.clone(),
+                db_name.clone(),
+            )
+            .expect("run_benchmark: parse_proto failed on a benchmarked iteration");
+            observed_cycles.lock().unwrap().push(report.cycles);
+        },
+        |_, _| {},
+    );
+
+    let observed_cycles = observed_cycles.into_inner().unwrap();
+    let first_cycles = observed_cycles[0];
+    if observed_cycles.iter().any(|&c| c != first_cycles) {
+        return Err(StepPerfError::NondeterministicCycles {
+            cycles: observed_cycles,
+        });
+    }
+
+    Ok(BenchmarkRunReport {
+        benchmark,
+        cycles: first_cycles,
+    })
 }
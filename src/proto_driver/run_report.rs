@@ -0,0 +1,134 @@
+//! Per-run resource-usage accounting for [`crate::proto_driver::parse_proto`]:
+//! alongside simulated cycles and wall-clock duration, [`RunReport`] captures
+//! the simulator process's own CPU time, peak RSS, and context switches (via
+//! `getrusage(2)`, snapshotted immediately before and after
+//! `initialized.run(run_options)`) so a change in simulated cost can be told
+//! apart from a change in the simulator's own resource usage -- e.g. a 10x
+//! larger HBM config blowing up peak RSS rather than just taking longer.
+//!
+//! On non-Unix targets, where `getrusage` isn't available, the resource
+//! fields are `None` and only wall-clock timing is reported.
+
+use std::time::Duration;
+
+use crate::proto_driver::metrics_history::RegressionReport;
+
+/// Everything [`crate::proto_driver::parse_proto`] reports about one run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub passed: bool,
+    pub cycles: u64,
+    pub wall_duration: Duration,
+    /// Process user CPU time consumed by the run (`ru_utime` delta).
+    pub user_cpu: Option<Duration>,
+    /// Process system CPU time consumed by the run (`ru_stime` delta).
+    pub sys_cpu: Option<Duration>,
+    /// Peak resident set size of the whole process, in bytes
+    /// (`ru_maxrss`, not a delta -- rusage only ever reports the high-water
+    /// mark since process start).
+    pub peak_rss: Option<u64>,
+    /// Voluntary plus involuntary context switches during the run
+    /// (`ru_nvcsw + ru_nivcsw` delta).
+    pub ctx_switches: Option<u64>,
+    /// Comparison against `SimConfig::metrics_history_file`, if one was
+    /// configured. `None` when history tracking is off, or when this is
+    /// the first run recorded for its config fingerprint.
+    pub regression: Option<RegressionReport>,
+}
+
+impl RunReport {
+    /// Builds a report from `before`/`after` [`rusage::Sample`]s taken
+    /// around the run -- `None` for either (i.e. on a non-Unix target)
+    /// leaves every resource field `None`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        passed: bool,
+        cycles: u64,
+        wall_duration: Duration,
+        before: Option<rusage::Sample>,
+        after: Option<rusage::Sample>,
+        regression: Option<RegressionReport>,
+    ) -> Self {
+        match (before, after) {
+            (Some(before), Some(after)) => Self {
+                passed,
+                cycles,
+                wall_duration,
+                user_cpu: Some(after.user_cpu.saturating_sub(before.user_cpu)),
+                sys_cpu: Some(after.sys_cpu.saturating_sub(before.sys_cpu)),
+                peak_rss: Some(after.peak_rss),
+                ctx_switches: Some(after.ctx_switches.saturating_sub(before.ctx_switches)),
+                regression,
+            },
+            _ => Self {
+                passed,
+                cycles,
+                wall_duration,
+                user_cpu: None,
+                sys_cpu: None,
+                peak_rss: None,
+                ctx_switches: None,
+                regression,
+            },
+        }
+    }
+}
+
+pub(crate) use rusage::sample;
+
+#[cfg(unix)]
+mod rusage {
+    use std::time::Duration;
+
+    /// A `getrusage(RUSAGE_SELF)` snapshot, reduced to the fields
+    /// [`super::RunReport`] cares about.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Sample {
+        pub user_cpu: Duration,
+        pub sys_cpu: Duration,
+        pub peak_rss: u64,
+        pub ctx_switches: u64,
+    }
+
+    /// Snapshots the calling process's resource usage, or `None` if the
+    /// `getrusage(2)` call itself fails.
+    pub fn sample() -> Option<Sample> {
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+            return None;
+        }
+        Some(Sample {
+            user_cpu: timeval_to_duration(usage.ru_utime),
+            sys_cpu: timeval_to_duration(usage.ru_stime),
+            peak_rss: maxrss_to_bytes(usage.ru_maxrss),
+            ctx_switches: (usage.ru_nvcsw + usage.ru_nivcsw) as u64,
+        })
+    }
+
+    fn timeval_to_duration(tv: libc::timeval) -> Duration {
+        Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000)
+    }
+
+    /// `ru_maxrss` is kibibytes on Linux but bytes on macOS -- normalize
+    /// both to bytes so `RunReport::peak_rss` means the same thing
+    /// regardless of platform.
+    #[cfg(target_os = "macos")]
+    fn maxrss_to_bytes(raw: libc::c_long) -> u64 {
+        raw as u64
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn maxrss_to_bytes(raw: libc::c_long) -> u64 {
+        raw as u64 * 1024
+    }
+}
+
+#[cfg(not(unix))]
+mod rusage {
+    #[derive(Debug, Clone, Copy)]
+    pub struct Sample;
+
+    pub fn sample() -> Option<Sample> {
+        None
+    }
+}
@@ -0,0 +1,151 @@
+//! Declarative scenario suites: a single TOML file names several scenarios
+//! (pipeline parameters, input sizes, iteration counts, comparison mode) that
+//! a runner can enumerate and select from, instead of hardcoding
+//! `Default::default()` at every `initialize()`/`run()` call site.
+//!
+//! ```toml
+//! [[scenario]]
+//! name = "small_matmul"
+//! input_size = 256
+//! sample_iters = 20
+//! rel_eps = 1e-6
+//!
+//! [[scenario]]
+//! name = "large_matmul"
+//! input_size = 4096
+//! warmup_iters = 5
+//! sample_iters = 50
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::utils::benchmark::BenchmarkOpts;
+use crate::utils::comparator::Tolerance;
+
+/// One named scenario's parameters. Fields left unset in the TOML file fall
+/// back to the same defaults [`BenchmarkOpts`]/[`Tolerance`] already use.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub input_size: Option<usize>,
+    #[serde(default)]
+    pub channel_depth: Option<usize>,
+    #[serde(default)]
+    pub warmup_iters: Option<usize>,
+    #[serde(default)]
+    pub sample_iters: Option<usize>,
+    #[serde(default)]
+    pub abs_eps: Option<f64>,
+    #[serde(default)]
+    pub rel_eps: Option<f64>,
+    #[serde(default)]
+    pub max_ulps: Option<u64>,
+}
+
+impl Scenario {
+    /// The [`BenchmarkOpts`] this scenario implies, layered over the
+    /// runner's defaults.
+    pub fn benchmark_opts(&self) -> BenchmarkOpts {
+        let defaults = BenchmarkOpts::default();
+        BenchmarkOpts {
+            warmup_iters: self.warmup_iters.unwrap_or(defaults.warmup_iters),
+            sample_iters: self.sample_iters.unwrap_or(defaults.sample_iters),
+            threads: defaults.threads,
+        }
+    }
+
+    /// The [`Tolerance`] this scenario implies. A scenario with none of
+    /// `abs_eps`/`rel_eps`/`max_ulps` set falls back to exact equality.
+    pub fn tolerance(&self) -> Tolerance {
+        Tolerance {
+            abs_eps: self.abs_eps,
+            rel_eps: self.rel_eps,
+            max_ulps: self.max_ulps,
+            ..Default::default()
+        }
+    }
+}
+
+/// A suite of named [`Scenario`]s loaded from a TOML file.
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct ScenarioSuite {
+    #[serde(rename = "scenario", default)]
+    pub scenarios: Vec<Scenario>,
+}
+
+impl ScenarioSuite {
+    pub fn from_toml_str(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_toml_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Scenarios whose name contains `filter`, or every scenario if
+    /// `filter` is `None`.
+    pub fn select(&self, filter: Option<&str>) -> Vec<&Scenario> {
+        self.scenarios
+            .iter()
+            .filter(|s| match filter {
+                Some(f) => s.name.contains(f),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITE_TOML: &str = r#"
+        [[scenario]]
+        name = "small_matmul"
+        input_size = 256
+        sample_iters = 20
+        rel_eps = 1e-6
+
+        [[scenario]]
+        name = "large_matmul"
+        input_size = 4096
+        warmup_iters = 5
+    "#;
+
+    #[test]
+    fn parses_multiple_named_scenarios() {
+        let suite = ScenarioSuite::from_toml_str(SUITE_TOML).unwrap();
+        assert_eq!(suite.scenarios.len(), 2);
+        assert_eq!(suite.scenarios[0].name, "small_matmul");
+        assert_eq!(suite.scenarios[0].input_size, Some(256));
+    }
+
+    #[test]
+    fn select_filters_by_name_substring() {
+        let suite = ScenarioSuite::from_toml_str(SUITE_TOML).unwrap();
+        let selected = suite.select(Some("small"));
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "small_matmul");
+
+        assert_eq!(suite.select(None).len(), 2);
+    }
+
+    #[test]
+    fn unset_fields_fall_back_to_runner_defaults() {
+        let suite = ScenarioSuite::from_toml_str(SUITE_TOML).unwrap();
+        let large = &suite.scenarios[1];
+        let opts = large.benchmark_opts();
+        assert_eq!(opts.warmup_iters, 5);
+        assert_eq!(opts.sample_iters, BenchmarkOpts::default().sample_iters);
+
+        let tolerance = large.tolerance();
+        assert_eq!(tolerance.abs_eps, None);
+        assert_eq!(tolerance.rel_eps, None);
+    }
+}
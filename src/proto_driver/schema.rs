@@ -0,0 +1,109 @@
+//! Schema/feature compatibility handshake for a `ProgramGraph` handed to
+//! [`super::parse_proto`] through `run_graph`/`run_graph_async`.
+//!
+//! The proto IR itself doesn't carry a `schema_version`/feature-flag
+//! header yet -- that `.proto` schema lives in the frontend's IR crate,
+//! not this tree, so this file can't add fields to it. Until it does,
+//! callers pass the version and feature set they're relying on explicitly
+//! alongside the graph bytes, and this module checks it against what this
+//! build actually understands before a single byte gets decoded. Once the
+//! header carries these fields on the wire, `read_program_graph` can read
+//! them off the decoded `ProgramGraph` instead of trusting the caller.
+//!
+//! `SUPPORTED_FEATURES` is kept in lockstep with the `OpType` match arms
+//! `build_from_proto` actually implements in `proto_driver::mod` -- add a
+//! name here in the same commit that adds an arm there.
+
+use crate::utils::error::StepPerfError;
+
+pub const MIN_SUPPORTED_SCHEMA_VERSION: u32 = 1;
+pub const MAX_SUPPORTED_SCHEMA_VERSION: u32 = 1;
+
+/// One entry per `OpType` variant `build_from_proto` has a real match arm
+/// for today. Keep alphabetized to make gaps easy to spot on review.
+pub const SUPPORTED_FEATURES: &[&str] = &[
+    "accum",
+    "binarymap",
+    "binarymap_accum",
+    "broadcast",
+    "bufferize",
+    "cache_read_addr_gen",
+    "consumer_context",
+    "dyn_off_chip_load",
+    "dyn_streamify",
+    "eager_merge",
+    "expand_ref",
+    "expert_addr_gen",
+    "filter_last_tile",
+    "flat_partition",
+    "flat_reassemble",
+    "flatten",
+    "metadata_gen",
+    "off_chip_load",
+    "off_chip_store",
+    "parallelize",
+    "printer_context",
+    "promote",
+    "random_off_chip_load",
+    "random_off_chip_store",
+    "repeat_static",
+    "reshape",
+    "retile_streamify",
+    "select_gen",
+    "streamify",
+    "unarymap",
+];
+
+/// Checks a graph's declared `schema_version` and `required_features`
+/// against what this build supports, before the graph is built or run.
+///
+/// Returns [`StepPerfError::UnsupportedSchema`] if the version falls
+/// outside `MIN_SUPPORTED_SCHEMA_VERSION..=MAX_SUPPORTED_SCHEMA_VERSION`
+/// (covers both a too-new graph from a newer frontend and a too-old one
+/// this build no longer supports), or
+/// [`StepPerfError::MissingFeature`] for the first required feature this
+/// build wasn't compiled with.
+pub fn check_compatible(
+    schema_version: u32,
+    required_features: &[String],
+) -> Result<(), StepPerfError> {
+    if !(MIN_SUPPORTED_SCHEMA_VERSION..=MAX_SUPPORTED_SCHEMA_VERSION).contains(&schema_version) {
+        return Err(StepPerfError::UnsupportedSchema {
+            found: schema_version,
+            min: MIN_SUPPORTED_SCHEMA_VERSION,
+            max: MAX_SUPPORTED_SCHEMA_VERSION,
+        });
+    }
+
+    for feature in required_features {
+        if !SUPPORTED_FEATURES.contains(&feature.as_str()) {
+            return Err(StepPerfError::MissingFeature {
+                feature: feature.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_in_range_version_and_known_features() {
+        assert!(check_compatible(1, &["unarymap".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn rejects_out_of_range_version() {
+        let err = check_compatible(MAX_SUPPORTED_SCHEMA_VERSION + 1, &[]).unwrap_err();
+        assert!(matches!(err, StepPerfError::UnsupportedSchema { .. }));
+    }
+
+    #[test]
+    fn rejects_unknown_required_feature() {
+        let err = check_compatible(1, &["quantum_teleport".to_string()]).unwrap_err();
+        assert!(matches!(err, StepPerfError::MissingFeature { .. }));
+    }
+}
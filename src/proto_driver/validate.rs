@@ -0,0 +1,571 @@
+//! Static lint pass over a `ProgramGraph`, run before [`super::parse_proto`]
+//! builds and executes the dataflow network (gated by
+//! [`super::configs::SimConfig::validate`]). Catches malformed graphs --
+//! like a dangling `input_id`, or an `ExpandRef` that can't be wired up --
+//! as [`Diagnostic`]s instead of a runtime panic deep inside `run`.
+//!
+//! Coverage note: `ProgramGraph`'s `op_type` oneof has many more operator
+//! variants than the handful whose edges/ranks are wired up below. The
+//! rules here cover exactly the operators `build_from_proto` itself reads
+//! `input_id`-style fields from today (`UnaryMap`, `BinaryMap`,
+//! `EagerMerge`, `ExpandRef`); extending a rule to a new variant is a
+//! matter of adding a match arm to [`input_ids`]. [`NoUnbufferedCycles`]
+//! and [`UnreachableFromEntry`] (deadlock/dead-subgraph detection) build
+//! directly on that same edge set, so they inherit the same blind spots.
+
+use std::collections::HashMap;
+use std::thread;
+
+use crate::proto_driver::proto_headers::graph_proto::{operation::OpType, Operation, ProgramGraph};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub node_id: u32,
+    pub message: String,
+}
+
+/// Where a single [`GraphRule`] collects its findings. Each rule gets its
+/// own sink (rules run concurrently on separate threads), and
+/// [`validate_graph`] merges them afterwards.
+#[derive(Debug, Default)]
+pub struct DiagnosticSink {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticSink {
+    pub fn error(&mut self, node_id: u32, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            node_id,
+            message: message.into(),
+        });
+    }
+
+    pub fn warning(&mut self, node_id: u32, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            node_id,
+            message: message.into(),
+        });
+    }
+}
+
+pub trait GraphRule: Sync {
+    fn check(&self, graph: &ProgramGraph, cx: &mut DiagnosticSink);
+}
+
+/// Runs every rule concurrently (one thread per rule) and merges their
+/// diagnostics. Errors should abort the run; warnings are for logging.
+pub fn validate_graph(graph: &ProgramGraph, rules: &[Box<dyn GraphRule>]) -> Vec<Diagnostic> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = rules
+            .iter()
+            .map(|rule| {
+                let rule = rule.as_ref();
+                scope.spawn(move || {
+                    let mut sink = DiagnosticSink::default();
+                    rule.check(graph, &mut sink);
+                    sink.diagnostics
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    })
+}
+
+/// The default rule set run by [`super::parse_proto`].
+pub fn default_rules() -> Vec<Box<dyn GraphRule>> {
+    vec![
+        Box::new(SingleReceiverPerSender),
+        Box::new(NoDanglingInputs),
+        Box::new(ExpandRefRankSanity),
+        Box::new(UnconsumedOutputs),
+        Box::new(NoUnbufferedCycles),
+        Box::new(UnreachableFromEntry),
+    ]
+}
+
+/// The operation ids a given `op_type` declares as its inputs, for the
+/// handful of variants this file currently understands (see the module
+/// doc comment's coverage note).
+fn input_ids(op_type: &OpType) -> Vec<u32> {
+    match op_type {
+        OpType::Unarymap(unarymap) => vec![unarymap.input_id],
+        OpType::Binarymap(binary_map) => vec![binary_map.input_id1, binary_map.input_id2],
+        OpType::EagerMerge(eager_merge) => eager_merge.input_id_list.clone(),
+        OpType::ExpandRef(expand_ref) => vec![expand_ref.input_id, expand_ref.ref_id],
+        _ => Vec::new(),
+    }
+}
+
+/// Rule (1): every producer's `(id, stream_idx)` channel slot is consumed
+/// by at most one downstream operator. `ChannelMap` (see
+/// `build_sim::channel`) only ever creates a single sender/receiver pair per
+/// slot, so a second consumer referencing the same producer without an
+/// intervening `Broadcast` operator would panic deep in channel setup --
+/// catch it here instead.
+struct SingleReceiverPerSender;
+
+impl GraphRule for SingleReceiverPerSender {
+    fn check(&self, graph: &ProgramGraph, cx: &mut DiagnosticSink) {
+        for (producer_id, consumer_ids) in producer_to_consumers(graph) {
+            if consumer_ids.len() > 1 {
+                cx.error(
+                    producer_id,
+                    format!(
+                        "operator {producer_id}'s output is read by {} operators ({consumer_ids:?}) \
+                        with no Broadcast in between",
+                        consumer_ids.len()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Rule (2): no operator names an `input_id` that doesn't correspond to any
+/// operator actually present in the graph.
+struct NoDanglingInputs;
+
+impl GraphRule for NoDanglingInputs {
+    fn check(&self, graph: &ProgramGraph, cx: &mut DiagnosticSink) {
+        let known_ids: std::collections::HashSet<u32> =
+            graph.operators.iter().map(|op| op.id).collect();
+
+        for operation in &graph.operators {
+            let Some(op_type) = &operation.op_type else {
+                cx.error(operation.id, "operator has no op_type set");
+                continue;
+            };
+            for producer_id in input_ids(op_type) {
+                if !known_ids.contains(&producer_id) {
+                    cx.error(
+                        operation.id,
+                        format!("input_id {producer_id} does not name any operator in the graph"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Rule (4) (warning only): an operator whose output is never read by
+/// another operator's `input_id`. Operators that are legitimate sinks
+/// (off-chip stores, for instance) will always trip this -- it's a
+/// warning, not an error, for exactly that reason.
+struct UnconsumedOutputs;
+
+impl GraphRule for UnconsumedOutputs {
+    fn check(&self, graph: &ProgramGraph, cx: &mut DiagnosticSink) {
+        let mut consumed: std::collections::HashSet<u32> = std::collections::HashSet::new();
+        for operation in &graph.operators {
+            if let Some(op_type) = &operation.op_type {
+                consumed.extend(input_ids(op_type));
+            }
+        }
+
+        for operation in &graph.operators {
+            if !consumed.contains(&operation.id) {
+                cx.warning(operation.id, "output is never consumed by another operator");
+            }
+        }
+    }
+}
+
+/// Rule (3), scoped down: a full rank-consistency check needs a
+/// rank-propagation pass across the whole op catalog, since most operator
+/// variants in this IR don't carry an explicit output-rank field. This
+/// catches the op-local invariants that don't require that pass: `in_stream`
+/// and `ref_stream` must be different producers, and `expand_rank` must be
+/// at least 1 (an expand that introduces zero new levels is meaningless).
+struct ExpandRefRankSanity;
+
+impl GraphRule for ExpandRefRankSanity {
+    fn check(&self, graph: &ProgramGraph, cx: &mut DiagnosticSink) {
+        for operation in &graph.operators {
+            let Some(OpType::ExpandRef(expand_ref)) = &operation.op_type else {
+                continue;
+            };
+
+            if expand_ref.expand_rank == 0 {
+                cx.error(
+                    operation.id,
+                    "ExpandRef.expand_rank is 0, which introduces no new axis",
+                );
+            }
+
+            if expand_ref.input_id == expand_ref.ref_id {
+                cx.error(
+                    operation.id,
+                    format!(
+                        "ExpandRef's in_stream and ref_stream both come from operator {}",
+                        expand_ref.input_id
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Producer id -> ids of every operator that reads its output, i.e. the
+/// edges of the graph viewed as (sender context) -> (receiver context).
+fn producer_to_consumers(graph: &ProgramGraph) -> HashMap<u32, Vec<u32>> {
+    let mut edges: HashMap<u32, Vec<u32>> = HashMap::new();
+    for operation in &graph.operators {
+        let Some(op_type) = &operation.op_type else {
+            continue;
+        };
+        for producer_id in input_ids(op_type) {
+            edges.entry(producer_id).or_default().push(operation.id);
+        }
+    }
+    edges
+}
+
+/// Tarjan's algorithm, iterative to avoid blowing the stack on a large
+/// graph. Returns each strongly connected component as a `Vec<u32>` of
+/// node ids.
+fn strongly_connected_components(node_ids: &[u32], edges: &HashMap<u32, Vec<u32>>) -> Vec<Vec<u32>> {
+    struct State {
+        index: HashMap<u32, usize>,
+        lowlink: HashMap<u32, usize>,
+        on_stack: std::collections::HashSet<u32>,
+        stack: Vec<u32>,
+        next_index: usize,
+        components: Vec<Vec<u32>>,
+    }
+
+    enum Frame {
+        Enter(u32),
+        Exit(u32),
+    }
+
+    let mut state = State {
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        on_stack: std::collections::HashSet::new(),
+        stack: Vec::new(),
+        next_index: 0,
+        components: Vec::new(),
+    };
+
+    for &start in node_ids {
+        if state.index.contains_key(&start) {
+            continue;
+        }
+
+        let mut work: Vec<Frame> = vec![Frame::Enter(start)];
+        while let Some(frame) = work.pop() {
+            match frame {
+                Frame::Enter(node) => {
+                    if state.index.contains_key(&node) {
+                        continue;
+                    }
+                    state.index.insert(node, state.next_index);
+                    state.lowlink.insert(node, state.next_index);
+                    state.next_index += 1;
+                    state.stack.push(node);
+                    state.on_stack.insert(node);
+
+                    work.push(Frame::Exit(node));
+                    for &neighbor in edges.get(&node).map(Vec::as_slice).unwrap_or(&[]) {
+                        if !state.index.contains_key(&neighbor) {
+                            work.push(Frame::Enter(neighbor));
+                        } else if state.on_stack.contains(&neighbor) {
+                            let lower = state.index[&neighbor].min(state.lowlink[&node]);
+                            state.lowlink.insert(node, lower);
+                        }
+                    }
+                }
+                Frame::Exit(node) => {
+                    for &neighbor in edges.get(&node).map(Vec::as_slice).unwrap_or(&[]) {
+                        if state.on_stack.contains(&neighbor) {
+                            let lower = state.lowlink[&neighbor].min(state.lowlink[&node]);
+                            state.lowlink.insert(node, lower);
+                        }
+                    }
+
+                    if state.lowlink[&node] == state.index[&node] {
+                        let mut component = Vec::new();
+                        loop {
+                            let member = state.stack.pop().unwrap();
+                            state.on_stack.remove(&member);
+                            component.push(member);
+                            if member == node {
+                                break;
+                            }
+                        }
+                        state.components.push(component);
+                    }
+                }
+            }
+        }
+    }
+
+    state.components
+}
+
+/// Rule (5): a cycle of contexts that all block waiting on a peer's channel
+/// deadlocks unless something in the cycle can emit before it's consumed
+/// from. Of the operators this file understands, only `ExpandRef` is
+/// confirmed to do that (it emits a `ValStop`-expanded tile per input
+/// element rather than passing one token straight through) -- so a
+/// nontrivial SCC containing no `ExpandRef` is flagged.
+///
+/// This can't yet account for unbounded channels also breaking a cycle:
+/// that's a property of `SimConfig.channel_depth`/`config_dict`, which
+/// isn't available to a `GraphRule` (rules only see the `ProgramGraph`).
+struct NoUnbufferedCycles;
+
+impl GraphRule for NoUnbufferedCycles {
+    fn check(&self, graph: &ProgramGraph, cx: &mut DiagnosticSink) {
+        let node_ids: Vec<u32> = graph.operators.iter().map(|op| op.id).collect();
+        let edges = producer_to_consumers(graph);
+        let op_types: HashMap<u32, &OpType> = graph
+            .operators
+            .iter()
+            .filter_map(|op| op.op_type.as_ref().map(|op_type| (op.id, op_type)))
+            .collect();
+
+        for component in strongly_connected_components(&node_ids, &edges) {
+            let is_cycle = component.len() > 1
+                || edges
+                    .get(&component[0])
+                    .is_some_and(|consumers| consumers.contains(&component[0]));
+            if !is_cycle {
+                continue;
+            }
+
+            let has_buffer = component
+                .iter()
+                .any(|id| matches!(op_types.get(id), Some(OpType::ExpandRef(_))));
+
+            if !has_buffer {
+                let mut cycle_ids = component.clone();
+                cycle_ids.sort_unstable();
+                cx.error(
+                    cycle_ids[0],
+                    format!(
+                        "potential deadlock: cycle {cycle_ids:?} has no buffering element \
+                        to break the cyclic wait"
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Rule (6) (warning only): contexts unreachable from any graph input --
+/// a dead subgraph that would hang waiting on a token nobody produces.
+/// "Input" here means an operator this file sees no recorded input edge
+/// for; given the coverage note above, that includes both true sources
+/// (off-chip loads, generators) and operators of variants `input_ids`
+/// doesn't look inside yet, so this rule can under-flag on graphs using
+/// those.
+struct UnreachableFromEntry;
+
+impl GraphRule for UnreachableFromEntry {
+    fn check(&self, graph: &ProgramGraph, cx: &mut DiagnosticSink) {
+        let edges = producer_to_consumers(graph);
+        let has_recorded_input: std::collections::HashSet<u32> = graph
+            .operators
+            .iter()
+            .filter(|op| {
+                op.op_type
+                    .as_ref()
+                    .is_some_and(|op_type| !input_ids(op_type).is_empty())
+            })
+            .map(|op| op.id)
+            .collect();
+
+        let entries: Vec<u32> = graph
+            .operators
+            .iter()
+            .map(|op| op.id)
+            .filter(|id| !has_recorded_input.contains(id))
+            .collect();
+
+        let mut reachable: std::collections::HashSet<u32> = entries.iter().copied().collect();
+        let mut frontier = entries;
+        while let Some(node) = frontier.pop() {
+            for &neighbor in edges.get(&node).map(Vec::as_slice).unwrap_or(&[]) {
+                if reachable.insert(neighbor) {
+                    frontier.push(neighbor);
+                }
+            }
+        }
+
+        for operation in &graph.operators {
+            if !reachable.contains(&operation.id) {
+                cx.warning(
+                    operation.id,
+                    "unreachable from any graph input; this subgraph will never run",
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(id: u32, op_type: OpType) -> Operation {
+        Operation {
+            id,
+            op_type: Some(op_type),
+        }
+    }
+
+    #[test]
+    fn flags_dangling_input_id() {
+        let graph = ProgramGraph {
+            operators: vec![op(
+                1,
+                OpType::Unarymap(crate::proto_driver::proto_headers::graph_proto::UnaryMap {
+                    input_id: 99,
+                    ..Default::default()
+                }),
+            )],
+        };
+
+        let diagnostics = validate_graph(&graph, &[Box::new(NoDanglingInputs)]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].node_id, 1);
+    }
+
+    #[test]
+    fn flags_expand_ref_self_reference_and_zero_rank() {
+        let graph = ProgramGraph {
+            operators: vec![op(
+                1,
+                OpType::ExpandRef(crate::proto_driver::proto_headers::graph_proto::ExpandRef {
+                    input_id: 7,
+                    ref_id: 7,
+                    expand_rank: 0,
+                    ..Default::default()
+                }),
+            )],
+        };
+
+        let diagnostics = validate_graph(&graph, &[Box::new(ExpandRefRankSanity)]);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+    }
+
+    #[test]
+    fn flags_cycle_with_no_buffering_element() {
+        // 1 <-> 2 via plain Unarymaps: a two-node cycle with no ExpandRef
+        // anywhere in it to break the cyclic wait.
+        let graph = ProgramGraph {
+            operators: vec![
+                op(
+                    1,
+                    OpType::Unarymap(crate::proto_driver::proto_headers::graph_proto::UnaryMap {
+                        input_id: 2,
+                        ..Default::default()
+                    }),
+                ),
+                op(
+                    2,
+                    OpType::Unarymap(crate::proto_driver::proto_headers::graph_proto::UnaryMap {
+                        input_id: 1,
+                        ..Default::default()
+                    }),
+                ),
+            ],
+        };
+
+        let diagnostics = validate_graph(&graph, &[Box::new(NoUnbufferedCycles)]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn expand_ref_breaks_an_otherwise_flagged_cycle() {
+        let graph = ProgramGraph {
+            operators: vec![
+                op(
+                    1,
+                    OpType::ExpandRef(crate::proto_driver::proto_headers::graph_proto::ExpandRef {
+                        input_id: 2,
+                        ref_id: 2,
+                        expand_rank: 1,
+                        ..Default::default()
+                    }),
+                ),
+                op(
+                    2,
+                    OpType::Unarymap(crate::proto_driver::proto_headers::graph_proto::UnaryMap {
+                        input_id: 1,
+                        ..Default::default()
+                    }),
+                ),
+            ],
+        };
+
+        let diagnostics = validate_graph(&graph, &[Box::new(NoUnbufferedCycles)]);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn warns_on_unreachable_subgraph() {
+        // Operator 2 is a true source (no input_id at all), operator 1
+        // consumes it and is reachable, and operator 3 is a dead island
+        // that nothing produces into.
+        let graph = ProgramGraph {
+            operators: vec![
+                op(
+                    2,
+                    OpType::OffChipLoad(
+                        crate::proto_driver::proto_headers::graph_proto::OffChipLoad::default(),
+                    ),
+                ),
+                op(
+                    1,
+                    OpType::Unarymap(crate::proto_driver::proto_headers::graph_proto::UnaryMap {
+                        input_id: 2,
+                        ..Default::default()
+                    }),
+                ),
+                op(
+                    3,
+                    OpType::Unarymap(crate::proto_driver::proto_headers::graph_proto::UnaryMap {
+                        input_id: 99,
+                        ..Default::default()
+                    }),
+                ),
+            ],
+        };
+
+        let diagnostics = validate_graph(&graph, &[Box::new(UnreachableFromEntry)]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].node_id, 3);
+    }
+
+    #[test]
+    fn warns_on_unconsumed_output() {
+        let graph = ProgramGraph {
+            operators: vec![op(
+                1,
+                OpType::Unarymap(crate::proto_driver::proto_headers::graph_proto::UnaryMap {
+                    input_id: 1,
+                    ..Default::default()
+                }),
+            )],
+        };
+
+        let diagnostics = validate_graph(&graph, &[Box::new(UnconsumedOutputs)]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+}
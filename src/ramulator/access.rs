@@ -0,0 +1,26 @@
+use dam::types::DAMType;
+use half::f16;
+
+/// One burst's worth of payload exchanged over Ramulator's split
+/// address/data write channel (see
+/// [`crate::memory::store_backend::RamulatorBackend`]). Only the `F16`
+/// burst shape that backend currently issues exists here; other element
+/// widths can be added as new variants once something drives them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MemoryData {
+    F16([f16; 32]),
+}
+
+impl Default for MemoryData {
+    fn default() -> Self {
+        MemoryData::F16([f16::from_f32(0.0); 32])
+    }
+}
+
+impl DAMType for MemoryData {
+    fn dam_size(&self) -> usize {
+        match self {
+            MemoryData::F16(beat) => std::mem::size_of_val(beat),
+        }
+    }
+}
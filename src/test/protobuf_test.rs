@@ -27,7 +27,7 @@ mod test {
     use crate::proto_driver::proto_headers::graph_proto::{
         data_type::Type, elemto_elem_func, operation::OpType, ProgramGraph,
     };
-    use crate::ramulator::hbm_context::{HBMConfig, HBMContext, ReadBundle, WriteBundle};
+    use crate::ramulator::hbm_context::{AddressMapping, HBMConfig, HBMContext, ReadBundle, WriteBundle};
     use crate::utils::{cast::to_usize_vec, events::SimpleEvent};
 
     #[test]
@@ -42,7 +42,7 @@ mod test {
 
         println!("Successfully read proto file");
 
-        let (passed, cycles, duration) = parse_proto(
+        let report = parse_proto(
             step_graph,
             logging,
             HBMConfig {
@@ -52,19 +52,48 @@ mod test {
                 per_channel_init_interval: 2,
                 per_channel_outstanding: 1,
                 per_channel_start_up_time: 14,
+                bank_num: 1,
+                row_size_bytes: 64,
+                row_conflict_penalty: 0,
+                address_mapping: AddressMapping::Linear,
             },
             SimConfig {
                 channel_depth: Some(16),
                 functional_sim: true,
                 mock_bf16: true,
                 config_dict: HashMap::new(),
+                validate: false,
+                mock_clock_step_ms: None,
+                par_dispatch_overrides: HashMap::new(),
+                store_max_inflight: 1,
+                trace_channel_ids: std::collections::HashSet::new(),
+                trace_data_file: None,
+                trace_buffer_size: 256,
+                trace_max_file_size: 64 * 1024 * 1024,
+                switch_cycles_overrides: HashMap::new(),
+                write_back_mu_overrides: HashMap::new(),
+                golden_capture_ids: HashMap::new(),
+                golden_compare_ids: HashMap::new(),
+                log_file_path: None,
+                metrics_history_file: None,
+                metrics_commit_hash: None,
+                metrics_regression_threshold_pct: None,
+                metrics_history_window: None,
+                watchdog_timeout_ms: None,
+                html_report_path: None,
+                log_buffer_size: None,
+                log_wall_clock_timestamps: false,
+                verify_store_writes: false,
+                allow_store_overwrite: false,
+                storage_format_overrides: HashMap::new(),
             },
             db_name,
-        );
+        )
+        .unwrap();
 
         println!(
             "Passed: {}, Elapsed Cycles: {}, Duration: {:?}",
-            passed, cycles, duration
+            report.passed, report.cycles, report.wall_duration
         );
     }
 }
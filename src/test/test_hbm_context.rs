@@ -7,10 +7,10 @@ mod test {
     use crate::memory::offchip_store::OffChipStore;
 
     use crate::functions::{map_accum_fn, map_fn};
-    use crate::operator::map::BinaryMap;
+    use crate::operator::map::{BinaryMap, BinaryMapConfig};
 
     use crate::operator::repeat::RepeatStatic;
-    use crate::ramulator::hbm_context::{HBMConfig, HBMContext, ReadBundle, WriteBundle};
+    use crate::ramulator::hbm_context::{AddressMapping, HBMConfig, HBMContext, ReadBundle, WriteBundle};
 
     use crate::utils::events::{SimpleEvent, DUMMY_ID};
     use dam::simulation::{DotConvertible, RunOptions};
@@ -86,6 +86,9 @@ mod test {
             tile_m_gen_q,
             tile_k_gen_q,
             n_byte as usize,
+            false,
+            None,
+            None,
             tensor_addrs.get("Input").unwrap().clone() as u64,
             ADDR_OFFSET,
             PAR_DISPATCH,
@@ -121,6 +124,9 @@ mod test {
             tile_k_gen_q,
             tile_n_gen_q,
             n_byte as usize,
+            false,
+            None,
+            None,
             tensor_addrs.get("W_Q").unwrap().clone() as u64,
             ADDR_OFFSET,
             PAR_DISPATCH,
@@ -159,22 +165,31 @@ mod test {
             Arc::new(|tile1, tile2, comp_bw, write_back_mu| {
                 map_fn::matmul(tile1, tile2, comp_bw, write_back_mu, false)
             }),
-            1022,
-            true,
+            BinaryMapConfig {
+                compute_bw: 1022,
+                write_back_mu: true,
+                bandwidth: Default::default(),
+                memory_unit_id: 0,
+                energy: Default::default(),
+                overlap_model: Default::default(),
+            },
             3,
         );
 
         // ====================== Store Context ======================
         let (waddr_snd, waddr_rcv) = ctx.unbounded();
         let (ack_snd, ack_rcv) = ctx.unbounded();
-        let store_ctx = OffChipStore::<SimpleEvent, f32>::new(
+        let store_ctx = OffChipStore::<SimpleEvent, f32, _>::new(
             vec![B / tile_m_gen_q, H / tile_n_gen_q],
             tile_m_gen_q,
             tile_n_gen_q,
             None, //Some("./step-perf/output.npy".to_string()),
+            false,
+            None,
             tensor_addrs.get("Output").unwrap().clone() as u64,
             ADDR_OFFSET,
             PAR_DISPATCH,
+            1,
             mm_rcv,
             waddr_snd,
             ack_rcv,
@@ -192,6 +207,10 @@ mod test {
                 per_channel_init_interval: 2,
                 per_channel_outstanding: 1, // For now, this does not have any effect
                 per_channel_start_up_time: 14, // Time to wait before the first request can be processed
+                bank_num: 1, // Single bank: every access contends on the same timeline.
+                row_size_bytes: ADDR_OFFSET,
+                row_conflict_penalty: 0,
+                address_mapping: AddressMapping::Linear,
             },
         );
         mem_context.add_reader(ReadBundle {
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use arrow::array::{Array, Float32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+
+use crate::operator::arrow_csf::CsfTensor;
+use crate::primitives::tile::Tile;
+
+const META_SHAPE: &str = "step_perf.shape";
+const META_OFFSET: &str = "step_perf.offset";
+const META_BYTES_PER_ELEM: &str = "step_perf.bytes_per_elem";
+const META_READ_FROM_MU: &str = "step_perf.read_from_mu";
+const META_WRITE_BACK_MU: &str = "step_perf.write_back_mu";
+
+fn shape_to_string(shape: &[usize]) -> String {
+    shape
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn shape_from_string(s: &str) -> Vec<usize> {
+    s.split(',').map(|d| d.parse().unwrap()).collect()
+}
+
+/// Encodes a tile as a single-column Arrow `RecordBatch` (the tile's data
+/// flattened row-major into an f32 column) and serializes it with Arrow's
+/// streaming IPC format. `shape`, `offset`, `bytes_per_elem`,
+/// `read_from_mu`, and `write_back_mu` are stashed in the schema's custom
+/// key/value metadata so `tile_from_ipc` can reconstruct the `Tile` exactly.
+pub fn tile_to_ipc(tile: &Tile<f32>, write_back_mu: bool) -> Vec<u8> {
+    let values: Vec<f32> = match &tile.underlying {
+        Some(arr) => arr.iter().copied().collect(),
+        None => vec![],
+    };
+
+    let mut metadata = std::collections::HashMap::new();
+    metadata.insert(META_SHAPE.to_string(), shape_to_string(&tile.shape));
+    metadata.insert(META_OFFSET.to_string(), tile.offset.to_string());
+    metadata.insert(
+        META_BYTES_PER_ELEM.to_string(),
+        tile.bytes_per_elem.to_string(),
+    );
+    metadata.insert(
+        META_READ_FROM_MU.to_string(),
+        tile.read_from_mu.to_string(),
+    );
+    metadata.insert(META_WRITE_BACK_MU.to_string(), write_back_mu.to_string());
+
+    let schema = Arc::new(
+        Schema::new(vec![Field::new("value", DataType::Float32, false)])
+            .with_metadata(metadata),
+    );
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(Float32Array::from(values))])
+        .expect("tile column should match its own schema");
+
+    let mut buf = Vec::new();
+    {
+        let mut writer =
+            StreamWriter::try_new(&mut buf, &schema).expect("failed to open Arrow IPC stream");
+        writer.write(&batch).expect("failed to write tile batch");
+        writer.finish().expect("failed to finish Arrow IPC stream");
+    }
+    buf
+}
+
+/// Inverse of [`tile_to_ipc`]: decodes an Arrow IPC stream produced by it
+/// back into a `Tile<f32>` with its original shape, offset, and flags.
+pub fn tile_from_ipc(bytes: &[u8]) -> Tile<f32> {
+    let mut reader =
+        StreamReader::try_new(bytes, None).expect("failed to open Arrow IPC stream");
+    let schema = reader.schema();
+    let metadata = schema.metadata();
+
+    let shape = shape_from_string(&metadata[META_SHAPE]);
+    let offset: usize = metadata[META_OFFSET].parse().unwrap();
+    let bytes_per_elem: usize = metadata[META_BYTES_PER_ELEM].parse().unwrap();
+    let read_from_mu: bool = metadata[META_READ_FROM_MU].parse().unwrap();
+
+    let batch = reader
+        .next()
+        .expect("Arrow IPC stream had no record batches")
+        .expect("failed to read tile batch");
+    let values = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<Float32Array>()
+        .expect("tile column should be Float32Array");
+
+    if values.is_empty() {
+        return Tile::new_blank_padded(shape, bytes_per_elem, read_from_mu, offset);
+    }
+
+    let arr = ndarray::Array2::from_shape_vec(
+        (shape[0], shape[1]),
+        values.values().to_vec(),
+    )
+    .expect("flattened column should match tile shape");
+    Tile::new_padded(arr.to_shared(), bytes_per_elem, read_from_mu, offset)
+}
+
+const META_LEVEL_LENS: &str = "step_perf.csf.level_lens";
+
+/// Encodes a flat `u64` column (one of a [`CsfTensor`]'s `indptr`/`indices`
+/// levels, concatenated) as a single-column Arrow IPC stream, the same way
+/// [`tile_to_ipc`] encodes a tile's values. `level_lens` (each level's
+/// original length, so the concatenation can be split back apart) rides
+/// along in the schema metadata.
+fn u64_column_to_ipc(flat: &[usize], level_lens: &[usize]) -> Vec<u8> {
+    let mut metadata = std::collections::HashMap::new();
+    metadata.insert(
+        META_LEVEL_LENS.to_string(),
+        level_lens
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    let schema = Arc::new(
+        Schema::new(vec![Field::new("value", DataType::UInt64, false)]).with_metadata(metadata),
+    );
+    let array: UInt64Array = flat.iter().map(|&v| v as u64).collect();
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(array)])
+        .expect("csf column should match its own schema");
+
+    let mut buf = Vec::new();
+    {
+        let mut writer =
+            StreamWriter::try_new(&mut buf, &schema).expect("failed to open Arrow IPC stream");
+        writer.write(&batch).expect("failed to write csf column");
+        writer.finish().expect("failed to finish Arrow IPC stream");
+    }
+    buf
+}
+
+/// Inverse of [`u64_column_to_ipc`]: returns the per-level arrays, split back
+/// apart using the lengths stashed in the stream's schema metadata.
+fn u64_column_from_ipc(bytes: &[u8]) -> Vec<Vec<usize>> {
+    let mut reader =
+        StreamReader::try_new(bytes, None).expect("failed to open Arrow IPC stream");
+    let schema = reader.schema();
+    let level_lens: Vec<usize> = schema.metadata()[META_LEVEL_LENS]
+        .split(',')
+        .map(|d| d.parse().unwrap())
+        .collect();
+
+    let batch = reader
+        .next()
+        .expect("Arrow IPC stream had no record batches")
+        .expect("failed to read csf column");
+    let array = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<UInt64Array>()
+        .expect("csf column should be UInt64Array");
+
+    let mut flat = array.values().iter().map(|&v| v as usize);
+    level_lens
+        .into_iter()
+        .map(|len| (&mut flat).take(len).collect())
+        .collect()
+}
+
+fn write_len_prefixed(bytes: Vec<u8>, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(&bytes);
+}
+
+fn read_len_prefixed<'a>(bytes: &'a [u8], cursor: &mut usize) -> &'a [u8] {
+    let len = u32::from_le_bytes(bytes[*cursor..*cursor + 4].try_into().unwrap()) as usize;
+    *cursor += 4;
+    let chunk = &bytes[*cursor..*cursor + len];
+    *cursor += len;
+    chunk
+}
+
+/// Encodes a [`CsfTensor<f32>`] as three length-prefixed, single-column
+/// Arrow IPC streams concatenated together -- `indptr` and `indices` (each
+/// flattened across levels, with per-level lengths in their stream's schema
+/// metadata) followed by `values` -- since a single Arrow `RecordBatch`
+/// requires every column to share one row count, which `indptr`/`indices`/
+/// `values` don't. Mirrors [`tile_to_ipc`]'s single-column-per-stream
+/// approach rather than introducing a new framing scheme.
+pub fn csf_tensor_to_ipc(tensor: &CsfTensor<f32>) -> Vec<u8> {
+    let indptr_lens: Vec<usize> = tensor.indptr.iter().map(|level| level.len()).collect();
+    let indptr_flat: Vec<usize> = tensor.indptr.iter().flatten().copied().collect();
+    let indices_lens: Vec<usize> = tensor.indices.iter().map(|level| level.len()).collect();
+    let indices_flat: Vec<usize> = tensor.indices.iter().flatten().copied().collect();
+
+    let values_schema = Arc::new(Schema::new(vec![Field::new(
+        "value",
+        DataType::Float32,
+        false,
+    )]));
+    let values_batch = RecordBatch::try_new(
+        values_schema.clone(),
+        vec![Arc::new(Float32Array::from(tensor.values.clone()))],
+    )
+    .expect("csf values should match its own schema");
+    let mut values_buf = Vec::new();
+    {
+        let mut writer = StreamWriter::try_new(&mut values_buf, &values_schema)
+            .expect("failed to open Arrow IPC stream");
+        writer
+            .write(&values_batch)
+            .expect("failed to write csf values");
+        writer.finish().expect("failed to finish Arrow IPC stream");
+    }
+
+    let mut out = Vec::new();
+    write_len_prefixed(u64_column_to_ipc(&indptr_flat, &indptr_lens), &mut out);
+    write_len_prefixed(u64_column_to_ipc(&indices_flat, &indices_lens), &mut out);
+    write_len_prefixed(values_buf, &mut out);
+    out
+}
+
+/// Inverse of [`csf_tensor_to_ipc`].
+pub fn csf_tensor_from_ipc(bytes: &[u8]) -> CsfTensor<f32> {
+    let mut cursor = 0;
+    let indptr = u64_column_from_ipc(read_len_prefixed(bytes, &mut cursor));
+    let indices = u64_column_from_ipc(read_len_prefixed(bytes, &mut cursor));
+    let values_bytes = read_len_prefixed(bytes, &mut cursor);
+
+    let mut reader =
+        StreamReader::try_new(values_bytes, None).expect("failed to open Arrow IPC stream");
+    let batch = reader
+        .next()
+        .expect("Arrow IPC stream had no record batches")
+        .expect("failed to read csf values");
+    let values = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<Float32Array>()
+        .expect("csf values column should be Float32Array")
+        .values()
+        .to_vec();
+
+    CsfTensor {
+        indptr,
+        indices,
+        values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let arr = ndarray::Array2::from_shape_fn((2, 3), |(i, j)| (i * 3 + j) as f32);
+        let tile = Tile::new_padded(arr.to_shared(), 4, true, 2);
+
+        let bytes = tile_to_ipc(&tile, false);
+        let round_tripped = tile_from_ipc(&bytes);
+
+        assert_eq!(round_tripped.shape, tile.shape);
+        assert_eq!(round_tripped.offset, tile.offset);
+        assert_eq!(round_tripped.bytes_per_elem, tile.bytes_per_elem);
+        assert_eq!(round_tripped.read_from_mu, tile.read_from_mu);
+        assert_eq!(round_tripped.underlying, tile.underlying);
+    }
+
+    #[test]
+    fn test_csf_tensor_roundtrip() {
+        let tensor = CsfTensor {
+            indptr: vec![vec![0, 2], vec![0, 2, 4]],
+            indices: vec![vec![0, 1], vec![0, 1, 0, 1]],
+            values: vec![0.0, 1.0, 2.0, 3.0],
+        };
+
+        let bytes = csf_tensor_to_ipc(&tensor);
+        let round_tripped = csf_tensor_from_ipc(&bytes);
+
+        assert_eq!(round_tripped, tensor);
+    }
+}
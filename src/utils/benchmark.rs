@@ -0,0 +1,210 @@
+//! Statistical microbenchmark harness around the `initialize().run()`
+//! lifecycle (see [`crate::proto_driver::parse_proto`]), for measuring
+//! throughput/latency rather than just correctness.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Configuration for [`BenchmarkRunner`].
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkOpts {
+    /// Untimed iterations run first, to let caches/allocators warm up.
+    pub warmup_iters: usize,
+    /// Timed iterations collected into the report.
+    pub sample_iters: usize,
+    /// Worker threads to spread the timed samples across. `1` (the
+    /// default) runs every sample on the calling thread.
+    pub threads: usize,
+}
+
+impl Default for BenchmarkOpts {
+    fn default() -> Self {
+        Self {
+            warmup_iters: 3,
+            sample_iters: 10,
+            threads: 1,
+        }
+    }
+}
+
+/// Wraps a caller-supplied graph-construction-and-run closure to produce a
+/// reproducible microbenchmark: a warmup phase, then `sample_iters` timed
+/// samples (optionally spread across `threads` worker threads), folded into
+/// a [`BenchmarkReport`].
+pub struct BenchmarkRunner {
+    opts: BenchmarkOpts,
+}
+
+impl BenchmarkRunner {
+    pub fn new(opts: BenchmarkOpts) -> Self {
+        Self { opts }
+    }
+
+    /// Runs `factory` (which should build a fresh graph, `initialize()`,
+    /// and `run()` it -- a `dam` program can only be run once) for the
+    /// configured warmup and sample counts, invoking `on_sample` after each
+    /// completed timed sample with its index and duration.
+    pub fn run<F>(&self, factory: F, on_sample: impl Fn(usize, Duration) + Sync) -> BenchmarkReport
+    where
+        F: Fn() + Sync,
+    {
+        for _ in 0..self.opts.warmup_iters {
+            factory();
+        }
+
+        let threads = self.opts.threads.max(1);
+        let samples = if threads == 1 {
+            (0..self.opts.sample_iters)
+                .map(|i| {
+                    let elapsed = time_one(&factory);
+                    on_sample(i, elapsed);
+                    elapsed
+                })
+                .collect::<Vec<_>>()
+        } else {
+            thread::scope(|scope| {
+                let chunks = split_evenly(self.opts.sample_iters, threads);
+                let mut offset = 0;
+                let handles: Vec<_> = chunks
+                    .into_iter()
+                    .map(|count| {
+                        let start = offset;
+                        offset += count;
+                        let factory = &factory;
+                        let on_sample = &on_sample;
+                        scope.spawn(move || {
+                            (0..count)
+                                .map(|i| {
+                                    let elapsed = time_one(factory);
+                                    on_sample(start + i, elapsed);
+                                    elapsed
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|h| h.join().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        BenchmarkReport::from_samples(samples)
+    }
+}
+
+fn time_one(factory: impl Fn()) -> Duration {
+    let start = Instant::now();
+    factory();
+    start.elapsed()
+}
+
+fn split_evenly(total: usize, buckets: usize) -> Vec<usize> {
+    let base = total / buckets;
+    let remainder = total % buckets;
+    (0..buckets)
+        .map(|i| base + usize::from(i < remainder))
+        .collect()
+}
+
+/// Summary statistics over a [`BenchmarkRunner`] run's timed samples.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BenchmarkReport {
+    pub samples: Vec<Duration>,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl BenchmarkReport {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(
+            !samples.is_empty(),
+            "BenchmarkRunner: sample_iters must be at least 1"
+        );
+        samples.sort();
+
+        let nanos: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        let mean_nanos = nanos.iter().sum::<f64>() / nanos.len() as f64;
+        let variance = nanos
+            .iter()
+            .map(|n| (n - mean_nanos).powi(2))
+            .sum::<f64>()
+            / nanos.len() as f64;
+
+        Self {
+            min: samples[0],
+            max: samples[samples.len() - 1],
+            median: samples[samples.len() / 2],
+            mean: Duration::from_nanos(mean_nanos.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+            samples,
+        }
+    }
+}
+
+impl std::fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} samples", self.samples.len())?;
+        writeln!(f, "  mean:   {:?}", self.mean)?;
+        writeln!(f, "  median: {:?}", self.median)?;
+        writeln!(f, "  stddev: {:?}", self.stddev)?;
+        writeln!(f, "  min:    {:?}", self.min)?;
+        write!(f, "  max:    {:?}", self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn collects_the_configured_number_of_samples() {
+        let runner = BenchmarkRunner::new(BenchmarkOpts {
+            warmup_iters: 2,
+            sample_iters: 5,
+            threads: 1,
+        });
+        let calls = AtomicUsize::new(0);
+        let report = runner.run(|| { calls.fetch_add(1, Ordering::SeqCst); }, |_, _| {});
+
+        assert_eq!(report.samples.len(), 5);
+        assert_eq!(calls.load(Ordering::SeqCst), 2 + 5);
+        assert!(report.min <= report.median && report.median <= report.max);
+    }
+
+    #[test]
+    fn progress_callback_fires_once_per_sample() {
+        let runner = BenchmarkRunner::new(BenchmarkOpts {
+            warmup_iters: 0,
+            sample_iters: 4,
+            threads: 1,
+        });
+        let progress_calls = AtomicUsize::new(0);
+        runner.run(
+            || {},
+            |_, _| {
+                progress_calls.fetch_add(1, Ordering::SeqCst);
+            },
+        );
+        assert_eq!(progress_calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn splits_samples_across_worker_threads() {
+        let runner = BenchmarkRunner::new(BenchmarkOpts {
+            warmup_iters: 0,
+            sample_iters: 7,
+            threads: 3,
+        });
+        let calls = AtomicUsize::new(0);
+        let report = runner.run(|| { calls.fetch_add(1, Ordering::SeqCst); }, |_, _| {});
+
+        assert_eq!(report.samples.len(), 7);
+        assert_eq!(calls.load(Ordering::SeqCst), 7);
+    }
+}
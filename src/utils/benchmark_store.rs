@@ -0,0 +1,209 @@
+//! Persisted [`BenchmarkReport`] results, keyed by a scenario/commit label,
+//! plus a regression comparison against a stored baseline -- the
+//! self-contained equivalent of a benchmark collector gathering per-commit
+//! numbers and a dashboard flagging the ones that moved.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::benchmark::BenchmarkReport;
+
+/// Significance thresholds for [`ResultStore::compare`]. A metric is
+/// flagged if it crosses EITHER configured threshold; leaving a threshold
+/// `None` disables that check.
+#[derive(Debug, Clone, Copy)]
+pub struct RegressionThreshold {
+    /// For the `mean` metric: flag when the new mean falls outside the
+    /// baseline's `mean ± k * stddev`.
+    pub stddev_multiple: Option<f64>,
+    /// For every metric: flag when the relative change from baseline
+    /// exceeds this fraction (e.g. `0.10` for 10%).
+    pub pct_delta: Option<f64>,
+}
+
+impl Default for RegressionThreshold {
+    fn default() -> Self {
+        Self {
+            stddev_multiple: Some(2.0),
+            pct_delta: Some(0.10),
+        }
+    }
+}
+
+/// One metric's change between a baseline and a new [`BenchmarkReport`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Delta {
+    pub metric: &'static str,
+    pub baseline: Duration,
+    pub current: Duration,
+    pub pct_change: f64,
+    pub flagged: bool,
+}
+
+/// A JSON-backed store of labelled [`BenchmarkReport`]s on disk.
+#[derive(Debug, Default)]
+pub struct ResultStore {
+    path: PathBuf,
+    results: HashMap<String, BenchmarkReport>,
+}
+
+impl ResultStore {
+    /// Opens the store at `path`, loading any results already on disk.
+    /// A missing file is treated as an empty store.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let results = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path, results })
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let file = fs::File::create(&self.path)?;
+        serde_json::to_writer_pretty(file, &self.results)?;
+        Ok(())
+    }
+
+    /// Records `result` under `label`, overwriting any prior result with
+    /// the same label, and persists the store to disk.
+    pub fn record(&mut self, label: impl Into<String>, result: BenchmarkReport) -> io::Result<()> {
+        self.results.insert(label.into(), result);
+        self.save()
+    }
+
+    pub fn get(&self, label: &str) -> Option<&BenchmarkReport> {
+        self.results.get(label)
+    }
+
+    /// Diffs `result` against the report stored under `baseline_label`,
+    /// returning one [`Delta`] per summary metric, or `None` if no
+    /// baseline is recorded under that label.
+    pub fn compare(
+        &self,
+        baseline_label: &str,
+        result: &BenchmarkReport,
+        threshold: RegressionThreshold,
+    ) -> Option<Vec<Delta>> {
+        let baseline = self.results.get(baseline_label)?;
+
+        let metric = |name, baseline: Duration, current: Duration| {
+            let pct_change = if baseline.as_nanos() == 0 {
+                0.0
+            } else {
+                (current.as_nanos() as f64 - baseline.as_nanos() as f64)
+                    / baseline.as_nanos() as f64
+            };
+
+            let pct_flagged = threshold
+                .pct_delta
+                .is_some_and(|max| pct_change.abs() > max);
+            let stddev_flagged = name == "mean"
+                && threshold.stddev_multiple.is_some_and(|k| {
+                    let bound = baseline_stddev_nanos(baseline) * k;
+                    (current.as_nanos() as f64 - baseline.as_nanos() as f64).abs() > bound
+                });
+
+            Delta {
+                metric: name,
+                baseline,
+                current,
+                pct_change,
+                flagged: pct_flagged || stddev_flagged,
+            }
+        };
+
+        Some(vec![
+            metric("mean", baseline.mean, result.mean),
+            metric("median", baseline.median, result.median),
+            metric("stddev", baseline.stddev, result.stddev),
+            metric("min", baseline.min, result.min),
+            metric("max", baseline.max, result.max),
+        ])
+    }
+}
+
+fn baseline_stddev_nanos(baseline: &BenchmarkReport) -> f64 {
+    baseline.stddev.as_nanos() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::benchmark::{BenchmarkOpts, BenchmarkRunner};
+
+    fn sample_report(mean_nanos: u64) -> BenchmarkReport {
+        let runner = BenchmarkRunner::new(BenchmarkOpts {
+            warmup_iters: 0,
+            sample_iters: 3,
+            threads: 1,
+        });
+        let report = runner.run(|| {}, |_, _| {});
+        BenchmarkReport {
+            mean: Duration::from_nanos(mean_nanos),
+            median: Duration::from_nanos(mean_nanos),
+            stddev: Duration::from_nanos(mean_nanos / 20),
+            min: Duration::from_nanos(mean_nanos),
+            max: Duration::from_nanos(mean_nanos),
+            ..report
+        }
+    }
+
+    #[test]
+    fn record_and_reload_round_trips_through_disk() {
+        let dir = std::env::temp_dir().join(format!(
+            "step_perf_benchmark_store_{:?}",
+            std::thread::current().id()
+        ));
+        let path = dir.with_extension("json");
+        let _ = fs::remove_file(&path);
+
+        let mut store = ResultStore::open(&path).unwrap();
+        store.record("baseline", sample_report(1_000_000)).unwrap();
+
+        let reloaded = ResultStore::open(&path).unwrap();
+        assert_eq!(reloaded.get("baseline").unwrap().mean.as_nanos(), 1_000_000);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn flags_a_mean_regression_beyond_the_percentage_threshold() {
+        let mut store = ResultStore::default();
+        store.results.insert("baseline".into(), sample_report(1_000_000));
+
+        let regressed = sample_report(1_300_000);
+        let deltas = store
+            .compare("baseline", &regressed, RegressionThreshold::default())
+            .unwrap();
+
+        let mean_delta = deltas.iter().find(|d| d.metric == "mean").unwrap();
+        assert!(mean_delta.flagged);
+        assert!(mean_delta.pct_change > 0.0);
+    }
+
+    #[test]
+    fn does_not_flag_changes_within_threshold() {
+        let mut store = ResultStore::default();
+        store.results.insert("baseline".into(), sample_report(1_000_000));
+
+        let steady = sample_report(1_010_000);
+        let deltas = store
+            .compare("baseline", &steady, RegressionThreshold::default())
+            .unwrap();
+
+        assert!(deltas.iter().all(|d| !d.flagged));
+    }
+
+    #[test]
+    fn compare_returns_none_for_an_unknown_baseline() {
+        let store = ResultStore::default();
+        assert!(store
+            .compare("missing", &sample_report(1), RegressionThreshold::default())
+            .is_none());
+    }
+}
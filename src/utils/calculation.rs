@@ -11,3 +11,23 @@ pub fn div_ceil(a: u64, b: u64) -> u64 {
     // Otherwise, add 1 to the integer division result
     a / b + 1
 }
+
+/// Rounds `x` up to the next multiple of `align` bytes (std140-style row
+/// padding). `align == 0` or `align == 1` means no padding, returning `x`
+/// unchanged.
+pub fn align_up(x: u64, align: u64) -> u64 {
+    if align <= 1 {
+        x
+    } else {
+        div_ceil(x, align) * align
+    }
+}
+
+/// Scales `base_flop_per_cycle` (the MAC rate at the 4-byte fp32 reference
+/// width) up for narrower operands, modeling the packed low-precision lanes
+/// real hardware exposes: 1-byte operands get 4x the MAC rate of fp32,
+/// 2-byte operands get 2x, and so on.
+pub fn effective_flop_per_cycle(base_flop_per_cycle: u64, bytes_per_elem: usize) -> u64 {
+    const REFERENCE_BYTES: u64 = 4;
+    (base_flop_per_cycle * REFERENCE_BYTES / (bytes_per_elem as u64).max(1)).max(1)
+}
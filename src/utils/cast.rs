@@ -1,17 +1,33 @@
-pub fn to_usize_vec<T: TryInto<usize>>(vec: Vec<T>) -> Vec<usize>
+use crate::utils::error::StepPerfError;
+
+pub fn to_usize_vec<T: TryInto<usize>>(vec: Vec<T>) -> Result<Vec<usize>, StepPerfError>
 where
-    <T as TryInto<usize>>::Error: std::fmt::Debug,
+    <T as TryInto<usize>>::Error: std::error::Error + Send + Sync + 'static,
 {
     vec.into_iter()
-        .map(|x| x.try_into().expect("Conversion to usize failed"))
+        .enumerate()
+        .map(|(index, x)| {
+            x.try_into().map_err(|source| StepPerfError::IntConversion {
+                from: "usize",
+                index,
+                source: Box::new(source),
+            })
+        })
         .collect()
 }
 
-pub fn to_u64_vec<T: TryInto<u64>>(vec: Vec<T>) -> Vec<u64>
+pub fn to_u64_vec<T: TryInto<u64>>(vec: Vec<T>) -> Result<Vec<u64>, StepPerfError>
 where
-    <T as TryInto<u64>>::Error: std::fmt::Debug,
+    <T as TryInto<u64>>::Error: std::error::Error + Send + Sync + 'static,
 {
     vec.into_iter()
-        .map(|x| x.try_into().expect("Conversion to usize failed"))
+        .enumerate()
+        .map(|(index, x)| {
+            x.try_into().map_err(|source| StepPerfError::IntConversion {
+                from: "u64",
+                index,
+                source: Box::new(source),
+            })
+        })
         .collect()
 }
@@ -0,0 +1,75 @@
+//! Pluggable wall-clock source for [`crate::proto_driver::parse_proto`]'s
+//! reported `duration`. Measuring against the real system clock makes
+//! simulator-overhead benchmarks non-reproducible in CI and impossible to
+//! unit-test, so callers can swap in a [`MockClock`] via
+//! [`crate::proto_driver::configs::SimConfig`] instead.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A source of [`Instant`]s. `parse_proto` reads `now()` before and after
+/// `initialized.run(...)` and reports the difference as `duration`.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The real system clock (`Instant::now()`). [`SimConfig`]'s default.
+///
+/// [`SimConfig`]: crate::proto_driver::configs::SimConfig
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A fake clock that advances by a fixed `step` on every call to `now()`,
+/// starting from the instant it was constructed. Lets a test or benchmark
+/// assert an exact `duration_ms`/`duration_s` regardless of how long the
+/// simulation actually took to build and run on the host.
+pub struct MockClock {
+    origin: Instant,
+    step: Duration,
+    ticks: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new(step: Duration) -> Self {
+        Self {
+            origin: Instant::now(),
+            step,
+            ticks: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        let tick = self.ticks.fetch_add(1, Ordering::SeqCst);
+        self.origin + self.step * tick as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_clock_advances_by_a_fixed_step_each_call() {
+        let clock = MockClock::new(Duration::from_millis(100));
+        let start = clock.now();
+        let _ = clock.now();
+        let end = clock.now();
+        assert_eq!(end.duration_since(start), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn system_clock_moves_forward() {
+        let clock = SystemClock;
+        let start = clock.now();
+        let end = clock.now();
+        assert!(end >= start);
+    }
+}
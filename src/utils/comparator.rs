@@ -0,0 +1,171 @@
+use crate::primitives::elem::Elem;
+
+/// How NaN compares under [`Tolerance::eq`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanMode {
+    /// NaN never compares equal to anything, including another NaN (the
+    /// IEEE-754 default, and what this type uses unless told otherwise).
+    #[default]
+    Ieee,
+    /// NaN compares equal to NaN, useful when a test wants to confirm a
+    /// pipeline propagates a NaN/missing-data marker rather than clobbering
+    /// it with a real value.
+    Bitwise,
+}
+
+/// Approximate floating-point equality for validating numeric step outputs,
+/// for use as (or inside) an `ApproxCheckerContext` predicate in place of a
+/// hard `|x, y| x == y`. Two values compare equal if they satisfy ANY of the
+/// configured bounds; leaving a bound `None` disables that criterion, and a
+/// `Tolerance::default()` falls back to exact bitwise equality.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tolerance {
+    /// Equal if `|a - b| <= abs_eps`.
+    pub abs_eps: Option<f64>,
+    /// Equal if `|a - b| <= rel_eps * max(|a|, |b|)`.
+    pub rel_eps: Option<f64>,
+    /// Equal if the signed-magnitude integer reinterpretations of the bits
+    /// differ by at most `max_ulps`.
+    pub max_ulps: Option<u64>,
+    pub nan_mode: NanMode,
+}
+
+impl Tolerance {
+    pub fn absolute(abs_eps: f64) -> Self {
+        Self {
+            abs_eps: Some(abs_eps),
+            ..Default::default()
+        }
+    }
+
+    pub fn relative(rel_eps: f64) -> Self {
+        Self {
+            rel_eps: Some(rel_eps),
+            ..Default::default()
+        }
+    }
+
+    pub fn ulps(max_ulps: u64) -> Self {
+        Self {
+            max_ulps: Some(max_ulps),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_nan_mode(mut self, nan_mode: NanMode) -> Self {
+        self.nan_mode = nan_mode;
+        self
+    }
+
+    /// Compares `a` and `b` under whichever bounds are configured, with
+    /// NaN and infinities handled before any of them are consulted.
+    pub fn eq(&self, a: f64, b: f64) -> bool {
+        if a.is_nan() || b.is_nan() {
+            return self.nan_mode == NanMode::Bitwise && a.is_nan() && b.is_nan();
+        }
+        if a.is_infinite() || b.is_infinite() {
+            return a == b;
+        }
+        if let Some(abs_eps) = self.abs_eps {
+            if (a - b).abs() <= abs_eps {
+                return true;
+            }
+        }
+        if let Some(rel_eps) = self.rel_eps {
+            if (a - b).abs() <= rel_eps * a.abs().max(b.abs()) {
+                return true;
+            }
+        }
+        if let Some(max_ulps) = self.max_ulps {
+            if ulps_distance(a, b) <= max_ulps {
+                return true;
+            }
+        }
+        a == b
+    }
+}
+
+/// Orders `f64` bit patterns the same way the floats themselves order, so a
+/// plain unsigned difference counts ULPs. Negatives map to `[0, 2^63)` in
+/// reverse (more negative -> smaller), positives to `[2^63, 2^64)` in
+/// increasing order; see Bruce Dawson's "Comparing Floating Point Numbers".
+fn ordered_bits(x: f64) -> u64 {
+    let bits = x.to_bits();
+    if bits & (1 << 63) != 0 {
+        !bits
+    } else {
+        bits | (1 << 63)
+    }
+}
+
+fn ulps_distance(a: f64, b: f64) -> u64 {
+    ordered_bits(a).abs_diff(ordered_bits(b))
+}
+
+/// Lifts a raw-value comparator into one that compares [`Elem`] tokens
+/// structurally -- both plain values, or both stops at the same level --
+/// so it can be passed directly as an `ApproxCheckerContext` predicate in
+/// place of `|x, y| x == y`.
+pub fn elem_eq<T>(cmp: impl Fn(&T, &T) -> bool) -> impl Fn(&Elem<T>, &Elem<T>) -> bool {
+    move |a, b| match (a, b) {
+        (Elem::Val(x), Elem::Val(y)) => cmp(x, y),
+        (Elem::ValStop(x, sx), Elem::ValStop(y, sy)) => sx == sy && cmp(x, y),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absolute_tolerance_accepts_small_differences() {
+        let tol = Tolerance::absolute(0.01);
+        assert!(tol.eq(1.0, 1.005));
+        assert!(!tol.eq(1.0, 1.02));
+    }
+
+    #[test]
+    fn relative_tolerance_scales_with_magnitude() {
+        let tol = Tolerance::relative(0.01);
+        assert!(tol.eq(1000.0, 1005.0));
+        assert!(!tol.eq(1.0, 1.02));
+    }
+
+    #[test]
+    fn ulps_tolerance_accepts_adjacent_representable_values() {
+        let tol = Tolerance::ulps(2);
+        let a = 1.0_f64;
+        let b = f64::from_bits(a.to_bits() + 2);
+        assert!(tol.eq(a, b));
+        let c = f64::from_bits(a.to_bits() + 10);
+        assert!(!tol.eq(a, c));
+    }
+
+    #[test]
+    fn nan_is_unequal_by_default_and_equal_in_bitwise_mode() {
+        let default_tol = Tolerance::absolute(1.0);
+        assert!(!default_tol.eq(f64::NAN, f64::NAN));
+
+        let bitwise_tol = Tolerance::absolute(1.0).with_nan_mode(NanMode::Bitwise);
+        assert!(bitwise_tol.eq(f64::NAN, f64::NAN));
+        assert!(!bitwise_tol.eq(f64::NAN, 1.0));
+    }
+
+    #[test]
+    fn infinities_only_equal_same_sign_infinity() {
+        let tol = Tolerance::absolute(f64::MAX);
+        assert!(tol.eq(f64::INFINITY, f64::INFINITY));
+        assert!(!tol.eq(f64::INFINITY, f64::NEG_INFINITY));
+        assert!(!tol.eq(f64::INFINITY, 1e300));
+    }
+
+    #[test]
+    fn elem_eq_requires_matching_stop_levels() {
+        let cmp = elem_eq(|a: &f64, b: &f64| Tolerance::absolute(0.1).eq(*a, *b));
+        assert!(cmp(&Elem::Val(1.0), &Elem::Val(1.05)));
+        assert!(cmp(&Elem::ValStop(1.0, 2), &Elem::ValStop(1.05, 2)));
+        assert!(!cmp(&Elem::ValStop(1.0, 1), &Elem::ValStop(1.05, 2)));
+        assert!(!cmp(&Elem::Val(1.0), &Elem::ValStop(1.0, 1)));
+    }
+}
@@ -0,0 +1,126 @@
+//! An opt-in debugging hook for DAM contexts that issue tile requests (e.g.
+//! [`crate::memory::random_offchip_load::RandomOffChipLoad`]): a context
+//! consults an optional [`DebugProbe`] at the top of each tile's
+//! processing, letting a user watch or halt on specific tiles/addresses
+//! without recompiling with `println!`s scattered through the context.
+//!
+//! Unlike [`crate::utils::probe::ProbeContext`] (a transparent pass-through
+//! context spliced into a channel), a `DebugProbe` is consulted directly by
+//! the host context, so it sees the same tile index and address list the
+//! host is about to act on rather than inferring it from an element on the
+//! wire.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// What the host context should do about the tile request a [`DebugProbe`]
+/// was just consulted about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugAction {
+    /// Proceed as normal.
+    Continue,
+    /// Proceed normally; the probe itself is responsible for any logging
+    /// (see [`Tracer`]).
+    TraceOnly,
+    /// Halt with a diagnostic dump of the current in-flight state for
+    /// `tile_idx` -- the same "halt" idiom [`crate::utils::probe::ProbeAction::Halt`]
+    /// uses, since a batch simulation has no interactive pause to drop into.
+    BreakAt { tile_idx: u64 },
+}
+
+/// Consulted by a host context once per tile, and optionally once per
+/// response, to decide whether to continue, trace, or break.
+pub trait DebugProbe: Send + Sync {
+    /// Called right before a tile's address requests are dispatched.
+    fn on_request(&mut self, ctx_id: u32, tile_idx: u64, addrs: &[u64]) -> DebugAction;
+
+    /// Called once per response as it arrives, regardless of the action
+    /// `on_request` returned. The default no-op suits probes that only
+    /// care about the request side.
+    fn on_response(&mut self, _ctx_id: u32, _tile_idx: u64, _addr: u64, _arrival_tick: u64) {}
+}
+
+/// Fires [`DebugAction::BreakAt`] when a watched tile index or address
+/// range is touched; otherwise continues silently.
+pub struct ConditionalBreak {
+    pub watch_tile_idx: Option<u64>,
+    /// Half-open `[start, end)` byte range; any touched address inside it
+    /// triggers a break.
+    pub watch_addr_range: Option<(u64, u64)>,
+}
+
+impl DebugProbe for ConditionalBreak {
+    fn on_request(&mut self, _ctx_id: u32, tile_idx: u64, addrs: &[u64]) -> DebugAction {
+        let tile_hit = self.watch_tile_idx == Some(tile_idx);
+        let addr_hit = self
+            .watch_addr_range
+            .is_some_and(|(start, end)| addrs.iter().any(|&a| a >= start && a < end));
+
+        if tile_hit || addr_hit {
+            DebugAction::BreakAt { tile_idx }
+        } else {
+            DebugAction::Continue
+        }
+    }
+}
+
+/// Records a per-context access log -- every issued tile's addresses, and
+/// every response's arrival tick -- to `path`.
+pub struct Tracer {
+    writer: BufWriter<File>,
+}
+
+impl Tracer {
+    pub fn new(path: &str) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+}
+
+impl DebugProbe for Tracer {
+    fn on_request(&mut self, ctx_id: u32, tile_idx: u64, addrs: &[u64]) -> DebugAction {
+        let _ = writeln!(self.writer, "req ctx={ctx_id} tile={tile_idx} addrs={addrs:?}");
+        let _ = self.writer.flush();
+        DebugAction::TraceOnly
+    }
+
+    fn on_response(&mut self, ctx_id: u32, tile_idx: u64, addr: u64, arrival_tick: u64) {
+        let _ = writeln!(
+            self.writer,
+            "resp ctx={ctx_id} tile={tile_idx} addr={addr} tick={arrival_tick}"
+        );
+        let _ = self.writer.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditional_break_fires_on_watched_tile_idx() {
+        let mut probe = ConditionalBreak {
+            watch_tile_idx: Some(3),
+            watch_addr_range: None,
+        };
+        assert_eq!(
+            probe.on_request(0, 3, &[100]),
+            DebugAction::BreakAt { tile_idx: 3 }
+        );
+        assert_eq!(probe.on_request(0, 4, &[100]), DebugAction::Continue);
+    }
+
+    #[test]
+    fn conditional_break_fires_on_watched_addr_range() {
+        let mut probe = ConditionalBreak {
+            watch_tile_idx: None,
+            watch_addr_range: Some((100, 200)),
+        };
+        assert_eq!(
+            probe.on_request(0, 1, &[50, 150]),
+            DebugAction::BreakAt { tile_idx: 1 }
+        );
+        assert_eq!(probe.on_request(0, 2, &[50, 250]), DebugAction::Continue);
+    }
+}
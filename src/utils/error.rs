@@ -0,0 +1,75 @@
+use thiserror::Error;
+
+/// Crate-wide error type for entry points that used to panic on bad input
+/// (`fs::read(...).unwrap()`, `ProgramGraph::decode(...).unwrap()`, the
+/// integer-conversion helpers in [`super::cast`]). `lib.rs`'s `run_graph`
+/// maps each variant to a Python exception instead of crashing the
+/// interpreter.
+#[derive(Error, Debug)]
+pub enum StepPerfError {
+    #[error("failed to read proto file: {source}")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+
+    #[error("failed to decode proto: {source}")]
+    ProtoDecode {
+        #[from]
+        source: prost::DecodeError,
+    },
+
+    #[error("element {index} of a {from} vector could not be converted: {source}")]
+    IntConversion {
+        from: &'static str,
+        index: usize,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("context {ctx_id}: rank mismatch, expected {expected} but found {found}")]
+    RankMismatch {
+        ctx_id: u32,
+        expected: u32,
+        found: u32,
+    },
+
+    /// One or more [`crate::proto_driver::validate::GraphRule`]s found the
+    /// graph malformed before it was ever built. Only the `Error`-severity
+    /// diagnostics are collected here; `Warning`s are logged and don't stop
+    /// the run.
+    #[error("graph failed validation:{}", diagnostics.iter().map(|d| format!("\n  operator {}: {}", d.node_id, d.message)).collect::<String>())]
+    GraphValidation {
+        diagnostics: Vec<crate::proto_driver::validate::Diagnostic>,
+    },
+
+    /// See [`crate::proto_driver::schema`]: the graph declares a
+    /// `schema_version` outside what this build supports, covering both
+    /// a too-new graph from a newer frontend and a too-old one this
+    /// build has dropped support for.
+    #[error("unsupported schema version {found} (this build supports {min}..={max})")]
+    UnsupportedSchema { found: u32, min: u32, max: u32 },
+
+    /// See [`crate::proto_driver::schema`]: the graph relies on a feature
+    /// this build wasn't compiled with.
+    #[error("graph requires feature {feature:?}, which this build was not compiled with")]
+    MissingFeature { feature: String },
+
+    /// One or more operations couldn't be lowered while building the
+    /// dataflow network (e.g. a dtype combination `build_from_proto`
+    /// doesn't implement). Unlike [`Self::GraphValidation`], these are only
+    /// discovered while actually constructing each node, so every failing
+    /// node is collected before reporting, rather than aborting on the
+    /// first one.
+    #[error("failed to build {} operation(s):{}", errors.len(), errors.iter().map(|e| format!("\n  operator {} ({}): {}", e.operation_id, e.op_type, e.unsupported)).collect::<String>())]
+    UnsupportedOps {
+        errors: Vec<crate::proto_driver::BuildError>,
+    },
+
+    /// See [`crate::proto_driver::run_benchmark`]: a simulation is expected
+    /// to produce the same `cycles` every time it's run against the same
+    /// inputs, so a benchmark where they disagree across iterations means
+    /// the wall-time samples aren't even comparable to each other.
+    #[error("benchmark samples disagreed on cycles: {cycles:?}")]
+    NondeterministicCycles { cycles: Vec<u64> },
+}
@@ -1,10 +1,23 @@
 // Define a trait for event types that can be logged
 
 use dam::dam_macros::event_type;
+use dam::logging::LogEvent;
 use serde::{Deserialize, Serialize};
 
 pub const DUMMY_ID: u32 = 0;
 
+/// Forwards `event` to dam's own logging backend exactly as
+/// `dam::logging::log_event` would (panicking on the same errors that call
+/// would), and additionally appends it to the local file sink from
+/// [`crate::proto_driver::log_sink`] when one is installed. Operator
+/// contexts should call this instead of `dam::logging::log_event` directly,
+/// so a run can emit to both backends (or neither) without each context
+/// needing to know which is active.
+pub fn log_event<E: LogEvent + Serialize>(event: &E) {
+    crate::proto_driver::log_sink::record(event);
+    dam::logging::log_event(event).unwrap();
+}
+
 pub trait LoggableEventSimple {
     fn new(name: String, id: u32, start_ns: u64, end_ns: u64, is_stop: bool) -> Self;
 }
@@ -35,6 +48,114 @@ impl SimpleEvent {
     pub const NAME: &'static str = stringify!(SimpleEvent);
 }
 
+/// Parallel to [`LoggableEventSimple`]: an event type that additionally
+/// carries the energy (in picojoules) a node spent on its load, compute,
+/// and store phases for one invocation. Kept separate from
+/// `LoggableEventSimple` rather than folded into it, since most existing
+/// contexts (and their `E: LoggableEventSimple` call sites) have no energy
+/// model and shouldn't need one just to keep logging cycles.
+pub trait LoggableEnergyEvent {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        name: String,
+        id: u32,
+        start_ns: u64,
+        end_ns: u64,
+        is_stop: bool,
+        load_pj: f64,
+        compute_pj: f64,
+        store_pj: f64,
+    ) -> Self;
+}
+
+/// Energy companion to [`SimpleEvent`], logged alongside it (via the same
+/// [`log_event`]) by nodes that set non-zero energy coefficients in their
+/// config -- e.g. [`crate::operator::map::EnergyConfig`]. Unlike cycles,
+/// `load_pj`/`compute_pj`/`store_pj` are not maxed against each other: they
+/// sum to the invocation's total energy, since energy is spent regardless
+/// of how much the three phases overlap in time.
+#[derive(Serialize, Deserialize, Debug)]
+#[event_type]
+pub struct EnergyEvent {
+    name: String,
+    id: u32,
+    start_ns: u64,
+    end_ns: u64,
+    is_stop: bool,
+    load_pj: f64,
+    compute_pj: f64,
+    store_pj: f64,
+}
+
+impl LoggableEnergyEvent for EnergyEvent {
+    fn new(
+        name: String,
+        id: u32,
+        start_ns: u64,
+        end_ns: u64,
+        is_stop: bool,
+        load_pj: f64,
+        compute_pj: f64,
+        store_pj: f64,
+    ) -> Self {
+        EnergyEvent {
+            name,
+            id,
+            start_ns,
+            end_ns,
+            is_stop,
+            load_pj,
+            compute_pj,
+            store_pj,
+        }
+    }
+}
+
+impl EnergyEvent {
+    pub const NAME: &'static str = stringify!(EnergyEvent);
+}
+
+/// Emits `events` as a Chrome/Perfetto JSON Trace Event Format document: one
+/// complete (`"ph":"X"`) event per record, with `ts`/`dur` converted from
+/// nanoseconds to the microseconds trace viewers expect. Records are
+/// grouped into per-context tracks by `id` (used as both `pid` and `tid`),
+/// with a `thread_name` metadata (`"ph":"M"`) event per track so each DAM
+/// context shows up as a labeled lane instead of a bare numeric id. Drop the
+/// output straight into chrome://tracing or Perfetto.
+pub fn export_chrome_trace<W: std::io::Write>(
+    events: &[SimpleEvent],
+    mut out: W,
+) -> std::io::Result<()> {
+    let mut track_names: std::collections::BTreeMap<u32, &str> = std::collections::BTreeMap::new();
+    for event in events {
+        track_names.entry(event.id).or_insert(event.name.as_str());
+    }
+
+    let mut trace_events = Vec::with_capacity(track_names.len() + events.len());
+    for (id, name) in &track_names {
+        trace_events.push(serde_json::json!({
+            "name": "thread_name",
+            "ph": "M",
+            "pid": id,
+            "tid": id,
+            "args": { "name": name },
+        }));
+    }
+    for event in events {
+        trace_events.push(serde_json::json!({
+            "name": event.name,
+            "ph": "X",
+            "ts": event.start_ns as f64 / 1000.0,
+            "dur": (event.end_ns - event.start_ns) as f64 / 1000.0,
+            "pid": event.id,
+            "tid": event.id,
+        }));
+    }
+
+    serde_json::to_writer(&mut out, &trace_events)?;
+    Ok(())
+}
+
 #[macro_export]
 macro_rules! define_simple_event {
     ($event_name:ident) => {
@@ -66,3 +187,41 @@ macro_rules! define_simple_event {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_chrome_trace_converts_ns_to_us_and_groups_tracks() {
+        let events = vec![
+            SimpleEvent::new("RandomOffChipLoad".to_string(), 1, 1_000, 3_000, false),
+            SimpleEvent::new("RandomOffChipLoad".to_string(), 1, 4_000, 5_000, true),
+            SimpleEvent::new("OffChipStore".to_string(), 2, 2_000, 2_500, false),
+        ];
+
+        let mut out = Vec::new();
+        export_chrome_trace(&events, &mut out).unwrap();
+        let trace: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let trace_events = trace.as_array().unwrap();
+
+        // One thread_name metadata event per distinct id, plus one "X"
+        // event per record.
+        let metadata: Vec<_> = trace_events
+            .iter()
+            .filter(|e| e["ph"] == "M")
+            .collect();
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata[0]["args"]["name"], "RandomOffChipLoad");
+        assert_eq!(metadata[1]["args"]["name"], "OffChipStore");
+
+        let complete: Vec<_> = trace_events
+            .iter()
+            .filter(|e| e["ph"] == "X")
+            .collect();
+        assert_eq!(complete.len(), 3);
+        assert_eq!(complete[0]["ts"], 1.0);
+        assert_eq!(complete[0]["dur"], 2.0);
+        assert_eq!(complete[0]["pid"], 1);
+    }
+}
@@ -0,0 +1,150 @@
+//! Self-contained HTML run report: a single shareable artifact summarizing
+//! a [`crate::proto_driver::parse_proto`] run, far easier to skim for the
+//! bottleneck channel than the raw `to_dot_string()` dump or digging
+//! through Mongo logs. See `crate::build_sim::occupancy` for where the
+//! per-channel numbers tabled here come from.
+//!
+//! Everything (CSS, the per-channel table, and an SVG timeline) is
+//! embedded inline in the returned string, and every piece of dynamic
+//! text is escaped, so the file is safe to open directly from a browser
+//! with no external dependencies.
+
+use crate::build_sim::occupancy::ChannelOccupancy;
+use crate::proto_driver::run_report::RunReport;
+
+/// Renders `run`'s summary plus a `channels` table/timeline into a
+/// self-contained HTML page. `channels` is expected to already be the
+/// `OccupancyLog::snapshot()` collected at the end of the run that
+/// produced `run`.
+pub fn render(run: &RunReport, channels: &[ChannelOccupancy]) -> String {
+    let status_class = if run.passed { "pass" } else { "fail" };
+    let status_text = if run.passed { "PASSED" } else { "FAILED" };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Simulation run report</title>\n");
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n");
+
+    html.push_str("<h1>Simulation run report</h1>\n");
+    html.push_str("<table class=\"summary\">\n");
+    html.push_str(&format!(
+        "<tr><th>Status</th><td class=\"{status_class}\">{status_text}</td></tr>\n"
+    ));
+    html.push_str(&format!(
+        "<tr><th>Cycles</th><td>{}</td></tr>\n",
+        escape(&run.cycles.to_string())
+    ));
+    html.push_str(&format!(
+        "<tr><th>Wall duration</th><td>{}</td></tr>\n",
+        escape(&format!("{:?}", run.wall_duration))
+    ));
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Channels</h2>\n");
+    html.push_str("<table class=\"channels\">\n");
+    html.push_str(
+        "<tr><th>Channel</th><th>Depth</th><th>Tokens</th><th>Stalled tokens</th>\
+         <th>Peak stall (cycles)</th><th>Avg stall (cycles)</th></tr>\n",
+    );
+    for channel in channels {
+        let avg_stall = if channel.tokens == 0 {
+            0.0
+        } else {
+            channel.total_stall as f64 / channel.tokens as f64
+        };
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td></tr>\n",
+            escape(&channel.id.to_string()),
+            escape(&channel.depth.to_string()),
+            escape(&channel.tokens.to_string()),
+            escape(&channel.stalled_tokens.to_string()),
+            escape(&channel.peak_stall.to_string()),
+            avg_stall,
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Per-channel stall timeline</h2>\n");
+    html.push_str(&render_timeline(channels));
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+const STYLE: &str = "<style>\n\
+body { font-family: sans-serif; margin: 2em; color: #1a1a1a; }\n\
+table { border-collapse: collapse; margin-bottom: 1.5em; }\n\
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }\n\
+th { background: #f0f0f0; }\n\
+td.pass { color: #1a7f37; font-weight: bold; }\n\
+td.fail { color: #b3261e; font-weight: bold; }\n\
+.timeline-row text { font-size: 12px; }\n\
+</style>\n";
+
+/// Renders one horizontal bar per channel: the full-width bar is the
+/// channel's peak stall (the longest any single token waited), and the
+/// darker inner segment is its average stall -- a quick visual read on
+/// which channels are routinely backed up versus occasionally spiking.
+fn render_timeline(channels: &[ChannelOccupancy]) -> String {
+    if channels.is_empty() {
+        return "<p>(no channels were instrumented for this run)</p>\n".to_string();
+    }
+
+    const ROW_HEIGHT: u32 = 24;
+    const BAR_WIDTH: u32 = 400;
+    const LABEL_WIDTH: u32 = 80;
+    let width = LABEL_WIDTH + BAR_WIDTH + 20;
+    let height = ROW_HEIGHT * channels.len() as u32 + 10;
+
+    let max_stall = channels.iter().map(|c| c.peak_stall).max().unwrap_or(0).max(1);
+
+    let mut svg = format!(
+        "<svg width=\"{width}\" height=\"{height}\" xmlns=\"http://www.w3.org/2000/svg\">\n"
+    );
+    for (row, channel) in channels.iter().enumerate() {
+        let y = row as u32 * ROW_HEIGHT;
+        let peak_w = (channel.peak_stall as f64 / max_stall as f64 * BAR_WIDTH as f64).round() as u32;
+        let avg_stall = if channel.tokens == 0 {
+            0.0
+        } else {
+            channel.total_stall as f64 / channel.tokens as f64
+        };
+        let avg_w = (avg_stall / max_stall as f64 * BAR_WIDTH as f64).round() as u32;
+
+        svg.push_str(&format!(
+            "<g class=\"timeline-row\" transform=\"translate(0,{y})\">\n"
+        ));
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{}</text>\n",
+            ROW_HEIGHT - 8,
+            escape(&format!("ch {}", channel.id))
+        ));
+        svg.push_str(&format!(
+            "<rect x=\"{LABEL_WIDTH}\" y=\"2\" width=\"{peak_w}\" height=\"{}\" fill=\"#cfe3ff\"/>\n",
+            ROW_HEIGHT - 6
+        ));
+        svg.push_str(&format!(
+            "<rect x=\"{LABEL_WIDTH}\" y=\"2\" width=\"{avg_w}\" height=\"{}\" fill=\"#3a7bd5\"/>\n",
+            ROW_HEIGHT - 6
+        ));
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Escapes the five HTML-significant characters so dynamic text (channel
+/// ids, durations, etc.) can never break out of the surrounding markup.
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&#39;".to_string(),
+            other => other.to_string(),
+        })
+        .collect()
+}
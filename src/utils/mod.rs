@@ -0,0 +1,15 @@
+pub mod arrow_ipc;
+pub mod benchmark;
+pub mod benchmark_store;
+pub mod calculation;
+pub mod cast;
+pub mod clock;
+pub mod comparator;
+pub mod debug_probe;
+pub mod error;
+pub mod events;
+pub mod html_report;
+pub mod probe;
+pub mod profiling;
+pub mod select_npy;
+pub mod watchdog;
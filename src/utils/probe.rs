@@ -0,0 +1,271 @@
+//! A transparent, insert-anywhere debugging tap for `Receiver<Elem<A>>`/
+//! `Sender<Elem<A>>` pairs: every element is dequeued and re-enqueued
+//! unchanged (same data, same timestamp), so splicing one into a graph
+//! never changes its behavior, only what a user can observe about it.
+//!
+//! Two independent features ride along with the pass-through:
+//! - **Trace mode** logs every element's simulation cycle via the existing
+//!   [`LoggableEventSimple`]/[`SimpleEvent`] machinery, the same way any
+//!   other instrumented context in this crate reports events.
+//! - **Trigger/break** fires a [`ProbeAction`] the `skip`-th time (and every
+//!   time after) a [`ProbeTrigger`] predicate matches -- letting a user ask
+//!   "halt when the 3rd rank-2 stop token crosses this channel" without
+//!   rebuilding the graph around a one-off check.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use dam::context_tools::*;
+
+use crate::primitives::elem::{Elem, StopType};
+use crate::utils::events::{log_event, LoggableEventSimple, SimpleEvent};
+
+/// A predicate over `(simulation_time, &Elem<A>)`, evaluated against every
+/// element [`ProbeContext`] sees. See [`stop_rank`], [`value_matches`], and
+/// [`time_at_least`] for the triggers called out as useful in practice.
+pub type ProbeTrigger<A> = Arc<dyn Fn(u64, &Elem<A>) -> bool + Send + Sync>;
+
+/// What to do once a trigger has matched (past the `skip` count).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeAction {
+    /// Print the last `recent_window` elements seen (including the one
+    /// that matched) to stderr, then keep running.
+    LogSnapshot,
+    /// Halt the run with a diagnostic panic identifying the probe, the
+    /// matching element, and the cycle it arrived on.
+    Halt,
+}
+
+/// Triggers when a `ValStop` of exactly `rank` passes through.
+pub fn stop_rank<A: 'static>(rank: StopType) -> ProbeTrigger<A> {
+    Arc::new(move |_time, elem| matches!(elem, Elem::ValStop(_, s) if *s == rank))
+}
+
+/// Triggers when the element's payload matches `f`, regardless of whether
+/// it arrived as a `Val` or a `ValStop`.
+pub fn value_matches<A: 'static>(f: impl Fn(&A) -> bool + Send + Sync + 'static) -> ProbeTrigger<A> {
+    Arc::new(move |_time, elem| match elem {
+        Elem::Val(x) => f(x),
+        Elem::ValStop(x, _) => f(x),
+    })
+}
+
+/// Triggers once the simulation has reached cycle `t` or later.
+pub fn time_at_least<A: 'static>(t: u64) -> ProbeTrigger<A> {
+    Arc::new(move |time, _elem| time >= t)
+}
+
+#[context_macro]
+pub struct ProbeContext<A: DAMType + Debug> {
+    in_stream: Receiver<Elem<A>>,
+    out_stream: Sender<Elem<A>>,
+    id: u32,
+    name: String,
+    trace: bool,
+    trigger: Option<ProbeTrigger<A>>,
+    skip: usize,
+    action: ProbeAction,
+    recent_window: usize,
+    matches_seen: usize,
+    recent: VecDeque<(u64, String)>,
+}
+
+impl<A: DAMType + Debug> ProbeContext<A>
+where
+    Self: Context,
+{
+    /// `trigger`/`action` default to doing nothing past the pass-through;
+    /// use the builder-style `with_*` methods to opt into trace mode or a
+    /// trigger.
+    pub fn new(in_stream: Receiver<Elem<A>>, out_stream: Sender<Elem<A>>, id: u32, name: impl Into<String>) -> Self {
+        let ctx = Self {
+            in_stream,
+            out_stream,
+            id,
+            name: name.into(),
+            trace: false,
+            trigger: None,
+            skip: 0,
+            action: ProbeAction::LogSnapshot,
+            recent_window: 8,
+            matches_seen: 0,
+            recent: VecDeque::new(),
+            context_info: Default::default(),
+        };
+        ctx.in_stream.attach_receiver(&ctx);
+        ctx.out_stream.attach_sender(&ctx);
+        ctx
+    }
+
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    /// Fires `action` the `skip`-th time (0-indexed) `trigger` matches, and
+    /// every time it matches afterward.
+    pub fn with_trigger(mut self, trigger: ProbeTrigger<A>, skip: usize, action: ProbeAction) -> Self {
+        self.trigger = Some(trigger);
+        self.skip = skip;
+        self.action = action;
+        self
+    }
+
+    pub fn with_recent_window(mut self, recent_window: usize) -> Self {
+        self.recent_window = recent_window;
+        self
+    }
+}
+
+impl<A: DAMType + Debug> Context for ProbeContext<A> {
+    fn run(&mut self) {
+        loop {
+            match self.in_stream.dequeue(&self.time) {
+                Ok(ChannelElement { time: arrive, data }) => {
+                    if self.trace {
+                        log_event(&SimpleEvent::new(
+                            self.name.clone(),
+                            self.id,
+                            arrive.time(),
+                            arrive.time(),
+                            matches!(&data, Elem::ValStop(_, _)),
+                        ));
+                    }
+
+                    if self.recent_window > 0 {
+                        if self.recent.len() == self.recent_window {
+                            self.recent.pop_front();
+                        }
+                        self.recent.push_back((arrive.time(), format!("{data:?}")));
+                    }
+
+                    if let Some(trigger) = self.trigger.clone() {
+                        if trigger(arrive.time(), &data) {
+                            if self.matches_seen >= self.skip {
+                                match self.action {
+                                    ProbeAction::LogSnapshot => {
+                                        eprintln!(
+                                            "probe '{}' (id {}) matched at cycle {}: {:?}",
+                                            self.name, self.id, arrive.time(), data
+                                        );
+                                        eprintln!("  recent elements: {:?}", self.recent);
+                                    }
+                                    ProbeAction::Halt => panic!(
+                                        "probe '{}' (id {}) matched at cycle {}: {:?}",
+                                        self.name, self.id, arrive.time(), data
+                                    ),
+                                }
+                            }
+                            self.matches_seen += 1;
+                        }
+                    }
+
+                    self.out_stream
+                        .enqueue(
+                            &self.time,
+                            ChannelElement {
+                                time: self.time.tick(),
+                                data,
+                            },
+                        )
+                        .unwrap();
+                }
+                Err(_) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dam::{
+        simulation::ProgramBuilder,
+        utility_contexts::{ApproxCheckerContext, ConsumerContext, GeneratorContext},
+    };
+
+    use crate::primitives::elem::Elem;
+
+    use super::{stop_rank, time_at_least, value_matches, ProbeAction, ProbeContext};
+
+    #[test]
+    fn pass_through_forwards_every_element_unchanged() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(1u32), Elem::Val(2), Elem::ValStop(3, 1)].into_iter(),
+            in_snd,
+        ));
+
+        ctx.add_child(ProbeContext::new(in_rcv, out_snd, 0, "probe").with_trace(true));
+
+        ctx.add_child(ApproxCheckerContext::new(
+            || vec![Elem::Val(1u32), Elem::Val(2), Elem::ValStop(3, 1)].into_iter(),
+            out_rcv,
+            |x, y| x == y,
+        ));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "matched at cycle")]
+    fn halts_when_the_stop_rank_trigger_matches() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(1u32), Elem::ValStop(2, 1)].into_iter(),
+            in_snd,
+        ));
+
+        ctx.add_child(ProbeContext::new(in_rcv, out_snd, 0, "probe").with_trigger(
+            stop_rank(1),
+            0,
+            ProbeAction::Halt,
+        ));
+
+        ctx.add_child(ConsumerContext::new(out_rcv));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn skip_n_defers_the_action_past_the_first_matches() {
+        let mut ctx = ProgramBuilder::default();
+        let (in_snd, in_rcv) = ctx.unbounded();
+        let (out_snd, out_rcv) = ctx.unbounded();
+
+        ctx.add_child(GeneratorContext::new(
+            || vec![Elem::Val(5u32), Elem::Val(5), Elem::ValStop(5, 1)].into_iter(),
+            in_snd,
+        ));
+
+        // Only the 3rd match (skip=2) should be eligible to act; since the
+        // action here is just a snapshot log (not a halt), this proves the
+        // probe doesn't panic on the first two matches.
+        ctx.add_child(ProbeContext::new(in_rcv, out_snd, 0, "probe").with_trigger(
+            value_matches(|x: &u32| *x == 5),
+            2,
+            ProbeAction::LogSnapshot,
+        ));
+
+        ctx.add_child(ConsumerContext::new(out_rcv));
+
+        ctx.initialize(Default::default())
+            .unwrap()
+            .run(Default::default());
+    }
+
+    #[test]
+    fn time_at_least_trigger_is_constructible() {
+        let _trigger = time_at_least::<u32>(10);
+    }
+}
@@ -0,0 +1,202 @@
+//! Opt-in sampling profiler for driving a step pipeline through
+//! `initialized.run(...)` (see [`crate::proto_driver::parse_proto`]). Samples
+//! the calling thread's call stack at a fixed frequency via `SIGPROF`, folds
+//! the captured stacks into the collapsed-stack format
+//! (`frame;frame;frame count`) that `inferno`/`flamegraph.pl` expect, and
+//! renders them as an SVG flamegraph -- no external `perf`/`stackcollapse`
+//! tooling required.
+//!
+//! ```ignore
+//! let (executed, report) = run_profiled(ProfileOpts::default(), || {
+//!     initialized.run(run_options)
+//! });
+//! println!("{report}");
+//! report.flamegraph(std::fs::File::create("flame.svg")?)?;
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+
+/// Sampling configuration for [`run_profiled`].
+#[derive(Debug, Clone, Copy)]
+pub struct ProfileOpts {
+    /// Stack-sampling frequency, in Hz.
+    pub sample_hz: u64,
+}
+
+impl Default for ProfileOpts {
+    fn default() -> Self {
+        Self { sample_hz: 100 }
+    }
+}
+
+type Stack = Vec<String>;
+
+/// A folded stack-trace histogram collected by [`run_profiled`].
+#[derive(Debug, Default)]
+pub struct Report {
+    counts: HashMap<Stack, u64>,
+}
+
+impl Report {
+    fn record(&mut self, stack: Stack) {
+        *self.counts.entry(stack).or_insert(0) += 1;
+    }
+
+    fn total_samples(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Collapsed-stack lines (`frame;frame;frame count`), the format
+    /// `inferno`/`flamegraph.pl` expect as input, leaf frame last.
+    fn collapsed_lines(&self) -> Vec<String> {
+        self.counts
+            .iter()
+            .map(|(stack, count)| format!("{} {count}", stack.join(";")))
+            .collect()
+    }
+
+    /// Renders the collected samples as an SVG flamegraph.
+    pub fn flamegraph<W: Write>(&self, writer: W) -> io::Result<()> {
+        let lines = self.collapsed_lines();
+        inferno::flamegraph::from_lines(
+            &mut inferno::flamegraph::Options::default(),
+            lines.iter().map(String::as_str),
+            writer,
+        )
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    /// The `n` hottest stacks by sample count, most-sampled first.
+    pub fn hottest(&self, n: usize) -> Vec<(&[String], u64)> {
+        let mut entries: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(stack, count)| (stack.as_slice(), *count))
+            .collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1));
+        entries.truncate(n);
+        entries
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total_samples();
+        writeln!(f, "{total} samples collected")?;
+        for (stack, count) in self.hottest(10) {
+            let pct = if total == 0 {
+                0.0
+            } else {
+                100.0 * count as f64 / total as f64
+            };
+            writeln!(
+                f,
+                "  {count:>8} ({pct:>5.1}%)  {}",
+                stack.last().map(String::as_str).unwrap_or("?")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `f` under a [`ProfileOpts::sample_hz`]-rate stack sampler and returns
+/// both `f`'s own result and the collected [`Report`].
+///
+/// On non-Unix targets (no `SIGPROF`/`setitimer`) this falls back to running
+/// `f` unprofiled and returning an empty report.
+pub fn run_profiled<Out>(opts: ProfileOpts, f: impl FnOnce() -> Out) -> (Out, Report) {
+    sampler::run(opts.sample_hz, f)
+}
+
+#[cfg(unix)]
+mod sampler {
+    use super::Report;
+    use std::cell::RefCell;
+
+    thread_local! {
+        // Raw instruction pointers captured per sample. Symbolizing inside
+        // the signal handler would risk reentering the allocator/locks a
+        // concurrently-interrupted `malloc` holds, so the handler only
+        // unwinds; symbol resolution happens afterwards, outside signal
+        // context.
+        static SAMPLES: RefCell<Vec<Vec<usize>>> = RefCell::new(Vec::new());
+    }
+
+    extern "C" fn on_sigprof(_signum: libc::c_int) {
+        let mut ips = Vec::with_capacity(64);
+        backtrace::trace(|frame| {
+            ips.push(frame.ip() as usize);
+            ips.len() < 64
+        });
+        SAMPLES.with(|samples| samples.borrow_mut().push(ips));
+    }
+
+    pub fn run<Out>(sample_hz: u64, f: impl FnOnce() -> Out) -> (Out, Report) {
+        SAMPLES.with(|samples| samples.borrow_mut().clear());
+
+        let interval = micros_to_timeval(1_000_000 / sample_hz.max(1));
+        let timer = libc::itimerval {
+            it_interval: interval,
+            it_value: interval,
+        };
+
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = on_sigprof as usize;
+            libc::sigemptyset(&mut action.sa_mask);
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigaction(libc::SIGPROF, &action, std::ptr::null_mut());
+            libc::setitimer(libc::ITIMER_PROF, &timer, std::ptr::null_mut());
+        }
+
+        let out = f();
+
+        unsafe {
+            let disarm: libc::itimerval = std::mem::zeroed();
+            libc::setitimer(libc::ITIMER_PROF, &disarm, std::ptr::null_mut());
+            libc::signal(libc::SIGPROF, libc::SIG_DFL);
+        }
+
+        let mut report = Report::default();
+        SAMPLES.with(|samples| {
+            for ips in samples.borrow().iter() {
+                // Leaf-first in `ips`; collapsed-stack lines want root-first.
+                let stack = ips
+                    .iter()
+                    .rev()
+                    .map(|&ip| symbolize(ip))
+                    .collect::<Vec<_>>();
+                report.record(stack);
+            }
+        });
+        report
+    }
+
+    fn symbolize(ip: usize) -> String {
+        let mut name = None;
+        backtrace::resolve(ip as *mut std::ffi::c_void, |symbol| {
+            if name.is_none() {
+                name = symbol.name().map(|n| n.to_string());
+            }
+        });
+        name.unwrap_or_else(|| format!("{ip:#x}"))
+    }
+
+    fn micros_to_timeval(micros: u64) -> libc::timeval {
+        libc::timeval {
+            tv_sec: (micros / 1_000_000) as libc::time_t,
+            tv_usec: (micros % 1_000_000) as libc::suseconds_t,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod sampler {
+    use super::Report;
+
+    pub fn run<Out>(_sample_hz: u64, f: impl FnOnce() -> Out) -> (Out, Report) {
+        (f(), Report::default())
+    }
+}
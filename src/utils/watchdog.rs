@@ -0,0 +1,100 @@
+//! Wall-clock watchdog for driving `initialized.run(...)` (see
+//! [`crate::proto_driver::parse_proto`]): `initialized.run` can spin
+//! indefinitely if the dataflow graph deadlocks (two contexts each blocked
+//! waiting on the other's channel), and dam gives us no way to cancel it
+//! mid-run. [`run_with_watchdog`] can't stop a hung run either, but it
+//! monitors it from a side thread so that once the configured budget is
+//! exceeded, a [`DeadlockReport`] naming the channels that have gone quiet
+//! is printed before a CI job's own timeout kills the process -- turning
+//! "my test job just times out with no output" into an actionable
+//! diagnostic pointing at the stuck channel.
+//!
+//! ```ignore
+//! let (executed, deadlock) = run_with_watchdog(
+//!     Duration::from_secs(90),
+//!     activity_log.clone(),
+//!     || initialized.run(run_options),
+//! );
+//! if let Some(report) = deadlock {
+//!     println!("{report}");
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::build_sim::watchdog::{ActivityLog, ChannelActivity};
+
+/// A wall-clock budget was exceeded while `f` was still running: the
+/// channels a token has crossed, ordered oldest-activity-first, so the
+/// channels at the front are the likeliest site of the deadlock.
+#[derive(Debug, Clone)]
+pub struct DeadlockReport {
+    pub timeout: Duration,
+    pub channels: Vec<ChannelActivity>,
+}
+
+impl fmt::Display for DeadlockReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "simulation exceeded its {:?} watchdog budget -- still running; \
+             channels below are ordered by how long they've gone quiet \
+             (oldest first, likeliest stuck):",
+            self.timeout
+        )?;
+        if self.channels.is_empty() {
+            writeln!(f, "  (no channel has carried a token yet)")?;
+        }
+        for channel in &self.channels {
+            writeln!(
+                f,
+                "  channel {}: last active at cycle {}, {} token(s) seen",
+                channel.id, channel.last_cycle, channel.tokens
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `f` to completion, as normal, but spawns a side thread that wakes up
+/// after `timeout` and -- if `f` hasn't returned by then -- snapshots `log`
+/// into a [`DeadlockReport`]. Since dam gives this crate no way to cancel an
+/// in-flight `run()`, `f` is never interrupted: this only gets a diagnostic
+/// printed before an external timeout (e.g. CI's) kills the process. Returns
+/// `f`'s own result alongside the report, or `None` if `f` finished inside
+/// its budget.
+pub fn run_with_watchdog<Out>(
+    timeout: Duration,
+    log: ActivityLog,
+    f: impl FnOnce() -> Out,
+) -> (Out, Option<DeadlockReport>) {
+    let done = Arc::new(AtomicBool::new(false));
+    let report = Arc::new(std::sync::Mutex::new(None));
+
+    // Intentionally not joined: this thread only ever sleeps once and then
+    // either records a report or exits, so letting it run down in the
+    // background costs nothing and lets `f`'s own completion return
+    // immediately instead of waiting out the rest of the budget.
+    {
+        let done = Arc::clone(&done);
+        let report = Arc::clone(&report);
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout);
+            if !done.load(Ordering::Acquire) {
+                *report.lock().unwrap() = Some(DeadlockReport {
+                    timeout,
+                    channels: log.snapshot(),
+                });
+            }
+        });
+    }
+
+    let out = f();
+    done.store(true, Ordering::Release);
+
+    let report = report.lock().unwrap().take();
+    (out, report)
+}